@@ -3,19 +3,45 @@
 //! 负责协调各个子模块，管理应用程序的整体生命周期
 
 use anyhow::Result;
-use chrono::TimeZone;
 use log::{info, error, warn};
 
 use crate::core::{
-    countdown::CountdownManager,
+    autostart::{self, AutostartState},
+    battery::ActionOverride,
+    condition::ConditionContext,
+    countdown::{CountdownManager, StatusHandle},
+    history::{HistoryEntry, HistoryLog},
     persistence::TaskPersistence,
+    plan::{format_warning_offset, is_short_countdown, RescheduleChoice, FINAL_WARNING_SECONDS},
+    recorder::{self, EventRecorder, RecordedKind, ReplayEvent},
+    remote::RemoteServer,
+    routine::{self, RoutineStep, LOGON_ROUTINE_DELAY_SECONDS},
+    schedule,
+    scheduler::{TaskListHandle, TaskStore},
+    screen_time::{is_session_locked, InputWatch, UsageTracker, SAMPLE_INTERVAL},
     shutdown::ShutdownExecutor,
+    stats::{RunResult, TaskStats},
+    storage::{self, Storage},
     system_compat::SystemCompatibility,
+    system_tasks::SystemTaskMirror,
     time_parser::TimeParser,
-    types::{UIEvent, TaskType, TimeInput},
+    trigger::{NetworkIdleSettings, NetworkIdleState, NetworkIdleWatch, ProcessExitTrigger, NETWORK_SAMPLE_SECONDS, POWER_SAMPLE_SECONDS, PROCESS_SAMPLE_SECONDS},
+    types::{new_task_id, ActivityBehavior, CountdownUpdate, LockedBehavior, StatusSnapshot, UIEvent, TaskType, TimeInput, TaskData, TaskAction, TaskSource, WeeklySlot, WindowMode, WindowRequest, QUICK_COUNTDOWN_ACTION},
+    webhook::{self, WebhookEvent},
 };
+use crate::platform::{self, DisplayControl, SystemInfo};
 use crate::ui::UIManager;
+use crate::ui::overlay::OverlayServer;
+use crate::utils::config::ConfigManager;
+use crate::utils::monitor::NetworkSampler;
+use crate::utils::policy::{Policy, POLICY_TASK_ID};
+use crate::utils::system;
 
+/// 等待工作站解锁时的检查间隔（秒）
+const UNLOCK_POLL_INTERVAL_SECONDS: u64 = 2;
+
+/// 看门狗检查倒计时任务心跳的间隔（秒）
+const WATCHDOG_INTERVAL_SECONDS: u64 = 5;
 
 /// 应用程序主结构体
 /// 
@@ -27,12 +53,64 @@ pub struct App {
     countdown_manager: CountdownManager,
     /// 关机执行器
     shutdown_executor: ShutdownExecutor,
-    /// 任务持久化管理器
-    task_persistence: TaskPersistence,
+    /// 任务存储
+    task_store: TaskStore,
+    /// 任务历史记录
+    history: HistoryLog,
+    /// 调试时开启的事件录制器
+    recorder: Option<std::sync::Arc<EventRecorder>>,
     /// 系统兼容性检查器
     system_compatibility: SystemCompatibility,
+    /// 计算机级管理策略
+    policy: Policy,
     /// UI管理器
     ui_manager: Option<UIManager>,
+    /// 启动界面后立即处理的事件（如命令行指定的倒计时）
+    launch_event: Option<UIEvent>,
+    /// 等待用户确认的短倒计时
+    pending_short_start: Option<TaskData>,
+    /// 启动时主窗口的显示方式
+    window_mode: WindowMode,
+}
+
+/// 正在运行的核心的句柄
+/// 
+/// 核心在后台调度任务、倒计时并执行操作，不依赖界面；
+/// 界面通过句柄中的通道连接核心：接收倒计时更新，发送界面事件
+pub struct CoreHandle {
+    /// 时间解析器
+    time_parser: TimeParser,
+    /// 倒计时更新接收器，核心启动时创建，界面连接前的更新不会丢失
+    countdown_receiver: tokio::sync::broadcast::Receiver<CountdownUpdate>,
+    /// UI事件发送器
+    ui_event_sender: tokio::sync::mpsc::UnboundedSender<UIEvent>,
+    /// 倒计时状态查询句柄
+    status_handle: StatusHandle,
+    /// 任务列表查询句柄
+    task_list: TaskListHandle,
+}
+
+impl CoreHandle {
+    /// 连接界面并运行界面事件循环
+    /// 
+    /// 窗口事件循环每个进程只能创建一次，界面退出后进程随之结束；
+    /// 后台代理方式下关闭主窗口只隐藏窗口，核心和托盘图标继续运行，需要时再显示
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_mode` - 主窗口的显示方式
+    pub fn attach_gui(self, window_mode: WindowMode) -> Result<()> {
+        info!("启动用户界面: {:?}", window_mode);
+        crate::ui::manager::run_with_params(
+            self.time_parser,
+            Some(self.countdown_receiver),
+            Some(self.ui_event_sender),
+            Some(self.status_handle),
+            Some(self.task_list),
+            window_mode,
+        )?;
+        Ok(())
+    }
 }
 
 impl App {
@@ -63,106 +141,1022 @@ impl App {
         }
 
         // 初始化核心组件
-        let time_parser = TimeParser::new();
+        let time_parser = TimeParser::configured();
         let countdown_manager = CountdownManager::new().await?;
-        let shutdown_executor = ShutdownExecutor::new().await?;
-        let task_persistence = TaskPersistence::new()?;
-
-        // 尝试恢复之前的任务
+        countdown_manager.set_warning_offsets(ConfigManager::load_warning_offsets());
+        let mut shutdown_executor = ShutdownExecutor::new().await?;
+        shutdown_executor.set_reason_template(&ConfigManager::load_reason_template());
+        shutdown_executor.set_battery_settings(ConfigManager::load_battery());
+        let mut task_persistence = TaskPersistence::new()?;
+        task_persistence.set_max_backups(ConfigManager::load_max_backup_files());
+        task_persistence.set_encrypt_data(ConfigManager::load_encrypt_data());
+        Self::enforce_data_budget(&task_persistence);
+        let data_dir = task_persistence.get_data_dir().to_path_buf();
+        let storage = storage::open(task_persistence, ConfigManager::load_storage_backend());
+        let history = HistoryLog::new(storage.clone());
+        let recorder = Self::start_recorder(&data_dir, storage.as_ref());
+        
         let app = Self {
             time_parser,
             countdown_manager,
             shutdown_executor,
-            task_persistence,
+            history,
+            recorder,
+            task_store: Self::load_task_store(storage),
             system_compatibility,
+            policy: Policy::load(),
             ui_manager: None,
+            launch_event: None,
+            pending_short_start: None,
+            window_mode: if ConfigManager::load_start_minimized() { WindowMode::Minimized } else { WindowMode::Normal },
         };
         
-        if let Ok(Some(task)) = app.task_persistence.load_task() {
-            info!("发现已保存的任务，尝试恢复: {:?}", task.task_type);
-            
-            // 检查任务是否仍然有效
-            if let Some(target_time) = task.target_time {
-                let now = chrono::Local::now();
-                
-                if target_time > now {
-                    // 任务仍然有效，恢复倒计时
-                    if let Err(e) = app.countdown_manager.start_countdown(target_time).await {
-                        error!("恢复倒计时失败: {}", e);
-                        // 清除无效任务
-                        let _ = app.task_persistence.clear_task();
-                    } else {
-                        info!("任务已恢复，目标时间: {:?}", target_time);
-                    }
-                } else {
-                    // 任务已过期，清除
-                    info!("任务已过期，清除保存的任务数据");
-                    let _ = app.task_persistence.clear_task();
-                }
+        // 恢复之前保存的任务，与管理策略要求的每日关机一起调度最近的一个
+        Self::schedule_next(&app.task_store, Some(&app.policy), &app.countdown_manager).await;
+        
+        return Ok(app);
+    }
+    
+    /// 设置启动选项
+    /// 
+    /// # 参数
+    /// 
+    /// * `launch_event` - 启动界面后立即处理的事件
+    /// * `window_mode` - 主窗口的显示方式，设置中已开启最小化时至少最小化到托盘
+    pub fn set_launch_options(&mut self, launch_event: Option<UIEvent>, window_mode: WindowMode) {
+        self.launch_event = launch_event;
+        self.window_mode = self.window_mode.max(window_mode);
+    }
+    
+    /// 调度最近要执行的任务
+    /// 
+    /// 先按管理策略保存每日关机任务，再从任务存储中选出最早执行的任务，
+    /// 与正在倒计时的任务不同时改为该任务倒计时，没有可执行的任务时停止倒计时
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `policy` - 管理策略，为空时不更新已保存的策略任务（如回放事件时）
+    /// * `countdown_manager` - 倒计时管理器
+    async fn schedule_next(task_store: &TaskStore, policy: Option<&Policy>, countdown_manager: &CountdownManager) {
+        let now = chrono::Local::now();
+        if let Some(policy) = policy {
+            if let Err(e) = task_store.apply_policy(POLICY_TASK_ID, policy.forced_daily_task(now)) {
+                error!("保存管理策略的每日关机任务失败: {}", e);
             }
         }
+        let next_task = task_store.next_task(now);
         
-        return Ok(app);
+        let active_task = countdown_manager.get_active_task().await;
+        match next_task {
+            // 保留正在进行的倒计时，不丢失暂停和延长
+            Some(task) if active_task.as_ref().is_some_and(|active| active.id == task.id) => {},
+            Some(task) => {
+                info!("调度任务 {}: {}", task.id, task.schedule_text());
+                if let Err(e) = countdown_manager.start_countdown_from_task(task).await {
+                    error!("启动任务倒计时失败: {}", e);
+                }
+            },
+            None if active_task.is_some() => {
+                info!("没有可执行的任务，停止倒计时");
+                if let Err(e) = countdown_manager.cancel_countdown().await {
+                    error!("停止倒计时失败: {}", e);
+                }
+            },
+            None => {},
+        }
+    }
+    
+    /// 添加任务并重新调度
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `policy` - 管理策略
+    /// * `countdown_manager` - 倒计时管理器
+    /// * `task_data` - 要添加的任务
+    async fn add_and_schedule(task_store: &TaskStore, policy: &Policy, countdown_manager: &CountdownManager, task_data: TaskData) {
+        if let Err(e) = task_store.add(task_data) {
+            error!("保存任务失败: {}", e);
+        }
+        Self::schedule_next(task_store, Some(policy), countdown_manager).await;
+    }
+    
+    /// 修改任务并重新调度
+    /// 
+    /// 正在倒计时的任务被修改时按新的计划重新开始倒计时
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `policy` - 管理策略
+    /// * `countdown_manager` - 倒计时管理器
+    /// * `task_data` - 修改后的任务
+    async fn replace_and_schedule(task_store: &TaskStore, policy: &Policy, countdown_manager: &CountdownManager, task_data: TaskData) -> Result<()> {
+        let id = task_data.id.clone();
+        if !task_store.replace(task_data)? {
+            warn!("要修改的任务不存在: {}", id);
+            return Ok(());
+        }
+        
+        if countdown_manager.get_active_task().await.is_some_and(|active| active.id == id) {
+            countdown_manager.cancel_countdown().await?;
+        }
+        Self::schedule_next(task_store, Some(policy), countdown_manager).await;
+        Ok(())
+    }
+    
+    /// 按第一次提醒时的选择改期正在倒计时的任务
+    /// 
+    /// 倒计时延长到新的到点时间；单次任务同时保存新的目标时间，重新启动后仍按改期后的时间执行，
+    /// 重复任务只改本次，之后按原计划执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `countdown_manager` - 倒计时管理器
+    /// * `choice` - 改期方式
+    async fn reschedule_active(task_store: &TaskStore, countdown_manager: &CountdownManager, choice: RescheduleChoice) -> Result<()> {
+        let now = chrono::Local::now();
+        let deadline = countdown_manager.status_snapshot().await.deadline
+            .ok_or_else(|| anyhow::anyhow!("没有进行中的倒计时"))?;
+        let target = match choice.target(deadline, now) {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+        
+        countdown_manager.extend(target - deadline).await?;
+        let stored = countdown_manager.get_active_task().await
+            .filter(|task| task.task_type == TaskType::Once)
+            .and_then(|task| task_store.get(&task.id));
+        if let Some(mut task) = stored {
+            task.target_time = Some(target);
+            task_store.replace(task)?;
+        }
+        info!("已改到 {}", target.format("%m-%d %H:%M"));
+        Ok(())
+    }
+    
+    /// 检查管理策略是否允许该事件
+    /// 
+    /// 策略设置了PIN时，取消和暂停倒计时必须通过带PIN的取消事件完成，也不能推迟、延长或改期；
+    /// 策略要求的关机正在倒计时时，也不能退出程序、删除停用修改任务、恢复备份、导入任务，
+    /// 或开始一个比它更早执行、会替换它的倒计时
+    /// 
+    /// # 参数
+    /// 
+    /// * `policy` - 管理策略
+    /// * `event` - UI事件
+    /// * `active` - 正在倒计时的任务
+    fn is_event_allowed(policy: &Policy, event: &UIEvent, active: Option<&TaskData>) -> bool {
+        if !policy.requires_pin() {
+            return true;
+        }
+        let policy_active = active.filter(|task| task.source == TaskSource::Policy);
+        // 新任务比策略关机更早执行时会替换正在进行的倒计时
+        let replaces_policy = |task: Option<TaskData>| {
+            let now = chrono::Local::now();
+            policy_active.is_some_and(|active| {
+                task.and_then(|task| schedule::next_fire(&task, now))
+                    .map_or(true, |(time, _)| active.target_time.map_or(true, |target| time < target))
+            })
+        };
+        
+        match event {
+            UIEvent::CancelCountdownWithPin(pin) => policy.verify_pin(pin),
+            UIEvent::CancelCountdown | UIEvent::PauseCountdown | UIEvent::ExtendCountdown(_) | UIEvent::Reschedule(_) => false,
+            UIEvent::StartCountdown(time_input, task_type, action) => {
+                !replaces_policy(TaskData::from_time_input(*task_type, time_input.clone(), *action, chrono::Local::now()).ok())
+            },
+            UIEvent::QuickCountdown(duration) => !replaces_policy(Self::build_quick_task(*duration).ok()),
+            UIEvent::Exit
+            | UIEvent::RemoveTask(_)
+            | UIEvent::SetTaskEnabled(_, false)
+            | UIEvent::UpdateTask(_)
+            | UIEvent::RestoreTaskBackup
+            | UIEvent::ImportTasks(_) => policy_active.is_none(),
+            _ => true,
+        }
     }
 
+    /// 获取倒计时状态快照
+    /// 
+    /// 托盘提示、命令行和本地HTTP接口显示的状态都来自同一份快照
+    pub async fn status(&self) -> StatusSnapshot {
+        self.countdown_manager.status_snapshot().await
+    }
+    
+    /// 按配置的空间预算清理数据目录
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_persistence` - 任务持久化管理器
+    fn enforce_data_budget(task_persistence: &TaskPersistence) {
+        let max_data_size_mb = match ConfigManager::new() {
+            Ok(config_manager) => config_manager.get_config().advanced.max_data_size_mb,
+            Err(e) => {
+                warn!("加载配置失败，跳过数据目录清理: {}", e);
+                return;
+            }
+        };
+        
+        match task_persistence.enforce_size_budget(max_data_size_mb * 1024 * 1024) {
+            Ok(freed) if freed > 0 => {
+                let data_size = task_persistence.get_data_size().unwrap_or_default();
+                info!("数据目录已清理，释放 {} KB，当前占用 {} KB", freed / 1024, data_size / 1024);
+            },
+            Ok(_) => {},
+            Err(e) => warn!("清理数据目录失败: {}", e),
+        }
+    }
+    
+    /// 按配置启动直播倒计时叠加层
+    /// 
+    /// 安全模式下不启动本地HTTP接口
+    /// 
+    /// # 参数
+    /// 
+    /// * `receiver` - 倒计时更新接收器
+    /// * `status_handle` - 状态查询句柄，用于`/status`接口
+    /// * `task_list` - 任务列表查询句柄，用于`/calendar.ics`接口
+    async fn start_overlay(receiver: tokio::sync::broadcast::Receiver<CountdownUpdate>, status_handle: StatusHandle, task_list: TaskListHandle) {
+        if system::is_safe_mode() {
+            info!("安全模式下不启动直播倒计时叠加层");
+            return;
+        }
+        // 没有启用叠加层时也启动本地接口，命令行`status`通过`/status`查询运行中的实例
+        let settings = ConfigManager::load_overlay();
+        if let Err(e) = OverlayServer::new(settings).with_status(status_handle).with_tasks(task_list).start(receiver).await {
+            error!("启动直播倒计时叠加层失败: {}", e);
+        }
+    }
+    
+    /// 按设置启动远程关机服务
+    /// 
+    /// 安全模式下不启动
+    /// 
+    /// # 参数
+    /// 
+    /// * `ui_event_sender` - 发送远程命令事件的UI事件通道
+    async fn start_remote_server(ui_event_sender: tokio::sync::mpsc::UnboundedSender<UIEvent>) {
+        let settings = ConfigManager::load_remote();
+        if !settings.enabled {
+            return;
+        }
+        if system::is_safe_mode() {
+            info!("安全模式下不启动远程关机服务");
+            return;
+        }
+        
+        if let Err(e) = RemoteServer::new(&settings).start(ui_event_sender).await {
+            error!("启动远程关机服务失败: {}", e);
+        }
+    }
+    
+    /// 按调试设置开始录制事件
+    /// 
+    /// # 参数
+    /// 
+    /// * `data_dir` - 保存录制文件的数据目录
+    /// * `storage` - 保存任务的存储，录制开始时的任务写入录制文件
+    fn start_recorder(data_dir: &std::path::Path, storage: &dyn Storage) -> Option<std::sync::Arc<EventRecorder>> {
+        if !ConfigManager::load_record_events() {
+            return None;
+        }
+        let tasks = storage.load_tasks().unwrap_or_default();
+        match EventRecorder::start(data_dir, tasks, chrono::Local::now()) {
+            Ok(recorder) => {
+                info!("开始录制事件: {:?}", recorder.path());
+                Some(std::sync::Arc::new(recorder))
+            },
+            Err(e) => {
+                error!("开始录制事件失败: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// 录制核心发出的倒计时更新
+    /// 
+    /// # 参数
+    /// 
+    /// * `receiver` - 倒计时更新接收器
+    /// * `recorder` - 事件录制器
+    fn start_update_recording(mut receiver: tokio::sync::broadcast::Receiver<CountdownUpdate>, recorder: std::sync::Arc<EventRecorder>) {
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(update) => recorder.record_update(&update, chrono::Local::now()),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("事件录制消息滞后，跳过了{}条消息", skipped);
+                    },
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+    
+    /// 回放录制的事件
+    /// 
+    /// 在临时目录中用全新的任务存储和倒计时按顺序处理录制的界面事件，每条事件后输出倒计时状态；
+    /// 录制的时间整体平移到现在，不运行倒计时结束后的执行，不会真正关机。
+    /// 回放时新建的任务标识与录制时不同，删除或修改录制期间新建的任务会失败
+    /// 
+    /// # 参数
+    /// 
+    /// * `path` - 录制文件路径
+    /// 
+    /// # 返回值
+    /// 
+    /// 逐条事件的回放结果
+    pub async fn replay(path: &std::path::Path) -> Result<String> {
+        let events = recorder::load_recording(path)?;
+        let offset = chrono::Local::now() - events[0].at;
+        let data_dir = tempfile::tempdir()?;
+        let task_store = TaskStore::load(std::sync::Arc::new(TaskPersistence::with_data_dir(data_dir.path())?));
+        let countdown_manager = CountdownManager::new().await?;
+        let policy = Policy::default();
+        
+        let mut lines = Vec::new();
+        for event in events {
+            let time = event.at.format("%m-%d %H:%M:%S");
+            match event.kind {
+                RecordedKind::Tasks { tasks } => {
+                    let count = tasks.len();
+                    for task in tasks {
+                        task_store.add(recorder::shift_task(task, offset))?;
+                    }
+                    Self::schedule_next(&task_store, None, &countdown_manager).await;
+                    lines.push(format!("{} 恢复{}个任务 -> {}", time, count, countdown_manager.status_snapshot().await.summary()));
+                },
+                RecordedKind::Ui { event, replay: Some(replay) } => {
+                    if let Err(e) = Self::replay_event(&task_store, &policy, &countdown_manager, replay, offset).await {
+                        lines.push(format!("{} {} 回放失败: {}", time, event, e));
+                        continue;
+                    }
+                    lines.push(format!("{} {} -> {}", time, event, countdown_manager.status_snapshot().await.summary()));
+                },
+                RecordedKind::Ui { event, replay: None } => lines.push(format!("{} {}（不回放）", time, event)),
+                RecordedKind::Update { update } => lines.push(format!("{}     录制时核心发出: {}", time, update)),
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+    
+    /// 回放一条界面事件，处理方式与界面事件循环相同
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `policy` - 管理策略
+    /// * `countdown_manager` - 倒计时管理器
+    /// * `event` - 要回放的事件
+    /// * `offset` - 回放时间与录制时间的差
+    async fn replay_event(task_store: &TaskStore, policy: &Policy, countdown_manager: &CountdownManager, event: ReplayEvent, offset: chrono::Duration) -> Result<()> {
+        match event {
+            ReplayEvent::AddTask { task } => {
+                Self::add_and_schedule(task_store, policy, countdown_manager, recorder::shift_task(task, offset)).await;
+            },
+            ReplayEvent::Cancel => {
+                if let Some(task) = countdown_manager.get_active_task().await {
+                    task_store.cancel(&task, chrono::Local::now())?;
+                }
+                countdown_manager.cancel_countdown().await?;
+                Self::schedule_next(task_store, None, countdown_manager).await;
+            },
+            ReplayEvent::Pause => countdown_manager.pause_countdown().await?,
+            ReplayEvent::Resume => countdown_manager.resume_countdown().await?,
+            ReplayEvent::Extend { seconds } => countdown_manager.extend(chrono::Duration::seconds(seconds as i64)).await?,
+            ReplayEvent::Shorten { seconds } => countdown_manager.subtract_time(chrono::Duration::seconds(seconds as i64)).await?,
+            ReplayEvent::Reschedule { choice } => Self::reschedule_active(task_store, countdown_manager, choice).await?,
+            ReplayEvent::RemoveTask { id } => {
+                if !task_store.remove(&id)? {
+                    return Err(anyhow::anyhow!("任务不存在: {}", id));
+                }
+                Self::schedule_next(task_store, Some(policy), countdown_manager).await;
+            },
+            ReplayEvent::UpdateTask { task } => {
+                Self::replace_and_schedule(task_store, policy, countdown_manager, recorder::shift_task(task, offset)).await?;
+            },
+            ReplayEvent::SetTaskEnabled { id, enabled } => {
+                task_store.set_enabled(&id, enabled)?;
+                Self::schedule_next(task_store, Some(policy), countdown_manager).await;
+            },
+        }
+        Ok(())
+    }
+    
+    /// 检查开机自动启动的登记状态
+    /// 
+    /// 管理策略可能固定了该设置，程序也可能被移动过，每次启动时按设置重新登记或取消；
+    /// 设置为开启但在任务管理器中被禁用时提示用户。reg.exe启动较慢，在后台线程中执行；
+    /// 安全模式下不修改登记
+    /// 
+    /// # 参数
+    /// 
+    /// * `updates` - 通知发送器
+    fn sync_autostart(updates: tokio::sync::broadcast::Sender<CountdownUpdate>) {
+        if !platform::capabilities().autostart {
+            return;
+        }
+        if system::is_safe_mode() {
+            info!("安全模式下不检查开机自动启动登记");
+            return;
+        }
+        let enabled = match ConfigManager::load_auto_start() {
+            Some(enabled) => enabled,
+            None => return,
+        };
+        tokio::task::spawn_blocking(move || {
+            match autostart::verify(enabled) {
+                Ok(AutostartState::DisabledByUser) => {
+                    warn!("开机自动启动已在任务管理器中被禁用");
+                    let _ = updates.send(CountdownUpdate::Notice {
+                        title: "QtShut - 开机自动启动".to_string(),
+                        content: format!("{}，可以在设置中重新开启", AutostartState::DisabledByUser),
+                    });
+                },
+                Ok(state) => info!("开机自动启动: {}", state),
+                Err(e) => warn!("同步开机自动启动设置失败: {}", e),
+            }
+        });
+    }
+    
     /// 运行应用程序
     /// 
-    /// 启动GUI界面并进入事件循环
+    /// 启动核心后连接界面并进入界面事件循环
     pub async fn run(self) -> Result<()> {
-        info!("启动用户界面...");
+        let window_mode = self.window_mode;
+        let core = self.start_core().await?;
+        core.attach_gui(window_mode)
+    }
+    
+    /// 启动核心
+    /// 
+    /// 启动倒计时结束后的执行、界面事件处理循环、远程关机服务等后台任务，
+    /// 这些任务在主窗口隐藏时继续运行
+    /// 
+    /// # 返回值
+    /// 
+    /// 界面连接核心使用的句柄
+    pub async fn start_core(self) -> Result<CoreHandle> {
+        info!("启动核心...");
 
         // 获取倒计时更新接收器
         let countdown_receiver = self.countdown_manager.get_update_receiver();
+        let mut finish_receiver = self.countdown_manager.get_update_receiver();
+        let activity_receiver = self.countdown_manager.get_update_receiver();
         info!("获取倒计时接收器成功");
+        Self::sync_autostart(self.countdown_manager.update_publisher());
+        let recorder = self.recorder.clone();
+        if let Some(recorder) = &recorder {
+            Self::start_update_recording(self.countdown_manager.get_update_receiver(), recorder.clone());
+        }
+        
+        // 启动直播倒计时叠加层
+        let status_handle = self.countdown_manager.status_handle();
+        Self::start_overlay(self.countdown_manager.get_update_receiver(), status_handle.clone(), self.task_store.handle()).await;
 
         // 创建UI事件通道
         let (ui_event_sender, ui_event_receiver) = tokio::sync::mpsc::unbounded_channel::<UIEvent>();
         info!("创建UI事件通道成功");
+        
+        // 其他电脑发来的命令与界面中点击开始一样交给事件循环处理
+        Self::start_remote_server(ui_event_sender.clone()).await;
+        
+        // 命令行指定的倒计时与界面中点击开始一样交给事件循环处理
+        if let Some(event) = self.launch_event {
+            info!("启动后立即处理: {:?}", event);
+            if let Err(e) = ui_event_sender.send(event) {
+                error!("发送启动事件失败: {}", e);
+            }
+        }
 
         // 创建一个共享的倒计时管理器引用
         let countdown_manager = std::sync::Arc::new(tokio::sync::Mutex::new(self.countdown_manager));
         let time_parser = self.time_parser.clone();
         let shutdown_executor = std::sync::Arc::new(tokio::sync::Mutex::new(self.shutdown_executor));
         
+        // 启动时检查已存在的外部关机计划，由用户选择保留或取消
+        {
+            let shutdown_executor = shutdown_executor.lock().await;
+            let countdown_manager = countdown_manager.lock().await;
+            Self::detect_external_shutdown(&shutdown_executor, &countdown_manager).await;
+        }
+        
+        let task_store = std::sync::Arc::new(self.task_store);
+        let task_list = task_store.handle();
+        let policy = self.policy;
+        let history = self.history;
+        
+        Self::start_activity_watch(activity_receiver, ui_event_sender.clone(), &policy);
+        Self::start_screen_time_watch(ui_event_sender.clone());
+        Self::start_watchdog(countdown_manager.clone());
+        Self::start_logon_routines(task_store.clone(), shutdown_executor.clone(), countdown_manager.lock().await.update_publisher());
+        
+        // 倒计时结束后执行任务操作，然后调度下一个任务
+        let shutdown_executor_finish = shutdown_executor.clone();
+        let countdown_manager_finish = countdown_manager.clone();
+        let task_store_finish = task_store.clone();
+        let policy_finish = policy.clone();
+        let history_finish = history.clone();
+        tokio::spawn(async move {
+            loop {
+                match finish_receiver.recv().await {
+                    Ok(CountdownUpdate::Finished) => {
+                        let mut task = countdown_manager_finish.lock().await
+                            .get_current_task().await;
+                        // 模板和每周计划创建的运行命令任务使用设置中的命令
+                        if let Some(task) = task.as_mut().filter(|task| task.custom_command.is_none()) {
+                            if task.action == TaskAction::RunCommand {
+                                task.custom_command = ConfigManager::load_custom_command();
+                            }
+                        }
+                        let action = task.as_ref().map(|task| task.action).unwrap_or_default();
+                        
+                        // 到点时已锁定，按设置推迟到下次解锁
+                        let locked_behavior = ConfigManager::load_locked_behavior();
+                        if locked_behavior != LockedBehavior::RunOnTime && is_session_locked() {
+                            info!("到点时工作站已锁定，解锁后再执行{}", action);
+                            Self::wait_for_unlock().await;
+                            if let Some(task) = task.as_ref().filter(|_| locked_behavior == LockedBehavior::DeferWarning) {
+                                let countdown_manager = countdown_manager_finish.lock().await;
+                                match countdown_manager.start_countdown_from_task(Self::deferred_warning_task(task)).await {
+                                    Ok(()) => continue,
+                                    Err(e) => error!("解锁后开始最后警告失败，直接执行: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // 执行条件不满足时按设置推迟，推迟结束后再次检查
+                        let conditions = ConfigManager::load_conditions();
+                        if let Some(unmet) = conditions.check(&ConditionContext::collect(&conditions).await) {
+                            let postpone = unmet.retry_after;
+                            info!("执行条件不满足，推迟{}分钟执行{}: {}", postpone.num_minutes(), action, unmet.reason);
+                            let countdown_manager = countdown_manager_finish.lock().await;
+                            let result = match &task {
+                                Some(task) => countdown_manager.start_countdown_from_task(Self::postponed_task(task, postpone)).await,
+                                None => countdown_manager.start_countdown(chrono::Local::now() + postpone).await,
+                            };
+                            match result {
+                                Ok(()) => {
+                                    if let Some(task) = &task {
+                                        if let Err(e) = task_store_finish.record_postpone(&task.id) {
+                                            error!("记录任务推迟失败: {}", e);
+                                        }
+                                    }
+                                    countdown_manager.publish_update(CountdownUpdate::Postponed {
+                                        reason: unmet.reason,
+                                        retry_after: postpone,
+                                    });
+                                    continue;
+                                },
+                                Err(e) => error!("推迟执行失败，直接执行: {}", e),
+                            }
+                        }
+                        // 使用电池供电且电量过低时按设置取消本次执行，设置可能在运行中修改过
+                        let cancelled = {
+                            let mut shutdown_executor = shutdown_executor_finish.lock().await;
+                            shutdown_executor.set_battery_settings(ConfigManager::load_battery());
+                            match shutdown_executor.action_override(action) {
+                                ActionOverride::Cancel(reason) => Some(reason),
+                                _ => None,
+                            }
+                        };
+                        let result = match &cancelled {
+                            Some(reason) => {
+                                let message = format!("{}，已取消本次{}", reason, action);
+                                warn!("{}", message);
+                                if let Some(task) = &task {
+                                    if let Err(e) = history_finish.append(HistoryEntry::skipped(task, reason.to_string(), chrono::Local::now())) {
+                                        error!("记录执行历史失败: {}", e);
+                                    }
+                                }
+                                Self::spawn_webhook(WebhookEvent::Cancelled, Some(action), task.clone(), Some(reason.to_string()));
+                                countdown_manager_finish.lock().await.publish_update(CountdownUpdate::Error(message));
+                                Ok(())
+                            },
+                            None => {
+                                info!("倒计时结束，执行{}", action);
+                                // 关机后进程来不及写入，执行前记录
+                                if let Some(task) = &task {
+                                    if let Err(e) = history_finish.append(HistoryEntry::executed(task, chrono::Local::now())) {
+                                        error!("记录执行历史失败: {}", e);
+                                    }
+                                }
+                                // 关机后无法再发送，等待发送完成（最多几秒）再执行
+                                webhook::notify(&ConfigManager::load_webhook(), WebhookEvent::Executing, Some(action), task.as_ref(), None).await;
+                                let shutdown_executor = shutdown_executor_finish.lock().await;
+                                match &task {
+                                    Some(task) => shutdown_executor.execute_task(task).await,
+                                    None => shutdown_executor.execute_action(action).await,
+                                }
+                            },
+                        };
+                        let run_result = match (&cancelled, &result) {
+                            (Some(_), _) => RunResult::Skipped,
+                            (None, Ok(())) => RunResult::Succeeded,
+                            (None, Err(_)) => RunResult::Failed,
+                        };
+                        if let Err(e) = result {
+                            error!("执行{}失败: {}", action, e);
+                            if let Some(task) = &task {
+                                if let Err(e) = history_finish.append(HistoryEntry::failed(task, e.to_string(), chrono::Local::now())) {
+                                    error!("记录执行历史失败: {}", e);
+                                }
+                            }
+                            Self::spawn_webhook(WebhookEvent::Failed, Some(action), task.clone(), Some(e.to_string()));
+                        }
+                        
+                        if let Some(task) = &task {
+                            // 倒计时可能在到点前不到一秒结束，到点后再计算重复任务的下一次执行时间
+                            if let Some(wait) = task.target_time.and_then(|time| (time - chrono::Local::now()).to_std().ok()) {
+                                tokio::time::sleep(wait).await;
+                            }
+                            if let Err(e) = task_store_finish.complete(&task.id, run_result, chrono::Local::now()) {
+                                error!("更新已执行的任务失败: {}", e);
+                            }
+                        }
+                        let countdown_manager = countdown_manager_finish.lock().await;
+                        Self::schedule_next(&task_store_finish, Some(&policy_finish), &countdown_manager).await;
+                    },
+                    Ok(_) => {},
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("关机监听消息滞后，跳过了{}条消息", skipped);
+                    },
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        info!("倒计时通道已关闭，停止关机监听");
+                        break;
+                    },
+                }
+            }
+        });
+        
         // 启动UI事件处理任务
         let countdown_manager_clone = countdown_manager.clone();
         let shutdown_executor_clone = shutdown_executor.clone();
         tokio::spawn(async move {
             info!("启动UI事件处理循环");
             let mut ui_event_receiver = ui_event_receiver;
+            // 正在等待下载完成或进程退出的后台任务
+            let mut trigger_watch: Option<tokio::task::JoinHandle<()>> = None;
+            // 等待用户确认的短倒计时
+            let mut pending_short_start: Option<TaskData> = None;
             while let Some(event) = ui_event_receiver.recv().await {
-                info!("收到UI事件: {:?}", event);
+                if let Some(recorder) = &recorder {
+                    recorder.record_ui(&event, chrono::Local::now());
+                }
+                let active = if policy.requires_pin() {
+                    countdown_manager_clone.lock().await.get_active_task().await
+                } else {
+                    None
+                };
+                if !Self::is_event_allowed(&policy, &event, active.as_ref()) {
+                    warn!("管理策略要求输入正确的PIN，已拒绝取消、暂停、推迟、改期或替换策略关机: {:?}", event);
+                    continue;
+                }
+                if !matches!(event, UIEvent::CancelCountdownWithPin(_)) {
+                    info!("收到UI事件: {:?}", event);
+                }
                 match event {
-                    UIEvent::StartCountdown(time_input, task_type) => {
-                         info!("处理开始倒计时事件: {:?}", time_input);
-                         let target_time = match time_input {
-                             TimeInput::Duration(duration) => chrono::Local::now() + duration,
-                             TimeInput::AbsoluteTime(datetime) => datetime,
-                             TimeInput::DailyTime(time) => {
-                                 // 将每日时间转换为今天的绝对时间
-                                 let today = chrono::Local::now().date_naive();
-                                 today.and_time(time).and_local_timezone(chrono::Local).unwrap()
-                             },
-                         };
-                        let countdown_manager = countdown_manager_clone.lock().await;
-                        if let Err(e) = countdown_manager.start_countdown(target_time).await {
-                            error!("启动倒计时失败: {}", e);
+                    UIEvent::StartCountdown(time_input, task_type, action) => {
+                         info!("处理开始倒计时事件: {:?}，操作: {}", time_input, action);
+                        // 与预览使用相同的方式计算目标时间
+                        let mut task_data = match TaskData::from_time_input(task_type, time_input, action, chrono::Local::now()) {
+                            Ok(task_data) => task_data,
+                            Err(e) => {
+                                error!("创建倒计时任务失败: {}", e);
+                                continue;
+                            }
+                        };
+                        if action == TaskAction::RunCommand {
+                            task_data.custom_command = ConfigManager::load_custom_command();
                         }
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        let Some(task_data) = Self::hold_short_countdown(&countdown_manager, &mut pending_short_start, task_data) else {
+                            continue;
+                        };
+                        Self::detect_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                        Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
                     },
-                    UIEvent::CancelCountdown => {
+                    UIEvent::CancelCountdown | UIEvent::CancelCountdownWithPin(_) => {
                         info!("处理取消倒计时事件");
                         let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Some(watch) = trigger_watch.take().filter(|watch| !watch.is_finished()) {
+                            watch.abort();
+                            info!("已停止等待下载完成或进程退出");
+                            countdown_manager.publish_update(CountdownUpdate::Cancelled);
+                        }
+                        let task = countdown_manager.get_active_task().await;
+                        if let Some(task) = &task {
+                            if let Err(e) = task_store.cancel(task, chrono::Local::now()) {
+                                error!("更新已取消的任务失败: {}", e);
+                            }
+                        }
                         if let Err(e) = countdown_manager.cancel_countdown().await {
                             error!("取消倒计时失败: {}", e);
                         }
+                        if let Err(e) = history.append(HistoryEntry::cancelled(task.as_ref(), chrono::Local::now())) {
+                            error!("记录取消历史失败: {}", e);
+                        }
+                        Self::spawn_webhook(WebhookEvent::Cancelled, task.as_ref().map(|task| task.action), task, None);
+                        // 继续调度其他任务，被取消的策略关机只跳过本次，之后每天仍按时执行
+                        Self::schedule_next(&task_store, Some(&policy), &countdown_manager).await;
+                    },
+                    UIEvent::RemoveTask(id) => {
+                        info!("处理删除任务事件: {}", id);
+                        match task_store.remove(&id) {
+                            Ok(true) => {
+                                let countdown_manager = countdown_manager_clone.lock().await;
+                                Self::schedule_next(&task_store, Some(&policy), &countdown_manager).await;
+                            },
+                            Ok(false) => warn!("要删除的任务不存在: {}", id),
+                            Err(e) => error!("删除任务失败: {}", e),
+                        }
+                    },
+                    UIEvent::SetTaskEnabled(id, enabled) => {
+                        info!("处理{}任务事件: {}", if enabled { "启用" } else { "停用" }, id);
+                        match task_store.set_enabled(&id, enabled) {
+                            Ok(_) => {
+                                let countdown_manager = countdown_manager_clone.lock().await;
+                                Self::schedule_next(&task_store, Some(&policy), &countdown_manager).await;
+                            },
+                            Err(e) => error!("修改任务失败: {}", e),
+                        }
+                    },
+                    UIEvent::UpdateTask(task_data) => {
+                        info!("处理修改任务事件: {}", task_data.id);
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Err(e) = Self::replace_and_schedule(&task_store, &policy, &countdown_manager, task_data).await {
+                            error!("修改任务失败: {}", e);
+                        }
+                    },
+                    UIEvent::PauseCountdown => {
+                        info!("处理暂停倒计时事件");
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Err(e) = countdown_manager.pause_countdown().await {
+                            error!("暂停倒计时失败: {}", e);
+                        }
+                    },
+                    UIEvent::ResumeCountdown => {
+                        info!("处理恢复倒计时事件");
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Err(e) = countdown_manager.resume_countdown().await {
+                            error!("恢复倒计时失败: {}", e);
+                        }
+                    },
+                    UIEvent::ExtendCountdown(seconds) => {
+                        info!("处理延长倒计时事件: {} 秒", seconds);
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Err(e) = countdown_manager.extend(chrono::Duration::seconds(seconds as i64)).await {
+                            error!("延长倒计时失败: {}", e);
+                        }
+                    },
+                    UIEvent::ShortenCountdown(seconds) => {
+                        info!("处理缩短倒计时事件: {} 秒", seconds);
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Err(e) = countdown_manager.subtract_time(chrono::Duration::seconds(seconds as i64)).await {
+                            error!("缩短倒计时失败: {}", e);
+                        }
+                    },
+                    UIEvent::Reschedule(choice) => {
+                        info!("处理改期事件: {}", choice.label());
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        if let Err(e) = Self::reschedule_active(&task_store, &countdown_manager, choice).await {
+                            error!("改期失败: {}", e);
+                        }
+                    },
+                    UIEvent::RecordCancelReason(reason) => {
+                        info!("处理取消原因事件: {}", reason);
+                        if let Err(e) = history.set_last_cancel_reason(reason) {
+                            error!("记录取消原因失败: {}", e);
+                        }
+                    },
+                    UIEvent::CancelExternalShutdown => {
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        Self::cancel_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                    },
+                    UIEvent::RestoreTaskBackup => {
+                        info!("处理恢复任务备份事件");
+                        match task_store.restore_previous() {
+                            Ok(count) => {
+                                info!("已恢复上一个版本的任务文件，共 {} 个任务", count);
+                                let countdown_manager = countdown_manager_clone.lock().await;
+                                Self::schedule_next(&task_store, Some(&policy), &countdown_manager).await;
+                            },
+                            Err(e) => error!("恢复任务备份失败: {}", e),
+                        }
+                    },
+                    UIEvent::ImportTasks(tasks) => {
+                        info!("处理导入任务事件，共 {} 个任务", tasks.len());
+                        match task_store.replace_all(tasks) {
+                            Ok(count) => {
+                                info!("已用导入的 {} 个任务替换当前任务", count);
+                                let countdown_manager = countdown_manager_clone.lock().await;
+                                Self::schedule_next(&task_store, Some(&policy), &countdown_manager).await;
+                            },
+                            Err(e) => error!("导入任务失败: {}", e),
+                        }
+                    },
+                    UIEvent::QuickCountdown(duration) => {
+                        info!("处理快速倒计时事件: {} 秒", duration.num_seconds());
+                        let task_data = match Self::build_quick_task(duration) {
+                            Ok(task_data) => task_data,
+                            Err(e) => {
+                                error!("创建快速倒计时任务失败: {}", e);
+                                continue;
+                            }
+                        };
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        let Some(task_data) = Self::hold_short_countdown(&countdown_manager, &mut pending_short_start, task_data) else {
+                            continue;
+                        };
+                        Self::detect_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                        Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                    },
+                    UIEvent::RepeatLast => {
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        match Self::build_repeat_task(&history) {
+                            Ok(task_data) => {
+                                info!("重复上次的倒计时: {}", task_data.schedule_text());
+                                if let Some(task_data) = Self::hold_short_countdown(&countdown_manager, &mut pending_short_start, task_data) {
+                                    Self::detect_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                                    Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                                }
+                            },
+                            Err(e) => {
+                                warn!("重复上次失败: {}", e);
+                                countdown_manager.publish_update(CountdownUpdate::Notice {
+                                    title: "QtShut - 重复上次".to_string(),
+                                    content: e.to_string(),
+                                });
+                            },
+                        }
                     },
-                    UIEvent::QuickCountdown(seconds) => {
-                        info!("处理快速倒计时事件: {} 秒", seconds);
-                        let duration = chrono::Duration::seconds(seconds as i64);
-                        let target_time = chrono::Local::now() + duration;
+                    UIEvent::ConfirmShortStart(confirmed) => {
+                        let Some(task_data) = pending_short_start.take() else {
+                            continue;
+                        };
+                        if !confirmed {
+                            info!("用户放弃了不到一分钟的倒计时: {}", task_data.schedule_text());
+                            continue;
+                        }
+                        info!("用户确认开始不到一分钟的倒计时: {}", task_data.schedule_text());
                         let countdown_manager = countdown_manager_clone.lock().await;
-                        if let Err(e) = countdown_manager.start_countdown(target_time).await {
-                            error!("启动快速倒计时失败: {}", e);
+                        Self::detect_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                        Self::add_and_schedule(&task_store, &policy, &countdown_manager, Self::confirmed_short_countdown(task_data, chrono::Local::now())).await;
+                    },
+                    UIEvent::WaitForNetworkIdle(action) => {
+                        if let Some(watch) = trigger_watch.take() {
+                            watch.abort();
+                        }
+                        let settings = ConfigManager::load_network_idle();
+                        info!("处理等待下载完成事件: {}后{}", settings.describe(), action);
+                        let updates = countdown_manager_clone.lock().await.update_publisher();
+                        let countdown_manager = countdown_manager_clone.clone();
+                        let task_store = task_store.clone();
+                        let policy = policy.clone();
+                        trigger_watch = Some(tokio::spawn(async move {
+                            if let Err(e) = Self::wait_for_network_idle(settings, &updates).await {
+                                error!("等待下载完成失败: {}", e);
+                                let _ = updates.send(CountdownUpdate::Error(e.to_string()));
+                                return;
+                            }
+                            match Self::build_final_warning_task(action) {
+                                Ok(task_data) => {
+                                    let countdown_manager = countdown_manager.lock().await;
+                                    Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                                },
+                                Err(e) => error!("创建下载完成后的任务失败: {}", e),
+                            }
+                        }));
+                    },
+                    UIEvent::WaitForProcessExit(trigger, action) => {
+                        if let Some(watch) = trigger_watch.take() {
+                            watch.abort();
+                        }
+                        info!("处理等待进程退出事件: {}{}", trigger.describe(), action);
+                        let updates = countdown_manager_clone.lock().await.update_publisher();
+                        let countdown_manager = countdown_manager_clone.clone();
+                        let task_store = task_store.clone();
+                        let policy = policy.clone();
+                        trigger_watch = Some(tokio::spawn(async move {
+                            if let Err(e) = Self::wait_for_process_exit(&trigger).await {
+                                error!("等待进程退出失败: {}", e);
+                                let _ = updates.send(CountdownUpdate::Error(e.to_string()));
+                                return;
+                            }
+                            match Self::build_delayed_task(action, trigger.delay()) {
+                                Ok(task_data) => {
+                                    let countdown_manager = countdown_manager.lock().await;
+                                    Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                                },
+                                Err(e) => error!("创建进程退出后的任务失败: {}", e),
+                            }
+                        }));
+                    },
+                    UIEvent::WaitForPowerLoss(action) => {
+                        if let Some(watch) = trigger_watch.take() {
+                            watch.abort();
+                        }
+                        info!("处理等待UPS断电事件: 断电后{}", action);
+                        let updates = countdown_manager_clone.lock().await.update_publisher();
+                        let countdown_manager = countdown_manager_clone.clone();
+                        let task_store = task_store.clone();
+                        let policy = policy.clone();
+                        trigger_watch = Some(tokio::spawn(async move {
+                            if let Err(e) = Self::wait_for_power_loss().await {
+                                error!("等待UPS断电失败: {}", e);
+                                let _ = updates.send(CountdownUpdate::Error(e.to_string()));
+                                return;
+                            }
+                            match Self::build_final_warning_task(action) {
+                                Ok(task_data) => {
+                                    let countdown_manager = countdown_manager.lock().await;
+                                    Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                                },
+                                Err(e) => error!("创建断电后的任务失败: {}", e),
+                            }
+                        }));
+                    },
+                    UIEvent::ScreenTimeLimitReached(action) => {
+                        info!("处理屏幕使用时间达到上限事件: {}", action);
+                        let task_data = match Self::build_screen_time_task(action) {
+                            Ok(task_data) => task_data,
+                            Err(e) => {
+                                error!("创建屏幕使用时间任务失败: {}", e);
+                                continue;
+                            }
+                        };
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                    },
+                    UIEvent::StartWeekly(slots) => {
+                        info!("处理每周计划事件: {} 个时间段", slots.len());
+                        let task_data = Self::build_weekly_task(slots);
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        let Some(task_data) = Self::hold_short_countdown(&countdown_manager, &mut pending_short_start, task_data) else {
+                            continue;
+                        };
+                        Self::detect_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                        Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                    },
+                    UIEvent::SaveLogonRoutine(steps) => {
+                        if let Err(e) = task_store.save_logon_routine(steps, chrono::Local::now()) {
+                            error!("保存登录任务失败: {}", e);
+                        }
+                    },
+                    UIEvent::RemoteCommand { action, delay, from } => {
+                        let task_data = match Self::build_remote_task(action, delay) {
+                            Ok(task_data) => task_data,
+                            Err(e) => {
+                                error!("创建远程关机任务失败: {}", e);
+                                continue;
+                            }
+                        };
+                        let countdown_manager = countdown_manager_clone.lock().await;
+                        countdown_manager.publish_update(CountdownUpdate::Notice {
+                            title: "QtShut - 远程关机".to_string(),
+                            content: format!("{}请求在{}后{}，可以在主窗口中取消", from, format_warning_offset(delay), action),
+                        });
+                        let Some(task_data) = Self::hold_short_countdown(&countdown_manager, &mut pending_short_start, task_data) else {
+                            continue;
+                        };
+                        Self::detect_external_shutdown(&*shutdown_executor_clone.lock().await, &countdown_manager).await;
+                        Self::add_and_schedule(&task_store, &policy, &countdown_manager, task_data).await;
+                    },
+                    UIEvent::SetSystemTasks(enabled) => {
+                        info!("处理{}任务计划程序同步事件", if enabled { "开启" } else { "关闭" });
+                        task_store.set_mirror_enabled(enabled);
+                    },
+                    UIEvent::SessionEnding(done) => {
+                        let task = countdown_manager_clone.lock().await.get_active_task().await;
+                        if let Err(e) = Self::reconcile_session_end(&task_store, &history, task.as_ref()) {
+                            error!("保存会话结束前的任务状态失败: {}", e);
+                        }
+                        let _ = done.send(());
+                    },
+                    UIEvent::SystemResumed => {
+                        countdown_manager_clone.lock().await.notify_resumed();
+                    },
+                    UIEvent::ShowMainWindow | UIEvent::ToggleMainWindow | UIEvent::ShowSettings | UIEvent::ShowAbout => {
+                        // 托盘菜单的窗口请求转给连接的界面，主窗口隐藏时也能重新显示
+                        let request = match event {
+                            UIEvent::ShowMainWindow => WindowRequest::Show,
+                            UIEvent::ShowSettings => WindowRequest::Settings,
+                            UIEvent::ShowAbout => WindowRequest::About,
+                            _ => WindowRequest::Toggle,
+                        };
+                        countdown_manager_clone.lock().await.publish_update(CountdownUpdate::WindowRequested(request));
+                    },
+                    UIEvent::Exit => {
+                        // 托盘菜单的退出转给界面，由界面恢复专注助手等设置后退出；没有界面时直接退出
+                        let manager = countdown_manager_clone.lock().await;
+                        if manager.update_publisher().receiver_count() > 0 {
+                            manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::Exit));
+                        } else {
+                            info!("退出应用程序");
+                            std::process::exit(0);
                         }
                     },
                     _ => {
@@ -173,14 +1167,502 @@ impl App {
             info!("UI事件处理循环结束");
         });
 
-        // 启动GUI事件循环，传递必要的参数
-        crate::ui::manager::run_with_params(
+        Ok(CoreHandle {
             time_parser,
-            Some(countdown_receiver),
-            Some(ui_event_sender),
-        )?;
-
-        Ok(())
+            countdown_receiver,
+            ui_event_sender,
+            status_handle,
+            task_list,
+        })
+    }
+    
+    /// 启动最后警告期间的输入监视
+    /// 
+    /// 进入最后警告后检测到键盘鼠标输入时，按设置发送暂停或取消事件，
+    /// 避免还有人在使用电脑时关机丢失数据。管理策略要求PIN时不启动
+    /// 
+    /// # 参数
+    /// 
+    /// * `receiver` - 倒计时更新接收器
+    /// * `ui_event_sender` - 发送暂停或取消事件的UI事件通道
+    /// * `policy` - 管理策略
+    fn start_activity_watch(
+        mut receiver: tokio::sync::broadcast::Receiver<CountdownUpdate>,
+        ui_event_sender: tokio::sync::mpsc::UnboundedSender<UIEvent>,
+        policy: &Policy,
+    ) {
+        let behavior = ConfigManager::load_activity_behavior();
+        if behavior == ActivityBehavior::Ignore || !platform::capabilities().idle_detection {
+            return;
+        }
+        if policy.requires_pin() {
+            info!("管理策略要求PIN才能取消，不启动最后警告期间的输入监视");
+            return;
+        }
+        let event = match behavior {
+            ActivityBehavior::Pause => UIEvent::PauseCountdown,
+            _ => UIEvent::CancelCountdown,
+        };
+        
+        tokio::spawn(async move {
+            let mut watch: Option<InputWatch> = None;
+            let mut triggered = false;
+            loop {
+                match receiver.recv().await {
+                    Ok(CountdownUpdate::Progress { remaining, .. }) => {
+                        let seconds = remaining.num_seconds();
+                        if seconds <= 0 || seconds > FINAL_WARNING_SECONDS {
+                            watch = None;
+                            continue;
+                        }
+                        let watch = watch.get_or_insert_with(InputWatch::start);
+                        if !triggered && watch.has_input() {
+                            info!("最后警告期间检测到键盘鼠标输入，发送{:?}", event);
+                            triggered = true;
+                            if ui_event_sender.send(event.clone()).is_err() {
+                                break;
+                            }
+                        }
+                    },
+                    Ok(_) => {
+                        watch = None;
+                        triggered = false;
+                    },
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("输入监视消息滞后，跳过了{}条消息", skipped);
+                    },
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+    
+    /// 启动屏幕使用时间统计
+    /// 
+    /// 定期采样会话状态，会话锁定或输入空闲时不计时；当天的使用时间达到设置的上限时
+    /// 发送事件开始最后警告。每次采样都重新读取设置，修改上限后立即生效
+    /// 
+    /// # 参数
+    /// 
+    /// * `ui_event_sender` - 发送达到上限事件的UI事件通道
+    fn start_screen_time_watch(ui_event_sender: tokio::sync::mpsc::UnboundedSender<UIEvent>) {
+        if !platform::capabilities().idle_detection {
+            info!("当前系统无法检测输入空闲，不统计屏幕使用时间");
+            return;
+        }
+        
+        tokio::spawn(async move {
+            let mut tracker: Option<UsageTracker> = None;
+            let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let settings = ConfigManager::load_screen_time();
+                let Some(limit) = settings.daily_limit() else {
+                    tracker = None;
+                    continue;
+                };
+                let tracker = tracker.get_or_insert_with(|| UsageTracker::new(limit));
+                tracker.set_daily_limit(limit);
+                tracker.sample();
+                if tracker.take_limit_reached() {
+                    info!("今天的屏幕使用时间已达到{}分钟，开始最后警告后{}", limit.as_secs() / 60, settings.action);
+                    if ui_event_sender.send(UIEvent::ScreenTimeLimitReached(settings.action)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+    
+    /// 启动倒计时看门狗
+    /// 
+    /// 定期检查倒计时任务的心跳，任务卡住或中止导致心跳超时时按到点时间重新开始，
+    /// 并显示错误通知，避免到点后一直不执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `countdown_manager` - 共享的倒计时管理器
+    fn start_watchdog(countdown_manager: std::sync::Arc<tokio::sync::Mutex<CountdownManager>>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(WATCHDOG_INTERVAL_SECONDS));
+            loop {
+                interval.tick().await;
+                let countdown_manager = countdown_manager.lock().await;
+                let Some(reason) = countdown_manager.stalled_reason(tokio::time::Instant::now()) else {
+                    continue;
+                };
+                error!("看门狗发现{}", reason);
+                match countdown_manager.restart_stalled().await {
+                    Ok(target_time) => countdown_manager.publish_update(CountdownUpdate::Error(format!(
+                        "{}，已按到点时间 {} 重新开始倒计时",
+                        reason,
+                        target_time.format("%H:%M:%S")
+                    ))),
+                    Err(e) => countdown_manager.set_error(format!("{}，重新开始倒计时失败: {}", reason, e)).await,
+                }
+            }
+        });
+    }
+    
+    /// 执行今天还没有执行的登录任务
+    /// 
+    /// 等待主窗口和托盘初始化后按顺序执行各个步骤，某个步骤失败时记录错误并继续执行后面的步骤；
+    /// 安全模式下不执行，登录任务保留到下次正常启动
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `shutdown_executor` - 关机执行器，用于运行自定义命令
+    /// * `updates` - 通知发送器，用于显示今日计划
+    fn start_logon_routines(
+        task_store: std::sync::Arc<TaskStore>,
+        shutdown_executor: std::sync::Arc<tokio::sync::Mutex<ShutdownExecutor>>,
+        updates: tokio::sync::broadcast::Sender<CountdownUpdate>,
+    ) {
+        if system::is_safe_mode() {
+            info!("安全模式下不执行登录任务");
+            return;
+        }
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(LOGON_ROUTINE_DELAY_SECONDS)).await;
+            let tasks = match task_store.take_due_logon_routines(chrono::Local::now().date_naive()) {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    error!("读取登录任务失败: {}", e);
+                    return;
+                }
+            };
+            
+            for task in tasks {
+                let Some(routine) = task.routine else {
+                    continue;
+                };
+                info!("执行登录任务: {}", routine.describe());
+                for step in routine.steps {
+                    let result = match step {
+                        RoutineStep::ShowSchedule => {
+                            let content = routine::today_schedule_text(&task_store.tasks(), chrono::Local::now());
+                            updates.send(CountdownUpdate::Notice { title: "QtShut - 今日计划".to_string(), content })
+                                .map(|_| ())
+                                .map_err(|e| anyhow::anyhow!("发送提示失败: {}", e))
+                        },
+                        RoutineStep::SetVolume(percent) => platform::native().set_volume(percent),
+                        RoutineStep::RunCommand => match task.custom_command.clone().or_else(ConfigManager::load_custom_command) {
+                            Some(command) => shutdown_executor.lock().await.run_custom_command(&command),
+                            None => Err(anyhow::anyhow!("加载自定义命令失败")),
+                        },
+                    };
+                    if let Err(e) = result {
+                        error!("登录任务步骤\"{}\"执行失败: {}", step, e);
+                    }
+                }
+            }
+        });
+    }
+    
+    /// 等待网络空闲
+    /// 
+    /// 定期采样网络速度并通知界面，网速持续低于设置值足够长时间后返回
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 网络空闲条件
+    /// * `updates` - 通知发送器
+    async fn wait_for_network_idle(
+        settings: NetworkIdleSettings,
+        updates: &tokio::sync::broadcast::Sender<CountdownUpdate>,
+    ) -> Result<()> {
+        if platform::native().network_bytes().is_none() {
+            return Err(anyhow::anyhow!("当前平台无法获取网络流量，不能等待下载完成"));
+        }
+        
+        let mut sampler = NetworkSampler::new();
+        sampler.sample();
+        let mut watch = NetworkIdleWatch::new(settings.clone());
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(NETWORK_SAMPLE_SECONDS));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Some(rate_kbps) = sampler.sample() else {
+                continue;
+            };
+            let state = watch.update(rate_kbps, std::time::Instant::now());
+            let quiet_seconds = match state {
+                NetworkIdleState::Busy => 0,
+                NetworkIdleState::Quiet(quiet_for) => quiet_for.as_secs(),
+                NetworkIdleState::Idle => settings.quiet_duration().as_secs(),
+            };
+            let _ = updates.send(CountdownUpdate::NetworkWaiting { rate_kbps, quiet_seconds });
+            if state == NetworkIdleState::Idle {
+                info!("{}，下载已完成", settings.describe());
+                return Ok(());
+            }
+        }
+    }
+    
+    /// 等待进程退出
+    /// 
+    /// 定期检查进程列表，指定的进程不再运行时返回
+    /// 
+    /// # 参数
+    /// 
+    /// * `trigger` - 进程退出触发
+    async fn wait_for_process_exit(trigger: &ProcessExitTrigger) -> Result<()> {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(PROCESS_SAMPLE_SECONDS));
+        loop {
+            interval.tick().await;
+            let running = system::get_running_processes()
+                .ok_or_else(|| anyhow::anyhow!("当前平台无法枚举进程，不能等待进程退出"))?;
+            if !trigger.is_running(&running) {
+                info!("{}已退出", trigger.process);
+                return Ok(());
+            }
+        }
+    }
+    
+    /// 等待市电断开
+    /// 
+    /// 定期检查电源状态，UPS（或笔记本电池）开始供电时返回；
+    /// 开始等待时已在使用电池供电则立即返回
+    async fn wait_for_power_loss() -> Result<()> {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(POWER_SAMPLE_SECONDS));
+        loop {
+            interval.tick().await;
+            let status = system::get_power_status()
+                .ok_or_else(|| anyhow::anyhow!("检测不到UPS或电池，不能等待断电"))?;
+            if status.on_battery {
+                match status.battery_percent {
+                    Some(percent) => info!("市电已断开，正在使用电池供电，剩余电量{}%", percent),
+                    None => info!("市电已断开，正在使用电池供电"),
+                }
+                return Ok(());
+            }
+        }
+    }
+    
+    /// 创建最后警告任务
+    /// 
+    /// 非定时触发（如下载完成）后先显示最后警告，再执行操作
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 要执行的操作
+    fn build_final_warning_task(action: TaskAction) -> Result<TaskData> {
+        Self::build_delayed_task(action, chrono::Duration::zero())
+    }
+    
+    /// 创建非定时触发后的倒计时任务
+    /// 
+    /// 倒计时至少包含完整的最后警告
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 要执行的操作
+    /// * `delay` - 触发后到执行操作的时长
+    fn build_delayed_task(action: TaskAction, delay: chrono::Duration) -> Result<TaskData> {
+        let duration = delay.max(chrono::Duration::seconds(FINAL_WARNING_SECONDS));
+        TaskData::from_time_input(TaskType::Once, TimeInput::Duration(duration), action, chrono::Local::now())
+    }
+    
+    /// 创建屏幕使用时间达到上限后的任务，先显示最后警告再执行操作
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 要执行的操作
+    fn build_screen_time_task(action: TaskAction) -> Result<TaskData> {
+        let mut task_data = Self::build_final_warning_task(action)?;
+        task_data.source = TaskSource::ScreenTime;
+        Ok(task_data)
+    }
+    
+    /// 创建快速倒计时任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `duration` - 倒计时时长
+    fn build_quick_task(duration: chrono::Duration) -> Result<TaskData> {
+        TaskData::from_time_input(TaskType::Once, TimeInput::Duration(duration), QUICK_COUNTDOWN_ACTION, chrono::Local::now())
+    }
+    
+    /// 按历史记录重新创建上次执行或取消的倒计时，到点时间从现在重新计算
+    /// 
+    /// # 参数
+    /// 
+    /// * `history` - 历史记录
+    fn build_repeat_task(history: &HistoryLog) -> Result<TaskData> {
+        let repeat = history.last_repeatable()?
+            .ok_or_else(|| anyhow::anyhow!("还没有可以重复的倒计时"))?;
+        repeat.to_task(chrono::Local::now())
+    }
+    
+    /// 创建其他电脑远程发来的任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    /// * `delay` - 多长时间后执行
+    fn build_remote_task(action: TaskAction, delay: chrono::Duration) -> Result<TaskData> {
+        let mut task_data = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(delay), action, chrono::Local::now())?;
+        task_data.source = TaskSource::Remote;
+        Ok(task_data)
+    }
+    
+    /// 根据时间段创建每周计划任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `slots` - 每周计划的时间段，没有时间段的日子不执行任何操作
+    fn build_weekly_task(slots: Vec<WeeklySlot>) -> TaskData {
+        let now = chrono::Local::now();
+        let mut task_data = TaskData {
+            id: new_task_id(),
+            task_type: TaskType::Weekly,
+            target_time: None,
+            daily_time: None,
+            enabled: true,
+            created_at: now,
+            action: TaskAction::Shutdown,
+            weekly_slots: slots,
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: None,
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
+        };
+        
+        if let Some((target_time, action)) = schedule::next_weekly(&task_data.weekly_slots, now) {
+            task_data.target_time = Some(target_time);
+            task_data.action = action;
+        }
+        task_data
+    }
+    
+    /// 等待工作站解锁
+    async fn wait_for_unlock() {
+        while is_session_locked() {
+            tokio::time::sleep(std::time::Duration::from_secs(UNLOCK_POLL_INTERVAL_SECONDS)).await;
+        }
+        info!("工作站已解锁");
+    }
+    
+    /// 生成解锁后显示最后警告的任务
+    /// 
+    /// 改为最后警告结束时执行的单次倒计时，任务标识不变，执行后仍按原任务更新任务存储
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 到点时推迟的任务
+    fn deferred_warning_task(task: &TaskData) -> TaskData {
+        Self::postponed_task(task, chrono::Duration::seconds(FINAL_WARNING_SECONDS))
+    }
+    
+    /// 生成推迟执行的任务
+    /// 
+    /// 改为指定时长后执行的单次倒计时，任务标识不变，执行后仍按原任务更新任务存储
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 推迟的任务
+    /// * `delay` - 推迟的时长
+    fn postponed_task(task: &TaskData, delay: chrono::Duration) -> TaskData {
+        TaskData {
+            task_type: TaskType::Once,
+            target_time: Some(chrono::Local::now() + delay),
+            ..task.clone()
+        }
+    }
+    
+    /// 加载任务存储
+    /// 
+    /// 当前平台支持时附带任务计划程序同步器，按设置决定是否同步
+    /// 
+    /// # 参数
+    /// 
+    /// * `storage` - 保存任务的存储
+    fn load_task_store(storage: std::sync::Arc<dyn Storage>) -> TaskStore {
+        let task_store = TaskStore::load(storage);
+        if !platform::capabilities().system_scheduler {
+            return task_store;
+        }
+        task_store.with_mirror(SystemTaskMirror::start(ConfigManager::load_system_tasks()))
+    }
+    
+    /// 不到一分钟就会执行操作的倒计时先请求界面确认
+    /// 
+    /// 命令行、托盘、远程命令和界面开始的倒计时都经过这里，"3秒"这类误输入不会直接关机
+    /// 
+    /// # 参数
+    /// 
+    /// * `countdown_manager` - 倒计时管理器，用于向界面发出确认请求
+    /// * `pending` - 等待确认的任务，新的短倒计时替换之前未确认的任务
+    /// * `task_data` - 要开始的任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 可以直接开始时返回任务，需要等待确认时返回None
+    fn hold_short_countdown(countdown_manager: &CountdownManager, pending: &mut Option<TaskData>, task_data: TaskData) -> Option<TaskData> {
+        let now = chrono::Local::now();
+        let target_time = match task_data.target_time {
+            Some(target_time) if is_short_countdown(target_time, now) => target_time,
+            _ => return Some(task_data),
+        };
+        let seconds = (target_time - now).num_seconds().max(0);
+        warn!("倒计时只有{}秒，开始前需要用户确认", seconds);
+        countdown_manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::ConfirmShortStart { seconds, action: task_data.action }));
+        *pending = Some(task_data);
+        None
+    }
+    
+    /// 用户确认后的短倒计时
+    /// 
+    /// 单次倒计时从确认时重新计时，等待确认的时间不占用倒计时
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_data` - 等待确认的任务
+    /// * `now` - 确认的时间
+    fn confirmed_short_countdown(mut task_data: TaskData, now: chrono::DateTime<chrono::Local>) -> TaskData {
+        if task_data.task_type == TaskType::Once {
+            task_data.target_time = task_data.target_time.map(|target_time| now + (target_time - task_data.created_at));
+        }
+        task_data
+    }
+    
+    /// 检查外部关机计划
+    /// 
+    /// 检测到其他程序或手动设置的关机计划时通知UI，由用户选择保留或取消，不直接取消该计划
+    /// 
+    /// # 参数
+    /// 
+    /// * `shutdown_executor` - 关机执行器
+    /// * `countdown_manager` - 倒计时管理器，用于发布通知
+    async fn detect_external_shutdown(shutdown_executor: &ShutdownExecutor, countdown_manager: &CountdownManager) {
+        if shutdown_executor.has_pending_shutdown().await {
+            warn!("检测到外部关机计划，等待用户选择保留或取消");
+            countdown_manager.publish_update(CountdownUpdate::ExternalShutdownDetected);
+        }
+    }
+    
+    /// 取消检测到的外部关机计划，并通知UI结果
+    /// 
+    /// # 参数
+    /// 
+    /// * `shutdown_executor` - 关机执行器
+    /// * `countdown_manager` - 倒计时管理器，用于发布通知
+    async fn cancel_external_shutdown(shutdown_executor: &ShutdownExecutor, countdown_manager: &CountdownManager) {
+        let content = match shutdown_executor.cancel_external_shutdown().await {
+            Ok(true) => "已取消其他关机计划，可以在QtShut中重新设置关机时间".to_string(),
+            Ok(false) => "其他关机计划已经不存在".to_string(),
+            Err(e) => {
+                error!("取消外部关机计划失败: {}", e);
+                format!("取消其他关机计划失败: {}", e)
+            },
+        };
+        countdown_manager.publish_update(CountdownUpdate::Notice {
+            title: "QtShut - 外部关机计划".to_string(),
+            content,
+        });
     }
 
     /// 处理用户输入的时间设置
@@ -196,54 +1678,69 @@ impl App {
         // 验证时间有效性
         self.time_parser.validate(&time_input)?;
 
-        // 启动倒计时
-        let target_time = match time_input {
-            crate::core::types::TimeInput::Duration(duration) => {
-                chrono::Local::now() + duration
-            },
-            crate::core::types::TimeInput::AbsoluteTime(datetime) => datetime,
-            crate::core::types::TimeInput::DailyTime(time) => {
-                // 计算下一个匹配的日期时间
-                let now = chrono::Local::now();
-                let today = now.date_naive();
-                let target_datetime = today.and_time(time);
-                
-                if target_datetime > now.naive_local() {
-                    chrono::Local.from_local_datetime(&target_datetime).unwrap()
-                } else {
-                    // 如果今天的时间已过，设置为明天
-                    let tomorrow = today + chrono::Duration::days(1);
-                    chrono::Local.from_local_datetime(&tomorrow.and_time(time)).unwrap()
-                }
-            }
-        };
-
-        // 保存任务
-        let task_data = crate::core::types::TaskData {
-            task_type,
-            target_time: Some(target_time),
-            daily_time: if let crate::core::types::TimeInput::DailyTime(time) = time_input {
-                Some(time)
-            } else {
-                None
-            },
-            enabled: true,
-            created_at: chrono::Local::now(),
-        };
+        // 计算目标时间并保存任务
+        let task_data = TaskData::from_time_input(task_type, time_input, TaskAction::Shutdown, chrono::Local::now())?;
+        let target_time = task_data.target_time;
         
-        self.task_persistence.save_task(&task_data)?;
+        self.task_store.add(task_data)?;
 
         // 启动倒计时
-        self.countdown_manager.start_countdown(target_time).await?;
+        Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
 
         info!("定时关机任务已设置: {:?}", target_time);
         Ok(())
     }
 
+    /// 系统因其他原因结束会话时整理任务状态
+    /// 
+    /// 还在倒计时的任务记为被系统关机打断：单次任务删除，下次启动时不会恢复已过时的倒计时；
+    /// 重复任务更新为下一次执行时间。本程序执行的关机不会走到这里，因为到点后倒计时已结束
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_store` - 任务存储
+    /// * `history` - 历史记录
+    /// * `task` - 还在倒计时的任务
+    fn reconcile_session_end(task_store: &TaskStore, history: &HistoryLog, task: Option<&TaskData>) -> Result<()> {
+        let task = match task {
+            Some(task) => task,
+            None => {
+                info!("系统正在结束会话，没有进行中的倒计时");
+                return Ok(());
+            }
+        };
+        
+        info!("系统在任务 {} 执行前结束会话", task.id);
+        let now = chrono::Local::now();
+        task_store.complete(&task.id, RunResult::Interrupted, now)?;
+        history.append(HistoryEntry::session_ended(task, now))?;
+        Ok(())
+    }
+    
+    /// 在后台按设置发送外部通知，不等待发送完成
+    /// 
+    /// # 参数
+    /// 
+    /// * `event` - 事件
+    /// * `action` - 操作
+    /// * `task` - 相关的任务
+    /// * `detail` - 取消原因或失败原因
+    fn spawn_webhook(event: WebhookEvent, action: Option<TaskAction>, task: Option<TaskData>, detail: Option<String>) {
+        tokio::spawn(async move {
+            webhook::notify(&ConfigManager::load_webhook(), event, action, task.as_ref(), detail).await;
+        });
+    }
+    
     /// 取消当前的关机任务
     pub async fn cancel_shutdown(&mut self) -> Result<()> {
+        let task = self.countdown_manager.get_active_task().await;
+        if let Some(task) = &task {
+            self.task_store.cancel(task, chrono::Local::now())?;
+        }
         self.countdown_manager.cancel_countdown().await?;
-        self.task_persistence.clear_task()?;
+        self.history.append(HistoryEntry::cancelled(task.as_ref(), chrono::Local::now()))?;
+        Self::spawn_webhook(WebhookEvent::Cancelled, task.as_ref().map(|task| task.action), task, None);
+        Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
         info!("关机任务已取消");
         Ok(())
     }
@@ -261,20 +1758,136 @@ impl App {
     /// 
     /// * `event` - UI事件
     pub async fn handle_ui_event(&mut self, event: UIEvent) -> Result<()> {
+        let active = self.countdown_manager.get_active_task().await;
+        if !Self::is_event_allowed(&self.policy, &event, active.as_ref()) {
+            return Err(anyhow::anyhow!("管理策略要求输入正确的PIN才能取消、暂停、推迟、改期或替换策略关机"));
+        }
+        
         match event {
-            UIEvent::StartCountdown(time_input, task_type) => {
-                info!("收到开始倒计时事件: {:?}", time_input);
-                self.start_countdown_from_input(time_input, task_type).await?;
+            UIEvent::StartCountdown(time_input, task_type, action) => {
+                info!("收到开始倒计时事件: {:?}，操作: {}", time_input, action);
+                self.start_countdown_from_input(time_input, task_type, action).await?;
             },
-            UIEvent::CancelCountdown => {
+            UIEvent::CancelCountdown | UIEvent::CancelCountdownWithPin(_) => {
                 info!("收到取消倒计时事件");
                 self.cancel_shutdown().await?;
             },
-            UIEvent::QuickCountdown(seconds) => {
-                info!("收到快速倒计时事件: {} 秒", seconds);
-                let duration = chrono::Duration::seconds(seconds as i64);
-                let target_time = chrono::Local::now() + duration;
-                self.countdown_manager.start_countdown(target_time).await?;
+            UIEvent::PauseCountdown => {
+                info!("收到暂停倒计时事件");
+                self.countdown_manager.pause_countdown().await?;
+            },
+            UIEvent::ResumeCountdown => {
+                info!("收到恢复倒计时事件");
+                self.countdown_manager.resume_countdown().await?;
+            },
+            UIEvent::ExtendCountdown(seconds) => {
+                info!("收到延长倒计时事件: {} 秒", seconds);
+                self.countdown_manager.extend(chrono::Duration::seconds(seconds as i64)).await?;
+            },
+            UIEvent::ShortenCountdown(seconds) => {
+                info!("收到缩短倒计时事件: {} 秒", seconds);
+                self.countdown_manager.subtract_time(chrono::Duration::seconds(seconds as i64)).await?;
+            },
+            UIEvent::Reschedule(choice) => {
+                info!("收到改期事件: {}", choice.label());
+                Self::reschedule_active(&self.task_store, &self.countdown_manager, choice).await?;
+            },
+            UIEvent::RecordCancelReason(reason) => {
+                info!("收到取消原因事件: {}", reason);
+                self.history.set_last_cancel_reason(reason)?;
+            },
+            UIEvent::CancelExternalShutdown => {
+                info!("收到取消外部关机计划事件");
+                if !self.shutdown_executor.cancel_external_shutdown().await? {
+                    info!("外部关机计划已经不存在");
+                }
+            },
+            UIEvent::RestoreTaskBackup => {
+                info!("收到恢复任务备份事件");
+                self.task_store.restore_previous()?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::ImportTasks(tasks) => {
+                info!("收到导入任务事件，共 {} 个任务", tasks.len());
+                self.task_store.replace_all(tasks)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::QuickCountdown(duration) => {
+                info!("收到快速倒计时事件: {} 秒", duration.num_seconds());
+                self.start_task(Self::build_quick_task(duration)?).await?;
+            },
+            UIEvent::RepeatLast => {
+                info!("收到重复上次事件");
+                self.start_task(Self::build_repeat_task(&self.history)?).await?;
+            },
+            UIEvent::ConfirmShortStart(confirmed) => {
+                if let Some(task_data) = self.pending_short_start.take().filter(|_| confirmed) {
+                    info!("用户确认开始不到一分钟的倒计时: {}", task_data.schedule_text());
+                    self.task_store.add(Self::confirmed_short_countdown(task_data, chrono::Local::now()))?;
+                    Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+                }
+            },
+            UIEvent::WaitForNetworkIdle(action) => {
+                info!("收到等待下载完成事件，操作: {}", action);
+                Self::wait_for_network_idle(ConfigManager::load_network_idle(), &self.countdown_manager.update_publisher()).await?;
+                self.task_store.add(Self::build_final_warning_task(action)?)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::WaitForProcessExit(trigger, action) => {
+                info!("收到等待进程退出事件: {}{}", trigger.describe(), action);
+                Self::wait_for_process_exit(&trigger).await?;
+                self.task_store.add(Self::build_delayed_task(action, trigger.delay())?)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::WaitForPowerLoss(action) => {
+                info!("收到等待UPS断电事件，操作: {}", action);
+                Self::wait_for_power_loss().await?;
+                self.task_store.add(Self::build_final_warning_task(action)?)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::ScreenTimeLimitReached(action) => {
+                info!("收到屏幕使用时间达到上限事件，操作: {}", action);
+                self.task_store.add(Self::build_screen_time_task(action)?)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::StartWeekly(slots) => {
+                info!("收到每周计划事件: {} 个时间段", slots.len());
+                self.start_task(Self::build_weekly_task(slots)).await?;
+            },
+            UIEvent::SaveLogonRoutine(steps) => {
+                info!("收到保存登录任务事件: {} 个步骤", steps.len());
+                self.task_store.save_logon_routine(steps, chrono::Local::now())?;
+            },
+            UIEvent::RemoteCommand { action, delay, from } => {
+                info!("收到来自{}的远程命令: {}", from, action);
+                self.start_task(Self::build_remote_task(action, delay)?).await?;
+            },
+            UIEvent::SetSystemTasks(enabled) => {
+                info!("收到{}任务计划程序同步事件", if enabled { "开启" } else { "关闭" });
+                self.task_store.set_mirror_enabled(enabled);
+            },
+            UIEvent::SessionEnding(done) => {
+                let task = self.countdown_manager.get_active_task().await;
+                let result = Self::reconcile_session_end(&self.task_store, &self.history, task.as_ref());
+                let _ = done.send(());
+                result?;
+            },
+            UIEvent::SystemResumed => {
+                self.countdown_manager.notify_resumed();
+            },
+            UIEvent::RemoveTask(id) => {
+                info!("收到删除任务事件: {}", id);
+                self.task_store.remove(&id)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::SetTaskEnabled(id, enabled) => {
+                info!("收到{}任务事件: {}", if enabled { "启用" } else { "停用" }, id);
+                self.task_store.set_enabled(&id, enabled)?;
+                Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+            },
+            UIEvent::UpdateTask(task_data) => {
+                info!("收到修改任务事件: {}", task_data.id);
+                Self::replace_and_schedule(&self.task_store, &self.policy, &self.countdown_manager, task_data).await?;
             },
             UIEvent::MinimizeToTray => {
                 info!("最小化到托盘");
@@ -286,23 +1899,27 @@ impl App {
             },
             UIEvent::ShowMainWindow => {
                 info!("显示主窗口");
-                // 这里可以添加显示主窗口逻辑
+                self.countdown_manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::Show));
             },
             UIEvent::ToggleMainWindow => {
                 info!("切换主窗口显示状态");
-                // 这里可以添加切换逻辑
+                self.countdown_manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::Toggle));
             },
             UIEvent::ShowSettings => {
                 info!("显示设置窗口");
-                // 这里可以添加设置窗口逻辑
+                self.countdown_manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::Settings));
             },
             UIEvent::ShowAbout => {
                 info!("显示关于窗口");
-                // 这里可以添加关于窗口逻辑
+                self.countdown_manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::About));
             },
             UIEvent::Exit => {
-                info!("退出应用程序");
-                std::process::exit(0);
+                if self.countdown_manager.update_publisher().receiver_count() > 0 {
+                    self.countdown_manager.publish_update(CountdownUpdate::WindowRequested(WindowRequest::Exit));
+                } else {
+                    info!("退出应用程序");
+                    std::process::exit(0);
+                }
             },
         }
         Ok(())
@@ -314,48 +1931,118 @@ impl App {
     /// 
     /// * `time_input` - 时间输入
     /// * `task_type` - 任务类型
-    async fn start_countdown_from_input(&mut self, time_input: TimeInput, task_type: TaskType) -> Result<()> {
-        // 计算目标时间
-        let target_time = match time_input {
-            TimeInput::Duration(duration) => {
-                chrono::Local::now() + duration
-            },
-            TimeInput::AbsoluteTime(datetime) => datetime,
-            TimeInput::DailyTime(time) => {
-                // 计算下一个匹配的日期时间
-                let now = chrono::Local::now();
-                let today = now.date_naive();
-                let target_datetime = today.and_time(time);
-                
-                if target_datetime > now.naive_local() {
-                    chrono::Local.from_local_datetime(&target_datetime).unwrap()
-                } else {
-                    // 如果今天的时间已过，设置为明天
-                    let tomorrow = today + chrono::Duration::days(1);
-                    chrono::Local.from_local_datetime(&tomorrow.and_time(time)).unwrap()
-                }
-            }
+    /// * `action` - 到点后执行的操作
+    async fn start_countdown_from_input(&mut self, time_input: TimeInput, task_type: TaskType, action: TaskAction) -> Result<()> {
+        // 计算目标时间并保存任务
+        let task_data = TaskData::from_time_input(task_type, time_input, action, chrono::Local::now())?;
+        let target_time = task_data.target_time;
+        
+        if self.start_task(task_data).await? {
+            info!("定时关机任务已设置: {:?}", target_time);
+        }
+        Ok(())
+    }
+    
+    /// 保存任务并启动倒计时，不到一分钟就会执行的任务先等待用户确认
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_data` - 要开始的任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 已开始倒计时时返回true，等待确认时返回false
+    async fn start_task(&mut self, task_data: TaskData) -> Result<bool> {
+        let Some(task_data) = Self::hold_short_countdown(&self.countdown_manager, &mut self.pending_short_start, task_data) else {
+            return Ok(false);
         };
+        self.task_store.add(task_data)?;
+        Self::schedule_next(&self.task_store, Some(&self.policy), &self.countdown_manager).await;
+        Ok(true)
+    }
+}
 
-        // 保存任务
-        let task_data = crate::core::types::TaskData {
-            task_type,
-            target_time: Some(target_time),
-            daily_time: if let TimeInput::DailyTime(time) = time_input {
-                Some(time)
-            } else {
-                None
-            },
-            enabled: true,
-            created_at: chrono::Local::now(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_pin_policy_blocks_postpone() {
+        let policy = Policy { cancel_pin_hash: Some(crate::utils::policy::hash_pin("1234")), ..Default::default() };
+        assert!(!App::is_event_allowed(&policy, &UIEvent::CancelCountdown, None));
+        assert!(!App::is_event_allowed(&policy, &UIEvent::PauseCountdown, None));
+        // 推迟和延长同样会让策略要求的关机一直不执行
+        assert!(!App::is_event_allowed(&policy, &UIEvent::ExtendCountdown(600), None));
+        assert!(App::is_event_allowed(&policy, &UIEvent::CancelCountdownWithPin("1234".to_string()), None));
+        assert!(!App::is_event_allowed(&policy, &UIEvent::CancelCountdownWithPin("0000".to_string()), None));
+        assert!(App::is_event_allowed(&policy, &UIEvent::ShortenCountdown(600), None));
+        // "改到明晚"等同于取消今晚的关机
+        assert!(!App::is_event_allowed(&policy, &UIEvent::Reschedule(RescheduleChoice::TomorrowNight), None));
+        assert!(!App::is_event_allowed(&policy, &UIEvent::Reschedule(RescheduleChoice::Later), None));
+        
+        // 没有设置PIN时不限制
+        assert!(App::is_event_allowed(&Policy::default(), &UIEvent::ExtendCountdown(600), None));
+    }
+    
+    #[test]
+    fn test_pin_policy_protects_policy_shutdown() {
+        let policy = Policy {
+            daily_shutdown: chrono::NaiveTime::from_hms_opt(23, 0, 0),
+            cancel_pin_hash: Some(crate::utils::policy::hash_pin("1234")),
+            ..Default::default()
         };
+        let now = chrono::Local::now();
+        let forced = policy.forced_daily_task(now).unwrap();
+        let user_task = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(chrono::Duration::hours(1)), TaskAction::Sleep, now).unwrap();
         
-        self.task_persistence.save_task(&task_data)?;
-
-        // 启动倒计时
-        self.countdown_manager.start_countdown(target_time).await?;
-
-        info!("定时关机任务已设置: {:?}", target_time);
-        Ok(())
+        // 策略关机倒计时期间不能退出、修改任务或导入
+        for event in [
+            UIEvent::Exit,
+            UIEvent::RemoveTask(user_task.id.clone()),
+            UIEvent::SetTaskEnabled(POLICY_TASK_ID.to_string(), false),
+            UIEvent::UpdateTask(user_task.clone()),
+            UIEvent::RestoreTaskBackup,
+            UIEvent::ImportTasks(Vec::new()),
+        ] {
+            assert!(!App::is_event_allowed(&policy, &event, Some(&forced)), "{:?}", event);
+            assert!(App::is_event_allowed(&policy, &event, Some(&user_task)), "{:?}", event);
+        }
+        assert!(App::is_event_allowed(&policy, &UIEvent::SetTaskEnabled(user_task.id.clone(), true), Some(&forced)));
+        
+        // 比策略关机更早执行的倒计时会替换它，更晚的不会
+        let remaining = forced.target_time.unwrap() - now;
+        let earlier = UIEvent::QuickCountdown(remaining - chrono::Duration::minutes(1));
+        let later = UIEvent::QuickCountdown(remaining + chrono::Duration::minutes(1));
+        assert!(!App::is_event_allowed(&policy, &earlier, Some(&forced)));
+        assert!(App::is_event_allowed(&policy, &later, Some(&forced)));
+        assert!(App::is_event_allowed(&policy, &earlier, Some(&user_task)));
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_short_countdown_requires_confirmation() {
+        let countdown_manager = CountdownManager::new().await.unwrap();
+        let mut receiver = countdown_manager.get_update_receiver();
+        let mut pending = None;
+        
+        // 托盘、远程命令等开始的3秒倒计时先请求界面确认
+        let short = App::build_remote_task(TaskAction::Restart, chrono::Duration::seconds(3)).unwrap();
+        assert!(App::hold_short_countdown(&countdown_manager, &mut pending, short).is_none());
+        assert!(pending.is_some());
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(CountdownUpdate::WindowRequested(WindowRequest::ConfirmShortStart { action: TaskAction::Restart, .. }))
+        ));
+        
+        // 确认后从确认时重新计时
+        let task = pending.take().unwrap();
+        let later = task.created_at + chrono::Duration::seconds(10);
+        let confirmed = App::confirmed_short_countdown(task, later);
+        assert_eq!(confirmed.target_time, Some(later + chrono::Duration::seconds(3)));
+        
+        // 一分钟以上的倒计时直接开始
+        let normal = App::build_quick_task(chrono::Duration::minutes(5)).unwrap();
+        assert!(App::hold_short_countdown(&countdown_manager, &mut pending, normal).is_some());
+        assert!(pending.is_none());
+        assert!(receiver.try_recv().is_err());
+    }
+}
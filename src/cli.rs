@@ -0,0 +1,436 @@
+//! 命令行参数模块
+//! 
+//! 解析启动参数，支持一次调用创建多个定时任务，
+//! 例如：`qtshut --daily 22:30 --once "明天早上8点" --action sleep`；
+//! 也可以在启动界面的同时开始倒计时，例如：`qtshut --in 45m --action restart --minimized`
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use log::{info, warn};
+
+use crate::core::{
+    autostart::AGENT_ARG,
+    cron::CronSchedule,
+    scheduler::select_next,
+    storage::Storage,
+    time_parser::TimeParser,
+    types::{StatusSnapshot, TaskAction, TaskData, TaskSource, TaskType, TimeInput, UIEvent, WindowMode},
+};
+use crate::utils::config::ConfigManager;
+
+/// 命令行帮助文本
+pub const HELP_TEXT: &str = "用法: qtshut [选项]
+
+选项:
+  --once <时间>      添加单次任务，如 --once 30分钟 或 --once \"今晚22:00\"
+  --daily <时间>     添加每日任务，如 --daily 22:30
+  --cron <表达式>    添加Cron计划（秒 分 时 日 月 星期），如 --cron \"0 30 22 * * 1-5\"
+  --in <时长>        启动后立即开始倒计时，如 --in 45m
+  --at <时间>        启动后立即开始倒计时，到指定时间执行，如 --at 22:30
+  --action <操作>    到点后执行的操作: shutdown(关机)、restart(重启)、sleep(睡眠)、lock(锁定)、logoff(注销)、monitor-off(关闭显示器) 或 command(运行设置中的自定义命令)，默认关机
+  --minimized        启动时最小化到托盘
+  --agent            以后台代理方式启动：只显示托盘图标，需要时再打开主窗口，关闭主窗口后继续在后台运行
+  --safe-mode        以安全模式启动：不启动直播叠加层接口、远程关机服务和登录任务，不运行自定义命令，不下载远程模板
+  status, --status   显示下一个已保存任务的状态后退出
+  selftest, --selftest  运行自检（时间解析、任务保存、通知、托盘和关机演练，不会真正关机）后退出
+  replay <文件>      用全新的任务和倒计时重新处理录制的事件（配置中开启 advanced.record_events 后录制），逐条输出倒计时状态后退出，不会真正关机
+  --hash-pin <PIN>   生成管理策略 policy.json 中 cancel_pin_hash 使用的PIN哈希后退出
+  -h, --help         显示帮助信息
+
+--once、--daily 和 --cron 可以重复使用，一次创建多个任务；
+--in 和 --at 只能使用其中一个，适合放在快捷方式中一键开始倒计时；
+这些功能的配置导致无法正常启动时，可以使用 --safe-mode 启动后在设置中修改";
+
+/// 单个计划参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleArg {
+    /// 任务类型
+    pub task_type: TaskType,
+    /// 原始时间输入
+    pub input: String,
+}
+
+/// 启动后立即开始的倒计时
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchTimer {
+    /// 多长时间后执行，如"45m"
+    In(String),
+    /// 到指定时间执行，如"22:30"
+    At(String),
+}
+
+impl LaunchTimer {
+    /// 解析为时间输入
+    /// 
+    /// `--in`只接受时长，`--at`只接受时间点，避免把"22:30"误当作时长
+    /// 
+    /// # 参数
+    /// 
+    /// * `parser` - 时间解析器
+    pub fn time_input(&self, parser: &TimeParser) -> Result<TimeInput> {
+        let time_input = match self {
+            LaunchTimer::In(value) => match parser.parse(value)? {
+                time_input @ TimeInput::Duration(_) => time_input,
+                _ => return Err(anyhow!("--in 需要时长，如 45m: {}", value)),
+            },
+            LaunchTimer::At(value) => match parser.parse(value)? {
+                time_input @ TimeInput::AbsoluteTime(_) => time_input,
+                _ => return Err(anyhow!("--at 需要时间点，如 22:30: {}", value)),
+            },
+        };
+        parser.validate(&time_input)?;
+        Ok(time_input)
+    }
+}
+
+/// 命令行参数
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CliArgs {
+    /// 要创建的计划
+    pub schedules: Vec<ScheduleArg>,
+    /// 启动后立即开始的倒计时
+    pub launch_timer: Option<LaunchTimer>,
+    /// 是否启动时最小化到托盘
+    pub start_minimized: bool,
+    /// 是否以后台代理方式启动
+    pub background_agent: bool,
+    /// 是否以安全模式启动
+    pub safe_mode: bool,
+    /// 到点后执行的操作，作用于本次创建的所有任务
+    pub action: TaskAction,
+    /// 是否显示帮助
+    pub show_help: bool,
+    /// 是否显示任务状态
+    pub show_status: bool,
+    /// 是否运行自检
+    pub run_selftest: bool,
+    /// 要回放的事件录制文件
+    pub replay_file: Option<PathBuf>,
+    /// 要生成哈希的管理策略PIN
+    pub hash_pin: Option<String>,
+}
+
+impl CliArgs {
+    /// 解析命令行参数
+    /// 
+    /// 同时支持 `--daily 22:30` 和 `--daily=22:30` 两种写法
+    /// 
+    /// # 参数
+    /// 
+    /// * `args` - 不包含程序名的参数列表
+    pub fn parse<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut result = CliArgs::default();
+        let mut args = args.into_iter();
+        
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            
+            match name.as_str() {
+                "-h" | "--help" => result.show_help = true,
+                "status" | "--status" => result.show_status = true,
+                "selftest" | "--selftest" => result.run_selftest = true,
+                "--minimized" => result.start_minimized = true,
+                AGENT_ARG => result.background_agent = true,
+                "--safe-mode" => result.safe_mode = true,
+                "--once" | "--daily" | "--cron" | "--in" | "--at" | "--action" | "replay" | "--replay" | "--hash-pin" => {
+                    let value = match inline_value.or_else(|| args.next()) {
+                        Some(value) if !value.trim().is_empty() => value,
+                        _ => return Err(anyhow!("参数 {} 缺少取值", name)),
+                    };
+                    
+                    match name.as_str() {
+                        "--once" => result.schedules.push(ScheduleArg { task_type: TaskType::Once, input: value }),
+                        "--daily" => result.schedules.push(ScheduleArg { task_type: TaskType::Daily, input: value }),
+                        "--cron" => result.schedules.push(ScheduleArg { task_type: TaskType::Cron, input: value }),
+                        "--in" | "--at" => {
+                            if result.launch_timer.is_some() {
+                                return Err(anyhow!("--in 和 --at 只能使用一个"));
+                            }
+                            result.launch_timer = Some(if name == "--in" { LaunchTimer::In(value) } else { LaunchTimer::At(value) });
+                        },
+                        "replay" | "--replay" => result.replay_file = Some(PathBuf::from(value)),
+                        "--hash-pin" => result.hash_pin = Some(value),
+                        _ => {
+                            result.action = TaskAction::from_name(&value)
+                                .ok_or_else(|| anyhow!("无法识别的操作: {}", value))?;
+                        },
+                    }
+                },
+                _ => return Err(anyhow!("无法识别的参数: {}", arg)),
+            }
+        }
+        
+        Ok(result)
+    }
+    
+    /// 是否需要创建任务
+    pub fn has_tasks(&self) -> bool {
+        !self.schedules.is_empty()
+    }
+    
+    /// 启动时主窗口的显示方式
+    pub fn window_mode(&self) -> WindowMode {
+        if self.background_agent {
+            WindowMode::TrayOnly
+        } else if self.start_minimized {
+            WindowMode::Minimized
+        } else {
+            WindowMode::Normal
+        }
+    }
+    
+    /// 生成启动后立即开始倒计时的界面事件
+    /// 
+    /// 与主窗口中输入时间后点击开始相同，由界面事件循环创建任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `parser` - 时间解析器
+    /// 
+    /// # 返回值
+    /// 
+    /// 未指定`--in`或`--at`时返回None，时间无法解析时返回错误
+    pub fn launch_event(&self, parser: &TimeParser) -> Result<Option<UIEvent>> {
+        let Some(timer) = &self.launch_timer else {
+            return Ok(None);
+        };
+        let time_input = timer.time_input(parser)?;
+        Ok(Some(UIEvent::StartCountdown(time_input, TaskType::Once, self.action)))
+    }
+}
+
+/// 根据计划参数构建任务数据
+/// 
+/// # 参数
+/// 
+/// * `parser` - 时间解析器
+/// * `schedule` - 计划参数
+/// * `action` - 到点后执行的操作
+pub fn build_task(parser: &TimeParser, schedule: &ScheduleArg, action: TaskAction) -> Result<TaskData> {
+    let time_input = match schedule.task_type {
+        TaskType::Cron => TimeInput::Cron(CronSchedule::parse(&schedule.input)?),
+        _ => parser.parse(&schedule.input)?,
+    };
+    if schedule.task_type == TaskType::Once {
+        parser.validate(&time_input)?;
+    }
+    
+    let mut task = TaskData::from_time_input(schedule.task_type, time_input, action, Local::now())?;
+    task.source = TaskSource::CommandLine;
+    if action == TaskAction::RunCommand {
+        task.custom_command = ConfigManager::load_custom_command();
+    }
+    Ok(task)
+}
+
+/// 创建命令行指定的所有任务并保存
+/// 
+/// 单个任务解析失败不影响其他任务
+/// 
+/// # 参数
+/// 
+/// * `args` - 命令行参数
+/// * `storage` - 保存任务的存储
+/// 
+/// # 返回值
+/// 
+/// 返回可直接显示给用户的结果摘要
+pub fn create_tasks(args: &CliArgs, storage: &dyn Storage) -> Result<String> {
+    let parser = TimeParser::global();
+    let mut tasks = Vec::new();
+    let mut lines = Vec::new();
+    
+    for schedule in &args.schedules {
+        match build_task(parser, schedule, args.action) {
+            Ok(task) => {
+                let when = task.target_time
+                    .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                lines.push(format!("  ✓ {} \"{}\" -> {}（{}）", schedule.task_type, schedule.input, when, task.action));
+                tasks.push(task);
+            },
+            Err(e) => {
+                warn!("命令行任务创建失败: {} - {}", schedule.input, e);
+                lines.push(format!("  ✗ {} \"{}\": {}", schedule.task_type, schedule.input, e));
+            },
+        }
+    }
+    
+    let failed = args.schedules.len() - tasks.len();
+    if !tasks.is_empty() {
+        storage.add_tasks(&tasks)?;
+    }
+    info!("命令行创建任务完成，成功{}个，失败{}个", tasks.len(), failed);
+    
+    let mut summary = format!("已创建 {} 个任务，失败 {} 个", tasks.len(), failed);
+    for line in lines {
+        summary.push('\n');
+        summary.push_str(&line);
+    }
+    Ok(summary)
+}
+
+/// 格式化状态快照
+/// 
+/// 快照来自正在运行的实例；没有实例在运行时来自已保存任务的计划状态
+/// 
+/// # 参数
+/// 
+/// * `snapshot` - 状态快照
+pub fn format_status(snapshot: &StatusSnapshot) -> String {
+    let mut lines = vec![snapshot.summary()];
+    if let Some(deadline) = snapshot.deadline {
+        lines.push(format!("到点时间: {}", deadline.format("%Y-%m-%d %H:%M:%S")));
+    }
+    if let Some(task_type) = snapshot.task_type {
+        lines.push(format!("任务类型: {}", task_type));
+    }
+    if let Some(source) = snapshot.source {
+        lines.push(format!("任务来源: {}", source));
+    }
+    lines.join("\n")
+}
+
+/// 查询下一个已保存任务的状态
+/// 
+/// # 参数
+/// 
+/// * `storage` - 保存任务的存储
+pub fn saved_task_status(storage: &dyn Storage) -> Result<StatusSnapshot> {
+    let now = Local::now();
+    let task = select_next(&storage.load_tasks()?, now);
+    Ok(StatusSnapshot::from_saved_task(task.as_ref(), now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use crate::core::persistence::TaskPersistence;
+    use tempfile::TempDir;
+    
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+    
+    #[test]
+    fn test_parse_multiple_schedules() {
+        let parsed = CliArgs::parse(args(&["--daily", "22:30", "--once=30分钟", "--action", "sleep"])).unwrap();
+        
+        assert_eq!(parsed.schedules.len(), 2);
+        assert_eq!(parsed.schedules[0], ScheduleArg { task_type: TaskType::Daily, input: "22:30".to_string() });
+        assert_eq!(parsed.schedules[1], ScheduleArg { task_type: TaskType::Once, input: "30分钟".to_string() });
+        assert_eq!(parsed.action, TaskAction::Sleep);
+        assert!(parsed.has_tasks());
+        
+        assert!(CliArgs::parse(args(&[])).unwrap().schedules.is_empty());
+        assert!(CliArgs::parse(args(&["--help"])).unwrap().show_help);
+        assert!(CliArgs::parse(args(&["status"])).unwrap().show_status);
+        assert!(CliArgs::parse(args(&["selftest"])).unwrap().run_selftest);
+        assert!(CliArgs::parse(args(&["--safe-mode"])).unwrap().safe_mode);
+        assert_eq!(CliArgs::parse(args(&["replay", "events.jsonl"])).unwrap().replay_file, Some(PathBuf::from("events.jsonl")));
+        assert_eq!(CliArgs::parse(args(&["--hash-pin", "1234"])).unwrap().hash_pin, Some("1234".to_string()));
+        assert!(CliArgs::parse(args(&["replay"])).is_err());
+    }
+    
+    #[test]
+    fn test_parse_errors() {
+        assert!(CliArgs::parse(args(&["--once"])).is_err());
+        assert!(CliArgs::parse(args(&["--action", "explode"])).is_err());
+        assert_eq!(CliArgs::parse(args(&["--action", "reboot"])).unwrap().action, TaskAction::Restart);
+        assert_eq!(CliArgs::parse(args(&["--action=lock"])).unwrap().action, TaskAction::Lock);
+        assert_eq!(CliArgs::parse(args(&["--action", "注销"])).unwrap().action, TaskAction::Logoff);
+        assert_eq!(CliArgs::parse(args(&["--action", "monitor-off"])).unwrap().action, TaskAction::MonitorOff);
+        assert!(CliArgs::parse(args(&["--unknown"])).is_err());
+        assert!(CliArgs::parse(args(&["--in", "45m", "--at", "22:30"])).is_err());
+    }
+    
+    #[test]
+    fn test_launch_timer() {
+        let parser = TimeParser::new();
+        
+        let parsed = CliArgs::parse(args(&["--in", "45m", "--action", "restart", "--minimized"])).unwrap();
+        assert_eq!(parsed.launch_timer, Some(LaunchTimer::In("45m".to_string())));
+        assert!(parsed.start_minimized);
+        assert_eq!(parsed.window_mode(), WindowMode::Minimized);
+        assert_eq!(CliArgs::parse(args(&["--minimized", "--agent"])).unwrap().window_mode(), WindowMode::TrayOnly);
+        assert!(!parsed.has_tasks());
+        match parsed.launch_event(&parser).unwrap() {
+            Some(UIEvent::StartCountdown(TimeInput::Duration(duration), TaskType::Once, TaskAction::Restart)) => {
+                assert_eq!(duration, chrono::Duration::minutes(45));
+            },
+            other => panic!("unexpected launch event: {:?}", other),
+        }
+        
+        let parsed = CliArgs::parse(args(&["--at=22:30"])).unwrap();
+        assert!(matches!(
+            parsed.launch_event(&parser).unwrap(),
+            Some(UIEvent::StartCountdown(TimeInput::AbsoluteTime(_), TaskType::Once, TaskAction::Shutdown))
+        ));
+        
+        // --in 不接受时间点，--at 不接受时长
+        assert!(LaunchTimer::In("22:30".to_string()).time_input(&parser).is_err());
+        assert!(LaunchTimer::At("45m".to_string()).time_input(&parser).is_err());
+        assert!(CliArgs::default().launch_event(&parser).unwrap().is_none());
+    }
+    
+    #[test]
+    fn test_build_task() {
+        let parser = TimeParser::new();
+        
+        let daily = ScheduleArg { task_type: TaskType::Daily, input: "22:30".to_string() };
+        let task = build_task(&parser, &daily, TaskAction::Sleep).unwrap();
+        assert_eq!(task.daily_time, NaiveTime::from_hms_opt(22, 30, 0));
+        assert!(task.target_time.unwrap() > Local::now());
+        assert_eq!(task.action, TaskAction::Sleep);
+        
+        let once = ScheduleArg { task_type: TaskType::Once, input: "30分钟".to_string() };
+        let task = build_task(&parser, &once, TaskAction::Shutdown).unwrap();
+        assert!(task.daily_time.is_none());
+        assert!(task.target_time.unwrap() > Local::now());
+        
+        // 每日任务不接受相对时间
+        let invalid = ScheduleArg { task_type: TaskType::Daily, input: "30分钟".to_string() };
+        assert!(build_task(&parser, &invalid, TaskAction::Shutdown).is_err());
+        
+        let cron = ScheduleArg { task_type: TaskType::Cron, input: "0 30 22 * * 1-5".to_string() };
+        let task = build_task(&parser, &cron, TaskAction::Shutdown).unwrap();
+        assert_eq!(task.task_type, TaskType::Cron);
+        assert!(task.target_time.unwrap() > Local::now());
+        let invalid = ScheduleArg { task_type: TaskType::Cron, input: "0 30 25 * * *".to_string() };
+        assert!(build_task(&parser, &invalid, TaskAction::Shutdown).is_err());
+    }
+    
+    #[test]
+    fn test_create_tasks_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        let parsed = CliArgs::parse(args(&["--daily", "22:30", "--once", "无效时间", "--once", "1小时"])).unwrap();
+        
+        let summary = create_tasks(&parsed, &persistence).unwrap();
+        assert!(summary.starts_with("已创建 2 个任务，失败 1 个"));
+        assert_eq!(persistence.load_tasks().unwrap().len(), 2);
+    }
+    
+    #[test]
+    fn test_saved_task_status() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        assert!(format_status(&saved_task_status(&persistence).unwrap()).starts_with("空闲"));
+        
+        let parsed = CliArgs::parse(args(&["--once", "1小时", "--action", "restart"])).unwrap();
+        create_tasks(&parsed, &persistence).unwrap();
+        
+        let text = format_status(&saved_task_status(&persistence).unwrap());
+        assert!(text.contains("到点后重启"));
+        assert!(text.contains("任务来源: 命令行"));
+    }
+}
@@ -0,0 +1,209 @@
+//! 开机自动启动模块
+//! 
+//! 开启后在当前用户注册表的Run项中写入`qtshut.exe --agent`，登录后以后台代理方式启动：
+//! 只显示托盘图标，需要时再打开主窗口。通过注册表API读取、reg.exe写入，不需要管理员权限。
+//! 用户在任务管理器的"启动"页中禁用后Run项仍然保留，禁用状态记录在StartupApproved项中
+
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+
+use crate::platform::{self, RegistryValue, SystemInfo};
+
+/// 以后台代理方式启动的命令行参数
+pub const AGENT_ARG: &str = "--agent";
+
+/// 当前用户登录时自动运行的程序所在的注册表项（HKEY_CURRENT_USER下）
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+/// 任务管理器记录启动项是否被禁用的注册表项（HKEY_CURRENT_USER下）
+const APPROVED_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved\\Run";
+
+/// Run项中QtShut使用的值名称
+const VALUE_NAME: &str = "QtShut";
+
+/// 开机自动启动的实际登记状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutostartState {
+    /// 没有登记
+    NotRegistered,
+    /// 已登记，登录后在后台运行
+    Registered,
+    /// 已登记，但在任务管理器的"启动"页中被禁用
+    DisabledByUser,
+    /// 登记的程序路径与当前程序不一致，程序可能被移动过
+    Outdated,
+}
+
+impl fmt::Display for AutostartState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutostartState::NotRegistered => write!(f, "未登记，登录后不会自动启动"),
+            AutostartState::Registered => write!(f, "已登记，登录后在后台运行"),
+            AutostartState::DisabledByUser => write!(f, "已在任务管理器的\"启动\"页中禁用，登录后不会自动启动"),
+            AutostartState::Outdated => write!(f, "登记的程序位置已失效，请重新开启"),
+        }
+    }
+}
+
+/// 登录时运行的命令
+/// 
+/// # 参数
+/// 
+/// * `exe` - 程序路径，包含空格时也能正确启动
+pub fn autostart_command(exe: &Path) -> String {
+    format!("\"{}\" {}", exe.display(), AGENT_ARG)
+}
+
+/// reg.exe使用的完整注册表项路径
+fn reg_key(key: &str) -> String {
+    format!("HKCU\\{}", key)
+}
+
+/// 写入Run项的reg.exe参数
+fn add_args(command: &str) -> Vec<String> {
+    ["add", reg_key(RUN_KEY).as_str(), "/v", VALUE_NAME, "/t", "REG_SZ", "/d", command, "/f"].iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// 从StartupApproved项的二进制值判断是否在任务管理器中被禁用
+/// 
+/// 值的第一个字节为奇数（通常是03）表示禁用，02表示启用
+fn parse_disabled(value: &[u8]) -> bool {
+    value.first().map_or(false, |flag| flag % 2 == 1)
+}
+
+/// 运行reg.exe
+/// 
+/// # 返回值
+/// 
+/// 退出码非0时返回包含标准错误的错误信息
+fn reg(args: &[String]) -> Result<()> {
+    debug!("执行reg: {}", args.join(" "));
+    let output = Command::new("reg.exe")
+        .args(args)
+        .output()
+        .map_err(|e| anyhow!("执行reg失败: {}", e))?;
+    
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("reg执行失败: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// 查询注册表项中QtShut的值，值不存在时返回None
+/// 
+/// 直接通过注册表API读取，不解析reg.exe按系统代码页输出的文本
+fn query(key: &str) -> Option<RegistryValue> {
+    platform::native().user_registry_value(key, VALUE_NAME)
+}
+
+/// 删除注册表项中QtShut的值
+fn delete(key: &str) -> Result<()> {
+    let args = ["delete", reg_key(key).as_str(), "/v", VALUE_NAME, "/f"].iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    reg(&args)
+}
+
+/// 读取Run项中已登记的QtShut命令，没有登记时返回None
+fn registered_command() -> Option<String> {
+    match query(RUN_KEY) {
+        Some(RegistryValue::String(command)) if !command.is_empty() => Some(command),
+        _ => None,
+    }
+}
+
+/// 当前程序登录时运行的命令
+fn current_command() -> Result<String> {
+    let exe = std::env::current_exe().map_err(|e| anyhow!("获取程序路径失败: {}", e))?;
+    Ok(autostart_command(&exe))
+}
+
+/// 读取开机自动启动的实际登记状态
+pub fn query_state() -> Result<AutostartState> {
+    let registered = match registered_command() {
+        Some(registered) => registered,
+        None => return Ok(AutostartState::NotRegistered),
+    };
+    if registered != current_command()? {
+        return Ok(AutostartState::Outdated);
+    }
+    if matches!(query(APPROVED_KEY), Some(RegistryValue::Binary(value)) if parse_disabled(&value)) {
+        return Ok(AutostartState::DisabledByUser);
+    }
+    Ok(AutostartState::Registered)
+}
+
+/// 按设置登记或取消开机自动启动
+/// 
+/// 用户在设置中开启时同时清除任务管理器中的禁用标记
+/// 
+/// # 参数
+/// 
+/// * `enabled` - 是否开机自动启动
+/// 
+/// # 返回值
+/// 
+/// 修改后的实际登记状态
+pub fn set_autostart(enabled: bool) -> Result<AutostartState> {
+    if enabled && query(APPROVED_KEY).is_some() {
+        delete(APPROVED_KEY)?;
+    }
+    verify(enabled)
+}
+
+/// 启动时检查登记状态与设置是否一致
+/// 
+/// 没有登记或程序移动位置后重新登记，关闭时删除登记；
+/// 在任务管理器中被禁用时尊重用户的选择，只返回该状态
+/// 
+/// # 参数
+/// 
+/// * `enabled` - 设置中是否开机自动启动
+/// 
+/// # 返回值
+/// 
+/// 检查后的实际登记状态
+pub fn verify(enabled: bool) -> Result<AutostartState> {
+    let registered = registered_command();
+    if !enabled {
+        if registered.is_some() {
+            delete(RUN_KEY)?;
+            info!("已取消开机自动启动");
+        }
+        return Ok(AutostartState::NotRegistered);
+    }
+    
+    let command = current_command()?;
+    if registered.as_deref() != Some(command.as_str()) {
+        reg(&add_args(&command))?;
+        info!("已登记开机自动启动: {}", command);
+    }
+    query_state()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    
+    #[test]
+    fn test_autostart_command() {
+        let command = autostart_command(&PathBuf::from("C:\\Program Files\\QtShut\\qtshut.exe"));
+        assert_eq!(command, "\"C:\\Program Files\\QtShut\\qtshut.exe\" --agent");
+        let args = add_args(&command);
+        assert_eq!(args[1], "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+        assert_eq!(args[7], command);
+    }
+    
+    #[test]
+    fn test_parse_disabled() {
+        assert!(parse_disabled(&[0x03, 0x00, 0x00, 0x00, 0x0A, 0x8C, 0x4D, 0x3F, 0x5E, 0x27, 0xDA, 0x01]));
+        assert!(!parse_disabled(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+        assert!(!parse_disabled(&[]));
+    }
+}
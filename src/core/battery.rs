@@ -0,0 +1,91 @@
+//! 电池供电处理模块
+//! 
+//! 笔记本电脑到点时如果正在使用电池供电，可以按设置把关机改为休眠（保留未保存的工作），
+//! 或在电量过低时取消本次执行，由执行器在执行操作前调整
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::TaskAction;
+use crate::platform::PowerStatus;
+
+/// 默认电量低于该百分比时取消执行
+pub const DEFAULT_CANCEL_BELOW_PERCENT: u8 = 20;
+
+/// 电池供电时的处理设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatterySettings {
+    /// 使用电池供电时用休眠代替关机
+    pub hibernate_instead_of_shutdown: bool,
+    /// 使用电池供电且电量低于该百分比时取消执行，为空表示不检查
+    pub cancel_below_percent: Option<u8>,
+}
+
+/// 按电源状态调整后的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionOverride {
+    /// 按原操作执行
+    Keep,
+    /// 改为休眠
+    Hibernate,
+    /// 取消本次执行
+    Cancel(String),
+}
+
+impl BatterySettings {
+    /// 是否设置了任何电池相关的处理
+    pub fn is_enabled(&self) -> bool {
+        self.hibernate_instead_of_shutdown || self.cancel_below_percent.is_some()
+    }
+    
+    /// 按电源状态决定如何执行操作
+    /// 
+    /// 电量过低的取消优先于改为休眠；没有电池、接通电源或无法获取电源状态时按原操作执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    /// * `status` - 当前电源状态
+    pub fn resolve(&self, action: TaskAction, status: Option<&PowerStatus>) -> ActionOverride {
+        let Some(status) = status.filter(|status| status.on_battery) else {
+            return ActionOverride::Keep;
+        };
+        
+        if let (Some(threshold), Some(percent)) = (self.cancel_below_percent, status.battery_percent) {
+            if percent < threshold {
+                return ActionOverride::Cancel(format!("使用电池供电，电量{}%低于{}%", percent, threshold));
+            }
+        }
+        if self.hibernate_instead_of_shutdown && action == TaskAction::Shutdown {
+            return ActionOverride::Hibernate;
+        }
+        ActionOverride::Keep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_resolve() {
+        let settings = BatterySettings {
+            hibernate_instead_of_shutdown: true,
+            cancel_below_percent: Some(DEFAULT_CANCEL_BELOW_PERCENT),
+        };
+        let on_battery = |percent| PowerStatus { on_battery: true, battery_percent: Some(percent) };
+        
+        assert_eq!(settings.resolve(TaskAction::Shutdown, Some(&on_battery(60))), ActionOverride::Hibernate);
+        // 只把关机改为休眠，其他操作不变
+        assert_eq!(settings.resolve(TaskAction::Restart, Some(&on_battery(60))), ActionOverride::Keep);
+        assert!(matches!(settings.resolve(TaskAction::Shutdown, Some(&on_battery(18))), ActionOverride::Cancel(reason) if reason.contains("18%")));
+        
+        // 接通电源或没有电池时按原操作执行
+        let plugged_in = PowerStatus { on_battery: false, battery_percent: Some(5) };
+        assert_eq!(settings.resolve(TaskAction::Shutdown, Some(&plugged_in)), ActionOverride::Keep);
+        assert_eq!(settings.resolve(TaskAction::Shutdown, None), ActionOverride::Keep);
+        
+        assert!(!BatterySettings::default().is_enabled());
+        assert_eq!(BatterySettings::default().resolve(TaskAction::Shutdown, Some(&on_battery(5))), ActionOverride::Keep);
+    }
+}
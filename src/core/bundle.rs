@@ -0,0 +1,158 @@
+//! 设置包模块
+//! 
+//! "导出设置"把任务、配置（包括主题、字体等界面设置）和配置方案保存为一个JSON文件，
+//! 在新电脑上"导入设置"即可恢复。任务和配置按各自的格式版本号保存，
+//! 旧版本导出的设置包导入时与数据文件一样先迁移到当前格式
+//! 
+//! 导入时替换当前的所有任务，替换前的任务仍可通过"恢复上一个版本"找回；
+//! 窗口位置和所在显示器只对原来的电脑有效，导入时不恢复
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::core::migration::{self, SchemaKind};
+use crate::core::persistence::write_atomic;
+use crate::core::types::{ProfileData, TaskData};
+use crate::utils::config::{AppConfig, ConfigValidator};
+
+/// 导出的设置包文件名前缀，后面加上导出时间
+pub const BUNDLE_FILE_PREFIX: &str = "qtshut-settings-";
+
+/// 设置包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    /// 导出时间
+    pub exported_at: DateTime<Local>,
+    /// 导出设置的电脑名称
+    pub computer: String,
+    /// 任务，与任务文件的格式相同
+    tasks: Value,
+    /// 配置，与配置文件的格式相同
+    config: Value,
+    /// 配置方案
+    pub profiles: Vec<ProfileData>,
+}
+
+impl SettingsBundle {
+    /// 生成设置包
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 当前的所有任务
+    /// * `config` - 当前配置
+    /// * `profiles` - 所有配置方案
+    /// * `computer` - 本机名称
+    /// * `now` - 当前时间
+    pub fn new(tasks: &[TaskData], config: &AppConfig, profiles: Vec<ProfileData>, computer: &str, now: DateTime<Local>) -> Result<Self> {
+        Ok(Self {
+            exported_at: now,
+            computer: computer.to_string(),
+            tasks: migration::stamp(SchemaKind::Tasks, json!({ "tasks": tasks }))?,
+            config: migration::stamp(SchemaKind::Config, serde_json::to_value(config)?)?,
+            profiles,
+        })
+    }
+    
+    /// 取出任务，旧格式先迁移到当前格式
+    pub fn tasks(&self) -> Result<Vec<TaskData>> {
+        let value = migration::migrate(SchemaKind::Tasks, self.tasks.clone())?;
+        serde_json::from_value(value["tasks"].clone()).map_err(|e| anyhow!("设置包中的任务格式错误: {}", e))
+    }
+    
+    /// 取出配置，旧格式先迁移到当前格式
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回去掉窗口位置后的配置，配置无效时返回错误
+    pub fn config(&self) -> Result<AppConfig> {
+        let value = migration::migrate(SchemaKind::Config, self.config.clone())?;
+        let mut config: AppConfig = serde_json::from_value(value).map_err(|e| anyhow!("设置包中的配置格式错误: {}", e))?;
+        let (valid, errors) = ConfigValidator::validate_config(&config);
+        if !valid {
+            return Err(anyhow!("设置包中的配置无效: {}", errors.join("；")));
+        }
+        config.ui.window_position = None;
+        config.ui.window_monitor = None;
+        Ok(config)
+    }
+    
+    /// 写入目录中，文件名带有导出时间
+    /// 
+    /// # 参数
+    /// 
+    /// * `dir` - 保存的目录
+    /// 
+    /// # 返回值
+    /// 
+    /// 设置包文件路径
+    pub fn write_to(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(format!("{}{}.json", BUNDLE_FILE_PREFIX, self.exported_at.format("%Y%m%d-%H%M%S")));
+        let json_data = serde_json::to_string_pretty(self)?;
+        write_atomic(&path, json_data.as_bytes()).map_err(|e| anyhow!("写入设置包失败: {}", e))?;
+        info!("设置已导出到: {:?}", path);
+        Ok(path)
+    }
+    
+    /// 读取设置包文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `path` - 设置包文件路径
+    pub fn read_from(path: &Path) -> Result<Self> {
+        let json_data = fs::read_to_string(path).map_err(|e| anyhow!("读取设置包失败: {}", e))?;
+        let bundle: Self = serde_json::from_str(&json_data).map_err(|e| anyhow!("不是QtShut设置包: {}", e))?;
+        migration::check_supported(SchemaKind::Tasks, &bundle.tasks)?;
+        migration::check_supported(SchemaKind::Config, &bundle.config)?;
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{TaskAction, TaskType, TimeInput};
+    use crate::ui::theme::ThemeType;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_bundle_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = Local::now();
+        let task = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(chrono::Duration::hours(2)), TaskAction::Restart, now).unwrap();
+        let mut config = AppConfig::default();
+        config.ui.theme_type = ThemeType::Dark;
+        config.ui.window_position = Some((100.0, 200.0));
+        
+        let bundle = SettingsBundle::new(std::slice::from_ref(&task), &config, vec![ProfileData::new("家庭")], "书房", now).unwrap();
+        let path = bundle.write_to(temp_dir.path()).unwrap();
+        assert!(path.file_name().unwrap().to_string_lossy().starts_with(BUNDLE_FILE_PREFIX));
+        
+        let loaded = SettingsBundle::read_from(&path).unwrap();
+        assert_eq!(loaded.computer, "书房");
+        assert_eq!(loaded.tasks().unwrap()[0].id, task.id);
+        assert_eq!(loaded.profiles[0].name, "家庭");
+        let imported = loaded.config().unwrap();
+        assert_eq!(imported.ui.theme_type, ThemeType::Dark);
+        assert_eq!(imported.ui.window_position, None);
+    }
+    
+    #[test]
+    fn test_reject_invalid_bundle() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tasks.json");
+        fs::write(&path, r#"{ "tasks": [] }"#).unwrap();
+        assert!(SettingsBundle::read_from(&path).unwrap_err().to_string().contains("不是QtShut设置包"));
+        
+        // 更新版本导出的设置包不能导入
+        let mut bundle = SettingsBundle::new(&[], &AppConfig::default(), Vec::new(), "书房", Local::now()).unwrap();
+        bundle.config["schema_version"] = json!(SchemaKind::Config.current_version() + 1);
+        let path = bundle.write_to(temp_dir.path()).unwrap();
+        assert!(SettingsBundle::read_from(&path).unwrap_err().to_string().contains("更新版本"));
+    }
+}
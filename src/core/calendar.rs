@@ -0,0 +1,235 @@
+//! 日历导出模块
+//! 
+//! 将已启用的任务导出为iCalendar（.ics）格式，导入或订阅到家庭日历后可以看到电脑每天几点关机。
+//! 每日和每周计划导出为重复事件，Cron计划展开为最近一段时间内的各次执行
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+use crate::core::schedule;
+use crate::core::types::{TaskAction, TaskData, TaskType, WeeklySlot};
+
+/// 导出的日历文件名
+pub const CALENDAR_FILE_NAME: &str = "qtshut.ics";
+
+/// 日历中每个事件的时长（分钟），只用于在日历中显示
+const EVENT_MINUTES: i64 = 15;
+
+/// Cron计划展开的天数
+const CRON_EXPAND_DAYS: i64 = 30;
+
+/// Cron计划最多展开的次数
+const CRON_EXPAND_LIMIT: usize = 100;
+
+/// 日历中的一个事件
+#[derive(Debug, Clone, PartialEq)]
+struct CalendarEvent {
+    /// 事件标识，同一任务重新导出时保持不变
+    uid: String,
+    /// 第一次执行的时间
+    start: NaiveDateTime,
+    /// 到点后执行的操作
+    action: TaskAction,
+    /// 重复规则，如"FREQ=DAILY"
+    rule: Option<String>,
+}
+
+/// 将任务导出为iCalendar文本
+/// 
+/// 停用的任务、登录任务和已过期的单次任务不导出；时间使用不带时区的本地时间
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// * `now` - 当前时间
+pub fn export_calendar(tasks: &[TaskData], now: DateTime<Local>) -> String {
+    let stamp = now.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//QtShut//QtShut//ZH".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:QtShut".to_string(),
+    ];
+    for event in tasks.iter().filter(|task| task.enabled).flat_map(|task| task_events(task, now)) {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@qtshut", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", event.start.format("%Y%m%dT%H%M%S")));
+        lines.push(format!("DURATION:PT{}M", EVENT_MINUTES));
+        if let Some(rule) = &event.rule {
+            lines.push(format!("RRULE:{}", rule));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&format!("QtShut: {}", event.action))));
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    
+    lines.iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n") + "\r\n"
+}
+
+/// 将任务导出为日历文件
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// * `dir` - 保存的目录，已有同名文件时覆盖
+/// * `now` - 当前时间
+/// 
+/// # 返回值
+/// 
+/// 日历文件路径
+pub fn write_calendar(tasks: &[TaskData], dir: &Path, now: DateTime<Local>) -> std::io::Result<PathBuf> {
+    let path = dir.join(CALENDAR_FILE_NAME);
+    std::fs::write(&path, export_calendar(tasks, now))?;
+    Ok(path)
+}
+
+/// 任务对应的日历事件
+fn task_events(task: &TaskData, now: DateTime<Local>) -> Vec<CalendarEvent> {
+    match task.task_type {
+        TaskType::Once => schedule::next_fire(task, now)
+            .map(|(time, action)| CalendarEvent {
+                uid: task.id.clone(),
+                start: time.naive_local(),
+                action,
+                rule: None,
+            })
+            .into_iter()
+            .collect(),
+        TaskType::Daily => schedule::next_fire(task, now)
+            .map(|(time, action)| CalendarEvent {
+                uid: task.id.clone(),
+                start: time.naive_local(),
+                action,
+                rule: Some("FREQ=DAILY".to_string()),
+            })
+            .into_iter()
+            .collect(),
+        TaskType::Weekly => task.weekly_groups().into_iter()
+            .enumerate()
+            .filter_map(|(index, (time, action, weekdays))| {
+                let start = first_weekly_start(&weekdays, time, action, now)?;
+                let days: Vec<&str> = weekdays.iter().map(|&weekday| weekday_code(weekday)).collect();
+                Some(CalendarEvent {
+                    uid: format!("{}-{}", task.id, index),
+                    start,
+                    action,
+                    rule: Some(format!("FREQ=WEEKLY;BYDAY={}", days.join(","))),
+                })
+            })
+            .collect(),
+        TaskType::Cron => {
+            let until = now + Duration::days(CRON_EXPAND_DAYS);
+            let mut events = Vec::new();
+            let mut after = now;
+            while let Some((time, action)) = schedule::next_fire(task, after).filter(|(time, _)| *time <= until) {
+                events.push(CalendarEvent {
+                    uid: format!("{}-{}", task.id, time.format("%Y%m%dT%H%M%S")),
+                    start: time.naive_local(),
+                    action,
+                    rule: None,
+                });
+                if events.len() >= CRON_EXPAND_LIMIT {
+                    break;
+                }
+                after = time;
+            }
+            events
+        },
+        TaskType::Logon => Vec::new(),
+    }
+}
+
+/// 每周重复事件第一次执行的时间，与重复规则中的星期一致
+/// 
+/// 夏令时切换当天顺延的执行时间仍按计划的时间导出，使重复规则的时间不变
+fn first_weekly_start(weekdays: &[Weekday], time: NaiveTime, action: TaskAction, now: DateTime<Local>) -> Option<NaiveDateTime> {
+    schedule::next_weekly(&WeeklySlot::for_days(weekdays, time, action), now)
+        .map(|(start, _)| start.date_naive().and_time(time))
+}
+
+/// iCalendar中的星期代码
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// 转义文本中的特殊字符
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// 按规范把超过75字节的行折叠，续行以空格开头
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for ch in line.chars() {
+        if length + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(ch);
+        length += ch.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    use crate::core::types::TimeInput;
+    
+    #[test]
+    fn test_export_calendar() {
+        // 2024-05-06是星期一
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 8, 0, 0).unwrap();
+        let time = NaiveTime::from_hms_opt(22, 30, 0).unwrap();
+        let daily = TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(time), TaskAction::Shutdown, now).unwrap();
+        let weekly = TaskData::from_time_input(
+            TaskType::Weekly,
+            TimeInput::Weekly { days: vec![Weekday::Sat, Weekday::Sun], time },
+            TaskAction::Sleep,
+            now,
+        ).unwrap();
+        let mut disabled = daily.clone();
+        disabled.enabled = false;
+        
+        let calendar = export_calendar(&[daily.clone(), weekly, disabled], now);
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains(&format!("UID:{}@qtshut\r\n", daily.id)));
+        assert!(calendar.contains("DTSTART:20240506T223000\r\nDURATION:PT15M\r\nRRULE:FREQ=DAILY\r\n"));
+        // 每周计划从第一个符合的星期开始
+        assert!(calendar.contains("DTSTART:20240511T223000\r\nDURATION:PT15M\r\nRRULE:FREQ=WEEKLY;BYDAY=SA,SU\r\n"));
+        assert!(calendar.contains("SUMMARY:QtShut: 睡眠\r\n"));
+    }
+    
+    #[test]
+    fn test_fold_line() {
+        assert_eq!(fold_line("SUMMARY:短"), "SUMMARY:短");
+        let long = format!("SUMMARY:{}", "关".repeat(30));
+        let folded = fold_line(&long);
+        assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), long);
+        assert_eq!(escape_text("a,b;c"), "a\\,b\\;c");
+    }
+}
@@ -0,0 +1,215 @@
+//! 执行条件模块
+//! 
+//! 倒计时结束后、执行操作前检查设置的条件，
+//! 条件不满足时由调用方按该条件的重新检查间隔推迟执行，之后再次检查
+
+use std::time::Duration;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::platform;
+use crate::utils::{monitor, system};
+
+/// 条件不满足时默认推迟的分钟数
+pub const DEFAULT_POSTPONE_MINUTES: u32 = 10;
+
+/// CPU占用过高时重新检查的间隔（分钟）
+pub const CPU_RECHECK_MINUTES: i64 = 1;
+
+/// 执行条件设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConditionSettings {
+    /// 电脑至少空闲多少分钟才执行，为空表示不检查
+    pub idle_minutes: Option<u32>,
+    /// CPU占用高于该百分比时推迟执行，为空表示不检查
+    pub cpu_above_percent: Option<u32>,
+    /// 其中任一进程正在运行时推迟执行（如"obs64.exe"），为空表示不检查
+    pub blocking_processes: Vec<String>,
+    /// 空闲或进程条件不满足时推迟的分钟数
+    pub postpone_minutes: u32,
+}
+
+impl Default for ConditionSettings {
+    fn default() -> Self {
+        Self {
+            idle_minutes: None,
+            cpu_above_percent: None,
+            blocking_processes: Vec::new(),
+            postpone_minutes: DEFAULT_POSTPONE_MINUTES,
+        }
+    }
+}
+
+/// 不满足的执行条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmetCondition {
+    /// 不满足的原因
+    pub reason: String,
+    /// 推迟多久后再次检查
+    pub retry_after: chrono::Duration,
+}
+
+impl ConditionSettings {
+    /// 空闲或进程条件不满足时推迟的时长，至少1分钟
+    pub fn postpone(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.postpone_minutes.max(1) as i64)
+    }
+    
+    /// 检查条件是否满足
+    /// 
+    /// # 参数
+    /// 
+    /// * `context` - 检查时的系统状态
+    /// 
+    /// # 返回值
+    /// 
+    /// 条件都满足时返回None，否则返回第一个不满足的条件
+    pub fn check(&self, context: &ConditionContext) -> Option<UnmetCondition> {
+        if let Some(minutes) = self.idle_minutes {
+            let required = Duration::from_secs(minutes as u64 * 60);
+            match context.idle_for {
+                Some(idle_for) if idle_for < required => {
+                    return Some(UnmetCondition {
+                        reason: format!("电脑仅空闲了{}分钟，未达到要求的{}分钟", idle_for.as_secs() / 60, minutes),
+                        retry_after: self.postpone(),
+                    });
+                },
+                Some(_) => {},
+                None => warn!("当前平台不支持空闲检测，忽略空闲条件"),
+            }
+        }
+        
+        if let Some(threshold) = self.cpu_above_percent {
+            match context.cpu_usage {
+                Some(usage) if usage > threshold as f64 => {
+                    return Some(UnmetCondition {
+                        reason: format!("CPU占用{:.0}%，高于设置的{}%", usage, threshold),
+                        retry_after: chrono::Duration::minutes(CPU_RECHECK_MINUTES),
+                    });
+                },
+                Some(_) => {},
+                None => warn!("当前平台无法获取CPU占用，忽略CPU条件"),
+            }
+        }
+        
+        if !self.blocking_processes.is_empty() {
+            match &context.running_processes {
+                Some(running) => {
+                    if let Some(name) = system::find_running_process(&self.blocking_processes, running) {
+                        return Some(UnmetCondition {
+                            reason: format!("{}正在运行", name),
+                            retry_after: self.postpone(),
+                        });
+                    }
+                },
+                None => warn!("当前平台无法枚举进程，忽略进程条件"),
+            }
+        }
+        None
+    }
+}
+
+/// 检查条件时的系统状态
+#[derive(Debug, Clone, Default)]
+pub struct ConditionContext {
+    /// 距离上次键盘鼠标输入的时长，平台不支持空闲检测时为空
+    pub idle_for: Option<Duration>,
+    /// CPU占用百分比，未设置CPU条件或平台不支持时为空
+    pub cpu_usage: Option<f64>,
+    /// 正在运行的进程名，未设置进程条件或平台不支持时为空
+    pub running_processes: Option<Vec<String>>,
+}
+
+impl ConditionContext {
+    /// 采集检查条件所需的系统状态
+    /// 
+    /// 只采集设置中用到的状态；设置了CPU条件时需要间隔采样，约等待1秒
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 执行条件设置
+    pub async fn collect(settings: &ConditionSettings) -> Self {
+        let idle_for = match settings.idle_minutes {
+            Some(_) if platform::capabilities().idle_detection => Some(system::get_idle_time()),
+            _ => None,
+        };
+        let cpu_usage = match settings.cpu_above_percent {
+            Some(_) => monitor::measure_cpu_usage(monitor::DEFAULT_SAMPLE_INTERVAL).await,
+            None => None,
+        };
+        let running_processes = if settings.blocking_processes.is_empty() {
+            None
+        } else {
+            system::get_running_processes()
+        };
+        Self { idle_for, cpu_usage, running_processes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_idle_condition() {
+        let settings = ConditionSettings { idle_minutes: Some(10), ..Default::default() };
+        
+        let busy = ConditionContext { idle_for: Some(Duration::from_secs(3 * 60 + 20)), ..Default::default() };
+        let unmet = settings.check(&busy).unwrap();
+        assert_eq!(unmet.reason, "电脑仅空闲了3分钟，未达到要求的10分钟");
+        assert_eq!(unmet.retry_after, chrono::Duration::minutes(10));
+        
+        let idle = ConditionContext { idle_for: Some(Duration::from_secs(10 * 60)), ..Default::default() };
+        assert!(settings.check(&idle).is_none());
+        
+        // 不支持空闲检测时不阻止执行
+        assert!(settings.check(&ConditionContext::default()).is_none());
+        assert!(ConditionSettings::default().check(&busy).is_none());
+    }
+    
+    #[test]
+    fn test_cpu_condition() {
+        let settings = ConditionSettings { cpu_above_percent: Some(20), ..Default::default() };
+        
+        let rendering = ConditionContext { cpu_usage: Some(87.4), ..Default::default() };
+        let unmet = settings.check(&rendering).unwrap();
+        assert_eq!(unmet.reason, "CPU占用87%，高于设置的20%");
+        assert_eq!(unmet.retry_after, chrono::Duration::minutes(CPU_RECHECK_MINUTES));
+        
+        let quiet = ConditionContext { cpu_usage: Some(20.0), ..Default::default() };
+        assert!(settings.check(&quiet).is_none());
+        assert!(settings.check(&ConditionContext::default()).is_none());
+    }
+    
+    #[test]
+    fn test_process_condition() {
+        let settings = ConditionSettings {
+            blocking_processes: vec!["obs64.exe".to_string(), "HandBrake.exe".to_string()],
+            ..Default::default()
+        };
+        
+        let recording = ConditionContext {
+            running_processes: Some(vec!["explorer.exe".to_string(), "HandBrake.exe".to_string()]),
+            ..Default::default()
+        };
+        let unmet = settings.check(&recording).unwrap();
+        assert_eq!(unmet.reason, "HandBrake.exe正在运行");
+        assert_eq!(unmet.retry_after, chrono::Duration::minutes(10));
+        
+        let finished = ConditionContext { running_processes: Some(vec!["explorer.exe".to_string()]), ..Default::default() };
+        assert!(settings.check(&finished).is_none());
+        
+        // 无法枚举进程时不阻止执行
+        assert!(settings.check(&ConditionContext::default()).is_none());
+        assert!(ConditionSettings::default().check(&recording).is_none());
+    }
+    
+    #[test]
+    fn test_postpone_is_at_least_one_minute() {
+        let settings = ConditionSettings { postpone_minutes: 0, ..Default::default() };
+        assert_eq!(settings.postpone(), chrono::Duration::minutes(1));
+        assert_eq!(ConditionSettings::default().postpone(), chrono::Duration::minutes(10));
+    }
+}
@@ -3,17 +3,167 @@
 //! 负责管理倒计时状态，提供实时更新和任务调度功能
 
 use anyhow::{Result, anyhow};
-use chrono::{DateTime, Local, Duration, TimeZone};
-use log::{info, error, debug};
+use chrono::{DateTime, Local, Duration};
+use log::{info, warn, error, debug};
 use tokio::sync::{mpsc, broadcast, RwLock, Notify};
-use tokio::time::{interval, Instant};
-use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use uuid::Uuid;
 
-use crate::core::types::{CountdownStatus, CountdownUpdate, TaskData, TaskType};
+use crate::core::schedule;
+use crate::core::types::{CountdownStatus, CountdownUpdate, StatusSnapshot, TaskData, TaskType};
 use crate::core::time_parser::TimeParser;
 
+/// 倒计时任务超过这个时间没有心跳时视为卡住
+pub const HEARTBEAT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// 重启卡住的倒计时时，已经过了到点时间的任务至少再等待的秒数
+pub const RESTART_GRACE_SECONDS: i64 = 5;
+
+/// 两次检查之间系统时间比单调时钟多走超过这个秒数时视为系统睡眠过
+pub const SUSPEND_DETECT_SECONDS: i64 = 5;
+
+/// 是否用系统时间对照单调时钟来发现系统睡眠
+/// 
+/// Linux和macOS的单调时钟在睡眠期间停止，需要对照系统时间补上睡眠时长；
+/// Windows的单调时钟计入睡眠时间，不需要对照，唤醒由系统电源消息通知，
+/// 也不会把系统时间向前调整误当作睡眠
+const SUSPEND_CLOCK_CHECK: bool = !cfg!(windows);
+
+/// 倒计时计时信息
+/// 
+/// 基于单调时钟记录倒计时的开始、暂停和延长，
+/// 所有剩余时间、进度的计算都从这里派生，不受系统时间调整影响
+#[derive(Debug, Clone, Copy)]
+struct CountdownTiming {
+    /// 开始时刻
+    started_at: Instant,
+    /// 初始总时长
+    initial_total: Duration,
+    /// 延长累计时长
+    extended: Duration,
+    /// 已结束的暂停累计时长
+    paused_total: std::time::Duration,
+    /// 当前暂停开始时刻
+    paused_at: Option<Instant>,
+    /// 系统睡眠期间单调时钟没有计入的时长，计入已过时间
+    suspended: std::time::Duration,
+}
+
+impl CountdownTiming {
+    /// 创建新的计时信息
+    /// 
+    /// # 参数
+    /// 
+    /// * `started_at` - 开始时刻
+    /// * `initial_total` - 初始总时长
+    fn new(started_at: Instant, initial_total: Duration) -> Self {
+        Self {
+            started_at,
+            initial_total,
+            extended: Duration::zero(),
+            paused_total: std::time::Duration::ZERO,
+            paused_at: None,
+            suspended: std::time::Duration::ZERO,
+        }
+    }
+    
+    /// 总时长（包含延长部分）
+    fn total(&self) -> Duration {
+        self.initial_total + self.extended
+    }
+    
+    /// 截至指定时刻的暂停累计时长（包含正在进行的暂停）
+    fn paused_at_time(&self, now: Instant) -> std::time::Duration {
+        let current_pause = self.paused_at
+            .map(|paused_at| now.saturating_duration_since(paused_at))
+            .unwrap_or_default();
+        self.paused_total + current_pause
+    }
+    
+    /// 截至指定时刻已经过的有效时长（不含暂停时间）
+    fn elapsed_at(&self, now: Instant) -> Duration {
+        let running = (now.saturating_duration_since(self.started_at) + self.suspended)
+            .saturating_sub(self.paused_at_time(now));
+        let elapsed = Duration::from_std(running).unwrap_or_else(|_| Duration::zero());
+        elapsed.min(self.total()).max(Duration::zero())
+    }
+    
+    /// 截至指定时刻的剩余时长
+    fn remaining_at(&self, now: Instant) -> Duration {
+        (self.total() - self.elapsed_at(now)).max(Duration::zero())
+    }
+    
+    /// 按指定时刻计算的截止时刻
+    /// 
+    /// 暂停期间截止时刻会随暂停时长顺延，系统睡眠的时长从中扣除
+    fn deadline_at(&self, now: Instant) -> Instant {
+        let total = self.total().to_std().unwrap_or_default();
+        (self.started_at + self.paused_at_time(now) + total)
+            .checked_sub(self.suspended)
+            .unwrap_or(self.started_at)
+    }
+    
+    /// 截至指定时刻的进度百分比
+    fn progress_at(&self, now: Instant) -> f64 {
+        let total_ms = self.total().num_milliseconds();
+        if total_ms <= 0 {
+            return 100.0;
+        }
+        
+        let progress = self.elapsed_at(now).num_milliseconds() as f64 / total_ms as f64;
+        (progress * 100.0).min(100.0).max(0.0)
+    }
+}
+
+/// 系统时间与单调时钟的对照，用于发现系统睡眠
+/// 
+/// 单调时钟在系统睡眠期间停止的平台上，唤醒后倒计时会比实际时间慢整个睡眠时长；
+/// 两次检查之间系统时间比单调时钟多走的部分视为睡眠时长。
+/// 系统时间向前调整超过阈值时无法与睡眠区分，同样计入已过时间，因此只在这些平台上使用
+#[derive(Debug, Clone, Copy)]
+struct ClockCheck {
+    /// 上次检查时的系统时间
+    wall: DateTime<Local>,
+    /// 上次检查时的单调时钟时刻
+    instant: Instant,
+}
+
+impl ClockCheck {
+    /// 以指定时刻作为对照起点
+    /// 
+    /// # 参数
+    /// 
+    /// * `wall` - 系统时间
+    /// * `instant` - 同一时刻的单调时钟时刻
+    fn new(wall: DateTime<Local>, instant: Instant) -> Self {
+        Self { wall, instant }
+    }
+    
+    /// 检查上次检查以来系统是否睡眠过，并以本次时刻作为新的对照起点
+    /// 
+    /// # 参数
+    /// 
+    /// * `wall` - 当前系统时间
+    /// * `instant` - 当前单调时钟时刻
+    /// 
+    /// # 返回值
+    /// 
+    /// 睡眠过时返回单调时钟没有计入的时长，否则返回None
+    fn suspended_since(&mut self, wall: DateTime<Local>, instant: Instant) -> Option<std::time::Duration> {
+        let monotonic = Duration::from_std(instant.saturating_duration_since(self.instant)).unwrap_or_else(|_| Duration::zero());
+        let gap = (wall - self.wall) - monotonic;
+        *self = Self::new(wall, instant);
+        if gap > Duration::seconds(SUSPEND_DETECT_SECONDS) {
+            gap.to_std().ok()
+        } else {
+            None
+        }
+    }
+}
+
 /// 倒计时管理器
 #[derive(Debug)]
 pub struct CountdownManager {
@@ -29,16 +179,69 @@ pub struct CountdownManager {
     cancel_sender: Arc<RwLock<Option<mpsc::UnboundedSender<()>>>>,
     /// 暂停/恢复通知
     pause_notify: Arc<Notify>,
+    /// 系统唤醒通知，收到后立即重新计算剩余时间
+    resume_notify: Arc<Notify>,
     /// 是否暂停
     is_paused: Arc<AtomicBool>,
     /// 开始时间戳
     start_timestamp: Arc<AtomicU64>,
-    /// 暂停累计时间（毫秒）
-    paused_duration: Arc<AtomicU64>,
+    /// 单调时钟计时信息
+    timing: Arc<StdRwLock<Option<CountdownTiming>>>,
+    /// 到点前的提醒时间，从远到近排列
+    warning_offsets: Arc<StdRwLock<Vec<Duration>>>,
+    /// 倒计时任务最后一次心跳的时间，任务正常结束后为空
+    heartbeat: Arc<StdRwLock<Option<Instant>>>,
+    /// 正在运行的倒计时任务
+    task_handle: Arc<StdMutex<Option<JoinHandle<()>>>>,
     /// 时间解析器
     time_parser: Arc<TimeParser>,
 }
 
+/// 倒计时状态查询句柄
+/// 
+/// 共享倒计时管理器的内部状态，可以在界面线程和本地HTTP服务中查询状态快照，
+/// 不需要持有倒计时管理器本身
+#[derive(Debug, Clone)]
+pub struct StatusHandle {
+    /// 倒计时管理器标识
+    id: Uuid,
+    /// 当前倒计时状态
+    status: Arc<RwLock<CountdownStatus>>,
+    /// 当前任务数据
+    current_task: Arc<RwLock<Option<TaskData>>>,
+    /// 是否暂停
+    is_paused: Arc<AtomicBool>,
+}
+
+impl StatusHandle {
+    /// 获取状态快照
+    pub async fn snapshot(&self) -> StatusSnapshot {
+        let status = self.status.read().await.clone();
+        let task = self.current_task.read().await.clone();
+        self.build_snapshot(&status, task.as_ref())
+    }
+    
+    /// 不等待锁获取状态快照
+    /// 
+    /// 供不能等待的同步代码（如界面更新）使用，状态正在被修改时返回None
+    pub fn try_snapshot(&self) -> Option<StatusSnapshot> {
+        let status = self.status.try_read().ok()?.clone();
+        let task = self.current_task.try_read().ok()?.clone();
+        Some(self.build_snapshot(&status, task.as_ref()))
+    }
+    
+    /// 由状态和任务生成快照
+    fn build_snapshot(&self, status: &CountdownStatus, task: Option<&TaskData>) -> StatusSnapshot {
+        StatusSnapshot::from_parts(
+            status,
+            task,
+            self.is_paused.load(Ordering::Relaxed),
+            Some(task.map(|task| task.id.clone()).unwrap_or_else(|| self.id.to_string())),
+            Local::now(),
+        )
+    }
+}
+
 impl CountdownManager {
     /// 创建新的倒计时管理器
     pub async fn new() -> Result<Self> {
@@ -51,9 +254,13 @@ impl CountdownManager {
             update_sender,
             cancel_sender: Arc::new(RwLock::new(None)),
             pause_notify: Arc::new(Notify::new()),
+            resume_notify: Arc::new(Notify::new()),
             is_paused: Arc::new(AtomicBool::new(false)),
             start_timestamp: Arc::new(AtomicU64::new(0)),
-            paused_duration: Arc::new(AtomicU64::new(0)),
+            timing: Arc::new(StdRwLock::new(None)),
+            warning_offsets: Arc::new(StdRwLock::new(Vec::new())),
+            heartbeat: Arc::new(StdRwLock::new(None)),
+            task_handle: Arc::new(StdMutex::new(None)),
             time_parser: Arc::new(TimeParser::new()),
         })
     }
@@ -63,6 +270,21 @@ impl CountdownManager {
         self.id
     }
     
+    /// 获取状态查询句柄
+    pub fn status_handle(&self) -> StatusHandle {
+        StatusHandle {
+            id: self.id,
+            status: Arc::clone(&self.status),
+            current_task: Arc::clone(&self.current_task),
+            is_paused: Arc::clone(&self.is_paused),
+        }
+    }
+    
+    /// 获取状态快照
+    pub async fn status_snapshot(&self) -> StatusSnapshot {
+        self.status_handle().snapshot().await
+    }
+    
     /// 获取更新通知接收器
     /// 
     /// UI组件可以通过此接收器获取倒计时状态更新
@@ -70,6 +292,42 @@ impl CountdownManager {
         self.update_sender.subscribe()
     }
     
+    /// 发布倒计时相关通知
+    /// 
+    /// 用于将倒计时之外产生的事件（如检测到外部关机计划）通知给UI
+    /// 
+    /// # 参数
+    /// 
+    /// * `update` - 要发布的通知
+    pub fn publish_update(&self, update: CountdownUpdate) {
+        if let Err(e) = self.update_sender.send(update) {
+            error!("发布倒计时通知失败: {:?}", e);
+        }
+    }
+    
+    /// 获取通知发送器
+    /// 
+    /// 供不持有倒计时管理器的后台任务（如等待下载完成）发布通知
+    pub fn update_publisher(&self) -> broadcast::Sender<CountdownUpdate> {
+        self.update_sender.clone()
+    }
+    
+    /// 设置到点前的提醒时间
+    /// 
+    /// 倒计时到达每个提醒时间时发送一次提醒通知，对正在进行的倒计时立即生效
+    /// 
+    /// # 参数
+    /// 
+    /// * `offsets` - 距离到点的提醒时间
+    pub fn set_warning_offsets(&self, mut offsets: Vec<Duration>) {
+        offsets.sort_by(|a, b| b.cmp(a));
+        offsets.dedup();
+        match self.warning_offsets.write() {
+            Ok(mut warning_offsets) => *warning_offsets = offsets,
+            Err(e) => error!("设置提醒时间失败: {}", e),
+        }
+    }
+    
     /// 获取当前倒计时状态
     pub async fn get_status(&self) -> CountdownStatus {
         self.status.read().await.clone()
@@ -80,31 +338,23 @@ impl CountdownManager {
     /// # 参数
     /// 
     /// * `task` - 任务数据
-    pub async fn start_countdown_from_task(&self, task: TaskData) -> Result<()> {
-        // 根据任务类型计算目标时间
+    pub async fn start_countdown_from_task(&self, mut task: TaskData) -> Result<()> {
+        // 单次任务使用目标时间，已过的时间也照常开始；重复任务按计划计算下一次执行时间
         let target_time = match task.task_type {
             TaskType::Once => {
                 task.target_time.ok_or_else(|| anyhow!("单次任务缺少目标时间"))?
             },
-            TaskType::Daily => {
-                let daily_time = task.daily_time.ok_or_else(|| anyhow!("每日任务缺少时间设置"))?;
-                let now = Local::now();
-                let today = now.date_naive();
-                let target_datetime = today.and_time(daily_time);
-                
-                // 如果今天的时间已过，则设置为明天
-                 if Local::now().naive_local() > target_datetime {
-                     let tomorrow = today + chrono::Duration::days(1);
-                     Local.from_local_datetime(&tomorrow.and_time(daily_time)).single().unwrap()
-                 } else {
-                     Local.from_local_datetime(&target_datetime).single().unwrap()
-                 }
-            }
+            TaskType::Logon => return Err(anyhow!("登录任务在登录时执行，不需要倒计时")),
+            _ => {
+                let (target_time, action) = schedule::next_fire(&task, Local::now())
+                    .ok_or_else(|| anyhow!("{}没有可执行的时间: {}", task.task_type, task.schedule_text()))?;
+                // 每周计划各时间段的操作可能不同，记录本次要执行的操作
+                task.action = action;
+                task.target_time = Some(target_time);
+                target_time
+            },
         };
         
-        // 保存任务数据
-        *self.current_task.write().await = Some(task.clone());
-        
         info!("开始倒计时任务: {:?} -> {}", task.task_type, target_time.format("%Y-%m-%d %H:%M:%S"));
         
         self.start_countdown_internal(target_time, Some(task)).await
@@ -120,7 +370,7 @@ impl CountdownManager {
     }
     
     /// 内部倒计时启动方法
-    async fn start_countdown_internal(&self, target_time: DateTime<Local>, _task: Option<TaskData>) -> Result<()> {
+    async fn start_countdown_internal(&self, target_time: DateTime<Local>, task: Option<TaskData>) -> Result<()> {
         // 检查目标时间是否有效
         let now = Local::now();
         if target_time <= now {
@@ -130,10 +380,15 @@ impl CountdownManager {
         // 取消之前的倒计时
         self.cancel_countdown().await?;
         
+        // 保存任务数据，普通倒计时不关联任务
+        *self.current_task.write().await = task;
+        
         // 重置状态
         self.is_paused.store(false, Ordering::Relaxed);
         self.start_timestamp.store(now.timestamp_millis() as u64, Ordering::Relaxed);
-        self.paused_duration.store(0, Ordering::Relaxed);
+        self.set_timing(Some(CountdownTiming::new(Instant::now(), target_time - now)));
+        // 开始后立即可以暂停、延长，不等待倒计时任务的第一次计时
+        *self.status.write().await = CountdownStatus::Running { remaining: target_time - now };
         
         // 创建取消通道
         let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
@@ -144,19 +399,33 @@ impl CountdownManager {
         let current_task = Arc::clone(&self.current_task);
         let update_sender = self.update_sender.clone();
         let pause_notify = Arc::clone(&self.pause_notify);
+        let resume_notify = Arc::clone(&self.resume_notify);
         let is_paused = Arc::clone(&self.is_paused);
-        let paused_duration = Arc::clone(&self.paused_duration);
+        let timing = Arc::clone(&self.timing);
+        let warning_offsets = Arc::clone(&self.warning_offsets);
+        let heartbeat = Arc::clone(&self.heartbeat);
+        let mut previous_remaining = target_time - now;
         
         info!("开始倒计时，目标时间: {}", target_time.format("%Y-%m-%d %H:%M:%S"));
         
         // 启动倒计时任务
-        tokio::spawn(async move {
+        self.beat();
+        let handle = tokio::spawn(async move {
             let mut interval = interval(tokio::time::Duration::from_secs(1));
-            let mut pause_start: Option<Instant> = None;
+            // 单调时钟在睡眠期间继续走的平台上，唤醒后不补发错过的tick
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let mut clock_check = ClockCheck::new(Local::now(), Instant::now());
+            // 任务正常结束时清除心跳；任务卡住或中止时心跳不再更新，供看门狗发现
+            let stop_heartbeat = || {
+                if let Ok(mut heartbeat) = heartbeat.write() {
+                    *heartbeat = None;
+                }
+            };
             
             loop {
                 // 检查是否收到取消信号
                 if cancel_rx.try_recv().is_ok() {
+                    stop_heartbeat();
                     info!("倒计时被取消");
                     *status.write().await = CountdownStatus::Cancelled;
                     info!("发送倒计时取消通知");
@@ -166,36 +435,75 @@ impl CountdownManager {
                     return;
                 }
                 
-                // 检查暂停状态
+                // 检查暂停状态，暂停时长由计时信息统一记录
                 if is_paused.load(Ordering::Relaxed) {
-                    if pause_start.is_none() {
-                        pause_start = Some(Instant::now());
-                        debug!("倒计时已暂停");
-                    }
-                    
-                    // 等待恢复信号
+                    // 等待恢复信号，暂停期间的睡眠不计入已过时间
                     pause_notify.notified().await;
-                    
-                    if let Some(start) = pause_start {
-                        let pause_duration_ms = start.elapsed().as_millis() as u64;
-                        paused_duration.fetch_add(pause_duration_ms, Ordering::Relaxed);
-                        pause_start = None;
-                        debug!("倒计时已恢复，暂停时长: {}ms", pause_duration_ms);
-                    }
+                    clock_check = ClockCheck::new(Local::now(), Instant::now());
                     continue;
                 }
                 
-                // 等待下一个tick
-                interval.tick().await;
+                // 等待下一个tick，或者在下一次提醒的准确时刻、系统唤醒时立即处理
+                let offsets = warning_offsets.read().map(|offsets| offsets.clone()).unwrap_or_default();
+                let next_warning = timing.read().ok().and_then(|timing| *timing).and_then(|snapshot| {
+                    let now = Instant::now();
+                    let offset = next_warning(&offsets, snapshot.remaining_at(now))?;
+                    Some(snapshot.deadline_at(now) - offset.to_std().ok()?)
+                });
+                match next_warning {
+                    Some(warning_at) => tokio::select! {
+                        _ = interval.tick() => {},
+                        _ = tokio::time::sleep_until(warning_at) => {},
+                        _ = resume_notify.notified() => {},
+                    },
+                    None => tokio::select! {
+                        _ = interval.tick() => {},
+                        _ = resume_notify.notified() => {},
+                    },
+                }
+                if let Ok(mut heartbeat) = heartbeat.write() {
+                    *heartbeat = Some(Instant::now());
+                }
                 
-                // 计算剩余时间（考虑暂停时间）
-                let now = Local::now();
-                let total_paused_ms = paused_duration.load(Ordering::Relaxed);
-                let adjusted_target = target_time + Duration::milliseconds(total_paused_ms as i64);
-                let remaining = adjusted_target - now;
+                // 系统从睡眠中唤醒后重新计算剩余时间，睡眠期间已过到点时间的立即执行
+                let suspended = if SUSPEND_CLOCK_CHECK {
+                    clock_check.suspended_since(Local::now(), Instant::now())
+                } else {
+                    None
+                };
+                if let Some(suspended) = suspended {
+                    info!("检测到系统睡眠约{}秒，重新计算剩余时间", suspended.as_secs());
+                    if let Ok(mut timing) = timing.write() {
+                        if let Some(timing) = timing.as_mut() {
+                            timing.suspended += suspended;
+                        }
+                    }
+                }
+                
+                // 根据单调时钟计时信息计算剩余时间和进度
+                let snapshot = match timing.read().ok().and_then(|timing| *timing) {
+                    Some(snapshot) => snapshot,
+                    None => {
+                        stop_heartbeat();
+                        debug!("计时信息已清除，结束倒计时任务");
+                        return;
+                    }
+                };
+                let now = Instant::now();
+                let remaining = snapshot.remaining_at(now);
+                
+                // 剩余时间越过提醒时间时发送提醒，同时越过多个时只提醒最近的一个
+                if let Some(offset) = crossed_warning(&offsets, previous_remaining, remaining) {
+                    info!("发送到点前提醒: 剩余 {} 秒", offset.num_seconds());
+                    if let Err(e) = update_sender.send(CountdownUpdate::Warning { remaining: offset }) {
+                        error!("发送到点前提醒失败: {:?}", e);
+                    }
+                }
+                previous_remaining = remaining;
                 
                 if remaining.num_seconds() <= 0 {
                     // 倒计时结束
+                    stop_heartbeat();
                     info!("倒计时结束");
                     *status.write().await = CountdownStatus::Finished;
                     
@@ -217,8 +525,7 @@ impl CountdownManager {
                     *status.write().await = CountdownStatus::Running { remaining };
                     
                     // 发送进度更新
-                    let start_time = Local::now() - (adjusted_target - target_time);
-                    let progress = Self::calculate_progress(start_time, adjusted_target, now);
+                    let progress = snapshot.progress_at(now);
                     debug!("发送倒计时进度更新: 剩余时间 {}秒, 进度 {:.1}%", remaining.num_seconds(), progress);
                     if let Err(e) = update_sender.send(CountdownUpdate::Progress { remaining, progress }) {
                         error!("发送倒计时进度更新失败: {:?}", e);
@@ -226,10 +533,77 @@ impl CountdownManager {
                 }
             }
         });
+        match self.task_handle.lock() {
+            Ok(mut task_handle) => *task_handle = Some(handle),
+            Err(e) => error!("保存倒计时任务句柄失败: {}", e),
+        }
         
         Ok(())
     }
     
+    /// 通知倒计时系统已从睡眠中唤醒
+    /// 
+    /// 倒计时任务立即重新计算剩余时间，睡眠期间已过到点时间的任务不再等待下一次tick
+    /// 
+    /// 睡眠期间倒计时任务没有心跳，唤醒后先刷新心跳，看门狗不会把睡眠误判为任务卡住
+    pub fn notify_resumed(&self) {
+        info!("系统已从睡眠中唤醒，重新计算剩余时间");
+        if let Ok(mut heartbeat) = self.heartbeat.write() {
+            if heartbeat.is_some() {
+                *heartbeat = Some(Instant::now());
+            }
+        }
+        self.resume_notify.notify_one();
+    }
+    
+    /// 记录一次心跳
+    fn beat(&self) {
+        if let Ok(mut heartbeat) = self.heartbeat.write() {
+            *heartbeat = Some(Instant::now());
+        }
+    }
+    
+    /// 检查倒计时任务是否仍在正常运行
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 任务正常、已结束或已暂停时返回None，任务卡住或已中止时返回原因说明
+    pub fn stalled_reason(&self, now: Instant) -> Option<String> {
+        let last_beat = self.heartbeat.read().ok().and_then(|heartbeat| *heartbeat);
+        heartbeat_problem(last_beat, self.is_paused.load(Ordering::Relaxed), now)
+    }
+    
+    /// 重新启动卡住的倒计时任务
+    /// 
+    /// 关联任务时按保存的目标时间重新开始，否则按原来的到点时间；
+    /// 到点时间已过时在几秒后执行，避免一直不执行
+    /// 
+    /// # 返回值
+    /// 
+    /// 重新开始后的目标时间
+    pub async fn restart_stalled(&self) -> Result<DateTime<Local>> {
+        let now = Local::now();
+        let task = self.current_task.read().await.clone();
+        let deadline = task.as_ref().and_then(|task| task.target_time)
+            .or_else(|| {
+                let snapshot = self.timing_snapshot()?;
+                Some(now + snapshot.remaining_at(Instant::now()))
+            })
+            .ok_or_else(|| anyhow!("没有可以恢复的倒计时"))?;
+        let target_time = deadline.max(now + Duration::seconds(RESTART_GRACE_SECONDS));
+        
+        if let Some(handle) = self.task_handle.lock().ok().and_then(|mut task_handle| task_handle.take()) {
+            handle.abort();
+        }
+        warn!("倒计时任务没有响应，按到点时间 {} 重新开始", target_time.format("%Y-%m-%d %H:%M:%S"));
+        self.start_countdown_internal(target_time, task).await?;
+        Ok(target_time)
+    }
+    
     /// 取消当前倒计时
     pub async fn cancel_countdown(&self) -> Result<()> {
         // 发送取消信号
@@ -240,6 +614,17 @@ impl CountdownManager {
         // 清除取消发送器
         *self.cancel_sender.write().await = None;
         
+        // 如果倒计时处于暂停状态，唤醒等待中的任务以便其处理取消信号
+        if self.is_paused.swap(false, Ordering::Relaxed) {
+            self.pause_notify.notify_one();
+        }
+        
+        // 清除计时信息
+        self.set_timing(None);
+        if let Ok(mut heartbeat) = self.heartbeat.write() {
+            *heartbeat = None;
+        }
+        
         // 更新状态
         *self.status.write().await = CountdownStatus::Cancelled;
         
@@ -251,6 +636,7 @@ impl CountdownManager {
     pub async fn pause_countdown(&self) -> Result<()> {
         if self.is_active().await && !self.is_paused.load(Ordering::Relaxed) {
             self.is_paused.store(true, Ordering::Relaxed);
+            self.update_timing(|timing| timing.paused_at = Some(Instant::now()));
             debug!("倒计时已暂停");
             
             // 发送暂停状态更新
@@ -265,7 +651,13 @@ impl CountdownManager {
     /// 恢复倒计时
     pub async fn resume_countdown(&self) -> Result<()> {
         if self.is_active().await && self.is_paused.load(Ordering::Relaxed) {
+            self.update_timing(|timing| {
+                if let Some(paused_at) = timing.paused_at.take() {
+                    timing.paused_total += paused_at.elapsed();
+                }
+            });
             self.is_paused.store(false, Ordering::Relaxed);
+            self.beat();
             self.pause_notify.notify_one();
             debug!("倒计时已恢复");
             
@@ -278,6 +670,151 @@ impl CountdownManager {
         Ok(())
     }
     
+    /// 延长倒计时
+    /// 
+    /// 到点时间和当前任务的目标时间一起推后，并立即广播新的进度，
+    /// 界面、托盘和通知不必等到下一次计时就能显示推迟后的剩余时间
+    /// 
+    /// # 参数
+    /// 
+    /// * `duration` - 要延长的时间
+    pub async fn extend(&self, duration: Duration) -> Result<()> {
+        if duration <= Duration::zero() {
+            return Err(anyhow!("延长时间必须大于0"));
+        }
+        
+        self.adjust(duration).await?;
+        info!("倒计时已延长 {} 秒", duration.num_seconds());
+        Ok(())
+    }
+    
+    /// 增加剩余时间
+    /// 
+    /// 与`extend`相同，用于在运行中微调倒计时
+    /// 
+    /// # 参数
+    /// 
+    /// * `duration` - 要增加的时间
+    pub async fn add_time(&self, duration: Duration) -> Result<()> {
+        self.extend(duration).await
+    }
+    
+    /// 减少剩余时间
+    /// 
+    /// 到点时间和当前任务的目标时间一起提前，剩余时间不足以减少时返回错误，
+    /// 避免误操作导致立即执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `duration` - 要减少的时间
+    pub async fn subtract_time(&self, duration: Duration) -> Result<()> {
+        if duration <= Duration::zero() {
+            return Err(anyhow!("减少的时间必须大于0"));
+        }
+        
+        let remaining = self.timing_snapshot()
+            .map(|timing| timing.remaining_at(Instant::now()))
+            .unwrap_or_else(Duration::zero);
+        if remaining <= duration {
+            return Err(anyhow!("剩余时间不足{}分钟，无法减少", duration.num_minutes().max(1)));
+        }
+        
+        self.adjust(-duration).await?;
+        info!("倒计时已减少 {} 秒", duration.num_seconds());
+        Ok(())
+    }
+    
+    /// 调整倒计时总时长
+    /// 
+    /// 当前任务的目标时间同步调整，并立即广播新的进度
+    /// 
+    /// # 参数
+    /// 
+    /// * `delta` - 调整量，正数推后，负数提前
+    async fn adjust(&self, delta: Duration) -> Result<()> {
+        if !self.is_active().await {
+            return Err(anyhow!("当前没有活动的倒计时"));
+        }
+        
+        self.update_timing(|timing| timing.extended = timing.extended + delta);
+        if let Some(task) = self.current_task.write().await.as_mut() {
+            task.target_time = task.target_time.map(|target_time| target_time + delta);
+        }
+        
+        if let Some(timing) = self.timing_snapshot() {
+            let now = Instant::now();
+            let remaining = timing.remaining_at(now);
+            *self.status.write().await = CountdownStatus::Running { remaining };
+            if let Err(e) = self.update_sender.send(CountdownUpdate::Progress { remaining, progress: timing.progress_at(now) }) {
+                error!("发送倒计时进度更新失败: {:?}", e);
+            }
+        }
+        Ok(())
+    }
+    
+    /// 获取总延长时长（毫秒）
+    pub fn get_total_extended_duration(&self) -> i64 {
+        self.timing_snapshot()
+            .map(|timing| timing.extended.num_milliseconds())
+            .unwrap_or(0)
+    }
+    
+    /// 获取当前倒计时已经过的有效时长（不含暂停时间）
+    /// 
+    /// 基于单调时钟计算，不受系统时间调整影响；没有倒计时时返回None
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.timing_snapshot().map(|timing| timing.elapsed_at(Instant::now()))
+    }
+    
+    /// 获取当前倒计时的总时长（包含延长部分）
+    /// 
+    /// 没有倒计时时返回None
+    pub fn total(&self) -> Option<Duration> {
+        self.timing_snapshot().map(|timing| timing.total())
+    }
+    
+    /// 获取当前倒计时的截止时刻（单调时钟）
+    /// 
+    /// 暂停期间截止时刻随暂停时长顺延；没有倒计时时返回None
+    pub fn deadline(&self) -> Option<Instant> {
+        self.timing_snapshot().map(|timing| timing.deadline_at(Instant::now()))
+    }
+    
+    /// 获取当前倒计时的进度百分比
+    /// 
+    /// 与`elapsed()`、`total()`使用相同的计时信息；没有倒计时时返回None
+    pub fn progress(&self) -> Option<f64> {
+        self.timing_snapshot().map(|timing| timing.progress_at(Instant::now()))
+    }
+    
+    /// 获取计时信息快照
+    fn timing_snapshot(&self) -> Option<CountdownTiming> {
+        self.timing.read().ok().and_then(|timing| *timing)
+    }
+    
+    /// 替换计时信息
+    fn set_timing(&self, timing: Option<CountdownTiming>) {
+        match self.timing.write() {
+            Ok(mut guard) => *guard = timing,
+            Err(e) => error!("更新计时信息失败: {}", e),
+        }
+    }
+    
+    /// 修改当前计时信息
+    fn update_timing<F>(&self, update: F)
+    where
+        F: FnOnce(&mut CountdownTiming),
+    {
+        match self.timing.write() {
+            Ok(mut guard) => {
+                if let Some(timing) = guard.as_mut() {
+                    update(timing);
+                }
+            },
+            Err(e) => error!("更新计时信息失败: {}", e),
+        }
+    }
+    
     /// 重置倒计时管理器
     /// 
     /// 取消当前倒计时并重置状态为空闲
@@ -306,6 +843,16 @@ impl CountdownManager {
         self.current_task.read().await.clone()
     }
     
+    /// 获取正在倒计时的任务
+    /// 
+    /// 刚开始还没有第一次进度更新的倒计时也算在内，倒计时已结束或已取消时返回None
+    pub async fn get_active_task(&self) -> Option<TaskData> {
+        if self.timing_snapshot().is_none() || *self.status.read().await == CountdownStatus::Finished {
+            return None;
+        }
+        self.get_current_task().await
+    }
+    
     /// 获取倒计时开始时间戳（毫秒）
     pub fn get_start_timestamp(&self) -> Option<u64> {
         let timestamp = self.start_timestamp.load(Ordering::Relaxed);
@@ -318,7 +865,9 @@ impl CountdownManager {
     
     /// 获取总暂停时长（毫秒）
     pub fn get_total_paused_duration(&self) -> u64 {
-        self.paused_duration.load(Ordering::Relaxed)
+        self.timing_snapshot()
+            .map(|timing| timing.paused_at_time(Instant::now()).as_millis() as u64)
+            .unwrap_or(0)
     }
     
     /// 获取剩余时间
@@ -457,7 +1006,7 @@ impl CountdownEventHandler {
                     callback(msg.clone());
                 }
             },
-            CountdownUpdate::Progress { .. } => {
+            CountdownUpdate::Progress { .. } | CountdownUpdate::Warning { .. } => {
                 // Progress事件通常由UI处理，这里不需要特殊处理
             },
             CountdownUpdate::Paused => {
@@ -468,15 +1017,81 @@ impl CountdownEventHandler {
             },
             CountdownUpdate::TaskCompleted { .. } => {
                 // 任务完成事件处理
+            },
+            CountdownUpdate::ExternalShutdownDetected | CountdownUpdate::NetworkWaiting { .. } | CountdownUpdate::Postponed { .. } | CountdownUpdate::Notice { .. } => {
+                // 外部关机计划、等待下载完成、推迟执行和其他提示由UI提示用户
+            },
+            CountdownUpdate::WindowRequested(_) => {
+                // 显示或隐藏主窗口由连接的界面处理
             }
         }
     }
 }
 
+/// 根据心跳判断倒计时任务的问题
+/// 
+/// # 参数
+/// 
+/// 发布版本中panic会直接终止进程，因此不检查任务是否退出，
+/// 卡住和中止的任务都表现为心跳超时
+/// 
+/// * `last_beat` - 最后一次心跳，任务正常结束后为空
+/// * `paused` - 倒计时是否已暂停，暂停期间不发送心跳
+/// * `now` - 当前时间
+fn heartbeat_problem(last_beat: Option<Instant>, paused: bool, now: Instant) -> Option<String> {
+    let last_beat = last_beat?;
+    let silent = now.saturating_duration_since(last_beat);
+    if !paused && silent > HEARTBEAT_TIMEOUT {
+        return Some(format!("倒计时任务已{}秒没有响应", silent.as_secs()));
+    }
+    None
+}
+
+/// 剩余时间之后最先到达的提醒时间
+/// 
+/// # 参数
+/// 
+/// * `offsets` - 从远到近排列的提醒时间
+/// * `remaining` - 当前剩余时间
+fn next_warning(offsets: &[Duration], remaining: Duration) -> Option<Duration> {
+    offsets.iter().copied().find(|offset| *offset < remaining)
+}
+
+/// 剩余时间从`previous`减少到`remaining`时越过的最近一个提醒时间
+/// 
+/// 延长倒计时后剩余时间增加，之后会再次越过已经提醒过的时间
+/// 
+/// # 参数
+/// 
+/// * `offsets` - 从远到近排列的提醒时间
+/// * `previous` - 上次检查时的剩余时间
+/// * `remaining` - 当前剩余时间
+fn crossed_warning(offsets: &[Duration], previous: Duration, remaining: Duration) -> Option<Duration> {
+    offsets.iter().copied()
+        .filter(|offset| remaining <= *offset && *offset < previous)
+        .last()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tokio::time::{sleep, Duration as TokioDuration};
+    use crate::core::stats::TaskStats;
+    
+    #[test]
+    fn test_warning_schedule() {
+        let offsets = [Duration::minutes(10), Duration::minutes(1), Duration::seconds(10)];
+        assert_eq!(next_warning(&offsets, Duration::minutes(30)), Some(Duration::minutes(10)));
+        assert_eq!(next_warning(&offsets, Duration::minutes(10)), Some(Duration::minutes(1)));
+        assert_eq!(next_warning(&offsets, Duration::seconds(5)), None);
+        
+        assert_eq!(crossed_warning(&offsets, Duration::seconds(601), Duration::seconds(600)), Some(Duration::minutes(10)));
+        assert_eq!(crossed_warning(&offsets, Duration::seconds(600), Duration::seconds(599)), None);
+        // 睡眠恢复后一次越过多个提醒时间，只提醒最近的一个
+        assert_eq!(crossed_warning(&offsets, Duration::minutes(20), Duration::seconds(30)), Some(Duration::minutes(1)));
+        // 开始时已不足的提醒时间不会提醒
+        assert_eq!(crossed_warning(&offsets, Duration::seconds(5), Duration::seconds(4)), None);
+    }
     
     #[tokio::test]
     async fn test_countdown_basic() {
@@ -498,6 +1113,50 @@ mod tests {
         assert!(manager.get_remaining_time().await.is_some());
     }
     
+    #[test]
+    fn test_heartbeat_problem() {
+        let now = Instant::now();
+        let recent = now - std::time::Duration::from_secs(2);
+        let stale = now - HEARTBEAT_TIMEOUT - std::time::Duration::from_secs(5);
+        
+        assert_eq!(heartbeat_problem(Some(recent), false, now), None);
+        assert_eq!(heartbeat_problem(Some(stale), false, now), Some("倒计时任务已15秒没有响应".to_string()));
+        // 暂停期间没有心跳，正常结束后心跳已清除
+        assert_eq!(heartbeat_problem(Some(stale), true, now), None);
+        assert_eq!(heartbeat_problem(None, false, now), None);
+    }
+    
+    #[tokio::test]
+    async fn test_restart_stalled_countdown() {
+        let manager = CountdownManager::new().await.unwrap();
+        let target = Local::now() + Duration::seconds(30);
+        manager.start_countdown(target).await.unwrap();
+        assert_eq!(manager.stalled_reason(Instant::now()), None);
+        
+        // 模拟倒计时任务中止后心跳超时
+        if let Some(handle) = manager.task_handle.lock().unwrap().as_ref() {
+            handle.abort();
+        }
+        sleep(TokioDuration::from_millis(50)).await;
+        let later = Instant::now() + HEARTBEAT_TIMEOUT + std::time::Duration::from_secs(5);
+        assert!(manager.stalled_reason(later).is_some());
+        
+        // 唤醒后刷新心跳，睡眠期间没有心跳不算卡住
+        manager.notify_resumed();
+        assert_eq!(manager.stalled_reason(Instant::now()), None);
+        assert!(manager.stalled_reason(later).is_some());
+        
+        let restarted = manager.restart_stalled().await.unwrap();
+        assert!((restarted - target).num_seconds().abs() <= 1);
+        sleep(TokioDuration::from_millis(100)).await;
+        assert_eq!(manager.stalled_reason(Instant::now()), None);
+        assert!(manager.is_active().await);
+        
+        manager.cancel_countdown().await.unwrap();
+        sleep(TokioDuration::from_millis(50)).await;
+        assert_eq!(manager.stalled_reason(Instant::now()), None);
+    }
+    
     #[tokio::test]
     async fn test_countdown_cancel() {
         let manager = CountdownManager::new().await.unwrap();
@@ -517,6 +1176,266 @@ mod tests {
         assert!(matches!(manager.get_status().await, CountdownStatus::Cancelled));
     }
     
+    #[tokio::test]
+    async fn test_countdown_extend() {
+        let manager = CountdownManager::new().await.unwrap();
+        
+        // 没有活动倒计时时不能延长
+        assert!(manager.extend(Duration::minutes(10)).await.is_err());
+        
+        let target = Local::now() + Duration::seconds(10);
+        manager.start_countdown(target).await.unwrap();
+        sleep(TokioDuration::from_millis(100)).await;
+        
+        manager.extend(Duration::minutes(10)).await.unwrap();
+        assert_eq!(manager.get_total_extended_duration(), 10 * 60 * 1000);
+        
+        // 等待下一次更新，剩余时间应包含延长的部分
+        sleep(TokioDuration::from_millis(1100)).await;
+        let remaining = manager.get_remaining_time().await.unwrap();
+        assert!(remaining > Duration::minutes(10));
+    }
+    
+    #[tokio::test]
+    async fn test_add_and_subtract_time() {
+        let manager = CountdownManager::new().await.unwrap();
+        assert!(manager.subtract_time(Duration::minutes(5)).await.is_err());
+        
+        manager.start_countdown(Local::now() + Duration::minutes(8)).await.unwrap();
+        manager.add_time(Duration::minutes(5)).await.unwrap();
+        manager.subtract_time(Duration::minutes(5)).await.unwrap();
+        assert_eq!(manager.get_total_extended_duration(), 0);
+        
+        // 剩余时间不足时不能减少，避免立即执行
+        assert!(manager.subtract_time(Duration::minutes(10)).await.is_err());
+        assert!(manager.subtract_time(Duration::zero()).await.is_err());
+        manager.subtract_time(Duration::minutes(5)).await.unwrap();
+        let remaining = manager.get_remaining_time().await.unwrap();
+        assert!(remaining <= Duration::minutes(3) && remaining > Duration::minutes(2));
+    }
+    
+    #[tokio::test]
+    async fn test_extend_shifts_task_target() {
+        let task = TaskData::from_time_input(
+            TaskType::Once,
+            crate::core::types::TimeInput::Duration(Duration::minutes(1)),
+            crate::core::types::TaskAction::Shutdown,
+            Local::now(),
+        ).unwrap();
+        let target_time = task.target_time.unwrap();
+        
+        let manager = CountdownManager::new().await.unwrap();
+        manager.start_countdown_from_task(task).await.unwrap();
+        let mut receiver = manager.get_update_receiver();
+        
+        manager.extend(Duration::minutes(10)).await.unwrap();
+        let current = manager.get_current_task().await.unwrap();
+        assert_eq!(current.target_time, Some(target_time + Duration::minutes(10)));
+        
+        // 延长后立即广播新的进度，不等待下一次计时
+        let mut extended = false;
+        while let Ok(update) = receiver.try_recv() {
+            if let CountdownUpdate::Progress { remaining, .. } = update {
+                extended |= remaining > Duration::minutes(10);
+            }
+        }
+        assert!(extended);
+    }
+    
+    #[test]
+    fn test_countdown_timing() {
+        let start = Instant::now();
+        let mut timing = CountdownTiming::new(start, Duration::seconds(100));
+        
+        let at_10s = start + std::time::Duration::from_secs(10);
+        assert_eq!(timing.elapsed_at(at_10s), Duration::seconds(10));
+        assert_eq!(timing.remaining_at(at_10s), Duration::seconds(90));
+        assert_eq!(timing.deadline_at(at_10s), start + std::time::Duration::from_secs(100));
+        assert!((timing.progress_at(at_10s) - 10.0).abs() < f64::EPSILON);
+        
+        // 暂停期间已过时间保持不变，截止时刻顺延
+        timing.paused_at = Some(at_10s);
+        let at_30s = start + std::time::Duration::from_secs(30);
+        assert_eq!(timing.elapsed_at(at_30s), Duration::seconds(10));
+        assert_eq!(timing.deadline_at(at_30s), start + std::time::Duration::from_secs(120));
+        
+        // 恢复并延长
+        timing.paused_at = None;
+        timing.paused_total = std::time::Duration::from_secs(20);
+        timing.extended = Duration::seconds(50);
+        let at_40s = start + std::time::Duration::from_secs(40);
+        assert_eq!(timing.total(), Duration::seconds(150));
+        assert_eq!(timing.elapsed_at(at_40s), Duration::seconds(20));
+        assert_eq!(timing.remaining_at(at_40s), Duration::seconds(130));
+        
+        // 超过截止时刻后剩余时间为0，进度为100%
+        let late = start + std::time::Duration::from_secs(500);
+        assert_eq!(timing.remaining_at(late), Duration::zero());
+        assert!((timing.progress_at(late) - 100.0).abs() < f64::EPSILON);
+        
+        // 系统睡眠的时长计入已过时间，截止时刻相应提前
+        timing.suspended = std::time::Duration::from_secs(30);
+        assert_eq!(timing.remaining_at(at_40s), Duration::seconds(100));
+        assert_eq!(timing.deadline_at(at_40s), start + std::time::Duration::from_secs(140));
+    }
+    
+    #[test]
+    fn test_clock_check_detects_suspend() {
+        let start = Instant::now();
+        let wall = Local::now();
+        let mut clock_check = ClockCheck::new(wall, start);
+        
+        // 正常走时和小幅校时不视为睡眠
+        let at_1s = start + std::time::Duration::from_secs(1);
+        assert_eq!(clock_check.suspended_since(wall + Duration::seconds(3), at_1s), None);
+        // 系统时间向后调整不影响倒计时
+        let at_2s = start + std::time::Duration::from_secs(2);
+        assert_eq!(clock_check.suspended_since(wall - Duration::hours(1), at_2s), None);
+        
+        // 单调时钟只走了1秒，系统时间从上次的wall - 1小时走到了wall + 1秒
+        let at_3s = start + std::time::Duration::from_secs(3);
+        let woke = wall + Duration::seconds(1);
+        assert_eq!(clock_check.suspended_since(woke, at_3s), Some(std::time::Duration::from_secs(3600)));
+        // 之后以唤醒时刻为起点
+        let at_4s = start + std::time::Duration::from_secs(4);
+        assert_eq!(clock_check.suspended_since(woke + Duration::seconds(1), at_4s), None);
+    }
+    
+    #[tokio::test]
+    async fn test_elapsed_total_deadline_accessors() {
+        let manager = CountdownManager::new().await.unwrap();
+        assert!(manager.elapsed().is_none());
+        assert!(manager.total().is_none());
+        assert!(manager.deadline().is_none());
+        
+        let target = Local::now() + Duration::seconds(10);
+        manager.start_countdown(target).await.unwrap();
+        
+        let total = manager.total().unwrap();
+        assert!(total <= Duration::seconds(10) && total > Duration::seconds(9));
+        assert!(manager.elapsed().unwrap() < Duration::seconds(1));
+        assert!(manager.deadline().unwrap() > Instant::now());
+        
+        manager.cancel_countdown().await.unwrap();
+        assert!(manager.elapsed().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_while_paused() {
+        let manager = CountdownManager::new().await.unwrap();
+        let mut receiver = manager.get_update_receiver();
+        
+        let target = Local::now() + Duration::seconds(10);
+        manager.start_countdown(target).await.unwrap();
+        sleep(TokioDuration::from_millis(100)).await;
+        
+        manager.pause_countdown().await.unwrap();
+        sleep(TokioDuration::from_millis(1100)).await;
+        manager.cancel_countdown().await.unwrap();
+        
+        // 暂停中的倒计时任务应被唤醒并发送取消通知
+        let cancelled = tokio::time::timeout(TokioDuration::from_secs(2), async {
+            loop {
+                match receiver.recv().await {
+                    Ok(CountdownUpdate::Cancelled) => return true,
+                    Ok(_) => continue,
+                    Err(_) => return false,
+                }
+            }
+        }).await;
+        assert_eq!(cancelled, Ok(true));
+        assert!(!manager.is_paused().await);
+    }
+    
+    #[tokio::test]
+    async fn test_status_snapshot() {
+        use crate::core::types::{StatusState, TaskAction, TaskSource};
+        
+        let manager = CountdownManager::new().await.unwrap();
+        let handle = manager.status_handle();
+        assert_eq!(handle.snapshot().await.state, StatusState::Idle);
+        
+        let mut task = TaskData::from_time_input(
+            TaskType::Once,
+            crate::core::types::TimeInput::Duration(Duration::seconds(30)),
+            TaskAction::Restart,
+            Local::now(),
+        ).unwrap();
+        task.source = TaskSource::CommandLine;
+        let task_id = task.id.clone();
+        manager.start_countdown_from_task(task).await.unwrap();
+        sleep(TokioDuration::from_millis(100)).await;
+        
+        let snapshot = manager.status_snapshot().await;
+        assert_eq!(snapshot.state, StatusState::Running);
+        assert_eq!(snapshot.action, Some(TaskAction::Restart));
+        assert_eq!(snapshot.source, Some(TaskSource::CommandLine));
+        assert_eq!(snapshot.task_id, Some(task_id));
+        assert!(snapshot.summary().contains("到点后重启"));
+        
+        manager.pause_countdown().await.unwrap();
+        let snapshot = handle.try_snapshot().unwrap();
+        assert_eq!(snapshot.state, StatusState::Paused);
+        assert!(snapshot.paused);
+        
+        manager.cancel_countdown().await.unwrap();
+        let snapshot = handle.snapshot().await;
+        assert_eq!(snapshot.state, StatusState::Cancelled);
+        assert_eq!(snapshot.action, None);
+    }
+    
+    #[tokio::test]
+    async fn test_weekly_task_uses_slot_action() {
+        use crate::core::types::{new_task_id, TaskAction, TaskSource, WeeklySlot};
+        use chrono::Datelike;
+        
+        let now = Local::now();
+        let slot_at = |time: DateTime<Local>, action| WeeklySlot {
+            weekday: time.weekday(),
+            time: time.time(),
+            action,
+        };
+        let soon = now + Duration::hours(2);
+        let task = TaskData {
+            id: new_task_id(),
+            task_type: TaskType::Weekly,
+            target_time: None,
+            daily_time: None,
+            enabled: true,
+            created_at: now,
+            action: TaskAction::Shutdown,
+            weekly_slots: vec![
+                slot_at(now + Duration::days(1) + Duration::hours(3), TaskAction::Shutdown),
+                slot_at(soon, TaskAction::Sleep),
+                // 已经过去的时间段顺延到下周
+                slot_at(now - Duration::hours(1), TaskAction::Shutdown),
+            ],
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: None,
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
+        };
+        
+        let (next_time, action) = schedule::next_weekly(&task.weekly_slots, now).unwrap();
+        assert_eq!(action, TaskAction::Sleep);
+        assert!((next_time - soon).num_seconds().abs() <= 1);
+        
+        let past_only = TaskData { weekly_slots: vec![task.weekly_slots[2]], ..task.clone() };
+        assert!(schedule::next_weekly(&past_only.weekly_slots, now).unwrap().0 > now + Duration::days(5));
+        
+        let manager = CountdownManager::new().await.unwrap();
+        manager.start_countdown_from_task(task).await.unwrap();
+        let current = manager.get_current_task().await.unwrap();
+        assert_eq!(current.action, TaskAction::Sleep);
+        assert_eq!(current.target_time, Some(next_time));
+        manager.cancel_countdown().await.unwrap();
+        
+        let empty = TaskData { weekly_slots: Vec::new(), ..current };
+        assert!(manager.start_countdown_from_task(empty).await.is_err());
+    }
+    
     #[test]
     fn test_format_duration() {
         let duration = Duration::seconds(3661); // 1小时1分1秒
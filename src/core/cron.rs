@@ -0,0 +1,252 @@
+//! Cron表达式模块
+//! 
+//! 解析"秒 分 时 日 月 星期"六段式cron表达式（也接受省略秒的五段式），
+//! 用于自然语言无法描述的复杂重复计划，如`0 30 22 * * 1-5`表示工作日22:30
+
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Local};
+
+use crate::core::types::resolve_local_datetime;
+
+/// 向后查找下一次执行时间的最大天数，覆盖闰年2月29日这类四年一次的计划
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 4 + 1;
+
+/// 月份名称，按1月到12月排列
+const MONTH_NAMES: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+/// 星期名称，按周日到周六排列
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Cron计划
+/// 
+/// 星期字段与常见crontab一致：0和7表示周日，1-5表示周一到周五。
+/// 日和星期同时限定时，满足其中任意一个即执行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    /// 原始表达式
+    expression: String,
+    /// 允许的秒
+    seconds: Vec<u32>,
+    /// 允许的分钟
+    minutes: Vec<u32>,
+    /// 允许的小时
+    hours: Vec<u32>,
+    /// 允许的日期
+    days_of_month: Vec<u32>,
+    /// 允许的月份
+    months: Vec<u32>,
+    /// 允许的星期（0表示周日）
+    days_of_week: Vec<u32>,
+    /// 日期字段是否为通配符
+    any_day_of_month: bool,
+    /// 星期字段是否为通配符
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// 解析cron表达式
+    /// 
+    /// 每段支持`*`、`?`、单个值、`a-b`范围、`a,b`列表和`/n`步长，月份和星期也可以使用英文缩写
+    /// 
+    /// # 参数
+    /// 
+    /// * `expression` - cron表达式，如`0 30 22 * * 1-5`
+    pub fn parse(expression: &str) -> Result<Self> {
+        let mut fields: Vec<&str> = expression.split_whitespace().collect();
+        match fields.len() {
+            5 => fields.insert(0, "0"),
+            6 => {},
+            count => return Err(anyhow!("cron表达式需要6段（秒 分 时 日 月 星期）或省略秒的5段，实际为{}段", count)),
+        }
+        
+        let mut days_of_week = parse_field(fields[5], "星期", 0, 7, &WEEKDAY_NAMES, 0)?;
+        // 7和0都表示周日
+        for day in days_of_week.iter_mut() {
+            *day %= 7;
+        }
+        days_of_week.sort_unstable();
+        days_of_week.dedup();
+        
+        Ok(Self {
+            expression: fields.join(" "),
+            seconds: parse_field(fields[0], "秒", 0, 59, &[], 0)?,
+            minutes: parse_field(fields[1], "分钟", 0, 59, &[], 0)?,
+            hours: parse_field(fields[2], "小时", 0, 23, &[], 0)?,
+            days_of_month: parse_field(fields[3], "日期", 1, 31, &[], 0)?,
+            months: parse_field(fields[4], "月份", 1, 12, &MONTH_NAMES, 1)?,
+            days_of_week,
+            any_day_of_month: is_wildcard(fields[3]),
+            any_day_of_week: is_wildcard(fields[5]),
+        })
+    }
+    
+    /// 规范化后的表达式（始终为六段式）
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+    
+    /// 计算指定时间之后的下一次执行时间
+    /// 
+    /// 夏令时切换时按`resolve_local_datetime`的规则调整
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 四年内没有匹配的时间（如`0 0 0 31 2 *`）时返回None
+    pub fn next_after(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = now.naive_local();
+        
+        (0..=MAX_LOOKAHEAD_DAYS)
+            .filter_map(|offset| start.date().checked_add_signed(Duration::days(offset)))
+            .filter(|date| self.months.contains(&date.month()) && self.matches_day(date.day(), date.weekday().num_days_from_sunday()))
+            .find_map(|date| {
+                self.hours.iter()
+                    .flat_map(|&hour| self.minutes.iter().map(move |&minute| (hour, minute)))
+                    .flat_map(|(hour, minute)| self.seconds.iter().map(move |&second| (hour, minute, second)))
+                    .filter_map(|(hour, minute, second)| date.and_hms_opt(hour, minute, second))
+                    .filter(|naive| *naive > start)
+                    .filter_map(|naive| resolve_local_datetime(naive).ok())
+                    .find(|time| *time > now)
+            })
+    }
+    
+    /// 日期是否匹配
+    /// 
+    /// 与crontab相同，日期和星期都限定时满足其一即可
+    fn matches_day(&self, day_of_month: u32, day_of_week: u32) -> bool {
+        let day_matches = self.days_of_month.contains(&day_of_month);
+        let weekday_matches = self.days_of_week.contains(&day_of_week);
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_matches || weekday_matches,
+            _ => day_matches && weekday_matches,
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+/// 字段是否为通配符
+fn is_wildcard(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+/// 解析cron表达式中的一段
+/// 
+/// # 参数
+/// 
+/// * `field` - 字段文本
+/// * `name` - 字段名称，用于错误提示
+/// * `min` - 最小值
+/// * `max` - 最大值
+/// * `names` - 可用的英文缩写
+/// * `name_offset` - 第一个英文缩写对应的数值
+/// 
+/// # 返回值
+/// 
+/// 返回排序后不重复的取值
+fn parse_field(field: &str, name: &str, min: u32, max: u32, names: &[&str], name_offset: u32) -> Result<Vec<u32>> {
+    let parse_value = |text: &str| -> Result<u32> {
+        let value = match names.iter().position(|item| item.eq_ignore_ascii_case(text)) {
+            Some(index) => index as u32 + name_offset,
+            None => text.parse().map_err(|_| anyhow!("{}字段中的\"{}\"不是有效的值", name, text))?,
+        };
+        if value < min || value > max {
+            return Err(anyhow!("{}的取值{}超出范围{}-{}", name, value, min, max));
+        }
+        Ok(value)
+    };
+    
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().ok().filter(|step| *step > 0)
+                    .ok_or_else(|| anyhow!("{}字段中的步长\"{}\"无效", name, step))?;
+                (range, Some(step))
+            },
+            None => (part, None),
+        };
+        
+        let (start, end) = if is_wildcard(range) {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start)?, parse_value(end)?)
+        } else {
+            let value = parse_value(range)?;
+            // "5/15"表示从5开始每15执行一次
+            (value, if step.is_some() { max } else { value })
+        };
+        
+        if start > end {
+            return Err(anyhow!("{}字段中的范围\"{}\"起始值大于结束值", name, range));
+        }
+        values.extend((start..=end).step_by(step.unwrap_or(1) as usize));
+    }
+    
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Timelike, Weekday};
+    
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        resolve_local_datetime(NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()).unwrap()
+    }
+    
+    #[test]
+    fn test_parse_cron_expression() {
+        let schedule = CronSchedule::parse("0 30 22 * * 1-5").unwrap();
+        assert_eq!(schedule.expression(), "0 30 22 * * 1-5");
+        assert_eq!(schedule.days_of_week, vec![1, 2, 3, 4, 5]);
+        
+        // 五段式省略秒，星期可以使用英文缩写，7表示周日
+        let schedule = CronSchedule::parse("*/15 9-17 * JAN,dec sat,7").unwrap();
+        assert_eq!(schedule.expression(), "0 */15 9-17 * JAN,dec sat,7");
+        assert_eq!(schedule.minutes, vec![0, 15, 30, 45]);
+        assert_eq!(schedule.months, vec![1, 12]);
+        assert_eq!(schedule.days_of_week, vec![0, 6]);
+        
+        assert!(CronSchedule::parse("30 22 * * *").is_ok());
+        assert!(CronSchedule::parse("30 22 * *").is_err());
+        assert!(CronSchedule::parse("0 60 22 * * *").is_err());
+        assert!(CronSchedule::parse("0 30 22 * * 5-1").is_err());
+        assert!(CronSchedule::parse("0 */0 22 * * *").is_err());
+        assert!(CronSchedule::parse("0 30 22 * * FUN").is_err());
+    }
+    
+    #[test]
+    fn test_next_after() {
+        let schedule = CronSchedule::parse("0 30 22 * * 1-5").unwrap();
+        
+        // 2024-03-08是周五，当天22:30之前执行当天的计划
+        let next = schedule.next_after(local(2024, 3, 8, 20, 0)).unwrap();
+        assert_eq!(next, local(2024, 3, 8, 22, 30));
+        
+        // 周五22:30之后跳过周末，下一次是周一
+        let next = schedule.next_after(local(2024, 3, 8, 22, 30)).unwrap();
+        assert_eq!(next.weekday(), Weekday::Mon);
+        assert_eq!((next.day(), next.hour(), next.minute()), (11, 22, 30));
+        
+        // 日期和星期都限定时满足其一即可：每月1日或每周日
+        let schedule = CronSchedule::parse("0 0 8 1 * 0").unwrap();
+        assert_eq!(schedule.next_after(local(2024, 3, 2, 12, 0)).unwrap(), local(2024, 3, 3, 8, 0));
+        assert_eq!(schedule.next_after(local(2024, 3, 31, 12, 0)).unwrap(), local(2024, 4, 1, 8, 0));
+        
+        // 闰日计划在四年内可以找到，不存在的日期返回None
+        assert_eq!(CronSchedule::parse("0 0 0 29 2 *").unwrap().next_after(local(2024, 3, 1, 0, 0)).unwrap(), local(2028, 2, 29, 0, 0));
+        assert!(CronSchedule::parse("0 0 0 31 2 *").unwrap().next_after(local(2024, 3, 1, 0, 0)).is_none());
+    }
+}
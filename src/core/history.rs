@@ -0,0 +1,439 @@
+//! 任务历史模块
+//! 
+//! 将任务的到点执行、取消、执行失败等记录追加到存储中（默认为数据目录中的历史文件），
+//! 供设置窗口的"历史"分页、统计、回顾和"重复上次"使用，历史记录损坏不影响任务本身
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::core::storage::Storage;
+use crate::core::types::{CustomCommand, TaskAction, TaskData, TaskSource, TaskType, TimeInput};
+
+/// 历史文件最多保留的记录数，超出时删除最早的记录
+pub const MAX_HISTORY_ENTRIES: usize = 500;
+
+/// 取消任务的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancelReason {
+    /// 还在使用电脑
+    StillUsing,
+    /// 要改成其他时间
+    ChangeTime,
+    /// 误操作
+    Mistake,
+}
+
+impl CancelReason {
+    /// 所有取消原因，按界面显示顺序排列
+    pub const ALL: [CancelReason; 3] = [CancelReason::StillUsing, CancelReason::ChangeTime, CancelReason::Mistake];
+}
+
+impl std::fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            CancelReason::StillUsing => "还在用",
+            CancelReason::ChangeTime => "改时间",
+            CancelReason::Mistake => "误点",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// 历史事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum HistoryEvent {
+    /// 任务被取消
+    Cancelled {
+        /// 用户选择的取消原因，未选择时为空
+        #[serde(default)]
+        reason: Option<CancelReason>,
+    },
+    /// 到点前系统因其他原因结束了会话
+    SessionEnded,
+    /// 任务到点执行，在执行前记录，关机后也能保留
+    Executed,
+    /// 到点时因电量过低等原因取消了本次执行
+    Skipped {
+        /// 取消执行的原因
+        reason: String,
+    },
+    /// 执行操作失败
+    Failed {
+        /// 失败原因
+        error: String,
+    },
+}
+
+impl std::fmt::Display for HistoryEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryEvent::Cancelled { reason: Some(reason) } => write!(f, "已取消（{}）", reason),
+            HistoryEvent::Cancelled { reason: None } => write!(f, "已取消"),
+            HistoryEvent::SessionEnded => write!(f, "系统结束了会话"),
+            HistoryEvent::Executed => write!(f, "已执行"),
+            HistoryEvent::Skipped { reason } => write!(f, "未执行: {}", reason),
+            HistoryEvent::Failed { error } => write!(f, "执行失败: {}", error),
+        }
+    }
+}
+
+/// 可以通过"重复上次"重新开始的倒计时
+/// 
+/// 只记录单次任务的倒计时时长，重复时从当前时间重新计算到点时间
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepeatableTask {
+    /// 倒计时时长（秒），从创建任务到到点时间
+    pub seconds: i64,
+    /// 到点后执行的操作
+    pub action: TaskAction,
+    /// 自定义命令（运行命令操作使用）
+    #[serde(default)]
+    pub custom_command: Option<CustomCommand>,
+}
+
+impl RepeatableTask {
+    /// 从任务中取出可以重复的倒计时
+    /// 
+    /// 重复任务按计划执行，管理策略和其他电脑设置的任务不是用户自己设置的，都返回None
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 任务
+    pub fn from_task(task: &TaskData) -> Option<Self> {
+        if task.task_type != TaskType::Once || matches!(task.source, TaskSource::Policy | TaskSource::Remote) {
+            return None;
+        }
+        let seconds = (task.target_time? - task.created_at).num_seconds();
+        (seconds > 0).then(|| Self { seconds, action: task.action, custom_command: task.custom_command.clone() })
+    }
+    
+    /// 从当前时间开始按相同的时长和操作创建新任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    pub fn to_task(&self, now: DateTime<Local>) -> Result<TaskData> {
+        let mut task = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::seconds(self.seconds)), self.action, now)?;
+        task.custom_command = self.custom_command.clone();
+        Ok(task)
+    }
+}
+
+/// 一条历史记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// 发生时间
+    pub at: DateTime<Local>,
+    /// 任务标识，普通倒计时为空
+    pub task_id: Option<String>,
+    /// 任务到点后执行的操作
+    pub action: Option<TaskAction>,
+    /// 任务计划说明
+    pub schedule: String,
+    /// 事件
+    pub event: HistoryEvent,
+    /// 可以重复的倒计时，重复任务和旧版本的记录为空
+    #[serde(default)]
+    pub repeat: Option<RepeatableTask>,
+    /// 任务是怎样设置的（手动、命令行、远程关机等），普通倒计时和旧版本的记录为空
+    #[serde(default)]
+    pub source: Option<TaskSource>,
+}
+
+impl HistoryEntry {
+    /// 生成取消记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 被取消的任务，普通倒计时为None
+    /// * `now` - 当前时间
+    pub fn cancelled(task: Option<&TaskData>, now: DateTime<Local>) -> Self {
+        Self {
+            at: now,
+            task_id: task.map(|task| task.id.clone()),
+            action: task.map(|task| task.action),
+            schedule: task.map(|task| task.schedule_text()).unwrap_or_default(),
+            event: HistoryEvent::Cancelled { reason: None },
+            repeat: task.and_then(RepeatableTask::from_task),
+            source: task.map(|task| task.source),
+        }
+    }
+    
+    /// 生成系统结束会话的记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 还在倒计时的任务
+    /// * `now` - 当前时间
+    pub fn session_ended(task: &TaskData, now: DateTime<Local>) -> Self {
+        Self {
+            at: now,
+            task_id: Some(task.id.clone()),
+            action: Some(task.action),
+            schedule: task.schedule_text(),
+            event: HistoryEvent::SessionEnded,
+            repeat: RepeatableTask::from_task(task),
+            source: Some(task.source),
+        }
+    }
+    
+    /// 生成任务到点执行的记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 到点的任务
+    /// * `now` - 当前时间
+    pub fn executed(task: &TaskData, now: DateTime<Local>) -> Self {
+        Self {
+            at: now,
+            task_id: Some(task.id.clone()),
+            action: Some(task.action),
+            schedule: task.schedule_text(),
+            event: HistoryEvent::Executed,
+            repeat: RepeatableTask::from_task(task),
+            source: Some(task.source),
+        }
+    }
+    
+    /// 生成到点时取消执行的记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 到点的任务
+    /// * `reason` - 取消执行的原因
+    /// * `now` - 当前时间
+    pub fn skipped(task: &TaskData, reason: String, now: DateTime<Local>) -> Self {
+        Self {
+            event: HistoryEvent::Skipped { reason },
+            repeat: None,
+            ..Self::executed(task, now)
+        }
+    }
+    
+    /// 生成执行失败的记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 到点的任务
+    /// * `error` - 失败原因
+    /// * `now` - 当前时间
+    pub fn failed(task: &TaskData, error: String, now: DateTime<Local>) -> Self {
+        Self {
+            event: HistoryEvent::Failed { error },
+            repeat: None,
+            ..Self::executed(task, now)
+        }
+    }
+    
+    /// 在历史记录列表中显示的一行说明
+    /// 
+    /// 如"03-08 22:30 关机 已执行（手动设置，每日 22:30）"
+    pub fn summary(&self) -> String {
+        let action = self.action.map(|action| action.to_string()).unwrap_or_else(|| "倒计时".to_string());
+        let details: Vec<String> = self.source.map(|source| source.to_string()).into_iter()
+            .chain((!self.schedule.is_empty()).then(|| self.schedule.clone()))
+            .collect();
+        let mut summary = format!("{} {} {}", self.at.format("%m-%d %H:%M"), action, self.event);
+        if !details.is_empty() {
+            summary.push_str(&format!("（{}）", details.join("，")));
+        }
+        summary
+    }
+}
+
+/// 历史记录
+#[derive(Debug, Clone)]
+pub struct HistoryLog {
+    /// 保存历史记录的存储
+    storage: Arc<dyn Storage>,
+}
+
+impl HistoryLog {
+    /// 使用存储中的历史记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `storage` - 存储
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+    
+    /// 加载所有历史记录
+    /// 
+    /// # 返回值
+    /// 
+    /// 按时间先后排列的历史记录，没有记录时返回空列表
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        self.storage.load_history()
+    }
+    
+    /// 追加一条历史记录
+    /// 
+    /// 历史记录损坏时重新开始记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `entry` - 历史记录
+    pub fn append(&self, entry: HistoryEntry) -> Result<()> {
+        self.storage.append_history(entry)
+    }
+    
+    /// 查询一段时间内的历史记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `from` - 开始时间（包含）
+    /// * `to` - 结束时间（不包含）
+    /// 
+    /// # 返回值
+    /// 
+    /// 按时间先后排列的历史记录
+    pub fn between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<HistoryEntry>> {
+        self.storage.history_between(from, to)
+    }
+    
+    /// 为最近一次还没有原因的取消记录补充原因
+    /// 
+    /// # 参数
+    /// 
+    /// * `reason` - 取消原因
+    /// 
+    /// # 返回值
+    /// 
+    /// 找到并更新了取消记录时返回true
+    pub fn set_last_cancel_reason(&self, reason: CancelReason) -> Result<bool> {
+        let mut entries = self.load()?;
+        let last_cancel = entries.iter_mut().rev().find_map(|entry| match &mut entry.event {
+            HistoryEvent::Cancelled { reason } => Some(reason),
+            HistoryEvent::SessionEnded | HistoryEvent::Executed | HistoryEvent::Skipped { .. } | HistoryEvent::Failed { .. } => None,
+        });
+        
+        match last_cancel {
+            Some(slot @ None) => {
+                *slot = Some(reason);
+                self.storage.save_history(&entries)?;
+                info!("已记录取消原因: {}", reason);
+                Ok(true)
+            },
+            _ => Ok(false),
+        }
+    }
+    
+    /// 最近一次执行或取消的单次倒计时，用于"重复上次"
+    pub fn last_repeatable(&self) -> Result<Option<RepeatableTask>> {
+        Ok(self.load()?.into_iter().rev().find_map(|entry| entry.repeat))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::persistence::TaskPersistence;
+    use crate::core::types::{TaskType, TimeInput};
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_cancel_reason_recorded() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = HistoryLog::new(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        assert!(history.load().unwrap().is_empty());
+        assert!(!history.set_last_cancel_reason(CancelReason::Mistake).unwrap());
+        
+        let task = TaskData::from_time_input(
+            TaskType::Once,
+            TimeInput::Duration(chrono::Duration::minutes(30)),
+            TaskAction::Shutdown,
+            Local::now(),
+        ).unwrap();
+        history.append(HistoryEntry::cancelled(Some(&task), Local::now())).unwrap();
+        assert!(history.set_last_cancel_reason(CancelReason::StillUsing).unwrap());
+        
+        // 已有原因的记录不会被覆盖
+        assert!(!history.set_last_cancel_reason(CancelReason::Mistake).unwrap());
+        let entries = history.load().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].task_id, Some(task.id));
+        assert_eq!(entries[0].event, HistoryEvent::Cancelled { reason: Some(CancelReason::StillUsing) });
+    }
+    
+    #[test]
+    fn test_last_repeatable() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = HistoryLog::new(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        assert_eq!(history.last_repeatable().unwrap(), None);
+        
+        let created = Local::now() - chrono::Duration::hours(2);
+        let task = TaskData::from_time_input(
+            TaskType::Once,
+            TimeInput::Duration(chrono::Duration::minutes(45)),
+            TaskAction::Sleep,
+            created,
+        ).unwrap();
+        history.append(HistoryEntry::executed(&task, Local::now())).unwrap();
+        // 重复任务和普通倒计时不会覆盖可以重复的记录
+        let daily = TaskData::from_time_input(
+            TaskType::Daily,
+            TimeInput::DailyTime(chrono::NaiveTime::from_hms_opt(22, 0, 0).unwrap()),
+            TaskAction::Shutdown,
+            created,
+        ).unwrap();
+        history.append(HistoryEntry::cancelled(Some(&daily), Local::now())).unwrap();
+        history.append(HistoryEntry::cancelled(None, Local::now())).unwrap();
+        
+        let repeat = history.last_repeatable().unwrap().unwrap();
+        assert_eq!(repeat.seconds, 45 * 60);
+        assert_eq!(repeat.action, TaskAction::Sleep);
+        
+        // 从现在重新计算到点时间
+        let now = Local::now();
+        let repeated = repeat.to_task(now).unwrap();
+        assert_eq!(repeated.target_time, Some(now + chrono::Duration::minutes(45)));
+        assert_ne!(repeated.id, task.id);
+    }
+    
+    #[test]
+    fn test_failed_and_skipped_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = HistoryLog::new(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        let now = Local::now();
+        let mut task = TaskData::from_time_input(
+            TaskType::Once,
+            TimeInput::Duration(chrono::Duration::minutes(30)),
+            TaskAction::Restart,
+            now,
+        ).unwrap();
+        task.source = TaskSource::Remote;
+        history.append(HistoryEntry::skipped(&task, "电量过低".to_string(), now - chrono::Duration::days(2))).unwrap();
+        history.append(HistoryEntry::failed(&task, "权限不足".to_string(), now)).unwrap();
+        
+        let recent = history.between(now - chrono::Duration::days(1), now + chrono::Duration::seconds(1)).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].event, HistoryEvent::Failed { error: "权限不足".to_string() });
+        assert_eq!(recent[0].repeat, None);
+        let summary = recent[0].summary();
+        assert!(summary.contains("重启 执行失败: 权限不足（远程关机，"));
+        
+        // 旧版本的记录没有来源
+        let legacy: HistoryEntry = serde_json::from_str(&format!(
+            r#"{{"at":"{}","task_id":null,"action":null,"schedule":"","event":{{"kind":"Cancelled"}}}}"#,
+            now.to_rfc3339(),
+        )).unwrap();
+        assert_eq!(legacy.source, None);
+        assert!(legacy.summary().ends_with("倒计时 已取消"));
+    }
+    
+    #[test]
+    fn test_history_is_capped() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = HistoryLog::new(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        for _ in 0..MAX_HISTORY_ENTRIES + 3 {
+            history.append(HistoryEntry::cancelled(None, Local::now())).unwrap();
+        }
+        assert_eq!(history.load().unwrap().len(), MAX_HISTORY_ENTRIES);
+    }
+}
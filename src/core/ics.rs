@@ -0,0 +1,449 @@
+//! 日历导入模块
+//! 
+//! 从iCalendar（.ics）文件或网址读取事件，把标题包含关键字（如"关机"）的事件导入为单次任务，
+//! 在家庭日历里安排好哪天几点关机即可。标题中写了其他操作（如"睡眠"）时按该操作执行；
+//! 关键字前紧跟"不"或"别"的事件（如"今晚不关机"）不算匹配；
+//! 重复事件、全天事件、已取消和已过去的事件不导入
+//! 
+//! 导入的任务标识由事件的UID生成，再次导入同一日历时更新原来的任务而不是重复添加
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use log::info;
+
+use crate::core::template;
+use crate::core::types::{resolve_local_datetime, TaskAction, TaskData, TaskSource, TaskType, TimeInput};
+
+/// 默认匹配的事件标题关键字
+pub const DEFAULT_KEYWORD: &str = "关机";
+
+/// 从日历导入的任务标识前缀
+pub const TASK_ID_PREFIX: &str = "calendar-";
+
+/// 出现在关键字前不远处时表示否定的词
+const NEGATIONS: [&str; 6] = ["不要", "不用", "无需", "取消", "不", "别"];
+
+/// 在关键字前多少个字以内查找否定词，遇到标点或空白时停止
+const NEGATION_WINDOW: usize = 4;
+
+/// 日历中的一个事件
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IcsEvent {
+    /// 事件标识
+    pub uid: Option<String>,
+    /// 事件标题
+    pub summary: String,
+    /// 开始时间，全天事件和无法识别的时间为None
+    pub start: Option<DateTime<Local>>,
+    /// 是否为全天事件
+    pub all_day: bool,
+    /// 是否为重复事件或重复事件中的一次
+    pub recurring: bool,
+    /// 是否已取消
+    pub cancelled: bool,
+}
+
+/// 解析iCalendar文本中的所有事件
+/// 
+/// 只读取事件本身的属性，事件中的提醒（VALARM）等子组件被忽略。
+/// 带TZID的时间按本地时间处理，以Z结尾的UTC时间转换为本地时间
+/// 
+/// # 参数
+/// 
+/// * `text` - iCalendar文本
+pub fn parse_events(text: &str) -> Result<Vec<IcsEvent>> {
+    let lines = unfold(text);
+    if !lines.iter().any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(anyhow!("不是iCalendar日历文件"));
+    }
+    
+    let mut events = Vec::new();
+    let mut current: Option<IcsEvent> = None;
+    // 当前事件中子组件的层数
+    let mut depth = 0usize;
+    for line in &lines {
+        let Some((name, params, value)) = split_property(line) else {
+            continue;
+        };
+        let Some(event) = current.as_mut() else {
+            if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+                current = Some(IcsEvent::default());
+                depth = 0;
+            }
+            continue;
+        };
+        match name.as_str() {
+            "BEGIN" => depth += 1,
+            "END" if depth > 0 => depth -= 1,
+            "END" => {
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    events.extend(current.take());
+                }
+            },
+            _ if depth > 0 => {},
+            "UID" => event.uid = Some(value.trim().to_string()).filter(|uid| !uid.is_empty()),
+            "SUMMARY" => event.summary = unescape(&value),
+            "DTSTART" => {
+                let date_only = params.iter().any(|(key, value)| key == "VALUE" && value.eq_ignore_ascii_case("DATE"));
+                event.all_day = date_only || !value.contains('T');
+                event.start = if event.all_day { None } else { parse_date_time(value.trim()) };
+            },
+            "RRULE" | "RDATE" | "RECURRENCE-ID" => event.recurring = true,
+            "STATUS" => event.cancelled = value.trim().eq_ignore_ascii_case("CANCELLED"),
+            _ => {},
+        }
+    }
+    Ok(events)
+}
+
+/// 展开折叠的行，以空格或制表符开头的行接在上一行后面
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)) {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// 拆分属性行为名称、参数和值
+/// 
+/// 名称转为大写，参数值两边的引号被去掉；引号中的冒号和分号不作为分隔符
+fn split_property(line: &str) -> Option<(String, Vec<(String, String)>, String)> {
+    let mut quoted = false;
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut value_start = None;
+    for (index, ch) in line.char_indices() {
+        match ch {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                parts.push(&line[start..index]);
+                start = index + 1;
+            },
+            ':' if !quoted => {
+                parts.push(&line[start..index]);
+                value_start = Some(index + 1);
+                break;
+            },
+            _ => {},
+        }
+    }
+    
+    let value = line[value_start?..].to_string();
+    let name = parts.first()?.trim().to_uppercase();
+    let params = parts[1..].iter()
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.trim().to_uppercase(), value.trim().trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value))
+}
+
+/// 解析日期时间，如"20261016T220000"或UTC时间"20261016T140000Z"
+/// 
+/// 本地时间在夏令时切换时不存在时顺延，重复时取较早的一次
+fn parse_date_time(value: &str) -> Option<DateTime<Local>> {
+    match value.strip_suffix(['Z', 'z']) {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()
+            .map(|time| Utc.from_utc_datetime(&time).with_timezone(&Local)),
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()
+            .and_then(|time| resolve_local_datetime(time).ok()),
+    }
+}
+
+/// 还原文本中转义的特殊字符
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+    result.trim().to_string()
+}
+
+/// 从日历生成的待导入任务
+#[derive(Debug, Clone, Default)]
+pub struct CalendarImport {
+    /// 匹配事件标题的关键字
+    pub keyword: String,
+    /// 可以导入的单次任务
+    pub tasks: Vec<TaskData>,
+    /// 标题匹配但无法导入的事件及原因
+    pub skipped: Vec<String>,
+}
+
+impl CalendarImport {
+    /// 把标题包含关键字的事件转换为单次任务
+    /// 
+    /// 关键字不区分大小写，被否定的关键字（如"不关机"）不算匹配
+    /// 
+    /// # 参数
+    /// 
+    /// * `events` - 日历中的事件
+    /// * `keyword` - 事件标题中的关键字
+    /// * `now` - 当前时间，已过去的事件不导入
+    pub fn from_events(events: &[IcsEvent], keyword: &str, now: DateTime<Local>) -> Self {
+        let keyword = keyword.trim();
+        let lowercase_keyword = keyword.to_lowercase();
+        let mut import = Self { keyword: keyword.to_string(), ..Self::default() };
+        
+        for event in events.iter().filter(|event| mentions(&event.summary.to_lowercase(), &lowercase_keyword)) {
+            let when = event.start.map(|time| time.format("%m-%d %H:%M ").to_string()).unwrap_or_default();
+            let task = match Self::build_task(event, now) {
+                Ok(task) => task,
+                Err(e) => {
+                    import.skipped.push(format!("{}{}: {}", when, event.summary, e));
+                    continue;
+                }
+            };
+            match import.tasks.iter_mut().find(|existing| existing.id == task.id) {
+                Some(existing) => *existing = task,
+                None => import.tasks.push(task),
+            }
+        }
+        import
+    }
+    
+    /// 将事件转换为单次任务
+    fn build_task(event: &IcsEvent, now: DateTime<Local>) -> Result<TaskData> {
+        if event.cancelled {
+            return Err(anyhow!("事件已取消"));
+        }
+        if event.recurring {
+            return Err(anyhow!("不支持重复事件"));
+        }
+        if event.all_day {
+            return Err(anyhow!("全天事件没有具体时间"));
+        }
+        let start = event.start.ok_or_else(|| anyhow!("无法识别开始时间"))?;
+        if start <= now {
+            return Err(anyhow!("时间已过"));
+        }
+        
+        let mut task = TaskData::from_time_input(TaskType::Once, TimeInput::AbsoluteTime(start), action_in(&event.summary), now)?;
+        task.id = task_id(event.uid.as_deref(), start);
+        task.source = TaskSource::Calendar;
+        Ok(task)
+    }
+    
+    /// 生成可显示给用户的预览文本
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("标题包含\"{}\"的事件: {}个任务", self.keyword, self.tasks.len())];
+        for task in &self.tasks {
+            let when = task.target_time
+                .map(|time| time.format("%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            lines.push(format!("  {} {}", when, task.action));
+        }
+        for skipped in &self.skipped {
+            lines.push(format!("  已跳过 {}", skipped));
+        }
+        lines.join("\n")
+    }
+    
+    /// 是否有可导入的任务
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+    
+    /// 合并到已有的任务中
+    /// 
+    /// 之前从同一事件导入的任务被更新，保留原任务的启用状态和统计
+    /// 
+    /// # 参数
+    /// 
+    /// * `existing` - 当前的所有任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回合并后的任务、新增的任务数和更新的任务数
+    pub fn merge(&self, existing: &[TaskData]) -> (Vec<TaskData>, usize, usize) {
+        let mut tasks = existing.to_vec();
+        let (mut added, mut updated) = (0, 0);
+        for task in &self.tasks {
+            match tasks.iter_mut().find(|existing| existing.id == task.id) {
+                Some(existing) => {
+                    *existing = TaskData { enabled: existing.enabled, stats: existing.stats.clone(), ..task.clone() };
+                    updated += 1;
+                },
+                None => {
+                    tasks.push(task.clone());
+                    added += 1;
+                }
+            }
+        }
+        (tasks, added, updated)
+    }
+}
+
+/// 标题中是否有未被否定的关键字
+/// 
+/// 关键字前同一分句的几个字以内有"不要"、"不用"、"别"、"无需"、"取消"等否定词时视为否定，
+/// 如"今晚不要关机"不包含"关机"
+fn mentions(summary: &str, keyword: &str) -> bool {
+    summary.match_indices(keyword).any(|(index, _)| {
+        let mut before: Vec<char> = summary[..index].chars().rev()
+            .take_while(|ch| !ch.is_whitespace() && !ch.is_ascii_punctuation() && !"，。；：！？、".contains(*ch))
+            .take(NEGATION_WINDOW)
+            .collect();
+        before.reverse();
+        let before: String = before.into_iter().collect();
+        !NEGATIONS.iter().any(|negation| before.contains(negation))
+    })
+}
+
+/// 事件标题中写明的操作，没有写明时关机
+/// 
+/// 自定义命令需要在设置中填写，不从日历导入
+fn action_in(summary: &str) -> TaskAction {
+    TaskAction::ALL.into_iter()
+        .filter(|action| *action != TaskAction::RunCommand)
+        .find(|action| summary.contains(&action.to_string()))
+        .unwrap_or(TaskAction::Shutdown)
+}
+
+/// 由事件标识生成任务标识，没有标识时使用开始时间
+/// 
+/// 任务标识会用作系统计划任务的名称，字母和数字以外的字符替换为"-"
+fn task_id(uid: Option<&str>, start: DateTime<Local>) -> String {
+    let key = match uid {
+        Some(uid) => uid.chars().map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' }).collect(),
+        None => start.format("%Y%m%dT%H%M%S").to_string(),
+    };
+    format!("{}{}", TASK_ID_PREFIX, key)
+}
+
+/// 从文件或网址读取日历并生成待导入的任务
+/// 
+/// # 参数
+/// 
+/// * `source` - .ics文件路径或网址
+/// * `keyword` - 事件标题中的关键字
+pub async fn load_calendar_import(source: &str, keyword: &str) -> Result<CalendarImport> {
+    if keyword.trim().is_empty() {
+        return Err(anyhow!("请输入事件标题中的关键字"));
+    }
+    let text = template::read_source(source, "日历").await?;
+    let events = parse_events(&text)?;
+    let import = CalendarImport::from_events(&events, keyword, Local::now());
+    info!("已读取日历: {}个事件，{}个可导入", events.len(), import.tasks.len());
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+    
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+        VERSION:2.0\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:exam-1@school.example\r\n\
+        DTSTART;TZID=\"Asia/Shanghai\":20261016T223000\r\n\
+        SUMMARY:考试前早点关机\\, 早\r\n \
+        点休息\r\n\
+        BEGIN:VALARM\r\n\
+        SUMMARY:提醒\r\n\
+        TRIGGER:-PT10M\r\n\
+        END:VALARM\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:night\r\n\
+        DTSTART:20261017T140000Z\r\n\
+        SUMMARY:电脑睡眠\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART;VALUE=DATE:20261018\r\n\
+        SUMMARY:国庆不关机\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        DTSTART:20261019T220000\r\n\
+        RRULE:FREQ=WEEKLY\r\n\
+        SUMMARY:每周关机\r\n\
+        END:VEVENT\r\n\
+        BEGIN:VEVENT\r\n\
+        UID:stay-on\r\n\
+        DTSTART:20261016T230000\r\n\
+        SUMMARY:今晚不关机\r\n\
+        END:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+    
+    #[test]
+    fn test_parse_events() {
+        let events = parse_events(CALENDAR).unwrap();
+        assert_eq!(events.len(), 5);
+        
+        // 折叠行被展开，转义字符被还原，提醒中的标题被忽略
+        assert_eq!(events[0].summary, "考试前早点关机, 早点休息");
+        assert_eq!(events[0].uid.as_deref(), Some("exam-1@school.example"));
+        let local = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(22, 30, 0).unwrap();
+        assert_eq!(events[0].start, resolve_local_datetime(local).ok());
+        
+        // UTC时间转换为本地时间
+        let utc = Utc.with_ymd_and_hms(2026, 10, 17, 14, 0, 0).unwrap();
+        assert_eq!(events[1].start, Some(utc.with_timezone(&Local)));
+        
+        assert!(events[2].all_day && events[2].start.is_none());
+        assert!(events[3].recurring);
+        assert!(parse_events("BEGIN:VEVENT\nEND:VEVENT").is_err());
+    }
+    
+    #[test]
+    fn test_calendar_import() {
+        let events = parse_events(CALENDAR).unwrap();
+        let now = resolve_local_datetime(NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(12, 0, 0).unwrap()).unwrap();
+        
+        let import = CalendarImport::from_events(&events, "关机", now);
+        assert_eq!(import.tasks.len(), 1);
+        assert_eq!(import.tasks[0].id, "calendar-exam-1-school-example");
+        assert_eq!(import.tasks[0].action, TaskAction::Shutdown);
+        assert_eq!(import.tasks[0].source, TaskSource::Calendar);
+        // 重复事件被跳过；否定关键字的事件（全天的"国庆不关机"和定时的"今晚不关机"）不算匹配
+        assert_eq!(import.skipped.len(), 1);
+        assert!(import.summary().contains("已跳过"));
+        assert!(!import.summary().contains("不关机"));
+        assert!(!mentions("今晚不关机", "关机"));
+        assert!(!mentions("今晚不要关机", "关机"));
+        assert!(!mentions("不用关机", "关机"));
+        assert!(!mentions("考试周无需关机", "关机"));
+        assert!(!mentions("取消今晚关机", "关机"));
+        assert!(!mentions("不要再关机", "关机"));
+        assert!(mentions("别关机了，今晚十点再关机", "关机"));
+        assert!(mentions("不要熬夜 22:00关机", "关机"));
+        
+        // 标题中写明的操作
+        let import = CalendarImport::from_events(&events, "电脑", now);
+        assert_eq!(import.tasks[0].action, TaskAction::Sleep);
+        
+        // 已过去的事件不导入
+        let import = CalendarImport::from_events(&events, "关机", now + Duration::days(2));
+        assert!(import.is_empty());
+    }
+    
+    #[test]
+    fn test_merge_updates_imported_tasks() {
+        let events = parse_events(CALENDAR).unwrap();
+        let now = resolve_local_datetime(NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(12, 0, 0).unwrap()).unwrap();
+        let import = CalendarImport::from_events(&events, "关机", now);
+        
+        let manual = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::hours(1)), TaskAction::Lock, now).unwrap();
+        let (mut tasks, added, updated) = import.merge(&[manual]);
+        assert_eq!((tasks.len(), added, updated), (2, 1, 0));
+        
+        // 再次导入时更新原来的任务，保留停用状态
+        tasks[1].enabled = false;
+        let (tasks, added, updated) = import.merge(&tasks);
+        assert_eq!((tasks.len(), added, updated), (2, 0, 1));
+        assert!(!tasks[1].enabled);
+    }
+}
@@ -0,0 +1,281 @@
+//! 时间解析语言包模块
+//! 
+//! 时间解析器使用的数字、时间单位、时间描述词、星期、日期标记、节日和自然语言短语都来自语言包，
+//! 内置的语言包编译进程序，用户可以在配置目录的`locales`文件夹中放置同名文件覆盖或补充，
+//! 增加日语、粤语等说法不需要修改代码
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// 默认语言包名称
+pub const DEFAULT_LOCALE: &str = "zh-CN";
+
+/// 用户语言包所在的文件夹名称，位于配置目录中
+pub const LOCALE_DIR: &str = "locales";
+
+/// 内置语言包
+const EMBEDDED_LOCALES: &[(&str, &str)] = &[
+    (DEFAULT_LOCALE, include_str!("locales/zh-CN.json")),
+];
+
+/// 自然语言短语的含义
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum PhraseMeaning {
+    /// 若干分钟后，如"半小时后"
+    After {
+        /// 分钟数
+        minutes: i64,
+    },
+    /// 若干天后的某个整点，如"明天"
+    OnDay {
+        /// 距离今天的天数
+        days: i64,
+        /// 小时
+        hour: u32,
+    },
+    /// 接下来的某个整点，今天已过时为明天，如"今晚"
+    Upcoming {
+        /// 小时
+        hour: u32,
+    },
+}
+
+/// 自然语言短语
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Phrase {
+    /// 表示同一含义的说法
+    pub texts: Vec<String>,
+    /// 含义
+    #[serde(flatten)]
+    pub meaning: PhraseMeaning,
+}
+
+/// 时间解析语言包
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalePack {
+    /// 数字写法，如"三十" -> 30
+    pub numbers: HashMap<String, u32>,
+    /// 时间单位及对应的秒数，如"分钟" -> 60
+    pub units: HashMap<String, i64>,
+    /// 时间描述词及对应的基准小时，如"晚上" -> 20；基准小时同时决定描述词后的12小时制时间是上午还是下午
+    pub descriptors: HashMap<String, i32>,
+    /// 每日时间的前缀，如"每天"
+    pub daily_prefixes: Vec<String>,
+    /// 星期的写法及对应的星期（1为周一，7为周日），如"五" -> 5、"天" -> 7
+    pub weekdays: HashMap<String, u32>,
+    /// 写在星期前面的词，如"周"、"星期"
+    pub weekday_prefixes: Vec<String>,
+    /// 写在星期前面表示哪一周的词及距离本周的周数，如"下" -> 1、"本" -> 0
+    pub week_offsets: HashMap<String, u32>,
+    /// 每周时间的前缀及包含的星期，如"工作日" -> [1, 2, 3, 4, 5]；为空时星期写在前缀后面，如"每周"
+    pub weekly_prefixes: HashMap<String, Vec<u32>>,
+    /// 星期范围中间的连接词，如"到"
+    pub range_words: Vec<String>,
+    /// 日期中年份后面的标记，如"年"
+    pub year_markers: Vec<String>,
+    /// 日期中月份后面的标记，如"月"
+    pub month_markers: Vec<String>,
+    /// 日期中日后面的标记，如"日"、"号"
+    pub day_markers: Vec<String>,
+    /// 公历日期固定的节日及对应的月和日，如"元旦" -> [1, 1]
+    pub holidays: HashMap<String, [u32; 2]>,
+    /// 自然语言短语，前面的优先
+    pub phrases: Vec<Phrase>,
+}
+
+impl LocalePack {
+    /// 内置的默认语言包
+    pub fn builtin() -> Self {
+        Self::embedded(DEFAULT_LOCALE).unwrap_or_default()
+    }
+    
+    /// 获取内置语言包
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 语言包名称，如"zh-CN"
+    /// 
+    /// # 返回值
+    /// 
+    /// 没有该名称的内置语言包时返回None
+    pub fn embedded(name: &str) -> Option<Self> {
+        let (_, json_data) = EMBEDDED_LOCALES.iter().find(|(locale, _)| locale.eq_ignore_ascii_case(name))?;
+        // 内置语言包在测试中校验，这里不会失败
+        Self::from_json(json_data).ok()
+    }
+    
+    /// 从JSON解析语言包
+    /// 
+    /// # 参数
+    /// 
+    /// * `json_data` - JSON文本，未出现的字段为空
+    pub fn from_json(json_data: &str) -> Result<Self> {
+        serde_json::from_str(json_data).map_err(|e| anyhow!("语言包格式错误: {}", e))
+    }
+    
+    /// 按顺序加载并合并多个语言包
+    /// 
+    /// 每个名称先取内置语言包，再用用户文件夹中的同名文件（如`zh-CN.json`）覆盖或补充，
+    /// 后面的语言包覆盖前面的同名条目
+    /// 
+    /// # 参数
+    /// 
+    /// * `names` - 语言包名称列表
+    /// * `user_dir` - 用户语言包所在的文件夹，为空时只使用内置语言包
+    /// 
+    /// # 返回值
+    /// 
+    /// 合并后的语言包，某个名称既没有内置语言包也没有用户文件时返回错误
+    pub fn load(names: &[String], user_dir: Option<&Path>) -> Result<Self> {
+        let mut pack = Self::default();
+        for name in names {
+            let embedded = Self::embedded(name);
+            let user_file = user_dir.map(|dir| dir.join(format!("{}.json", name))).filter(|path| path.exists());
+            if embedded.is_none() && user_file.is_none() {
+                return Err(anyhow!("找不到语言包: {}", name));
+            }
+            
+            if let Some(embedded) = embedded {
+                pack.merge(embedded);
+            }
+            if let Some(path) = user_file {
+                let json_data = fs::read_to_string(&path)
+                    .map_err(|e| anyhow!("读取语言包失败 {:?}: {}", path, e))?;
+                pack.merge(Self::from_json(&json_data).map_err(|e| anyhow!("{:?}: {}", path, e))?);
+                info!("已加载用户语言包: {:?}", path);
+            }
+        }
+        Ok(pack)
+    }
+    
+    /// 合并另一个语言包，同名条目以另一个语言包为准
+    /// 
+    /// # 参数
+    /// 
+    /// * `other` - 要合并的语言包
+    pub fn merge(&mut self, other: LocalePack) {
+        /// 追加还没有的词
+        fn extend_unique(words: &mut Vec<String>, other: Vec<String>) {
+            for word in other {
+                if !words.contains(&word) {
+                    words.push(word);
+                }
+            }
+        }
+        
+        self.numbers.extend(other.numbers);
+        self.units.extend(other.units);
+        self.descriptors.extend(other.descriptors);
+        self.holidays.extend(other.holidays);
+        self.weekdays.extend(other.weekdays);
+        self.week_offsets.extend(other.week_offsets);
+        self.weekly_prefixes.extend(other.weekly_prefixes);
+        extend_unique(&mut self.daily_prefixes, other.daily_prefixes);
+        extend_unique(&mut self.weekday_prefixes, other.weekday_prefixes);
+        extend_unique(&mut self.range_words, other.range_words);
+        extend_unique(&mut self.year_markers, other.year_markers);
+        extend_unique(&mut self.month_markers, other.month_markers);
+        extend_unique(&mut self.day_markers, other.day_markers);
+        let mut phrases = other.phrases;
+        phrases.append(&mut self.phrases);
+        self.phrases = phrases;
+    }
+    
+    /// 查找短语的含义
+    /// 
+    /// # 参数
+    /// 
+    /// * `text` - 完整的输入
+    pub fn phrase(&self, text: &str) -> Option<&PhraseMeaning> {
+        self.phrases.iter()
+            .find(|phrase| phrase.texts.iter().any(|candidate| candidate == text))
+            .map(|phrase| &phrase.meaning)
+    }
+    
+    /// 按长度降序排列的数字写法，替换时先替换长的，避免部分匹配
+    pub fn numbers_longest_first(&self) -> Vec<(&str, u32)> {
+        let mut numbers: Vec<(&str, u32)> = self.numbers.iter().map(|(text, number)| (text.as_str(), *number)).collect();
+        numbers.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(b.0)));
+        numbers
+    }
+}
+
+/// 生成匹配任意一个词的正则表达式分组内容
+/// 
+/// 长的词排在前面，避免先匹配到较短的前缀
+/// 
+/// # 参数
+/// 
+/// * `words` - 要匹配的词
+pub fn alternation<'a>(words: impl IntoIterator<Item = &'a String>) -> String {
+    let mut words: Vec<&String> = words.into_iter().filter(|word| !word.is_empty()).collect();
+    words.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then_with(|| a.cmp(b)));
+    words.dedup();
+    words.into_iter().map(|word| regex::escape(word)).collect::<Vec<_>>().join("|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_builtin_locale() {
+        for (name, json_data) in EMBEDDED_LOCALES {
+            assert!(LocalePack::from_json(json_data).is_ok(), "内置语言包格式错误: {}", name);
+        }
+        
+        let pack = LocalePack::builtin();
+        assert_eq!(pack.numbers.get("三十"), Some(&30));
+        assert_eq!(pack.units.get("分钟"), Some(&60));
+        assert_eq!(pack.descriptors.get("晚上"), Some(&20));
+        assert_eq!(pack.holidays.get("元旦"), Some(&[1, 1]));
+        assert_eq!(pack.weekdays.get("天"), Some(&7));
+        assert_eq!(pack.week_offsets.get("下个"), Some(&1));
+        assert_eq!(pack.weekly_prefixes.get("周末"), Some(&vec![6, 7]));
+        assert_eq!(pack.day_markers, vec!["日".to_string(), "号".to_string()]);
+        assert_eq!(pack.phrase("明早"), Some(&PhraseMeaning::OnDay { days: 1, hour: 7 }));
+        assert_eq!(pack.phrase("今晚"), Some(&PhraseMeaning::Upcoming { hour: 20 }));
+        assert_eq!(pack.phrase("随便"), None);
+        assert_eq!(pack.numbers_longest_first()[0].0.chars().count(), 3);
+    }
+    
+    #[test]
+    fn test_user_locale_overrides_builtin() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("yue.json"), r#"{
+            "numbers": { "廿": 20 },
+            "descriptors": { "夜晚": 20 },
+            "phrases": [
+                { "texts": ["今晚"], "kind": "Upcoming", "hour": 21 },
+                { "texts": ["听日"], "kind": "OnDay", "days": 1, "hour": 9 }
+            ]
+        }"#).unwrap();
+        
+        let names = vec![DEFAULT_LOCALE.to_string(), "yue".to_string()];
+        let pack = LocalePack::load(&names, Some(temp_dir.path())).unwrap();
+        assert_eq!(pack.numbers.get("廿"), Some(&20));
+        assert_eq!(pack.numbers.get("三十"), Some(&30));
+        assert_eq!(pack.phrase("听日"), Some(&PhraseMeaning::OnDay { days: 1, hour: 9 }));
+        assert_eq!(pack.phrase("今晚"), Some(&PhraseMeaning::Upcoming { hour: 21 }));
+        assert_eq!(pack.daily_prefixes, vec!["每天".to_string(), "每日".to_string()]);
+        
+        assert!(LocalePack::load(&["ja".to_string()], Some(temp_dir.path())).is_err());
+        
+        fs::write(temp_dir.path().join("ja.json"), "not json").unwrap();
+        assert!(LocalePack::load(&["ja".to_string()], Some(temp_dir.path())).is_err());
+    }
+    
+    #[test]
+    fn test_alternation() {
+        let words = vec!["分".to_string(), "分钟".to_string(), "a.b".to_string(), String::new()];
+        assert_eq!(alternation(&words), r"a\.b|分钟|分");
+    }
+}
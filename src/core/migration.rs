@@ -0,0 +1,180 @@
+//! 数据格式迁移模块
+//! 
+//! 任务文件和配置文件保存时写入格式版本号`schema_version`。加载时先按版本号把旧格式的JSON
+//! 逐步转换为当前格式再反序列化，`TaskData`或`AppConfig`的格式变化后不会因为无法解析而丢弃用户数据。
+//! 更新版本的程序保存的文件不加载，由调用者避免用默认数据覆盖
+//! 
+//! 修改保存的格式时在对应数据的迁移步骤列表末尾添加一个步骤，版本号随之加一
+
+use anyhow::{anyhow, Result};
+use log::info;
+use serde_json::{json, Value};
+
+/// 文件中记录格式版本号的字段名，没有该字段的文件为版本0
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// 迁移步骤，把上一个版本的数据转换为下一个版本
+type Migration = fn(Value) -> Result<Value>;
+
+/// 保存的数据种类，每种数据各自编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// 任务文件
+    Tasks,
+    /// 配置文件
+    Config,
+}
+
+impl SchemaKind {
+    /// 当前的格式版本号
+    pub fn current_version(self) -> u32 {
+        self.migrations().len() as u32
+    }
+    
+    /// 按版本顺序排列的迁移步骤，第n个步骤把版本n转换为版本n+1
+    fn migrations(self) -> &'static [Migration] {
+        match self {
+            SchemaKind::Tasks => &[tasks_v0_to_v1],
+            SchemaKind::Config => &[config_v0_to_v1],
+        }
+    }
+    
+    /// 在日志和错误信息中显示的名称
+    fn name(self) -> &'static str {
+        match self {
+            SchemaKind::Tasks => "任务文件",
+            SchemaKind::Config => "配置文件",
+        }
+    }
+}
+
+/// 读取数据的格式版本号
+/// 
+/// # 参数
+/// 
+/// * `value` - 文件内容，没有版本号字段时为版本0
+pub fn schema_version(value: &Value) -> Result<u32> {
+    match value.get(SCHEMA_VERSION_KEY) {
+        None => Ok(0),
+        Some(version) => version.as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("格式版本号无效: {}", version)),
+    }
+}
+
+/// 检查数据是否能被当前程序读取
+/// 
+/// # 参数
+/// 
+/// * `kind` - 数据种类
+/// * `value` - 文件内容
+/// 
+/// # 返回值
+/// 
+/// 返回数据的格式版本号，由更新版本的程序保存时返回错误
+pub fn check_supported(kind: SchemaKind, value: &Value) -> Result<u32> {
+    let version = schema_version(value)?;
+    let current = kind.current_version();
+    if version > current {
+        return Err(anyhow!(
+            "{}由更新版本的QtShut保存（格式版本{}，当前程序支持到版本{}），请升级程序后再使用",
+            kind.name(), version, current,
+        ));
+    }
+    Ok(version)
+}
+
+/// 把数据迁移到当前格式
+/// 
+/// # 参数
+/// 
+/// * `kind` - 数据种类
+/// * `value` - 文件内容
+/// 
+/// # 返回值
+/// 
+/// 返回带有当前版本号的数据，版本号无效、由更新版本的程序保存或迁移失败时返回错误
+pub fn migrate(kind: SchemaKind, mut value: Value) -> Result<Value> {
+    let version = check_supported(kind, &value)?;
+    for (from, migration) in kind.migrations().iter().enumerate().skip(version as usize) {
+        value = migration(value)
+            .and_then(|value| with_version(value, from as u32 + 1))
+            .map_err(|e| anyhow!("{}从格式版本{}迁移失败: {}", kind.name(), from, e))?;
+    }
+    
+    let current = kind.current_version();
+    if version < current {
+        info!("{}已从格式版本{}迁移到{}", kind.name(), version, current);
+    }
+    Ok(value)
+}
+
+/// 为要保存的数据写入当前版本号
+/// 
+/// # 参数
+/// 
+/// * `kind` - 数据种类
+/// * `value` - 要保存的数据，需要是JSON对象
+pub fn stamp(kind: SchemaKind, value: Value) -> Result<Value> {
+    with_version(value, kind.current_version())
+}
+
+/// 设置数据的版本号字段
+fn with_version(mut value: Value, version: u32) -> Result<Value> {
+    let map = value.as_object_mut().ok_or_else(|| anyhow!("数据不是JSON对象"))?;
+    map.insert(SCHEMA_VERSION_KEY.to_string(), json!(version));
+    Ok(value)
+}
+
+/// 任务文件版本0到1：任务数组或单个任务（更早的格式）改为放在`tasks`字段中
+fn tasks_v0_to_v1(value: Value) -> Result<Value> {
+    let tasks = match value {
+        Value::Array(tasks) => tasks,
+        Value::Object(_) => vec![value],
+        other => return Err(anyhow!("不是任务列表: {}", other)),
+    };
+    Ok(json!({ "tasks": tasks }))
+}
+
+/// 配置文件版本0到1：内容不变，只增加版本号
+fn config_v0_to_v1(value: Value) -> Result<Value> {
+    if !value.is_object() {
+        return Err(anyhow!("配置不是JSON对象"));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_migrate_legacy_tasks() {
+        let legacy = json!([{ "task_type": "Once" }, { "task_type": "Daily" }]);
+        let migrated = migrate(SchemaKind::Tasks, legacy).unwrap();
+        assert_eq!(schema_version(&migrated).unwrap(), SchemaKind::Tasks.current_version());
+        assert_eq!(migrated["tasks"].as_array().unwrap().len(), 2);
+        
+        // 更早的单个任务格式
+        let single = migrate(SchemaKind::Tasks, json!({ "task_type": "Once" })).unwrap();
+        assert_eq!(single["tasks"][0]["task_type"], "Once");
+        
+        // 当前格式不再转换
+        assert_eq!(migrate(SchemaKind::Tasks, migrated.clone()).unwrap(), migrated);
+        assert!(migrate(SchemaKind::Tasks, json!("tasks")).is_err());
+    }
+    
+    #[test]
+    fn test_stamp_and_version_checks() {
+        let config = stamp(SchemaKind::Config, json!({ "app": {} })).unwrap();
+        assert_eq!(config[SCHEMA_VERSION_KEY], SchemaKind::Config.current_version());
+        assert_eq!(migrate(SchemaKind::Config, json!({ "app": {} })).unwrap(), config);
+        assert!(stamp(SchemaKind::Config, json!([])).is_err());
+        
+        // 更新版本保存的文件不能读取
+        let newer = json!({ "schema_version": SchemaKind::Config.current_version() + 1 });
+        assert!(check_supported(SchemaKind::Config, &newer).unwrap_err().to_string().contains("更新版本"));
+        assert!(migrate(SchemaKind::Config, newer).is_err());
+        assert!(schema_version(&json!({ "schema_version": "1" })).is_err());
+    }
+}
@@ -2,11 +2,37 @@
 //! 
 //! 包含应用程序的所有核心功能实现
 
+pub mod autostart;
+pub mod battery;
+pub mod bundle;
+pub mod calendar;
+pub mod condition;
 pub mod countdown;
+pub mod cron;
+pub mod history;
+pub mod ics;
+pub mod locale;
+pub mod migration;
 pub mod persistence;
+pub mod plan;
+pub mod recorder;
+pub mod remote;
+pub mod routine;
+pub mod schedule;
+pub mod scheduler;
+pub mod screen_time;
 pub mod shutdown;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_storage;
+pub mod stats;
+pub mod storage;
+pub mod suggestions;
 pub mod system_compat;
+pub mod system_tasks;
+pub mod template;
 pub mod time_parser;
+pub mod trigger;
 pub mod types;
+pub mod webhook;
 
 // 重新导出常用类型
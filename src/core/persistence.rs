@@ -1,16 +1,41 @@
 //! 任务持久化模块
 //! 
 //! 负责将用户设置的定时任务保存到本地文件，确保应用重启后能恢复任务
+//! 
+//! 开启加密后，任务、配置和配置方案文件通过系统的数据加密接口（Windows上为DPAPI）加密保存，
+//! 其他用户无法读取或修改。读取时根据文件开头的标记自动解密，调用者不需要区分
+//! 
+//! 所有文件先写入同一目录中的临时文件并同步到磁盘，再重命名为目标文件，
+//! 写入过程中程序崩溃或断电时目标文件保持原来的完整内容；仍然无法解析的文件备份为`.corrupted.bak`
 
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDateTime};
 use log::{info, warn, error};
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use tokio::fs as async_fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-use crate::core::types::TaskData;
+use crate::core::history::HistoryEntry;
+use crate::core::migration::{self, SchemaKind};
+use crate::core::storage::{Storage, TaskBackup};
+use crate::core::types::{resolve_local_datetime, ProfileData, TaskData, DEFAULT_PROFILE_NAME};
+use crate::platform::{self, DataProtection};
+
+/// 默认保留的任务文件备份数
+pub const DEFAULT_MAX_BACKUPS: usize = 5;
+
+/// 任务文件备份的文件名前缀
+const TASK_BACKUP_PREFIX: &str = "tasks-";
+
+/// 备份文件名中的时间格式，各部分等宽
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S%.6f";
+
+/// 加密文件开头的标记，读取时据此区分加密文件和明文JSON
+const ENCRYPTED_HEADER: &[u8] = b"QTSHUT-ENCRYPTED\n";
 
 /// 持久化配置
 #[derive(Debug, Clone)]
@@ -21,6 +46,18 @@ struct PersistenceConfig {
     task_file: String,
     /// 配置文件名
     config_file: String,
+    /// 历史文件名
+    history_file: String,
+    /// 配置方案目录名
+    profile_dir: String,
+    /// 日志目录名
+    log_dir: String,
+    /// 任务文件备份目录名
+    backup_dir: String,
+    /// 保留的任务文件备份数，0表示不备份
+    max_backups: usize,
+    /// 是否加密保存任务、配置和配置方案
+    encrypt_data: bool,
 }
 
 impl Default for PersistenceConfig {
@@ -29,6 +66,12 @@ impl Default for PersistenceConfig {
             data_dir: Self::get_default_data_dir(),
             task_file: "tasks.json".to_string(),
             config_file: "config.json".to_string(),
+            history_file: "history.json".to_string(),
+            profile_dir: "profiles".to_string(),
+            log_dir: "logs".to_string(),
+            backup_dir: "backups".to_string(),
+            max_backups: DEFAULT_MAX_BACKUPS,
+            encrypt_data: false,
         }
     }
 }
@@ -53,6 +96,228 @@ impl PersistenceConfig {
     fn get_config_file_path(&self) -> PathBuf {
         self.data_dir.join(&self.config_file)
     }
+    
+    /// 获取历史文件完整路径
+    fn get_history_file_path(&self) -> PathBuf {
+        self.data_dir.join(&self.history_file)
+    }
+    
+    /// 获取可按空间预算清理的目录
+    /// 
+    /// 这些目录中的文件丢失不影响任务和配置
+    fn get_prunable_dirs(&self) -> Vec<PathBuf> {
+        vec![self.data_dir.join(&self.log_dir), self.get_backup_dir()]
+    }
+    
+    /// 获取任务文件备份目录
+    fn get_backup_dir(&self) -> PathBuf {
+        self.data_dir.join(&self.backup_dir)
+    }
+    
+    /// 获取配置方案文件完整路径
+    fn get_profile_file_path(&self, name: &str) -> PathBuf {
+        self.data_dir.join(&self.profile_dir).join(format!("{}.json", name))
+    }
+}
+
+/// 任务文件内容
+/// 
+/// 带有格式版本号，旧版本保存的任务数组和单个任务格式在加载时迁移为当前格式
+#[derive(Debug, Deserialize)]
+struct TaskFileContent {
+    /// 所有任务
+    tasks: Vec<TaskData>,
+}
+
+impl TaskFileContent {
+    /// 解析任务文件，先把旧格式迁移到当前格式
+    /// 
+    /// # 参数
+    /// 
+    /// * `json_data` - 任务文件内容
+    fn parse(json_data: &str) -> Result<Vec<TaskData>> {
+        let value = serde_json::from_str(json_data)?;
+        let value = migration::migrate(SchemaKind::Tasks, value)?;
+        let content: Self = serde_json::from_value(value)?;
+        Ok(content.tasks)
+    }
+    
+    /// 生成带有当前版本号的任务文件内容
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 要保存的任务
+    fn to_json(tasks: &[TaskData]) -> Result<String> {
+        let value = migration::stamp(SchemaKind::Tasks, serde_json::json!({ "tasks": tasks }))?;
+        serde_json::to_string_pretty(&value)
+            .map_err(|e| anyhow!("序列化任务数据失败: {}", e))
+    }
+}
+
+/// 生成要写入文件的内容
+/// 
+/// # 参数
+/// 
+/// * `json_data` - JSON内容
+/// * `protection` - 加密接口，为空时以明文保存
+pub fn seal_data(json_data: &str, protection: Option<&dyn DataProtection>) -> Result<Vec<u8>> {
+    match protection {
+        None => Ok(json_data.as_bytes().to_vec()),
+        Some(protection) => {
+            let mut data = ENCRYPTED_HEADER.to_vec();
+            data.extend(protection.protect_data(json_data.as_bytes())?);
+            Ok(data)
+        }
+    }
+}
+
+/// 读取文件内容，带有加密标记时先解密
+/// 
+/// # 参数
+/// 
+/// * `data` - 文件内容
+/// * `protection` - 加密接口
+/// * `encrypted_only` - 是否拒绝未加密的内容，开启加密后用于防止明文文件被替换进来
+pub fn open_data(data: Vec<u8>, protection: &dyn DataProtection, encrypted_only: bool) -> Result<String> {
+    let data = match data.strip_prefix(ENCRYPTED_HEADER) {
+        Some(encrypted) => protection.unprotect_data(encrypted)?,
+        None if encrypted_only && !data.is_empty() => return Err(anyhow!("已开启加密，拒绝读取未加密的文件")),
+        None => data,
+    };
+    String::from_utf8(data).map_err(|e| anyhow!("文件不是有效的文本: {}", e))
+}
+
+/// 内容是否已加密
+/// 
+/// # 参数
+/// 
+/// * `data` - 文件内容
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_HEADER)
+}
+
+/// 写入文件时使用的临时文件路径，与目标文件在同一目录中
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// 原子地写入文件
+/// 
+/// 先写入临时文件并同步到磁盘，再重命名为目标文件，失败时删除临时文件，目标文件不变
+/// 
+/// # 参数
+/// 
+/// * `path` - 目标文件路径
+/// * `data` - 文件内容
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let temp = temp_path(path);
+    let result = fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// 异步原子地写入文件
+/// 
+/// # 参数
+/// 
+/// * `path` - 目标文件路径
+/// * `data` - 文件内容
+pub async fn write_atomic_async(path: &Path, data: &[u8]) -> io::Result<()> {
+    let temp = temp_path(path);
+    let result = async {
+        let mut file = async_fs::File::create(&temp).await?;
+        file.write_all(data).await?;
+        file.sync_all().await?;
+        async_fs::rename(&temp, path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = async_fs::remove_file(&temp).await;
+    }
+    result
+}
+
+/// 备份文件，文件名为前缀加上备份时间，只保留最近的几份
+/// 
+/// 文件不存在时不备份，备份失败只记录警告，不影响之后的写入
+/// 
+/// # 参数
+/// 
+/// * `file` - 要备份的文件
+/// * `backup_dir` - 备份目录
+/// * `prefix` - 备份文件名前缀，区分不同文件的备份
+/// * `max_backups` - 保留的备份数，0表示不备份
+pub fn backup_file(file: &Path, backup_dir: &Path, prefix: &str, max_backups: usize) {
+    if max_backups == 0 || !file.exists() {
+        return;
+    }
+    
+    let backup_path = backup_dir.join(format!(
+        "{}{}.json",
+        prefix,
+        Local::now().format(BACKUP_TIME_FORMAT)
+    ));
+    let result = fs::create_dir_all(backup_dir)
+        .and_then(|_| fs::copy(file, &backup_path));
+    if let Err(e) = result {
+        warn!("备份文件失败 {:?}: {}", file, e);
+        return;
+    }
+    
+    for old_backup in list_backups(backup_dir, prefix).into_iter().skip(max_backups) {
+        if let Err(e) = fs::remove_file(&old_backup) {
+            warn!("删除旧的备份失败 {:?}: {}", old_backup, e);
+        }
+    }
+}
+
+/// 列出备份文件
+/// 
+/// # 参数
+/// 
+/// * `backup_dir` - 备份目录
+/// * `prefix` - 备份文件名前缀
+/// 
+/// # 返回值
+/// 
+/// 按时间从新到旧排列的备份文件路径
+pub fn list_backups(backup_dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = match fs::read_dir(backup_dir) {
+        Ok(entries) => entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| backup_time(path, prefix).is_some())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    // 文件名中的时间戳等宽，按名称排序即按时间排序
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+/// 从备份文件名中读取备份时间
+/// 
+/// # 参数
+/// 
+/// * `path` - 备份文件路径
+/// * `prefix` - 备份文件名前缀
+/// 
+/// # 返回值
+/// 
+/// 不是该前缀的备份文件时返回None
+pub fn backup_time(path: &Path, prefix: &str) -> Option<DateTime<Local>> {
+    let stamp = path.file_name()?.to_str()?.strip_prefix(prefix)?.strip_suffix(".json")?;
+    let time = NaiveDateTime::parse_from_str(stamp, BACKUP_TIME_FORMAT).ok()?;
+    resolve_local_datetime(time).ok()
 }
 
 /// 应用配置数据
@@ -68,6 +333,14 @@ pub struct AppConfig {
     pub theme: String,
     /// 语言设置
     pub language: String,
+    /// 当前使用的配置方案
+    #[serde(default = "default_active_profile")]
+    pub active_profile: String,
+}
+
+/// 默认使用的配置方案名称
+fn default_active_profile() -> String {
+    DEFAULT_PROFILE_NAME.to_string()
 }
 
 impl Default for AppConfig {
@@ -78,12 +351,13 @@ impl Default for AppConfig {
             confirm_before_shutdown: true,
             theme: "light".to_string(),
             language: "zh-CN".to_string(),
+            active_profile: default_active_profile(),
         }
     }
 }
 
 /// 任务持久化管理器
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TaskPersistence {
     /// 持久化配置
     config: PersistenceConfig,
@@ -131,10 +405,10 @@ impl TaskPersistence {
     pub fn save_task(&self, task_data: &TaskData) -> Result<()> {
         let task_file = self.config.get_task_file_path();
         
-        let json_data = serde_json::to_string_pretty(task_data)
-            .map_err(|e| anyhow!("序列化任务数据失败: {}", e))?;
-            
-        fs::write(&task_file, json_data)
+        let json_data = TaskFileContent::to_json(std::slice::from_ref(task_data))?;
+        
+        self.backup_task_file();
+        write_atomic(&task_file, &self.encode(&json_data)?)
             .map_err(|e| anyhow!("写入任务文件失败: {}", e))?;
             
         info!("任务数据已保存到: {:?}", task_file);
@@ -149,18 +423,12 @@ impl TaskPersistence {
     pub async fn save_task_async(&self, task_data: &TaskData) -> Result<()> {
         let task_file = self.config.get_task_file_path();
         
-        let json_data = serde_json::to_string_pretty(task_data)
-            .map_err(|e| anyhow!("序列化任务数据失败: {}", e))?;
-            
-        let mut file = async_fs::File::create(&task_file).await
-            .map_err(|e| anyhow!("创建任务文件失败: {}", e))?;
-            
-        file.write_all(json_data.as_bytes()).await
+        let data = self.encode(&TaskFileContent::to_json(std::slice::from_ref(task_data))?)?;
+        
+        self.backup_task_file();
+        write_atomic_async(&task_file, &data).await
             .map_err(|e| anyhow!("写入任务文件失败: {}", e))?;
             
-        file.flush().await
-            .map_err(|e| anyhow!("刷新任务文件失败: {}", e))?;
-            
         info!("任务数据已异步保存到: {:?}", task_file);
         Ok(())
     }
@@ -178,15 +446,16 @@ impl TaskPersistence {
             return Ok(None);
         }
         
-        let json_data = fs::read_to_string(&task_file)
+        let data = fs::read(&task_file)
             .map_err(|e| anyhow!("读取任务文件失败: {}", e))?;
+        let json_data = self.decode(data)?;
             
         if json_data.trim().is_empty() {
             info!("任务文件为空");
             return Ok(None);
         }
         
-        let task_data: TaskData = serde_json::from_str(&json_data)
+        let tasks = TaskFileContent::parse(&json_data)
             .map_err(|e| {
                 error!("反序列化任务数据失败: {}", e);
                 // 如果反序列化失败，备份损坏的文件并返回None
@@ -195,9 +464,12 @@ impl TaskPersistence {
                 }
                 anyhow!("任务数据格式错误: {}", e)
             })?;
-            
-        info!("任务数据已加载: {:?}", task_data.task_type);
-        Ok(Some(task_data))
+        
+        let task_data = Self::select_next_task(tasks);
+        if let Some(task) = &task_data {
+            info!("任务数据已加载: {:?}", task.task_type);
+        }
+        Ok(task_data)
     }
     
     /// 异步加载任务数据
@@ -216,23 +488,282 @@ impl TaskPersistence {
         let mut file = async_fs::File::open(&task_file).await
             .map_err(|e| anyhow!("打开任务文件失败: {}", e))?;
             
-        let mut json_data = String::new();
-        file.read_to_string(&mut json_data).await
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).await
             .map_err(|e| anyhow!("读取任务文件失败: {}", e))?;
+        let json_data = self.decode(data)?;
             
         if json_data.trim().is_empty() {
             info!("任务文件为空");
             return Ok(None);
         }
         
-        let task_data: TaskData = serde_json::from_str(&json_data)
+        let tasks = TaskFileContent::parse(&json_data)
             .map_err(|e| {
                 error!("反序列化任务数据失败: {}", e);
                 anyhow!("任务数据格式错误: {}", e)
             })?;
-            
-        info!("任务数据已异步加载: {:?}", task_data.task_type);
-        Ok(Some(task_data))
+        
+        let task_data = Self::select_next_task(tasks);
+        if let Some(task) = &task_data {
+            info!("任务数据已异步加载: {:?}", task.task_type);
+        }
+        Ok(task_data)
+    }
+    
+    /// 加载所有任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回保存的所有任务，文件不存在或为空时返回空列表
+    pub fn load_tasks(&self) -> Result<Vec<TaskData>> {
+        let task_file = self.config.get_task_file_path();
+        
+        if !task_file.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let data = fs::read(&task_file)
+            .map_err(|e| anyhow!("读取任务文件失败: {}", e))?;
+        
+        // 无法读取的文件（包括更新版本保存的和无法解密的文件）先备份，之后保存任务时不会丢失
+        let tasks = self.decode(data)
+            .and_then(|json_data| {
+                if json_data.trim().is_empty() {
+                    Ok(Vec::new())
+                } else {
+                    TaskFileContent::parse(&json_data)
+                }
+            })
+            .map_err(|e| {
+                error!("反序列化任务数据失败: {}", e);
+                if let Err(backup_err) = self.backup_corrupted_file(&task_file) {
+                    warn!("备份损坏文件失败: {}", backup_err);
+                }
+                anyhow!("任务数据格式错误: {}", e)
+            })?;
+        
+        info!("已加载 {} 个任务", tasks.len());
+        Ok(tasks)
+    }
+    
+    /// 保存所有任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 要保存的任务列表
+    pub fn save_tasks(&self, tasks: &[TaskData]) -> Result<()> {
+        let task_file = self.config.get_task_file_path();
+        
+        let json_data = TaskFileContent::to_json(tasks)?;
+        
+        self.backup_task_file();
+        write_atomic(&task_file, &self.encode(&json_data)?)
+            .map_err(|e| anyhow!("写入任务文件失败: {}", e))?;
+        
+        info!("{} 个任务已保存到: {:?}", tasks.len(), task_file);
+        Ok(())
+    }
+    
+    /// 设置保留的任务文件备份数
+    /// 
+    /// # 参数
+    /// 
+    /// * `max_backups` - 保留的备份数，0表示不备份
+    pub fn set_max_backups(&mut self, max_backups: usize) {
+        self.config.max_backups = max_backups;
+    }
+    
+    /// 设置是否加密保存任务、配置和配置方案
+    /// 
+    /// 开启时立即加密以前以明文保存的文件，之后拒绝读取未加密的文件；关闭后加密和未加密的文件都能读取。
+    /// 当前平台不支持加密时继续以明文保存
+    /// 
+    /// # 参数
+    /// 
+    /// * `encrypt_data` - 是否加密
+    pub fn set_encrypt_data(&mut self, encrypt_data: bool) {
+        if encrypt_data && !platform::capabilities().data_protection {
+            warn!("当前平台不支持加密数据，继续以明文保存");
+            self.config.encrypt_data = false;
+            return;
+        }
+        self.config.encrypt_data = encrypt_data;
+        if encrypt_data {
+            self.encrypt_plaintext_files();
+        }
+    }
+    
+    /// 加密数据目录中以前以明文保存的任务、历史、配置、配置方案和任务备份
+    /// 
+    /// 加密失败的文件只记录警告，之后读取时会被拒绝
+    fn encrypt_plaintext_files(&self) {
+        let mut files = vec![
+            self.config.get_task_file_path(),
+            self.config.get_history_file_path(),
+            self.config.get_config_file_path(),
+        ];
+        files.extend(self.list_profiles().unwrap_or_default().iter().map(|name| self.config.get_profile_file_path(name)));
+        files.extend(self.list_task_backups());
+        
+        for file in files {
+            let data = match fs::read(&file) {
+                Ok(data) if !data.is_empty() && !is_sealed(&data) => data,
+                _ => continue,
+            };
+            let result = String::from_utf8(data)
+                .map_err(|e| anyhow!("文件不是有效的文本: {}", e))
+                .and_then(|json_data| self.encode(&json_data))
+                .and_then(|sealed| write_atomic(&file, &sealed).map_err(Into::into));
+            match result {
+                Ok(()) => info!("已加密以明文保存的文件: {:?}", file),
+                Err(e) => warn!("加密文件失败 {:?}: {}", file, e),
+            }
+        }
+    }
+    
+    /// 保留的任务备份数
+    #[cfg(feature = "sqlite")]
+    pub fn max_backups(&self) -> usize {
+        self.config.max_backups
+    }
+    
+    /// 是否加密保存数据
+    #[cfg(feature = "sqlite")]
+    pub fn encrypt_data(&self) -> bool {
+        self.config.encrypt_data
+    }
+    
+    /// 加载所有历史记录
+    /// 
+    /// # 返回值
+    /// 
+    /// 按时间先后排列的历史记录，文件不存在时返回空列表
+    pub fn load_history(&self) -> Result<Vec<HistoryEntry>> {
+        let history_file = self.config.get_history_file_path();
+        if !history_file.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let data = fs::read(&history_file)
+            .map_err(|e| anyhow!("读取历史文件失败: {}", e))?;
+        let json_data = self.decode(data)?;
+        if json_data.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        serde_json::from_str(&json_data).map_err(|e| anyhow!("历史文件格式错误: {}", e))
+    }
+    
+    /// 保存所有历史记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `entries` - 按时间先后排列的历史记录
+    pub fn save_history(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let json_data = serde_json::to_string_pretty(entries)
+            .map_err(|e| anyhow!("序列化历史记录失败: {}", e))?;
+        write_atomic(&self.config.get_history_file_path(), &self.encode(&json_data)?)
+            .map_err(|e| anyhow!("写入历史文件失败: {}", e))
+    }
+    
+    /// 生成要写入文件的内容，开启加密时加密
+    fn encode(&self, json_data: &str) -> Result<Vec<u8>> {
+        let protection: Option<&dyn DataProtection> = if self.config.encrypt_data { Some(platform::native()) } else { None };
+        seal_data(json_data, protection)
+    }
+    
+    /// 读取文件内容，加密的文件先解密，开启加密时拒绝未加密的文件
+    fn decode(&self, data: Vec<u8>) -> Result<String> {
+        open_data(data, platform::native(), self.config.encrypt_data)
+    }
+    
+    /// 列出任务文件备份
+    /// 
+    /// # 返回值
+    /// 
+    /// 按时间从新到旧排列的备份文件路径
+    pub fn list_task_backups(&self) -> Vec<PathBuf> {
+        list_backups(&self.config.get_backup_dir(), TASK_BACKUP_PREFIX)
+    }
+    
+    /// 恢复上一个版本的任务文件
+    /// 
+    /// 用最新的备份覆盖任务文件，并删除该备份，再次恢复时继续回退到更早的版本
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回恢复后的所有任务
+    pub fn restore_previous_tasks(&self) -> Result<Vec<TaskData>> {
+        let backup = self.list_task_backups().into_iter().next()
+            .ok_or_else(|| anyhow!("没有可恢复的任务备份"))?;
+        let (data, tasks) = self.read_task_backup(&backup)?;
+        
+        // 按原样写回，保持备份时的加密状态
+        write_atomic(&self.config.get_task_file_path(), &data)
+            .map_err(|e| anyhow!("写入任务文件失败: {}", e))?;
+        fs::remove_file(&backup)
+            .map_err(|e| anyhow!("删除已恢复的任务备份失败: {}", e))?;
+        
+        info!("已从备份恢复 {} 个任务: {:?}", tasks.len(), backup);
+        Ok(tasks)
+    }
+    
+    /// 读取指定的任务备份，不修改任务文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 备份文件名
+    pub fn load_task_backup(&self, name: &str) -> Result<Vec<TaskData>> {
+        let backup = self.list_task_backups().into_iter()
+            .find(|path| path.file_name().is_some_and(|file_name| file_name == name))
+            .ok_or_else(|| anyhow!("任务备份不存在: {}", name))?;
+        let (_, tasks) = self.read_task_backup(&backup)?;
+        Ok(tasks)
+    }
+    
+    /// 读取任务备份
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回备份文件的原始内容和其中的任务
+    fn read_task_backup(&self, backup: &Path) -> Result<(Vec<u8>, Vec<TaskData>)> {
+        let data = fs::read(backup)
+            .map_err(|e| anyhow!("读取任务备份失败: {}", e))?;
+        let json_data = self.decode(data.clone())?;
+        let tasks = if json_data.trim().is_empty() {
+            Vec::new()
+        } else {
+            TaskFileContent::parse(&json_data)
+                .map_err(|e| anyhow!("任务备份格式错误: {}", e))?
+        };
+        Ok((data, tasks))
+    }
+    
+    /// 修改任务文件前备份当前内容
+    /// 
+    /// 备份失败只记录警告，不影响保存
+    fn backup_task_file(&self) {
+        backup_file(&self.config.get_task_file_path(), &self.config.get_backup_dir(), TASK_BACKUP_PREFIX, self.config.max_backups);
+    }
+    
+    /// 从任务列表中选出下一个要执行的任务
+    /// 
+    /// 优先选择目标时间在未来且最早的已启用任务，没有则返回第一个已启用任务
+    fn select_next_task(tasks: Vec<TaskData>) -> Option<TaskData> {
+        let now = chrono::Local::now();
+        let mut enabled: Vec<TaskData> = tasks.into_iter().filter(|task| task.enabled).collect();
+        
+        let upcoming = enabled.iter()
+            .enumerate()
+            .filter_map(|(index, task)| task.target_time.filter(|time| *time > now).map(|time| (time, index)))
+            .min()
+            .map(|(_, index)| index);
+        
+        match upcoming {
+            Some(index) => Some(enabled.swap_remove(index)),
+            None => enabled.into_iter().next(),
+        }
     }
     
     /// 清除任务数据
@@ -242,6 +773,7 @@ impl TaskPersistence {
         let task_file = self.config.get_task_file_path();
         
         if task_file.exists() {
+            self.backup_task_file();
             fs::remove_file(&task_file)
                 .map_err(|e| anyhow!("删除任务文件失败: {}", e))?;
             info!("任务文件已删除: {:?}", task_file);
@@ -257,6 +789,7 @@ impl TaskPersistence {
         let task_file = self.config.get_task_file_path();
         
         if task_file.exists() {
+            self.backup_task_file();
             async_fs::remove_file(&task_file).await
                 .map_err(|e| anyhow!("删除任务文件失败: {}", e))?;
             info!("任务文件已异步删除: {:?}", task_file);
@@ -278,7 +811,7 @@ impl TaskPersistence {
         let json_data = serde_json::to_string_pretty(config)
             .map_err(|e| anyhow!("序列化配置数据失败: {}", e))?;
             
-        fs::write(&config_file, json_data)
+        write_atomic(&config_file, &self.encode(&json_data)?)
             .map_err(|e| anyhow!("写入配置文件失败: {}", e))?;
             
         info!("配置数据已保存到: {:?}", config_file);
@@ -303,8 +836,9 @@ impl TaskPersistence {
             return Ok(default_config);
         }
         
-        let json_data = fs::read_to_string(&config_file)
+        let data = fs::read(&config_file)
             .map_err(|e| anyhow!("读取配置文件失败: {}", e))?;
+        let json_data = self.decode(data)?;
             
         let config: AppConfig = serde_json::from_str(&json_data)
             .map_err(|e| {
@@ -321,6 +855,173 @@ impl TaskPersistence {
         Ok(config)
     }
     
+    /// 检查配置方案名称是否可用作文件名
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 配置方案名称
+    pub fn validate_profile_name(name: &str) -> Result<()> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            return Err(anyhow!("配置方案名称不能为空"));
+        }
+        
+        if trimmed != name {
+            return Err(anyhow!("配置方案名称首尾不能包含空白字符"));
+        }
+        
+        let invalid_chars = ['/', '\\', ':', '*', '?', '"', '<', '>', '|', '.'];
+        if name.chars().any(|c| invalid_chars.contains(&c) || c.is_control()) {
+            return Err(anyhow!("配置方案名称包含非法字符: {}", name));
+        }
+        
+        Ok(())
+    }
+    
+    /// 保存配置方案
+    /// 
+    /// # 参数
+    /// 
+    /// * `profile` - 要保存的配置方案
+    pub fn save_profile(&self, profile: &ProfileData) -> Result<()> {
+        Self::validate_profile_name(&profile.name)?;
+        let profile_file = self.config.get_profile_file_path(&profile.name);
+        
+        if let Some(parent) = profile_file.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| anyhow!("创建配置方案目录失败: {}", e))?;
+        }
+        
+        let json_data = serde_json::to_string_pretty(profile)
+            .map_err(|e| anyhow!("序列化配置方案失败: {}", e))?;
+        
+        write_atomic(&profile_file, &self.encode(&json_data)?)
+            .map_err(|e| anyhow!("写入配置方案文件失败: {}", e))?;
+        
+        info!("配置方案已保存到: {:?}", profile_file);
+        Ok(())
+    }
+    
+    /// 加载配置方案
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 配置方案名称
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回加载的配置方案，如果文件不存在则返回同名的空白方案
+    pub fn load_profile(&self, name: &str) -> Result<ProfileData> {
+        Self::validate_profile_name(name)?;
+        let profile_file = self.config.get_profile_file_path(name);
+        
+        if !profile_file.exists() {
+            info!("配置方案不存在，使用空白方案: {}", name);
+            return Ok(ProfileData::new(name));
+        }
+        
+        let data = fs::read(&profile_file)
+            .map_err(|e| anyhow!("读取配置方案文件失败: {}", e))?;
+        let json_data = self.decode(data)?;
+        
+        let mut profile: ProfileData = serde_json::from_str(&json_data)
+            .map_err(|e| {
+                error!("反序列化配置方案失败: {}", e);
+                if let Err(backup_err) = self.backup_corrupted_file(&profile_file) {
+                    warn!("备份损坏文件失败: {}", backup_err);
+                }
+                anyhow!("配置方案格式错误: {}", e)
+            })?;
+        
+        // 以文件名为准，避免手动修改文件后名称不一致
+        profile.name = name.to_string();
+        
+        info!("配置方案已加载: {}", name);
+        Ok(profile)
+    }
+    
+    /// 列出所有配置方案名称
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回按名称排序的配置方案列表，默认方案始终排在第一位
+    pub fn list_profiles(&self) -> Result<Vec<String>> {
+        let profile_dir = self.config.data_dir.join(&self.config.profile_dir);
+        let mut names = Vec::new();
+        
+        if profile_dir.exists() {
+            let entries = fs::read_dir(&profile_dir)
+                .map_err(|e| anyhow!("读取配置方案目录失败: {}", e))?;
+            
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().map_or(false, |ext| ext == "json") {
+                    if let Some(stem) = path.file_stem() {
+                        let name = stem.to_string_lossy().to_string();
+                        if name != DEFAULT_PROFILE_NAME && Self::validate_profile_name(&name).is_ok() {
+                            names.push(name);
+                        }
+                    }
+                }
+            }
+        }
+        
+        names.sort();
+        names.insert(0, DEFAULT_PROFILE_NAME.to_string());
+        Ok(names)
+    }
+    
+    /// 删除配置方案
+    /// 
+    /// 默认方案不能删除
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 配置方案名称
+    pub fn delete_profile(&self, name: &str) -> Result<()> {
+        Self::validate_profile_name(name)?;
+        if name == DEFAULT_PROFILE_NAME {
+            return Err(anyhow!("默认配置方案不能删除"));
+        }
+        
+        let profile_file = self.config.get_profile_file_path(name);
+        if profile_file.exists() {
+            fs::remove_file(&profile_file)
+                .map_err(|e| anyhow!("删除配置方案文件失败: {}", e))?;
+            info!("配置方案已删除: {}", name);
+        }
+        
+        Ok(())
+    }
+    
+    /// 记录当前使用的配置方案
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 配置方案名称
+    pub fn set_active_profile(&self, name: &str) -> Result<()> {
+        Self::validate_profile_name(name)?;
+        let mut config = self.load_config()?;
+        config.active_profile = name.to_string();
+        self.save_config(&config)
+    }
+    
+    /// 加载当前使用的配置方案
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回当前配置方案，名称无效时回退到默认方案
+    pub fn load_active_profile(&self) -> Result<ProfileData> {
+        let config = self.load_config()?;
+        match self.load_profile(&config.active_profile) {
+            Ok(profile) => Ok(profile),
+            Err(e) => {
+                warn!("加载当前配置方案失败，使用默认方案: {}", e);
+                self.load_profile(DEFAULT_PROFILE_NAME)
+            }
+        }
+    }
+    
     /// 检查数据目录是否存在且可写
     pub fn validate_data_directory(&self) -> Result<()> {
         let data_dir = &self.config.data_dir;
@@ -351,22 +1052,96 @@ impl TaskPersistence {
     }
     
     /// 获取数据目录大小（字节）
+    /// 
+    /// 包含日志、配置方案等子目录中的文件
     pub fn get_data_size(&self) -> Result<u64> {
-        let mut total_size = 0u64;
+        Ok(Self::dir_size(&self.config.data_dir))
+    }
+    
+    /// 递归计算目录大小
+    fn dir_size(dir: &Path) -> u64 {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return 0,
+        };
+        
+        entries.flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                if metadata.is_dir() {
+                    Some(Self::dir_size(&entry.path()))
+                } else {
+                    Some(metadata.len())
+                }
+            })
+            .sum()
+    }
+    
+    /// 按总空间预算清理数据目录
+    /// 
+    /// 数据目录超出预算时，从最旧的日志和损坏文件备份开始删除，
+    /// 任务、配置和配置方案文件不会被删除
+    /// 
+    /// # 参数
+    /// 
+    /// * `max_bytes` - 数据目录允许占用的最大字节数
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回释放的字节数
+    pub fn enforce_size_budget(&self, max_bytes: u64) -> Result<u64> {
+        let mut total_size = self.get_data_size()?;
+        if total_size <= max_bytes {
+            return Ok(0);
+        }
+        
+        info!("数据目录大小 {} 字节超出预算 {} 字节，开始清理", total_size, max_bytes);
         
-        if let Ok(entries) = fs::read_dir(&self.config.data_dir) {
-            for entry in entries {
-                if let Ok(entry) = entry {
+        let mut candidates = Vec::new();
+        let mut collect = |dir: &Path, only_backups: bool| {
+            if let Ok(entries) = fs::read_dir(dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    let is_backup = path.to_string_lossy().ends_with(".bak");
                     if let Ok(metadata) = entry.metadata() {
-                        if metadata.is_file() {
-                            total_size += metadata.len();
+                        if metadata.is_file() && (!only_backups || is_backup) {
+                            let modified = metadata.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+                            candidates.push((modified, path, metadata.len()));
                         }
                     }
                 }
             }
+        };
+        
+        collect(&self.config.data_dir, true);
+        for dir in self.config.get_prunable_dirs() {
+            collect(&dir, false);
+        }
+        candidates.sort();
+        
+        let mut freed = 0u64;
+        let mut removed = 0usize;
+        for (_, path, size) in candidates {
+            if total_size <= max_bytes {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Ok(_) => {
+                    info!("已删除旧文件: {:?}", path);
+                    total_size = total_size.saturating_sub(size);
+                    freed += size;
+                    removed += 1;
+                },
+                // 正在使用的日志文件无法删除，跳过
+                Err(e) => warn!("删除文件失败 {:?}: {}", path, e),
+            }
         }
         
-        Ok(total_size)
+        if total_size > max_bytes {
+            warn!("清理后数据目录仍超出预算: {} 字节", total_size);
+        }
+        info!("空间预算清理完成，删除了 {} 个文件，释放 {} 字节，当前大小 {} 字节", removed, freed, total_size);
+        Ok(freed)
     }
     
     /// 清理所有数据
@@ -378,6 +1153,14 @@ impl TaskPersistence {
         // 删除任务文件
         let _ = self.clear_task();
         
+        // 删除配置方案目录
+        let profile_dir = self.config.data_dir.join(&self.config.profile_dir);
+        if profile_dir.exists() {
+            fs::remove_dir_all(&profile_dir)
+                .map_err(|e| anyhow!("删除配置方案目录失败: {}", e))?;
+            info!("配置方案目录已删除: {:?}", profile_dir);
+        }
+        
         // 删除配置文件
         let config_file = self.config.get_config_file_path();
         if config_file.exists() {
@@ -400,20 +1183,65 @@ impl TaskPersistence {
     }
 }
 
+impl Storage for TaskPersistence {
+    fn load_tasks(&self) -> Result<Vec<TaskData>> {
+        TaskPersistence::load_tasks(self)
+    }
+    
+    fn save_tasks(&self, tasks: &[TaskData]) -> Result<()> {
+        TaskPersistence::save_tasks(self, tasks)
+    }
+    
+    fn restore_previous_tasks(&self) -> Result<Vec<TaskData>> {
+        TaskPersistence::restore_previous_tasks(self)
+    }
+    
+    fn task_backups(&self) -> Vec<TaskBackup> {
+        self.list_task_backups().iter()
+            .filter_map(|path| Some(TaskBackup {
+                id: path.file_name()?.to_str()?.to_string(),
+                at: backup_time(path, TASK_BACKUP_PREFIX)?,
+            }))
+            .collect()
+    }
+    
+    fn load_task_backup(&self, id: &str) -> Result<Vec<TaskData>> {
+        TaskPersistence::load_task_backup(self, id)
+    }
+    
+    fn load_history(&self) -> Result<Vec<HistoryEntry>> {
+        TaskPersistence::load_history(self)
+    }
+    
+    fn save_history(&self, entries: &[HistoryEntry]) -> Result<()> {
+        TaskPersistence::save_history(self, entries)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
     use chrono::Local;
-    use crate::core::types::TaskType;
+    use crate::core::stats::TaskStats;
+    use crate::core::types::{new_task_id, TaskAction, TaskSource, TaskType};
     
     fn create_test_task_data() -> TaskData {
         TaskData {
+            id: new_task_id(),
             task_type: TaskType::Once,
             target_time: Some(Local::now() + chrono::Duration::hours(1)),
             daily_time: None,
             enabled: true,
             created_at: Local::now(),
+            action: TaskAction::Shutdown,
+            weekly_slots: Vec::new(),
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: None,
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
         }
     }
     
@@ -464,6 +1292,40 @@ mod tests {
         assert!(persistence.load_task().unwrap().is_none());
     }
     
+    #[test]
+    fn test_task_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        persistence.set_max_backups(2);
+        
+        let tasks: Vec<TaskData> = (0..4).map(|_| create_test_task_data()).collect();
+        for count in 1..=tasks.len() {
+            persistence.save_tasks(&tasks[..count]).unwrap();
+        }
+        // 第一次保存时还没有任务文件，之后每次保存前备份一次，只保留最近2个
+        assert_eq!(persistence.list_task_backups().len(), 2);
+        
+        let restored = persistence.restore_previous_tasks().unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(persistence.load_tasks().unwrap().len(), 3);
+        
+        assert_eq!(persistence.restore_previous_tasks().unwrap().len(), 2);
+        assert!(persistence.restore_previous_tasks().is_err());
+    }
+    
+    #[test]
+    fn test_clear_task_keeps_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        
+        persistence.save_tasks(&[create_test_task_data()]).unwrap();
+        persistence.clear_task().unwrap();
+        assert!(persistence.load_tasks().unwrap().is_empty());
+        
+        assert_eq!(persistence.restore_previous_tasks().unwrap().len(), 1);
+        assert_eq!(persistence.load_tasks().unwrap().len(), 1);
+    }
+    
     #[test]
     fn test_save_and_load_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -502,4 +1364,213 @@ mod tests {
         // 确认任务已清除
         assert!(persistence.load_task_async().await.unwrap().is_none());
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_write_atomic() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tasks.json");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic_async(&path, b"second").await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert!(!temp_path(&path).exists());
+        
+        // 写入失败时不留下临时文件
+        let missing = temp_dir.path().join("不存在").join("tasks.json");
+        assert!(write_atomic(&missing, b"data").is_err());
+        assert!(write_atomic_async(&missing, b"data").await.is_err());
+        assert!(!temp_path(&missing).exists());
+    }
+    
+    #[test]
+    fn test_enforce_size_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        persistence.save_task(&create_test_task_data()).unwrap();
+        
+        let log_dir = temp_dir.path().join("logs");
+        fs::create_dir_all(&log_dir).unwrap();
+        let now = std::time::SystemTime::now();
+        let write_file = |path: PathBuf, size: usize, age_secs: u64| {
+            fs::write(&path, vec![b'x'; size]).unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - std::time::Duration::from_secs(age_secs)).unwrap();
+        };
+        write_file(log_dir.join("qtshut_20240101.log"), 4000, 300);
+        write_file(log_dir.join("qtshut_20240102.log"), 4000, 200);
+        write_file(temp_dir.path().join("tasks.corrupted.bak"), 2000, 100);
+        write_file(log_dir.join("qtshut_20240103.log"), 1000, 0);
+        
+        let task_size = fs::metadata(temp_dir.path().join("tasks.json")).unwrap().len();
+        let total = persistence.get_data_size().unwrap();
+        assert_eq!(total, task_size + 11000);
+        
+        // 未超出预算时不清理
+        assert_eq!(persistence.enforce_size_budget(total).unwrap(), 0);
+        
+        // 从最旧的文件开始删除，直到不超出预算
+        let freed = persistence.enforce_size_budget(task_size + 4000).unwrap();
+        assert_eq!(freed, 8000);
+        assert!(!log_dir.join("qtshut_20240101.log").exists());
+        assert!(!log_dir.join("qtshut_20240102.log").exists());
+        assert!(temp_dir.path().join("tasks.corrupted.bak").exists());
+        assert_eq!(persistence.get_data_size().unwrap(), total - freed);
+        
+        // 任务文件不会被删除
+        persistence.enforce_size_budget(0).unwrap();
+        assert!(persistence.load_task().unwrap().is_some());
+    }
+    
+    #[test]
+    fn test_multiple_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        
+        let mut later = create_test_task_data();
+        later.target_time = Some(Local::now() + chrono::Duration::hours(3));
+        let mut sooner = create_test_task_data();
+        sooner.target_time = Some(Local::now() + chrono::Duration::minutes(30));
+        sooner.action = TaskAction::Sleep;
+        
+        assert_eq!(persistence.add_tasks(&[later.clone()]).unwrap(), 1);
+        assert_eq!(persistence.add_tasks(&[sooner.clone()]).unwrap(), 2);
+        assert_eq!(persistence.load_tasks().unwrap().len(), 2);
+        
+        // 加载单个任务时返回最早到期的任务
+        let next = persistence.load_task().unwrap().unwrap();
+        assert_eq!(next.target_time, sooner.target_time);
+        assert_eq!(next.action, TaskAction::Sleep);
+    }
+    
+    #[test]
+    fn test_load_legacy_single_task_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        
+        // 旧版本保存的单个任务，且没有action字段
+        let legacy = r#"{
+            "task_type": "Once",
+            "target_time": "2099-01-01T22:00:00+08:00",
+            "daily_time": null,
+            "enabled": true,
+            "created_at": "2024-01-01T12:00:00+08:00"
+        }"#;
+        fs::write(temp_dir.path().join("tasks.json"), legacy).unwrap();
+        
+        let tasks = persistence.load_tasks().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].action, TaskAction::Shutdown);
+        assert!(persistence.load_task().unwrap().is_some());
+    }
+    
+    #[test]
+    fn test_task_file_schema_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        let task_file = temp_dir.path().join("tasks.json");
+        
+        persistence.save_tasks(&[create_test_task_data()]).unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&task_file).unwrap()).unwrap();
+        assert_eq!(saved[migration::SCHEMA_VERSION_KEY], SchemaKind::Tasks.current_version());
+        assert_eq!(persistence.load_tasks().unwrap().len(), 1);
+        
+        // 更新版本保存的任务文件不能读取，备份后不会在下次保存时丢失
+        let newer = format!(r#"{{"schema_version":{},"tasks":[],"groups":[]}}"#, SchemaKind::Tasks.current_version() + 1);
+        fs::write(&task_file, &newer).unwrap();
+        assert!(persistence.load_tasks().unwrap_err().to_string().contains("更新版本"));
+        assert_eq!(fs::read_to_string(task_file.with_extension("corrupted.bak")).unwrap(), newer);
+    }
+    
+    /// 把每个字节取反的测试用加密接口
+    struct InvertProtection;
+    
+    impl DataProtection for InvertProtection {
+        fn protect_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|byte| !byte).collect())
+        }
+        
+        fn unprotect_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.protect_data(data)
+        }
+    }
+    
+    #[test]
+    fn test_sealed_data() {
+        let json_data = r#"{"tasks":[]}"#;
+        let sealed = seal_data(json_data, Some(&InvertProtection)).unwrap();
+        assert!(sealed.starts_with(ENCRYPTED_HEADER));
+        assert!(!String::from_utf8_lossy(&sealed).contains("tasks"));
+        assert_eq!(open_data(sealed.clone(), &InvertProtection, true).unwrap(), json_data);
+        assert!(is_sealed(&sealed));
+        
+        // 未加密的文件原样读取，开启加密后拒绝读取
+        let plain = seal_data(json_data, None).unwrap();
+        assert_eq!(plain, json_data.as_bytes());
+        assert!(!is_sealed(&plain));
+        assert_eq!(open_data(plain.clone(), &InvertProtection, false).unwrap(), json_data);
+        assert!(open_data(plain, &InvertProtection, true).is_err());
+        
+        // 其他方式加密的数据无法解密
+        let sealed = seal_data(json_data, Some(&InvertProtection)).unwrap();
+        assert!(open_data(sealed, platform::native(), false).is_err());
+    }
+    
+    #[test]
+    fn test_profile_name_validation() {
+        assert!(TaskPersistence::validate_profile_name("工作").is_ok());
+        assert!(TaskPersistence::validate_profile_name("").is_err());
+        assert!(TaskPersistence::validate_profile_name(" 工作").is_err());
+        assert!(TaskPersistence::validate_profile_name("../工作").is_err());
+        assert!(TaskPersistence::validate_profile_name("a/b").is_err());
+    }
+    
+    #[test]
+    fn test_profiles_are_namespaced() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        
+        let mut work = ProfileData::new("工作");
+        work.record_input("30分钟");
+        work.add_preset("18:00");
+        work.quick_minutes = vec![15, 45];
+        persistence.save_profile(&work).unwrap();
+        
+        let mut home = ProfileData::new("家庭");
+        home.record_input("今晚11点");
+        persistence.save_profile(&home).unwrap();
+        
+        let loaded_work = persistence.load_profile("工作").unwrap();
+        assert_eq!(loaded_work, work);
+        
+        let loaded_home = persistence.load_profile("家庭").unwrap();
+        assert_eq!(loaded_home.input_history, vec!["今晚11点".to_string()]);
+        assert!(loaded_home.presets.is_empty());
+        assert_eq!(loaded_home.quick_minutes, vec![5, 10, 30, 60]);
+        
+        let names = persistence.list_profiles().unwrap();
+        assert_eq!(names[0], DEFAULT_PROFILE_NAME);
+        assert!(names.contains(&"工作".to_string()));
+        assert!(names.contains(&"家庭".to_string()));
+        
+        persistence.delete_profile("家庭").unwrap();
+        assert!(!persistence.list_profiles().unwrap().contains(&"家庭".to_string()));
+        assert!(persistence.delete_profile(DEFAULT_PROFILE_NAME).is_err());
+    }
+    
+    #[test]
+    fn test_active_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        
+        // 未设置时使用默认方案
+        assert_eq!(persistence.load_active_profile().unwrap().name, DEFAULT_PROFILE_NAME);
+        
+        let mut profile = ProfileData::new("夜间");
+        profile.record_input("凌晨1点");
+        persistence.save_profile(&profile).unwrap();
+        persistence.set_active_profile("夜间").unwrap();
+        
+        let active = persistence.load_active_profile().unwrap();
+        assert_eq!(active.name, "夜间");
+        assert_eq!(active.input_history, vec!["凌晨1点".to_string()]);
+    }
+}
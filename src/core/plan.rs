@@ -0,0 +1,382 @@
+//! 执行计划预览模块
+//! 
+//! 在不创建任务的情况下解析用户输入，展示到点时间、执行操作、
+//! 提醒时间点以及权限和电源需求，与实际开始倒计时使用相同的解析流程
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    time_parser::TimeParser,
+    types::{resolve_local_datetime, PowerRequirement, TaskAction, TaskData, TaskType},
+};
+
+/// 倒计时进入最后确认阶段的秒数
+pub const FINAL_WARNING_SECONDS: i64 = 30;
+
+/// 不到该秒数的倒计时开始前需要确认，开始后直接进入最后确认阶段
+pub const SHORT_COUNTDOWN_SECONDS: i64 = 60;
+
+/// 是否为需要确认的短倒计时
+/// 
+/// "3秒"这类误输入来不及反应就会关机，开始前需要用户确认
+/// 
+/// # 参数
+/// 
+/// * `target_time` - 到点时间
+/// * `now` - 当前时间
+pub fn is_short_countdown(target_time: DateTime<Local>, now: DateTime<Local>) -> bool {
+    target_time - now < Duration::seconds(SHORT_COUNTDOWN_SECONDS)
+}
+
+/// "推迟"按钮每次延长的分钟数
+pub const SNOOZE_MINUTES: u32 = 10;
+
+/// 运行中微调倒计时的"+/−"按钮每次调整的分钟数
+pub const ADJUST_MINUTES: u32 = 5;
+
+/// 默认的到点前提醒时间，从远到近排列
+pub const DEFAULT_WARNING_OFFSETS: [&str; 5] = ["30m", "10m", "5m", "1m", "10s"];
+
+/// 解析到点前的提醒时间
+/// 
+/// # 参数
+/// 
+/// * `text` - 数字加单位，单位为 h（小时）、m（分钟）或 s（秒），如"30m"、"10s"
+pub fn parse_warning_offset(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: i64 = number.parse()
+        .map_err(|_| anyhow!("提醒时间格式错误: \"{}\"，应为数字加单位，如 30m、10s", text))?;
+    let offset = match unit.trim() {
+        "h" => Duration::hours(value),
+        "m" => Duration::minutes(value),
+        "s" => Duration::seconds(value),
+        _ => return Err(anyhow!("提醒时间单位错误: \"{}\"，可用单位为 h、m、s", text)),
+    };
+    if offset <= Duration::zero() || offset > Duration::days(1) {
+        return Err(anyhow!("提醒时间应在1秒到24小时之间: \"{}\"", text));
+    }
+    Ok(offset)
+}
+
+/// 解析提醒时间列表
+/// 
+/// # 参数
+/// 
+/// * `texts` - 提醒时间文本，顺序不限
+/// 
+/// # 返回值
+/// 
+/// 去重后从远到近排列的提醒时间
+pub fn parse_warning_offsets<S: AsRef<str>>(texts: &[S]) -> Result<Vec<Duration>> {
+    let mut offsets = texts.iter()
+        .map(|text| parse_warning_offset(text.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+    offsets.sort_by(|a, b| b.cmp(a));
+    offsets.dedup();
+    Ok(offsets)
+}
+
+/// 提醒时间的显示文本，如"30分钟"、"1分30秒"
+/// 
+/// # 参数
+/// 
+/// * `offset` - 距离到点的时长
+pub fn format_warning_offset(offset: Duration) -> String {
+    let seconds = offset.num_seconds().max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    match (hours, minutes, seconds) {
+        (0, 0, s) => format!("{}秒", s),
+        (0, m, 0) => format!("{}分钟", m),
+        (0, m, s) => format!("{}分{}秒", m, s),
+        (h, 0, 0) => format!("{}小时", h),
+        (h, m, _) => format!("{}小时{}分钟", h, m),
+    }
+}
+
+/// 第一次提醒时"改到稍后"从现在起推迟的分钟数
+pub const RESCHEDULE_LATER_MINUTES: i64 = 30;
+
+/// 第一次提醒时可以选择的改期方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RescheduleChoice {
+    /// 改到现在起30分钟后
+    Later,
+    /// 改到明天的同一时间
+    TomorrowNight,
+    /// 保持原计划
+    Keep,
+}
+
+impl RescheduleChoice {
+    /// 所有改期方式，按界面显示的顺序排列
+    pub const ALL: [RescheduleChoice; 3] = [RescheduleChoice::Later, RescheduleChoice::TomorrowNight, RescheduleChoice::Keep];
+    
+    /// 按钮文字
+    pub fn label(&self) -> String {
+        match self {
+            RescheduleChoice::Later => format!("改到{}分钟后", RESCHEDULE_LATER_MINUTES),
+            RescheduleChoice::TomorrowNight => "改到明晚".to_string(),
+            RescheduleChoice::Keep => "保持".to_string(),
+        }
+    }
+    
+    /// 改期后的到点时间
+    /// 
+    /// # 参数
+    /// 
+    /// * `deadline` - 原来的到点时间
+    /// * `now` - 当前时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 保持原计划或改期后不晚于原来的到点时间时返回None
+    pub fn target(&self, deadline: DateTime<Local>, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let target = match self {
+            RescheduleChoice::Later => now + Duration::minutes(RESCHEDULE_LATER_MINUTES),
+            // 按日期加一天，跨夏令时切换时仍是明天的同一时刻
+            RescheduleChoice::TomorrowNight => resolve_local_datetime(deadline.naive_local() + Duration::days(1)).ok()?,
+            RescheduleChoice::Keep => return None,
+        };
+        Some(target).filter(|target| *target > deadline)
+    }
+    
+    /// 在指定时间可以选择的改期方式，总是包含保持原计划
+    /// 
+    /// # 参数
+    /// 
+    /// * `deadline` - 原来的到点时间
+    /// * `now` - 当前时间
+    pub fn available(deadline: DateTime<Local>, now: DateTime<Local>) -> Vec<RescheduleChoice> {
+        Self::ALL.into_iter()
+            .filter(|choice| *choice == RescheduleChoice::Keep || choice.target(deadline, now).is_some())
+            .collect()
+    }
+}
+
+/// 计划中的一次提醒
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedWarning {
+    /// 提醒时间
+    pub at: DateTime<Local>,
+    /// 提醒内容
+    pub message: String,
+}
+
+/// 解析后的执行计划
+#[derive(Debug, Clone)]
+pub struct TaskPlan {
+    /// 将要创建的任务
+    pub task: TaskData,
+    /// 倒计时期间会出现的提醒
+    pub warnings: Vec<PlannedWarning>,
+    /// 电源需求
+    pub power_requirements: Vec<PowerRequirement>,
+    /// 是否需要以管理员身份运行
+    pub needs_elevation: bool,
+}
+
+impl TaskPlan {
+    /// 解析输入并生成执行计划
+    /// 
+    /// # 参数
+    /// 
+    /// * `parser` - 时间解析器
+    /// * `input` - 用户输入的时间
+    /// * `task_type` - 任务类型
+    /// * `action` - 到点后执行的操作
+    /// * `has_admin` - 当前进程是否具有管理员权限
+    /// * `now` - 当前时间
+    pub fn resolve(
+        parser: &TimeParser,
+        input: &str,
+        task_type: TaskType,
+        action: TaskAction,
+        has_admin: bool,
+        now: DateTime<Local>,
+    ) -> Result<Self> {
+        let time_input = parser.parse(input)?;
+        if task_type == TaskType::Once {
+            parser.validate(&time_input)?;
+        }
+        let task = TaskData::from_time_input(task_type, time_input, action, now)?;
+        Ok(Self::for_task(task, has_admin, now))
+    }
+    
+    /// 根据任务数据生成执行计划
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 任务数据
+    /// * `has_admin` - 当前进程是否具有管理员权限
+    /// * `now` - 当前时间
+    pub fn for_task(task: TaskData, has_admin: bool, now: DateTime<Local>) -> Self {
+        let mut warnings = Vec::new();
+        if let Some(target_time) = task.target_time {
+            let final_warning = target_time - Duration::seconds(FINAL_WARNING_SECONDS);
+            if final_warning > now {
+                warnings.push(PlannedWarning {
+                    at: final_warning,
+                    message: format!("进入最后确认阶段，提示即将{}", task.action),
+                });
+            }
+            warnings.push(PlannedWarning {
+                at: target_time,
+                message: format!("倒计时结束通知，随后执行{}", task.action),
+            });
+        }
+        
+        Self {
+            power_requirements: task.power_requirements(),
+            // 睡眠、锁定和注销不需要关机权限
+            needs_elevation: task.action.requires_shutdown_privilege() && !has_admin,
+            task,
+            warnings,
+        }
+    }
+    
+    /// 加入到点前的提醒
+    /// 
+    /// 早于当前时间的提醒不会出现，提醒按时间先后插入最后确认阶段之前
+    /// 
+    /// # 参数
+    /// 
+    /// * `offsets` - 距离到点的提醒时间
+    /// * `now` - 当前时间
+    pub fn with_warning_offsets(mut self, offsets: &[Duration], now: DateTime<Local>) -> Self {
+        if let Some(target_time) = self.task.target_time {
+            for &offset in offsets {
+                let at = target_time - offset;
+                if at > now {
+                    self.warnings.push(PlannedWarning {
+                        at,
+                        message: format!("提醒{}后将{}", format_warning_offset(offset), self.task.action),
+                    });
+                }
+            }
+            self.warnings.sort_by_key(|warning| warning.at);
+        }
+        self
+    }
+    
+    /// 生成可显示给用户的计划文本
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(target_time) = self.task.target_time {
+            lines.push(format!("{}：{} 执行{}",
+                self.task.task_type, target_time.format("%Y-%m-%d %H:%M:%S"), self.task.action));
+        }
+        for warning in &self.warnings {
+            lines.push(format!("  {} {}", warning.at.format("%H:%M:%S"), warning.message));
+        }
+        if !self.power_requirements.is_empty() {
+            let requirements: Vec<String> = self.power_requirements.iter().map(|r| r.to_string()).collect();
+            lines.push(format!("电源: {}", requirements.join("、")));
+        }
+        if self.needs_elevation {
+            lines.push("权限: 当前没有管理员权限，可能无法执行关机".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, Weekday};
+    use crate::core::types::WeeklySlot;
+    
+    #[test]
+    fn test_resolve_once_plan() {
+        let now = Local::now();
+        let plan = TaskPlan::resolve(&TimeParser::new(), "10分钟", TaskType::Once, TaskAction::Shutdown, false, now).unwrap();
+        
+        assert_eq!(plan.task.target_time, Some(now + Duration::minutes(10)));
+        assert_eq!(plan.warnings.len(), 2);
+        assert_eq!(plan.warnings[0].at, now + Duration::minutes(10) - Duration::seconds(FINAL_WARNING_SECONDS));
+        assert_eq!(plan.power_requirements, vec![PowerRequirement::KeepAwake]);
+        assert!(plan.needs_elevation);
+        assert!(plan.summary().contains("管理员权限"));
+    }
+    
+    #[test]
+    fn test_short_plan_and_sleep_action() {
+        let now = Local::now();
+        let plan = TaskPlan::resolve(&TimeParser::new(), "10秒", TaskType::Once, TaskAction::Sleep, false, now).unwrap();
+        
+        // 不足最后确认阶段时只有结束通知
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(!plan.needs_elevation);
+        // 计算机睡眠后不必再睡眠，不需要阻止睡眠
+        assert!(plan.power_requirements.is_empty());
+        assert!(is_short_countdown(plan.task.target_time.unwrap(), now));
+        assert!(!is_short_countdown(now + Duration::seconds(SHORT_COUNTDOWN_SECONDS), now));
+        
+        assert!(TaskPlan::resolve(&TimeParser::new(), "无效时间", TaskType::Once, TaskAction::Shutdown, true, now).is_err());
+    }
+    
+    #[test]
+    fn test_power_requirements() {
+        let now = Local::now();
+        let mut daily = TaskPlan::resolve(&TimeParser::new(), "每天22:30", TaskType::Daily, TaskAction::Shutdown, false, now).unwrap().task;
+        assert_eq!(daily.power_requirements(), vec![PowerRequirement::WakeTimer]);
+        daily.action = TaskAction::MonitorOff;
+        assert!(daily.power_requirements().is_empty());
+        
+        // 每周计划中有一个时间段需要关机就需要唤醒定时器
+        let time = NaiveTime::from_hms_opt(22, 0, 0).unwrap();
+        let mut weekly = daily.clone();
+        weekly.task_type = TaskType::Weekly;
+        weekly.weekly_slots = vec![
+            WeeklySlot { weekday: Weekday::Mon, time, action: TaskAction::Sleep },
+            WeeklySlot { weekday: Weekday::Fri, time, action: TaskAction::Shutdown },
+        ];
+        assert_eq!(weekly.power_requirements(), vec![PowerRequirement::WakeTimer]);
+        
+        // 停用的任务没有电源需求
+        weekly.enabled = false;
+        assert!(weekly.power_requirements().is_empty());
+    }
+    
+    #[test]
+    fn test_reschedule_choices() {
+        let now = Local::now();
+        let deadline = now + Duration::minutes(10);
+        assert_eq!(RescheduleChoice::Later.target(deadline, now), Some(now + Duration::minutes(30)));
+        let tomorrow = RescheduleChoice::TomorrowNight.target(deadline, now).unwrap();
+        assert_eq!(tomorrow.naive_local(), deadline.naive_local() + Duration::days(1));
+        assert_eq!(RescheduleChoice::Keep.target(deadline, now), None);
+        assert_eq!(RescheduleChoice::available(deadline, now), RescheduleChoice::ALL.to_vec());
+        
+        // 剩余时间已超过30分钟时不提供"改到30分钟后"
+        let deadline = now + Duration::minutes(45);
+        assert_eq!(RescheduleChoice::available(deadline, now), vec![RescheduleChoice::TomorrowNight, RescheduleChoice::Keep]);
+    }
+    
+    #[test]
+    fn test_warning_offsets() {
+        let offsets = parse_warning_offsets(&DEFAULT_WARNING_OFFSETS).unwrap();
+        assert_eq!(offsets.len(), 5);
+        assert_eq!(offsets[0], Duration::minutes(30));
+        assert_eq!(offsets[4], Duration::seconds(10));
+        assert_eq!(parse_warning_offsets(&["10s", "1h", "10s"]).unwrap(), vec![Duration::hours(1), Duration::seconds(10)]);
+        assert!(parse_warning_offset("5分钟").is_err());
+        assert!(parse_warning_offset("0s").is_err());
+        
+        assert_eq!(format_warning_offset(Duration::minutes(30)), "30分钟");
+        assert_eq!(format_warning_offset(Duration::seconds(90)), "1分30秒");
+        assert_eq!(format_warning_offset(Duration::seconds(10)), "10秒");
+        
+        // 10分钟的倒计时只有5分钟、1分钟和10秒的提醒
+        let now = Local::now();
+        let plan = TaskPlan::resolve(&TimeParser::new(), "10分钟", TaskType::Once, TaskAction::Shutdown, false, now)
+            .unwrap()
+            .with_warning_offsets(&offsets, now);
+        assert_eq!(plan.warnings.len(), 5);
+        assert_eq!(plan.warnings[0].at, now + Duration::minutes(5));
+        assert!(plan.warnings.windows(2).all(|pair| pair[0].at <= pair[1].at));
+    }
+}
@@ -0,0 +1,269 @@
+//! 事件录制模块
+//! 
+//! 调试时在配置中开启`advanced.record_events`后，界面发给核心的事件和核心发出的倒计时更新
+//! 按时间顺序追加到数据目录的`events.jsonl`中，每行一条。`qtshut replay <文件>`用全新的任务存储
+//! 和倒计时重新处理录制的事件并逐条输出倒计时状态，便于排查"倒计时不见了"这类问题
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::core::plan::RescheduleChoice;
+use crate::core::types::{CountdownUpdate, TaskAction, TaskData, TaskType, TimeInput, UIEvent, QUICK_COUNTDOWN_ACTION};
+
+/// 录制文件名
+pub const RECORDING_FILE: &str = "events.jsonl";
+
+/// 录制文件的大小上限（字节），开始录制时超过该大小则重新录制
+const MAX_RECORDING_BYTES: u64 = 5 * 1024 * 1024;
+
+/// 可以回放的界面事件
+/// 
+/// 开始倒计时等事件按录制时的时间换算为任务保存，回放时整体平移到回放的时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum ReplayEvent {
+    /// 添加任务并调度
+    AddTask { task: TaskData },
+    /// 取消倒计时
+    Cancel,
+    /// 暂停倒计时
+    Pause,
+    /// 恢复倒计时
+    Resume,
+    /// 延长倒计时
+    Extend { seconds: u32 },
+    /// 缩短倒计时
+    Shorten { seconds: u32 },
+    /// 改期正在倒计时的任务
+    Reschedule { choice: RescheduleChoice },
+    /// 删除任务
+    RemoveTask { id: String },
+    /// 修改任务
+    UpdateTask { task: TaskData },
+    /// 启用或停用任务
+    SetTaskEnabled { id: String, enabled: bool },
+}
+
+impl ReplayEvent {
+    /// 把界面事件转换为可以回放的事件
+    /// 
+    /// # 参数
+    /// 
+    /// * `event` - 界面事件
+    /// * `now` - 事件发生的时间，用于换算开始倒计时的到点时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 不影响任务和倒计时的事件（如显示窗口）和依赖外部状态的事件（如等待进程退出）返回None
+    pub fn from_ui_event(event: &UIEvent, now: DateTime<Local>) -> Option<Self> {
+        let replay = match event {
+            UIEvent::StartCountdown(time_input, task_type, action) => ReplayEvent::AddTask {
+                task: TaskData::from_time_input(*task_type, time_input.clone(), *action, now).ok()?,
+            },
+            UIEvent::QuickCountdown(duration) => ReplayEvent::AddTask {
+                task: TaskData::from_time_input(TaskType::Once, TimeInput::Duration(*duration), QUICK_COUNTDOWN_ACTION, now).ok()?,
+            },
+            UIEvent::CancelCountdown | UIEvent::CancelCountdownWithPin(_) => ReplayEvent::Cancel,
+            UIEvent::PauseCountdown => ReplayEvent::Pause,
+            UIEvent::ResumeCountdown => ReplayEvent::Resume,
+            UIEvent::ExtendCountdown(seconds) => ReplayEvent::Extend { seconds: *seconds },
+            UIEvent::ShortenCountdown(seconds) => ReplayEvent::Shorten { seconds: *seconds },
+            UIEvent::Reschedule(choice) => ReplayEvent::Reschedule { choice: *choice },
+            UIEvent::RemoveTask(id) => ReplayEvent::RemoveTask { id: id.clone() },
+            UIEvent::UpdateTask(task) => ReplayEvent::UpdateTask { task: task.clone() },
+            UIEvent::SetTaskEnabled(id, enabled) => ReplayEvent::SetTaskEnabled { id: id.clone(), enabled: *enabled },
+            _ => return None,
+        };
+        Some(replay)
+    }
+}
+
+/// 录制的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RecordedKind {
+    /// 开始录制时已保存的任务，回放前先恢复
+    Tasks { tasks: Vec<TaskData> },
+    /// 界面发给核心的事件，`event`为事件的描述
+    Ui { event: String, replay: Option<ReplayEvent> },
+    /// 核心发出的倒计时更新的描述，每秒的进度更新不录制
+    Update { update: String },
+}
+
+/// 录制的一条事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// 发生的时间
+    pub at: DateTime<Local>,
+    /// 录制的内容
+    pub kind: RecordedKind,
+}
+
+/// 把录制的任务平移到回放的时间
+/// 
+/// # 参数
+/// 
+/// * `task` - 录制的任务
+/// * `offset` - 回放时间与录制时间的差
+pub fn shift_task(mut task: TaskData, offset: Duration) -> TaskData {
+    task.target_time = task.target_time.map(|time| time + offset);
+    task
+}
+
+/// 事件录制器
+/// 
+/// 界面事件循环和倒计时更新监听同时写入，写入失败只记录警告
+#[derive(Debug)]
+pub struct EventRecorder {
+    /// 录制文件路径
+    path: PathBuf,
+    /// 录制文件
+    file: Mutex<File>,
+}
+
+impl EventRecorder {
+    /// 开始录制
+    /// 
+    /// # 参数
+    /// 
+    /// * `data_dir` - 数据目录
+    /// * `tasks` - 已保存的任务
+    /// * `now` - 当前时间
+    pub fn start(data_dir: &Path, tasks: Vec<TaskData>, now: DateTime<Local>) -> Result<Self> {
+        let path = data_dir.join(RECORDING_FILE);
+        if fs::metadata(&path).map_or(false, |metadata| metadata.len() > MAX_RECORDING_BYTES) {
+            info!("录制文件超过{}MB，重新录制", MAX_RECORDING_BYTES / 1024 / 1024);
+            fs::remove_file(&path).map_err(|e| anyhow!("删除录制文件失败: {}", e))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| anyhow!("打开录制文件失败: {}", e))?;
+        
+        let recorder = Self { path, file: Mutex::new(file) };
+        recorder.append(&RecordedEvent { at: now, kind: RecordedKind::Tasks { tasks } });
+        Ok(recorder)
+    }
+    
+    /// 录制文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    /// 录制界面事件
+    /// 
+    /// # 参数
+    /// 
+    /// * `event` - 界面事件
+    /// * `now` - 当前时间
+    pub fn record_ui(&self, event: &UIEvent, now: DateTime<Local>) {
+        // PIN不写入文件
+        let description = match event {
+            UIEvent::CancelCountdownWithPin(_) => "CancelCountdownWithPin".to_string(),
+            _ => format!("{:?}", event),
+        };
+        self.append(&RecordedEvent {
+            at: now,
+            kind: RecordedKind::Ui { event: description, replay: ReplayEvent::from_ui_event(event, now) },
+        });
+    }
+    
+    /// 录制倒计时更新，每秒的进度更新不录制
+    /// 
+    /// # 参数
+    /// 
+    /// * `update` - 倒计时更新
+    /// * `now` - 当前时间
+    pub fn record_update(&self, update: &CountdownUpdate, now: DateTime<Local>) {
+        if matches!(update, CountdownUpdate::Progress { .. }) {
+            return;
+        }
+        self.append(&RecordedEvent { at: now, kind: RecordedKind::Update { update: format!("{:?}", update) } });
+    }
+    
+    /// 追加一条记录
+    fn append(&self, event: &RecordedEvent) {
+        let line = match serde_json::to_string(event) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("序列化录制事件失败: {}", e);
+                return;
+            }
+        };
+        let mut file = match self.file.lock() {
+            Ok(file) => file,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(e) = writeln!(file, "{}", line) {
+            warn!("写入录制文件失败: {}", e);
+        }
+    }
+}
+
+/// 读取录制文件
+/// 
+/// 格式错误的行（如写入中断的最后一行）跳过
+/// 
+/// # 参数
+/// 
+/// * `path` - 录制文件路径
+pub fn load_recording(path: &Path) -> Result<Vec<RecordedEvent>> {
+    let content = fs::read_to_string(path).map_err(|e| anyhow!("读取录制文件失败: {}", e))?;
+    let events: Vec<RecordedEvent> = content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(event) => Some(event),
+            Err(e) => {
+                warn!("跳过格式错误的录制事件: {}", e);
+                None
+            }
+        })
+        .collect();
+    if events.is_empty() {
+        return Err(anyhow!("录制文件中没有事件: {:?}", path));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_record_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = Local::now();
+        let recorder = EventRecorder::start(temp_dir.path(), Vec::new(), now).unwrap();
+        recorder.record_ui(&UIEvent::QuickCountdown(Duration::minutes(30)), now);
+        recorder.record_ui(&UIEvent::CancelCountdownWithPin("1234".to_string()), now);
+        recorder.record_ui(&UIEvent::ShowSettings, now);
+        recorder.record_update(&CountdownUpdate::Progress { remaining: Duration::minutes(29), progress: 1.0 }, now);
+        recorder.record_update(&CountdownUpdate::Cancelled, now);
+        
+        let events = load_recording(recorder.path()).unwrap();
+        assert_eq!(events.len(), 5);
+        assert!(matches!(&events[0].kind, RecordedKind::Tasks { tasks } if tasks.is_empty()));
+        match &events[1].kind {
+            RecordedKind::Ui { replay: Some(ReplayEvent::AddTask { task }), .. } => {
+                assert_eq!(task.target_time, Some(now + Duration::minutes(30)));
+                let shifted = shift_task(task.clone(), Duration::hours(1));
+                assert_eq!(shifted.target_time, Some(now + Duration::minutes(90)));
+            },
+            other => panic!("unexpected event: {:?}", other),
+        }
+        // PIN不会写入文件
+        assert!(matches!(&events[2].kind, RecordedKind::Ui { event, replay: Some(ReplayEvent::Cancel) } if event == "CancelCountdownWithPin"));
+        assert!(matches!(&events[3].kind, RecordedKind::Ui { replay: None, .. }));
+        assert!(matches!(&events[4].kind, RecordedKind::Update { update } if update == "Cancelled"));
+        assert!(!fs::read_to_string(recorder.path()).unwrap().contains("1234"));
+    }
+}
@@ -0,0 +1,542 @@
+//! 远程关机模块
+//! 
+//! 局域网中运行QtShut的电脑之间可以互相发送关机命令，例如在自己的电脑上关闭孩子的电脑。
+//! 协议基于TCP，每个连接只处理一条命令：
+//! 
+//! 1. 接收方发送一行 `{"nonce":"..."}`，随机数每个连接都不同
+//! 2. 发送方回复一行 `{"action":"Shutdown","delay_seconds":60,"from":"...","mac":"..."}`，
+//!    `mac`是用双方共同的口令对随机数、操作、延迟和来源计算的HMAC-SHA256
+//! 3. 接收方校验后回复一行 `{"ok":true,"message":"..."}`，命令按普通倒计时执行，
+//!    被关机的电脑上照常显示提醒和最后警告，可以取消
+//! 
+//! 口令不会在网络上传输，截获的请求也无法在其他连接中重放。
+//! 默认只监听本机地址，需要接受其他电脑的命令时把监听地址改为局域网地址或`0.0.0.0`；
+//! 同一地址连续口令错误达到一定次数后暂时拒绝该地址的命令，防止猜测口令
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
+
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::core::plan::FINAL_WARNING_SECONDS;
+use crate::core::types::{TaskAction, UIEvent};
+
+/// 默认监听端口
+pub const DEFAULT_REMOTE_PORT: u16 = 17322;
+
+/// 远程关机默认的延迟秒数，给对方留出看到提醒和取消的时间
+pub const DEFAULT_REMOTE_DELAY_SECONDS: u32 = 60;
+
+/// 单条消息的最大字节数
+const MAX_MESSAGE_BYTES: u64 = 1024;
+
+/// 连接、等待回复的超时时间（秒）
+const REMOTE_TIMEOUT_SECONDS: u64 = 10;
+
+/// 默认监听地址，只接受本机发来的命令
+pub const DEFAULT_REMOTE_BIND_ADDRESS: &str = "127.0.0.1";
+
+/// 同一地址连续口令错误达到该次数后暂时拒绝
+const MAX_AUTH_FAILURES: u32 = 5;
+
+/// 口令错误次数过多后拒绝该地址的时长
+const AUTH_LOCKOUT: StdDuration = StdDuration::from_secs(5 * 60);
+
+/// 远程关机设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    /// 是否接受其他电脑发来的命令
+    pub enabled: bool,
+    /// 监听地址，默认只接受本机的命令，`0.0.0.0`表示接受所有网卡上的命令
+    pub bind_address: String,
+    /// 监听端口
+    pub port: u16,
+    /// 本机口令，发送方需要使用相同的口令，为空时不接受命令
+    pub secret: String,
+    /// 可以关机的其他电脑
+    pub targets: Vec<RemoteTarget>,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: DEFAULT_REMOTE_BIND_ADDRESS.to_string(),
+            port: DEFAULT_REMOTE_PORT,
+            secret: String::new(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// 可以远程关机的电脑
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteTarget {
+    /// 显示名称，如"孩子的电脑"
+    pub name: String,
+    /// 地址，如"192.168.1.20"或"kid-pc:17322"，省略端口时使用默认端口
+    pub address: String,
+    /// 对方设置的口令
+    pub secret: String,
+}
+
+impl RemoteTarget {
+    /// 带端口的连接地址
+    pub fn socket_address(&self) -> String {
+        let address = self.address.trim();
+        if address.rsplit_once(':').map_or(false, |(_, port)| port.parse::<u16>().is_ok()) {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, DEFAULT_REMOTE_PORT)
+        }
+    }
+}
+
+/// 接收方发出的随机数
+#[derive(Debug, Serialize, Deserialize)]
+struct Challenge {
+    nonce: String,
+}
+
+/// 发送方的命令
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteCommand {
+    /// 到点后执行的操作
+    pub action: TaskAction,
+    /// 多少秒后执行
+    pub delay_seconds: u32,
+    /// 发送方的名称，显示在被关机电脑的通知中
+    pub from: String,
+    /// 签名
+    mac: String,
+}
+
+/// 接收方的回复
+#[derive(Debug, Serialize, Deserialize)]
+struct Reply {
+    ok: bool,
+    message: String,
+}
+
+impl RemoteCommand {
+    /// 创建并签名命令
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    /// * `delay_seconds` - 多少秒后执行
+    /// * `from` - 发送方的名称
+    /// * `secret` - 对方的口令
+    /// * `nonce` - 对方发来的随机数
+    /// 
+    /// # 返回值
+    /// 
+    /// 无法用口令计算签名时返回错误
+    pub fn signed(action: TaskAction, delay_seconds: u32, from: &str, secret: &str, nonce: &str) -> Result<Self> {
+        let mut command = Self {
+            action,
+            delay_seconds,
+            from: from.to_string(),
+            mac: String::new(),
+        };
+        command.mac = command.signature(secret, nonce)?;
+        Ok(command)
+    }
+    
+    /// 用口令校验签名
+    /// 
+    /// # 参数
+    /// 
+    /// * `secret` - 本机口令
+    /// * `nonce` - 本次连接发出的随机数
+    pub fn verify(&self, secret: &str, nonce: &str) -> bool {
+        let (Some(expected), Ok(mac)) = (decode_hex(&self.mac), self.mac_for(secret, nonce)) else {
+            return false;
+        };
+        mac.verify_slice(&expected).is_ok()
+    }
+    
+    /// 检查命令是否可以执行
+    /// 
+    /// 不接受运行命令，延迟不能短于最后警告，保证对方有机会取消
+    pub fn validate(&self) -> Result<()> {
+        if self.action == TaskAction::RunCommand {
+            return Err(anyhow!("不接受远程运行命令"));
+        }
+        if i64::from(self.delay_seconds) < FINAL_WARNING_SECONDS {
+            return Err(anyhow!("延迟不能少于{}秒", FINAL_WARNING_SECONDS));
+        }
+        Ok(())
+    }
+    
+    /// 转换为开始倒计时的界面事件
+    pub fn to_event(&self) -> UIEvent {
+        UIEvent::RemoteCommand {
+            action: self.action,
+            delay: chrono::Duration::seconds(i64::from(self.delay_seconds)),
+            from: self.from.clone(),
+        }
+    }
+    
+    /// 计算签名的十六进制文本
+    fn signature(&self, secret: &str, nonce: &str) -> Result<String> {
+        Ok(self.mac_for(secret, nonce)?.finalize().into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+    
+    /// 对随机数和命令内容计算HMAC
+    fn mac_for(&self, secret: &str, nonce: &str) -> Result<Hmac<Sha256>> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|e| anyhow!("无法使用该口令计算签名: {}", e))?;
+        mac.update(format!("{}\n{:?}\n{}\n{}", nonce, self.action, self.delay_seconds, self.from).as_bytes());
+        Ok(mac)
+    }
+}
+
+/// 解析十六进制文本
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| text.get(index..index + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
+
+/// 某个地址的口令错误记录
+#[derive(Debug, Clone, Copy, Default)]
+struct FailureRecord {
+    /// 连续口令错误的次数
+    count: u32,
+    /// 在此之前拒绝该地址的命令
+    locked_until: Option<Instant>,
+}
+
+/// 口令错误限制
+/// 
+/// 按来源地址统计连续口令错误的次数，达到上限后在一段时间内拒绝该地址的命令，
+/// 口令正确或拒绝时间结束后重新计数
+#[derive(Debug, Default)]
+struct AuthThrottle {
+    /// 各地址的口令错误记录
+    failures: HashMap<IpAddr, FailureRecord>,
+}
+
+impl AuthThrottle {
+    /// 该地址当前是否被拒绝
+    /// 
+    /// # 参数
+    /// 
+    /// * `ip` - 来源地址
+    /// * `now` - 当前时刻
+    fn is_locked(&mut self, ip: IpAddr, now: Instant) -> bool {
+        match self.failures.get(&ip).and_then(|record| record.locked_until) {
+            Some(until) if now < until => true,
+            Some(_) => {
+                self.failures.remove(&ip);
+                false
+            },
+            None => false,
+        }
+    }
+    
+    /// 记录一次口令错误
+    /// 
+    /// # 参数
+    /// 
+    /// * `ip` - 来源地址
+    /// * `now` - 当前时刻
+    fn record_failure(&mut self, ip: IpAddr, now: Instant) {
+        let record = self.failures.entry(ip).or_default();
+        record.count += 1;
+        if record.count >= MAX_AUTH_FAILURES {
+            warn!("{}连续{}次口令错误，{}分钟内拒绝该地址的命令", ip, record.count, AUTH_LOCKOUT.as_secs() / 60);
+            record.locked_until = Some(now + AUTH_LOCKOUT);
+        }
+    }
+    
+    /// 口令正确后清除该地址的错误记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `ip` - 来源地址
+    fn record_success(&mut self, ip: IpAddr) {
+        self.failures.remove(&ip);
+    }
+}
+
+/// 远程关机服务
+/// 
+/// 监听局域网中其他电脑发来的命令，校验后交给界面事件循环开始倒计时
+pub struct RemoteServer {
+    /// 监听地址
+    bind_address: String,
+    /// 监听端口
+    port: u16,
+    /// 本机口令
+    secret: String,
+}
+
+impl RemoteServer {
+    /// 创建远程关机服务
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 远程关机设置
+    pub fn new(settings: &RemoteSettings) -> Self {
+        Self {
+            bind_address: settings.bind_address.clone(),
+            port: settings.port,
+            secret: settings.secret.clone(),
+        }
+    }
+    
+    /// 启动服务
+    /// 
+    /// 没有设置口令时不启动，避免局域网中任何人都能关机
+    /// 
+    /// # 参数
+    /// 
+    /// * `ui_event_sender` - 发送开始倒计时事件的UI事件通道
+    pub async fn start(self, ui_event_sender: mpsc::UnboundedSender<UIEvent>) -> Result<()> {
+        if self.secret.trim().is_empty() {
+            return Err(anyhow!("没有设置本机口令"));
+        }
+        let bind_address: IpAddr = self.bind_address.trim().parse()
+            .map_err(|_| anyhow!("无效的监听地址: {}", self.bind_address))?;
+        let listener = TcpListener::bind((bind_address, self.port)).await?;
+        info!("远程关机服务已启动，监听: {}:{}", bind_address, self.port);
+        
+        let throttle = Arc::new(Mutex::new(AuthThrottle::default()));
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let secret = self.secret.clone();
+                        let ui_event_sender = ui_event_sender.clone();
+                        let throttle = throttle.clone();
+                        tokio::spawn(async move {
+                            let handled = tokio::time::timeout(
+                                StdDuration::from_secs(REMOTE_TIMEOUT_SECONDS),
+                                Self::handle_connection(stream, peer.ip(), &secret, &throttle, &ui_event_sender),
+                            ).await;
+                            match handled {
+                                Ok(Ok(())) => {},
+                                Ok(Err(e)) => warn!("处理来自{}的远程命令失败: {}", peer, e),
+                                Err(_) => warn!("来自{}的远程命令超时", peer),
+                            }
+                        });
+                    },
+                    Err(e) => {
+                        error!("远程关机服务接受连接失败: {}", e);
+                        break;
+                    },
+                }
+            }
+        });
+        
+        Ok(())
+    }
+    
+    /// 处理单个连接
+    /// 
+    /// 口令错误次数过多的地址照常完成一次交换，但不校验命令，直接回复拒绝
+    async fn handle_connection(
+        stream: TcpStream,
+        peer: IpAddr,
+        secret: &str,
+        throttle: &Mutex<AuthThrottle>,
+        ui_event_sender: &mpsc::UnboundedSender<UIEvent>,
+    ) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader.take(MAX_MESSAGE_BYTES)).lines();
+        
+        let nonce = uuid::Uuid::new_v4().to_string();
+        write_line(&mut writer, &Challenge { nonce: nonce.clone() }).await?;
+        
+        let line = lines.next_line().await?.ok_or_else(|| anyhow!("连接已关闭"))?;
+        let command: RemoteCommand = serde_json::from_str(&line)?;
+        let result = {
+            let mut throttle = throttle.lock().unwrap_or_else(|e| e.into_inner());
+            if throttle.is_locked(peer, Instant::now()) {
+                Err(anyhow!("口令错误次数过多，请稍后再试"))
+            } else if !command.verify(secret, &nonce) {
+                throttle.record_failure(peer, Instant::now());
+                Err(anyhow!("口令不正确"))
+            } else {
+                throttle.record_success(peer);
+                command.validate()
+            }
+        };
+        
+        let reply = match result {
+            Ok(()) => {
+                info!("收到来自{}的远程命令: {}秒后{}", command.from, command.delay_seconds, command.action);
+                ui_event_sender.send(command.to_event()).map_err(|e| anyhow!("发送远程命令事件失败: {}", e))?;
+                Reply { ok: true, message: format!("{}秒后{}", command.delay_seconds, command.action) }
+            },
+            Err(e) => {
+                warn!("拒绝来自{}的远程命令: {}", command.from, e);
+                Reply { ok: false, message: e.to_string() }
+            },
+        };
+        write_line(&mut writer, &reply).await
+    }
+}
+
+/// 写入一行JSON
+async fn write_line<W, T>(writer: &mut W, message: &T) -> Result<()>
+where
+    W: AsyncWriteExt + Unpin,
+    T: Serialize,
+{
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+/// 向其他电脑发送命令
+/// 
+/// # 参数
+/// 
+/// * `target` - 目标电脑
+/// * `action` - 到点后执行的操作
+/// * `delay_seconds` - 多少秒后执行
+/// * `from` - 本机名称
+/// 
+/// # 返回值
+/// 
+/// 对方接受时返回对方的说明，如"60秒后关机"
+pub async fn send_command(target: &RemoteTarget, action: TaskAction, delay_seconds: u32, from: &str) -> Result<String> {
+    let exchange = async {
+        let stream = TcpStream::connect(target.socket_address()).await?;
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader.take(MAX_MESSAGE_BYTES)).lines();
+        
+        let line = lines.next_line().await?.ok_or_else(|| anyhow!("对方已断开连接"))?;
+        let challenge: Challenge = serde_json::from_str(&line)?;
+        write_line(&mut writer, &RemoteCommand::signed(action, delay_seconds, from, &target.secret, &challenge.nonce)?).await?;
+        
+        let line = lines.next_line().await?.ok_or_else(|| anyhow!("对方已断开连接"))?;
+        let reply: Reply = serde_json::from_str(&line)?;
+        if reply.ok {
+            Ok(reply.message)
+        } else {
+            Err(anyhow!("{}拒绝了命令: {}", target.name, reply.message))
+        }
+    };
+    
+    tokio::time::timeout(StdDuration::from_secs(REMOTE_TIMEOUT_SECONDS), exchange).await
+        .map_err(|_| anyhow!("连接{}超时", target.name))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_signature() {
+        let command = RemoteCommand::signed(TaskAction::Shutdown, 60, "爸爸的电脑", "口令", "nonce-1").unwrap();
+        assert!(command.verify("口令", "nonce-1"));
+        // 口令不同或在其他连接中重放都无法通过
+        assert!(!command.verify("其他口令", "nonce-1"));
+        assert!(!command.verify("口令", "nonce-2"));
+        
+        let mut tampered = command.clone();
+        tampered.action = TaskAction::Restart;
+        assert!(!tampered.verify("口令", "nonce-1"));
+        
+        assert!(command.validate().is_ok());
+        assert!(RemoteCommand::signed(TaskAction::Shutdown, 5, "", "口令", "n").unwrap().validate().is_err());
+        assert!(RemoteCommand::signed(TaskAction::RunCommand, 60, "", "口令", "n").unwrap().validate().is_err());
+    }
+    
+    #[test]
+    fn test_auth_throttle() {
+        let mut throttle = AuthThrottle::default();
+        let peer: IpAddr = "192.168.1.30".parse().unwrap();
+        let other: IpAddr = "192.168.1.31".parse().unwrap();
+        let start = Instant::now();
+        
+        for _ in 0..MAX_AUTH_FAILURES - 1 {
+            throttle.record_failure(peer, start);
+        }
+        assert!(!throttle.is_locked(peer, start));
+        // 口令正确后重新计数
+        throttle.record_success(peer);
+        for _ in 0..MAX_AUTH_FAILURES - 1 {
+            throttle.record_failure(peer, start);
+        }
+        assert!(!throttle.is_locked(peer, start));
+        
+        throttle.record_failure(peer, start);
+        assert!(throttle.is_locked(peer, start + StdDuration::from_secs(60)));
+        assert!(!throttle.is_locked(other, start));
+        // 拒绝时间结束后重新计数
+        assert!(!throttle.is_locked(peer, start + AUTH_LOCKOUT));
+        throttle.record_failure(peer, start + AUTH_LOCKOUT);
+        assert!(!throttle.is_locked(peer, start + AUTH_LOCKOUT));
+    }
+    
+    #[test]
+    fn test_socket_address() {
+        let target = |address: &str| RemoteTarget {
+            name: "孩子的电脑".to_string(),
+            address: address.to_string(),
+            secret: String::new(),
+        };
+        assert_eq!(target("192.168.1.20").socket_address(), "192.168.1.20:17322");
+        assert_eq!(target(" kid-pc:18000 ").socket_address(), "kid-pc:18000");
+    }
+    
+    #[tokio::test]
+    async fn test_send_command() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let settings = RemoteSettings {
+            enabled: true,
+            bind_address: DEFAULT_REMOTE_BIND_ADDRESS.to_string(),
+            port: 17399,
+            secret: "口令".to_string(),
+            targets: Vec::new(),
+        };
+        RemoteServer::new(&settings).start(sender).await.unwrap();
+        
+        let target = RemoteTarget {
+            name: "孩子的电脑".to_string(),
+            address: "127.0.0.1:17399".to_string(),
+            secret: "口令".to_string(),
+        };
+        assert_eq!(send_command(&target, TaskAction::Shutdown, 60, "爸爸的电脑").await.unwrap(), "60秒后关机");
+        assert!(matches!(
+            receiver.recv().await,
+            Some(UIEvent::RemoteCommand { action: TaskAction::Shutdown, from, .. }) if from == "爸爸的电脑"
+        ));
+        
+        let wrong = RemoteTarget { secret: "错误".to_string(), ..target.clone() };
+        assert!(send_command(&wrong, TaskAction::Shutdown, 60, "").await.is_err());
+        
+        // 连续口令错误后正确的口令也被暂时拒绝
+        for _ in 1..MAX_AUTH_FAILURES {
+            assert!(send_command(&wrong, TaskAction::Shutdown, 60, "").await.is_err());
+        }
+        let error = send_command(&target, TaskAction::Shutdown, 60, "爸爸的电脑").await.unwrap_err();
+        assert!(error.to_string().contains("次数过多"));
+        
+        let invalid = RemoteSettings { bind_address: "本机".to_string(), ..settings };
+        assert!(RemoteServer::new(&invalid).start(mpsc::unbounded_channel().0).await.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! 登录任务模块
+//! 
+//! 除了到点关机，还可以在每天第一次登录时执行一组早间步骤：显示今天的计划、调整音量、运行自定义命令。
+//! 登录任务与其他任务保存在同一个任务列表中，由应用启动时检查并执行
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::core::scheduler;
+use crate::core::types::{TaskAction, TaskData};
+
+/// 登录后执行步骤前等待的秒数，让主窗口和托盘先完成初始化
+pub const LOGON_ROUTINE_DELAY_SECONDS: u64 = 5;
+
+/// 登录任务中的一个步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoutineStep {
+    /// 显示今天的计划
+    ShowSchedule,
+    /// 将系统音量调整为指定百分比
+    SetVolume(u8),
+    /// 运行设置中的自定义命令
+    RunCommand,
+}
+
+impl fmt::Display for RoutineStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoutineStep::ShowSchedule => write!(f, "显示今日计划"),
+            RoutineStep::SetVolume(percent) => write!(f, "音量调到{}%", percent),
+            RoutineStep::RunCommand => write!(f, "运行命令"),
+        }
+    }
+}
+
+/// 登录任务的步骤和执行记录
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogonRoutine {
+    /// 按顺序执行的步骤
+    pub steps: Vec<RoutineStep>,
+    /// 上次执行的日期，同一天多次登录只执行一次
+    pub last_run: Option<NaiveDate>,
+}
+
+impl LogonRoutine {
+    /// 今天是否还需要执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `today` - 今天的日期
+    pub fn is_due(&self, today: NaiveDate) -> bool {
+        !self.steps.is_empty() && self.last_run.map_or(true, |date| date < today)
+    }
+    
+    /// 步骤说明，如"显示今日计划、音量调到30%"
+    pub fn describe(&self) -> String {
+        self.steps.iter()
+            .map(|step| step.to_string())
+            .collect::<Vec<_>>()
+            .join("、")
+    }
+}
+
+/// 生成今天剩余计划的说明
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// * `now` - 当前时间
+pub fn today_schedule_text(tasks: &[TaskData], now: DateTime<Local>) -> String {
+    let events: Vec<(DateTime<Local>, TaskAction)> = scheduler::upcoming(tasks, now, usize::MAX)
+        .into_iter()
+        .filter(|(time, _)| time.date_naive() == now.date_naive())
+        .collect();
+    if events.is_empty() {
+        "今天没有计划执行的任务".to_string()
+    } else {
+        format!("今天的计划: {}", scheduler::format_upcoming(&events, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveTime, TimeZone};
+    
+    use crate::core::types::{TaskType, TimeInput};
+    
+    #[test]
+    fn test_is_due() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let mut routine = LogonRoutine {
+            steps: vec![RoutineStep::ShowSchedule, RoutineStep::SetVolume(30)],
+            last_run: None,
+        };
+        assert!(routine.is_due(today));
+        assert_eq!(routine.describe(), "显示今日计划、音量调到30%");
+        
+        // 同一天再次登录不重复执行
+        routine.last_run = Some(today);
+        assert!(!routine.is_due(today));
+        assert!(routine.is_due(today + Duration::days(1)));
+        
+        routine.steps.clear();
+        assert!(!routine.is_due(today + Duration::days(1)));
+    }
+    
+    #[test]
+    fn test_today_schedule_text() {
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 8, 0, 0).unwrap();
+        assert_eq!(today_schedule_text(&[], now), "今天没有计划执行的任务");
+        
+        let tasks = vec![
+            TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(NaiveTime::from_hms_opt(23, 0, 0).unwrap()), TaskAction::Shutdown, now).unwrap(),
+            TaskData::from_time_input(TaskType::Once, TimeInput::DailyTime(NaiveTime::from_hms_opt(12, 0, 0).unwrap()), TaskAction::Lock, now).unwrap(),
+            // 明天的任务不在今天的计划中
+            TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::days(1)), TaskAction::Sleep, now).unwrap(),
+        ];
+        assert_eq!(today_schedule_text(&tasks, now), "今天的计划: 12:00 锁定 · 23:00 关机");
+    }
+}
@@ -0,0 +1,184 @@
+//! 执行时间计算模块
+//! 
+//! 根据任务的计划计算下一次执行的时间和操作。倒计时管理器、任务调度（包括托盘提示中的即将执行事件）
+//! 和日历导出都调用这里的函数，不再各自计算日期。函数只依赖传入的当前时间，便于测试
+//! 
+//! 本地时间因夏令时切换重复或不存在时按`resolve_local_datetime`的规则处理
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime};
+
+use crate::core::types::{resolve_local_datetime, TaskAction, TaskData, TaskType, WeeklySlot};
+
+/// 每周计划向后查找的天数，第7天用于覆盖今天已过去的时间段
+const WEEKLY_LOOKAHEAD_DAYS: i64 = 7;
+
+/// 计算任务的下一次执行时间
+/// 
+/// 单次任务使用目标时间，每日任务、每周计划和Cron计划按当前时间重新计算，
+/// 取消过本次执行的重复任务从跳过的时间之后计算。
+/// 不检查任务是否启用，由调用者决定是否跳过停用的任务
+/// 
+/// # 参数
+/// 
+/// * `task` - 任务数据
+/// * `now` - 当前时间
+/// 
+/// # 返回值
+/// 
+/// 返回晚于当前时间的下一次执行时间和操作，单次任务已过期、没有可执行的时间或是登录任务时返回None
+pub fn next_fire(task: &TaskData, now: DateTime<Local>) -> Option<(DateTime<Local>, TaskAction)> {
+    let now = task.skip_until.map_or(now, |skip_until| skip_until.max(now));
+    match task.task_type {
+        TaskType::Once => task.target_time
+            .filter(|time| *time > now)
+            .map(|time| (time, task.action)),
+        TaskType::Daily => task.daily_time
+            .and_then(|time| next_daily(time, now).ok())
+            .map(|time| (time, task.action)),
+        TaskType::Weekly => next_weekly(&task.weekly_slots, now),
+        TaskType::Cron => task.cron_schedule()
+            .and_then(|schedule| schedule.next_after(now))
+            .map(|time| (time, task.action)),
+        TaskType::Logon => None,
+    }
+}
+
+/// 计算每日时间的下一次发生时间
+/// 
+/// 今天的时间未到时为今天，已到或已过时为明天
+/// 
+/// # 参数
+/// 
+/// * `time` - 每日时间
+/// * `now` - 当前时间
+pub fn next_daily(time: NaiveTime, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let today = now.date_naive();
+    let date = if today.and_time(time) > now.naive_local() {
+        today
+    } else {
+        today + Duration::days(1)
+    };
+    
+    resolve_local_datetime(date.and_time(time))
+}
+
+/// 计算每周时间段的下一次发生时间
+/// 
+/// # 参数
+/// 
+/// * `slots` - 每周计划的时间段
+/// * `now` - 当前时间
+/// 
+/// # 返回值
+/// 
+/// 返回最近一个时间段的执行时间和操作，没有时间段时返回None
+pub fn next_weekly(slots: &[WeeklySlot], now: DateTime<Local>) -> Option<(DateTime<Local>, TaskAction)> {
+    let today = now.date_naive();
+    
+    (0..=WEEKLY_LOOKAHEAD_DAYS)
+        .filter_map(|offset| today.checked_add_signed(Duration::days(offset)))
+        .flat_map(|date| {
+            slots.iter()
+                .filter(move |slot| slot.weekday == date.weekday())
+                .filter_map(move |slot| {
+                    resolve_local_datetime(date.and_time(slot.time))
+                        .ok()
+                        .map(|time| (time, slot.action))
+                })
+        })
+        .filter(|(time, _)| *time > now)
+        .min_by_key(|(time, _)| *time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Weekday};
+    use crate::core::types::TimeInput;
+    
+    /// 2024-06-05（星期三）的本地时间，前后一周没有夏令时切换
+    fn wednesday(hour: u32, minute: u32) -> DateTime<Local> {
+        resolve_local_datetime(NaiveDate::from_ymd_opt(2024, 6, 5).unwrap().and_hms_opt(hour, minute, 0).unwrap()).unwrap()
+    }
+    
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+    
+    fn weekly_task(slots: Vec<WeeklySlot>, now: DateTime<Local>) -> TaskData {
+        let mut task = TaskData::from_time_input(
+            TaskType::Weekly,
+            TimeInput::Weekly { days: vec![Weekday::Mon], time: time(8, 0) },
+            TaskAction::Shutdown,
+            now,
+        ).unwrap();
+        task.weekly_slots = slots;
+        task
+    }
+    
+    #[test]
+    fn test_next_daily_rollover() {
+        let now = wednesday(22, 0);
+        assert_eq!(next_daily(time(22, 30), now).unwrap(), wednesday(22, 30));
+        // 已到或已过的时间顺延到明天
+        assert_eq!(next_daily(time(22, 0), now).unwrap(), wednesday(22, 0) + Duration::days(1));
+        assert_eq!(next_daily(time(7, 0), now).unwrap(), wednesday(7, 0) + Duration::days(1));
+        
+        let task = TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(time(23, 0)), TaskAction::Sleep, now).unwrap();
+        assert_eq!(next_fire(&task, now), Some((wednesday(23, 0), TaskAction::Sleep)));
+        assert_eq!(next_fire(&task, wednesday(23, 0)), Some((wednesday(23, 0) + Duration::days(1), TaskAction::Sleep)));
+        
+        let missing_time = TaskData { daily_time: None, ..task };
+        assert_eq!(next_fire(&missing_time, now), None);
+    }
+    
+    #[test]
+    fn test_next_weekly() {
+        let now = wednesday(12, 0);
+        let slots = vec![
+            WeeklySlot { weekday: Weekday::Fri, time: time(23, 0), action: TaskAction::Shutdown },
+            WeeklySlot { weekday: Weekday::Thu, time: time(22, 0), action: TaskAction::Sleep },
+            WeeklySlot { weekday: Weekday::Wed, time: time(9, 0), action: TaskAction::Lock },
+        ];
+        
+        // 选出最近的时间段和它的操作
+        assert_eq!(next_weekly(&slots, now), Some((wednesday(22, 0) + Duration::days(1), TaskAction::Sleep)));
+        let task = weekly_task(slots.clone(), now);
+        assert_eq!(next_fire(&task, now), next_weekly(&slots, now));
+        
+        // 今天已过去的时间段顺延到下周同一天
+        assert_eq!(next_weekly(&slots[2..], now), Some((wednesday(9, 0) + Duration::days(7), TaskAction::Lock)));
+        
+        // 跨过周末到下周一
+        let monday = vec![WeeklySlot { weekday: Weekday::Mon, time: time(8, 0), action: TaskAction::Shutdown }];
+        assert_eq!(next_weekly(&monday, now), Some((wednesday(8, 0) + Duration::days(5), TaskAction::Shutdown)));
+        
+        assert_eq!(next_weekly(&[], now), None);
+    }
+    
+    #[test]
+    fn test_next_fire_once_cron_logon() {
+        let now = wednesday(12, 0);
+        let once = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::minutes(30)), TaskAction::Restart, now).unwrap();
+        assert_eq!(next_fire(&once, now), Some((wednesday(12, 30), TaskAction::Restart)));
+        // 目标时间已到的单次任务不再执行
+        assert_eq!(next_fire(&once, wednesday(12, 30)), None);
+        
+        let mut cron = TaskData::from_time_input(
+            TaskType::Cron,
+            TimeInput::Cron(crate::core::cron::CronSchedule::parse("0 30 22 * * 1-5").unwrap()),
+            TaskAction::Shutdown,
+            now,
+        ).unwrap();
+        assert_eq!(next_fire(&cron, now), Some((wednesday(22, 30), TaskAction::Shutdown)));
+        // 星期五之后跳过周末
+        let friday_night = wednesday(23, 0) + Duration::days(2);
+        assert_eq!(next_fire(&cron, friday_night), Some((wednesday(22, 30) + Duration::days(5), TaskAction::Shutdown)));
+        cron.cron = Some("无效".to_string());
+        assert_eq!(next_fire(&cron, now), None);
+        
+        let logon = TaskData::logon(Vec::new(), now);
+        assert_eq!(next_fire(&logon, now), None);
+    }
+}
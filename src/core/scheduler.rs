@@ -0,0 +1,736 @@
+//! 任务调度模块
+//! 
+//! 任务存储保存所有定时任务，每个任务有独立的标识、操作和计划，修改后立即写入任务文件；
+//! 调度时从已启用的任务中选出最近要执行的一个交给倒计时管理器
+
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use log::{info, warn};
+
+use crate::core::{
+    routine::RoutineStep,
+    schedule,
+    stats::RunResult,
+    storage::Storage,
+    system_tasks::SystemTaskMirror,
+    types::{TaskAction, TaskData, TaskType},
+};
+
+/// 从任务列表中选出最近要执行的任务
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// * `now` - 当前时间
+/// 
+/// # 返回值
+/// 
+/// 返回下一次执行时间最早的已启用任务，目标时间和操作已更新为下一次执行的值
+pub fn select_next(tasks: &[TaskData], now: DateTime<Local>) -> Option<TaskData> {
+    tasks.iter()
+        .filter(|task| task.enabled)
+        .filter_map(|task| schedule::next_fire(task, now).map(|occurrence| (occurrence, task)))
+        .min_by_key(|((time, _), _)| *time)
+        .map(|((time, action), task)| TaskData {
+            target_time: Some(time),
+            action,
+            ..task.clone()
+        })
+}
+
+/// 托盘提示中最多列出的即将执行事件数
+pub const UPCOMING_LIMIT: usize = 3;
+
+/// 计算即将执行的事件队列
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// * `now` - 当前时间
+/// * `limit` - 最多返回的事件数
+/// 
+/// # 返回值
+/// 
+/// 已启用任务的下一次执行时间和操作，按时间先后排列
+pub fn upcoming(tasks: &[TaskData], now: DateTime<Local>, limit: usize) -> Vec<(DateTime<Local>, TaskAction)> {
+    let mut events: Vec<_> = tasks.iter()
+        .filter(|task| task.enabled)
+        .filter_map(|task| schedule::next_fire(task, now))
+        .collect();
+    events.sort_by_key(|(time, _)| *time);
+    events.truncate(limit);
+    events
+}
+
+/// 生成即将执行事件的一行说明，如"22:30 锁定 · 23:30 关机"
+/// 
+/// 不在今天的事件前加上日期
+/// 
+/// # 参数
+/// 
+/// * `events` - 即将执行的事件
+/// * `now` - 当前时间
+pub fn format_upcoming(events: &[(DateTime<Local>, TaskAction)], now: DateTime<Local>) -> String {
+    events.iter()
+        .map(|(time, action)| {
+            let format = if time.date_naive() == now.date_naive() { "%H:%M" } else { "%m-%d %H:%M" };
+            format!("{} {}", time.format(format), action)
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// 任务列表查询句柄
+/// 
+/// 与任务存储共享任务列表，界面线程可以随时读取，不需要持有任务存储本身
+#[derive(Debug, Clone)]
+pub struct TaskListHandle {
+    /// 任务列表
+    tasks: Arc<RwLock<Vec<TaskData>>>,
+}
+
+impl TaskListHandle {
+    /// 获取所有任务
+    pub fn tasks(&self) -> Vec<TaskData> {
+        read_tasks(&self.tasks).clone()
+    }
+    
+    /// 获取即将执行的事件队列
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    /// * `limit` - 最多返回的事件数
+    pub fn upcoming(&self, now: DateTime<Local>, limit: usize) -> Vec<(DateTime<Local>, TaskAction)> {
+        upcoming(&read_tasks(&self.tasks), now, limit)
+    }
+}
+
+/// 任务存储
+#[derive(Debug)]
+pub struct TaskStore {
+    /// 保存任务的存储
+    storage: Arc<dyn Storage>,
+    /// 任务列表
+    tasks: Arc<RwLock<Vec<TaskData>>>,
+    /// Windows任务计划程序同步器
+    mirror: Option<SystemTaskMirror>,
+}
+
+impl TaskStore {
+    /// 从存储加载任务
+    /// 
+    /// 已过期的单次任务不再加载，任务读取失败时从空列表开始
+    /// 
+    /// # 参数
+    /// 
+    /// * `storage` - 保存任务的存储
+    pub fn load(storage: Arc<dyn Storage>) -> Self {
+        let mut tasks = storage.load_tasks().unwrap_or_else(|e| {
+            warn!("加载任务失败，使用空任务列表: {}", e);
+            Vec::new()
+        });
+        drop_expired(&mut tasks, Local::now());
+        
+        Self {
+            storage,
+            tasks: Arc::new(RwLock::new(tasks)),
+            mirror: None,
+        }
+    }
+    
+    /// 设置任务计划程序同步器，开启同步时立即同步一次
+    /// 
+    /// # 参数
+    /// 
+    /// * `mirror` - 任务计划程序同步器
+    pub fn with_mirror(mut self, mirror: SystemTaskMirror) -> Self {
+        mirror.sync(self.tasks());
+        self.mirror = Some(mirror);
+        self
+    }
+    
+    /// 开启或关闭任务计划程序同步
+    /// 
+    /// # 参数
+    /// 
+    /// * `enabled` - 是否同步
+    pub fn set_mirror_enabled(&self, enabled: bool) {
+        match &self.mirror {
+            Some(mirror) => mirror.set_enabled(enabled, self.tasks()),
+            None => warn!("当前平台不支持同步到任务计划程序"),
+        }
+    }
+    
+    /// 获取任务列表查询句柄
+    pub fn handle(&self) -> TaskListHandle {
+        TaskListHandle {
+            tasks: Arc::clone(&self.tasks),
+        }
+    }
+    
+    /// 获取所有任务
+    pub fn tasks(&self) -> Vec<TaskData> {
+        read_tasks(&self.tasks).clone()
+    }
+    
+    /// 按标识查找任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 任务标识
+    pub fn get(&self, id: &str) -> Option<TaskData> {
+        read_tasks(&self.tasks).iter().find(|task| task.id == id).cloned()
+    }
+    
+    /// 获取最近要执行的任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    pub fn next_task(&self, now: DateTime<Local>) -> Option<TaskData> {
+        select_next(&read_tasks(&self.tasks), now)
+    }
+    
+    /// 添加任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 任务数据
+    pub fn add(&self, task: TaskData) -> Result<()> {
+        info!("添加任务 {}: {}", task.id, task.schedule_text());
+        self.update(|tasks| {
+            tasks.push(task);
+            true
+        })?;
+        Ok(())
+    }
+    
+    /// 删除任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 任务标识
+    /// 
+    /// # 返回值
+    /// 
+    /// 任务存在并已删除时返回true
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        self.update(|tasks| {
+            let count = tasks.len();
+            tasks.retain(|task| task.id != id);
+            tasks.len() < count
+        })
+    }
+    
+    /// 修改任务
+    /// 
+    /// 按任务标识替换原任务的全部内容
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 修改后的任务数据
+    /// 
+    /// # 返回值
+    /// 
+    /// 任务存在并已修改时返回true
+    pub fn replace(&self, task: TaskData) -> Result<bool> {
+        info!("修改任务 {}: {}", task.id, task.schedule_text());
+        self.update(|tasks| match tasks.iter_mut().find(|item| item.id == task.id) {
+            Some(item) => {
+                *item = task;
+                true
+            },
+            None => false,
+        })
+    }
+    
+    /// 保存或删除管理策略要求的每日关机任务
+    /// 
+    /// 策略任务与其他任务一起保存，取消后只跳过本次执行，之后每天仍按时执行；
+    /// 已有的策略任务保留执行统计和跳过的时间，被停用或修改时恢复为策略的设置
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 策略任务的固定标识
+    /// * `forced` - 策略要求的任务，策略没有设置每日关机时为None
+    pub fn apply_policy(&self, id: &str, forced: Option<TaskData>) -> Result<()> {
+        self.update(|tasks| {
+            let index = tasks.iter().position(|task| task.id == id);
+            match (index, forced) {
+                (Some(index), Some(forced)) => {
+                    let current = &tasks[index];
+                    let unchanged = current.enabled
+                        && current.task_type == forced.task_type
+                        && current.daily_time == forced.daily_time
+                        && current.action == forced.action;
+                    if unchanged {
+                        return false;
+                    }
+                    tasks[index] = TaskData {
+                        stats: current.stats.clone(),
+                        skip_until: current.skip_until,
+                        ..forced
+                    };
+                },
+                (Some(index), None) => {
+                    tasks.remove(index);
+                },
+                (None, Some(forced)) => tasks.push(forced),
+                (None, None) => return false,
+            }
+            true
+        })?;
+        Ok(())
+    }
+    
+    /// 启用或停用任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 任务标识
+    /// * `enabled` - 是否启用
+    /// 
+    /// # 返回值
+    /// 
+    /// 任务存在并已修改时返回true
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<bool> {
+        self.update(|tasks| match tasks.iter_mut().find(|task| task.id == id) {
+            Some(task) if task.enabled != enabled => {
+                task.enabled = enabled;
+                true
+            },
+            _ => false,
+        })
+    }
+    
+    /// 记录任务因执行条件不满足而推迟
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 任务标识
+    pub fn record_postpone(&self, id: &str) -> Result<()> {
+        self.update(|tasks| match tasks.iter_mut().find(|task| task.id == id) {
+            Some(task) => {
+                task.stats.record_postpone();
+                true
+            },
+            None => false,
+        })?;
+        Ok(())
+    }
+    
+    /// 记录任务已执行
+    /// 
+    /// 单次任务执行后删除，重复任务记录执行结果并更新为下一次执行时间
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 任务标识
+    /// * `result` - 执行结果
+    /// * `now` - 当前时间
+    pub fn complete(&self, id: &str, result: RunResult, now: DateTime<Local>) -> Result<()> {
+        self.update(|tasks| {
+            let index = match tasks.iter().position(|task| task.id == id) {
+                Some(index) => index,
+                None => return false,
+            };
+            
+            if tasks[index].task_type == TaskType::Once {
+                tasks.remove(index);
+            } else {
+                let next_time = schedule::next_fire(&tasks[index], now).map(|(time, _)| time);
+                tasks[index].target_time = next_time;
+                tasks[index].stats.record_run(result, now);
+            }
+            true
+        })?;
+        Ok(())
+    }
+    
+    /// 记录任务被用户取消
+    /// 
+    /// 单次任务取消后删除，重复任务记录取消并跳过本次执行，从下一次执行时间继续
+    /// 
+    /// # 参数
+    /// 
+    /// * `active` - 正在倒计时的任务，目标时间为本次执行时间
+    /// * `now` - 当前时间
+    pub fn cancel(&self, active: &TaskData, now: DateTime<Local>) -> Result<()> {
+        self.update(|tasks| {
+            let index = match tasks.iter().position(|task| task.id == active.id) {
+                Some(index) => index,
+                None => return false,
+            };
+            
+            if tasks[index].task_type == TaskType::Once {
+                tasks.remove(index);
+            } else {
+                let occurrence = active.target_time.map_or(now, |time| time.max(now));
+                tasks[index].skip_until = Some(occurrence);
+                tasks[index].target_time = schedule::next_fire(&tasks[index], now).map(|(time, _)| time);
+                tasks[index].stats.record_run(RunResult::Cancelled, now);
+            }
+            true
+        })?;
+        Ok(())
+    }
+    
+    /// 保存每天第一次登录时执行的步骤
+    /// 
+    /// 任务列表中只保留一个登录任务，已有时替换其步骤，步骤为空时删除
+    /// 
+    /// # 参数
+    /// 
+    /// * `steps` - 按顺序执行的步骤
+    /// * `now` - 当前时间
+    pub fn save_logon_routine(&self, steps: Vec<RoutineStep>, now: DateTime<Local>) -> Result<()> {
+        self.update(|tasks| {
+            let index = tasks.iter().position(|task| task.task_type == TaskType::Logon);
+            match (index, steps.is_empty()) {
+                (Some(index), true) => {
+                    tasks.remove(index);
+                },
+                (Some(index), false) => {
+                    tasks[index].routine.get_or_insert_with(Default::default).steps = steps;
+                },
+                (None, false) => tasks.push(TaskData::logon(steps, now)),
+                (None, true) => return false,
+            }
+            true
+        })?;
+        Ok(())
+    }
+    
+    /// 取出今天还需要执行的登录任务
+    /// 
+    /// 返回前记录为今天已执行，同一天多次启动时只返回一次
+    /// 
+    /// # 参数
+    /// 
+    /// * `today` - 今天的日期
+    pub fn take_due_logon_routines(&self, today: NaiveDate) -> Result<Vec<TaskData>> {
+        let mut due = Vec::new();
+        self.update(|tasks| {
+            for task in tasks.iter_mut() {
+                let is_due = task.enabled
+                    && task.task_type == TaskType::Logon
+                    && task.routine.as_ref().is_some_and(|routine| routine.is_due(today));
+                if is_due {
+                    due.push(task.clone());
+                    if let Some(routine) = task.routine.as_mut() {
+                        routine.last_run = Some(today);
+                    }
+                }
+            }
+            !due.is_empty()
+        })?;
+        Ok(due)
+    }
+    
+    /// 恢复上一个版本的任务文件并替换当前任务列表
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回恢复后的任务数，已过期的单次任务不计入
+    pub fn restore_previous(&self) -> Result<usize> {
+        let mut restored = self.storage.restore_previous_tasks()?;
+        drop_expired(&mut restored, Local::now());
+        
+        let mut tasks = write_tasks(&self.tasks);
+        *tasks = restored;
+        Ok(tasks.len())
+    }
+    
+    /// 用导入的任务替换当前任务列表
+    /// 
+    /// 替换前的任务文件会先备份，可以再恢复上一个版本
+    /// 
+    /// # 参数
+    /// 
+    /// * `imported` - 导入的任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回替换后的任务数，已过期的单次任务不计入
+    pub fn replace_all(&self, mut imported: Vec<TaskData>) -> Result<usize> {
+        drop_expired(&mut imported, Local::now());
+        let count = imported.len();
+        self.update(move |tasks| {
+            *tasks = imported;
+            true
+        })?;
+        Ok(count)
+    }
+    
+    /// 修改任务列表并保存
+    /// 
+    /// # 参数
+    /// 
+    /// * `modify` - 修改函数，返回是否有修改，没有修改时不写入任务文件
+    fn update<F>(&self, modify: F) -> Result<bool>
+    where
+        F: FnOnce(&mut Vec<TaskData>) -> bool,
+    {
+        let mut tasks = write_tasks(&self.tasks);
+        if !modify(&mut tasks) {
+            return Ok(false);
+        }
+        self.storage.save_tasks(&tasks)?;
+        if let Some(mirror) = &self.mirror {
+            mirror.sync(tasks.clone());
+        }
+        Ok(true)
+    }
+}
+
+/// 去掉已过期的单次任务
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// * `now` - 当前时间
+fn drop_expired(tasks: &mut Vec<TaskData>, now: DateTime<Local>) {
+    let loaded = tasks.len();
+    tasks.retain(|task| task.task_type != TaskType::Once || schedule::next_fire(task, now).is_some());
+    if tasks.len() < loaded {
+        info!("已忽略 {} 个过期的单次任务", loaded - tasks.len());
+    }
+}
+
+/// 读取任务列表
+/// 
+/// 其他线程持有锁时出错不影响任务数据本身，继续使用其中的数据
+fn read_tasks(tasks: &RwLock<Vec<TaskData>>) -> RwLockReadGuard<'_, Vec<TaskData>> {
+    tasks.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 获取任务列表的写锁
+fn write_tasks(tasks: &RwLock<Vec<TaskData>>) -> RwLockWriteGuard<'_, Vec<TaskData>> {
+    tasks.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveTime, TimeZone};
+    use tempfile::TempDir;
+    
+    use crate::core::persistence::TaskPersistence;
+    use crate::core::types::TimeInput;
+    
+    fn once_task(minutes: i64, action: TaskAction) -> TaskData {
+        TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::minutes(minutes)), action, Local::now()).unwrap()
+    }
+    
+    #[test]
+    fn test_select_next() {
+        let now = Local::now();
+        let later = once_task(120, TaskAction::Shutdown);
+        let sooner = once_task(30, TaskAction::Sleep);
+        let mut disabled = once_task(10, TaskAction::Restart);
+        disabled.enabled = false;
+        
+        let next = select_next(&[later.clone(), sooner.clone(), disabled], now).unwrap();
+        assert_eq!(next.id, sooner.id);
+        assert_eq!(next.action, TaskAction::Sleep);
+        
+        // 每日任务按当前时间计算下一次执行时间
+        let mut daily = TaskData::from_time_input(
+            TaskType::Daily,
+            TimeInput::DailyTime(NaiveTime::from_hms_opt(3, 0, 0).unwrap()),
+            TaskAction::Lock,
+            now - Duration::days(3),
+        ).unwrap();
+        daily.target_time = Some(now - Duration::days(2));
+        let next = select_next(&[daily], now).unwrap();
+        assert!(next.target_time.unwrap() > now);
+        
+        assert!(select_next(&[], now).is_none());
+    }
+    
+    #[test]
+    fn test_upcoming() {
+        let now = Local::now();
+        let first = once_task(30, TaskAction::Lock);
+        let second = once_task(90, TaskAction::Shutdown);
+        let third = once_task(120, TaskAction::Sleep);
+        let fourth = once_task(180, TaskAction::Restart);
+        let mut disabled = once_task(10, TaskAction::Logoff);
+        disabled.enabled = false;
+        
+        let tasks = [fourth.clone(), second.clone(), disabled, third.clone(), first.clone()];
+        let events = upcoming(&tasks, now, UPCOMING_LIMIT);
+        assert_eq!(events, vec![
+            (first.target_time.unwrap(), TaskAction::Lock),
+            (second.target_time.unwrap(), TaskAction::Shutdown),
+            (third.target_time.unwrap(), TaskAction::Sleep),
+        ]);
+        
+        assert!(upcoming(&[], now, UPCOMING_LIMIT).is_empty());
+    }
+    
+    #[test]
+    fn test_format_upcoming() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 20, 0, 0).unwrap();
+        let events = [
+            (Local.with_ymd_and_hms(2024, 5, 1, 22, 30, 0).unwrap(), TaskAction::Lock),
+            (Local.with_ymd_and_hms(2024, 5, 1, 23, 30, 0).unwrap(), TaskAction::Shutdown),
+            (Local.with_ymd_and_hms(2024, 5, 2, 7, 0, 0).unwrap(), TaskAction::Restart),
+        ];
+        
+        assert_eq!(format_upcoming(&events, now), "22:30 锁定 · 23:30 关机 · 05-02 07:00 重启");
+        assert_eq!(format_upcoming(&[], now), "");
+    }
+    
+    #[test]
+    fn test_task_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        let handle = store.handle();
+        
+        let first = once_task(60, TaskAction::Shutdown);
+        let second = once_task(20, TaskAction::Sleep);
+        store.add(first.clone()).unwrap();
+        store.add(second.clone()).unwrap();
+        assert_eq!(handle.tasks().len(), 2);
+        assert_eq!(store.next_task(Local::now()).unwrap().id, second.id);
+        
+        assert!(store.set_enabled(&second.id, false).unwrap());
+        assert!(!store.set_enabled(&second.id, false).unwrap());
+        assert_eq!(store.next_task(Local::now()).unwrap().id, first.id);
+        
+        // 执行后删除单次任务
+        store.complete(&first.id, RunResult::Succeeded, Local::now()).unwrap();
+        assert!(store.get(&first.id).is_none());
+        assert!(store.next_task(Local::now()).is_none());
+        
+        // 修改任务保留标识，改为新的计划
+        let mut edited = once_task(5, TaskAction::Lock);
+        edited.id = second.id.clone();
+        assert!(store.replace(edited).unwrap());
+        assert_eq!(store.get(&second.id).unwrap().action, TaskAction::Lock);
+        assert!(!store.replace(once_task(5, TaskAction::Lock)).unwrap());
+        
+        assert!(store.remove(&second.id).unwrap());
+        assert!(!store.remove(&second.id).unwrap());
+        assert!(handle.tasks().is_empty());
+    }
+    
+    #[test]
+    fn test_task_store_persists_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        
+        let mut expired = once_task(60, TaskAction::Shutdown);
+        expired.target_time = Some(Local::now() - Duration::minutes(5));
+        let daily = TaskData::from_time_input(
+            TaskType::Daily,
+            TimeInput::DailyTime(NaiveTime::from_hms_opt(22, 30, 0).unwrap()),
+            TaskAction::Sleep,
+            Local::now(),
+        ).unwrap();
+        persistence.save_tasks(&[expired, daily.clone()]).unwrap();
+        
+        // 过期的单次任务不再加载
+        let store = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        assert_eq!(store.tasks().len(), 1);
+        
+        // 重复任务执行后保留，记录执行统计
+        store.record_postpone(&daily.id).unwrap();
+        store.complete(&daily.id, RunResult::Succeeded, Local::now()).unwrap();
+        
+        // 取消重复任务只跳过本次执行，不停用
+        let now = Local::now();
+        let active = store.next_task(now).unwrap();
+        store.cancel(&active, now).unwrap();
+        let reloaded = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        let task = reloaded.get(&daily.id).unwrap();
+        assert!(task.enabled);
+        assert_eq!(reloaded.next_task(now).unwrap().target_time, Some(active.target_time.unwrap() + Duration::days(1)));
+        assert_eq!(task.action, TaskAction::Sleep);
+        assert_eq!(task.stats.run_count, 1);
+        assert_eq!(task.stats.total_postpones, 1);
+        assert_eq!(task.stats.last_result, Some(RunResult::Cancelled));
+    }
+    
+    #[test]
+    fn test_apply_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        let now = Local::now();
+        let forced = TaskData {
+            id: "policy".to_string(),
+            ..TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(NaiveTime::from_hms_opt(23, 0, 0).unwrap()), TaskAction::Shutdown, now).unwrap()
+        };
+        
+        store.apply_policy("policy", Some(forced.clone())).unwrap();
+        let active = store.next_task(now).unwrap();
+        assert_eq!(active.id, "policy");
+        
+        // 取消后只跳过本次，停用后重新应用策略时恢复启用
+        store.cancel(&active, now).unwrap();
+        store.set_enabled("policy", false).unwrap();
+        store.apply_policy("policy", Some(forced.clone())).unwrap();
+        let task = store.get("policy").unwrap();
+        assert!(task.enabled);
+        assert_eq!(task.stats.last_result, Some(RunResult::Cancelled));
+        assert_eq!(store.next_task(now).unwrap().target_time, Some(active.target_time.unwrap() + Duration::days(1)));
+        
+        // 策略不再要求每日关机时删除
+        store.apply_policy("policy", None).unwrap();
+        assert!(store.tasks().is_empty());
+    }
+    
+    #[test]
+    fn test_logon_routine() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        let now = Local::now();
+        let today = now.date_naive();
+        
+        store.save_logon_routine(vec![RoutineStep::ShowSchedule], now).unwrap();
+        store.save_logon_routine(vec![RoutineStep::SetVolume(30), RoutineStep::RunCommand], now).unwrap();
+        let tasks = store.tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].routine.as_ref().unwrap().steps, vec![RoutineStep::SetVolume(30), RoutineStep::RunCommand]);
+        // 登录任务不参与倒计时调度
+        assert!(store.next_task(now).is_none());
+        
+        // 同一天只执行一次，执行记录写入任务文件
+        assert_eq!(store.take_due_logon_routines(today).unwrap().len(), 1);
+        let reloaded = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        assert!(reloaded.take_due_logon_routines(today).unwrap().is_empty());
+        assert_eq!(reloaded.take_due_logon_routines(today + Duration::days(1)).unwrap().len(), 1);
+        
+        reloaded.save_logon_routine(Vec::new(), now).unwrap();
+        assert!(reloaded.tasks().is_empty());
+    }
+    
+    #[test]
+    fn test_restore_previous() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TaskStore::load(Arc::new(TaskPersistence::with_data_dir(temp_dir.path()).unwrap()));
+        let handle = store.handle();
+        
+        let task = once_task(60, TaskAction::Shutdown);
+        store.add(task.clone()).unwrap();
+        store.remove(&task.id).unwrap();
+        assert!(handle.tasks().is_empty());
+        
+        // 恢复删除前的版本
+        assert_eq!(store.restore_previous().unwrap(), 1);
+        assert_eq!(handle.tasks()[0].id, task.id);
+        
+        // 导入的任务替换当前任务，过期的单次任务不导入
+        let imported = once_task(30, TaskAction::Sleep);
+        let mut expired = once_task(30, TaskAction::Shutdown);
+        expired.target_time = Some(Local::now() - Duration::minutes(30));
+        assert_eq!(store.replace_all(vec![imported.clone(), expired]).unwrap(), 1);
+        assert_eq!(handle.tasks()[0].id, imported.id);
+        assert_eq!(store.restore_previous().unwrap(), 1);
+        assert_eq!(handle.tasks()[0].id, task.id);
+    }
+}
@@ -0,0 +1,330 @@
+//! 屏幕使用时间统计模块
+//! 
+//! 统计当天的有效使用时间，会话锁定或超过一定时间没有键盘鼠标输入时暂停计时
+
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::TaskAction;
+use crate::platform::{self, SessionInfo};
+
+/// 无输入超过该时长视为空闲
+pub const IDLE_THRESHOLD: Duration = Duration::from_secs(5 * 60);
+
+/// 两次采样的最大计入间隔
+/// 
+/// 超过该间隔（例如计算机睡眠后恢复）的时间不计入使用时间
+const MAX_SAMPLE_GAP: Duration = Duration::from_secs(60);
+
+/// 判断输入是否发生在监视开始之后时允许的计时误差
+/// 
+/// 上次输入时间和本地时钟来自不同的计时源，差值小于该误差的输入视为监视开始前的输入
+const INPUT_WATCH_TOLERANCE: Duration = Duration::from_millis(250);
+
+/// 检查使用时间的采样间隔
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 每日使用上限的最大分钟数
+pub const MAX_DAILY_MINUTES: u32 = 24 * 60;
+
+/// 屏幕使用时间限制设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenTimeSettings {
+    /// 每天可以使用的分钟数，为空表示不限制
+    pub daily_minutes: Option<u32>,
+    /// 达到上限后执行的操作
+    pub action: TaskAction,
+}
+
+impl ScreenTimeSettings {
+    /// 获取每日使用上限，未设置时返回空
+    pub fn daily_limit(&self) -> Option<Duration> {
+        self.daily_minutes.map(|minutes| Duration::from_secs(minutes as u64 * 60))
+    }
+}
+
+/// 会话活动状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityState {
+    /// 正在使用
+    Active,
+    /// 输入空闲超过阈值
+    Idle,
+    /// 会话已锁定
+    Locked,
+}
+
+impl ActivityState {
+    /// 根据锁定状态和空闲时长判断活动状态
+    /// 
+    /// # 参数
+    /// 
+    /// * `locked` - 会话是否已锁定
+    /// * `idle_for` - 距离上次输入的时长
+    pub fn classify(locked: bool, idle_for: Duration) -> Self {
+        if locked {
+            ActivityState::Locked
+        } else if idle_for > IDLE_THRESHOLD {
+            ActivityState::Idle
+        } else {
+            ActivityState::Active
+        }
+    }
+    
+    /// 检测当前会话的活动状态
+    pub fn detect() -> Self {
+        Self::classify(is_session_locked(), idle_duration())
+    }
+}
+
+/// 获取距离上次键盘鼠标输入的时长
+pub fn idle_duration() -> Duration {
+    platform::native().idle_duration()
+}
+
+/// 判断当前会话是否已锁定
+pub fn is_session_locked() -> bool {
+    platform::native().is_session_locked()
+}
+
+/// 输入监视
+/// 
+/// 记录开始监视的时刻，之后检测是否有新的键盘鼠标输入，
+/// 用于最后警告期间判断是否还有人在使用电脑
+#[derive(Debug, Clone, Copy)]
+pub struct InputWatch {
+    /// 开始监视的时刻
+    started: Instant,
+}
+
+impl InputWatch {
+    /// 从当前时刻开始监视
+    pub fn start() -> Self {
+        Self::start_at(Instant::now())
+    }
+    
+    /// 从指定时刻开始监视
+    /// 
+    /// # 参数
+    /// 
+    /// * `started` - 开始监视的时刻
+    pub fn start_at(started: Instant) -> Self {
+        Self { started }
+    }
+    
+    /// 开始监视后是否有过输入
+    pub fn has_input(&self) -> bool {
+        self.has_input_at(Instant::now(), idle_duration())
+    }
+    
+    /// 根据采样结果判断开始监视后是否有过输入
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 采样时刻
+    /// * `idle_for` - 采样时距离上次输入的时长
+    pub fn has_input_at(&self, now: Instant, idle_for: Duration) -> bool {
+        let watched = now.saturating_duration_since(self.started);
+        watched > idle_for + INPUT_WATCH_TOLERANCE
+    }
+}
+
+/// 每日使用时间统计器
+/// 
+/// 定期调用`record`采样，只有处于活动状态的时间才会计入
+#[derive(Debug, Clone)]
+pub struct UsageTracker {
+    /// 每日使用上限
+    daily_limit: Duration,
+    /// 统计日期
+    date: NaiveDate,
+    /// 当天已使用时间
+    used: Duration,
+    /// 上次采样时间及状态
+    last_sample: Option<(Instant, ActivityState)>,
+    /// 当天是否已经报告过达到上限
+    limit_reported: bool,
+}
+
+impl UsageTracker {
+    /// 创建使用时间统计器
+    /// 
+    /// # 参数
+    /// 
+    /// * `daily_limit` - 每日使用上限
+    pub fn new(daily_limit: Duration) -> Self {
+        Self {
+            daily_limit,
+            date: Local::now().date_naive(),
+            used: Duration::ZERO,
+            last_sample: None,
+            limit_reported: false,
+        }
+    }
+    
+    /// 修改每日使用上限，已统计的使用时间保持不变
+    /// 
+    /// # 参数
+    /// 
+    /// * `daily_limit` - 每日使用上限
+    pub fn set_daily_limit(&mut self, daily_limit: Duration) {
+        self.daily_limit = daily_limit;
+    }
+    
+    /// 采样当前会话状态并累计使用时间
+    pub fn sample(&mut self) -> ActivityState {
+        let state = ActivityState::detect();
+        self.record(state, Instant::now(), Local::now().date_naive());
+        state
+    }
+    
+    /// 记录一次采样
+    /// 
+    /// 上次采样到本次采样之间的时间只有在上次为活动状态时才计入
+    /// 
+    /// # 参数
+    /// 
+    /// * `state` - 当前活动状态
+    /// * `now` - 采样时刻
+    /// * `today` - 当前日期，跨天时重新统计
+    pub fn record(&mut self, state: ActivityState, now: Instant, today: NaiveDate) {
+        if today != self.date {
+            info!("日期变更，重置屏幕使用时间统计: {}", today);
+            self.date = today;
+            self.used = Duration::ZERO;
+            self.limit_reported = false;
+        } else if let Some((last_time, last_state)) = self.last_sample {
+            let elapsed = now.saturating_duration_since(last_time);
+            if last_state == ActivityState::Active && elapsed <= MAX_SAMPLE_GAP {
+                self.used += elapsed;
+            }
+        }
+        
+        if self.last_sample.map(|(_, last_state)| last_state) != Some(state) {
+            debug!("会话活动状态: {:?}", state);
+        }
+        self.last_sample = Some((now, state));
+    }
+    
+    /// 获取当天已使用时间
+    pub fn used_today(&self) -> Duration {
+        self.used
+    }
+    
+    /// 获取当天剩余可用时间
+    pub fn remaining(&self) -> Duration {
+        self.daily_limit.saturating_sub(self.used)
+    }
+    
+    /// 是否已达到每日上限
+    pub fn is_limit_reached(&self) -> bool {
+        self.used >= self.daily_limit
+    }
+    
+    /// 本次采样是否刚达到每日上限
+    /// 
+    /// 每天只返回一次`true`，避免取消执行后每次采样都再次触发
+    pub fn take_limit_reached(&mut self) -> bool {
+        if self.limit_reported || !self.is_limit_reached() {
+            return false;
+        }
+        self.limit_reported = true;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_classify_activity() {
+        assert_eq!(ActivityState::classify(false, Duration::from_secs(10)), ActivityState::Active);
+        assert_eq!(ActivityState::classify(false, IDLE_THRESHOLD + Duration::from_secs(1)), ActivityState::Idle);
+        assert_eq!(ActivityState::classify(true, Duration::ZERO), ActivityState::Locked);
+    }
+    
+    #[test]
+    fn test_input_watch() {
+        let start = Instant::now();
+        let watch = InputWatch::start_at(start);
+        
+        // 开始监视前就已空闲，之后没有输入
+        assert!(!watch.has_input_at(start + Duration::from_secs(5), Duration::from_secs(600)));
+        // 开始监视时正在输入，之后停止
+        assert!(!watch.has_input_at(start + Duration::from_secs(5), Duration::from_secs(5)));
+        // 开始监视3秒后有输入
+        assert!(watch.has_input_at(start + Duration::from_secs(5), Duration::from_secs(2)));
+    }
+    
+    #[test]
+    fn test_usage_excludes_idle_and_locked() {
+        let today = Local::now().date_naive();
+        let start = Instant::now();
+        let mut tracker = UsageTracker::new(Duration::from_secs(3600));
+        
+        tracker.record(ActivityState::Active, start, today);
+        tracker.record(ActivityState::Locked, start + Duration::from_secs(30), today);
+        tracker.record(ActivityState::Idle, start + Duration::from_secs(60), today);
+        tracker.record(ActivityState::Active, start + Duration::from_secs(90), today);
+        tracker.record(ActivityState::Active, start + Duration::from_secs(120), today);
+        
+        // 只有锁定前和最后一段的活动时间被计入
+        assert_eq!(tracker.used_today(), Duration::from_secs(60));
+        assert_eq!(tracker.remaining(), Duration::from_secs(3540));
+        assert!(!tracker.is_limit_reached());
+    }
+    
+    #[test]
+    fn test_usage_ignores_long_gaps_and_resets_daily() {
+        let today = Local::now().date_naive();
+        let start = Instant::now();
+        let mut tracker = UsageTracker::new(Duration::from_secs(60));
+        
+        tracker.record(ActivityState::Active, start, today);
+        tracker.record(ActivityState::Active, start + Duration::from_secs(60), today);
+        assert!(tracker.is_limit_reached());
+        
+        // 睡眠恢复后的长间隔不计入
+        tracker.record(ActivityState::Active, start + Duration::from_secs(3600), today);
+        assert_eq!(tracker.used_today(), Duration::from_secs(60));
+        
+        tracker.record(ActivityState::Active, start + Duration::from_secs(3630), today.succ_opt().unwrap());
+        assert_eq!(tracker.used_today(), Duration::ZERO);
+    }
+    
+    #[test]
+    fn test_limit_reported_once_per_day() {
+        let today = Local::now().date_naive();
+        let start = Instant::now();
+        let mut tracker = UsageTracker::new(Duration::from_secs(60));
+        
+        tracker.record(ActivityState::Active, start, today);
+        tracker.record(ActivityState::Active, start + Duration::from_secs(30), today);
+        assert!(!tracker.take_limit_reached());
+        tracker.record(ActivityState::Active, start + Duration::from_secs(60), today);
+        assert!(tracker.take_limit_reached());
+        tracker.record(ActivityState::Active, start + Duration::from_secs(90), today);
+        assert!(!tracker.take_limit_reached());
+        
+        // 第二天重新统计，达到上限后再次报告
+        let tomorrow = today.succ_opt().unwrap();
+        tracker.record(ActivityState::Active, start + Duration::from_secs(120), tomorrow);
+        tracker.record(ActivityState::Active, start + Duration::from_secs(180), tomorrow);
+        assert!(tracker.take_limit_reached());
+    }
+    
+    #[test]
+    fn test_screen_time_settings() {
+        let settings = ScreenTimeSettings::default();
+        assert_eq!(settings.daily_limit(), None);
+        assert_eq!(settings.action, TaskAction::Shutdown);
+        
+        let settings = ScreenTimeSettings { daily_minutes: Some(90), action: TaskAction::Lock };
+        assert_eq!(settings.daily_limit(), Some(Duration::from_secs(5400)));
+    }
+}
@@ -6,24 +6,85 @@ use anyhow::{Result, anyhow};
 use log::{info, warn, error};
 use tokio::process::Command as AsyncCommand;
 
-use crate::core::types::{ShutdownMethod, UserPermissions};
+use crate::core::battery::{ActionOverride, BatterySettings};
+use crate::core::types::{CustomCommand, ShutdownMethod, TaskAction, TaskData, UserPermissions};
 use crate::core::system_compat::SystemCompatibility;
+use crate::utils::system;
 
-#[cfg(windows)]
-use winapi::um::winuser::{ExitWindowsEx, EWX_SHUTDOWN, EWX_FORCE};
-#[cfg(windows)]
-use winapi::um::winnt::{TOKEN_ADJUST_PRIVILEGES, TOKEN_QUERY};
-#[cfg(windows)]
-use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
-#[cfg(windows)]
-use winapi::um::securitybaseapi::AdjustTokenPrivileges;
-#[cfg(windows)]
-use winapi::um::winbase::LookupPrivilegeValueW;
-#[cfg(windows)]
-use winapi::shared::minwindef::FALSE;
-use winapi::um::winnt::LUID;
-#[cfg(windows)]
-use winapi::um::winnt::{HANDLE, TOKEN_PRIVILEGES, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED};
+use crate::platform::{self, Capabilities, PowerControl, SessionEnd, SystemInfo};
+
+/// shutdown /a 在没有待执行关机时的退出码（ERROR_NO_SHUTDOWN_IN_PROGRESS）
+const NO_SHUTDOWN_IN_PROGRESS_EXIT_CODE: i32 = 1116;
+
+/// 默认的关机原因模板
+pub const DEFAULT_REASON_TEMPLATE: &str = "QtShut: 定时任务『{task}』将在 {seconds} 秒后执行";
+
+/// 关机原因的最大字符数（shutdown.exe /c 参数的限制）
+const MAX_REASON_LENGTH: usize = 512;
+
+/// 填充关机原因模板
+/// 
+/// 支持的占位符：`{task}` 任务说明、`{action}` 操作名称、`{seconds}` 延迟秒数、
+/// `{time}` 执行时间、`{source}` 任务来源。没有任务时`{task}`为"定时"加操作名称，
+/// 模板为空时使用默认模板
+/// 
+/// # 参数
+/// 
+/// * `template` - 关机原因模板
+/// * `task` - 到点的任务，直接调用关机时为None
+/// * `action` - 操作名称，如"关机"
+/// * `delay_seconds` - 延迟秒数
+pub fn render_reason(template: &str, task: Option<&TaskData>, action: &str, delay_seconds: u32) -> String {
+    let template = if template.trim().is_empty() { DEFAULT_REASON_TEMPLATE } else { template };
+    let task_text = task.map(|task| task.schedule_text()).unwrap_or_else(|| format!("定时{}", action));
+    let time = chrono::Local::now() + chrono::Duration::seconds(delay_seconds as i64);
+    let source = task.map(|task| task.source.to_string()).unwrap_or_default();
+    
+    template
+        .replace("{task}", &task_text)
+        .replace("{action}", action)
+        .replace("{seconds}", &delay_seconds.to_string())
+        .replace("{time}", &time.format("%H:%M:%S").to_string())
+        .replace("{source}", &source)
+        .chars()
+        .take(MAX_REASON_LENGTH)
+        .collect()
+}
+
+/// 关机类操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowerOff {
+    /// 关机
+    Shutdown,
+    /// 重启
+    Restart,
+}
+
+impl PowerOff {
+    /// shutdown.exe 使用的参数
+    fn command_flag(&self) -> &'static str {
+        match self {
+            PowerOff::Shutdown => "/s",
+            PowerOff::Restart => "/r",
+        }
+    }
+    
+    /// 对应的结束会话方式
+    fn session_end(&self) -> SessionEnd {
+        match self {
+            PowerOff::Shutdown => SessionEnd::Shutdown,
+            PowerOff::Restart => SessionEnd::Restart,
+        }
+    }
+    
+    /// 操作名称
+    fn label(&self) -> &'static str {
+        match self {
+            PowerOff::Shutdown => "关机",
+            PowerOff::Restart => "重启",
+        }
+    }
+}
 
 /// 关机执行器
 #[derive(Debug)]
@@ -34,6 +95,12 @@ pub struct ShutdownExecutor {
     system_compatibility: SystemCompatibility,
     /// 用户权限信息
     user_permissions: UserPermissions,
+    /// 系统关机对话框中显示的原因模板
+    reason_template: String,
+    /// 平台支持的功能
+    capabilities: Capabilities,
+    /// 电池供电时的处理设置
+    battery: BatterySettings,
 }
 
 impl ShutdownExecutor {
@@ -43,9 +110,12 @@ impl ShutdownExecutor {
         let mut system_compatibility = SystemCompatibility::new();
         system_compatibility.initialize().await?;
         let user_permissions = Self::check_user_permissions()?;
+        let capabilities = platform::capabilities();
         
-        // 根据系统版本和权限选择最佳关机方法
-        let preferred_method = if user_permissions.can_shutdown {
+        // 根据系统版本和权限选择最佳关机方法，没有shutdown.exe的平台只能使用系统接口
+        let preferred_method = if !capabilities.shutdown_command {
+            ShutdownMethod::WinAPI
+        } else if user_permissions.can_shutdown {
             if let Some(version) = system_compatibility.get_windows_version() {
                 if version.major >= 10 {
                     ShutdownMethod::WinAPI
@@ -65,47 +135,233 @@ impl ShutdownExecutor {
             preferred_method,
             system_compatibility,
             user_permissions,
+            reason_template: DEFAULT_REASON_TEMPLATE.to_string(),
+            capabilities,
+            battery: BatterySettings::default(),
         })
     }
     
+    /// 设置关机原因模板
+    /// 
+    /// # 参数
+    /// 
+    /// * `template` - 关机原因模板，占位符见`render_reason`
+    pub fn set_reason_template(&mut self, template: &str) {
+        self.reason_template = template.to_string();
+    }
+    
+    /// 设置电池供电时的处理方式
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 电池供电时的处理设置
+    pub fn set_battery_settings(&mut self, settings: BatterySettings) {
+        self.battery = settings;
+    }
+    
+    /// 按当前电源状态决定如何执行操作
+    /// 
+    /// 安全模式下取消运行命令；未设置电池相关处理时不查询电源状态；当前平台不能休眠时按原操作执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    pub fn action_override(&self, action: TaskAction) -> ActionOverride {
+        if action == TaskAction::RunCommand && system::is_safe_mode() {
+            return ActionOverride::Cancel("安全模式下不运行自定义命令".to_string());
+        }
+        if !self.battery.is_enabled() {
+            return ActionOverride::Keep;
+        }
+        match self.battery.resolve(action, system::get_power_status().as_ref()) {
+            ActionOverride::Hibernate if !self.capabilities.hibernate => {
+                warn!("当前平台不支持休眠，按原操作执行{}", action);
+                ActionOverride::Keep
+            },
+            other => other,
+        }
+    }
+    
     /// 执行关机操作
     /// 
     /// # 参数
     /// 
     /// * `delay_seconds` - 延迟秒数（0表示立即关机）
     pub async fn shutdown(&self, delay_seconds: u32) -> Result<()> {
-        info!("开始执行关机操作，延迟: {}秒", delay_seconds);
+        self.power_off(PowerOff::Shutdown, delay_seconds, None).await
+    }
+    
+    /// 执行重启操作
+    /// 
+    /// # 参数
+    /// 
+    /// * `delay_seconds` - 延迟秒数（0表示立即重启）
+    pub async fn restart(&self, delay_seconds: u32) -> Result<()> {
+        self.power_off(PowerOff::Restart, delay_seconds, None).await
+    }
+    
+    /// 执行关机或重启，首选方法失败时使用备用方法
+    /// 
+    /// 系统关机对话框中的原因由模板和到点的任务生成
+    async fn power_off(&self, kind: PowerOff, delay_seconds: u32, task: Option<&TaskData>) -> Result<()> {
+        info!("开始执行{}操作，延迟: {}秒", kind.label(), delay_seconds);
+        let reason = render_reason(&self.reason_template, task, kind.label(), delay_seconds);
         
         // 尝试首选方法
-        match self.try_shutdown(self.preferred_method, delay_seconds).await {
+        match self.try_shutdown(self.preferred_method, kind, delay_seconds, &reason).await {
             Ok(_) => {
-                info!("关机命令执行成功");
+                info!("{}命令执行成功", kind.label());
                 Ok(())
             },
             Err(e) => {
-                warn!("首选关机方法失败: {}, 尝试备用方法", e);
+                warn!("首选{}方法失败: {}, 尝试备用方法", kind.label(), e);
                 
                 // 尝试备用方法
                 let backup_method = match self.preferred_method {
+                    ShutdownMethod::WinAPI if !self.capabilities.shutdown_command => {
+                        error!("当前平台没有备用{}方法", kind.label());
+                        return Err(e);
+                    },
                     ShutdownMethod::WinAPI => ShutdownMethod::Command,
                     ShutdownMethod::Command => ShutdownMethod::WinAPI,
                 };
                 
-                self.try_shutdown(backup_method, delay_seconds).await
+                self.try_shutdown(backup_method, kind, delay_seconds, &reason).await
                     .map_err(|backup_err| {
-                        error!("所有关机方法都失败了");
-                        anyhow!("关机失败: 首选方法错误: {}, 备用方法错误: {}", e, backup_err)
+                        error!("所有{}方法都失败了", kind.label());
+                        anyhow!("{}失败: 首选方法错误: {}, 备用方法错误: {}", kind.label(), e, backup_err)
                     })
             }
         }
     }
     
+    /// 执行任务操作
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    pub async fn execute_action(&self, action: TaskAction) -> Result<()> {
+        self.execute(action, None).await
+    }
+    
+    /// 执行任务
+    /// 
+    /// 运行命令操作使用任务中保存的自定义命令，其他操作与`execute_action`相同
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 到点的任务
+    pub async fn execute_task(&self, task: &TaskData) -> Result<()> {
+        match (task.action, &task.custom_command) {
+            (TaskAction::RunCommand, Some(command)) => self.run_custom_command(command),
+            (action, _) => self.execute(action, Some(task)).await,
+        }
+    }
+    
+    /// 执行操作，关机和重启的原因使用任务信息填充
+    /// 
+    /// 先按电池设置调整：使用电池供电时可改为休眠，电量过低时取消并返回错误
+    async fn execute(&self, action: TaskAction, task: Option<&TaskData>) -> Result<()> {
+        match self.action_override(action) {
+            ActionOverride::Keep => {},
+            ActionOverride::Hibernate => {
+                info!("正在使用电池供电，{}改为休眠", action);
+                return self.hibernate();
+            },
+            ActionOverride::Cancel(reason) => return Err(anyhow!("{}，已取消{}", reason, action)),
+        }
+        info!("执行任务操作: {}", action);
+        match action {
+            TaskAction::Shutdown => self.power_off(PowerOff::Shutdown, 0, task).await,
+            TaskAction::Sleep => self.sleep(),
+            TaskAction::Restart => self.power_off(PowerOff::Restart, 0, task).await,
+            TaskAction::Lock => self.lock(),
+            TaskAction::Logoff => self.logoff(),
+            TaskAction::MonitorOff => self.turn_off_monitor(),
+            TaskAction::RunCommand => Err(anyhow!("运行命令需要设置自定义命令")),
+        }
+    }
+    
+    /// 运行自定义命令
+    /// 
+    /// 命令在后台启动，不等待其结束，便于运行耗时较长的备份或脚本
+    /// 
+    /// # 参数
+    /// 
+    /// * `command` - 自定义命令
+    pub fn run_custom_command(&self, command: &CustomCommand) -> Result<()> {
+        if command.is_empty() {
+            return Err(anyhow!("自定义命令未设置程序"));
+        }
+        if system::is_safe_mode() {
+            return Err(anyhow!("安全模式下不运行自定义命令"));
+        }
+        
+        info!("运行自定义命令: {}", command);
+        let mut process = std::process::Command::new(command.program.trim());
+        process.args(&command.args);
+        if let Some(working_dir) = command.working_dir.as_deref().filter(|dir| !dir.trim().is_empty()) {
+            process.current_dir(working_dir.trim());
+        }
+        
+        let child = process.spawn()
+            .map_err(|e| anyhow!("启动自定义命令失败: {}", e))?;
+        info!("自定义命令已启动，进程ID: {}", child.id());
+        Ok(())
+    }
+    
+    /// 关闭显示器
+    /// 
+    /// 只关闭显示器，程序和音乐播放继续运行，移动鼠标或按键后显示器恢复
+    pub fn turn_off_monitor(&self) -> Result<()> {
+        info!("关闭显示器");
+        platform::native().turn_off_monitor()
+            .map_err(|e| anyhow!("关闭显示器失败: {}", e))
+    }
+    
+    /// 锁定计算机
+    pub fn lock(&self) -> Result<()> {
+        info!("锁定计算机");
+        platform::native().lock_workstation()
+            .map_err(|e| anyhow!("锁定计算机失败: {}", e))
+    }
+    
+    /// 注销当前用户
+    /// 
+    /// 与关机不同，注销不强制关闭程序，未保存的工作会提示用户处理
+    pub fn logoff(&self) -> Result<()> {
+        info!("注销当前用户");
+        platform::native().end_session(SessionEnd::Logoff, false)
+            .map_err(|e| anyhow!("注销失败: {}", e))
+    }
+    
+    /// 使计算机进入睡眠状态
+    pub fn sleep(&self) -> Result<()> {
+        info!("进入睡眠");
+        platform::native().suspend()
+            .map_err(|e| anyhow!("进入睡眠失败: {}", e))
+    }
+    
+    /// 使计算机进入休眠状态
+    pub fn hibernate(&self) -> Result<()> {
+        info!("进入休眠");
+        platform::native().hibernate()
+            .map_err(|e| anyhow!("进入休眠失败: {}", e))
+    }
+    
     /// 取消关机操作
     /// 
     /// 尝试取消之前设置的延迟关机
     pub async fn cancel_shutdown(&self) -> Result<()> {
         info!("尝试取消关机操作");
         
+        if !self.capabilities.shutdown_command {
+            platform::native().abort_scheduled_shutdown()
+                .map_err(|e| anyhow!("取消关机失败: {}", e))?;
+            info!("关机操作已取消");
+            return Ok(());
+        }
+        
         // 使用shutdown命令取消
         let output = AsyncCommand::new("shutdown")
             .args(["/a"])
@@ -122,27 +378,81 @@ impl ShutdownExecutor {
         }
     }
     
-    /// 检查是否有待执行的关机任务
+    /// 检查系统中是否有其他程序或手动设置的待执行关机计划
+    /// 
+    /// 只查询状态，不取消该计划，由用户决定保留还是取消
     pub async fn has_pending_shutdown(&self) -> bool {
-        // 通过查询系统状态检查是否有待执行的关机
-        // 这里简化实现，实际可以通过查询系统服务或注册表
-        false
+        platform::native().has_scheduled_shutdown()
     }
     
-    /// 尝试使用指定方法关机
-    async fn try_shutdown(&self, method: ShutdownMethod, delay_seconds: u32) -> Result<()> {
+    /// 取消系统中已有的待执行关机计划
+    /// 
+    /// 用户选择取消检测到的外部关机计划后调用，通过`shutdown /a`取消，
+    /// 避免与QtShut的任务重复触发
+    /// 
+    /// # 返回值
+    /// 
+    /// 存在并已取消外部关机计划返回true，没有待执行的关机计划返回false
+    pub async fn cancel_external_shutdown(&self) -> Result<bool> {
+        info!("取消外部关机计划");
+        
+        if !self.capabilities.shutdown_command {
+            let cancelled = platform::native().abort_scheduled_shutdown()
+                .map_err(|e| anyhow!("取消外部关机计划失败: {}", e))?;
+            if cancelled {
+                warn!("已取消外部关机计划");
+            }
+            return Ok(cancelled);
+        }
+        
+        let output = AsyncCommand::new("shutdown")
+            .args(["/a"])
+            .output()
+            .await
+            .map_err(|e| anyhow!("执行取消关机命令失败: {}", e))?;
+        
+        let cancelled = Self::interpret_abort_exit_code(output.status.code())?;
+        if cancelled {
+            warn!("已取消外部关机计划");
+        } else {
+            info!("没有待执行的外部关机计划");
+        }
+        
+        Ok(cancelled)
+    }
+    
+    /// 解析`shutdown /a`的退出码
+    /// 
+    /// # 参数
+    /// 
+    /// * `exit_code` - 进程退出码
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功取消返回true，没有待执行关机返回false，其他情况返回错误
+    fn interpret_abort_exit_code(exit_code: Option<i32>) -> Result<bool> {
+        match exit_code {
+            Some(0) => Ok(true),
+            Some(NO_SHUTDOWN_IN_PROGRESS_EXIT_CODE) => Ok(false),
+            Some(code) => Err(anyhow!("取消外部关机计划失败，退出码: {}", code)),
+            None => Err(anyhow!("取消外部关机计划失败，进程被终止")),
+        }
+    }
+    
+    /// 尝试使用指定方法关机或重启
+    async fn try_shutdown(&self, method: ShutdownMethod, kind: PowerOff, delay_seconds: u32, reason: &str) -> Result<()> {
         match method {
-            ShutdownMethod::Command => self.shutdown_by_command(delay_seconds).await,
-            ShutdownMethod::WinAPI => self.shutdown_by_winapi(delay_seconds).await,
+            ShutdownMethod::Command => self.shutdown_by_command(kind, delay_seconds, reason).await,
+            ShutdownMethod::WinAPI => self.shutdown_by_winapi(kind, delay_seconds, reason).await,
         }
     }
     
-    /// 使用shutdown.exe命令关机
-    async fn shutdown_by_command(&self, delay_seconds: u32) -> Result<()> {
-        info!("使用shutdown命令关机");
+    /// 使用shutdown.exe命令关机或重启
+    async fn shutdown_by_command(&self, kind: PowerOff, delay_seconds: u32, reason: &str) -> Result<()> {
+        info!("使用shutdown命令{}", kind.label());
         
         let mut cmd = AsyncCommand::new("shutdown");
-        cmd.args(["/s", "/f"]); // /s = 关机, /f = 强制关闭应用程序
+        cmd.args([kind.command_flag(), "/f"]); // /s = 关机, /r = 重启, /f = 强制关闭应用程序
         
         if delay_seconds > 0 {
             cmd.args(["/t", &delay_seconds.to_string()]);
@@ -151,7 +461,7 @@ impl ShutdownExecutor {
         }
         
         // 添加关机消息
-        cmd.args(["/c", "QtShut 定时关机"]);
+        cmd.args(["/c", reason]);
         
         let output = cmd.output().await
             .map_err(|e| anyhow!("执行shutdown命令失败: {}", e))?;
@@ -164,92 +474,22 @@ impl ShutdownExecutor {
         }
     }
     
-    /// 使用Windows API关机
-    #[cfg(windows)]
-    async fn shutdown_by_winapi(&self, delay_seconds: u32) -> Result<()> {
-        info!("使用Windows API关机");
-        
-        // 如果有延迟，先使用命令行方式设置延迟
-        if delay_seconds > 0 {
-            return self.shutdown_by_command(delay_seconds).await;
-        }
+    /// 使用Windows API关机或重启
+    async fn shutdown_by_winapi(&self, kind: PowerOff, delay_seconds: u32, reason: &str) -> Result<()> {
+        info!("使用Windows API{}", kind.label());
+        let platform = platform::native();
         
         // 获取关机权限
-        self.enable_shutdown_privilege()?;
-        
-        // 执行关机
-        let result = unsafe {
-            ExitWindowsEx(EWX_SHUTDOWN | EWX_FORCE, 0)
-        };
+        platform.enable_shutdown_privilege()?;
         
-        if result != 0 {
-            Ok(())
-        } else {
-            Err(anyhow!("Windows API关机失败"))
-        }
-    }
-    
-    /// 非Windows系统的API关机实现
-    #[cfg(not(windows))]
-    async fn shutdown_by_winapi(&self, _delay_seconds: u32) -> Result<()> {
-        Err(anyhow!("Windows API在非Windows系统上不可用"))
-    }
-    
-    /// 启用关机权限
-    #[cfg(windows)]
-    fn enable_shutdown_privilege(&self) -> Result<()> {
-        unsafe {
-            let mut token_handle: HANDLE = std::ptr::null_mut();
-            
-            // 打开进程令牌
-            let result = OpenProcessToken(
-                GetCurrentProcess(),
-                TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
-                &mut token_handle,
-            );
-            
-            if result == FALSE {
-                return Err(anyhow!("无法打开进程令牌"));
-            }
-            
-            // 查找关机权限
-            let mut luid = LUID { LowPart: 0, HighPart: 0 };
-            let privilege_name = "SeShutdownPrivilege\0".encode_utf16().collect::<Vec<u16>>();
-            
-            let result = LookupPrivilegeValueW(
-                std::ptr::null(),
-                privilege_name.as_ptr(),
-                &mut luid,
-            );
-            
-            if result == FALSE {
-                return Err(anyhow!("无法查找关机权限"));
-            }
-            
-            // 调整令牌权限
-            let mut token_privileges = TOKEN_PRIVILEGES {
-                PrivilegeCount: 1,
-                Privileges: [LUID_AND_ATTRIBUTES {
-                    Luid: luid,
-                    Attributes: SE_PRIVILEGE_ENABLED,
-                }],
-            };
-            
-            let result = AdjustTokenPrivileges(
-                token_handle,
-                FALSE,
-                &mut token_privileges,
-                0,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
-            );
-            
-            if result == FALSE {
-                return Err(anyhow!("无法调整令牌权限"));
-            }
-            
-            Ok(())
+        // 有延迟时由系统倒计时，关机对话框中显示原因
+        if delay_seconds > 0 {
+            return platform.schedule_shutdown(reason, delay_seconds, kind == PowerOff::Restart)
+                .map_err(|e| anyhow!("Windows API延迟{}失败: {}", kind.label(), e));
         }
+        
+        platform.end_session(kind.session_end(), true)
+            .map_err(|e| anyhow!("Windows API{}失败: {}", kind.label(), e))
     }
     
     /// 检查用户权限
@@ -257,41 +497,31 @@ impl ShutdownExecutor {
         // 简化的权限检查实现
         // 实际应用中可以通过Windows API检查用户是否有关机权限
         
-        #[cfg(windows)]
-        {
-            // 在Windows上，大多数用户都有关机权限
-            Ok(UserPermissions {
-                can_shutdown: true,
-                is_admin: Self::is_admin(),
-            })
-        }
-        
-        #[cfg(not(windows))]
-        {
-            Ok(UserPermissions {
-                can_shutdown: false,
-                is_admin: false,
-            })
-        }
+        // 大多数用户都有关机权限，由平台决定能否关机
+        Ok(UserPermissions {
+            can_shutdown: platform::capabilities().power_off,
+            is_admin: Self::is_admin(),
+        })
     }
     
     /// 检查是否为管理员权限
-    #[cfg(windows)]
-    fn is_admin() -> bool {
-        // 简化实现，实际可以通过Windows API检查
-        // 这里假设普通用户也可以关机
-        false
-    }
-    
-    #[cfg(not(windows))]
     fn is_admin() -> bool {
-        false
+        platform::native().is_elevated().unwrap_or(false)
     }
     
     /// 验证关机命令是否可用
     pub async fn validate_shutdown_capability(&self) -> Result<()> {
         info!("验证关机功能可用性");
         
+        if !self.capabilities.shutdown_command {
+            return if self.capabilities.power_off {
+                info!("使用系统命令关机");
+                Ok(())
+            } else {
+                Err(anyhow!("当前平台不支持关机"))
+            };
+        }
+        
         // 测试shutdown命令是否存在
         let output = AsyncCommand::new("shutdown")
             .args(["/?"])
@@ -324,6 +554,40 @@ impl ShutdownExecutor {
         )
     }
     
+    /// 演练任务操作
+    /// 
+    /// 检查平台是否支持该操作并获取所需权限，生成关机原因，但不会真正执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 要演练的操作
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回将要使用的方法和关机原因
+    pub async fn dry_run(&self, action: TaskAction) -> Result<String> {
+        info!("演练任务操作: {}", action);
+        
+        if !action.is_supported(&self.capabilities) {
+            return Err(anyhow!("当前平台不支持{}", action));
+        }
+        
+        let kind = match action {
+            TaskAction::Shutdown => PowerOff::Shutdown,
+            TaskAction::Restart => PowerOff::Restart,
+            _ => return Ok(format!("{}可以执行", action)),
+        };
+        
+        self.validate_shutdown_capability().await?;
+        if self.preferred_method == ShutdownMethod::WinAPI {
+            platform::native().enable_shutdown_privilege()
+                .map_err(|e| anyhow!("获取关机权限失败: {}", e))?;
+        }
+        
+        let reason = render_reason(&self.reason_template, None, kind.label(), 60);
+        Ok(format!("{}，关机原因: {}", self.get_shutdown_info(), reason))
+    }
+    
     /// 模拟关机（用于测试）
     /// 
     /// 在测试环境中使用，不会真正关机
@@ -342,6 +606,81 @@ impl ShutdownExecutor {
     }
 }
 
+/// 关机阻止原因管理器
+/// 
+/// 在最后确认阶段注册关机阻止原因，避免其他程序同时发起的系统关机与本程序的关机竞争，
+/// 确认完成或取消后释放
+#[derive(Debug)]
+pub struct ShutdownBlocker {
+    /// 注册阻止原因的窗口句柄
+    window_handle: isize,
+    /// 是否已注册阻止原因
+    active: bool,
+}
+
+impl ShutdownBlocker {
+    /// 创建新的关机阻止原因管理器
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 主窗口句柄（Windows下为HWND）
+    pub fn new(window_handle: isize) -> Self {
+        Self {
+            window_handle,
+            active: false,
+        }
+    }
+    
+    /// 注册关机阻止原因
+    /// 
+    /// 已注册时直接返回成功
+    /// 
+    /// # 参数
+    /// 
+    /// * `reason` - 显示在系统关机界面上的原因
+    pub fn block(&mut self, reason: &str) -> Result<()> {
+        if self.active {
+            return Ok(());
+        }
+        
+        platform::native().block_shutdown(self.window_handle, reason)
+            .map_err(|e| anyhow!("注册关机阻止原因失败: {}", e))?;
+        
+        self.active = true;
+        info!("已注册关机阻止原因: {}", reason);
+        Ok(())
+    }
+    
+    /// 释放关机阻止原因
+    /// 
+    /// 未注册时直接返回成功
+    pub fn release(&mut self) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        
+        self.active = false;
+        platform::native().unblock_shutdown(self.window_handle)
+            .map_err(|e| anyhow!("释放关机阻止原因失败: {}", e))?;
+        
+        info!("已释放关机阻止原因");
+        Ok(())
+    }
+    
+    /// 是否已注册关机阻止原因
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+impl Drop for ShutdownBlocker {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            warn!("{}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,6 +708,36 @@ mod tests {
         assert!(result.is_ok());
     }
     
+    #[tokio::test]
+    async fn test_dry_run() {
+        let executor = ShutdownExecutor::new().await.unwrap();
+        
+        // 运行命令在所有平台上都支持，演练不会执行任何操作
+        assert!(executor.dry_run(TaskAction::RunCommand).await.is_ok());
+        if !platform::capabilities().lock {
+            assert!(executor.dry_run(TaskAction::Lock).await.is_err());
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_action_override() {
+        let mut executor = ShutdownExecutor::new().await.unwrap();
+        
+        // 未设置电池相关处理时总是按原操作执行
+        assert_eq!(executor.action_override(TaskAction::Shutdown), ActionOverride::Keep);
+        
+        executor.set_battery_settings(BatterySettings {
+            hibernate_instead_of_shutdown: true,
+            cancel_below_percent: None,
+        });
+        let expected = match system::get_power_status() {
+            Some(status) if status.on_battery && platform::capabilities().hibernate => ActionOverride::Hibernate,
+            _ => ActionOverride::Keep,
+        };
+        assert_eq!(executor.action_override(TaskAction::Shutdown), expected);
+        assert_eq!(executor.action_override(TaskAction::Lock), ActionOverride::Keep);
+    }
+    
     #[tokio::test]
     async fn test_get_shutdown_info() {
         let executor = ShutdownExecutor::new().await.unwrap();
@@ -376,4 +745,75 @@ mod tests {
         let info = executor.get_shutdown_info();
         assert!(!info.is_empty());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_interpret_abort_exit_code() {
+        assert!(ShutdownExecutor::interpret_abort_exit_code(Some(0)).unwrap());
+        assert!(!ShutdownExecutor::interpret_abort_exit_code(Some(NO_SHUTDOWN_IN_PROGRESS_EXIT_CODE)).unwrap());
+        assert!(ShutdownExecutor::interpret_abort_exit_code(Some(5)).is_err());
+        assert!(ShutdownExecutor::interpret_abort_exit_code(None).is_err());
+    }
+    
+    #[test]
+    fn test_power_off_command_flags() {
+        assert_eq!(PowerOff::Shutdown.command_flag(), "/s");
+        assert_eq!(PowerOff::Restart.command_flag(), "/r");
+        assert_eq!(PowerOff::Restart.label(), "重启");
+    }
+    
+    #[test]
+    fn test_render_reason() {
+        let task = TaskData::from_time_input(
+            crate::core::types::TaskType::Daily,
+            crate::core::types::TimeInput::DailyTime(chrono::NaiveTime::from_hms_opt(22, 30, 0).unwrap()),
+            TaskAction::Shutdown,
+            chrono::Local::now(),
+        ).unwrap();
+        
+        assert_eq!(
+            render_reason(DEFAULT_REASON_TEMPLATE, Some(&task), "关机", 60),
+            format!("QtShut: 定时任务『{}』将在 60 秒后执行", task.schedule_text()),
+        );
+        assert_eq!(render_reason("{action}（{source}）", Some(&task), "关机", 0), "关机（手动设置）");
+        // 没有任务或模板为空时使用默认说明
+        assert_eq!(render_reason("", None, "重启", 30), "QtShut: 定时任务『定时重启』将在 30 秒后执行");
+        assert_eq!(render_reason(&"长".repeat(600), None, "关机", 0).chars().count(), MAX_REASON_LENGTH);
+    }
+    
+    #[test]
+    fn test_shutdown_blocker_release_without_block() {
+        let mut blocker = ShutdownBlocker::new(0);
+        assert!(!blocker.is_active());
+        
+        // 未注册时释放应直接成功
+        assert!(blocker.release().is_ok());
+        assert!(!blocker.is_active());
+        
+        // 无效窗口句柄无法注册阻止原因
+        assert!(blocker.block("测试").is_err());
+        assert!(!blocker.is_active());
+    }
+    
+    #[tokio::test]
+    async fn test_custom_command() {
+        let args = CustomCommand::split_args(r#"--target "D:\备份 目录" -v "" "#);
+        assert_eq!(args, vec!["--target".to_string(), "D:\\备份 目录".to_string(), "-v".to_string(), String::new()]);
+        
+        let command = CustomCommand { program: "robocopy".to_string(), args, working_dir: None };
+        assert_eq!(CustomCommand::split_args(&command.args_text()), command.args);
+        
+        let executor = ShutdownExecutor::new().await.unwrap();
+        assert!(executor.run_custom_command(&CustomCommand::default()).is_err());
+        
+        // 运行命令任务缺少命令时不会执行其他操作
+        let mut task = TaskData::from_time_input(
+            crate::core::types::TaskType::Once,
+            crate::core::types::TimeInput::Duration(chrono::Duration::minutes(1)),
+            TaskAction::RunCommand,
+            chrono::Local::now(),
+        ).unwrap();
+        assert!(executor.execute_task(&task).await.is_err());
+        task.custom_command = Some(CustomCommand::default());
+        assert!(executor.execute_task(&task).await.is_err());
+    }
+}
@@ -0,0 +1,426 @@
+//! SQLite存储模块
+//! 
+//! 编译时启用`sqlite`特性后可以把任务和历史记录保存到数据目录中的`qtshut.db`。
+//! 任务（包括其中的统计数据）和任务备份以JSON保存在各自的行中，历史记录按发生时间建立索引，
+//! 按时间查询时不需要读取全部记录，也不再只保留最近的记录。
+//! 第一次创建数据库时导入已有的`tasks.json`和`history.json`，原文件保留不删除
+//! 
+//! 开启数据目录加密后，每行中的JSON与数据目录中的文件使用相同的方式加密，以二进制值保存
+
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, TimeZone};
+use log::{info, warn};
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, Transaction};
+
+use crate::core::history::HistoryEntry;
+use crate::core::persistence::{is_sealed, open_data, seal_data, TaskPersistence};
+use crate::core::storage::{Storage, TaskBackup};
+use crate::core::types::TaskData;
+use crate::platform::{self, DataProtection};
+
+/// 数据库文件名
+const DATABASE_FILE: &str = "qtshut.db";
+
+/// 按版本顺序排列的数据库结构升级步骤，第n个步骤把版本n升级到版本n+1
+const SCHEMA_UPGRADES: [&str; 2] = [
+    // 创建数据表
+    "
+    CREATE TABLE tasks (position INTEGER PRIMARY KEY, data TEXT NOT NULL);
+    CREATE TABLE task_backups (id INTEGER PRIMARY KEY AUTOINCREMENT, data TEXT NOT NULL);
+    CREATE TABLE history (id INTEGER PRIMARY KEY AUTOINCREMENT, at INTEGER NOT NULL, data TEXT NOT NULL);
+    CREATE INDEX history_at ON history (at);
+    ",
+    // 记录任务备份时间，已有的备份按升级时间记录
+    "
+    ALTER TABLE task_backups ADD COLUMN at INTEGER NOT NULL DEFAULT 0;
+    UPDATE task_backups SET at = CAST(strftime('%s', 'now') AS INTEGER);
+    ",
+];
+
+/// 数据库结构版本，保存在`PRAGMA user_version`中
+const SCHEMA_VERSION: i64 = SCHEMA_UPGRADES.len() as i64;
+
+/// 保存JSON的数据表
+const PAYLOAD_TABLES: [&str; 3] = ["tasks", "task_backups", "history"];
+
+/// SQLite数据库存储
+#[derive(Debug)]
+pub struct SqliteStorage {
+    /// 数据库连接
+    conn: Mutex<Connection>,
+    /// 保留的任务备份数
+    max_backups: usize,
+    /// 是否加密保存每行中的JSON
+    encrypt_data: bool,
+}
+
+impl SqliteStorage {
+    /// 打开数据目录中的数据库，不存在时创建并导入已有的JSON文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `persistence` - 任务持久化管理器，提供数据目录、备份数和要导入的JSON文件
+    /// 
+    /// # 返回值
+    /// 
+    /// 数据库由更新版本的程序创建或无法打开时返回错误
+    pub fn open(persistence: &TaskPersistence) -> Result<Self> {
+        let path = persistence.get_data_dir().join(DATABASE_FILE);
+        let mut conn = Connection::open(&path).map_err(|e| anyhow!("打开数据库失败: {}", e))?;
+        let protection = protection(persistence.encrypt_data());
+        
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(anyhow!(
+                "数据库由更新版本的QtShut创建（结构版本{}，当前程序支持到版本{}），请升级程序后再使用",
+                version, SCHEMA_VERSION,
+            ));
+        }
+        if version < SCHEMA_VERSION {
+            let tx = conn.transaction()?;
+            for upgrade in &SCHEMA_UPGRADES[version as usize..] {
+                tx.execute_batch(upgrade)?;
+            }
+            if version == 0 {
+                let tasks = persistence.load_tasks()?;
+                let history = persistence.load_history()?;
+                insert_tasks(&tx, &tasks, protection)?;
+                insert_history(&tx, &history, protection)?;
+                info!("已创建数据库 {:?}，导入{}个任务和{}条历史记录", path, tasks.len(), history.len());
+            }
+            tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            tx.commit()?;
+        }
+        if let Some(protection) = protection {
+            let tx = conn.transaction()?;
+            seal_plaintext_rows(&tx, protection)?;
+            tx.commit()?;
+        }
+        
+        Ok(Self {
+            conn: Mutex::new(conn),
+            max_backups: persistence.max_backups(),
+            encrypt_data: protection.is_some(),
+        })
+    }
+    
+    /// 获取数据库连接，其他线程持有连接时崩溃不影响继续使用
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// 加密每行中JSON使用的加密接口，未开启加密时为None
+    fn protection(&self) -> Option<&'static dyn DataProtection> {
+        protection(self.encrypt_data)
+    }
+}
+
+/// 加密接口，未开启加密时为None
+fn protection(encrypt_data: bool) -> Option<&'static dyn DataProtection> {
+    if encrypt_data {
+        Some(platform::native())
+    } else {
+        None
+    }
+}
+
+/// 生成要写入数据库的值，加密后以二进制值保存，未加密时以文本保存
+/// 
+/// # 参数
+/// 
+/// * `json_data` - JSON内容
+/// * `protection` - 加密接口，为空时不加密
+fn encode_payload(json_data: &str, protection: Option<&dyn DataProtection>) -> Result<Value> {
+    Ok(match protection {
+        Some(_) => Value::Blob(seal_data(json_data, protection)?),
+        None => Value::Text(json_data.to_string()),
+    })
+}
+
+/// 读取数据库中的值，加密的值先解密，开启加密时拒绝未加密的值
+/// 
+/// # 参数
+/// 
+/// * `value` - 数据库中的值
+/// * `protection` - 加密接口，为空时未开启加密
+fn decode_payload(value: Value, protection: Option<&dyn DataProtection>) -> Result<String> {
+    let data = match value {
+        Value::Text(text) => text.into_bytes(),
+        Value::Blob(data) => data,
+        _ => return Err(anyhow!("数据库中的值类型错误")),
+    };
+    open_data(data, protection.unwrap_or(platform::native()), protection.is_some())
+}
+
+/// 开启加密后加密以前以明文保存的行
+fn seal_plaintext_rows(tx: &Transaction, protection: &dyn DataProtection) -> Result<()> {
+    for table in PAYLOAD_TABLES {
+        let rows = tx.prepare(&format!("SELECT rowid, data FROM {}", table))?
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Value>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut count = 0;
+        for (rowid, value) in rows {
+            let Value::Text(json_data) = value else {
+                continue;
+            };
+            if is_sealed(json_data.as_bytes()) {
+                continue;
+            }
+            let sealed = encode_payload(&json_data, Some(protection))?;
+            tx.execute(&format!("UPDATE {} SET data = ?1 WHERE rowid = ?2", table), params![sealed, rowid])?;
+            count += 1;
+        }
+        if count > 0 {
+            info!("已加密数据表{}中以明文保存的{}行", table, count);
+        }
+    }
+    Ok(())
+}
+
+/// 读取当前的所有任务
+fn select_tasks(conn: &Connection, protection: Option<&dyn DataProtection>) -> Result<Vec<TaskData>> {
+    let mut stmt = conn.prepare("SELECT data FROM tasks ORDER BY position")?;
+    let rows = stmt.query_map([], |row| row.get::<_, Value>(0))?;
+    rows.map(|data| -> Result<TaskData> {
+        serde_json::from_str(&decode_payload(data?, protection)?).map_err(|e| anyhow!("任务格式错误: {}", e))
+    })
+    .collect()
+}
+
+/// 按顺序写入任务
+fn insert_tasks(tx: &Transaction, tasks: &[TaskData], protection: Option<&dyn DataProtection>) -> Result<()> {
+    let mut stmt = tx.prepare("INSERT INTO tasks (position, data) VALUES (?1, ?2)")?;
+    for (position, task) in tasks.iter().enumerate() {
+        stmt.execute(params![position as i64, encode_payload(&serde_json::to_string(task)?, protection)?])?;
+    }
+    Ok(())
+}
+
+/// 按顺序写入历史记录
+fn insert_history(tx: &Transaction, entries: &[HistoryEntry], protection: Option<&dyn DataProtection>) -> Result<()> {
+    let mut stmt = tx.prepare("INSERT INTO history (at, data) VALUES (?1, ?2)")?;
+    for entry in entries {
+        stmt.execute(params![entry.at.timestamp(), encode_payload(&serde_json::to_string(entry)?, protection)?])?;
+    }
+    Ok(())
+}
+
+/// 解析查询到的历史记录
+fn parse_history(rows: impl Iterator<Item = rusqlite::Result<Value>>, protection: Option<&dyn DataProtection>) -> Result<Vec<HistoryEntry>> {
+    rows.map(|data| -> Result<HistoryEntry> {
+        serde_json::from_str(&decode_payload(data?, protection)?).map_err(|e| anyhow!("历史记录格式错误: {}", e))
+    })
+    .collect()
+}
+
+/// 解析任务备份
+fn parse_backup(data: Value, protection: Option<&dyn DataProtection>) -> Result<Vec<TaskData>> {
+    serde_json::from_str(&decode_payload(data, protection)?).map_err(|e| anyhow!("任务备份格式错误: {}", e))
+}
+
+impl Storage for SqliteStorage {
+    fn load_tasks(&self) -> Result<Vec<TaskData>> {
+        select_tasks(&self.conn(), self.protection())
+    }
+    
+    fn save_tasks(&self, tasks: &[TaskData]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        if self.max_backups > 0 {
+            let current = encode_payload(&serde_json::to_string(&select_tasks(&tx, self.protection())?)?, self.protection())?;
+            tx.execute("INSERT INTO task_backups (at, data) VALUES (?1, ?2)", params![Local::now().timestamp(), current])?;
+            tx.execute(
+                "DELETE FROM task_backups WHERE id NOT IN (SELECT id FROM task_backups ORDER BY id DESC LIMIT ?1)",
+                params![self.max_backups as i64],
+            )?;
+        }
+        tx.execute("DELETE FROM tasks", [])?;
+        insert_tasks(&tx, tasks, self.protection())?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    fn restore_previous_tasks(&self) -> Result<Vec<TaskData>> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let (id, data): (i64, Value) = tx
+            .query_row("SELECT id, data FROM task_backups ORDER BY id DESC LIMIT 1", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|_| anyhow!("没有可以恢复的任务备份"))?;
+        let tasks = parse_backup(data, self.protection())?;
+        
+        tx.execute("DELETE FROM tasks", [])?;
+        insert_tasks(&tx, &tasks, self.protection())?;
+        tx.execute("DELETE FROM task_backups WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        info!("已从数据库恢复任务备份，共{}个任务", tasks.len());
+        Ok(tasks)
+    }
+    
+    fn task_backups(&self) -> Vec<TaskBackup> {
+        let conn = self.conn();
+        let backups = conn.prepare("SELECT id, at FROM task_backups ORDER BY id DESC").and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        });
+        match backups {
+            Ok(backups) => backups.into_iter()
+                .filter_map(|(id, at)| Some(TaskBackup { id: id.to_string(), at: Local.timestamp_opt(at, 0).single()? }))
+                .collect(),
+            Err(e) => {
+                warn!("读取任务备份列表失败: {}", e);
+                Vec::new()
+            }
+        }
+    }
+    
+    fn load_task_backup(&self, id: &str) -> Result<Vec<TaskData>> {
+        let id: i64 = id.parse().map_err(|_| anyhow!("任务备份不存在: {}", id))?;
+        let data: Value = self.conn()
+            .query_row("SELECT data FROM task_backups WHERE id = ?1", params![id], |row| row.get(0))
+            .map_err(|_| anyhow!("任务备份不存在: {}", id))?;
+        parse_backup(data, self.protection())
+    }
+    
+    fn load_history(&self) -> Result<Vec<HistoryEntry>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT data FROM history ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, Value>(0))?;
+        parse_history(rows, self.protection())
+    }
+    
+    fn save_history(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM history", [])?;
+        insert_history(&tx, entries, self.protection())?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    fn append_history(&self, entry: HistoryEntry) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO history (at, data) VALUES (?1, ?2)",
+            params![entry.at.timestamp(), encode_payload(&serde_json::to_string(&entry)?, self.protection())?],
+        )?;
+        Ok(())
+    }
+    
+    fn history_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<HistoryEntry>> {
+        let conn = self.conn();
+        // 数据库中的时间精确到秒，边界上的记录再按完整时间筛选
+        let mut stmt = conn.prepare("SELECT data FROM history WHERE at >= ?1 AND at <= ?2 ORDER BY id")?;
+        let rows = stmt.query_map(params![from.timestamp(), to.timestamp()], |row| row.get::<_, Value>(0))?;
+        Ok(parse_history(rows, self.protection())?
+            .into_iter()
+            .filter(|entry| entry.at >= from && entry.at < to)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{TaskAction, TaskType, TimeInput};
+    use tempfile::TempDir;
+    
+    fn create_task(now: DateTime<Local>) -> TaskData {
+        TaskData::from_time_input(TaskType::Once, TimeInput::Duration(chrono::Duration::minutes(30)), TaskAction::Shutdown, now).unwrap()
+    }
+    
+    #[test]
+    fn test_import_json_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        let now = Local::now();
+        let task = create_task(now);
+        persistence.save_tasks(std::slice::from_ref(&task)).unwrap();
+        persistence.save_history(&[HistoryEntry::executed(&task, now)]).unwrap();
+        
+        let storage = SqliteStorage::open(&persistence).unwrap();
+        assert_eq!(storage.load_tasks().unwrap()[0].id, task.id);
+        assert_eq!(storage.load_history().unwrap().len(), 1);
+        
+        // 已有数据库时不重复导入，JSON文件保留
+        persistence.save_tasks(&[]).unwrap();
+        drop(storage);
+        let storage = SqliteStorage::open(&persistence).unwrap();
+        assert_eq!(storage.load_tasks().unwrap()[0].id, task.id);
+        assert!(temp_dir.path().join("tasks.json").exists());
+    }
+    
+    #[test]
+    fn test_tasks_and_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SqliteStorage::open(&TaskPersistence::with_data_dir(temp_dir.path()).unwrap()).unwrap();
+        let task = create_task(Local::now());
+        assert_eq!(storage.add_tasks(std::slice::from_ref(&task)).unwrap(), 1);
+        assert_eq!(storage.add_tasks(std::slice::from_ref(&task)).unwrap(), 2);
+        let backups = storage.task_backups();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(storage.load_task_backup(&backups[0].id).unwrap().len(), 1);
+        assert!(storage.load_task_backup("tasks.json").is_err());
+        
+        assert_eq!(storage.restore_previous_tasks().unwrap().len(), 1);
+        assert_eq!(storage.load_tasks().unwrap().len(), 1);
+        assert!(storage.restore_previous_tasks().unwrap().is_empty());
+        assert!(storage.restore_previous_tasks().is_err());
+    }
+    
+    #[test]
+    fn test_history_between() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SqliteStorage::open(&TaskPersistence::with_data_dir(temp_dir.path()).unwrap()).unwrap();
+        let now = Local::now();
+        let task = create_task(now);
+        storage.append_history(HistoryEntry::executed(&task, now - chrono::Duration::days(40))).unwrap();
+        storage.append_history(HistoryEntry::cancelled(Some(&task), now)).unwrap();
+        
+        let recent = storage.history_between(now - chrono::Duration::days(30), now + chrono::Duration::seconds(1)).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].at, now);
+        assert!(storage.history_between(now - chrono::Duration::days(30), now).unwrap().is_empty());
+        
+        storage.save_history(&recent).unwrap();
+        assert_eq!(storage.load_history().unwrap(), recent);
+    }
+    
+    /// 把每个字节取反的测试用加密接口
+    struct InvertProtection;
+    
+    impl DataProtection for InvertProtection {
+        fn protect_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|byte| !byte).collect())
+        }
+        
+        fn unprotect_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.protect_data(data)
+        }
+    }
+    
+    #[test]
+    fn test_encrypted_payload() {
+        let json_data = r#"{"action":"Shutdown"}"#;
+        let sealed = encode_payload(json_data, Some(&InvertProtection)).unwrap();
+        assert!(matches!(&sealed, Value::Blob(data) if !String::from_utf8_lossy(data).contains("Shutdown")));
+        assert_eq!(decode_payload(sealed, Some(&InvertProtection)).unwrap(), json_data);
+        
+        // 未开启加密时以文本保存，开启加密后拒绝读取未加密的值
+        let plain = encode_payload(json_data, None).unwrap();
+        assert_eq!(plain, Value::Text(json_data.to_string()));
+        assert_eq!(decode_payload(plain.clone(), None).unwrap(), json_data);
+        assert!(decode_payload(plain, Some(&InvertProtection)).is_err());
+        
+        // 开启加密后已有的明文行被加密
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SqliteStorage::open(&TaskPersistence::with_data_dir(temp_dir.path()).unwrap()).unwrap();
+        storage.save_tasks(&[create_task(Local::now())]).unwrap();
+        let mut conn = storage.conn();
+        let tx = conn.transaction().unwrap();
+        seal_plaintext_rows(&tx, &InvertProtection).unwrap();
+        assert_eq!(select_tasks(&tx, Some(&InvertProtection)).unwrap().len(), 1);
+        assert!(select_tasks(&tx, None).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! 任务执行统计模块
+//! 
+//! 重复任务记录执行次数、上次执行的时间和结果以及平均推迟次数，
+//! 随任务一起保存在任务文件中，显示在任务列表里
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 任务一次到点后的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunResult {
+    /// 已执行
+    Succeeded,
+    /// 执行失败
+    Failed,
+    /// 因电量过低等原因跳过本次执行
+    Skipped,
+    /// 被用户取消
+    Cancelled,
+    /// 到点前系统因其他原因关机、重启或注销
+    Interrupted,
+}
+
+impl fmt::Display for RunResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunResult::Succeeded => write!(f, "成功"),
+            RunResult::Failed => write!(f, "失败"),
+            RunResult::Skipped => write!(f, "已跳过"),
+            RunResult::Cancelled => write!(f, "已取消"),
+            RunResult::Interrupted => write!(f, "被系统关机打断"),
+        }
+    }
+}
+
+/// 任务执行统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskStats {
+    /// 到点执行的次数，包括失败和跳过，不包括取消和被系统关机打断
+    pub run_count: u32,
+    /// 上次执行或取消的时间
+    pub last_run: Option<DateTime<Local>>,
+    /// 上次执行或取消的结果
+    pub last_result: Option<RunResult>,
+    /// 已执行的各次累计推迟的次数
+    pub total_postpones: u32,
+    /// 本次到点后已推迟的次数，执行后计入累计，取消后清零
+    pub pending_postpones: u32,
+}
+
+impl TaskStats {
+    /// 记录一次因执行条件不满足而推迟
+    pub fn record_postpone(&mut self) {
+        self.pending_postpones += 1;
+    }
+    
+    /// 记录一次执行或取消的结果
+    /// 
+    /// # 参数
+    /// 
+    /// * `result` - 执行结果
+    /// * `now` - 当前时间
+    pub fn record_run(&mut self, result: RunResult, now: DateTime<Local>) {
+        let postpones = std::mem::take(&mut self.pending_postpones);
+        if !matches!(result, RunResult::Cancelled | RunResult::Interrupted) {
+            self.run_count += 1;
+            self.total_postpones += postpones;
+        }
+        self.last_run = Some(now);
+        self.last_result = Some(result);
+    }
+    
+    /// 平均每次执行前推迟的次数
+    pub fn average_postpones(&self) -> f64 {
+        if self.run_count == 0 {
+            0.0
+        } else {
+            f64::from(self.total_postpones) / f64::from(self.run_count)
+        }
+    }
+    
+    /// 统计说明，如"已执行12次，上次 05-06 23:00 成功，平均推迟0.5次"
+    /// 
+    /// # 返回值
+    /// 
+    /// 从未执行或取消过时返回None
+    pub fn summary(&self) -> Option<String> {
+        let (last_run, last_result) = self.last_run.zip(self.last_result)?;
+        let mut text = format!(
+            "已执行{}次，上次 {} {}",
+            self.run_count,
+            last_run.format("%m-%d %H:%M"),
+            last_result,
+        );
+        if self.total_postpones > 0 {
+            text.push_str(&format!("，平均推迟{:.1}次", self.average_postpones()));
+        }
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_record_run() {
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 23, 0, 0).unwrap();
+        let mut stats = TaskStats::default();
+        assert_eq!(stats.summary(), None);
+        
+        stats.record_postpone();
+        stats.record_postpone();
+        stats.record_run(RunResult::Succeeded, now);
+        stats.record_run(RunResult::Failed, now);
+        assert_eq!(stats.run_count, 2);
+        assert_eq!(stats.average_postpones(), 1.0);
+        
+        // 取消不计入执行次数，推迟次数也不累计
+        stats.record_postpone();
+        stats.record_run(RunResult::Cancelled, now);
+        assert_eq!(stats.run_count, 2);
+        assert_eq!(stats.total_postpones, 2);
+        assert_eq!(stats.pending_postpones, 0);
+        assert_eq!(stats.summary().unwrap(), "已执行2次，上次 05-06 23:00 已取消，平均推迟1.0次");
+        
+        stats.record_run(RunResult::Interrupted, now);
+        assert_eq!(stats.run_count, 2);
+        assert_eq!(stats.last_result, Some(RunResult::Interrupted));
+    }
+}
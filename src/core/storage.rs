@@ -0,0 +1,194 @@
+//! 数据存储模块
+//! 
+//! 任务和历史记录通过`Storage`接口读写。默认保存为数据目录中的JSON文件（`TaskPersistence`）；
+//! 编译时启用`sqlite`特性并在配置中把`advanced.storage`设为`"sqlite"`后，改用数据目录中的SQLite数据库，
+//! 第一次打开时自动导入已有的`tasks.json`和`history.json`，按时间查询历史记录时不需要读取全部记录
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::core::history::{HistoryEntry, MAX_HISTORY_ENTRIES};
+use crate::core::persistence::TaskPersistence;
+use crate::core::types::TaskData;
+
+/// 存储方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// 数据目录中的JSON文件
+    #[default]
+    Json,
+    /// 数据目录中的SQLite数据库，需要编译时启用`sqlite`特性
+    Sqlite,
+}
+
+/// 一份任务备份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskBackup {
+    /// 备份标识，JSON文件为备份文件名，数据库中为备份的行号
+    pub id: String,
+    /// 备份时间
+    pub at: DateTime<Local>,
+}
+
+/// 任务和历史记录的存储
+pub trait Storage: fmt::Debug + Send + Sync {
+    /// 加载所有任务，没有保存过任务时返回空列表
+    fn load_tasks(&self) -> Result<Vec<TaskData>>;
+    
+    /// 保存所有任务，保存前备份当前的任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 要保存的任务列表
+    fn save_tasks(&self, tasks: &[TaskData]) -> Result<()>;
+    
+    /// 恢复最近一次备份的任务，并删除该备份
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回恢复后的所有任务，没有备份时返回错误
+    fn restore_previous_tasks(&self) -> Result<Vec<TaskData>>;
+    
+    /// 列出可以恢复的任务备份
+    /// 
+    /// # 返回值
+    /// 
+    /// 按时间从新到旧排列的备份
+    fn task_backups(&self) -> Vec<TaskBackup>;
+    
+    /// 读取一份任务备份，不修改当前任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `id` - 备份标识
+    fn load_task_backup(&self, id: &str) -> Result<Vec<TaskData>>;
+    
+    /// 追加任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `new_tasks` - 要追加的任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回追加后的任务总数
+    fn add_tasks(&self, new_tasks: &[TaskData]) -> Result<usize> {
+        let mut tasks = self.load_tasks()?;
+        tasks.extend_from_slice(new_tasks);
+        self.save_tasks(&tasks)?;
+        Ok(tasks.len())
+    }
+    
+    /// 加载所有历史记录，按时间先后排列
+    fn load_history(&self) -> Result<Vec<HistoryEntry>>;
+    
+    /// 用新的历史记录替换全部历史记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `entries` - 按时间先后排列的历史记录
+    fn save_history(&self, entries: &[HistoryEntry]) -> Result<()>;
+    
+    /// 追加一条历史记录
+    /// 
+    /// 默认实现只保留最近的记录，历史记录损坏时重新开始记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `entry` - 历史记录
+    fn append_history(&self, entry: HistoryEntry) -> Result<()> {
+        let mut entries = self.load_history().unwrap_or_else(|e| {
+            warn!("加载历史记录失败，重新开始记录: {}", e);
+            Vec::new()
+        });
+        entries.push(entry);
+        if entries.len() > MAX_HISTORY_ENTRIES {
+            entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+        }
+        self.save_history(&entries)
+    }
+    
+    /// 查询一段时间内的历史记录
+    /// 
+    /// # 参数
+    /// 
+    /// * `from` - 开始时间（包含）
+    /// * `to` - 结束时间（不包含）
+    fn history_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<HistoryEntry>> {
+        Ok(self.load_history()?
+            .into_iter()
+            .filter(|entry| entry.at >= from && entry.at < to)
+            .collect())
+    }
+}
+
+/// 按设置打开存储
+/// 
+/// SQLite数据库打开失败或编译时没有启用`sqlite`特性时使用JSON文件
+/// 
+/// # 参数
+/// 
+/// * `persistence` - 任务持久化管理器，JSON文件和数据库都保存在其数据目录中
+/// * `backend` - 存储方式
+pub fn open(persistence: TaskPersistence, backend: StorageBackend) -> Arc<dyn Storage> {
+    match backend {
+        StorageBackend::Json => Arc::new(persistence),
+        StorageBackend::Sqlite => open_sqlite(persistence),
+    }
+}
+
+/// 打开SQLite数据库
+#[cfg(feature = "sqlite")]
+fn open_sqlite(persistence: TaskPersistence) -> Arc<dyn Storage> {
+    match crate::core::sqlite_storage::SqliteStorage::open(&persistence) {
+        Ok(storage) => Arc::new(storage),
+        Err(e) => {
+            log::error!("打开数据库失败，继续使用JSON文件: {}", e);
+            Arc::new(persistence)
+        }
+    }
+}
+
+/// 没有启用`sqlite`特性时继续使用JSON文件
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite(persistence: TaskPersistence) -> Arc<dyn Storage> {
+    warn!("当前版本编译时没有启用SQLite存储，继续使用JSON文件");
+    Arc::new(persistence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{TaskAction, TaskType, TimeInput};
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_json_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = open(TaskPersistence::with_data_dir(temp_dir.path()).unwrap(), StorageBackend::Json);
+        assert!(storage.load_tasks().unwrap().is_empty());
+        
+        let now = Local::now();
+        let task = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(chrono::Duration::minutes(30)), TaskAction::Shutdown, now).unwrap();
+        assert_eq!(storage.add_tasks(std::slice::from_ref(&task)).unwrap(), 1);
+        assert_eq!(storage.add_tasks(std::slice::from_ref(&task)).unwrap(), 2);
+        let backups = storage.task_backups();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(storage.load_task_backup(&backups[0].id).unwrap().len(), 1);
+        assert!(storage.load_task_backup("tasks.json").is_err());
+        assert_eq!(storage.restore_previous_tasks().unwrap().len(), 1);
+        
+        storage.append_history(HistoryEntry::executed(&task, now - chrono::Duration::days(40))).unwrap();
+        storage.append_history(HistoryEntry::cancelled(Some(&task), now)).unwrap();
+        assert_eq!(storage.load_history().unwrap().len(), 2);
+        let recent = storage.history_between(now - chrono::Duration::days(30), now + chrono::Duration::seconds(1)).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].at, now);
+    }
+}
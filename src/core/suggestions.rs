@@ -0,0 +1,109 @@
+//! 时间输入建议模块
+//! 
+//! 输入时间时在输入框下方列出建议，来源依次为配置方案的最近输入、预设和常用写法，
+//! 按当前输入的文字筛选，开头相同的排在前面
+
+/// 最多显示的建议条数
+pub const MAX_SUGGESTIONS: usize = 6;
+
+/// 常用的时间写法，没有最近输入和预设时也能给出建议
+pub const COMMON_INPUTS: [&str; 10] = [
+    "30分钟", "1小时", "2小时", "今晚22:00", "今晚23:00",
+    "每天23:00", "工作日23:00", "周五22:00", "明早", "cron 0 30 22 * * 1-5",
+];
+
+/// 时间输入建议
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SuggestionProvider {
+    /// 按优先级排列且不重复的候选
+    candidates: Vec<String>,
+}
+
+impl SuggestionProvider {
+    /// 创建建议来源
+    /// 
+    /// # 参数
+    /// 
+    /// * `recent` - 最近的输入，最近的在前
+    /// * `presets` - 时间输入预设
+    pub fn new(recent: &[String], presets: &[String]) -> Self {
+        let mut candidates: Vec<String> = Vec::new();
+        let sources = recent.iter().chain(presets).map(String::as_str).chain(COMMON_INPUTS);
+        for candidate in sources.map(str::trim).filter(|candidate| !candidate.is_empty()) {
+            if !candidates.iter().any(|existing| existing == candidate) {
+                candidates.push(candidate.to_string());
+            }
+        }
+        Self { candidates }
+    }
+    
+    /// 按当前输入筛选建议
+    /// 
+    /// 不区分大小写和空格，开头相同的排在包含输入的前面，与输入完全相同的不再建议
+    /// 
+    /// # 参数
+    /// 
+    /// * `input` - 当前输入的文字，为空时不给出建议
+    pub fn suggest(&self, input: &str) -> Vec<String> {
+        let query = Self::normalize(input);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut prefixed = Vec::new();
+        let mut containing = Vec::new();
+        for candidate in &self.candidates {
+            let normalized = Self::normalize(candidate);
+            if normalized == query {
+                continue;
+            }
+            if normalized.starts_with(&query) {
+                prefixed.push(candidate.clone());
+            } else if normalized.contains(&query) {
+                containing.push(candidate.clone());
+            }
+        }
+        prefixed.extend(containing);
+        prefixed.truncate(MAX_SUGGESTIONS);
+        prefixed
+    }
+    
+    /// 去掉空格并转为小写，用于比较
+    fn normalize(text: &str) -> String {
+        text.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time_parser::TimeParser;
+    
+    #[test]
+    fn test_suggestions_order_and_filter() {
+        let recent = vec!["20分钟".to_string(), "今晚23:30".to_string()];
+        let presets = vec!["每天22:30".to_string(), "20分钟".to_string()];
+        let provider = SuggestionProvider::new(&recent, &presets);
+        
+        // 最近输入在前，开头相同的排在包含输入的前面
+        let suggestions = provider.suggest("2");
+        assert_eq!(suggestions[..2], ["20分钟", "2小时"]);
+        assert_eq!(suggestions.len(), MAX_SUGGESTIONS);
+        assert_eq!(provider.suggest("23"), vec!["今晚23:30", "今晚23:00", "每天23:00", "工作日23:00"]);
+        assert_eq!(provider.suggest("今晚"), vec!["今晚23:30", "今晚22:00", "今晚23:00"]);
+        assert_eq!(provider.suggest("CRON"), vec!["cron 0 30 22 * * 1-5"]);
+        
+        // 空输入和完全相同的输入不给出建议
+        assert!(provider.suggest(" ").is_empty());
+        assert!(!provider.suggest("20 分钟").contains(&"20分钟".to_string()));
+        assert!(provider.suggest("分").len() <= MAX_SUGGESTIONS);
+    }
+    
+    #[test]
+    fn test_common_inputs_parse() {
+        let parser = TimeParser::new();
+        for input in COMMON_INPUTS {
+            assert!(parser.parse(input).is_ok(), "常用写法无法解析: {}", input);
+        }
+    }
+}
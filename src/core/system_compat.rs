@@ -2,14 +2,20 @@
 //! 
 //! 提供Windows版本检测、权限检查和系统兼容性功能
 
-use std::ffi::OsString;
-use std::os::windows::ffi::OsStringExt;
-use winapi::um::sysinfoapi::GetVersionExW;
-use winapi::um::winnt::OSVERSIONINFOEXW;
-use winapi::um::winnt::VER_NT_WORKSTATION;
-use winapi::shared::minwindef::{DWORD, FALSE};
 use anyhow::Result;
-use log::info;
+use log::{info, warn};
+
+use crate::core::types::TaskAction;
+use crate::platform::{self, SessionInfo, SystemInfo};
+
+/// 远程桌面会话中执行会断开连接的操作前显示的警告
+/// 
+/// # 参数
+/// 
+/// * `action` - 到点后执行的操作，如关机或睡眠
+pub fn remote_session_warning(action: TaskAction) -> String {
+    format!("您正在远程连接这台电脑，{}后将无法重新连接", action)
+}
 
 /// Windows版本信息
 #[derive(Debug, Clone)]
@@ -48,11 +54,13 @@ impl SystemCompatibility {
     pub async fn initialize(&mut self) -> Result<()> {
         info!("正在初始化系统兼容性检查器...");
         
-        // 检测Windows版本
-        self.windows_version = Some(self.detect_windows_version()?);
-        
-        if let Some(ref version) = self.windows_version {
-            info!("检测到Windows版本: {} (Build {})", version.version_name, version.build);
+        // 检测Windows版本，非Windows平台上无法检测时保持未知
+        match self.detect_windows_version() {
+            Ok(version) => {
+                info!("检测到Windows版本: {} (Build {})", version.version_name, version.build);
+                self.windows_version = Some(version);
+            },
+            Err(e) => warn!("检测Windows版本失败: {}", e),
         }
         
         Ok(())
@@ -60,49 +68,26 @@ impl SystemCompatibility {
 
     /// 检测Windows版本
     fn detect_windows_version(&self) -> Result<WindowsVersion> {
-        unsafe {
-            let mut version_info: OSVERSIONINFOEXW = std::mem::zeroed();
-            version_info.dwOSVersionInfoSize = std::mem::size_of::<OSVERSIONINFOEXW>() as DWORD;
-            
-            if GetVersionExW(&mut version_info as *mut _ as *mut _) == FALSE {
-                return Err(anyhow::anyhow!("无法获取Windows版本信息"));
-            }
-            
-            let major = version_info.dwMajorVersion;
-            let minor = version_info.dwMinorVersion;
-            let build = version_info.dwBuildNumber;
-            
-            // 转换服务包信息
-            let service_pack = {
-                let sp_slice = std::slice::from_raw_parts(
-                    version_info.szCSDVersion.as_ptr(),
-                    version_info.szCSDVersion.len(),
-                );
-                let end = sp_slice.iter().position(|&c| c == 0).unwrap_or(sp_slice.len());
-                OsString::from_wide(&sp_slice[..end])
-                    .to_string_lossy()
-                    .to_string()
-            };
-            
-            // 确定产品类型
-            let product_type = if version_info.wProductType == VER_NT_WORKSTATION {
-                WindowsProductType::Workstation
-            } else {
-                WindowsProductType::Server
-            };
-            
-            // 确定版本名称
-            let version_name = self.get_windows_version_name(major, minor, build, &product_type);
-            
-            Ok(WindowsVersion {
-                major,
-                minor,
-                build,
-                service_pack,
-                product_type,
-                version_name,
-            })
-        }
+        let version = platform::native().os_version()?;
+        
+        // 确定产品类型
+        let product_type = if version.is_workstation {
+            WindowsProductType::Workstation
+        } else {
+            WindowsProductType::Server
+        };
+        
+        // 确定版本名称
+        let version_name = self.get_windows_version_name(version.major, version.minor, version.build, &product_type);
+        
+        Ok(WindowsVersion {
+            major: version.major,
+            minor: version.minor,
+            build: version.build,
+            service_pack: version.service_pack,
+            product_type,
+            version_name,
+        })
     }
 
     /// 根据版本号获取Windows版本名称
@@ -184,50 +169,16 @@ impl SystemCompatibility {
 
     /// 检查当前进程是否具有管理员权限
     pub fn has_admin_privileges(&self) -> bool {
-        use winapi::um::processthreadsapi::GetCurrentProcess;
-        use winapi::um::securitybaseapi::GetTokenInformation;
-        use winapi::um::winnt::{
-            TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY, HANDLE,
-        };
-        use winapi::um::handleapi::CloseHandle;
-        
-        unsafe {
-            let mut token: HANDLE = std::ptr::null_mut();
-            let process = GetCurrentProcess();
-            
-            // 打开进程令牌
-            let result = winapi::um::processthreadsapi::OpenProcessToken(
-                process,
-                TOKEN_QUERY,
-                &mut token,
-            );
-            
-            if result == FALSE || token.is_null() {
-                return false;
-            }
-            
-            let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
-            let mut return_length: DWORD = 0;
-            
-            // 查询令牌提升信息
-            let result = GetTokenInformation(
-                token,
-                TokenElevation,
-                &mut elevation as *mut _ as *mut _,
-                std::mem::size_of::<TOKEN_ELEVATION>() as DWORD,
-                &mut return_length,
-            );
-            
-            CloseHandle(token);
-            
-            if result == FALSE {
-                return false;
-            }
-            
-            elevation.TokenIsElevated != 0
-        }
+        platform::native().is_elevated().unwrap_or(false)
     }
 
+    /// 检查当前是否运行在远程桌面会话中
+    /// 
+    /// 远程会话中关机或睡眠后无法再远程连接这台电脑
+    pub fn is_remote_session(&self) -> bool {
+        platform::native().is_remote_session()
+    }
+    
     /// 生成系统兼容性报告
     pub fn generate_compatibility_report(&self) -> String {
         let mut report = String::new();
@@ -251,6 +202,8 @@ impl SystemCompatibility {
             if self.requires_admin_privileges() { "是" } else { "否" }));
         report.push_str(&format!("当前具有管理员权限: {}\n", 
             if self.has_admin_privileges() { "是" } else { "否" }));
+        report.push_str(&format!("远程桌面会话: {}\n", 
+            if self.is_remote_session() { "是" } else { "否" }));
         
         if self.requires_admin_privileges() && !self.has_admin_privileges() {
             report.push_str("\n⚠️  警告: 当前程序没有管理员权限，可能无法执行关机操作。\n");
@@ -280,9 +233,16 @@ mod tests {
         let mut compat = SystemCompatibility::new();
         let result = compat.initialize().await;
         assert!(result.is_ok());
+        #[cfg(windows)]
         assert!(compat.get_windows_version().is_some());
     }
     
+    #[test]
+    fn test_remote_session_warning_names_action() {
+        assert_eq!(remote_session_warning(TaskAction::Shutdown), "您正在远程连接这台电脑，关机后将无法重新连接");
+        assert_eq!(remote_session_warning(TaskAction::Sleep), "您正在远程连接这台电脑，睡眠后将无法重新连接");
+    }
+    
     #[test]
     fn test_windows_version_name_detection() {
         let compat = SystemCompatibility::new();
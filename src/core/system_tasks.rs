@@ -0,0 +1,368 @@
+//! Windows任务计划程序同步模块
+//! 
+//! 开启后把已启用的每日和每周计划同步为任务计划程序中的任务，QtShut没有运行时也会按时关机。
+//! QtShut正在运行时系统任务不执行操作，取消、推迟、跳过等以QtShut的处理为准。
+//! 任务放在任务计划程序的"QtShut"文件夹中，通过schtasks.exe创建、更新和删除，
+//! 任务列表每次修改后在后台线程中重新同步
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveTime, Weekday};
+use log::{debug, info, warn};
+
+use crate::core::types::{TaskAction, TaskData, TaskType};
+
+/// 任务计划程序中存放QtShut任务的文件夹
+const TASK_FOLDER: &str = "\\QtShut\\";
+
+/// 系统任务比计划时间晚执行的分钟数
+/// 
+/// QtShut正在运行时按自己的提醒和执行条件处理，到这个时间电脑通常已经关闭，系统任务不会再运行
+pub const FALLBACK_DELAY_MINUTES: i64 = 5;
+
+/// 无法取得程序文件名时使用的进程名
+const DEFAULT_IMAGE_NAME: &str = "qtshut.exe";
+
+/// 系统任务执行关机或重启前等待的秒数，期间可以用`shutdown /a`取消
+const SHUTDOWN_TIMEOUT_SECONDS: u32 = 60;
+
+/// 任务计划程序中的一个任务
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemTask {
+    /// 任务名称，包含文件夹，如"\QtShut\{id}"
+    pub name: String,
+    /// 执行的星期，为空表示每天执行
+    pub weekdays: Vec<Weekday>,
+    /// 执行时间
+    pub time: NaiveTime,
+    /// 执行的命令
+    pub command: String,
+}
+
+impl SystemTask {
+    /// 创建任务的schtasks参数，已有同名任务时覆盖
+    pub fn create_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "/Create".to_string(),
+            "/F".to_string(),
+            "/TN".to_string(),
+            self.name.clone(),
+            "/TR".to_string(),
+            self.command.clone(),
+        ];
+        if self.weekdays.is_empty() {
+            args.extend(["/SC".to_string(), "DAILY".to_string()]);
+        } else {
+            let days: Vec<&str> = self.weekdays.iter().map(|&weekday| weekday_code(weekday)).collect();
+            args.extend(["/SC".to_string(), "WEEKLY".to_string(), "/D".to_string(), days.join(",")]);
+        }
+        args.extend(["/ST".to_string(), self.time.format("%H:%M").to_string()]);
+        args
+    }
+}
+
+/// 根据任务列表生成要同步的系统任务
+/// 
+/// 只同步已启用的每日和每周计划；睡眠、锁定等需要QtShut运行的操作不同步。
+/// 执行时间比计划时间晚`FALLBACK_DELAY_MINUTES`分钟，跨过零点时星期顺延一天
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+pub fn system_tasks(tasks: &[TaskData]) -> Vec<SystemTask> {
+    let image = image_name();
+    let mut system_tasks = Vec::new();
+    for task in tasks.iter().filter(|task| task.enabled) {
+        let groups = match task.task_type {
+            TaskType::Daily => task.daily_time
+                .map(|time| vec![(time, task.action, Vec::new())])
+                .unwrap_or_default(),
+            TaskType::Weekly => task.weekly_groups(),
+            TaskType::Once | TaskType::Cron | TaskType::Logon => continue,
+        };
+        let single = groups.len() == 1;
+        for (index, (time, action, weekdays)) in groups.into_iter().enumerate() {
+            let command = match shutdown_command(action, &image) {
+                Some(command) => command,
+                None => continue,
+            };
+            let (time, overflow) = time.overflowing_add_signed(Duration::minutes(FALLBACK_DELAY_MINUTES));
+            let weekdays = if overflow > 0 {
+                weekdays.into_iter().map(|weekday| weekday.succ()).collect()
+            } else {
+                weekdays
+            };
+            let name = if single {
+                format!("{}{}", TASK_FOLDER, task.id)
+            } else {
+                format!("{}{}-{}", TASK_FOLDER, task.id, index)
+            };
+            system_tasks.push(SystemTask { name, weekdays, time, command });
+        }
+    }
+    system_tasks
+}
+
+/// 当前程序的进程名，用于判断QtShut是否正在运行
+/// 
+/// 文件名包含空白时无法不加引号传给findstr，使用默认的进程名
+fn image_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_name().map(|name| name.to_string_lossy().to_string()))
+        .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace))
+        .unwrap_or_else(|| DEFAULT_IMAGE_NAME.to_string())
+}
+
+/// 系统任务执行的命令，不需要QtShut运行就能完成的操作才有
+/// 
+/// QtShut正在运行时不执行，用户在QtShut中取消、推迟或跳过的计划不会被系统任务执行。
+/// 命令中不使用引号，避免schtasks的/TR参数转义出错
+/// 
+/// # 参数
+/// 
+/// * `action` - 操作
+/// * `image` - QtShut的进程名
+fn shutdown_command(action: TaskAction, image: &str) -> Option<String> {
+    let shutdown = match action {
+        TaskAction::Shutdown => format!("shutdown.exe /s /t {}", SHUTDOWN_TIMEOUT_SECONDS),
+        TaskAction::Restart => format!("shutdown.exe /r /t {}", SHUTDOWN_TIMEOUT_SECONDS),
+        TaskAction::Logoff => "shutdown.exe /l".to_string(),
+        TaskAction::Sleep | TaskAction::Lock | TaskAction::MonitorOff | TaskAction::RunCommand => return None,
+    };
+    Some(format!("cmd.exe /c tasklist /NH | findstr /B /I /C:{} >nul || {}", image, shutdown))
+}
+
+/// schtasks使用的星期代码
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MON",
+        Weekday::Tue => "TUE",
+        Weekday::Wed => "WED",
+        Weekday::Thu => "THU",
+        Weekday::Fri => "FRI",
+        Weekday::Sat => "SAT",
+        Weekday::Sun => "SUN",
+    }
+}
+
+/// 从`schtasks /Query /FO CSV /NH`的输出中取出QtShut文件夹中的任务名称
+fn parse_task_names(output: &str) -> Vec<String> {
+    let mut names: Vec<String> = output.lines()
+        .filter_map(|line| line.split(',').next())
+        .map(|name| name.trim().trim_matches('"').to_string())
+        .filter(|name| name.starts_with(TASK_FOLDER))
+        .collect();
+    names.dedup();
+    names
+}
+
+/// 运行schtasks.exe
+/// 
+/// # 返回值
+/// 
+/// 成功返回标准输出，退出码非0时返回包含标准错误的错误信息
+fn schtasks(args: &[String]) -> Result<String> {
+    debug!("执行schtasks: {}", args.join(" "));
+    let output = Command::new("schtasks.exe")
+        .args(args)
+        .output()
+        .map_err(|e| anyhow!("执行schtasks失败: {}", e))?;
+    
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(anyhow!("schtasks执行失败: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// 列出任务计划程序中已有的QtShut任务
+fn existing_task_names() -> Result<Vec<String>> {
+    let output = schtasks(&["/Query".to_string(), "/FO".to_string(), "CSV".to_string(), "/NH".to_string()])?;
+    Ok(parse_task_names(&output))
+}
+
+/// 删除任务计划程序中的任务
+fn delete_task(name: &str) -> Result<()> {
+    schtasks(&["/Delete".to_string(), "/F".to_string(), "/TN".to_string(), name.to_string()])?;
+    Ok(())
+}
+
+/// 按任务列表同步任务计划程序
+/// 
+/// 创建或覆盖需要的任务，删除不再需要的QtShut任务
+/// 
+/// # 参数
+/// 
+/// * `tasks` - 任务列表
+/// 
+/// # 返回值
+/// 
+/// 同步后任务计划程序中的QtShut任务数
+pub fn sync(tasks: &[TaskData]) -> Result<usize> {
+    let wanted = system_tasks(tasks);
+    for name in existing_task_names()? {
+        if !wanted.iter().any(|task| task.name == name) {
+            delete_task(&name)?;
+        }
+    }
+    for task in &wanted {
+        schtasks(&task.create_args())?;
+    }
+    Ok(wanted.len())
+}
+
+/// 删除任务计划程序中所有的QtShut任务
+/// 
+/// # 返回值
+/// 
+/// 删除的任务数
+pub fn remove_all() -> Result<usize> {
+    let names = existing_task_names()?;
+    for name in &names {
+        delete_task(name)?;
+    }
+    Ok(names.len())
+}
+
+/// 同步请求
+#[derive(Debug)]
+enum MirrorRequest {
+    /// 按任务列表同步
+    Sync(Vec<TaskData>),
+    /// 删除所有QtShut任务
+    Clear,
+}
+
+/// 任务计划程序同步器
+/// 
+/// schtasks.exe启动较慢，在后台线程中按顺序处理，连续的修改只同步最后一次
+#[derive(Debug)]
+pub struct SystemTaskMirror {
+    /// 是否同步
+    enabled: AtomicBool,
+    /// 同步请求发送器
+    sender: mpsc::Sender<MirrorRequest>,
+}
+
+impl SystemTaskMirror {
+    /// 启动同步线程
+    /// 
+    /// # 参数
+    /// 
+    /// * `enabled` - 是否同步，关闭时不处理任务列表的修改
+    pub fn start(enabled: bool) -> Self {
+        let (sender, receiver) = mpsc::channel::<MirrorRequest>();
+        thread::spawn(move || {
+            while let Ok(mut request) = receiver.recv() {
+                // 只处理排队中的最后一个请求
+                while let Ok(next) = receiver.try_recv() {
+                    request = next;
+                }
+                let result = match request {
+                    MirrorRequest::Sync(tasks) => sync(&tasks)
+                        .map(|count| info!("已同步到任务计划程序，共 {} 个任务", count)),
+                    MirrorRequest::Clear => remove_all()
+                        .map(|count| info!("已从任务计划程序删除 {} 个任务", count)),
+                };
+                if let Err(e) = result {
+                    warn!("同步任务计划程序失败: {}", e);
+                }
+            }
+        });
+        Self {
+            enabled: AtomicBool::new(enabled),
+            sender,
+        }
+    }
+    
+    /// 是否同步
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    
+    /// 开启或关闭同步
+    /// 
+    /// 开启时立即按任务列表同步，关闭时删除已同步的任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `enabled` - 是否同步
+    /// * `tasks` - 当前任务列表
+    pub fn set_enabled(&self, enabled: bool, tasks: Vec<TaskData>) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        let request = if enabled { MirrorRequest::Sync(tasks) } else { MirrorRequest::Clear };
+        let _ = self.sender.send(request);
+    }
+    
+    /// 按任务列表同步任务计划程序，关闭同步时不做任何事
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 任务列表
+    pub fn sync(&self, tasks: Vec<TaskData>) {
+        if self.is_enabled() {
+            let _ = self.sender.send(MirrorRequest::Sync(tasks));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    
+    use crate::core::types::{TimeInput, WeeklySlot};
+    
+    #[test]
+    fn test_system_tasks() {
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 8, 0, 0).unwrap();
+        let time = NaiveTime::from_hms_opt(22, 30, 0).unwrap();
+        let daily = TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(time), TaskAction::Shutdown, now).unwrap();
+        let sleep = TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(time), TaskAction::Sleep, now).unwrap();
+        let once = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::minutes(30)), TaskAction::Shutdown, now).unwrap();
+        let mut weekly = TaskData::from_time_input(
+            TaskType::Weekly,
+            TimeInput::Weekly { days: vec![Weekday::Fri, Weekday::Sat], time: NaiveTime::from_hms_opt(23, 58, 0).unwrap() },
+            TaskAction::Restart,
+            now,
+        ).unwrap();
+        weekly.weekly_slots.push(WeeklySlot { weekday: Weekday::Sun, time, action: TaskAction::Shutdown });
+        
+        let system_tasks = system_tasks(&[daily.clone(), sleep, once, weekly.clone()]);
+        assert_eq!(system_tasks.len(), 3);
+        assert_eq!(system_tasks[0].name, format!("\\QtShut\\{}", daily.id));
+        assert_eq!(system_tasks[0].create_args().join(" "), format!(
+            "/Create /F /TN \\QtShut\\{} /TR cmd.exe /c tasklist /NH | findstr /B /I /C:{} >nul || shutdown.exe /s /t 60 /SC DAILY /ST 22:35",
+            daily.id,
+            image_name(),
+        ));
+        // 跨过零点时星期顺延
+        assert_eq!(system_tasks[1].name, format!("\\QtShut\\{}-0", weekly.id));
+        assert_eq!(system_tasks[1].weekdays, vec![Weekday::Sat, Weekday::Sun]);
+        assert_eq!(system_tasks[1].time, NaiveTime::from_hms_opt(0, 3, 0).unwrap());
+        assert!(system_tasks[2].create_args().ends_with(&["/SC".to_string(), "WEEKLY".to_string(), "/D".to_string(), "SUN".to_string(), "/ST".to_string(), "22:35".to_string()]));
+    }
+    
+    #[test]
+    fn test_shutdown_command_skips_when_running() {
+        assert_eq!(
+            shutdown_command(TaskAction::Restart, "qtshut.exe").unwrap(),
+            "cmd.exe /c tasklist /NH | findstr /B /I /C:qtshut.exe >nul || shutdown.exe /r /t 60",
+        );
+        assert_eq!(shutdown_command(TaskAction::Sleep, "qtshut.exe"), None);
+        assert!(!image_name().contains(' '));
+    }
+    
+    #[test]
+    fn test_parse_task_names() {
+        let output = "\"\\QtShut\\abc\",\"05/06/2024 22:35:00\",\"Ready\"\r\n\
+                      \"\\Microsoft\\Windows\\Defrag\\ScheduledDefrag\",\"N/A\",\"Ready\"\r\n\
+                      \"\\QtShut\\def-1\",\"05/10/2024 00:03:00\",\"Ready\"\r\n";
+        assert_eq!(parse_task_names(output), vec!["\\QtShut\\abc".to_string(), "\\QtShut\\def-1".to_string()]);
+    }
+}
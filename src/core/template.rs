@@ -0,0 +1,365 @@
+//! 任务模板模块
+//! 
+//! 从JSON文件或网址导入任务和预设模板（如学校推荐的作息时间表），
+//! 导入前先校验并生成预览，确认后再合并到持久化数据中
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command as AsyncCommand;
+
+use crate::core::{
+    persistence::TaskPersistence,
+    plan::is_short_countdown,
+    schedule,
+    stats::TaskStats,
+    storage::Storage,
+    time_parser::TimeParser,
+    types::{new_task_id, ProfileData, TaskAction, TaskData, TaskSource, TaskType, WeeklySlot},
+};
+use crate::utils::system;
+
+/// 下载模板的超时时间（秒）
+const DOWNLOAD_TIMEOUT_SECONDS: u32 = 15;
+
+/// 模板中的单个任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateTask {
+    /// 任务类型
+    pub task_type: TaskType,
+    /// 时间输入（单次和每日任务使用），格式与主窗口输入相同
+    #[serde(default)]
+    pub time: Option<String>,
+    /// 到点后执行的操作
+    #[serde(default)]
+    pub action: TaskAction,
+    /// 每周计划的时间段（每周任务使用）
+    #[serde(default)]
+    pub weekly_slots: Vec<WeeklySlot>,
+}
+
+/// 任务模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    /// 模板名称
+    pub name: String,
+    /// 模板说明
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 模板中的任务
+    #[serde(default)]
+    pub tasks: Vec<TemplateTask>,
+    /// 模板中的时间输入预设
+    #[serde(default)]
+    pub presets: Vec<String>,
+}
+
+/// 模板导入预览
+/// 
+/// 只包含校验通过的任务和预设，校验失败的条目记录在错误列表中
+#[derive(Debug, Clone)]
+pub struct TemplatePreview {
+    /// 模板名称
+    pub name: String,
+    /// 模板说明
+    pub description: Option<String>,
+    /// 校验通过的任务
+    pub tasks: Vec<TaskData>,
+    /// 校验通过的预设
+    pub presets: Vec<String>,
+    /// 校验失败的条目
+    pub errors: Vec<String>,
+}
+
+impl TaskTemplate {
+    /// 从JSON字符串解析模板
+    /// 
+    /// # 参数
+    /// 
+    /// * `json` - 模板JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        let template: TaskTemplate = serde_json::from_str(json)
+            .map_err(|e| anyhow!("模板格式错误: {}", e))?;
+        
+        if template.name.trim().is_empty() {
+            return Err(anyhow!("模板缺少名称"));
+        }
+        if template.tasks.is_empty() && template.presets.is_empty() {
+            return Err(anyhow!("模板中没有任务或预设"));
+        }
+        Ok(template)
+    }
+    
+    /// 校验模板并生成导入预览
+    /// 
+    /// # 参数
+    /// 
+    /// * `parser` - 时间解析器
+    pub fn preview(&self, parser: &TimeParser) -> TemplatePreview {
+        let now = Local::now();
+        let mut tasks = Vec::new();
+        let mut errors = Vec::new();
+        
+        for (index, task) in self.tasks.iter().enumerate() {
+            match Self::build_task(parser, task, now) {
+                Ok(task_data) => tasks.push(task_data),
+                Err(e) => errors.push(format!("第{}个任务: {}", index + 1, e)),
+            }
+        }
+        
+        let mut presets: Vec<String> = Vec::new();
+        for preset in &self.presets {
+            let preset = preset.trim();
+            if preset.is_empty() || presets.iter().any(|item| item == preset) {
+                continue;
+            }
+            match parser.parse(preset) {
+                Ok(_) => presets.push(preset.to_string()),
+                Err(e) => errors.push(format!("预设\"{}\": {}", preset, e)),
+            }
+        }
+        
+        TemplatePreview {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            tasks,
+            presets,
+            errors,
+        }
+    }
+    
+    /// 将模板任务转换为任务数据
+    fn build_task(parser: &TimeParser, task: &TemplateTask, now: chrono::DateTime<Local>) -> Result<TaskData> {
+        if task.task_type == TaskType::Weekly {
+            let mut task_data = TaskData {
+                id: new_task_id(),
+                task_type: TaskType::Weekly,
+                target_time: None,
+                daily_time: None,
+                enabled: true,
+                created_at: now,
+                action: task.action,
+                weekly_slots: task.weekly_slots.clone(),
+                source: TaskSource::Template,
+                custom_command: None,
+                cron: None,
+                routine: None,
+                stats: TaskStats::default(),
+                skip_until: None,
+            };
+            let (target_time, action) = schedule::next_weekly(&task_data.weekly_slots, now)
+                .ok_or_else(|| anyhow!("每周计划没有设置任何时间段"))?;
+            task_data.target_time = Some(target_time);
+            task_data.action = action;
+            return Ok(task_data);
+        }
+        
+        let time = task.time.as_deref().ok_or_else(|| anyhow!("缺少时间"))?;
+        let time_input = parser.parse(time)?;
+        if task.task_type == TaskType::Once {
+            parser.validate(&time_input)?;
+        }
+        let mut task_data = TaskData::from_time_input(task.task_type, time_input, task.action, now)?;
+        // 导入后不经确认就开始，不允许不到一分钟就执行的任务
+        if task.task_type == TaskType::Once && task_data.target_time.is_some_and(|target_time| is_short_countdown(target_time, now)) {
+            return Err(anyhow!("不到一分钟就会执行，请设置更晚的时间"));
+        }
+        task_data.source = TaskSource::Template;
+        Ok(task_data)
+    }
+}
+
+impl TemplatePreview {
+    /// 生成可显示给用户的预览文本
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("模板: {}", self.name)];
+        if let Some(description) = &self.description {
+            lines.push(description.clone());
+        }
+        
+        for task in &self.tasks {
+            let when = task.target_time
+                .map(|time| time.format("%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            lines.push(format!("  任务: {} 下次 {}（{}）", task.task_type, when, task.action));
+        }
+        for preset in &self.presets {
+            lines.push(format!("  预设: {}", preset));
+        }
+        for error in &self.errors {
+            lines.push(format!("  已跳过 {}", error));
+        }
+        lines.join("\n")
+    }
+    
+    /// 是否有可导入的内容
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty() && self.presets.is_empty()
+    }
+    
+    /// 合并到持久化数据
+    /// 
+    /// 任务追加到存储，预设追加到指定配置方案
+    /// 
+    /// # 参数
+    /// 
+    /// * `storage` - 保存任务的存储
+    /// * `persistence` - 保存配置方案的持久化管理器
+    /// * `profile` - 接收预设的配置方案
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回实际导入的任务数和预设数
+    pub fn merge_into(&self, storage: &dyn Storage, persistence: &TaskPersistence, profile: &mut ProfileData) -> Result<(usize, usize)> {
+        if !self.tasks.is_empty() {
+            storage.add_tasks(&self.tasks)?;
+        }
+        
+        let added_presets = self.presets.iter()
+            .filter(|preset| profile.add_preset(preset))
+            .count();
+        if added_presets > 0 {
+            persistence.save_profile(profile)?;
+        }
+        
+        info!("已导入模板\"{}\": {}个任务，{}个预设", self.name, self.tasks.len(), added_presets);
+        Ok((self.tasks.len(), added_presets))
+    }
+}
+
+/// 读取模板内容
+/// 
+/// # 参数
+/// 
+/// * `source` - 文件路径或网址
+pub async fn read_template_source(source: &str) -> Result<String> {
+    read_source(source, "模板").await
+}
+
+/// 读取文件或网址的文本内容
+/// 
+/// 以http://或https://开头时从网址下载，否则按本地文件路径读取
+/// 
+/// # 参数
+/// 
+/// * `source` - 文件路径或网址
+/// * `kind` - 内容名称，如"模板"、"日历"，用于错误提示
+pub async fn read_source(source: &str, kind: &str) -> Result<String> {
+    let source = source.trim();
+    if source.is_empty() {
+        return Err(anyhow!("请输入{}文件路径或网址", kind));
+    }
+    
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return download(source, kind).await;
+    }
+    
+    let path = Path::new(source);
+    tokio::fs::read_to_string(path).await
+        .map_err(|e| anyhow!("读取{}文件失败: {}", kind, e))
+}
+
+/// 下载文本内容
+/// 
+/// 使用系统自带的curl下载（Windows 10 1803及以上版本内置）
+async fn download(url: &str, kind: &str) -> Result<String> {
+    if system::is_safe_mode() {
+        return Err(anyhow!("安全模式下不下载远程{0}，请使用本地{0}文件", kind));
+    }
+    info!("下载{}: {}", kind, url);
+    let output = AsyncCommand::new("curl")
+        .args(["-fsSL", "--max-time", &DOWNLOAD_TIMEOUT_SECONDS.to_string(), url])
+        .output()
+        .await
+        .map_err(|e| anyhow!("无法运行curl下载{}: {}", kind, e))?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        warn!("下载{}失败: {}", kind, stderr.trim());
+        return Err(anyhow!("下载{}失败: {}", kind, stderr.trim()));
+    }
+    
+    String::from_utf8(output.stdout).map_err(|_| anyhow!("{}内容不是有效的UTF-8文本", kind))
+}
+
+/// 从文件或网址加载模板并生成预览
+/// 
+/// # 参数
+/// 
+/// * `source` - 文件路径或网址
+pub async fn load_template_preview(source: &str) -> Result<TemplatePreview> {
+    let json = read_template_source(source).await?;
+    let template = TaskTemplate::from_json(&json)?;
+    Ok(template.preview(TimeParser::global()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    const TEMPLATE_JSON: &str = r#"{
+        "name": "学校作息",
+        "description": "周日到周四晚上按时休息",
+        "tasks": [
+            { "task_type": "Daily", "time": "21:30", "action": "Sleep" },
+            { "task_type": "Weekly", "weekly_slots": [
+                { "weekday": "Sun", "time": "21:00:00", "action": "Shutdown" }
+            ] },
+            { "task_type": "Daily", "time": "30分钟" }
+        ],
+        "presets": ["30分钟", "30分钟", "无效时间"]
+    }"#;
+    
+    #[test]
+    fn test_template_preview_validation() {
+        let template = TaskTemplate::from_json(TEMPLATE_JSON).unwrap();
+        let preview = template.preview(&TimeParser::new());
+        
+        assert_eq!(preview.tasks.len(), 2);
+        assert_eq!(preview.tasks[0].action, TaskAction::Sleep);
+        assert_eq!(preview.tasks[1].task_type, TaskType::Weekly);
+        assert!(preview.tasks[1].target_time.is_some());
+        assert_eq!(preview.presets, vec!["30分钟".to_string()]);
+        // 每日任务的相对时间和无效预设被跳过
+        assert_eq!(preview.errors.len(), 2);
+        assert!(preview.summary().contains("学校作息"));
+    }
+    
+    #[test]
+    fn test_invalid_templates() {
+        assert!(TaskTemplate::from_json("不是JSON").is_err());
+        assert!(TaskTemplate::from_json(r#"{ "name": "空模板" }"#).is_err());
+        assert!(TaskTemplate::from_json(r#"{ "name": " ", "presets": ["5分钟"] }"#).is_err());
+        
+        // 不到一分钟就执行的任务不能导入
+        let template = TaskTemplate::from_json(r#"{ "name": "误输入", "tasks": [{ "task_type": "Once", "time": "3秒" }] }"#).unwrap();
+        let preview = template.preview(&TimeParser::new());
+        assert!(preview.tasks.is_empty());
+        assert_eq!(preview.errors.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_load_and_merge_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_file = temp_dir.path().join("template.json");
+        std::fs::write(&template_file, TEMPLATE_JSON).unwrap();
+        
+        let preview = load_template_preview(template_file.to_str().unwrap()).await.unwrap();
+        assert!(!preview.is_empty());
+        
+        let persistence = TaskPersistence::with_data_dir(temp_dir.path()).unwrap();
+        let mut profile = ProfileData::default();
+        assert_eq!(preview.merge_into(&persistence, &persistence, &mut profile).unwrap(), (2, 1));
+        assert_eq!(persistence.load_tasks().unwrap().len(), 2);
+        assert_eq!(profile.presets, vec!["30分钟".to_string()]);
+        
+        // 重复导入不会产生重复预设
+        assert_eq!(preview.merge_into(&persistence, &persistence, &mut profile).unwrap(), (2, 0));
+        
+        assert!(load_template_preview("").await.is_err());
+    }
+}
@@ -3,22 +3,23 @@
 //! 负责解析用户输入的各种时间格式，支持自然语言和标准格式
 
 use anyhow::{Result, anyhow};
-use chrono::{Local, NaiveTime, Duration, Timelike, TimeZone};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Duration, Timelike, Weekday};
 use regex::Regex;
-use std::collections::HashMap;
-use lazy_static::lazy_static;
 use log::{debug, warn, info};
 use std::sync::OnceLock;
 
-use crate::core::types::TimeInput;
+use crate::core::cron::CronSchedule;
+use crate::core::locale::{alternation, LocalePack, PhraseMeaning};
+use crate::core::types::{format_weekdays, resolve_local_datetime, weekday_label, TaskAction, TaskData, TaskType, TimeInput};
+use crate::utils::config::ConfigManager;
 
 /// 时间解析器
 #[derive(Debug, Clone)]
 pub struct TimeParser {
     /// 预编译的正则表达式
     patterns: TimePatterns,
-    /// 中文数字映射
-    chinese_numbers: HashMap<String, u32>,
+    /// 数字、时间单位、描述词和短语所在的语言包
+    locale: LocalePack,
 }
 
 /// 时间模式集合
@@ -28,123 +29,112 @@ struct TimePatterns {
     duration_pattern: Regex,
     /// 绝对时间模式（如"22:30"、"晚上10点"）
     absolute_pattern: Regex,
+    /// 星期几的绝对时间模式（如"周五22:00"、"下周一早上8点"），匹配预处理前的输入，星期后的部分另行匹配
+    weekday_pattern: Regex,
+    /// 日期的绝对时间模式（如"12月31日 23:30"、"2025-01-01 08:00"），匹配预处理前的输入，日期后的部分另行匹配
+    date_pattern: Regex,
+    /// 节日的绝对时间模式（如"元旦早上"），节日后的部分另行匹配
+    holiday_pattern: Regex,
+    /// 星期、日期或节日之后的时间（如"22:00"、"08:00:00"、"早上8点"、"晚上"）
+    time_of_day_pattern: Regex,
     /// 每日时间模式（如"每天22:00"）
     daily_pattern: Regex,
+    /// 每周时间模式（如"每周五22:00"、"工作日23:00"）
+    weekly_pattern: Regex,
+    /// 每周时间中星期列表里的星期和范围连接词（如"1到5"）
+    weekday_list_pattern: Regex,
 }
 
-lazy_static! {
-    /// 时间单位映射
-    static ref TIME_UNITS: HashMap<&'static str, i64> = {
-        let mut m = HashMap::new();
-        // 秒
-        m.insert("秒", 1);
-        m.insert("秒钟", 1);
-        m.insert("s", 1);
-        m.insert("sec", 1);
-        m.insert("second", 1);
-        m.insert("seconds", 1);
-        
-        // 分钟
-        m.insert("分", 60);
-        m.insert("分钟", 60);
-        m.insert("m", 60);
-        m.insert("min", 60);
-        m.insert("minute", 60);
-        m.insert("minutes", 60);
-        
-        // 小时
-        m.insert("时", 3600);
-        m.insert("小时", 3600);
-        m.insert("h", 3600);
-        m.insert("hour", 3600);
-        m.insert("hours", 3600);
-        
-        m
-    };
-    
-    /// 时间描述词映射
-    static ref TIME_DESCRIPTIONS: HashMap<&'static str, i32> = {
-        let mut m = HashMap::new();
-        m.insert("早上", 8);
-        m.insert("上午", 10);
-        m.insert("中午", 12);
-        m.insert("下午", 14);
-        m.insert("傍晚", 18);
-        m.insert("晚上", 20);
-        m.insert("深夜", 23);
-        m
-    };
-}
+/// 语言包中没有对应的词时使用的分组内容，不匹配任何输入
+const NEVER_MATCH: &str = r"[^\s\S]";
+
+/// 绝对时间最远可以设置到几天后，没有写年份的日期最远在一年后
+const MAX_ABSOLUTE_DAYS: i64 = 366;
 
 impl TimeParser {
-    /// 创建新的时间解析器
+    /// 使用内置语言包创建时间解析器
     pub fn new() -> Self {
+        Self::with_locale(LocalePack::builtin())
+    }
+    
+    /// 使用设置中的语言包创建时间解析器
+    /// 
+    /// 语言包加载失败时使用内置语言包
+    pub fn configured() -> Self {
+        Self::with_locale(ConfigManager::load_parser_locale())
+    }
+    
+    /// 使用指定的语言包创建时间解析器
+    /// 
+    /// # 参数
+    /// 
+    /// * `locale` - 语言包
+    pub fn with_locale(locale: LocalePack) -> Self {
+        let group = |words: Vec<&String>| {
+            let words = alternation(words);
+            if words.is_empty() { NEVER_MATCH.to_string() } else { words }
+        };
+        let units = group(locale.units.keys().collect());
+        let descriptors = group(locale.descriptors.keys().collect());
+        let daily_prefixes = group(locale.daily_prefixes.iter().collect());
+        let holidays = group(locale.holidays.keys().collect());
+        let weekdays = group(locale.weekdays.keys().collect());
+        let weekday_prefixes = group(locale.weekday_prefixes.iter().collect());
+        let week_offsets = group(locale.week_offsets.keys().collect());
+        let weekly_prefixes = group(locale.weekly_prefixes.keys().collect());
+        let range_words = group(locale.range_words.iter().collect());
+        let year_markers = group(locale.year_markers.iter().collect());
+        let month_markers = group(locale.month_markers.iter().collect());
+        let day_markers = group(locale.day_markers.iter().collect());
+        
         let patterns = TimePatterns {
             // 匹配相对时间：数字+单位
-            duration_pattern: Regex::new(r"(?i)(\d+)\s*(秒钟?|分钟?|小?时|[smh]|sec|min|hour)s?").unwrap(),
+            duration_pattern: Regex::new(&format!(r"(?i)(\d+)\s*({})s?", units)).unwrap(),
             
             // 匹配绝对时间：HH:MM 或 描述词+时间
-            absolute_pattern: Regex::new(r"(?i)(早上|上午|中午|下午|傍晚|晚上|深夜)?\s*(\d{1,2})[：:]?(\d{2})?").unwrap(),
+            absolute_pattern: Regex::new(&format!(r"(?i)({})?\s*(\d{{1,2}})[：:]?(\d{{2}})?", descriptors)).unwrap(),
+            
+            // 匹配星期几：可选的"下周"/"这周"/"本周" + 周/星期/礼拜 + 星期，中文数字在预处理前匹配，避免"周五十点"被当作"周50点"
+            weekday_pattern: Regex::new(&format!(r"^({})?(?:{})({})(.*)$", week_offsets, weekday_prefixes, weekdays)).unwrap(),
+            
+            // 匹配日期：年-月-日（分隔符可以是"-"、"/"、"."或年月日标记）或 月日，没有年份时必须写月和日的标记
+            date_pattern: Regex::new(&format!(
+                r"^(?:(\d{{4}})\s*(?:[-/.]|{})\s*(\d{{1,2}})\s*(?:[-/.]|{})\s*(\d{{1,2}})\s*(?:{})?|(\d{{1,2}})\s*(?:{})\s*(\d{{1,2}})\s*(?:{}))(.*)$",
+                year_markers, month_markers, day_markers, month_markers, day_markers
+            )).unwrap(),
+            
+            // 匹配节日：节日名称 + 时间
+            holiday_pattern: Regex::new(&format!(r"^({})(.*)$", holidays)).unwrap(),
+            
+            // 匹配星期、日期或节日之后的时间：描述词和时间都可以省略其一
+            time_of_day_pattern: Regex::new(&format!(r"(?i)^\s*({})?\s*(?:(\d{{1,2}})(?:[：:](\d{{2}})(?:[：:](\d{{2}}))?|点)?)?\s*$", descriptors)).unwrap(),
             
             // 匹配每日时间：每天/每日 + 时间
-            daily_pattern: Regex::new(r"(?i)(每天|每日)\s*(\d{1,2})[：:]?(\d{2})?").unwrap(),
+            daily_pattern: Regex::new(&format!(r"(?i)({})\s*(\d{{1,2}})[：:]?(\d{{2}})?", daily_prefixes)).unwrap(),
+            
+            // 匹配每周时间：每周/工作日/周末 + 星期列表 + 时间，小时后必须有冒号或"点"，避免与星期数字混淆
+            weekly_pattern: Regex::new(&format!(
+                r"^({})((?:{}|{}|{}|[、，,\s])*?)\s*({})?\s*(\d{{1,2}})(?:[：:](\d{{2}})|点)",
+                weekly_prefixes, weekdays, weekday_prefixes, range_words, descriptors
+            )).unwrap(),
+            
+            // 匹配星期列表中的星期或范围连接词
+            weekday_list_pattern: Regex::new(&format!(r"({})|(?:{})", weekdays, range_words)).unwrap(),
         };
         
-        Self { 
-            patterns,
-            chinese_numbers: Self::init_chinese_numbers(),
-        }
+        Self { patterns, locale }
     }
     
     /// 获取全局时间解析器实例（单例模式）
     /// 
+    /// 第一次使用时按设置加载语言包
+    /// 
     /// # 返回值
     /// 
     /// 时间解析器的静态引用
     pub fn global() -> &'static TimeParser {
         static INSTANCE: OnceLock<TimeParser> = OnceLock::new();
-        INSTANCE.get_or_init(|| TimeParser::new())
-    }
-    
-    /// 初始化中文数字映射
-    fn init_chinese_numbers() -> HashMap<String, u32> {
-        let mut chinese_numbers = HashMap::new();
-        chinese_numbers.insert("零".to_string(), 0);
-        chinese_numbers.insert("一".to_string(), 1);
-        chinese_numbers.insert("二".to_string(), 2);
-        chinese_numbers.insert("两".to_string(), 2);
-        chinese_numbers.insert("三".to_string(), 3);
-        chinese_numbers.insert("四".to_string(), 4);
-        chinese_numbers.insert("五".to_string(), 5);
-        chinese_numbers.insert("六".to_string(), 6);
-        chinese_numbers.insert("七".to_string(), 7);
-        chinese_numbers.insert("八".to_string(), 8);
-        chinese_numbers.insert("九".to_string(), 9);
-        chinese_numbers.insert("十".to_string(), 10);
-        chinese_numbers.insert("十一".to_string(), 11);
-        chinese_numbers.insert("十二".to_string(), 12);
-        chinese_numbers.insert("十三".to_string(), 13);
-        chinese_numbers.insert("十四".to_string(), 14);
-        chinese_numbers.insert("十五".to_string(), 15);
-        chinese_numbers.insert("十六".to_string(), 16);
-        chinese_numbers.insert("十七".to_string(), 17);
-        chinese_numbers.insert("十八".to_string(), 18);
-        chinese_numbers.insert("十九".to_string(), 19);
-        chinese_numbers.insert("二十".to_string(), 20);
-        chinese_numbers.insert("二十一".to_string(), 21);
-        chinese_numbers.insert("二十二".to_string(), 22);
-        chinese_numbers.insert("二十三".to_string(), 23);
-        chinese_numbers.insert("二十四".to_string(), 24);
-        chinese_numbers.insert("二十五".to_string(), 25);
-        chinese_numbers.insert("二十六".to_string(), 26);
-        chinese_numbers.insert("二十七".to_string(), 27);
-        chinese_numbers.insert("二十八".to_string(), 28);
-        chinese_numbers.insert("二十九".to_string(), 29);
-        chinese_numbers.insert("三十".to_string(), 30);
-        chinese_numbers.insert("四十".to_string(), 40);
-        chinese_numbers.insert("五十".to_string(), 50);
-        chinese_numbers.insert("六十".to_string(), 60);
-        chinese_numbers
+        INSTANCE.get_or_init(TimeParser::configured)
     }
     
     /// 解析用户输入的时间字符串
@@ -160,6 +150,12 @@ impl TimeParser {
         let input = input.trim();
         info!("开始解析时间输入: {}", input);
         
+        // 尝试解析cron表达式，cron表达式中的数字不做中文数字预处理
+        if let Some(expression) = Self::cron_expression(input) {
+            debug!("匹配到cron表达式");
+            return Ok(TimeInput::Cron(CronSchedule::parse(expression)?));
+        }
+        
         // 预处理：转换中文数字
         let processed_input = self.preprocess_chinese_numbers(input);
         debug!("预处理后的输入: {}", processed_input);
@@ -170,6 +166,30 @@ impl TimeParser {
             return Ok(time_input);
         }
         
+        // 尝试解析日期
+        if let Some(captures) = self.patterns.date_pattern.captures(input) {
+            debug!("匹配到日期时间模式");
+            return self.parse_date_time(&captures);
+        }
+        
+        // 尝试解析节日
+        if let Some(captures) = self.patterns.holiday_pattern.captures(input) {
+            debug!("匹配到节日时间模式");
+            return self.parse_holiday_time(&captures);
+        }
+        
+        // 尝试解析星期几的时间
+        if let Some(captures) = self.patterns.weekday_pattern.captures(input) {
+            debug!("匹配到星期几时间模式");
+            return self.parse_weekday_time(&captures);
+        }
+        
+        // 尝试解析每周时间
+        if let Some(captures) = self.patterns.weekly_pattern.captures(&processed_input) {
+            debug!("匹配到每周时间模式");
+            return self.parse_weekly_time(&captures);
+        }
+        
         // 尝试解析每日时间
         if let Some(captures) = self.patterns.daily_pattern.captures(&processed_input) {
             debug!("匹配到每日时间模式");
@@ -192,15 +212,29 @@ impl TimeParser {
         Err(anyhow!("无法识别的时间格式: {}", input))
     }
     
+    /// 提取输入中的cron表达式
+    /// 
+    /// 以"cron"开头的输入总是按cron表达式解析，便于显示表达式本身的错误；
+    /// 没有前缀时，只有5到6段、每段都由cron字符组成的输入才视为cron表达式
+    fn cron_expression(input: &str) -> Option<&str> {
+        if let Some(prefix) = input.get(..4).filter(|prefix| prefix.eq_ignore_ascii_case("cron")) {
+            return Some(input[prefix.len()..].trim_start_matches(|c: char| c == ':' || c == '：' || c.is_whitespace()));
+        }
+        
+        let fields: Vec<&str> = input.split_whitespace().collect();
+        let is_cron_field = |field: &&str| {
+            field.chars().all(|c| c.is_ascii_alphanumeric() || "*/,?-".contains(c))
+                && field.chars().any(|c| c.is_ascii_digit() || c == '*' || c == '?')
+        };
+        ((5..=6).contains(&fields.len()) && fields.iter().all(is_cron_field)).then_some(input)
+    }
+    
     /// 预处理中文数字
     fn preprocess_chinese_numbers(&self, input: &str) -> String {
         let mut result = input.to_string();
         
-        // 按长度降序排列，先替换长的字符串，避免部分匹配
-        let mut sorted_numbers: Vec<_> = self.chinese_numbers.iter().collect();
-        sorted_numbers.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
-        
-        for (chinese, number) in sorted_numbers {
+        // 先替换长的字符串，避免部分匹配
+        for (chinese, number) in self.locale.numbers_longest_first() {
             result = result.replace(chinese, &number.to_string());
         }
         result
@@ -208,137 +242,27 @@ impl TimeParser {
     
     /// 解析自然语言时间表达
     fn parse_natural_language(&self, input: &str) -> Result<TimeInput> {
-        match input {
-            "半小时后" => Ok(TimeInput::Duration(Duration::minutes(30))),
-            "一小时后" | "1小时后" => Ok(TimeInput::Duration(Duration::hours(1))),
-            "两小时后" | "2小时后" => Ok(TimeInput::Duration(Duration::hours(2))),
-            "三小时后" | "3小时后" => Ok(TimeInput::Duration(Duration::hours(3))),
-            "明天" => {
-                let now = Local::now();
-                let tomorrow = now + Duration::days(1);
-                let target = tomorrow.date_naive().and_hms_opt(9, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造明天时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                Ok(TimeInput::AbsoluteTime(target_dt))
-            },
-            "后天" => {
-                let now = Local::now();
-                let day_after_tomorrow = now + Duration::days(2);
-                let target = day_after_tomorrow.date_naive().and_hms_opt(9, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造后天时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                Ok(TimeInput::AbsoluteTime(target_dt))
-            },
-            "今晚" => {
-                let now = Local::now();
-                let today = now.date_naive();
-                let target = today.and_hms_opt(20, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造今晚时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                let final_target = if target_dt <= now {
-                    target_dt + Duration::days(1)
-                } else {
-                    target_dt
-                };
-                Ok(TimeInput::AbsoluteTime(final_target))
-            },
-            "明早" | "明天早上" => {
-                let now = Local::now();
-                let tomorrow = now + Duration::days(1);
-                let target = tomorrow.date_naive().and_hms_opt(7, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造明早时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                Ok(TimeInput::AbsoluteTime(target_dt))
-            },
-            "明天晚上" => {
-                let now = Local::now();
-                let tomorrow = now + Duration::days(1);
-                let target = tomorrow.date_naive().and_hms_opt(20, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造明天晚上时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                Ok(TimeInput::AbsoluteTime(target_dt))
-            },
-            "中午" => {
-                let now = Local::now();
-                let today = now.date_naive();
-                let target = today.and_hms_opt(12, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造中午时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                let final_target = if target_dt <= now {
-                    target_dt + Duration::days(1)
-                } else {
-                    target_dt
-                };
-                Ok(TimeInput::AbsoluteTime(final_target))
-            },
-            "下午" => {
-                let now = Local::now();
-                let today = now.date_naive();
-                let target = today.and_hms_opt(14, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造下午时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                let final_target = if target_dt <= now {
-                    target_dt + Duration::days(1)
-                } else {
-                    target_dt
-                };
-                Ok(TimeInput::AbsoluteTime(final_target))
-            },
-            "晚上" => {
-                let now = Local::now();
-                let today = now.date_naive();
-                let target = today.and_hms_opt(20, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造晚上时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                let final_target = if target_dt <= now {
-                    target_dt + Duration::days(1)
-                } else {
-                    target_dt
-                };
-                Ok(TimeInput::AbsoluteTime(final_target))
-            },
-            "深夜" => {
-                let now = Local::now();
-                let today = now.date_naive();
-                let target = today.and_hms_opt(23, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造深夜时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
+        let meaning = self.locale.phrase(input).ok_or_else(|| anyhow!("不支持的自然语言表达"))?;
+        let now = Local::now();
+        let at_hour = |days: i64, hour: u32| {
+            let date = now.date_naive() + Duration::days(days);
+            date.and_hms_opt(hour, 0, 0).ok_or_else(|| anyhow!("无法构造\"{}\"对应的时间", input))
+        };
+        
+        match *meaning {
+            PhraseMeaning::After { minutes } => Ok(TimeInput::Duration(Duration::minutes(minutes))),
+            PhraseMeaning::OnDay { days, hour } => Ok(TimeInput::AbsoluteTime(resolve_local_datetime(at_hour(days, hour)?)?)),
+            PhraseMeaning::Upcoming { hour } => {
+                // 今天的时间已过则为明天，按日期加一天，避免跨夏令时后时刻偏移
+                let target = at_hour(0, hour)?;
+                let target_dt = resolve_local_datetime(target)?;
                 let final_target = if target_dt <= now {
-                    target_dt + Duration::days(1)
+                    resolve_local_datetime(target + Duration::days(1))?
                 } else {
                     target_dt
                 };
                 Ok(TimeInput::AbsoluteTime(final_target))
             },
-            "凌晨" => {
-                let now = Local::now();
-                let tomorrow = now + Duration::days(1);
-                let target = tomorrow.date_naive().and_hms_opt(2, 0, 0)
-                    .ok_or_else(|| anyhow!("无法构造凌晨时间"))?;
-                let target_dt = Local.from_local_datetime(&target)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
-                Ok(TimeInput::AbsoluteTime(target_dt))
-            },
-            _ => Err(anyhow!("不支持的自然语言表达"))
         }
     }
     
@@ -362,14 +286,23 @@ impl TimeParser {
                 if *datetime <= now {
                     return Err(anyhow!("目标时间必须在当前时间之后"));
                 }
-                // 检查是否超过24小时
                 let diff = *datetime - now;
-                if diff.num_hours() > 24 {
-                    return Err(anyhow!("目标时间不能超过24小时后"));
+                if diff.num_days() >= MAX_ABSOLUTE_DAYS {
+                    return Err(anyhow!("目标时间不能超过{}天后", MAX_ABSOLUTE_DAYS));
                 }
             },
             TimeInput::DailyTime(_time) => {
                 // 每日时间总是有效的，因为会自动调整到下一个匹配的时间
+            },
+            TimeInput::Weekly { days, .. } => {
+                if days.is_empty() {
+                    return Err(anyhow!("每周计划需要至少选择一天"));
+                }
+            },
+            TimeInput::Cron(schedule) => {
+                if schedule.next_after(Local::now()).is_none() {
+                    return Err(anyhow!("cron表达式\"{}\"没有可执行的时间", schedule));
+                }
             }
         }
         Ok(())
@@ -381,7 +314,7 @@ impl TimeParser {
             .ok_or_else(|| anyhow!("无法提取数字"))?
             .as_str();
             
-        let number: i64 = if let Some(&num) = self.chinese_numbers.get(number_str) {
+        let number: i64 = if let Some(&num) = self.locale.numbers.get(number_str) {
             num as i64
         } else {
             number_str.parse()
@@ -393,7 +326,7 @@ impl TimeParser {
             .as_str()
             .to_lowercase();
             
-        let seconds = TIME_UNITS.get(unit.as_str())
+        let seconds = self.locale.units.get(unit.as_str())
             .ok_or_else(|| anyhow!("不支持的时间单位: {}", unit))?;
             
         let total_seconds = number * seconds;
@@ -419,14 +352,14 @@ impl TimeParser {
             .as_str();
         let minute_str = captures.get(3).map(|m| m.as_str()).unwrap_or("0");
         
-        let mut hour: u32 = if let Some(&num) = self.chinese_numbers.get(hour_str) {
+        let mut hour: u32 = if let Some(&num) = self.locale.numbers.get(hour_str) {
             num
         } else {
             hour_str.parse()
                 .map_err(|_| anyhow!("无效的小时格式"))?
         };
         
-        let minute: u32 = if let Some(&num) = self.chinese_numbers.get(minute_str) {
+        let minute: u32 = if let Some(&num) = self.locale.numbers.get(minute_str) {
             num
         } else {
             minute_str.parse()
@@ -434,14 +367,7 @@ impl TimeParser {
         };
             
         // 处理时间描述词
-        if let Some(desc) = description {
-            if let Some(&base_hour) = TIME_DESCRIPTIONS.get(desc) {
-                // 如果用户输入的是相对小时（如"晚上8点"），调整小时
-                if hour <= 12 {
-                    hour = (base_hour as u32 + hour - 8).max(0).min(23);
-                }
-            }
-        }
+        hour = self.apply_description(description, hour);
         
         // 验证时间范围
         if hour >= 24 || minute >= 60 {
@@ -455,13 +381,11 @@ impl TimeParser {
             .ok_or_else(|| anyhow!("无法构造时间"))?;
         let target_datetime = today.and_time(target_time);
         
-        let target = Local.from_local_datetime(&target_datetime)
-            .single()
-            .ok_or_else(|| anyhow!("无法构造本地时间"))?;
+        let target = resolve_local_datetime(target_datetime)?;
             
-        // 如果目标时间已过，设置为明天
+        // 如果目标时间已过，设置为明天（按日期加一天，避免跨夏令时后时刻偏移）
         let final_target = if target <= now {
-            target + Duration::days(1)
+            resolve_local_datetime(target_datetime + Duration::days(1))?
         } else {
             target
         };
@@ -469,6 +393,271 @@ impl TimeParser {
         Ok(TimeInput::AbsoluteTime(final_target))
     }
     
+    /// 解析星期几的时间
+    /// 
+    /// 没有前缀时为最近的一个该星期（当天的时间已过则为下周）；"下周"为下一个自然周（周一开始）中的该星期，
+    /// 语言包中周数更大的前缀依次往后推；"这周"/"本周"为本周中的该星期，已经过去时报错。
+    /// 只写描述词时（如"星期天晚上"）使用描述词对应的整点
+    fn parse_weekday_time(&self, captures: &regex::Captures) -> Result<TimeInput> {
+        let weeks = captures.get(1).and_then(|m| self.locale.week_offsets.get(m.as_str()).copied());
+        let day = captures.get(2)
+            .and_then(|m| self.weekday_of(m.as_str()))
+            .ok_or_else(|| anyhow!("无法提取星期"))?;
+        let time = self.parse_time_of_day(captures.get(3).map(|m| m.as_str()).unwrap_or(""))?
+            .ok_or_else(|| anyhow!("请在{}后面写上时间，如 周五22:00、下周一早上8点", weekday_label(day)))?;
+        
+        // 按日期计算，避免跨夏令时后时刻偏移
+        let now = Local::now();
+        let today = now.date_naive();
+        let offset = day.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64;
+        let target = match weeks {
+            Some(weeks) if weeks > 0 => resolve_local_datetime((today + Duration::days(offset + 7 * weeks as i64)).and_time(time))?,
+            Some(_) => {
+                let target = resolve_local_datetime((today + Duration::days(offset)).and_time(time))?;
+                if target <= now {
+                    return Err(anyhow!("本周的{} {}已经过去，下周请输入 下{}", weekday_label(day), time.format("%H:%M"), weekday_label(day)));
+                }
+                target
+            },
+            None => {
+                let date = today + Duration::days(offset.rem_euclid(7));
+                let target = resolve_local_datetime(date.and_time(time))?;
+                if target <= now {
+                    resolve_local_datetime((date + Duration::days(7)).and_time(time))?
+                } else {
+                    target
+                }
+            },
+        };
+        
+        Ok(TimeInput::AbsoluteTime(target))
+    }
+    
+    /// 解析日期的时间
+    /// 
+    /// 写了年份时按原样返回，已经过去的时间由验证时报错；没有写年份时为今年的该日期，已经过去则为明年
+    fn parse_date_time(&self, captures: &regex::Captures) -> Result<TimeInput> {
+        // 有年份和没有年份的写法分别在第2、3组和第4、5组
+        let number = |with_year: usize, without_year: usize, name: &str| -> Result<u32> {
+            captures.get(with_year)
+                .or_else(|| captures.get(without_year))
+                .ok_or_else(|| anyhow!("无法提取{}", name))?
+                .as_str()
+                .parse()
+                .map_err(|_| anyhow!("无效的{}格式", name))
+        };
+        let year = captures.get(1)
+            .map(|m| m.as_str().parse::<i32>())
+            .transpose()
+            .map_err(|_| anyhow!("无效的年份格式"))?;
+        let month = number(2, 4, "月份")?;
+        let day = number(3, 5, "日期")?;
+        let time = self.parse_time_of_day(captures.get(6).map(|m| m.as_str()).unwrap_or(""))?
+            .ok_or_else(|| anyhow!("请在{}月{}日后面写上时间，如 12月31日 23:30", month, day))?;
+        
+        let target = match year {
+            Some(year) => Self::date_time(year, month, day, time)?,
+            None => Self::upcoming_date_time(month, day, time)?,
+        };
+        Ok(TimeInput::AbsoluteTime(target))
+    }
+    
+    /// 解析节日的时间，节日为最近的一个
+    fn parse_holiday_time(&self, captures: &regex::Captures) -> Result<TimeInput> {
+        let name = captures.get(1)
+            .ok_or_else(|| anyhow!("无法提取节日"))?
+            .as_str();
+        let &[month, day] = self.locale.holidays.get(name)
+            .ok_or_else(|| anyhow!("不支持的节日: {}", name))?;
+        let time = self.parse_time_of_day(captures.get(2).map(|m| m.as_str()).unwrap_or(""))?
+            .ok_or_else(|| anyhow!("请在{}后面写上时间，如 元旦早上、圣诞节20:00", name))?;
+        
+        Ok(TimeInput::AbsoluteTime(Self::upcoming_date_time(month, day, time)?))
+    }
+    
+    /// 今年的该日期和时间，已经过去则为明年
+    fn upcoming_date_time(month: u32, day: u32, time: NaiveTime) -> Result<DateTime<Local>> {
+        let now = Local::now();
+        let target = Self::date_time(now.year(), month, day, time)?;
+        if target <= now {
+            Self::date_time(now.year() + 1, month, day, time)
+        } else {
+            Ok(target)
+        }
+    }
+    
+    /// 构造本地时间，日期不存在时报错
+    fn date_time(year: i32, month: u32, day: u32, time: NaiveTime) -> Result<DateTime<Local>> {
+        let date = NaiveDate::from_ymd_opt(year, month, day)
+            .ok_or_else(|| anyhow!("无效的日期: {}年{}月{}日", year, month, day))?;
+        resolve_local_datetime(date.and_time(time))
+    }
+    
+    /// 解析星期、日期或节日之后的时间
+    /// 
+    /// 只写描述词时（如"晚上"）使用描述词对应的整点
+    /// 
+    /// # 返回值
+    /// 
+    /// 没有时间或无法识别时返回None，时间超出范围时返回错误
+    fn parse_time_of_day(&self, text: &str) -> Result<Option<NaiveTime>> {
+        let text = self.preprocess_chinese_numbers(text);
+        let Some(captures) = self.patterns.time_of_day_pattern.captures(&text) else {
+            return Ok(None);
+        };
+        let description = captures.get(1).map(|m| m.as_str());
+        let number = |index: usize, name: &str| -> Result<u32> {
+            captures.get(index)
+                .map(|m| m.as_str())
+                .unwrap_or("0")
+                .parse()
+                .map_err(|_| anyhow!("无效的{}格式", name))
+        };
+        let (hour, minute, second) = match captures.get(2) {
+            Some(_) => (self.apply_description(description, number(2, "小时")?), number(3, "分钟")?, number(4, "秒")?),
+            None => match description.and_then(|desc| self.locale.descriptors.get(desc)) {
+                Some(&base_hour) => (base_hour as u32, 0, 0),
+                None => return Ok(None),
+            },
+        };
+        
+        // 验证时间范围
+        if hour >= 24 || minute >= 60 || second >= 60 {
+            return Err(anyhow!("无效的时间: {}:{:02}:{:02}", hour, minute, second));
+        }
+        NaiveTime::from_hms_opt(hour, minute, second)
+            .map(Some)
+            .ok_or_else(|| anyhow!("无法构造时间"))
+    }
+    
+    /// 星期的写法对应的星期，如"日"、"天"和"七"表示周日
+    fn weekday_of(&self, text: &str) -> Option<Weekday> {
+        self.locale.weekdays.get(text).copied().and_then(Self::weekday_from_number)
+    }
+    
+    /// 语言包中的星期序号对应的星期，1为周一，7为周日
+    fn weekday_from_number(number: u32) -> Option<Weekday> {
+        let index = u8::try_from(number.checked_sub(1)?).ok()?;
+        Weekday::try_from(index).ok()
+    }
+    
+    /// 按时间描述词把12小时制的小时换算为24小时制
+    /// 
+    /// 描述词按基准小时分为四类，每类的上午下午固定：
+    /// 
+    /// * 基准在12点前（凌晨、早上、上午）：上午，12点为0点；基准在9点以后（上午）时12点为中午12点
+    /// * 基准为12点（中午）：12点不变，1到5点为下午
+    /// * 基准在13点到19点（下午、傍晚）：下午，12点不变
+    /// * 基准在20点以后（晚上、深夜）：下午，12点为0点，1到4点为后半夜
+    /// 
+    /// 0点和13点以后已经是24小时制，不再换算
+    fn apply_description(&self, description: Option<&str>, hour: u32) -> u32 {
+        let Some(&base_hour) = description.and_then(|desc| self.locale.descriptors.get(desc)) else {
+            return hour;
+        };
+        if !(1..=12).contains(&hour) {
+            return hour;
+        }
+        
+        let (morning, afternoon) = (hour % 12, hour % 12 + 12);
+        match base_hour.rem_euclid(24) {
+            9..=11 if hour == 12 => hour,
+            0..=11 => morning,
+            12 if (1..=5).contains(&hour) => afternoon,
+            12 => hour,
+            13..=19 => afternoon,
+            _ if hour == 12 || hour <= 4 => morning,
+            _ => afternoon,
+        }
+    }
+    
+    /// 解析每周时间
+    fn parse_weekly_time(&self, captures: &regex::Captures) -> Result<TimeInput> {
+        let prefix = captures.get(1)
+            .ok_or_else(|| anyhow!("无法提取每周时间"))?
+            .as_str();
+        // 前缀本身包含星期时（如"工作日"、"周末"）忽略后面的星期列表
+        let prefix_days: Vec<Weekday> = self.locale.weekly_prefixes.get(prefix)
+            .into_iter()
+            .flatten()
+            .filter_map(|&number| Self::weekday_from_number(number))
+            .collect();
+        let days = if prefix_days.is_empty() {
+            self.parse_weekdays(captures.get(2).map(|m| m.as_str()).unwrap_or(""))?
+        } else {
+            prefix_days
+        };
+        
+        let hour: u32 = captures.get(4)
+            .ok_or_else(|| anyhow!("无法提取小时"))?
+            .as_str()
+            .parse()
+            .map_err(|_| anyhow!("无效的小时格式"))?;
+        let minute: u32 = captures.get(5)
+            .map(|m| m.as_str())
+            .unwrap_or("0")
+            .parse()
+            .map_err(|_| anyhow!("无效的分钟格式"))?;
+        let hour = self.apply_description(captures.get(3).map(|m| m.as_str()), hour);
+        
+        // 验证时间范围
+        if hour >= 24 || minute >= 60 {
+            return Err(anyhow!("无效的时间: {}:{:02}", hour, minute));
+        }
+        
+        let time = NaiveTime::from_hms_opt(hour, minute, 0)
+            .ok_or_else(|| anyhow!("无法构造时间"))?;
+        
+        Ok(TimeInput::Weekly { days, time })
+    }
+    
+    /// 解析星期列表
+    /// 
+    /// 支持"5"、"1、3、5"、"1到5"等写法（中文数字已在预处理中转换），星期和范围连接词来自语言包
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回按周一到周日排序且不重复的星期
+    fn parse_weekdays(&self, spec: &str) -> Result<Vec<Weekday>> {
+        let mut days: Vec<Weekday> = Vec::new();
+        let mut range_start: Option<Weekday> = None;
+        let mut in_range = false;
+        
+        for captures in self.patterns.weekday_list_pattern.captures_iter(spec) {
+            let day = match captures.get(1) {
+                None => {
+                    in_range = range_start.is_some();
+                    continue;
+                },
+                Some(m) => match self.weekday_of(m.as_str()) {
+                    Some(day) => day,
+                    None => continue,
+                },
+            };
+            
+            match range_start.filter(|_| in_range) {
+                // 范围可以跨周末，如"5到1"表示周五到下周一
+                Some(start) => {
+                    let mut current = start;
+                    while current != day {
+                        current = current.succ();
+                        days.push(current);
+                    }
+                },
+                None => days.push(day),
+            }
+            range_start = Some(day);
+            in_range = false;
+        }
+        
+        if days.is_empty() {
+            return Err(anyhow!("请指定星期几，如 每周五22:00"));
+        }
+        days.sort_by_key(|day| day.num_days_from_monday());
+        days.dedup();
+        Ok(days)
+    }
+    
     /// 解析每日时间
     fn parse_daily_time(&self, captures: &regex::Captures) -> Result<TimeInput> {
         let hour_str = captures.get(2)
@@ -476,14 +665,14 @@ impl TimeParser {
             .as_str();
         let minute_str = captures.get(3).map(|m| m.as_str()).unwrap_or("0");
         
-        let hour: u32 = if let Some(&num) = self.chinese_numbers.get(hour_str) {
+        let hour: u32 = if let Some(&num) = self.locale.numbers.get(hour_str) {
             num
         } else {
             hour_str.parse()
                 .map_err(|_| anyhow!("无效的小时格式"))?
         };
         
-        let minute: u32 = if let Some(&num) = self.chinese_numbers.get(minute_str) {
+        let minute: u32 = if let Some(&num) = self.locale.numbers.get(minute_str) {
             num
         } else {
             minute_str.parse()
@@ -513,6 +702,12 @@ impl TimeParser {
             TimeInput::DailyTime(time) => {
                 time.format("%H:%M").to_string()
             },
+            TimeInput::Weekly { days, time } => {
+                format!("{} {}", format_weekdays(days), time.format("%H:%M"))
+            },
+            TimeInput::Cron(schedule) => {
+                format!("cron {}", schedule)
+            },
         }
     }
     
@@ -539,6 +734,13 @@ impl TimeParser {
             TimeInput::DailyTime(time) => {
                 format!("每天 {}", time.format("%H:%M"))
             },
+            TimeInput::Weekly { days, time } => {
+                format!("每周 {} {}", format_weekdays(days), time.format("%H:%M"))
+            },
+            TimeInput::Cron(schedule) => match schedule.next_after(Local::now()) {
+                Some(next) => format!("cron {}（下次 {}）", schedule, next.format("%m-%d %H:%M")),
+                None => format!("cron {}", schedule),
+            },
         }
     }
     
@@ -631,6 +833,16 @@ impl TimeParser {
                     return Err(anyhow!("无效的每日时间格式"));
                 }
             },
+            TimeInput::Weekly { days, .. } => {
+                if days.is_empty() {
+                    return Err(anyhow!("每周计划需要至少选择一天"));
+                }
+            },
+            TimeInput::Cron(schedule) => {
+                if schedule.next_after(Local::now()).is_none() {
+                    return Err(anyhow!("cron表达式\"{}\"没有可执行的时间", schedule));
+                }
+            },
         }
         Ok(())
     }
@@ -651,12 +863,10 @@ impl TimeParser {
                 let today = now.date_naive();
                 let target_today = today.and_time(*time);
                 
-                let target_dt = Local.from_local_datetime(&target_today)
-                    .single()
-                    .ok_or_else(|| anyhow!("无法构造本地时间"))?;
+                let target_dt = resolve_local_datetime(target_today)?;
                 
                 let target = if target_dt <= now {
-                    target_dt + Duration::days(1)
+                    resolve_local_datetime(target_today + Duration::days(1))?
                 } else {
                     target_dt
                 };
@@ -664,6 +874,18 @@ impl TimeParser {
                 let diff = target - now;
                 Ok(diff.num_seconds().max(0))
             },
+            TimeInput::Weekly { .. } => {
+                let now = Local::now();
+                let task = TaskData::from_time_input(TaskType::Weekly, input.clone(), TaskAction::default(), now)?;
+                let target = task.target_time.ok_or_else(|| anyhow!("无法计算每周计划的执行时间"))?;
+                Ok((target - now).num_seconds().max(0))
+            },
+            TimeInput::Cron(schedule) => {
+                let now = Local::now();
+                let target = schedule.next_after(now)
+                    .ok_or_else(|| anyhow!("cron表达式\"{}\"没有可执行的时间", schedule))?;
+                Ok((target - now).num_seconds().max(0))
+            },
         }
     }
     
@@ -679,8 +901,11 @@ impl TimeParser {
     pub fn get_format_examples(&self) -> Vec<(&'static str, &'static str)> {
         vec![
             ("相对时间", "30分钟后, 2小时后, 1天后"),
-            ("绝对时间", "14:30, 2024-01-01 15:00:00"),
+            ("绝对时间", "14:30, 周五22:00, 下周一早上8点, 星期天晚上"),
+            ("日期时间", "12月31日 23:30, 2025-01-01 08:00, 元旦早上"),
             ("每日时间", "每天8点, 每天18:30"),
+            ("每周时间", "每周五22:00, 每周一到周五 23:00, 工作日23:00, 周末10点"),
+            ("Cron表达式", "cron 0 30 22 * * 1-5（秒 分 时 日 月 星期）"),
             ("自然语言", "半小时后, 明天, 今晚, 中午"),
             ("中文数字", "三十分钟后, 两小时后, 明天八点"),
             ("复合时间", "1小时30分钟后, 2天3小时后"),
@@ -696,7 +921,7 @@ mod tests {
     #[test]
     fn test_time_parser_creation() {
         let parser = TimeParser::new();
-        assert!(!parser.chinese_numbers.is_empty());
+        assert!(!parser.locale.numbers.is_empty());
     }
     
     #[test]
@@ -704,7 +929,7 @@ mod tests {
         let parser1 = TimeParser::global();
         let parser2 = TimeParser::global();
         // 验证是同一个实例（地址相同）
-        assert_eq!(parser1.chinese_numbers.len(), parser2.chinese_numbers.len());
+        assert_eq!(parser1.locale.numbers.len(), parser2.locale.numbers.len());
     }
     
     #[test]
@@ -762,6 +987,67 @@ mod tests {
         assert!(parser.parse("下午2:30").is_ok());
     }
     
+    #[test]
+    fn test_descriptive_hours() {
+        let parser = TimeParser::new();
+        
+        let test_cases = vec![
+            ("凌晨1点", 1, 0),
+            ("凌晨5:30", 5, 30),
+            ("凌晨12点", 0, 0),
+            ("早上6点", 6, 0),
+            ("早上8:15", 8, 15),
+            ("早上2点", 2, 0),
+            ("上午3点", 3, 0),
+            ("上午4点", 4, 0),
+            ("上午11点", 11, 0),
+            ("上午12点", 12, 0),
+            ("早上12点", 0, 0),
+            ("中午11:30", 11, 30),
+            ("中午12点", 12, 0),
+            ("中午十二点", 12, 0),
+            ("中午1点", 13, 0),
+            ("下午12点", 12, 0),
+            ("下午1点", 13, 0),
+            ("下午2:30", 14, 30),
+            ("下午6点", 18, 0),
+            ("下午8点", 20, 0),
+            ("下午9点", 21, 0),
+            ("傍晚6点", 18, 0),
+            ("傍晚7:30", 19, 30),
+            ("晚上7点", 19, 0),
+            ("晚上10点", 22, 0),
+            ("晚上11:59", 23, 59),
+            ("晚上12点", 0, 0),
+            ("晚上1点", 1, 0),
+            ("晚上2点", 2, 0),
+            ("深夜11点", 23, 0),
+            ("深夜12点", 0, 0),
+            ("深夜1点", 1, 0),
+            // 已经是24小时制的不再换算
+            ("下午14:00", 14, 0),
+            ("晚上22:30", 22, 30),
+            ("晚上0点", 0, 0),
+        ];
+        
+        for (input, expected_hour, expected_minute) in test_cases {
+            match parser.parse(input) {
+                Ok(TimeInput::AbsoluteTime(datetime)) => {
+                    assert_eq!((datetime.hour(), datetime.minute()), (expected_hour, expected_minute), "Wrong time for: {}", input);
+                },
+                other => panic!("Expected AbsoluteTime for: {}, got {:?}", input, other),
+            }
+            
+            // 每周时间使用相同的换算
+            match parser.parse(&format!("每周五 {}", input)) {
+                Ok(TimeInput::Weekly { time, .. }) => {
+                    assert_eq!((time.hour(), time.minute()), (expected_hour, expected_minute), "Wrong weekly time for: {}", input);
+                },
+                other => panic!("Expected Weekly for: 每周五 {}, got {:?}", input, other),
+            }
+        }
+    }
+    
     #[test]
     fn test_daily_time_parsing() {
         let parser = TimeParser::new();
@@ -784,6 +1070,166 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_weekly_time_parsing() {
+        use chrono::Weekday::*;
+        let parser = TimeParser::new();
+        
+        let test_cases = vec![
+            ("每周五22:00", vec![Fri], 22, 0),
+            ("每周一三五 23:30", vec![Mon, Wed, Fri], 23, 30),
+            ("每周一到周五 23:00", vec![Mon, Tue, Wed, Thu, Fri], 23, 0),
+            ("每星期六、日 晚上10点", vec![Sat, Sun], 22, 0),
+            ("每周五到周一 1:00", vec![Mon, Fri, Sat, Sun], 1, 0),
+            ("工作日23:00", vec![Mon, Tue, Wed, Thu, Fri], 23, 0),
+            ("周末 10:00", vec![Sat, Sun], 10, 0),
+        ];
+        
+        for (input, expected_days, expected_hour, expected_minute) in test_cases {
+            match parser.parse(input) {
+                Ok(TimeInput::Weekly { days, time }) => {
+                    assert_eq!(days, expected_days, "Wrong days for: {}", input);
+                    assert_eq!(time.hour(), expected_hour, "Wrong hour for: {}", input);
+                    assert_eq!(time.minute(), expected_minute, "Wrong minute for: {}", input);
+                },
+                other => panic!("Expected Weekly for: {}, got {:?}", input, other),
+            }
+        }
+        
+        // 没有指定星期时报错
+        assert!(parser.parse("每周 22:00").is_err());
+        
+        let input = parser.parse("工作日23:00").unwrap();
+        assert_eq!(parser.format_time_input(&input), "工作日 23:00");
+        assert!(parser.validate(&input).is_ok());
+        assert!(parser.get_remaining_seconds(&input).unwrap() <= 7 * 24 * 3600);
+        
+        // 每周时间总是创建每周计划
+        let task = TaskData::from_time_input(TaskType::Once, input, TaskAction::Shutdown, Local::now()).unwrap();
+        assert_eq!(task.task_type, TaskType::Weekly);
+        assert_eq!(task.weekly_slots.len(), 5);
+        assert!(task.target_time.unwrap() > Local::now());
+        assert_eq!(task.schedule_text(), "每周计划 工作日 23:00，关机");
+    }
+    
+    #[test]
+    fn test_weekday_time_parsing() {
+        use chrono::Weekday::*;
+        let parser = TimeParser::new();
+        let now = Local::now();
+        let this_monday = now.date_naive() - Duration::days(now.weekday().num_days_from_monday() as i64);
+        
+        let test_cases = vec![
+            ("周五22:00", Fri, 22, 0),
+            ("周五十点", Fri, 10, 0),
+            ("星期天晚上", Sun, 20, 0),
+            ("礼拜三 晚上10点", Wed, 22, 0),
+            ("周6 8:30", Sat, 8, 30),
+        ];
+        for (input, expected_day, expected_hour, expected_minute) in test_cases {
+            match parser.parse(input) {
+                Ok(TimeInput::AbsoluteTime(datetime)) => {
+                    assert_eq!(datetime.weekday(), expected_day, "Wrong day for: {}", input);
+                    assert_eq!(datetime.hour(), expected_hour, "Wrong hour for: {}", input);
+                    assert_eq!(datetime.minute(), expected_minute, "Wrong minute for: {}", input);
+                    // 最近的一个该星期
+                    assert!(datetime > now && datetime - now <= Duration::days(7), "Wrong date for: {}", input);
+                    assert!(parser.validate(&TimeInput::AbsoluteTime(datetime)).is_ok());
+                },
+                other => panic!("Expected AbsoluteTime for: {}, got {:?}", input, other),
+            }
+        }
+        
+        // "下周"为下一个自然周
+        match parser.parse("下周一早上8点").unwrap() {
+            TimeInput::AbsoluteTime(datetime) => {
+                assert_eq!(datetime.date_naive(), this_monday + Duration::days(7));
+                assert_eq!(datetime.time(), NaiveTime::from_hms_opt(8, 0, 0).unwrap());
+                assert!(parser.validate(&TimeInput::AbsoluteTime(datetime)).is_ok());
+            },
+            other => panic!("Expected AbsoluteTime, got {:?}", other),
+        }
+        match parser.parse("下个星期天 23:59").unwrap() {
+            TimeInput::AbsoluteTime(datetime) => assert_eq!(datetime.date_naive(), this_monday + Duration::days(13)),
+            other => panic!("Expected AbsoluteTime, got {:?}", other),
+        }
+        
+        // 本周已经过去的时间报错
+        if now.weekday() != Mon {
+            assert!(parser.parse("这周一早上8点").unwrap_err().to_string().contains("下周一"));
+        }
+        
+        // 没有时间或无法识别时报错，不当作其他格式
+        assert!(parser.parse("周五").is_err());
+        assert!(parser.parse("周一到周五23:00").is_err());
+        
+        // 每周时间和周末不受影响
+        assert!(matches!(parser.parse("每周五22:00").unwrap(), TimeInput::Weekly { .. }));
+        assert!(matches!(parser.parse("周末10点").unwrap(), TimeInput::Weekly { .. }));
+    }
+    
+    #[test]
+    fn test_date_time_parsing() {
+        let parser = TimeParser::new();
+        let now = Local::now();
+        let next_year = now.year() + 1;
+        
+        // 写了年份的日期按原样解析
+        for input in [format!("{}-01-01 08:00", next_year), format!("{}/1/1 8:00:00", next_year), format!("{}年1月1日早上8点", next_year)] {
+            match parser.parse(&input) {
+                Ok(TimeInput::AbsoluteTime(datetime)) => {
+                    assert_eq!(datetime.naive_local(), NaiveDate::from_ymd_opt(next_year, 1, 1).unwrap().and_hms_opt(8, 0, 0).unwrap(), "Wrong time for: {}", input);
+                    assert!(parser.validate(&TimeInput::AbsoluteTime(datetime)).is_ok());
+                },
+                other => panic!("Expected AbsoluteTime for: {}, got {:?}", input, other),
+            }
+        }
+        
+        // 没有写年份时为最近的该日期
+        for (input, month, day, hour, minute) in [("12月31日 23:30", 12, 31, 23, 30), ("5月1号晚上", 5, 1, 20, 0), ("元旦早上", 1, 1, 8, 0), ("圣诞节20:00", 12, 25, 20, 0)] {
+            match parser.parse(input) {
+                Ok(TimeInput::AbsoluteTime(datetime)) => {
+                    assert_eq!((datetime.month(), datetime.day(), datetime.hour(), datetime.minute()), (month, day, hour, minute), "Wrong time for: {}", input);
+                    assert!(datetime > now && datetime.year() <= next_year, "Wrong year for: {}", input);
+                    assert!(parser.validate(&TimeInput::AbsoluteTime(datetime)).is_ok());
+                },
+                other => panic!("Expected AbsoluteTime for: {}, got {:?}", input, other),
+            }
+        }
+        
+        // 已经过去的时间在验证时报错
+        let past = parser.parse("2020-01-01 08:00").unwrap();
+        assert!(parser.validate(&past).is_err());
+        
+        // 日期无效、时间无效或没有时间时报错
+        assert!(parser.parse("2月30日 8:00").unwrap_err().to_string().contains("无效的日期"));
+        assert!(parser.parse("12月31日 25:00").is_err());
+        assert!(parser.parse("元旦").unwrap_err().to_string().contains("写上时间"));
+    }
+    
+    #[test]
+    fn test_cron_parsing() {
+        let parser = TimeParser::new();
+        
+        for input in ["cron 0 30 22 * * 1-5", "CRON: 0 30 22 * * MON-FRI", "0 30 22 * * 1-5", "30 22 * * 1-5"] {
+            match parser.parse(input) {
+                Ok(TimeInput::Cron(schedule)) => assert!(schedule.expression().starts_with("0 30 22 * *"), "Wrong expression for: {}", input),
+                other => panic!("Expected Cron for: {}, got {:?}", input, other),
+            }
+        }
+        
+        // 带前缀时直接显示表达式的错误
+        let error = parser.parse("cron 0 61 22 * * *").unwrap_err();
+        assert!(error.to_string().contains("分钟"));
+        
+        let input = parser.parse("cron 0 30 22 * * 1-5").unwrap();
+        assert!(parser.validate(&input).is_ok());
+        let task = TaskData::from_time_input(TaskType::Once, input, TaskAction::Sleep, Local::now()).unwrap();
+        assert_eq!(task.task_type, TaskType::Cron);
+        assert_eq!(task.cron.as_deref(), Some("0 30 22 * * 1-5"));
+        assert!(crate::core::schedule::next_fire(&task, Local::now()).is_some());
+    }
+    
     #[test]
     fn test_natural_language_parsing() {
         let parser = TimeParser::new();
@@ -801,6 +1247,24 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_relative_days_use_calendar_date() {
+        let parser = TimeParser::new();
+        let tomorrow = Local::now().date_naive() + Duration::days(1);
+        
+        // 按日历日期计算，跨夏令时切换时也不会偏移一小时
+        match parser.parse("明天").unwrap() {
+            TimeInput::AbsoluteTime(datetime) => {
+                assert_eq!(datetime.date_naive(), tomorrow);
+                assert_eq!(datetime.time(), NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+            },
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+        
+        let naive = tomorrow.and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(resolve_local_datetime(naive).unwrap().naive_local(), naive);
+    }
+    
     #[test]
     fn test_format_duration() {
         let parser = TimeParser::new();
@@ -885,6 +1349,64 @@ mod tests {
         assert!(examples.iter().any(|(category, _)| category.contains("每日时间")));
     }
     
+    #[test]
+    fn test_custom_locale() {
+        let mut locale = LocalePack::builtin();
+        locale.merge(LocalePack::from_json(r#"{
+            "numbers": { "廿": 20 },
+            "units": { "个钟": 3600 },
+            "descriptors": { "夜晚": 20 },
+            "week_offsets": { "下下": 2 },
+            "weekly_prefixes": { "逢": [], "平日": [1, 2, 3, 4, 5] },
+            "day_markers": ["號"],
+            "phrases": [{ "texts": ["听日"], "kind": "OnDay", "days": 1, "hour": 9 }]
+        }"#).unwrap());
+        let parser = TimeParser::with_locale(locale);
+        
+        match parser.parse("两个钟").unwrap() {
+            TimeInput::Duration(duration) => assert_eq!(duration, Duration::hours(2)),
+            other => panic!("Expected Duration, got {:?}", other),
+        }
+        match parser.parse("廿分钟").unwrap() {
+            TimeInput::Duration(duration) => assert_eq!(duration, Duration::minutes(20)),
+            other => panic!("Expected Duration, got {:?}", other),
+        }
+        match parser.parse("听日").unwrap() {
+            TimeInput::AbsoluteTime(datetime) => assert_eq!(datetime.date_naive(), Local::now().date_naive() + Duration::days(1)),
+            other => panic!("Expected AbsoluteTime, got {:?}", other),
+        }
+        match parser.parse("每周五 夜晚10点").unwrap() {
+            TimeInput::Weekly { time, .. } => assert_eq!(time.hour(), 22),
+            other => panic!("Expected Weekly, got {:?}", other),
+        }
+        
+        // 星期、每周前缀和日期标记同样来自语言包
+        match parser.parse("下下周一 8:00").unwrap() {
+            TimeInput::AbsoluteTime(datetime) => {
+                let today = Local::now().date_naive();
+                let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+                assert_eq!(datetime.date_naive(), monday + Duration::days(14));
+            },
+            other => panic!("Expected AbsoluteTime, got {:?}", other),
+        }
+        match parser.parse("逢周五22:00").unwrap() {
+            TimeInput::Weekly { days, .. } => assert_eq!(days, vec![Weekday::Fri]),
+            other => panic!("Expected Weekly, got {:?}", other),
+        }
+        match parser.parse("平日23:00").unwrap() {
+            TimeInput::Weekly { days, .. } => assert_eq!(days.len(), 5),
+            other => panic!("Expected Weekly, got {:?}", other),
+        }
+        match parser.parse("12月31號 23:30").unwrap() {
+            TimeInput::AbsoluteTime(datetime) => assert_eq!((datetime.month(), datetime.day()), (12, 31)),
+            other => panic!("Expected AbsoluteTime, got {:?}", other),
+        }
+        
+        // 内置语言包不认识这些说法
+        assert!(TimeParser::new().parse("听日").is_err());
+        assert!(!matches!(TimeParser::new().parse("平日23:00"), Ok(TimeInput::Weekly { .. })));
+    }
+    
     #[test]
     fn test_preprocess_chinese_numbers() {
         let parser = TimeParser::new();
@@ -921,4 +1443,32 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_task_input_text_round_trip() {
+        use crate::core::types::{TaskAction, TaskData, TaskType};
+        use crate::core::cron::CronSchedule;
+        use chrono::{NaiveTime, Weekday};
+        
+        let parser = TimeParser::new();
+        let now = Local::now();
+        let time = NaiveTime::from_hms_opt(22, 30, 0).unwrap();
+        let tasks = vec![
+            TaskData::from_time_input(TaskType::Daily, TimeInput::DailyTime(time), TaskAction::Sleep, now).unwrap(),
+            TaskData::from_time_input(TaskType::Weekly, TimeInput::Weekly { days: vec![Weekday::Mon, Weekday::Wed, Weekday::Sun], time }, TaskAction::Shutdown, now).unwrap(),
+            TaskData::from_time_input(TaskType::Cron, TimeInput::Cron(CronSchedule::parse("0 30 22 * * 1-5").unwrap()), TaskAction::Shutdown, now).unwrap(),
+            TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::days(2)), TaskAction::Lock, now).unwrap(),
+        ];
+        
+        // 重新解析后的计划与原任务相同
+        for task in tasks {
+            let input = task.input_text(now).unwrap();
+            let time_input = parser.parse(&input).unwrap();
+            let reparsed = TaskData::from_time_input(time_input.task_type_or(TaskType::Once), time_input, task.action, now).unwrap();
+            assert_eq!(reparsed.task_type, task.task_type, "Wrong type for: {}", input);
+            assert_eq!(reparsed.daily_time, task.daily_time, "Wrong daily time for: {}", input);
+            assert_eq!(reparsed.weekly_slots, task.weekly_slots, "Wrong slots for: {}", input);
+            assert_eq!(reparsed.cron, task.cron, "Wrong cron for: {}", input);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! 非定时触发模块
+//! 
+//! 除了按时间倒计时，还可以等待某个系统状态出现后再开始最后警告，
+//! 如网络流量持续低于设置的速度（下载完成）、指定的进程退出（导出完成）、
+//! 市电断开后改由UPS电池供电
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::system;
+
+/// 默认视为下载完成的网络速度（KB/s）
+pub const DEFAULT_NETWORK_BELOW_KBPS: u32 = 50;
+
+/// 默认网络速度需要持续低于设置值的分钟数
+pub const DEFAULT_NETWORK_QUIET_MINUTES: u32 = 5;
+
+/// 等待网络空闲时的采样间隔（秒）
+pub const NETWORK_SAMPLE_SECONDS: u64 = 5;
+
+/// 等待进程退出时的检查间隔（秒）
+pub const PROCESS_SAMPLE_SECONDS: u64 = 5;
+
+/// 默认进程退出后再等待的分钟数
+pub const DEFAULT_PROCESS_EXIT_DELAY_MINUTES: u32 = 5;
+
+/// 等待市电断开时的检查间隔（秒）
+pub const POWER_SAMPLE_SECONDS: u64 = 5;
+
+/// 网络空闲触发设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkIdleSettings {
+    /// 网络收发速度低于多少KB/s视为空闲
+    pub below_kbps: u32,
+    /// 需要持续空闲的分钟数
+    pub quiet_minutes: u32,
+}
+
+impl Default for NetworkIdleSettings {
+    fn default() -> Self {
+        Self {
+            below_kbps: DEFAULT_NETWORK_BELOW_KBPS,
+            quiet_minutes: DEFAULT_NETWORK_QUIET_MINUTES,
+        }
+    }
+}
+
+impl NetworkIdleSettings {
+    /// 需要持续空闲的时长，至少1分钟
+    pub fn quiet_duration(&self) -> Duration {
+        Duration::from_secs(self.quiet_minutes.max(1) as u64 * 60)
+    }
+    
+    /// 触发条件的说明，如"网速低于50KB/s持续5分钟"
+    pub fn describe(&self) -> String {
+        format!("网速低于{}KB/s持续{}分钟", self.below_kbps, self.quiet_minutes.max(1))
+    }
+}
+
+/// 网络空闲的判断结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NetworkIdleState {
+    /// 网络仍在忙碌
+    Busy,
+    /// 已空闲一段时间，还没有达到要求
+    Quiet(Duration),
+    /// 已持续空闲足够长的时间
+    Idle,
+}
+
+/// 网络空闲监视
+/// 
+/// 记录网络速度从什么时候开始低于设置值，速度回升时重新计时
+#[derive(Debug, Clone)]
+pub struct NetworkIdleWatch {
+    /// 触发设置
+    settings: NetworkIdleSettings,
+    /// 速度开始低于设置值的时间
+    quiet_since: Option<Instant>,
+}
+
+impl NetworkIdleWatch {
+    /// 创建网络空闲监视
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 触发设置
+    pub fn new(settings: NetworkIdleSettings) -> Self {
+        Self { settings, quiet_since: None }
+    }
+    
+    /// 记录一次采样结果
+    /// 
+    /// # 参数
+    /// 
+    /// * `rate_kbps` - 采样得到的网络速度（KB/s）
+    /// * `now` - 采样时间
+    pub fn update(&mut self, rate_kbps: f64, now: Instant) -> NetworkIdleState {
+        if rate_kbps >= self.settings.below_kbps as f64 {
+            self.quiet_since = None;
+            return NetworkIdleState::Busy;
+        }
+        
+        let quiet_for = now.saturating_duration_since(*self.quiet_since.get_or_insert(now));
+        if quiet_for >= self.settings.quiet_duration() {
+            NetworkIdleState::Idle
+        } else {
+            NetworkIdleState::Quiet(quiet_for)
+        }
+    }
+}
+
+/// 进程退出触发
+/// 
+/// 指定的进程退出若干分钟后执行操作，如编码、导出完成后关机
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessExitTrigger {
+    /// 等待退出的进程名，如"HandBrake.exe"
+    pub process: String,
+    /// 进程退出后再等待的分钟数
+    pub delay_minutes: u32,
+}
+
+impl ProcessExitTrigger {
+    /// 进程退出后到执行操作的时长
+    pub fn delay(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.delay_minutes as i64)
+    }
+    
+    /// 进程是否仍在运行
+    /// 
+    /// # 参数
+    /// 
+    /// * `running` - 正在运行的进程名
+    pub fn is_running(&self, running: &[String]) -> bool {
+        system::find_running_process(std::slice::from_ref(&self.process), running).is_some()
+    }
+    
+    /// 触发条件的说明，如"HandBrake.exe退出后5分钟"
+    pub fn describe(&self) -> String {
+        if self.delay_minutes == 0 {
+            format!("{}退出后", self.process)
+        } else {
+            format!("{}退出后{}分钟", self.process, self.delay_minutes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_network_idle_watch() {
+        let settings = NetworkIdleSettings { below_kbps: 50, quiet_minutes: 5 };
+        assert_eq!(settings.describe(), "网速低于50KB/s持续5分钟");
+        let mut watch = NetworkIdleWatch::new(settings);
+        let start = Instant::now();
+        let minutes = |n: u64| start + Duration::from_secs(n * 60);
+        
+        assert_eq!(watch.update(800.0, start), NetworkIdleState::Busy);
+        assert_eq!(watch.update(10.0, minutes(1)), NetworkIdleState::Quiet(Duration::ZERO));
+        assert_eq!(watch.update(10.0, minutes(4)), NetworkIdleState::Quiet(Duration::from_secs(3 * 60)));
+        
+        // 速度回升后重新计时
+        assert_eq!(watch.update(50.0, minutes(5)), NetworkIdleState::Busy);
+        assert_eq!(watch.update(0.0, minutes(6)), NetworkIdleState::Quiet(Duration::ZERO));
+        assert_eq!(watch.update(0.0, minutes(11)), NetworkIdleState::Idle);
+    }
+    
+    #[test]
+    fn test_process_exit_trigger() {
+        let trigger = ProcessExitTrigger { process: "HandBrake.exe".to_string(), delay_minutes: 5 };
+        assert_eq!(trigger.describe(), "HandBrake.exe退出后5分钟");
+        assert_eq!(trigger.delay(), chrono::Duration::minutes(5));
+        
+        assert!(trigger.is_running(&["explorer.exe".to_string(), "handbrake.exe".to_string()]));
+        assert!(!trigger.is_running(&["explorer.exe".to_string()]));
+        
+        let immediate = ProcessExitTrigger { delay_minutes: 0, ..trigger };
+        assert_eq!(immediate.describe(), "HandBrake.exe退出后");
+    }
+    
+    #[test]
+    fn test_quiet_duration_is_at_least_one_minute() {
+        let settings = NetworkIdleSettings { quiet_minutes: 0, ..Default::default() };
+        assert_eq!(settings.quiet_duration(), Duration::from_secs(60));
+    }
+}
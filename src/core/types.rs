@@ -2,10 +2,21 @@
 //! 
 //! 定义应用程序中使用的所有核心数据结构和枚举
 
-use chrono::{DateTime, Local, NaiveTime, Duration};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, LocalResult, NaiveDateTime, NaiveTime, Duration, TimeZone, Weekday};
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::core::history::CancelReason;
+use crate::core::plan::RescheduleChoice;
+use crate::core::cron::CronSchedule;
+use crate::core::routine::{LogonRoutine, RoutineStep};
+use crate::core::schedule;
+use crate::core::stats::TaskStats;
+use crate::core::trigger::ProcessExitTrigger;
+use crate::platform::{self, Capabilities};
+
 /// 任务类型枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskType {
@@ -13,6 +24,19 @@ pub enum TaskType {
     Once,
     /// 每日重复关机任务
     Daily,
+    /// 每周计划任务，每天的时间和操作可以不同
+    Weekly,
+    /// 按cron表达式重复的高级计划
+    Cron,
+    /// 每天第一次登录时执行的登录任务，不参与倒计时
+    Logon,
+}
+
+impl TaskType {
+    /// 可以在主窗口输入时间创建的任务类型，用于界面选择
+    /// 
+    /// 登录任务在设置中单独编辑
+    pub const ALL: [TaskType; 4] = [TaskType::Once, TaskType::Daily, TaskType::Weekly, TaskType::Cron];
 }
 
 impl fmt::Display for TaskType {
@@ -20,6 +44,151 @@ impl fmt::Display for TaskType {
         match self {
             TaskType::Once => write!(f, "单次关机"),
             TaskType::Daily => write!(f, "每日关机"),
+            TaskType::Weekly => write!(f, "每周计划"),
+            TaskType::Cron => write!(f, "Cron计划"),
+            TaskType::Logon => write!(f, "登录时"),
+        }
+    }
+}
+
+/// 任务到点后执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TaskAction {
+    /// 关机
+    #[default]
+    Shutdown,
+    /// 睡眠
+    Sleep,
+    /// 重启
+    Restart,
+    /// 锁定计算机
+    Lock,
+    /// 注销当前用户
+    Logoff,
+    /// 关闭显示器，适合听音乐入睡时使用
+    MonitorOff,
+    /// 运行自定义命令
+    RunCommand,
+}
+
+impl TaskAction {
+    /// 所有可选操作
+    pub const ALL: [TaskAction; 7] = [
+        TaskAction::Shutdown,
+        TaskAction::Restart,
+        TaskAction::Sleep,
+        TaskAction::Lock,
+        TaskAction::Logoff,
+        TaskAction::MonitorOff,
+        TaskAction::RunCommand,
+    ];
+    
+    /// 从名称解析操作
+    /// 
+    /// 支持英文名称（如"sleep"）和中文名称（如"睡眠"）
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 操作名称
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "shutdown" | "关机" => Some(TaskAction::Shutdown),
+            "sleep" | "睡眠" => Some(TaskAction::Sleep),
+            "restart" | "reboot" | "重启" => Some(TaskAction::Restart),
+            "lock" | "锁定" | "锁屏" => Some(TaskAction::Lock),
+            "logoff" | "logout" | "注销" => Some(TaskAction::Logoff),
+            "monitor-off" | "monitoroff" | "关闭显示器" | "关屏" => Some(TaskAction::MonitorOff),
+            "command" | "run" | "运行命令" => Some(TaskAction::RunCommand),
+            _ => None,
+        }
+    }
+    
+    /// 是否需要关机权限
+    pub fn requires_shutdown_privilege(&self) -> bool {
+        matches!(self, TaskAction::Shutdown | TaskAction::Restart)
+    }
+    
+    /// 执行后是否无法再远程连接这台电脑
+    /// 
+    /// 关机和睡眠后远程桌面连接断开且无法重新连接，重启后通常可以重新连接
+    pub fn ends_remote_access(&self) -> bool {
+        matches!(self, TaskAction::Shutdown | TaskAction::Sleep)
+    }
+    
+    /// 当前平台是否支持该操作
+    /// 
+    /// # 参数
+    /// 
+    /// * `capabilities` - 平台支持的功能
+    pub fn is_supported(&self, capabilities: &Capabilities) -> bool {
+        match self {
+            TaskAction::Shutdown | TaskAction::Restart => capabilities.power_off,
+            TaskAction::Sleep => capabilities.sleep,
+            TaskAction::Lock => capabilities.lock,
+            TaskAction::Logoff => capabilities.logoff,
+            TaskAction::MonitorOff => capabilities.monitor_off,
+            TaskAction::RunCommand => true,
+        }
+    }
+    
+    /// 计算机睡眠后是否仍需按时执行该操作
+    /// 
+    /// 计算机已经睡眠时，睡眠、锁定和关闭显示器都已没有必要
+    pub fn needs_awake_computer(self) -> bool {
+        !matches!(self, TaskAction::Sleep | TaskAction::Lock | TaskAction::MonitorOff)
+    }
+    
+    /// 当前平台支持的所有操作，用于界面中的操作选择
+    pub fn available() -> Vec<TaskAction> {
+        let capabilities = platform::capabilities();
+        TaskAction::ALL.into_iter().filter(|action| action.is_supported(&capabilities)).collect()
+    }
+}
+
+impl fmt::Display for TaskAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskAction::Shutdown => write!(f, "关机"),
+            TaskAction::Sleep => write!(f, "睡眠"),
+            TaskAction::Restart => write!(f, "重启"),
+            TaskAction::Lock => write!(f, "锁定"),
+            TaskAction::Logoff => write!(f, "注销"),
+            TaskAction::MonitorOff => write!(f, "关闭显示器"),
+            TaskAction::RunCommand => write!(f, "运行命令"),
+        }
+    }
+}
+
+/// 任务来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TaskSource {
+    /// 在主窗口或托盘中设置
+    #[default]
+    Manual,
+    /// 通过命令行参数创建
+    CommandLine,
+    /// 从模板导入
+    Template,
+    /// 由管理策略强制设置
+    Policy,
+    /// 由局域网中的其他电脑发来
+    Remote,
+    /// 从日历事件导入
+    Calendar,
+    /// 当天的屏幕使用时间达到上限
+    ScreenTime,
+}
+
+impl fmt::Display for TaskSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskSource::Manual => write!(f, "手动设置"),
+            TaskSource::CommandLine => write!(f, "命令行"),
+            TaskSource::Template => write!(f, "模板"),
+            TaskSource::Policy => write!(f, "管理策略"),
+            TaskSource::Remote => write!(f, "远程关机"),
+            TaskSource::Calendar => write!(f, "日历"),
+            TaskSource::ScreenTime => write!(f, "屏幕使用时间"),
         }
     }
 }
@@ -33,6 +202,34 @@ pub enum TimeInput {
     AbsoluteTime(DateTime<Local>),
     /// 每日重复时间（如"22:00"）
     DailyTime(NaiveTime),
+    /// 每周重复时间（如"每周五22:00"、"工作日23:00"）
+    Weekly {
+        /// 执行的星期，按周一到周日排序
+        days: Vec<Weekday>,
+        /// 执行时间
+        time: NaiveTime,
+    },
+    /// cron表达式（如"cron 0 30 22 * * 1-5"）
+    Cron(CronSchedule),
+}
+
+impl TimeInput {
+    /// 输入对应的任务类型
+    /// 
+    /// 每日时间（如"每天22:30"）总是创建每日计划，每周重复时间总是创建每周计划，
+    /// cron表达式总是创建Cron计划，其他输入使用指定的任务类型
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_type` - 界面或命令行指定的任务类型
+    pub fn task_type_or(&self, task_type: TaskType) -> TaskType {
+        match self {
+            TimeInput::DailyTime(_) => TaskType::Daily,
+            TimeInput::Weekly { .. } => TaskType::Weekly,
+            TimeInput::Cron(_) => TaskType::Cron,
+            _ => task_type,
+        }
+    }
 }
 
 impl Default for TimeInput {
@@ -82,9 +279,152 @@ impl fmt::Display for CountdownStatus {
     }
 }
 
+/// 状态快照中的倒计时状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusState {
+    /// 空闲
+    Idle,
+    /// 运行中
+    Running,
+    /// 已暂停
+    Paused,
+    /// 已结束
+    Finished,
+    /// 已取消
+    Cancelled,
+    /// 出错
+    Error,
+}
+
+/// 倒计时状态快照
+/// 
+/// 托盘提示、命令行`status`和本地HTTP接口都使用该快照，保证显示的信息一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    /// 倒计时状态
+    pub state: StatusState,
+    /// 到点时间
+    pub deadline: Option<DateTime<Local>>,
+    /// 剩余秒数
+    pub remaining_seconds: Option<i64>,
+    /// 到点后执行的操作
+    pub action: Option<TaskAction>,
+    /// 任务类型
+    pub task_type: Option<TaskType>,
+    /// 当前任务标识，没有关联任务时为倒计时标识
+    pub task_id: Option<String>,
+    /// 是否已暂停
+    pub paused: bool,
+    /// 任务来源
+    pub source: Option<TaskSource>,
+    /// 错误信息
+    pub error: Option<String>,
+}
+
+impl StatusSnapshot {
+    /// 根据倒计时状态和当前任务生成快照
+    /// 
+    /// # 参数
+    /// 
+    /// * `status` - 倒计时状态
+    /// * `task` - 当前任务
+    /// * `paused` - 是否已暂停
+    /// * `task_id` - 倒计时标识
+    /// * `now` - 当前时间
+    pub fn from_parts(
+        status: &CountdownStatus,
+        task: Option<&TaskData>,
+        paused: bool,
+        task_id: Option<String>,
+        now: DateTime<Local>,
+    ) -> Self {
+        let remaining = match status {
+            CountdownStatus::Running { remaining } => Some(*remaining),
+            _ => None,
+        };
+        let state = match status {
+            CountdownStatus::Idle => StatusState::Idle,
+            CountdownStatus::Running { .. } if paused => StatusState::Paused,
+            CountdownStatus::Running { .. } => StatusState::Running,
+            CountdownStatus::Finished => StatusState::Finished,
+            CountdownStatus::Cancelled => StatusState::Cancelled,
+            CountdownStatus::Error(_) => StatusState::Error,
+        };
+        let active = matches!(state, StatusState::Running | StatusState::Paused);
+        let task = task.filter(|_| active);
+        
+        Self {
+            state,
+            // 暂停时到点时间会随暂停时长推迟，用剩余时间推算
+            deadline: remaining.map(|remaining| now + remaining).or_else(|| task.and_then(|task| task.target_time)),
+            remaining_seconds: remaining.map(|remaining| remaining.num_seconds().max(0)),
+            action: task.map(|task| task.action),
+            task_type: task.map(|task| task.task_type),
+            task_id: task_id.filter(|_| active),
+            paused: state == StatusState::Paused,
+            source: task.map(|task| task.source),
+            error: match status {
+                CountdownStatus::Error(msg) => Some(msg.clone()),
+                _ => None,
+            },
+        }
+    }
+    
+    /// 根据已保存的任务生成快照
+    /// 
+    /// 用于程序未运行时（如命令行查询）显示下一次计划
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 已保存的任务
+    /// * `now` - 当前时间
+    pub fn from_saved_task(task: Option<&TaskData>, now: DateTime<Local>) -> Self {
+        let status = match task.and_then(|task| task.target_time) {
+            Some(target_time) if target_time > now => CountdownStatus::Running { remaining: target_time - now },
+            _ => CountdownStatus::Idle,
+        };
+        Self::from_parts(&status, task, false, None, now)
+    }
+    
+    /// 生成一行状态说明
+    pub fn summary(&self) -> String {
+        match self.state {
+            StatusState::Idle => "空闲，未设置定时任务".to_string(),
+            StatusState::Running | StatusState::Paused => {
+                let total_seconds = self.remaining_seconds.unwrap_or_default();
+                let mut text = format!("剩余时间: {:02}:{:02}:{:02}",
+                    total_seconds / 3600, (total_seconds % 3600) / 60, total_seconds % 60);
+                if let Some(action) = self.action {
+                    text.push_str(&format!("，到点后{}", action));
+                }
+                if self.paused {
+                    text.push_str("（已暂停）");
+                }
+                text
+            },
+            StatusState::Finished => "倒计时结束".to_string(),
+            StatusState::Cancelled => "任务已取消".to_string(),
+            StatusState::Error => format!("错误: {}", self.error.as_deref().unwrap_or_default()),
+        }
+    }
+    
+    /// 转换为JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// 生成新的任务标识
+pub fn new_task_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
 /// 任务数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskData {
+    /// 任务标识，旧版本保存的任务在加载时生成
+    #[serde(default = "new_task_id")]
+    pub id: String,
     /// 任务类型
     pub task_type: TaskType,
     /// 目标时间（单次任务使用）
@@ -95,6 +435,586 @@ pub struct TaskData {
     pub enabled: bool,
     /// 创建时间
     pub created_at: DateTime<Local>,
+    /// 到点后执行的操作
+    #[serde(default)]
+    pub action: TaskAction,
+    /// 每周计划的时间段（每周任务使用），每个时间段有自己的操作
+    #[serde(default)]
+    pub weekly_slots: Vec<WeeklySlot>,
+    /// 任务来源
+    #[serde(default)]
+    pub source: TaskSource,
+    /// 自定义命令（运行命令操作使用）
+    #[serde(default)]
+    pub custom_command: Option<CustomCommand>,
+    /// cron表达式（Cron计划使用）
+    #[serde(default)]
+    pub cron: Option<String>,
+    /// 登录时执行的步骤（登录任务使用）
+    #[serde(default)]
+    pub routine: Option<LogonRoutine>,
+    /// 执行统计
+    #[serde(default)]
+    pub stats: TaskStats,
+    /// 重复任务跳过这个时间及之前的执行，取消本次执行后从下一次开始
+    #[serde(default)]
+    pub skip_until: Option<DateTime<Local>>,
+}
+
+/// 自定义命令
+/// 
+/// 程序和参数分开保存，不经过命令行解释器，参数中的空格和引号不需要转义
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomCommand {
+    /// 程序路径或命令名称
+    pub program: String,
+    /// 命令参数
+    pub args: Vec<String>,
+    /// 工作目录，为空时使用当前目录
+    pub working_dir: Option<String>,
+}
+
+impl CustomCommand {
+    /// 是否未设置程序
+    pub fn is_empty(&self) -> bool {
+        self.program.trim().is_empty()
+    }
+    
+    /// 将参数文本拆分为参数列表
+    /// 
+    /// 以空白分隔，双引号内的空白保留在同一个参数中
+    /// 
+    /// # 参数
+    /// 
+    /// * `input` - 参数文本，如 `--target "D:\备份 目录"`
+    pub fn split_args(input: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+        let mut has_arg = false;
+        
+        for ch in input.chars() {
+            match ch {
+                '"' => {
+                    in_quotes = !in_quotes;
+                    has_arg = true;
+                },
+                c if c.is_whitespace() && !in_quotes => {
+                    if has_arg {
+                        args.push(std::mem::take(&mut current));
+                        has_arg = false;
+                    }
+                },
+                c => {
+                    current.push(c);
+                    has_arg = true;
+                },
+            }
+        }
+        if has_arg {
+            args.push(current);
+        }
+        args
+    }
+    
+    /// 将参数列表合并为可编辑的参数文本
+    pub fn args_text(&self) -> String {
+        self.args.iter()
+            .map(|arg| if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl fmt::Display for CustomCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        if !self.args.is_empty() {
+            write!(f, " {}", self.args_text())?;
+        }
+        Ok(())
+    }
+}
+
+/// 每周计划中的一个时间段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeeklySlot {
+    /// 星期几
+    pub weekday: Weekday,
+    /// 执行时间
+    pub time: NaiveTime,
+    /// 到点后执行的操作
+    pub action: TaskAction,
+}
+
+impl WeeklySlot {
+    /// 为多个星期创建时间和操作相同的时间段
+    /// 
+    /// # 参数
+    /// 
+    /// * `days` - 执行的星期
+    /// * `time` - 执行时间
+    /// * `action` - 到点后执行的操作
+    pub fn for_days(days: &[Weekday], time: NaiveTime, action: TaskAction) -> Vec<WeeklySlot> {
+        days.iter()
+            .map(|&weekday| WeeklySlot { weekday, time, action })
+            .collect()
+    }
+}
+
+/// 星期的中文名称
+pub fn weekday_label(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "周一",
+        Weekday::Tue => "周二",
+        Weekday::Wed => "周三",
+        Weekday::Thu => "周四",
+        Weekday::Fri => "周五",
+        Weekday::Sat => "周六",
+        Weekday::Sun => "周日",
+    }
+}
+
+/// 将多个星期格式化为说明文字，如"周一、周三"，周一到周五显示为"工作日"
+/// 
+/// # 参数
+/// 
+/// * `days` - 按周一到周日排序的星期
+pub fn format_weekdays(days: &[Weekday]) -> String {
+    use Weekday::*;
+    match days {
+        [Mon, Tue, Wed, Thu, Fri] => "工作日".to_string(),
+        [Sat, Sun] => "周末".to_string(),
+        [Mon, Tue, Wed, Thu, Fri, Sat, Sun] => "每天".to_string(),
+        _ => days.iter().map(|&day| weekday_label(day)).collect::<Vec<_>>().join("、"),
+    }
+}
+
+impl TaskData {
+    /// 根据时间输入创建任务
+    /// 
+    /// 每周重复时间总是创建每周计划，每个星期一个时间段；cron表达式总是创建Cron计划
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_type` - 任务类型，每周计划和Cron计划只能由对应的时间输入创建
+    /// * `time_input` - 解析后的时间输入
+    /// * `action` - 到点后执行的操作
+    /// * `now` - 当前时间
+    pub fn from_time_input(task_type: TaskType, time_input: TimeInput, action: TaskAction, now: DateTime<Local>) -> Result<TaskData> {
+        let (target_time, daily_time) = match task_type {
+            TaskType::Once => {
+                let target_time = match time_input {
+                    TimeInput::Duration(duration) => now + duration,
+                    TimeInput::AbsoluteTime(datetime) => datetime,
+                    TimeInput::DailyTime(time) => schedule::next_daily(time, now)?,
+                    TimeInput::Weekly { days, time } => return Self::weekly_from_days(&days, time, action, now),
+                    TimeInput::Cron(schedule) => return Self::from_cron(&schedule, action, now),
+                };
+                (target_time, None)
+            },
+            TaskType::Daily => {
+                let time = match time_input {
+                    TimeInput::DailyTime(time) => time,
+                    TimeInput::AbsoluteTime(datetime) => datetime.time(),
+                    TimeInput::Duration(_) => return Err(anyhow!("每日任务需要具体时间，如 22:30")),
+                    TimeInput::Weekly { days, time } => return Self::weekly_from_days(&days, time, action, now),
+                    TimeInput::Cron(schedule) => return Self::from_cron(&schedule, action, now),
+                };
+                (schedule::next_daily(time, now)?, Some(time))
+            },
+            TaskType::Weekly => match time_input {
+                TimeInput::Weekly { days, time } => return Self::weekly_from_days(&days, time, action, now),
+                _ => return Err(anyhow!("每周计划需要设置时间段")),
+            },
+            TaskType::Cron => match time_input {
+                TimeInput::Cron(schedule) => return Self::from_cron(&schedule, action, now),
+                _ => return Err(anyhow!("Cron计划需要cron表达式，如 cron 0 30 22 * * 1-5")),
+            },
+            TaskType::Logon => return Err(anyhow!("登录任务需要在设置中编辑")),
+        };
+        
+        Ok(TaskData {
+            id: new_task_id(),
+            task_type,
+            target_time: Some(target_time),
+            daily_time,
+            enabled: true,
+            created_at: now,
+            action,
+            weekly_slots: Vec::new(),
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: None,
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
+        })
+    }
+    
+    /// 创建每个星期时间和操作相同的每周计划
+    fn weekly_from_days(days: &[Weekday], time: NaiveTime, action: TaskAction, now: DateTime<Local>) -> Result<TaskData> {
+        let mut task = TaskData {
+            id: new_task_id(),
+            task_type: TaskType::Weekly,
+            target_time: None,
+            daily_time: None,
+            enabled: true,
+            created_at: now,
+            action,
+            weekly_slots: WeeklySlot::for_days(days, time, action),
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: None,
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
+        };
+        let (target_time, _) = schedule::next_weekly(&task.weekly_slots, now)
+            .ok_or_else(|| anyhow!("每周计划需要至少选择一天"))?;
+        task.target_time = Some(target_time);
+        Ok(task)
+    }
+    
+    /// 创建按cron表达式重复的Cron计划
+    fn from_cron(schedule: &CronSchedule, action: TaskAction, now: DateTime<Local>) -> Result<TaskData> {
+        let target_time = schedule.next_after(now)
+            .ok_or_else(|| anyhow!("cron表达式\"{}\"没有可执行的时间", schedule))?;
+        Ok(TaskData {
+            id: new_task_id(),
+            task_type: TaskType::Cron,
+            target_time: Some(target_time),
+            daily_time: None,
+            enabled: true,
+            created_at: now,
+            action,
+            weekly_slots: Vec::new(),
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: Some(schedule.expression().to_string()),
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
+        })
+    }
+    
+    /// 创建每天第一次登录时执行的登录任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `steps` - 按顺序执行的步骤
+    /// * `now` - 当前时间
+    pub fn logon(steps: Vec<RoutineStep>, now: DateTime<Local>) -> TaskData {
+        TaskData {
+            id: new_task_id(),
+            task_type: TaskType::Logon,
+            target_time: None,
+            daily_time: None,
+            enabled: true,
+            created_at: now,
+            action: TaskAction::default(),
+            weekly_slots: Vec::new(),
+            source: TaskSource::Manual,
+            custom_command: None,
+            cron: None,
+            routine: Some(LogonRoutine { steps, last_run: None }),
+            stats: TaskStats::default(),
+            skip_until: None,
+        }
+    }
+    
+    /// 把每周计划中时间和操作相同的时间段合并为一组
+    /// 
+    /// # 返回值
+    /// 
+    /// 每组的执行时间、操作和星期，按时间段出现的顺序排列
+    pub fn weekly_groups(&self) -> Vec<(NaiveTime, TaskAction, Vec<Weekday>)> {
+        let mut groups: Vec<(NaiveTime, TaskAction, Vec<Weekday>)> = Vec::new();
+        for slot in &self.weekly_slots {
+            match groups.iter_mut().find(|(time, action, _)| *time == slot.time && *action == slot.action) {
+                Some((_, _, weekdays)) => weekdays.push(slot.weekday),
+                None => groups.push((slot.time, slot.action, vec![slot.weekday])),
+            }
+        }
+        groups
+    }
+    
+    /// 生成一行计划说明，如"每日关机 22:30，睡眠"
+    pub fn schedule_text(&self) -> String {
+        let schedule = match self.task_type {
+            TaskType::Once => self.target_time
+                .map(|time| time.format("%m-%d %H:%M").to_string())
+                .unwrap_or_default(),
+            TaskType::Daily => self.daily_time
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            TaskType::Weekly => self.weekly_summary(),
+            TaskType::Cron => self.cron.clone().unwrap_or_default(),
+            TaskType::Logon => {
+                let steps = self.routine.as_ref().map(|routine| routine.describe()).unwrap_or_default();
+                return format!("{}，{}", self.task_type, steps);
+            },
+        };
+        format!("{} {}，{}", self.task_type, schedule, self.action)
+    }
+    
+    /// 解析任务保存的cron表达式
+    /// 
+    /// 表达式缺失或无效时返回None并记录警告
+    pub fn cron_schedule(&self) -> Option<CronSchedule> {
+        let expression = self.cron.as_deref()?;
+        CronSchedule::parse(expression)
+            .map_err(|e| warn!("任务 {} 的cron表达式无效: {}", self.id, e))
+            .ok()
+    }
+    
+    /// 每周计划的说明
+    /// 
+    /// 所有时间段的时间和操作相同时显示星期和时间，如"工作日 23:00"，否则显示时间段数量
+    fn weekly_summary(&self) -> String {
+        match self.weekly_slots.first() {
+            Some(first) if self.weekly_slots.iter().all(|slot| slot.time == first.time && slot.action == first.action) => {
+                let mut days: Vec<Weekday> = self.weekly_slots.iter().map(|slot| slot.weekday).collect();
+                days.sort_by_key(|day| day.num_days_from_monday());
+                days.dedup();
+                format!("{} {}", format_weekdays(&days), first.time.format("%H:%M"))
+            },
+            _ => format!("{}个时间段", self.weekly_slots.len()),
+        }
+    }
+    
+    /// 生成可以重新输入主窗口的时间文本
+    /// 
+    /// 用于编辑已保存的任务，解析结果与原任务的计划相同
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 单次任务已过期、每周计划各时间段的时间或操作不同，或是登录任务时返回None
+    pub fn input_text(&self, now: DateTime<Local>) -> Option<String> {
+        match self.task_type {
+            TaskType::Once => {
+                let target_time = self.target_time.filter(|time| *time > now)?;
+                if target_time.date_naive() == now.date_naive() {
+                    Some(target_time.format("%H:%M").to_string())
+                } else {
+                    let minutes = ((target_time - now).num_seconds() + 59) / 60;
+                    Some(format!("{}分钟", minutes))
+                }
+            },
+            TaskType::Daily => self.daily_time.map(|time| format!("每天{}", time.format("%H:%M"))),
+            TaskType::Weekly => {
+                let first = self.weekly_slots.first()?;
+                if !self.weekly_slots.iter().all(|slot| slot.time == first.time && slot.action == first.action) {
+                    return None;
+                }
+                let mut days: Vec<u32> = self.weekly_slots.iter().map(|slot| slot.weekday.number_from_monday()).collect();
+                days.sort_unstable();
+                days.dedup();
+                let days: Vec<String> = days.iter().map(|day| day.to_string()).collect();
+                Some(format!("每周{} {}", days.join("、"), first.time.format("%H:%M")))
+            },
+            TaskType::Cron => self.cron.as_ref().map(|expression| format!("cron {}", expression)),
+            TaskType::Logon => None,
+        }
+    }
+    
+    /// 计算任务的电源需求
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回执行该任务所需的电源条件
+    pub fn power_requirements(&self) -> Vec<PowerRequirement> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        match self.task_type {
+            TaskType::Weekly => {
+                let actions: Vec<TaskAction> = self.weekly_slots.iter().map(|slot| slot.action).collect();
+                PowerRequirement::for_task(self.task_type, &actions)
+            },
+            _ => PowerRequirement::for_task(self.task_type, &[self.action]),
+        }
+    }
+}
+
+/// 夏令时跳过的时间最长按该时长向前查找切换前的时区偏移
+const DST_LOOKBACK_HOURS: i64 = 3;
+
+/// 将本地日期时间转换为带时区的时间
+/// 
+/// 夏令时切换时的处理方式：
+/// - 重复出现的时间（时钟回拨）取较早的一次
+/// - 不存在的时间（时钟拨快）按切换前的时区偏移换算，即顺延跳过的时长，
+///   例如跳过02:00-03:00时，02:30顺延为03:30
+/// 
+/// 两种情况都会记录警告日志
+/// 
+/// # 参数
+/// 
+/// * `naive` - 本地日期时间
+pub fn resolve_local_datetime(naive: NaiveDateTime) -> Result<DateTime<Local>> {
+    match Local.from_local_datetime(&naive) {
+        LocalResult::Single(time) => Ok(time),
+        LocalResult::Ambiguous(earliest, _) => {
+            warn!("本地时间 {} 因夏令时切换出现两次，使用较早的一次", naive);
+            Ok(earliest)
+        },
+        LocalResult::None => {
+            let before = Local.from_local_datetime(&(naive - Duration::hours(DST_LOOKBACK_HOURS)))
+                .earliest()
+                .ok_or_else(|| anyhow!("时间 {} 在本地时区不存在", naive.format("%Y-%m-%d %H:%M")))?;
+            let utc = naive - Duration::seconds(before.offset().local_minus_utc() as i64);
+            let shifted = Local.from_utc_datetime(&utc);
+            warn!("本地时间 {} 因夏令时切换不存在，顺延为 {}", naive, shifted.format("%Y-%m-%d %H:%M"));
+            Ok(shifted)
+        },
+    }
+}
+
+/// 任务电源需求枚举
+/// 
+/// 用于提示笔记本用户任务对睡眠和电量的影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerRequirement {
+    /// 需要唤醒定时器，在计算机睡眠时到点唤醒
+    WakeTimer,
+    /// 需要阻止系统睡眠直到任务执行
+    KeepAwake,
+}
+
+impl PowerRequirement {
+    /// 根据任务触发方式和到点后执行的操作计算电源需求
+    /// 
+    /// 单次任务依赖进程内倒计时，计算机睡眠后倒计时无法按时触发，因此需要阻止睡眠；
+    /// 每日任务跨越较长时间，计算机可能已进入睡眠，需要唤醒定时器才能按时执行；
+    /// 登录任务在登录后执行，没有电源需求。所有操作在计算机睡眠后都不必再执行时
+    /// （睡眠、锁定、关闭显示器），同样没有电源需求
+    /// 
+    /// # 参数
+    /// 
+    /// * `task_type` - 任务类型
+    /// * `actions` - 到点后执行的操作，每周计划的每个时间段各有一个
+    pub fn for_task(task_type: TaskType, actions: &[TaskAction]) -> Vec<PowerRequirement> {
+        if !actions.iter().any(|action| action.needs_awake_computer()) {
+            return Vec::new();
+        }
+        match task_type {
+            TaskType::Once => vec![PowerRequirement::KeepAwake],
+            TaskType::Daily | TaskType::Weekly | TaskType::Cron => vec![PowerRequirement::WakeTimer],
+            TaskType::Logon => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for PowerRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowerRequirement::WakeTimer => write!(f, "需要唤醒定时器"),
+            PowerRequirement::KeepAwake => write!(f, "阻止睡眠"),
+        }
+    }
+}
+
+/// 默认配置方案名称
+pub const DEFAULT_PROFILE_NAME: &str = "默认";
+
+/// 输入历史最多保留的条数
+pub const MAX_INPUT_HISTORY: usize = 10;
+
+/// 配置方案数据
+/// 
+/// 每个配置方案独立保存时间输入历史、预设和快速倒计时按钮
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileData {
+    /// 配置方案名称
+    pub name: String,
+    /// 时间输入历史（最近的在前）
+    #[serde(default)]
+    pub input_history: Vec<String>,
+    /// 时间输入预设
+    #[serde(default)]
+    pub presets: Vec<String>,
+    /// 快速倒计时按钮（分钟）
+    #[serde(default = "ProfileData::default_quick_minutes")]
+    pub quick_minutes: Vec<u32>,
+}
+
+impl Default for ProfileData {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROFILE_NAME)
+    }
+}
+
+impl ProfileData {
+    /// 创建新的配置方案
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 配置方案名称
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            input_history: Vec::new(),
+            presets: Vec::new(),
+            quick_minutes: Self::default_quick_minutes(),
+        }
+    }
+    
+    /// 默认的快速倒计时按钮
+    fn default_quick_minutes() -> Vec<u32> {
+        vec![5, 10, 30, 60]
+    }
+    
+    /// 快速倒计时按钮的时长
+    pub fn quick_durations(&self) -> Vec<Duration> {
+        self.quick_minutes.iter().map(|&minutes| Duration::minutes(minutes as i64)).collect()
+    }
+    
+    /// 记录一次时间输入
+    /// 
+    /// 重复的输入会被移动到最前面，超出上限的旧记录会被丢弃
+    /// 
+    /// # 参数
+    /// 
+    /// * `input` - 时间输入字符串
+    pub fn record_input(&mut self, input: &str) {
+        let input = input.trim();
+        if input.is_empty() {
+            return;
+        }
+        
+        self.input_history.retain(|item| item != input);
+        self.input_history.insert(0, input.to_string());
+        self.input_history.truncate(MAX_INPUT_HISTORY);
+    }
+    
+    /// 添加时间输入预设
+    /// 
+    /// # 参数
+    /// 
+    /// * `preset` - 预设的时间输入字符串
+    /// 
+    /// # 返回值
+    /// 
+    /// 添加成功返回true，空字符串或已存在返回false
+    pub fn add_preset(&mut self, preset: &str) -> bool {
+        let preset = preset.trim();
+        if preset.is_empty() || self.presets.iter().any(|item| item == preset) {
+            return false;
+        }
+        
+        self.presets.push(preset.to_string());
+        true
+    }
 }
 
 /// 倒计时更新消息
@@ -102,6 +1022,8 @@ pub struct TaskData {
 pub enum CountdownUpdate {
     /// 倒计时进度更新
     Progress { remaining: Duration, progress: f64 },
+    /// 到达到点前的提醒时间，`remaining`为该提醒时间
+    Warning { remaining: Duration },
     /// 倒计时完成
     Finished,
     /// 倒计时取消
@@ -112,17 +1034,100 @@ pub enum CountdownUpdate {
     Resumed,
     /// 任务完成
     TaskCompleted { task_info: TaskData },
+    /// 检测到其他程序或手动设置的关机计划，等待用户选择保留或取消
+    ExternalShutdownDetected,
+    /// 正在等待网络空闲（下载完成），`quiet_seconds`为网速已持续低于设置值的秒数
+    NetworkWaiting { rate_kbps: f64, quiet_seconds: u64 },
+    /// 到点时执行条件不满足（如指定的进程正在运行），已推迟`retry_after`后再次检查
+    Postponed { reason: String, retry_after: Duration },
+    /// 需要提示用户的消息，如登录任务显示的今日计划
+    Notice { title: String, content: String },
+    /// 托盘菜单等要求显示或隐藏主窗口，由连接的界面处理
+    WindowRequested(WindowRequest),
     /// 倒计时错误
     Error(String),
 }
 
+/// 对主窗口的请求
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowRequest {
+    /// 显示主窗口
+    Show,
+    /// 切换主窗口显示状态
+    Toggle,
+    /// 显示主窗口并打开设置
+    Settings,
+    /// 显示主窗口并打开关于
+    About,
+    /// 恢复界面改动的系统设置后退出程序
+    Exit,
+    /// 显示主窗口并确认是否开始不到一分钟就会执行操作的倒计时
+    ConfirmShortStart {
+        /// 距离执行操作的秒数
+        seconds: i64,
+        /// 到点后执行的操作
+        action: TaskAction,
+    },
+}
+
+/// 启动时主窗口的显示方式，后面的方式优先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum WindowMode {
+    /// 正常显示主窗口
+    #[default]
+    Normal,
+    /// 最小化到托盘
+    Minimized,
+    /// 后台代理：不显示主窗口，只显示托盘图标，关闭主窗口时隐藏而不退出
+    TrayOnly,
+}
+
+/// 快速倒计时时长的显示文字
+/// 
+/// 整小时显示为"1小时"，其余按分钟显示，如"90分钟"
+/// 
+/// # 参数
+/// 
+/// * `duration` - 倒计时时长
+pub fn format_quick_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 60 && minutes % 60 == 0 {
+        format!("{}小时", minutes / 60)
+    } else {
+        format!("{}分钟", minutes)
+    }
+}
+
+/// 快速倒计时（主窗口和托盘中的时长按钮）到点后执行的操作
+pub const QUICK_COUNTDOWN_ACTION: TaskAction = TaskAction::Shutdown;
+
 /// UI事件枚举
 #[derive(Debug, Clone)]
 pub enum UIEvent {
     /// 开始倒计时
-    StartCountdown(TimeInput, TaskType),
+    StartCountdown(TimeInput, TaskType, TaskAction),
     /// 取消倒计时
     CancelCountdown,
+    /// 输入PIN后取消倒计时（管理策略要求PIN时使用）
+    CancelCountdownWithPin(String),
+    /// 暂停倒计时
+    PauseCountdown,
+    /// 恢复倒计时
+    ResumeCountdown,
+    /// 延长倒计时（秒）
+    ExtendCountdown(u32),
+    /// 缩短倒计时（秒）
+    ShortenCountdown(u32),
+    /// 第一次提醒时选择的改期方式
+    Reschedule(RescheduleChoice),
+    /// 为最近一次取消补充原因
+    RecordCancelReason(CancelReason),
+    /// 删除任务
+    RemoveTask(String),
+    /// 修改已保存的任务（按任务标识替换）
+    UpdateTask(TaskData),
+    /// 启用或停用任务
+    SetTaskEnabled(String, bool),
     /// 最小化到托盘
     MinimizeToTray,
     /// 从托盘恢复
@@ -131,8 +1136,45 @@ pub enum UIEvent {
     ShowMainWindow,
     /// 切换主窗口显示状态
     ToggleMainWindow,
-    /// 快速倒计时
-    QuickCountdown(u32),
+    /// 快速倒计时，到点后执行`QUICK_COUNTDOWN_ACTION`
+    QuickCountdown(Duration),
+    /// 按历史记录从现在开始重复上次执行或取消的倒计时
+    RepeatLast,
+    /// 用户确认（true）或放弃（false）等待确认的短倒计时
+    ConfirmShortStart(bool),
+    /// 网络空闲（下载完成）后开始最后警告，然后执行指定操作
+    WaitForNetworkIdle(TaskAction),
+    /// 指定进程退出后按设置的分钟数倒计时，然后执行指定操作
+    WaitForProcessExit(ProcessExitTrigger, TaskAction),
+    /// 市电断开、改由UPS电池供电后开始最后警告，然后执行指定操作
+    WaitForPowerLoss(TaskAction),
+    /// 当天的屏幕使用时间达到上限，开始最后警告，然后执行指定操作
+    ScreenTimeLimitReached(TaskAction),
+    /// 开始每周计划
+    StartWeekly(Vec<WeeklySlot>),
+    /// 保存每天第一次登录时执行的步骤，步骤为空时删除登录任务
+    SaveLogonRoutine(Vec<RoutineStep>),
+    /// 局域网中的其他电脑发来的命令，延迟后执行操作
+    RemoteCommand {
+        /// 到点后执行的操作
+        action: TaskAction,
+        /// 多长时间后执行
+        delay: Duration,
+        /// 发送方的名称
+        from: String,
+    },
+    /// 开启或关闭同步到Windows任务计划程序
+    SetSystemTasks(bool),
+    /// 系统即将结束会话（关机、重启或注销），处理完成后通过发送器通知
+    SessionEnding(std::sync::mpsc::Sender<()>),
+    /// 系统从睡眠或休眠中唤醒
+    SystemResumed,
+    /// 取消检测到的外部关机计划
+    CancelExternalShutdown,
+    /// 恢复上一个版本的任务文件
+    RestoreTaskBackup,
+    /// 用导入的设置包中的任务替换当前任务
+    ImportTasks(Vec<TaskData>),
     /// 显示设置
     ShowSettings,
     /// 显示关于
@@ -146,10 +1188,34 @@ pub enum UIEvent {
 pub enum ShutdownMethod {
     /// 使用shutdown.exe命令
     Command,
-    /// 使用Windows API
+    /// 使用系统接口（Windows API，Linux和macOS下为systemctl等系统命令）
     WinAPI,
 }
 
+/// 到点时工作站已锁定的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LockedBehavior {
+    /// 按时执行
+    #[default]
+    RunOnTime,
+    /// 推迟到下次解锁后立即执行
+    DeferAction,
+    /// 推迟到下次解锁后先显示最后警告，警告结束再执行
+    DeferWarning,
+}
+
+/// 最后警告期间检测到键盘鼠标输入时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ActivityBehavior {
+    /// 不处理，按时执行
+    #[default]
+    Ignore,
+    /// 暂停倒计时
+    Pause,
+    /// 取消倒计时
+    Cancel,
+}
+
 /// Windows版本信息
 #[derive(Debug, Clone)]
 pub struct WindowsVersion {
@@ -0,0 +1,211 @@
+//! 外部通知模块
+//! 
+//! 在设置中填写网址后，任务到点执行、被取消或执行失败时把事件以JSON格式POST到该网址，
+//! 例如家里共用电脑上的定时关机执行后在手机上收到消息。使用系统自带的curl发送，
+//! 发送失败只记录警告，不影响任务执行。不能直接发送邮件，需要邮件通知时可以使用转发邮件的webhook服务
+//! 
+//! 发送的内容如：
+//! 
+//! ```json
+//! {"event":"executing","computer":"书房","action":"Shutdown","task":"每日 22:30，关机","message":"书房即将关机","detail":null,"at":"2024-03-08T22:30:00+08:00"}
+//! ```
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command as AsyncCommand;
+
+use crate::core::types::{TaskAction, TaskData};
+use crate::platform::{self, SystemInfo};
+use crate::utils::system;
+
+/// 发送的超时时间（秒），到点执行前会等待发送完成
+pub const WEBHOOK_TIMEOUT_SECONDS: u64 = 5;
+
+/// 外部通知设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    /// 接收事件的网址，为空时不发送
+    pub url: String,
+}
+
+impl WebhookSettings {
+    /// 是否发送外部通知
+    pub fn is_enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+}
+
+/// 检查接收事件的网址
+/// 
+/// # 参数
+/// 
+/// * `url` - 网址，只支持http和https
+pub fn validate_url(url: &str) -> Result<()> {
+    let url = url.trim();
+    let host = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| anyhow!("网址需要以http://或https://开头"))?;
+    if host.is_empty() || host.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err(anyhow!("网址格式不正确: {}", url));
+    }
+    Ok(())
+}
+
+/// 外部通知的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// 到点后即将执行操作
+    Executing,
+    /// 倒计时被取消，包括电量过低时自动取消
+    Cancelled,
+    /// 执行操作失败
+    Failed,
+    /// 在设置中发送的测试消息
+    Test,
+}
+
+/// 发送的事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// 事件
+    pub event: WebhookEvent,
+    /// 发出事件的电脑名称
+    pub computer: String,
+    /// 操作，没有任务的倒计时被取消时为空
+    pub action: Option<TaskAction>,
+    /// 任务的计划说明
+    pub task: Option<String>,
+    /// 一句话说明，可以直接显示在手机通知中
+    pub message: String,
+    /// 取消原因或失败原因
+    pub detail: Option<String>,
+    /// 事件发生的时间
+    pub at: DateTime<Local>,
+}
+
+impl WebhookPayload {
+    /// 创建事件内容
+    /// 
+    /// # 参数
+    /// 
+    /// * `event` - 事件
+    /// * `computer` - 电脑名称
+    /// * `action` - 操作
+    /// * `task` - 相关的任务
+    /// * `detail` - 取消原因或失败原因
+    /// * `now` - 当前时间
+    pub fn new(event: WebhookEvent, computer: &str, action: Option<TaskAction>, task: Option<&TaskData>, detail: Option<String>, now: DateTime<Local>) -> Self {
+        let action_text = action.map(|action| action.to_string()).unwrap_or_else(|| "倒计时".to_string());
+        let mut message = match event {
+            WebhookEvent::Executing => format!("{}即将{}", computer, action_text),
+            WebhookEvent::Cancelled => format!("{}已取消{}", computer, action_text),
+            WebhookEvent::Failed => format!("{}{}失败", computer, action_text),
+            WebhookEvent::Test => format!("{}的QtShut测试消息", computer),
+        };
+        if let Some(detail) = &detail {
+            message = format!("{}: {}", message, detail);
+        }
+        Self {
+            event,
+            computer: computer.to_string(),
+            action,
+            task: task.map(|task| task.schedule_text()),
+            message,
+            detail,
+            at: now,
+        }
+    }
+}
+
+/// 发送事件
+/// 
+/// # 参数
+/// 
+/// * `url` - 接收事件的网址
+/// * `payload` - 事件内容
+pub async fn send(url: &str, payload: &WebhookPayload) -> Result<()> {
+    if system::is_safe_mode() {
+        return Err(anyhow!("安全模式下不发送外部通知"));
+    }
+    validate_url(url)?;
+    let body = serde_json::to_string(payload).map_err(|e| anyhow!("序列化事件失败: {}", e))?;
+    let output = AsyncCommand::new("curl")
+        .args([
+            "-fsS",
+            "--max-time", &WEBHOOK_TIMEOUT_SECONDS.to_string(),
+            "-X", "POST",
+            "-H", "Content-Type: application/json",
+            "--data-binary", &body,
+            url.trim(),
+        ])
+        .output()
+        .await
+        .map_err(|e| anyhow!("无法运行curl发送外部通知: {}", e))?;
+    
+    if !output.status.success() {
+        return Err(anyhow!("发送外部通知失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    info!("已发送外部通知: {}", payload.message);
+    Ok(())
+}
+
+/// 按设置发送事件，没有设置网址时不发送，发送失败只记录警告
+/// 
+/// # 参数
+/// 
+/// * `settings` - 外部通知设置
+/// * `event` - 事件
+/// * `action` - 操作
+/// * `task` - 相关的任务
+/// * `detail` - 取消原因或失败原因
+pub async fn notify(settings: &WebhookSettings, event: WebhookEvent, action: Option<TaskAction>, task: Option<&TaskData>, detail: Option<String>) {
+    if !settings.is_enabled() {
+        return;
+    }
+    let payload = WebhookPayload::new(event, &computer_name(), action, task, detail, Local::now());
+    if let Err(e) = send(&settings.url, &payload).await {
+        warn!("{}", e);
+    }
+}
+
+/// 本机名称，获取失败时为"QtShut"
+pub fn computer_name() -> String {
+    platform::native().computer_name().unwrap_or_else(|_| "QtShut".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{TaskType, TimeInput};
+    
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://example.com/hook").is_ok());
+        assert!(validate_url(" http://192.168.1.2:8080/qtshut ").is_ok());
+        assert!(validate_url("example.com/hook").is_err());
+        assert!(validate_url("https://").is_err());
+        assert!(validate_url("https://example.com/a b").is_err());
+        assert!(!WebhookSettings::default().is_enabled());
+    }
+    
+    #[test]
+    fn test_payload() {
+        let now = Local::now();
+        let task = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(chrono::Duration::minutes(30)), TaskAction::Shutdown, now).unwrap();
+        let payload = WebhookPayload::new(WebhookEvent::Executing, "书房", Some(task.action), Some(&task), None, now);
+        assert_eq!(payload.message, "书房即将关机");
+        assert_eq!(payload.task, Some(task.schedule_text()));
+        
+        let json = serde_json::to_string(&payload).unwrap();
+        assert!(json.contains(r#""event":"executing""#));
+        assert!(json.contains(r#""action":"Shutdown""#));
+        
+        let failed = WebhookPayload::new(WebhookEvent::Failed, "书房", Some(TaskAction::Restart), None, Some("权限不足".to_string()), now);
+        assert_eq!(failed.message, "书房重启失败: 权限不足");
+        let cancelled = WebhookPayload::new(WebhookEvent::Cancelled, "书房", None, None, None, now);
+        assert_eq!(cancelled.message, "书房已取消倒计时");
+    }
+}
@@ -3,10 +3,15 @@
 //! 这是一个专为普通家庭用户设计的定时关机工具，
 //! 提供极简的操作界面和可靠的定时关机功能。
 
-use log::info;
+use std::io::Write;
+
+use log::{info, warn};
 
 mod app;
+mod cli;
 mod core;
+mod platform;
+mod selftest;
 mod ui;
 mod utils;
 
@@ -16,15 +21,106 @@ mod utils;
 /// 初始化日志系统并启动GUI
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // 初始化日志系统
+    // 按设置中的日志级别初始化日志系统，同时保留最近的日志供崩溃报告使用
     env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info)
+        .filter_level(utils::config::ConfigManager::load_log_level())
+        .format(|buf, record| {
+            let line = format!(
+                "[{}] [{}] [{}] {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            utils::crash::remember_log(&line);
+            utils::logger::write_log_line(&line);
+            writeln!(buf, "{}", line)
+        })
         .init();
 
     info!("QtShut 启动中...");
+    
+    // 崩溃时在数据目录中保存诊断报告
+    match core::persistence::TaskPersistence::new() {
+        Ok(persistence) => utils::crash::install_panic_hook(persistence.get_data_dir().to_path_buf()),
+        Err(e) => warn!("无法保存崩溃报告: {}", e),
+    }
 
+    // 解析命令行参数
+    let cli_args = match cli::CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli_args) => cli_args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::HELP_TEXT);
+            return Err(e.into());
+        }
+    };
+    
+    if cli_args.show_help {
+        println!("{}", cli::HELP_TEXT);
+        return Ok(());
+    }
+    
+    if let Some(pin) = &cli_args.hash_pin {
+        println!("{}", utils::policy::hash_pin(pin));
+        return Ok(());
+    }
+    
+    // 优先查询正在运行的实例，没有实例在运行时显示已保存任务的计划状态
+    if cli_args.show_status {
+        let port = utils::config::ConfigManager::load_overlay().port;
+        let snapshot = match ui::overlay::OverlayServer::fetch_status(port).await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                info!("没有连接到正在运行的QtShut（{}），显示已保存的任务", e);
+                let persistence = core::persistence::TaskPersistence::new()?;
+                let storage = core::storage::open(persistence, utils::config::ConfigManager::load_storage_backend());
+                cli::saved_task_status(storage.as_ref())?
+            }
+        };
+        println!("{}", cli::format_status(&snapshot));
+        return Ok(());
+    }
+    
+    if cli_args.run_selftest {
+        let report = selftest::run().await;
+        println!("{}", report.format());
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    
+    if let Some(path) = &cli_args.replay_file {
+        println!("{}", app::App::replay(path).await?);
+        return Ok(());
+    }
+    
+    if cli_args.safe_mode {
+        utils::system::enable_safe_mode();
+    }
+    
+    // 创建命令行指定的任务
+    if cli_args.has_tasks() {
+        let mut persistence = core::persistence::TaskPersistence::new()?;
+        persistence.set_max_backups(utils::config::ConfigManager::load_max_backup_files());
+        persistence.set_encrypt_data(utils::config::ConfigManager::load_encrypt_data());
+        let storage = core::storage::open(persistence, utils::config::ConfigManager::load_storage_backend());
+        let summary = cli::create_tasks(&cli_args, storage.as_ref())?;
+        println!("{}", summary);
+    }
+    
+    // 启动后立即开始的倒计时，时间有误时直接退出，不启动界面
+    let launch_event = match cli_args.launch_event(core::time_parser::TimeParser::global()) {
+        Ok(launch_event) => launch_event,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::HELP_TEXT);
+            return Err(e.into());
+        }
+    };
+    
     // 创建并启动应用
-    let app = app::App::new().await?;
+    let mut app = app::App::new().await?;
+    app.set_launch_options(launch_event, cli_args.window_mode());
     app.run().await?;
     
     Ok(())
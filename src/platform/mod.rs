@@ -0,0 +1,488 @@
+//! 平台抽象模块
+//! 
+//! 用安全的接口封装所有系统调用：关机和电源操作、令牌权限、系统版本、
+//! 空闲时间与会话状态、窗口与显示器、提示音和屏幕阅读器通知、数据加密。
+//! Windows上基于windows crate实现，Linux和macOS通过systemctl、osascript等系统命令实现，
+//! 其他平台使用空实现，核心逻辑可以跨平台编译和测试。
+//! 各平台支持的功能不同，界面根据`Capabilities`隐藏不可用的功能
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+
+#[cfg(windows)]
+mod win32;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod unix;
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+mod stub;
+
+#[cfg(windows)]
+pub use win32::NativePlatform;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub use unix::NativePlatform;
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+pub use stub::NativePlatform;
+
+/// 获取当前平台的系统接口
+pub fn native() -> &'static NativePlatform {
+    &NativePlatform
+}
+
+/// 获取当前平台支持的功能
+pub fn capabilities() -> Capabilities {
+    NativePlatform::CAPABILITIES
+}
+
+/// 平台支持的功能
+/// 
+/// 界面和执行器据此隐藏或跳过当前平台上不可用的功能
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// 能否关机和重启
+    pub power_off: bool,
+    /// 能否进入睡眠
+    pub sleep: bool,
+    /// 能否休眠（保存到硬盘后断电）
+    pub hibernate: bool,
+    /// 能否锁定
+    pub lock: bool,
+    /// 能否注销
+    pub logoff: bool,
+    /// 能否关闭显示器
+    pub monitor_off: bool,
+    /// 是否提供shutdown.exe命令
+    pub shutdown_command: bool,
+    /// 能否注册关机阻止原因
+    pub shutdown_block: bool,
+    /// 能否显示托盘图标
+    pub system_tray: bool,
+    /// 能否查询和移动窗口位置
+    pub window_placement: bool,
+    /// 能否发出屏幕阅读器通知
+    pub screen_reader: bool,
+    /// 能否检测键盘鼠标空闲时间
+    pub idle_detection: bool,
+    /// 能否查询和切换专注助手（勿扰模式）
+    pub focus_assist: bool,
+    /// 能否在系统结束会话前收到通知
+    pub session_end_notice: bool,
+    /// 能否在系统从睡眠中唤醒时收到通知
+    pub resume_notice: bool,
+    /// 能否把计划同步到系统的任务计划程序
+    pub system_scheduler: bool,
+    /// 能否登记开机自动启动
+    pub autostart: bool,
+    /// 能否加密只允许当前用户读取的数据
+    pub data_protection: bool,
+}
+
+/// 系统启动以来的累计CPU时间
+/// 
+/// 两次采样的差值即为这段时间内的CPU占用，单位由平台决定，只用于计算比例
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    /// 空闲时间
+    pub idle: u64,
+    /// 总时间（含空闲）
+    pub total: u64,
+}
+
+/// 电源状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerStatus {
+    /// 是否正在使用电池供电
+    pub on_battery: bool,
+    /// 剩余电量百分比，无法获取时为空
+    pub battery_percent: Option<u8>,
+}
+
+/// 结束会话的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEnd {
+    /// 关机
+    Shutdown,
+    /// 重启
+    Restart,
+    /// 注销当前用户
+    Logoff,
+}
+
+/// 操作系统版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsVersionInfo {
+    /// 主版本号
+    pub major: u32,
+    /// 次版本号
+    pub minor: u32,
+    /// 内部版本号
+    pub build: u32,
+    /// 服务包名称，没有时为空
+    pub service_pack: String,
+    /// 是否为工作站版本（非服务器）
+    pub is_workstation: bool,
+}
+
+/// 系统启动模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootMode {
+    /// 正常启动
+    Normal,
+    /// 安全模式
+    SafeMode,
+    /// 安全模式（带网络）
+    SafeModeWithNetwork,
+    /// 无法识别的启动模式
+    Unknown(i32),
+}
+
+/// 注册表中的值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryValue {
+    /// 字符串（REG_SZ）
+    String(String),
+    /// 二进制数据（REG_BINARY）
+    Binary(Vec<u8>),
+}
+
+/// 系统提示音
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Beep {
+    /// 默认提示音
+    Default,
+    /// 信息提示音
+    Asterisk,
+    /// 警告提示音
+    Exclamation,
+    /// 错误提示音
+    Hand,
+}
+
+/// 专注助手（勿扰模式）状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusAssist {
+    /// 关闭，正常显示所有通知
+    Off,
+    /// 仅显示优先通知
+    PriorityOnly,
+    /// 仅显示闹钟
+    AlarmsOnly,
+}
+
+/// 矩形区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenRect {
+    /// 左边界
+    pub left: i32,
+    /// 上边界
+    pub top: i32,
+    /// 右边界
+    pub right: i32,
+    /// 下边界
+    pub bottom: i32,
+}
+
+impl ScreenRect {
+    /// 宽度
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+    
+    /// 高度
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+    
+    /// 是否包含指定点
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.left && x < self.right && y >= self.top && y < self.bottom
+    }
+}
+
+/// 显示器信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorInfo {
+    /// 显示器设备名称，如"\\.\DISPLAY2"
+    pub name: String,
+    /// 工作区域（不含任务栏）
+    pub work_area: ScreenRect,
+}
+
+/// 关机和电源操作
+pub trait PowerControl {
+    /// 为当前进程启用关机权限
+    fn enable_shutdown_privilege(&self) -> Result<()>;
+    
+    /// 立即关机、重启或注销
+    /// 
+    /// # 参数
+    /// 
+    /// * `kind` - 结束会话的方式
+    /// * `force` - 是否强制关闭程序（忽略未保存的工作）
+    fn end_session(&self, kind: SessionEnd, force: bool) -> Result<()>;
+    
+    /// 由系统倒计时后关机或重启，倒计时期间关机对话框中显示原因
+    /// 
+    /// # 参数
+    /// 
+    /// * `reason` - 关机原因
+    /// * `delay_seconds` - 延迟秒数
+    /// * `restart` - 是否重启
+    fn schedule_shutdown(&self, reason: &str, delay_seconds: u32, restart: bool) -> Result<()>;
+    
+    /// 取消系统中待执行的延迟关机
+    /// 
+    /// # 返回值
+    /// 
+    /// 存在并已取消返回true，没有待执行的关机返回false
+    fn abort_scheduled_shutdown(&self) -> Result<bool>;
+    
+    /// 系统中是否有待执行的延迟关机，只查询不取消
+    fn has_scheduled_shutdown(&self) -> bool;
+    
+    /// 进入睡眠状态
+    fn suspend(&self) -> Result<()>;
+    
+    /// 进入休眠状态
+    fn hibernate(&self) -> Result<()>;
+    
+    /// 锁定工作站
+    fn lock_workstation(&self) -> Result<()>;
+    
+    /// 关闭显示器
+    fn turn_off_monitor(&self) -> Result<()>;
+    
+    /// 注册关机阻止原因，显示在系统关机界面上
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 注册原因的窗口句柄
+    /// * `reason` - 阻止原因
+    fn block_shutdown(&self, window_handle: isize, reason: &str) -> Result<()>;
+    
+    /// 释放关机阻止原因
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 注册原因的窗口句柄
+    fn unblock_shutdown(&self, window_handle: isize) -> Result<()>;
+}
+
+/// 系统信息查询
+pub trait SystemInfo {
+    /// 操作系统版本
+    fn os_version(&self) -> Result<OsVersionInfo>;
+    
+    /// 当前进程是否以管理员身份（已提升权限）运行
+    fn is_elevated(&self) -> Result<bool>;
+    
+    /// 计算机名
+    fn computer_name(&self) -> Result<String>;
+    
+    /// 当前用户名
+    fn user_name(&self) -> Result<String>;
+    
+    /// 系统启动模式
+    fn boot_mode(&self) -> BootMode;
+    
+    /// 系统已运行的时间（毫秒）
+    fn uptime_ms(&self) -> u64;
+    
+    /// 所有处理器的累计CPU时间，平台不支持时为空
+    fn cpu_times(&self) -> Option<CpuTimes>;
+    
+    /// 所有物理网卡累计收发的字节数，不含回环网卡，平台不支持时为空
+    fn network_bytes(&self) -> Option<u64>;
+    
+    /// 正在运行的进程的可执行文件名（如"obs64.exe"），平台不支持时为空
+    fn process_names(&self) -> Option<Vec<String>>;
+    
+    /// 电源状态，没有电池或平台不支持时为空
+    fn power_status(&self) -> Option<PowerStatus>;
+    
+    /// 读取当前用户注册表项中的值，值不存在、类型不是字符串或二进制数据、平台不支持时为空
+    /// 
+    /// # 参数
+    /// 
+    /// * `key` - HKEY_CURRENT_USER下的项路径，如"Software\\Microsoft\\Windows\\CurrentVersion\\Run"
+    /// * `name` - 值名称
+    fn user_registry_value(&self, key: &str, name: &str) -> Option<RegistryValue>;
+}
+
+/// 用户会话状态
+pub trait SessionInfo {
+    /// 距离上次键盘鼠标输入的时长
+    fn idle_duration(&self) -> Duration;
+    
+    /// 当前会话是否已锁定
+    fn is_session_locked(&self) -> bool;
+    
+    /// 当前是否运行在远程桌面会话中
+    fn is_remote_session(&self) -> bool;
+    
+    /// 在系统结束会话（关机、重启或注销）前调用`on_end`
+    /// 
+    /// `on_end`在窗口线程中同步调用，返回后系统才会继续结束会话，
+    /// 应尽快保存状态后返回
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 接收会话结束消息的窗口句柄（Windows下为HWND）
+    /// * `on_end` - 会话结束前调用的函数
+    fn watch_session_end(&self, window_handle: isize, on_end: Box<dyn Fn() + Send + Sync>) -> Result<()>;
+    
+    /// 在系统从睡眠或休眠中唤醒后调用`on_resume`
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 接收电源消息的窗口句柄（Windows下为HWND）
+    /// * `on_resume` - 唤醒后调用的函数，在窗口线程中调用
+    fn watch_resume(&self, window_handle: isize, on_resume: Box<dyn Fn() + Send + Sync>) -> Result<()>;
+}
+
+/// 窗口、显示器和用户提示
+pub trait DisplayControl {
+    /// 所有已连接的显示器
+    fn list_monitors(&self) -> Vec<MonitorInfo>;
+    
+    /// 窗口当前所在的显示器和窗口区域
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 窗口句柄
+    fn window_placement(&self, window_handle: isize) -> Option<(MonitorInfo, ScreenRect)>;
+    
+    /// 移动窗口，不改变大小和层次
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 窗口句柄
+    /// * `position` - 窗口左上角的新位置
+    fn move_window(&self, window_handle: isize, position: (i32, i32)) -> bool;
+    
+    /// 播放系统提示音
+    fn beep(&self, sound: Beep);
+    
+    /// 发出屏幕阅读器通知
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 发出通知的窗口句柄
+    /// * `text` - 播报文本
+    /// * `activity_id` - 活动ID，屏幕阅读器据此合并同类通知
+    fn announce(&self, window_handle: isize, text: &str, activity_id: &str) -> Result<()>;
+    
+    /// 当前的专注助手状态，平台不支持或查询失败时为空
+    fn focus_assist(&self) -> Option<FocusAssist>;
+    
+    /// 切换专注助手状态
+    /// 
+    /// # 参数
+    /// 
+    /// * `mode` - 新的专注助手状态
+    fn set_focus_assist(&self, mode: FocusAssist) -> Result<()>;
+    
+    /// 设置默认输出设备的主音量
+    /// 
+    /// # 参数
+    /// 
+    /// * `percent` - 音量百分比，超过100按100处理
+    fn set_volume(&self, percent: u8) -> Result<()>;
+    
+    /// 用系统默认程序打开文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `path` - 文件路径
+    fn open_file(&self, path: &Path) -> Result<()>;
+}
+
+/// 数据加密
+/// 
+/// 加密后的数据只有当前用户在本机上才能解密，用于保护数据目录中的任务和配置方案
+pub trait DataProtection {
+    /// 加密数据
+    /// 
+    /// # 参数
+    /// 
+    /// * `data` - 要加密的数据
+    fn protect_data(&self, data: &[u8]) -> Result<Vec<u8>>;
+    
+    /// 解密数据
+    /// 
+    /// # 参数
+    /// 
+    /// * `data` - `protect_data`加密后的数据
+    fn unprotect_data(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_screen_rect() {
+        let rect = ScreenRect { left: 1920, top: 0, right: 3840, bottom: 1040 };
+        assert_eq!(rect.width(), 1920);
+        assert_eq!(rect.height(), 1040);
+        assert!(rect.contains(1920, 0));
+        assert!(!rect.contains(3840, 500));
+    }
+    
+    #[test]
+    fn test_capabilities() {
+        use crate::core::types::TaskAction;
+        
+        let capabilities = Capabilities {
+            power_off: true,
+            sleep: true,
+            hibernate: false,
+            lock: false,
+            logoff: true,
+            monitor_off: false,
+            shutdown_command: false,
+            shutdown_block: false,
+            system_tray: false,
+            window_placement: false,
+            screen_reader: false,
+            idle_detection: false,
+            focus_assist: false,
+            session_end_notice: false,
+            resume_notice: false,
+            system_scheduler: false,
+            autostart: false,
+            data_protection: false,
+        };
+        assert!(TaskAction::Restart.is_supported(&capabilities));
+        assert!(!TaskAction::Lock.is_supported(&capabilities));
+        assert!(!TaskAction::MonitorOff.is_supported(&capabilities));
+        // 自定义命令在任何平台上都可以运行
+        assert!(TaskAction::RunCommand.is_supported(&capabilities));
+        
+        let available = TaskAction::available();
+        assert!(available.contains(&TaskAction::RunCommand));
+        assert!(available.iter().all(|action| action.is_supported(&super::capabilities())));
+    }
+    
+    #[test]
+    fn test_native_queries() {
+        let platform = native();
+        
+        // 查询类接口在任何平台上都不应出错或阻塞
+        let _ = platform.is_session_locked();
+        let _ = platform.is_remote_session();
+        let _ = platform.idle_duration();
+        let _ = platform.cpu_times();
+        let _ = platform.network_bytes();
+        let _ = platform.process_names();
+        let _ = platform.power_status();
+        let _ = platform.boot_mode();
+        let _ = platform.focus_assist();
+        assert!(platform.is_elevated().is_ok());
+        
+        // 无效窗口句柄不能移动，也不能注册关机阻止原因
+        assert!(!platform.move_window(0, (0, 0)));
+        assert!(platform.block_shutdown(0, "测试").is_err());
+    }
+}
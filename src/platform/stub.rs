@@ -0,0 +1,217 @@
+//! 非Windows平台的空实现
+//! 
+//! 电源操作返回错误，查询返回默认值，使核心逻辑和测试可以在其他平台上运行
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use super::{
+    Beep, BootMode, Capabilities, CpuTimes, DataProtection, DisplayControl, FocusAssist, MonitorInfo, OsVersionInfo, PowerControl, PowerStatus, RegistryValue, ScreenRect,
+    SessionEnd, SessionInfo, SystemInfo,
+};
+
+/// 非Windows平台系统接口
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativePlatform;
+
+impl NativePlatform {
+    /// 不支持任何系统功能
+    pub const CAPABILITIES: Capabilities = Capabilities {
+        power_off: false,
+        sleep: false,
+        hibernate: false,
+        lock: false,
+        logoff: false,
+        monitor_off: false,
+        shutdown_command: false,
+        shutdown_block: false,
+        system_tray: false,
+        window_placement: false,
+        screen_reader: false,
+        idle_detection: false,
+        focus_assist: false,
+        session_end_notice: false,
+        resume_notice: false,
+        system_scheduler: false,
+        autostart: false,
+        data_protection: false,
+    };
+}
+
+/// 生成"功能在当前平台上不可用"错误
+fn unsupported<T>(feature: &str) -> Result<T> {
+    Err(anyhow!("{}在当前平台上不可用", feature))
+}
+
+impl PowerControl for NativePlatform {
+    fn enable_shutdown_privilege(&self) -> Result<()> {
+        unsupported("关机权限")
+    }
+    
+    fn end_session(&self, kind: SessionEnd, _force: bool) -> Result<()> {
+        match kind {
+            SessionEnd::Shutdown => unsupported("关机"),
+            SessionEnd::Restart => unsupported("重启"),
+            SessionEnd::Logoff => unsupported("注销"),
+        }
+    }
+    
+    fn schedule_shutdown(&self, _reason: &str, _delay_seconds: u32, _restart: bool) -> Result<()> {
+        unsupported("延迟关机")
+    }
+    
+    fn abort_scheduled_shutdown(&self) -> Result<bool> {
+        Ok(false)
+    }
+    
+    fn has_scheduled_shutdown(&self) -> bool {
+        false
+    }
+    
+    fn suspend(&self) -> Result<()> {
+        unsupported("睡眠")
+    }
+    
+    fn hibernate(&self) -> Result<()> {
+        unsupported("休眠")
+    }
+    
+    fn lock_workstation(&self) -> Result<()> {
+        unsupported("锁定")
+    }
+    
+    fn turn_off_monitor(&self) -> Result<()> {
+        unsupported("关闭显示器")
+    }
+    
+    fn block_shutdown(&self, _window_handle: isize, _reason: &str) -> Result<()> {
+        unsupported("关机阻止原因")
+    }
+    
+    fn unblock_shutdown(&self, _window_handle: isize) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl SystemInfo for NativePlatform {
+    fn os_version(&self) -> Result<OsVersionInfo> {
+        unsupported("Windows版本检测")
+    }
+    
+    fn is_elevated(&self) -> Result<bool> {
+        Ok(false)
+    }
+    
+    fn computer_name(&self) -> Result<String> {
+        std::env::var("HOSTNAME").map_err(|_| anyhow!("无法获取计算机名"))
+    }
+    
+    fn user_name(&self) -> Result<String> {
+        std::env::var("USER").map_err(|_| anyhow!("无法获取用户名"))
+    }
+    
+    fn boot_mode(&self) -> BootMode {
+        BootMode::Normal
+    }
+    
+    fn uptime_ms(&self) -> u64 {
+        // Linux下从/proc/uptime读取，其他平台无法获取时返回0
+        std::fs::read_to_string("/proc/uptime")
+            .ok()
+            .and_then(|content| content.split_whitespace().next()?.parse::<f64>().ok())
+            .map(|seconds| (seconds * 1000.0) as u64)
+            .unwrap_or(0)
+    }
+    
+    fn cpu_times(&self) -> Option<CpuTimes> {
+        None
+    }
+    
+    fn network_bytes(&self) -> Option<u64> {
+        None
+    }
+    
+    fn process_names(&self) -> Option<Vec<String>> {
+        None
+    }
+    
+    fn power_status(&self) -> Option<PowerStatus> {
+        None
+    }
+    
+    fn user_registry_value(&self, _key: &str, _name: &str) -> Option<RegistryValue> {
+        None
+    }
+}
+
+impl SessionInfo for NativePlatform {
+    fn idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+    
+    fn is_session_locked(&self) -> bool {
+        false
+    }
+    
+    fn is_remote_session(&self) -> bool {
+        false
+    }
+    
+    fn watch_session_end(&self, _window_handle: isize, _on_end: Box<dyn Fn() + Send + Sync>) -> Result<()> {
+        unsupported("会话结束通知")
+    }
+    
+    fn watch_resume(&self, _window_handle: isize, _on_resume: Box<dyn Fn() + Send + Sync>) -> Result<()> {
+        unsupported("系统唤醒通知")
+    }
+}
+
+impl DisplayControl for NativePlatform {
+    fn list_monitors(&self) -> Vec<MonitorInfo> {
+        Vec::new()
+    }
+    
+    fn window_placement(&self, _window_handle: isize) -> Option<(MonitorInfo, ScreenRect)> {
+        None
+    }
+    
+    fn move_window(&self, _window_handle: isize, _position: (i32, i32)) -> bool {
+        false
+    }
+    
+    fn beep(&self, sound: Beep) {
+        log::info!("系统提示音: {:?}", sound);
+    }
+    
+    fn announce(&self, _window_handle: isize, _text: &str, _activity_id: &str) -> Result<()> {
+        unsupported("屏幕阅读器播报")
+    }
+    
+    fn focus_assist(&self) -> Option<FocusAssist> {
+        None
+    }
+    
+    fn set_focus_assist(&self, _mode: FocusAssist) -> Result<()> {
+        unsupported("专注助手")
+    }
+    
+    fn set_volume(&self, _percent: u8) -> Result<()> {
+        unsupported("调整音量")
+    }
+    
+    fn open_file(&self, _path: &Path) -> Result<()> {
+        unsupported("打开文件")
+    }
+}
+
+impl DataProtection for NativePlatform {
+    fn protect_data(&self, _data: &[u8]) -> Result<Vec<u8>> {
+        unsupported("数据加密")
+    }
+    
+    fn unprotect_data(&self, _data: &[u8]) -> Result<Vec<u8>> {
+        unsupported("数据解密")
+    }
+}
@@ -0,0 +1,630 @@
+//! Linux和macOS平台实现
+//! 
+//! 通过系统命令完成电源操作：Linux使用systemd的`systemctl`和`loginctl`，
+//! macOS使用`osascript`和`pmset`。托盘、窗口位置和屏幕阅读器等界面功能不可用
+
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+
+use super::{
+    Beep, BootMode, Capabilities, CpuTimes, DataProtection, DisplayControl, FocusAssist, MonitorInfo, OsVersionInfo, PowerControl, PowerStatus, RegistryValue,
+    ScreenRect, SessionEnd, SessionInfo, SystemInfo,
+};
+
+/// systemd记录待执行关机计划的文件
+#[cfg(target_os = "linux")]
+const SYSTEMD_SCHEDULED_SHUTDOWN: &str = "/run/systemd/shutdown/scheduled";
+
+/// Linux和macOS平台系统接口
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativePlatform;
+
+impl NativePlatform {
+    /// 电源操作通过系统命令完成，界面集成功能不可用
+    pub const CAPABILITIES: Capabilities = Capabilities {
+        power_off: true,
+        sleep: true,
+        // macOS没有单独的休眠命令，由hibernatemode决定睡眠时是否写入硬盘
+        hibernate: cfg!(target_os = "linux"),
+        lock: true,
+        logoff: true,
+        monitor_off: true,
+        shutdown_command: false,
+        shutdown_block: false,
+        system_tray: false,
+        window_placement: false,
+        screen_reader: false,
+        idle_detection: false,
+        focus_assist: false,
+        session_end_notice: false,
+        resume_notice: false,
+        system_scheduler: false,
+        autostart: false,
+        data_protection: false,
+    };
+}
+
+/// 系统命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SystemCommand {
+    /// 程序名
+    program: &'static str,
+    /// 参数
+    args: Vec<String>,
+}
+
+impl SystemCommand {
+    /// 创建系统命令
+    fn new(program: &'static str, args: &[&str]) -> Self {
+        Self {
+            program,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+    
+    /// 运行命令并等待结束
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回标准输出，退出码非0时返回包含标准错误的错误信息
+    fn run(&self) -> Result<String> {
+        debug!("执行系统命令: {} {}", self.program, self.args.join(" "));
+        let output = Command::new(self.program)
+            .args(&self.args)
+            .output()
+            .map_err(|e| anyhow!("执行{}失败: {}", self.program, e))?;
+        
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(anyhow!("{}执行失败: {}", self.program, String::from_utf8_lossy(&output.stderr).trim()))
+        }
+    }
+}
+
+/// 结束会话的命令
+/// 
+/// # 参数
+/// 
+/// * `kind` - 结束会话的方式
+/// * `force` - 是否忽略其他程序的关机阻止
+#[cfg(target_os = "linux")]
+fn end_session_command(kind: SessionEnd, force: bool) -> SystemCommand {
+    let mut command = match kind {
+        SessionEnd::Shutdown => SystemCommand::new("systemctl", &["poweroff"]),
+        SessionEnd::Restart => SystemCommand::new("systemctl", &["reboot"]),
+        SessionEnd::Logoff => return match std::env::var("XDG_SESSION_ID") {
+            Ok(session) => SystemCommand::new("loginctl", &["terminate-session", &session]),
+            Err(_) => SystemCommand::new("loginctl", &["terminate-user", &std::env::var("USER").unwrap_or_default()]),
+        },
+    };
+    if force {
+        // -i 忽略systemd-inhibit注册的关机阻止
+        command.args.push("-i".to_string());
+    }
+    command
+}
+
+/// 结束会话的命令
+/// 
+/// System Events的关机会询问未保存的文档，无法强制执行
+/// 
+/// # 参数
+/// 
+/// * `kind` - 结束会话的方式
+/// * `force` - 是否强制（macOS下忽略）
+#[cfg(target_os = "macos")]
+fn end_session_command(kind: SessionEnd, _force: bool) -> SystemCommand {
+    let script = match kind {
+        SessionEnd::Shutdown => "tell application \"System Events\" to shut down",
+        SessionEnd::Restart => "tell application \"System Events\" to restart",
+        SessionEnd::Logoff => "tell application \"System Events\" to log out",
+    };
+    SystemCommand::new("osascript", &["-e", script])
+}
+
+/// 延迟关机的命令
+/// 
+/// `shutdown`只接受分钟，不足一分钟的部分向上取整
+/// 
+/// # 参数
+/// 
+/// * `reason` - 关机原因，作为广播消息发送给已登录的用户
+/// * `delay_seconds` - 延迟秒数
+/// * `restart` - 是否重启
+#[cfg(target_os = "linux")]
+fn schedule_shutdown_command(reason: &str, delay_seconds: u32, restart: bool) -> SystemCommand {
+    let minutes = format!("+{}", delay_seconds.div_ceil(60));
+    let mode = if restart { "-r" } else { "-P" };
+    SystemCommand::new("shutdown", &[mode, &minutes, reason])
+}
+
+/// 设置默认输出设备音量的命令
+/// 
+/// Linux下通过PulseAudio（或PipeWire的兼容层）的`pactl`设置，macOS下通过AppleScript设置
+/// 
+/// # 参数
+/// 
+/// * `percent` - 音量百分比
+fn volume_command(percent: u8) -> SystemCommand {
+    #[cfg(target_os = "linux")]
+    let command = SystemCommand::new("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &format!("{}%", percent)]);
+    #[cfg(target_os = "macos")]
+    let command = SystemCommand::new("osascript", &["-e", &format!("set volume output volume {}", percent)]);
+    command
+}
+
+/// 用默认程序打开文件的命令
+/// 
+/// # 参数
+/// 
+/// * `path` - 文件路径
+fn open_file_command(path: &Path) -> SystemCommand {
+    let path = path.to_string_lossy();
+    #[cfg(target_os = "linux")]
+    let command = SystemCommand::new("xdg-open", &[&path]);
+    #[cfg(target_os = "macos")]
+    let command = SystemCommand::new("open", &[&path]);
+    command
+}
+
+/// 读取以空白分隔的第一个数字
+#[cfg(any(target_os = "linux", test))]
+fn parse_first_number(text: &str) -> Option<f64> {
+    text.split_whitespace().next()?.parse().ok()
+}
+
+/// 解析/proc/stat中汇总所有处理器的"cpu"行
+/// 
+/// 各列依次为user、nice、system、idle、iowait等，idle和iowait计为空闲
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_stat(text: &str) -> Option<CpuTimes> {
+    let line = text.lines().find(|line| line.starts_with("cpu "))?;
+    let values: Vec<u64> = line.split_whitespace().skip(1).filter_map(|value| value.parse().ok()).collect();
+    if values.len() < 4 {
+        return None;
+    }
+    Some(CpuTimes {
+        idle: values[3] + values.get(4).copied().unwrap_or(0),
+        total: values.iter().sum(),
+    })
+}
+
+/// 解析/proc/net/dev中所有网卡累计收发的字节数，不含回环网卡
+/// 
+/// 每行为"网卡名: 接收字节 ...（共8列） 发送字节 ..."
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_net_dev(text: &str) -> Option<u64> {
+    let mut total = None;
+    for line in text.lines() {
+        let Some((name, counters)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim() == "lo" {
+            continue;
+        }
+        let values: Vec<u64> = counters.split_whitespace().filter_map(|value| value.parse().ok()).collect();
+        if let (Some(received), Some(sent)) = (values.first(), values.get(8)) {
+            total = Some(total.unwrap_or(0) + received + sent);
+        }
+    }
+    total
+}
+
+/// 从命令行中取出可执行文件名
+/// 
+/// /proc/<pid>/cmdline和`ps -axo comm=`都以程序路径开头，取最后一段路径；
+/// /proc/<pid>/cmdline的各参数以0分隔
+fn process_name_from_command(command: &str) -> Option<String> {
+    let program = command.split('\0').next()?.trim();
+    let name = program.rsplit('/').next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// 由/sys/class/power_supply中电池的status和capacity生成电源状态
+/// 
+/// status为"Discharging"时表示正在使用电池供电
+#[cfg(any(target_os = "linux", test))]
+fn parse_battery_supply(status: &str, capacity: &str) -> PowerStatus {
+    PowerStatus {
+        on_battery: status.trim() == "Discharging",
+        battery_percent: capacity.trim().parse::<u8>().ok().filter(|percent| *percent <= 100),
+    }
+}
+
+/// 解析`pmset -g batt`的输出
+/// 
+/// 第一行为"Now drawing from 'Battery Power'"或"'AC Power'"，
+/// 之后每块电池一行，如" -InternalBattery-0 (id=123)	85%; discharging; 4:10 remaining"；
+/// 没有电池时返回None
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset_battery(text: &str) -> Option<PowerStatus> {
+    let battery = text.lines().find(|line| line.contains("InternalBattery"))?;
+    let battery_percent = battery.split_whitespace()
+        .find_map(|field| field.trim_end_matches(';').strip_suffix('%'))
+        .and_then(|percent| percent.parse::<u8>().ok())
+        .filter(|percent| *percent <= 100);
+    Some(PowerStatus {
+        on_battery: text.lines().next().is_some_and(|line| line.contains("'Battery Power'")),
+        battery_percent,
+    })
+}
+
+/// 解析`sysctl -n kern.boottime`的输出，如"{ sec = 1700000000, usec = 0 } Tue Nov 14 ..."
+#[cfg(any(target_os = "macos", test))]
+fn parse_boot_time(text: &str) -> Option<u64> {
+    let rest = text.split("sec =").nth(1)?;
+    rest.split(',').next()?.trim().parse().ok()
+}
+
+impl PowerControl for NativePlatform {
+    fn enable_shutdown_privilege(&self) -> Result<()> {
+        // 由systemd-logind或系统偏好设置授权，不需要调整进程权限
+        Ok(())
+    }
+    
+    fn end_session(&self, kind: SessionEnd, force: bool) -> Result<()> {
+        end_session_command(kind, force).run().map(|_| ())
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn schedule_shutdown(&self, reason: &str, delay_seconds: u32, restart: bool) -> Result<()> {
+        schedule_shutdown_command(reason, delay_seconds, restart).run().map(|_| ())
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn schedule_shutdown(&self, _reason: &str, _delay_seconds: u32, _restart: bool) -> Result<()> {
+        // shutdown需要root权限，由倒计时结束后立即执行代替
+        Err(anyhow!("延迟关机在macOS上不可用"))
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn abort_scheduled_shutdown(&self) -> Result<bool> {
+        if !std::path::Path::new(SYSTEMD_SCHEDULED_SHUTDOWN).exists() {
+            return Ok(false);
+        }
+        SystemCommand::new("shutdown", &["-c"]).run()?;
+        Ok(true)
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn abort_scheduled_shutdown(&self) -> Result<bool> {
+        Ok(false)
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn has_scheduled_shutdown(&self) -> bool {
+        std::path::Path::new(SYSTEMD_SCHEDULED_SHUTDOWN).exists()
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn has_scheduled_shutdown(&self) -> bool {
+        false
+    }
+    
+    fn suspend(&self) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        let command = SystemCommand::new("systemctl", &["suspend"]);
+        #[cfg(target_os = "macos")]
+        let command = SystemCommand::new("pmset", &["sleepnow"]);
+        command.run().map(|_| ())
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn hibernate(&self) -> Result<()> {
+        SystemCommand::new("systemctl", &["hibernate"]).run().map(|_| ())
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn hibernate(&self) -> Result<()> {
+        Err(anyhow!("macOS不支持单独的休眠操作"))
+    }
+    
+    fn lock_workstation(&self) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        let command = SystemCommand::new("loginctl", &["lock-session"]);
+        #[cfg(target_os = "macos")]
+        let command = SystemCommand::new(
+            "osascript",
+            &["-e", "tell application \"System Events\" to keystroke \"q\" using {control down, command down}"],
+        );
+        command.run().map(|_| ())
+    }
+    
+    fn turn_off_monitor(&self) -> Result<()> {
+        // Linux下只支持X11会话
+        #[cfg(target_os = "linux")]
+        let command = SystemCommand::new("xset", &["dpms", "force", "off"]);
+        #[cfg(target_os = "macos")]
+        let command = SystemCommand::new("pmset", &["displaysleepnow"]);
+        command.run().map(|_| ())
+    }
+    
+    fn block_shutdown(&self, _window_handle: isize, _reason: &str) -> Result<()> {
+        Err(anyhow!("关机阻止原因在当前平台上不可用"))
+    }
+    
+    fn unblock_shutdown(&self, _window_handle: isize) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl SystemInfo for NativePlatform {
+    fn os_version(&self) -> Result<OsVersionInfo> {
+        Err(anyhow!("Windows版本检测在当前平台上不可用"))
+    }
+    
+    fn is_elevated(&self) -> Result<bool> {
+        Ok(SystemCommand::new("id", &["-u"]).run()? == "0")
+    }
+    
+    fn computer_name(&self) -> Result<String> {
+        SystemCommand::new("hostname", &[]).run()
+    }
+    
+    fn user_name(&self) -> Result<String> {
+        std::env::var("USER").map_err(|_| anyhow!("无法获取用户名"))
+    }
+    
+    fn boot_mode(&self) -> BootMode {
+        BootMode::Normal
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn uptime_ms(&self) -> u64 {
+        std::fs::read_to_string("/proc/uptime")
+            .ok()
+            .and_then(|content| parse_first_number(&content))
+            .map(|seconds| (seconds * 1000.0) as u64)
+            .unwrap_or(0)
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn uptime_ms(&self) -> u64 {
+        let boot_time = SystemCommand::new("sysctl", &["-n", "kern.boottime"]).run().ok()
+            .and_then(|output| parse_boot_time(&output));
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|now| now.as_secs());
+        match (boot_time, now) {
+            (Some(boot_time), Ok(now)) => now.saturating_sub(boot_time) * 1000,
+            _ => 0,
+        }
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn cpu_times(&self) -> Option<CpuTimes> {
+        parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?)
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn cpu_times(&self) -> Option<CpuTimes> {
+        None
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn network_bytes(&self) -> Option<u64> {
+        parse_proc_net_dev(&std::fs::read_to_string("/proc/net/dev").ok()?)
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn network_bytes(&self) -> Option<u64> {
+        None
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn process_names(&self) -> Option<Vec<String>> {
+        let names = std::fs::read_dir("/proc").ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+            .filter_map(|entry| {
+                // 内核线程的命令行为空，使用comm中的名称（最多15个字符）
+                let cmdline = std::fs::read(entry.path().join("cmdline")).ok()?;
+                match process_name_from_command(&String::from_utf8_lossy(&cmdline)) {
+                    Some(name) => Some(name),
+                    None => std::fs::read_to_string(entry.path().join("comm")).ok()
+                        .map(|comm| comm.trim().to_string())
+                        .filter(|comm| !comm.is_empty()),
+                }
+            })
+            .collect();
+        Some(names)
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn process_names(&self) -> Option<Vec<String>> {
+        let output = SystemCommand::new("ps", &["-axo", "comm="]).run().ok()?;
+        Some(output.lines().filter_map(process_name_from_command).collect())
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn power_status(&self) -> Option<PowerStatus> {
+        std::fs::read_dir("/sys/class/power_supply").ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| std::fs::read_to_string(path.join("type")).is_ok_and(|kind| kind.trim() == "Battery"))
+            .find_map(|path| {
+                let status = std::fs::read_to_string(path.join("status")).ok()?;
+                let capacity = std::fs::read_to_string(path.join("capacity")).unwrap_or_default();
+                Some(parse_battery_supply(&status, &capacity))
+            })
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn power_status(&self) -> Option<PowerStatus> {
+        parse_pmset_battery(&SystemCommand::new("pmset", &["-g", "batt"]).run().ok()?)
+    }
+    
+    fn user_registry_value(&self, _key: &str, _name: &str) -> Option<RegistryValue> {
+        None
+    }
+}
+
+impl SessionInfo for NativePlatform {
+    fn idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+    
+    #[cfg(target_os = "linux")]
+    fn is_session_locked(&self) -> bool {
+        let Ok(session) = std::env::var("XDG_SESSION_ID") else {
+            return false;
+        };
+        SystemCommand::new("loginctl", &["show-session", &session, "-p", "LockedHint", "--value"])
+            .run()
+            .is_ok_and(|value| value == "yes")
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn is_session_locked(&self) -> bool {
+        false
+    }
+    
+    fn is_remote_session(&self) -> bool {
+        // 通过SSH连接时关机后同样无法再连接
+        std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_CLIENT").is_some()
+    }
+    
+    fn watch_session_end(&self, _window_handle: isize, _on_end: Box<dyn Fn() + Send + Sync>) -> Result<()> {
+        Err(anyhow!("会话结束通知在当前平台上不可用"))
+    }
+    
+    fn watch_resume(&self, _window_handle: isize, _on_resume: Box<dyn Fn() + Send + Sync>) -> Result<()> {
+        Err(anyhow!("系统唤醒通知在当前平台上不可用"))
+    }
+}
+
+impl DataProtection for NativePlatform {
+    fn protect_data(&self, _data: &[u8]) -> Result<Vec<u8>> {
+        Err(anyhow!("数据加密在当前平台上不可用"))
+    }
+    
+    fn unprotect_data(&self, _data: &[u8]) -> Result<Vec<u8>> {
+        Err(anyhow!("数据解密在当前平台上不可用"))
+    }
+}
+
+impl DisplayControl for NativePlatform {
+    fn list_monitors(&self) -> Vec<MonitorInfo> {
+        Vec::new()
+    }
+    
+    fn window_placement(&self, _window_handle: isize) -> Option<(MonitorInfo, ScreenRect)> {
+        None
+    }
+    
+    fn move_window(&self, _window_handle: isize, _position: (i32, i32)) -> bool {
+        false
+    }
+    
+    fn beep(&self, sound: Beep) {
+        info!("系统提示音: {:?}", sound);
+    }
+    
+    fn announce(&self, _window_handle: isize, _text: &str, _activity_id: &str) -> Result<()> {
+        Err(anyhow!("屏幕阅读器播报在当前平台上不可用"))
+    }
+    
+    fn focus_assist(&self) -> Option<FocusAssist> {
+        None
+    }
+    
+    fn set_focus_assist(&self, _mode: FocusAssist) -> Result<()> {
+        Err(anyhow!("专注助手在当前平台上不可用"))
+    }
+    
+    fn set_volume(&self, percent: u8) -> Result<()> {
+        volume_command(percent.min(100)).run().map(|_| ())
+    }
+    
+    fn open_file(&self, path: &Path) -> Result<()> {
+        open_file_command(path).run().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linux_commands() {
+        assert_eq!(end_session_command(SessionEnd::Shutdown, false), SystemCommand::new("systemctl", &["poweroff"]));
+        assert_eq!(end_session_command(SessionEnd::Restart, true), SystemCommand::new("systemctl", &["reboot", "-i"]));
+        assert_eq!(end_session_command(SessionEnd::Logoff, false).program, "loginctl");
+        
+        // 延迟不足一分钟时按一分钟计算
+        assert_eq!(
+            schedule_shutdown_command("QtShut", 90, false),
+            SystemCommand::new("shutdown", &["-P", "+2", "QtShut"]),
+        );
+        assert_eq!(schedule_shutdown_command("QtShut", 60, true).args[..2], ["-r".to_string(), "+1".to_string()]);
+        assert_eq!(
+            volume_command(30),
+            SystemCommand::new("pactl", &["set-sink-volume", "@DEFAULT_SINK@", "30%"]),
+        );
+    }
+    
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_macos_commands() {
+        let command = end_session_command(SessionEnd::Shutdown, true);
+        assert_eq!(command.program, "osascript");
+        assert!(command.args[1].ends_with("shut down"));
+    }
+    
+    #[test]
+    fn test_parse_system_output() {
+        assert_eq!(parse_first_number("12345.67 54321.00\n"), Some(12345.67));
+        assert_eq!(parse_first_number(""), None);
+        assert_eq!(parse_boot_time("{ sec = 1700000000, usec = 123456 } Tue Nov 14 22:13:20 2023"), Some(1700000000));
+        assert_eq!(parse_boot_time("unknown"), None);
+    }
+    
+    #[test]
+    fn test_parse_proc_net_dev() {
+        let text = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 9000 10 0 0 0 0 0 0 9000 10 0 0 0 0 0 0
+  eth0: 1500 20 0 0 0 0 0 0 500 5 0 0 0 0 0 0
+ wlan0: 100 1 0 0 0 0 0 0 20 1 0 0 0 0 0 0
+";
+        assert_eq!(parse_proc_net_dev(text), Some(2120));
+        assert_eq!(parse_proc_net_dev("    lo: 9000 10 0 0 0 0 0 0 9000 10 0 0 0 0 0 0"), None);
+    }
+    
+    #[test]
+    fn test_process_name_from_command() {
+        assert_eq!(process_name_from_command("/usr/bin/obs\0--startreplaybuffer\0"), Some("obs".to_string()));
+        assert_eq!(process_name_from_command("/Applications/HandBrake.app/Contents/MacOS/HandBrake"), Some("HandBrake".to_string()));
+        assert_eq!(process_name_from_command("ffmpeg"), Some("ffmpeg".to_string()));
+        assert_eq!(process_name_from_command(""), None);
+    }
+    
+    #[test]
+    fn test_parse_power_status() {
+        assert_eq!(
+            parse_battery_supply("Discharging\n", "18\n"),
+            PowerStatus { on_battery: true, battery_percent: Some(18) },
+        );
+        assert_eq!(
+            parse_battery_supply("Charging\n", ""),
+            PowerStatus { on_battery: false, battery_percent: None },
+        );
+        
+        let text = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t85%; discharging; 4:10 remaining present: true\n";
+        assert_eq!(parse_pmset_battery(text), Some(PowerStatus { on_battery: true, battery_percent: Some(85) }));
+        let text = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        assert_eq!(parse_pmset_battery(text), Some(PowerStatus { on_battery: false, battery_percent: Some(100) }));
+        assert_eq!(parse_pmset_battery("Now drawing from 'AC Power'\n"), None);
+    }
+    
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_proc_stat(stat), Some(CpuTimes { idle: 850, total: 1000 }));
+        assert_eq!(parse_proc_stat("intr 12345"), None);
+    }
+}
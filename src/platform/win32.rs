@@ -0,0 +1,821 @@
+//! Windows平台实现
+//! 
+//! 基于windows crate调用Win32 API，所有unsafe调用都集中在这里
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use windows::core::{s, w, BSTR, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, LocalFree, BOOL, BOOLEAN, ERROR_NO_SHUTDOWN_IN_PROGRESS, FILETIME, HANDLE, HLOCAL, HWND, LPARAM, LRESULT, LUID,
+    RECT, TRUE, WPARAM,
+};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, GetMonitorInfoW, MonitorFromWindow, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+    MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::Security::{
+    AdjustTokenPrivileges, GetTokenInformation, LookupPrivilegeValueW, TokenElevation, LUID_AND_ATTRIBUTES,
+    SE_PRIVILEGE_ENABLED, SE_SHUTDOWN_NAME, TOKEN_ACCESS_MASK, TOKEN_ADJUST_PRIVILEGES, TOKEN_ELEVATION,
+    TOKEN_PRIVILEGES, TOKEN_QUERY,
+};
+use windows::Win32::Security::Cryptography::{
+    CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+};
+use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+use windows::Win32::Media::Audio::{eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator};
+use windows::Win32::NetworkManagement::IpHelper::{FreeMibTable, GetIfTable2, IF_TYPE_SOFTWARE_LOOPBACK, MIB_IF_TABLE2};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+use windows::Win32::System::Diagnostics::Debug::MessageBeep;
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
+use windows::Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress};
+use windows::Win32::System::Power::{GetSystemPowerStatus, SetSuspendState, SYSTEM_POWER_STATUS};
+use windows::Win32::System::Registry::{
+    RegGetValueW, HKEY_CURRENT_USER, REG_BINARY, REG_SZ, REG_VALUE_TYPE, RRF_RT_REG_BINARY, RRF_RT_REG_SZ,
+};
+use windows::Win32::System::RemoteDesktop::{
+    WTSFreeMemory, WTSQuerySessionInformationW, WTSSessionInfoEx, WTSINFOEXW, WTS_CURRENT_SERVER_HANDLE,
+    WTS_CURRENT_SESSION, WTS_SESSIONSTATE_LOCK,
+};
+use windows::Win32::System::Shutdown::{
+    AbortSystemShutdownW, ExitWindowsEx, InitiateSystemShutdownExW, LockWorkStation, ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy,
+    EWX_FORCE, EWX_LOGOFF, EWX_REBOOT, EWX_SHUTDOWN, SHTDN_REASON_FLAG_PLANNED, SHUTDOWN_REASON,
+};
+use windows::Win32::System::StationsAndDesktops::{
+    CloseDesktop, OpenInputDesktop, SwitchDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_SWITCHDESKTOP,
+};
+use windows::Win32::System::SystemInformation::{GetTickCount, GetTickCount64, GetVersionExW, OSVERSIONINFOEXW};
+use windows::Win32::System::SystemServices::VER_NT_WORKSTATION;
+use windows::Win32::System::Threading::{GetCurrentProcess, GetSystemTimes, OpenProcessToken};
+use windows::Win32::System::WindowsProgramming::{GetComputerNameW, GetUserNameW};
+use windows::Win32::UI::Accessibility::{
+    NotificationKind_Other, NotificationProcessing_ImportantMostRecent, UiaHostProviderFromHwnd,
+    UiaRaiseNotificationEvent,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass, ShellExecuteW};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetSystemMetrics, GetWindowRect, PostMessageW, SetWindowPos, HWND_BROADCAST, MB_ICONASTERISK,
+    MB_ICONEXCLAMATION, MB_ICONHAND, MB_OK, PBT_APMRESUMEAUTOMATIC, SC_MONITORPOWER, SM_CLEANBOOT, SM_REMOTESESSION,
+    SM_SHUTTINGDOWN,
+    SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER, SW_SHOWNORMAL, WM_ENDSESSION, WM_POWERBROADCAST, WM_SYSCOMMAND,
+};
+
+use super::{
+    Beep, BootMode, Capabilities, CpuTimes, DataProtection, DisplayControl, FocusAssist, MonitorInfo, OsVersionInfo, PowerControl, PowerStatus, RegistryValue, ScreenRect,
+    SessionEnd, SessionInfo, SystemInfo,
+};
+
+/// SC_MONITORPOWER 关闭显示器的参数
+const MONITOR_POWER_OFF: isize = 2;
+
+/// 专注助手当前配置的WNF状态名（WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED）
+/// 
+/// 系统没有公开切换专注助手的API，设置界面和操作中心都通过这个WNF状态读写
+const WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u64 = 0x0D83_063E_A3BF_1C75;
+
+/// ntdll中的NtQueryWnfStateData
+type NtQueryWnfStateData = unsafe extern "system" fn(
+    state_name: *const u64,
+    type_id: *const std::ffi::c_void,
+    explicit_scope: *const std::ffi::c_void,
+    change_stamp: *mut u32,
+    buffer: *mut std::ffi::c_void,
+    buffer_size: *mut u32,
+) -> i32;
+
+/// ntdll中的NtUpdateWnfStateData
+type NtUpdateWnfStateData = unsafe extern "system" fn(
+    state_name: *const u64,
+    buffer: *const std::ffi::c_void,
+    length: u32,
+    type_id: *const std::ffi::c_void,
+    explicit_scope: *const std::ffi::c_void,
+    matching_change_stamp: u32,
+    check_stamp: u32,
+) -> i32;
+
+/// Windows标准计算机名最大长度
+const MAX_COMPUTERNAME_LENGTH: usize = 15;
+
+/// Windows标准用户名最大长度
+const UNLEN: usize = 256;
+
+/// Windows平台系统接口
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativePlatform;
+
+impl NativePlatform {
+    /// Windows支持所有功能
+    pub const CAPABILITIES: Capabilities = Capabilities {
+        power_off: true,
+        sleep: true,
+        hibernate: true,
+        lock: true,
+        logoff: true,
+        monitor_off: true,
+        shutdown_command: true,
+        shutdown_block: true,
+        system_tray: true,
+        window_placement: true,
+        screen_reader: true,
+        idle_detection: true,
+        focus_assist: true,
+        session_end_notice: true,
+        resume_notice: true,
+        system_scheduler: true,
+        autostart: true,
+        data_protection: true,
+    };
+}
+
+/// 转换为以0结尾的UTF-16字符串
+fn to_wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// 读取以0结尾的UTF-16缓冲区
+fn from_wide(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// 由GetSystemPowerStatus的结果生成电源状态
+/// 
+/// 没有电池（BatteryFlag为128）时返回None，电量未知时为255
+fn power_status_from_system(status: &SYSTEM_POWER_STATUS) -> Option<PowerStatus> {
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN: u8 = 255;
+    
+    if status.BatteryFlag == NO_SYSTEM_BATTERY || status.BatteryFlag == UNKNOWN {
+        return None;
+    }
+    Some(PowerStatus {
+        // ACLineStatus为0表示未接通电源，255表示未知
+        on_battery: status.ACLineStatus == 0,
+        battery_percent: Some(status.BatteryLifePercent).filter(|percent| *percent <= 100),
+    })
+}
+
+/// 打开当前进程的访问令牌
+/// 
+/// 返回的句柄由调用方关闭
+fn open_process_token(access: TOKEN_ACCESS_MASK) -> Result<HANDLE> {
+    let mut token = HANDLE::default();
+    unsafe { OpenProcessToken(GetCurrentProcess(), access, &mut token) }
+        .map_err(|e| anyhow!("无法打开进程令牌: {}", e))?;
+    Ok(token)
+}
+
+/// 将Windows矩形转换为屏幕区域
+fn from_rect(rect: &RECT) -> ScreenRect {
+    ScreenRect {
+        left: rect.left,
+        top: rect.top,
+        right: rect.right,
+        bottom: rect.bottom,
+    }
+}
+
+/// 读取显示器信息
+fn monitor_info(monitor: HMONITOR) -> Option<MonitorInfo> {
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    
+    let result = unsafe { GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO) };
+    if !result.as_bool() {
+        return None;
+    }
+    
+    Some(MonitorInfo {
+        name: from_wide(&info.szDevice),
+        work_area: from_rect(&info.monitorInfo.rcWork),
+    })
+}
+
+/// 通过WTS会话信息查询锁定状态
+/// 
+/// 查询失败时返回None
+fn wts_session_locked() -> Option<bool> {
+    unsafe {
+        let mut buffer = PWSTR::null();
+        let mut bytes = 0u32;
+        
+        if WTSQuerySessionInformationW(
+            WTS_CURRENT_SERVER_HANDLE,
+            WTS_CURRENT_SESSION,
+            WTSSessionInfoEx,
+            &mut buffer,
+            &mut bytes,
+        ).is_err() || buffer.is_null() {
+            return None;
+        }
+        
+        let info = &*(buffer.0 as *const WTSINFOEXW);
+        let locked = (info.Level == 1)
+            .then(|| info.Data.WTSInfoExLevel1.SessionFlags as u32 == WTS_SESSIONSTATE_LOCK);
+        WTSFreeMemory(buffer.0 as *mut _);
+        locked
+    }
+}
+
+/// 通过输入桌面判断是否已锁定
+/// 
+/// 锁屏时输入桌面切换到安全桌面，当前进程无法切换到该桌面
+fn input_desktop_locked() -> bool {
+    unsafe {
+        let desktop = match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), BOOL::from(false), DESKTOP_SWITCHDESKTOP) {
+            Ok(desktop) => desktop,
+            Err(_) => return true,
+        };
+        let switched = SwitchDesktop(desktop).is_ok();
+        let _ = CloseDesktop(desktop);
+        !switched
+    }
+}
+
+/// 查找ntdll中未公开的函数
+/// 
+/// # 参数
+/// 
+/// * `name` - 以0结尾的函数名
+fn ntdll_proc(name: windows::core::PCSTR) -> Option<unsafe extern "system" fn() -> isize> {
+    unsafe {
+        let module = GetModuleHandleW(w!("ntdll.dll")).ok()?;
+        GetProcAddress(module, name)
+    }
+}
+
+/// 专注助手状态对应的WNF配置值
+fn focus_assist_profile(mode: FocusAssist) -> u32 {
+    match mode {
+        FocusAssist::Off => 0,
+        FocusAssist::PriorityOnly => 1,
+        FocusAssist::AlarmsOnly => 2,
+    }
+}
+
+impl PowerControl for NativePlatform {
+    fn enable_shutdown_privilege(&self) -> Result<()> {
+        let token = open_process_token(TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY)?;
+        
+        let result = (|| unsafe {
+            // 查找关机权限
+            let mut luid = LUID::default();
+            LookupPrivilegeValueW(PCWSTR::null(), SE_SHUTDOWN_NAME, &mut luid)
+                .map_err(|e| anyhow!("无法查找关机权限: {}", e))?;
+            
+            // 调整令牌权限
+            let token_privileges = TOKEN_PRIVILEGES {
+                PrivilegeCount: 1,
+                Privileges: [LUID_AND_ATTRIBUTES {
+                    Luid: luid,
+                    Attributes: SE_PRIVILEGE_ENABLED,
+                }],
+            };
+            AdjustTokenPrivileges(token, BOOL::from(false), Some(&token_privileges), 0, None, None)
+                .map_err(|e| anyhow!("无法调整令牌权限: {}", e))
+        })();
+        
+        unsafe {
+            let _ = CloseHandle(token);
+        }
+        result
+    }
+    
+    fn end_session(&self, kind: SessionEnd, force: bool) -> Result<()> {
+        let mut flags = match kind {
+            SessionEnd::Shutdown => EWX_SHUTDOWN,
+            SessionEnd::Restart => EWX_REBOOT,
+            SessionEnd::Logoff => EWX_LOGOFF,
+        };
+        if force {
+            flags = flags | EWX_FORCE;
+        }
+        
+        unsafe { ExitWindowsEx(flags, SHUTDOWN_REASON(0)) }
+            .map_err(|e| anyhow!("ExitWindowsEx调用失败: {}", e))
+    }
+    
+    fn schedule_shutdown(&self, reason: &str, delay_seconds: u32, restart: bool) -> Result<()> {
+        let message = to_wide(reason);
+        unsafe {
+            InitiateSystemShutdownExW(
+                PCWSTR::null(),
+                PCWSTR(message.as_ptr()),
+                delay_seconds,
+                BOOL::from(true),
+                BOOL::from(restart),
+                SHTDN_REASON_FLAG_PLANNED,
+            )
+        }.map_err(|e| anyhow!("InitiateSystemShutdownExW调用失败: {}", e))
+    }
+    
+    fn abort_scheduled_shutdown(&self) -> Result<bool> {
+        match unsafe { AbortSystemShutdownW(PCWSTR::null()) } {
+            Ok(()) => Ok(true),
+            Err(e) if e.code() == ERROR_NO_SHUTDOWN_IN_PROGRESS.to_hresult() => Ok(false),
+            Err(e) => Err(anyhow!("AbortSystemShutdownW调用失败: {}", e)),
+        }
+    }
+    
+    fn has_scheduled_shutdown(&self) -> bool {
+        // shutdown.exe和InitiateSystemShutdownEx设置的关机倒计时期间不为0
+        unsafe { GetSystemMetrics(SM_SHUTTINGDOWN) != 0 }
+    }
+    
+    fn suspend(&self) -> Result<()> {
+        // 参数依次为：是否休眠、是否强制、是否禁用唤醒事件
+        let result = unsafe { SetSuspendState(BOOLEAN(0), BOOLEAN(0), BOOLEAN(0)) };
+        if result.0 != 0 {
+            Ok(())
+        } else {
+            Err(anyhow!("SetSuspendState调用失败"))
+        }
+    }
+    
+    fn hibernate(&self) -> Result<()> {
+        // 系统未启用休眠时调用失败
+        let result = unsafe { SetSuspendState(BOOLEAN(1), BOOLEAN(0), BOOLEAN(0)) };
+        if result.0 != 0 {
+            Ok(())
+        } else {
+            Err(anyhow!("SetSuspendState调用失败，系统可能未启用休眠"))
+        }
+    }
+    
+    fn lock_workstation(&self) -> Result<()> {
+        unsafe { LockWorkStation() }.map_err(|e| anyhow!("LockWorkStation调用失败: {}", e))
+    }
+    
+    fn turn_off_monitor(&self) -> Result<()> {
+        // 使用PostMessage广播，避免无响应的窗口阻塞调用
+        unsafe {
+            PostMessageW(
+                HWND_BROADCAST,
+                WM_SYSCOMMAND,
+                WPARAM(SC_MONITORPOWER as usize),
+                LPARAM(MONITOR_POWER_OFF),
+            )
+        }.map_err(|e| anyhow!("广播关闭显示器消息失败: {}", e))
+    }
+    
+    fn block_shutdown(&self, window_handle: isize, reason: &str) -> Result<()> {
+        let reason = to_wide(reason);
+        unsafe { ShutdownBlockReasonCreate(HWND(window_handle), PCWSTR(reason.as_ptr())) }
+            .map_err(|e| anyhow!("ShutdownBlockReasonCreate调用失败: {}", e))
+    }
+    
+    fn unblock_shutdown(&self, window_handle: isize) -> Result<()> {
+        unsafe { ShutdownBlockReasonDestroy(HWND(window_handle)) }
+            .map_err(|e| anyhow!("ShutdownBlockReasonDestroy调用失败: {}", e))
+    }
+}
+
+impl SystemInfo for NativePlatform {
+    fn os_version(&self) -> Result<OsVersionInfo> {
+        let mut version_info = OSVERSIONINFOEXW {
+            dwOSVersionInfoSize: std::mem::size_of::<OSVERSIONINFOEXW>() as u32,
+            ..Default::default()
+        };
+        
+        unsafe { GetVersionExW(&mut version_info as *mut OSVERSIONINFOEXW as *mut _) }
+            .map_err(|e| anyhow!("无法获取Windows版本信息: {}", e))?;
+        
+        Ok(OsVersionInfo {
+            major: version_info.dwMajorVersion,
+            minor: version_info.dwMinorVersion,
+            build: version_info.dwBuildNumber,
+            service_pack: from_wide(&version_info.szCSDVersion),
+            is_workstation: version_info.wProductType as u32 == VER_NT_WORKSTATION,
+        })
+    }
+    
+    fn is_elevated(&self) -> Result<bool> {
+        let token = open_process_token(TOKEN_QUERY)?;
+        
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut return_length = 0u32;
+        let result = unsafe {
+            GetTokenInformation(
+                token,
+                TokenElevation,
+                Some(&mut elevation as *mut TOKEN_ELEVATION as *mut _),
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut return_length,
+            )
+        };
+        unsafe {
+            let _ = CloseHandle(token);
+        }
+        
+        result.map_err(|e| anyhow!("无法查询令牌提升信息: {}", e))?;
+        Ok(elevation.TokenIsElevated != 0)
+    }
+    
+    fn computer_name(&self) -> Result<String> {
+        let mut buffer = vec![0u16; MAX_COMPUTERNAME_LENGTH + 1];
+        let mut size = buffer.len() as u32;
+        
+        unsafe { GetComputerNameW(PWSTR(buffer.as_mut_ptr()), &mut size) }
+            .map_err(|e| anyhow!("无法获取计算机名: {}", e))?;
+        Ok(from_wide(&buffer[..size as usize]))
+    }
+    
+    fn user_name(&self) -> Result<String> {
+        let mut buffer = vec![0u16; UNLEN + 1];
+        let mut size = buffer.len() as u32;
+        
+        unsafe { GetUserNameW(PWSTR(buffer.as_mut_ptr()), &mut size) }
+            .map_err(|e| anyhow!("无法获取用户名: {}", e))?;
+        Ok(from_wide(&buffer))
+    }
+    
+    fn boot_mode(&self) -> BootMode {
+        match unsafe { GetSystemMetrics(SM_CLEANBOOT) } {
+            0 => BootMode::Normal,
+            1 => BootMode::SafeMode,
+            2 => BootMode::SafeModeWithNetwork,
+            other => BootMode::Unknown(other),
+        }
+    }
+    
+    fn uptime_ms(&self) -> u64 {
+        unsafe { GetTickCount64() }
+    }
+    
+    fn cpu_times(&self) -> Option<CpuTimes> {
+        let mut idle = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        unsafe { GetSystemTimes(Some(&mut idle), Some(&mut kernel), Some(&mut user)) }.ok()?;
+        
+        // 内核时间已包含空闲时间
+        let ticks = |time: FILETIME| ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64;
+        Some(CpuTimes {
+            idle: ticks(idle),
+            total: ticks(kernel) + ticks(user),
+        })
+    }
+    
+    fn network_bytes(&self) -> Option<u64> {
+        let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+        unsafe { GetIfTable2(&mut table) }.ok()?;
+        
+        // 只统计物理网卡，同一网卡的筛选驱动也会以单独的接口出现
+        let rows = unsafe { std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize) };
+        let total = rows.iter()
+            .filter(|row| row.InterfaceAndOperStatusFlags._bitfield & 1 != 0 && row.Type != IF_TYPE_SOFTWARE_LOOPBACK)
+            .map(|row| row.InOctets + row.OutOctets)
+            .sum();
+        let _ = unsafe { FreeMibTable(table as *const std::ffi::c_void) };
+        Some(total)
+    }
+    
+    fn process_names(&self) -> Option<Vec<String>> {
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }.ok()?;
+        
+        let mut names = Vec::new();
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut found = unsafe { Process32FirstW(snapshot, &mut entry) }.is_ok();
+        while found {
+            names.push(from_wide(&entry.szExeFile));
+            found = unsafe { Process32NextW(snapshot, &mut entry) }.is_ok();
+        }
+        
+        unsafe {
+            let _ = CloseHandle(snapshot);
+        }
+        Some(names)
+    }
+    
+    fn power_status(&self) -> Option<PowerStatus> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+        power_status_from_system(&status)
+    }
+    
+    fn user_registry_value(&self, key: &str, name: &str) -> Option<RegistryValue> {
+        let key = to_wide(key);
+        let name = to_wide(name);
+        let flags = RRF_RT_REG_SZ | RRF_RT_REG_BINARY;
+        
+        // 第一次调用只取数据大小
+        let mut size = 0u32;
+        unsafe {
+            RegGetValueW(HKEY_CURRENT_USER, PCWSTR(key.as_ptr()), PCWSTR(name.as_ptr()), flags, None, None, Some(&mut size))
+        }
+        .ok()
+        .ok()?;
+        
+        let mut value_type = REG_VALUE_TYPE::default();
+        let mut data = vec![0u8; size as usize];
+        unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(key.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                flags,
+                Some(&mut value_type),
+                Some(data.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        }
+        .ok()
+        .ok()?;
+        data.truncate(size as usize);
+        
+        match value_type {
+            REG_SZ => {
+                let wide: Vec<u16> = data.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+                Some(RegistryValue::String(from_wide(&wide)))
+            }
+            REG_BINARY => Some(RegistryValue::Binary(data)),
+            _ => None,
+        }
+    }
+}
+
+impl SessionInfo for NativePlatform {
+    fn idle_duration(&self) -> Duration {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        
+        unsafe {
+            if !GetLastInputInfo(&mut info).as_bool() {
+                return Duration::ZERO;
+            }
+            // 两者都是32位毫秒计数，约49天回绕一次
+            Duration::from_millis(GetTickCount().wrapping_sub(info.dwTime) as u64)
+        }
+    }
+    
+    fn is_session_locked(&self) -> bool {
+        // 优先使用WTS会话信息，查询失败时退回到输入桌面检测
+        wts_session_locked().unwrap_or_else(input_desktop_locked)
+    }
+    
+    fn is_remote_session(&self) -> bool {
+        unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+    }
+    
+    fn watch_session_end(&self, window_handle: isize, on_end: Box<dyn Fn() + Send + Sync>) -> Result<()> {
+        // 回调在窗口存在期间一直使用，不再释放
+        let data = Box::into_raw(Box::new(on_end));
+        let installed = unsafe {
+            SetWindowSubclass(HWND(window_handle), Some(session_end_subclass), SESSION_END_SUBCLASS_ID, data as usize)
+        };
+        if !installed.as_bool() {
+            drop(unsafe { Box::from_raw(data) });
+            return Err(anyhow!("SetWindowSubclass调用失败"));
+        }
+        Ok(())
+    }
+    
+    fn watch_resume(&self, window_handle: isize, on_resume: Box<dyn Fn() + Send + Sync>) -> Result<()> {
+        // 回调在窗口存在期间一直使用，不再释放
+        let data = Box::into_raw(Box::new(on_resume));
+        let installed = unsafe {
+            SetWindowSubclass(HWND(window_handle), Some(resume_subclass), RESUME_SUBCLASS_ID, data as usize)
+        };
+        if !installed.as_bool() {
+            drop(unsafe { Box::from_raw(data) });
+            return Err(anyhow!("SetWindowSubclass调用失败"));
+        }
+        Ok(())
+    }
+}
+
+/// 系统唤醒通知使用的窗口子类标识
+const RESUME_SUBCLASS_ID: usize = 0x5154_5257;
+
+/// 处理电源消息的窗口子类过程
+/// 
+/// 系统唤醒时调用`watch_resume`注册的回调。无论唤醒是否由用户操作引起都会收到PBT_APMRESUMEAUTOMATIC，
+/// 用户操作唤醒时另外收到的PBT_APMRESUMESUSPEND不再处理，避免重复通知
+unsafe extern "system" fn resume_subclass(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    data: usize,
+) -> LRESULT {
+    if message == WM_POWERBROADCAST && wparam.0 == PBT_APMRESUMEAUTOMATIC as usize {
+        let on_resume = &*(data as *const Box<dyn Fn() + Send + Sync>);
+        on_resume();
+    }
+    DefSubclassProc(hwnd, message, wparam, lparam)
+}
+
+/// 会话结束通知使用的窗口子类标识
+const SESSION_END_SUBCLASS_ID: usize = 0x5154_5345;
+
+/// 处理会话结束消息的窗口子类过程
+/// 
+/// 始终允许系统结束会话，确认结束时调用`watch_session_end`注册的回调
+unsafe extern "system" fn session_end_subclass(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    data: usize,
+) -> LRESULT {
+    // WM_QUERYENDSESSION交给默认处理，返回TRUE表示允许结束；
+    // WM_ENDSESSION的wparam为FALSE时表示结束会话已被取消
+    if message == WM_ENDSESSION && wparam.0 != 0 {
+        let on_end = &*(data as *const Box<dyn Fn() + Send + Sync>);
+        on_end();
+    }
+    DefSubclassProc(hwnd, message, wparam, lparam)
+}
+
+/// 把数据包装为DPAPI的输入，调用期间数据不能释放
+fn crypt_blob(data: &[u8]) -> CRYPT_INTEGER_BLOB {
+    CRYPT_INTEGER_BLOB { cbData: data.len() as u32, pbData: data.as_ptr() as *mut u8 }
+}
+
+/// 复制DPAPI输出的数据，并释放系统分配的内存
+fn take_crypt_blob(blob: CRYPT_INTEGER_BLOB) -> Vec<u8> {
+    if blob.pbData.is_null() {
+        return Vec::new();
+    }
+    unsafe {
+        let data = std::slice::from_raw_parts(blob.pbData, blob.cbData as usize).to_vec();
+        let _ = LocalFree(HLOCAL(blob.pbData as isize));
+        data
+    }
+}
+
+impl DataProtection for NativePlatform {
+    fn protect_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let input = crypt_blob(data);
+        let mut output = CRYPT_INTEGER_BLOB::default();
+        // 不提供额外的熵，只有同一用户在本机上能解密
+        unsafe { CryptProtectData(&input, w!("QtShut"), None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output) }
+            .map_err(|e| anyhow!("加密数据失败: {}", e))?;
+        Ok(take_crypt_blob(output))
+    }
+    
+    fn unprotect_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let input = crypt_blob(data);
+        let mut output = CRYPT_INTEGER_BLOB::default();
+        unsafe { CryptUnprotectData(&input, None, None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output) }
+            .map_err(|e| anyhow!("解密数据失败，可能是其他用户或其他电脑加密的文件: {}", e))?;
+        Ok(take_crypt_blob(output))
+    }
+}
+
+impl DisplayControl for NativePlatform {
+    fn list_monitors(&self) -> Vec<MonitorInfo> {
+        unsafe extern "system" fn collect(monitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, data: LPARAM) -> BOOL {
+            let monitors = &mut *(data.0 as *mut Vec<MonitorInfo>);
+            if let Some(info) = monitor_info(monitor) {
+                monitors.push(info);
+            }
+            TRUE
+        }
+        
+        let mut monitors: Vec<MonitorInfo> = Vec::new();
+        unsafe {
+            EnumDisplayMonitors(
+                HDC::default(),
+                None,
+                Some(collect),
+                LPARAM(&mut monitors as *mut Vec<MonitorInfo> as isize),
+            );
+        }
+        monitors
+    }
+    
+    fn window_placement(&self, window_handle: isize) -> Option<(MonitorInfo, ScreenRect)> {
+        let hwnd = HWND(window_handle);
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(hwnd, &mut rect) }.ok()?;
+        
+        let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+        monitor_info(monitor).map(|info| (info, from_rect(&rect)))
+    }
+    
+    fn move_window(&self, window_handle: isize, position: (i32, i32)) -> bool {
+        unsafe {
+            SetWindowPos(
+                HWND(window_handle),
+                HWND::default(),
+                position.0,
+                position.1,
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            )
+        }.is_ok()
+    }
+    
+    fn beep(&self, sound: Beep) {
+        let style = match sound {
+            Beep::Default => MB_OK,
+            Beep::Asterisk => MB_ICONASTERISK,
+            Beep::Exclamation => MB_ICONEXCLAMATION,
+            Beep::Hand => MB_ICONHAND,
+        };
+        let _ = unsafe { MessageBeep(style) };
+    }
+    
+    fn announce(&self, window_handle: isize, text: &str, activity_id: &str) -> Result<()> {
+        unsafe {
+            let provider = UiaHostProviderFromHwnd(HWND(window_handle))
+                .map_err(|e| anyhow!("获取UI Automation提供程序失败: {}", e))?;
+            UiaRaiseNotificationEvent(
+                &provider,
+                NotificationKind_Other,
+                NotificationProcessing_ImportantMostRecent,
+                &BSTR::from(text),
+                &BSTR::from(activity_id),
+            )
+        }.map_err(|e| anyhow!("发出屏幕阅读器通知失败: {}", e))
+    }
+    
+    fn focus_assist(&self) -> Option<FocusAssist> {
+        let query: NtQueryWnfStateData = unsafe { std::mem::transmute(ntdll_proc(s!("NtQueryWnfStateData"))?) };
+        let mut change_stamp = 0u32;
+        let mut profile = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            query(
+                &WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED,
+                std::ptr::null(),
+                std::ptr::null(),
+                &mut change_stamp,
+                &mut profile as *mut u32 as *mut _,
+                &mut size,
+            )
+        };
+        if status < 0 {
+            return None;
+        }
+        
+        match profile {
+            0 => Some(FocusAssist::Off),
+            1 => Some(FocusAssist::PriorityOnly),
+            2 => Some(FocusAssist::AlarmsOnly),
+            _ => None,
+        }
+    }
+    
+    fn set_focus_assist(&self, mode: FocusAssist) -> Result<()> {
+        let update: NtUpdateWnfStateData = unsafe {
+            std::mem::transmute(ntdll_proc(s!("NtUpdateWnfStateData")).ok_or_else(|| anyhow!("系统不支持切换专注助手"))?)
+        };
+        let profile = focus_assist_profile(mode);
+        let status = unsafe {
+            update(
+                &WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED,
+                &profile as *const u32 as *const _,
+                std::mem::size_of::<u32>() as u32,
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                0,
+            )
+        };
+        if status < 0 {
+            return Err(anyhow!("NtUpdateWnfStateData调用失败: 0x{:08X}", status));
+        }
+        Ok(())
+    }
+    
+    fn open_file(&self, path: &Path) -> Result<()> {
+        let file = to_wide(&path.to_string_lossy());
+        let result = unsafe { ShellExecuteW(HWND(0), w!("open"), PCWSTR(file.as_ptr()), PCWSTR::null(), PCWSTR::null(), SW_SHOWNORMAL) };
+        // 返回值大于32表示成功
+        if result.0 > 32 {
+            Ok(())
+        } else {
+            Err(anyhow!("打开文件失败: {}（错误码 {}）", path.display(), result.0))
+        }
+    }
+    
+    fn set_volume(&self, percent: u8) -> Result<()> {
+        let level = f32::from(percent.min(100)) / 100.0;
+        unsafe {
+            // 当前线程已初始化COM时返回错误，但不影响后续调用
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| anyhow!("创建音频设备枚举器失败: {}", e))?;
+            let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(|e| anyhow!("获取默认音频输出设备失败: {}", e))?;
+            let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+                .map_err(|e| anyhow!("获取音量控制接口失败: {}", e))?;
+            volume.SetMasterVolumeLevelScalar(level, std::ptr::null())
+        }.map_err(|e| anyhow!("设置音量失败: {}", e))
+    }
+}
@@ -0,0 +1,227 @@
+//! 自检模块
+//! 
+//! 依次检查时间解析、任务持久化、通知显示、托盘更新和关机演练，
+//! 报告每个子系统是否正常，适合新安装后确认程序可用。
+//! 自检不会修改用户数据，也不会真正关机
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use log::{info, warn};
+use tokio::sync::mpsc;
+
+use crate::core::{
+    persistence::TaskPersistence,
+    shutdown::ShutdownExecutor,
+    time_parser::TimeParser,
+    types::{CountdownStatus, StatusSnapshot, TaskAction, TaskData, TaskType},
+};
+use crate::platform;
+use crate::ui::tray::TrayManager;
+use crate::utils::notification::{NotificationBuilder, NotificationManager};
+
+/// 时间解析自检使用的示例输入
+const PARSER_SAMPLES: [&str; 4] = ["30分钟", "22:30", "每周五22:00", "cron 0 30 22 * * 1-5"];
+
+/// 单项检查结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// 通过
+    Passed,
+    /// 失败
+    Failed,
+    /// 当前平台不支持，已跳过
+    Skipped,
+}
+
+impl CheckStatus {
+    /// 显示用的状态文本
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Passed => "通过",
+            CheckStatus::Failed => "失败",
+            CheckStatus::Skipped => "跳过",
+        }
+    }
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    /// 子系统名称
+    pub name: &'static str,
+    /// 检查状态
+    pub status: CheckStatus,
+    /// 检查详情或错误信息
+    pub detail: String,
+}
+
+impl CheckResult {
+    /// 根据检查结果生成
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 子系统名称
+    /// * `result` - 成功时为详情，失败时为错误信息
+    fn from_result(name: &'static str, result: Result<String>) -> Self {
+        match result {
+            Ok(detail) => Self { name, status: CheckStatus::Passed, detail },
+            Err(e) => {
+                warn!("自检失败: {} - {}", name, e);
+                Self { name, status: CheckStatus::Failed, detail: e.to_string() }
+            }
+        }
+    }
+    
+    /// 生成跳过的检查结果
+    fn skipped(name: &'static str, detail: &str) -> Self {
+        Self { name, status: CheckStatus::Skipped, detail: detail.to_string() }
+    }
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfTestReport {
+    /// 各子系统的检查结果，按执行顺序排列
+    pub results: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    /// 是否没有失败的检查，跳过的检查不算失败
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.status != CheckStatus::Failed)
+    }
+    
+    /// 汇总文本，如"自检完成：通过 4 项，失败 0 项，跳过 1 项"
+    pub fn summary(&self) -> String {
+        let count = |status: CheckStatus| self.results.iter().filter(|result| result.status == status).count();
+        format!(
+            "自检完成：通过 {} 项，失败 {} 项，跳过 {} 项",
+            count(CheckStatus::Passed),
+            count(CheckStatus::Failed),
+            count(CheckStatus::Skipped)
+        )
+    }
+    
+    /// 格式化为多行文本，命令行直接输出
+    pub fn format(&self) -> String {
+        let mut lines: Vec<String> = self.results.iter()
+            .map(|result| format!("[{}] {}: {}", result.status.label(), result.name, result.detail))
+            .collect();
+        lines.push(self.summary());
+        lines.join("\n")
+    }
+}
+
+/// 运行全部自检
+/// 
+/// 单项检查失败不影响后续检查
+pub async fn run() -> SelfTestReport {
+    info!("开始自检");
+    
+    let results = vec![
+        CheckResult::from_result("时间解析", check_parser(TimeParser::global())),
+        CheckResult::from_result("任务持久化", check_persistence()),
+        CheckResult::from_result("通知显示", check_notification().await),
+        check_tray(),
+        CheckResult::from_result("关机演练", check_shutdown().await),
+    ];
+    
+    let report = SelfTestReport { results };
+    info!("{}", report.summary());
+    report
+}
+
+/// 解析示例输入，确认常用格式都能识别
+fn check_parser(parser: &TimeParser) -> Result<String> {
+    for sample in PARSER_SAMPLES {
+        let time_input = parser.parse(sample)
+            .map_err(|e| anyhow!("无法解析\"{}\": {}", sample, e))?;
+        TaskData::from_time_input(time_input.task_type_or(TaskType::Once), time_input, TaskAction::Shutdown, Local::now())
+            .map_err(|e| anyhow!("无法由\"{}\"创建任务: {}", sample, e))?;
+    }
+    
+    Ok(format!("已解析 {} 个示例输入", PARSER_SAMPLES.len()))
+}
+
+/// 在临时目录中保存并重新加载任务，不影响已保存的任务
+fn check_persistence() -> Result<String> {
+    let temp_dir = tempfile::tempdir()?;
+    let persistence = TaskPersistence::with_data_dir(temp_dir.path())?;
+    let time_input = TimeParser::global().parse(PARSER_SAMPLES[0])?;
+    let task = TaskData::from_time_input(TaskType::Once, time_input, TaskAction::Shutdown, Local::now())?;
+    
+    persistence.save_tasks(std::slice::from_ref(&task))?;
+    let loaded = persistence.load_tasks()?;
+    match loaded.as_slice() {
+        [restored] if restored.id == task.id && restored.target_time == task.target_time => {
+            Ok("任务保存后可以完整读回".to_string())
+        },
+        _ => Err(anyhow!("读回的任务与保存的不一致，共 {} 个", loaded.len())),
+    }
+}
+
+/// 显示一条测试通知
+async fn check_notification() -> Result<String> {
+    let mut manager = NotificationManager::new(1, 1);
+    let notification = NotificationBuilder::info("QtShut 自检", "这是一条测试通知").with_sound(false);
+    let id = manager.show_notification(notification).await
+        .map_err(|e| anyhow!("显示通知失败: {}", e))?;
+    
+    if manager.get_notification_by_id(&id).is_some() {
+        Ok("测试通知已显示".to_string())
+    } else {
+        Err(anyhow!("通知未进入活跃列表"))
+    }
+}
+
+/// 创建临时托盘图标并更新提示文本
+fn check_tray() -> CheckResult {
+    const NAME: &str = "托盘更新";
+    if !platform::capabilities().system_tray {
+        return CheckResult::skipped(NAME, "当前平台不支持系统托盘");
+    }
+    
+    let (sender, _receiver) = mpsc::unbounded_channel();
+    let mut tray = TrayManager::new(sender);
+    if let Err(e) = tray.initialize() {
+        return CheckResult::from_result(NAME, Err(anyhow!("创建托盘图标失败: {}", e)));
+    }
+    
+    let snapshot = StatusSnapshot::from_parts(&CountdownStatus::Idle, None, false, None, Local::now());
+    tray.update_tooltip(&snapshot, &[]);
+    tray.destroy();
+    CheckResult::from_result(NAME, Ok("托盘图标已创建并更新提示".to_string()))
+}
+
+/// 演练关机操作，只做执行前的检查
+async fn check_shutdown() -> Result<String> {
+    let executor = ShutdownExecutor::new().await?;
+    executor.dry_run(TaskAction::Shutdown).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parser_and_persistence_checks() {
+        assert!(check_parser(&TimeParser::new()).is_ok());
+        assert!(check_persistence().is_ok());
+    }
+    
+    #[test]
+    fn test_report_summary() {
+        let mut report = SelfTestReport {
+            results: vec![
+                CheckResult::from_result("时间解析", Ok("已解析".to_string())),
+                CheckResult::skipped("托盘更新", "当前平台不支持系统托盘"),
+            ],
+        };
+        assert!(report.passed());
+        assert_eq!(report.summary(), "自检完成：通过 1 项，失败 0 项，跳过 1 项");
+        
+        report.results.push(CheckResult::from_result("关机演练", Err(anyhow!("不支持"))));
+        assert!(!report.passed());
+        assert!(report.format().contains("[失败] 关机演练: 不支持"));
+    }
+}
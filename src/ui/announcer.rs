@@ -0,0 +1,126 @@
+//! 屏幕阅读器播报模块
+//! 
+//! 通过UI Automation通知事件定期播报剩余时间（如"剩余10分钟"），
+//! 使用屏幕阅读器的用户无需切换到主窗口也能了解倒计时进度
+
+use anyhow::{anyhow, Result};
+use chrono::Duration;
+use log::info;
+
+use crate::platform::{self, DisplayControl};
+
+/// 播报通知的活动ID，屏幕阅读器据此合并同类通知
+const ACTIVITY_ID: &str = "QtShut.Countdown";
+
+/// 剩余时间播报器
+#[derive(Debug)]
+pub struct ScreenReaderAnnouncer {
+    /// 发出通知的窗口句柄
+    window_handle: Option<isize>,
+    /// 播报间隔（分钟），0表示不播报
+    interval_minutes: u32,
+    /// 上次播报时的剩余分钟数
+    last_announced: Option<i64>,
+}
+
+impl ScreenReaderAnnouncer {
+    /// 创建新的播报器
+    /// 
+    /// # 参数
+    /// 
+    /// * `interval_minutes` - 播报间隔（分钟），0表示不播报
+    pub fn new(interval_minutes: u32) -> Self {
+        Self {
+            window_handle: None,
+            interval_minutes,
+            last_announced: None,
+        }
+    }
+    
+    /// 设置发出通知的窗口句柄
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 主窗口句柄（Windows下为HWND）
+    pub fn set_window_handle(&mut self, window_handle: isize) {
+        self.window_handle = Some(window_handle);
+    }
+    
+    /// 是否启用播报
+    pub fn is_enabled(&self) -> bool {
+        self.interval_minutes > 0
+    }
+    
+    /// 重置播报进度
+    /// 
+    /// 倒计时结束或取消后调用，下次倒计时重新开始播报
+    pub fn reset(&mut self) {
+        self.last_announced = None;
+    }
+    
+    /// 计算本次是否需要播报
+    /// 
+    /// 剩余分钟数（向上取整）是播报间隔的整数倍时播报一次
+    /// 
+    /// # 参数
+    /// 
+    /// * `remaining` - 剩余时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 需要播报时返回播报文本
+    pub fn due_announcement(&mut self, remaining: Duration) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+        
+        let seconds = remaining.num_seconds();
+        if seconds <= 0 {
+            return None;
+        }
+        
+        let minutes = (seconds + 59) / 60;
+        if minutes % self.interval_minutes as i64 != 0 || self.last_announced == Some(minutes) {
+            return None;
+        }
+        
+        self.last_announced = Some(minutes);
+        Some(format!("剩余{}分钟", minutes))
+    }
+    
+    /// 发出屏幕阅读器通知
+    /// 
+    /// # 参数
+    /// 
+    /// * `text` - 播报文本
+    pub fn announce(&self, text: &str) -> Result<()> {
+        let window_handle = self.window_handle.ok_or_else(|| anyhow!("主窗口句柄不可用"))?;
+        platform::native().announce(window_handle, text, ACTIVITY_ID)?;
+        
+        info!("屏幕阅读器播报: {}", text);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_due_announcement() {
+        let mut announcer = ScreenReaderAnnouncer::new(10);
+        
+        assert_eq!(announcer.due_announcement(Duration::seconds(20 * 60)).as_deref(), Some("剩余20分钟"));
+        // 同一分钟内只播报一次
+        assert!(announcer.due_announcement(Duration::seconds(20 * 60 - 30)).is_none());
+        assert!(announcer.due_announcement(Duration::seconds(15 * 60)).is_none());
+        assert_eq!(announcer.due_announcement(Duration::seconds(9 * 60 + 30)).as_deref(), Some("剩余10分钟"));
+        
+        announcer.reset();
+        assert!(announcer.due_announcement(Duration::seconds(10 * 60)).is_some());
+        
+        // 默认不播报
+        let mut silent = ScreenReaderAnnouncer::new(0);
+        assert!(silent.due_announcement(Duration::seconds(10 * 60)).is_none());
+    }
+}
@@ -2,10 +2,15 @@
 //! 
 //! 提供应用程序的各种UI组件，包括主窗口、时间输入面板、倒计时显示等
 
-use iced::widget::{button, column, container, row, text, text_input, pick_list, Space};
+use iced::widget::{button, column, container, row, slider, text, text_input, pick_list, Row, Space};
+use iced::widget::container::Appearance as ContainerAppearance;
 use iced::{Element, Length, Color, Background, Alignment, Theme as IcedTheme, Border, Shadow};
-use chrono::Duration;
-use crate::core::types::{TimeInput, TaskType};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Local, Weekday};
+use crate::core::time_parser::TimeParser;
+use crate::core::plan::SNOOZE_MINUTES;
+use crate::core::schedule;
+use crate::core::types::{format_quick_duration, PowerRequirement, TaskAction, TaskData, TimeInput, TaskType};
 use crate::ui::theme::Theme;
 use crate::ui::manager::Message;
 
@@ -244,22 +249,22 @@ impl MainWindowState {
         
         let quick_buttons = row![
             button(text("1分钟").size(12))
-                .on_press(Message::QuickCountdown(1))
+                .on_press(Message::QuickCountdown(Duration::minutes(1)))
                 .padding(8)
                 .width(Length::Fixed(80.0))
                 .style(iced::theme::Button::Secondary),
             button(text("5分钟").size(12))
-                .on_press(Message::QuickCountdown(5))
+                .on_press(Message::QuickCountdown(Duration::minutes(5)))
                 .padding(8)
                 .width(Length::Fixed(80.0))
                 .style(iced::theme::Button::Secondary),
             button(text("10分钟").size(12))
-                .on_press(Message::QuickCountdown(10))
+                .on_press(Message::QuickCountdown(Duration::minutes(10)))
                 .padding(8)
                 .width(Length::Fixed(80.0))
                 .style(iced::theme::Button::Secondary),
             button(text("30分钟").size(12))
-                .on_press(Message::QuickCountdown(30))
+                .on_press(Message::QuickCountdown(Duration::minutes(30)))
                 .padding(8)
                 .width(Length::Fixed(80.0))
                 .style(iced::theme::Button::Secondary),
@@ -373,4 +378,473 @@ impl ControlPanel {
     pub fn new() -> Self {
         Self::default()
     }
-}
\ No newline at end of file
+}
+
+/// 构建电源需求标记
+/// 
+/// # 参数
+/// 
+/// * `requirements` - 任务的电源需求
+pub fn power_badges(requirements: &[PowerRequirement]) -> Row<'static, Message> {
+    requirements.iter().fold(
+        Row::new().spacing(5),
+        |row, requirement| {
+            row.push(
+                container(text(requirement.to_string()).size(12))
+                    .padding([2, 6])
+                    .style(ContainerAppearance {
+                        background: Some(Background::Color(Color::from_rgb8(255, 243, 205))),
+                        border: Border {
+                            color: Color::from_rgb8(255, 193, 7),
+                            width: 1.0,
+                            radius: 4.0.into(),
+                        },
+                        ..Default::default()
+                    })
+            )
+        },
+    )
+}
+
+/// 任务列表组件
+/// 
+/// 列出所有已保存的任务及其下次执行时间和操作，提供启用、编辑和删除按钮
+#[derive(Debug, Clone, Default)]
+pub struct TaskListView {
+    /// 已保存的任务
+    pub tasks: Vec<TaskData>,
+    /// 正在倒计时的任务标识
+    pub active_id: Option<String>,
+}
+
+impl TaskListView {
+    /// 创建任务列表
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 已保存的任务
+    /// * `active_id` - 正在倒计时的任务标识
+    pub fn new(tasks: Vec<TaskData>, active_id: Option<String>) -> Self {
+        Self { tasks, active_id }
+    }
+    
+    /// 构建任务列表视图
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间，用于计算下次执行时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回任务列表的Element，不借用组件本身，可以直接放入弹窗
+    pub fn view(&self, now: DateTime<Local>) -> Element<'static, Message> {
+        let task_rows = self.tasks.iter().fold(
+            column![].spacing(8),
+            |rows, task| rows.push(self.build_task_row(task, now)),
+        );
+        
+        let hint = if self.tasks.is_empty() {
+            "还没有任务，在主窗口开始倒计时即可添加"
+        } else {
+            "到点最早的已启用任务会自动开始倒计时"
+        };
+        
+        column![
+            text("任务列表").size(24),
+            text(hint).size(14),
+            Space::with_height(Length::Fixed(10.0)),
+            task_rows,
+            Space::with_height(Length::Fixed(10.0)),
+            button("关闭").on_press(Message::ToggleTaskList),
+        ]
+        .spacing(10)
+        .into()
+    }
+    
+    /// 构建单个任务的行
+    /// 
+    /// 计划无法转换为主窗口输入的任务不能编辑
+    fn build_task_row(&self, task: &TaskData, now: DateTime<Local>) -> Element<'static, Message> {
+        let mut label = task.schedule_text();
+        if self.active_id.as_deref() == Some(task.id.as_str()) {
+            label.push_str("（倒计时中）");
+        } else if !task.enabled {
+            label.push_str("（已停用）");
+        }
+        
+        let edit_message = task.input_text(now).map(|_| Message::EditTask(task.id.clone()));
+        
+        let mut info = column![
+            text(label).size(14),
+            text(next_fire_text(task, now))
+                .size(12)
+                .style(Color::from_rgb8(108, 117, 125)),
+        ]
+        .spacing(2)
+        .width(Length::Fill);
+        // 重复任务显示执行统计
+        if let Some(summary) = task.stats.summary().filter(|_| task.task_type != TaskType::Once) {
+            info = info.push(text(summary).size(12).style(Color::from_rgb8(108, 117, 125)));
+        }
+        let requirements = task.power_requirements();
+        if !requirements.is_empty() {
+            info = info.push(power_badges(&requirements));
+        }
+        
+        row![
+            info,
+            button(if task.enabled { "停用" } else { "启用" })
+                .on_press(Message::SetTaskEnabled(task.id.clone(), !task.enabled)),
+            button("编辑").on_press_maybe(edit_message),
+            button("删除").on_press(Message::RemoveTask(task.id.clone())),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .into()
+    }
+}
+
+/// 任务编辑器组件
+/// 
+/// 在弹窗中修改已保存任务的时间、重复方式和操作，保存后按原任务标识替换
+#[derive(Debug, Clone)]
+pub struct TaskEditor {
+    /// 正在编辑的原任务
+    pub task: TaskData,
+    /// 时间输入，格式与主窗口相同
+    pub time_input: String,
+    /// 重复方式
+    pub repeat: TaskType,
+    /// 到点后执行的操作
+    pub action: TaskAction,
+    /// 上次保存失败的原因
+    pub error: Option<String>,
+}
+
+impl TaskEditor {
+    /// 打开任务编辑器
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 要编辑的任务
+    /// * `now` - 当前时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 任务的计划无法转换为时间输入时（如各天时间不同的每周计划）返回None
+    pub fn new(task: TaskData, now: DateTime<Local>) -> Option<Self> {
+        let time_input = task.input_text(now)?;
+        Some(Self {
+            repeat: task.task_type,
+            action: task.action,
+            time_input,
+            task,
+            error: None,
+        })
+    }
+    
+    /// 根据编辑内容生成修改后的任务
+    /// 
+    /// 输入本身带有重复规则（如"每周五22:00"或cron表达式）时以输入为准；
+    /// 每周计划只输入时间时保留原来的星期
+    /// 
+    /// # 参数
+    /// 
+    /// * `parser` - 时间解析器
+    /// * `now` - 当前时间
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回保留原任务标识、启用状态、来源和执行统计的任务数据
+    pub fn build_task(&self, parser: &TimeParser, now: DateTime<Local>) -> Result<TaskData> {
+        let time_input = parser.parse(&self.time_input)?;
+        let repeat = time_input.task_type_or(self.repeat);
+        let time_input = match (repeat, time_input) {
+            (TaskType::Weekly, TimeInput::DailyTime(time)) => self.weekly_input(time)?,
+            (TaskType::Weekly, TimeInput::AbsoluteTime(datetime)) => self.weekly_input(datetime.time())?,
+            (TaskType::Once, time_input) => {
+                parser.validate(&time_input)?;
+                time_input
+            },
+            (_, time_input) => time_input,
+        };
+        
+        let updated = TaskData::from_time_input(repeat, time_input, self.action, now)?;
+        Ok(TaskData {
+            id: self.task.id.clone(),
+            enabled: self.task.enabled,
+            created_at: self.task.created_at,
+            source: self.task.source,
+            custom_command: self.task.custom_command.clone().filter(|_| self.action == TaskAction::RunCommand),
+            stats: self.task.stats.clone(),
+            ..updated
+        })
+    }
+    
+    /// 沿用原每周计划的星期，使用新的时间
+    fn weekly_input(&self, time: chrono::NaiveTime) -> Result<TimeInput> {
+        let mut days: Vec<Weekday> = self.task.weekly_slots.iter().map(|slot| slot.weekday).collect();
+        if days.is_empty() {
+            return Err(anyhow!("请指定星期几，如 每周一三五 22:00"));
+        }
+        days.sort_by_key(|day| day.num_days_from_monday());
+        days.dedup();
+        Ok(TimeInput::Weekly { days, time })
+    }
+    
+    /// 构建编辑器视图
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回编辑器的Element，不借用组件本身，可以直接放入弹窗
+    pub fn view(&self) -> Element<'static, Message> {
+        let error_text = match &self.error {
+            Some(error) => text(error).size(14).style(Color::from_rgb8(220, 53, 69)),
+            None => text(""),
+        };
+        
+        column![
+            text("编辑任务").size(24),
+            text(self.task.schedule_text()).size(14).style(Color::from_rgb8(108, 117, 125)),
+            Space::with_height(Length::Fixed(10.0)),
+            text_input("如 22:30、每周一三五 23:00 或 cron 0 30 22 * * 1-5", &self.time_input)
+                .on_input(Message::EditorTimeChanged)
+                .on_submit(Message::SaveTaskEdit)
+                .padding(8),
+            row![
+                text("重复:").size(14),
+                pick_list(TaskType::ALL.to_vec(), Some(self.repeat), Message::EditorRepeatChanged),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            row![
+                text("到点后:").size(14),
+                pick_list(TaskAction::available(), Some(self.action), Message::EditorActionChanged),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            error_text,
+            row![
+                button("保存").on_press(Message::SaveTaskEdit),
+                button("取消").on_press(Message::CloseTaskEditor),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+/// 大按钮模式中按钮和滑块的高度（像素），触控目标不小于48像素
+pub const LARGE_TOUCH_TARGET: f32 = 56.0;
+
+/// 大按钮模式滑块可选的最短时长（分钟）
+pub const LARGE_SLIDER_MIN_MINUTES: u32 = 5;
+
+/// 大按钮模式滑块可选的最长时长（分钟）
+pub const LARGE_SLIDER_MAX_MINUTES: u32 = 240;
+
+/// 大按钮模式滑块每格的分钟数
+pub const LARGE_SLIDER_STEP_MINUTES: u32 = 5;
+
+/// 大按钮模式滑块的默认时长（分钟）
+pub const DEFAULT_LARGE_SLIDER_MINUTES: u32 = 30;
+
+/// 大按钮模式面板
+/// 
+/// 面向触摸屏一体机，只用滑块和预设按钮选择时长，不需要键盘输入
+#[derive(Debug, Clone)]
+pub struct LargeButtonPanel {
+    /// 滑块选择的分钟数
+    pub minutes: u32,
+    /// 到点后执行的操作
+    pub action: TaskAction,
+    /// 预设时长，来自当前配置方案的快速倒计时
+    pub presets: Vec<Duration>,
+    /// 倒计时状态说明
+    pub status: String,
+    /// 是否显示推迟按钮，处于最后确认阶段且管理策略允许推迟时显示
+    pub show_snooze: bool,
+}
+
+impl LargeButtonPanel {
+    /// 预设时长对应的滑块分钟数
+    /// 
+    /// 超出滑块范围或不是整格的预设不显示
+    pub fn preset_minutes(&self) -> Vec<u32> {
+        self.presets.iter()
+            .filter_map(|duration| u32::try_from(duration.num_minutes()).ok())
+            .filter(|minutes| (LARGE_SLIDER_MIN_MINUTES..=LARGE_SLIDER_MAX_MINUTES).contains(minutes))
+            .filter(|minutes| minutes % LARGE_SLIDER_STEP_MINUTES == 0)
+            .collect()
+    }
+    
+    /// 构建大按钮模式视图
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回面板的Element，不借用组件本身
+    pub fn view(&self) -> Element<'static, Message> {
+        let selected = format!("{}后{}", format_quick_duration(Duration::minutes(i64::from(self.minutes))), self.action);
+        
+        let presets = self.preset_minutes().into_iter().fold(
+            row![].spacing(10),
+            |row, minutes| row.push(large_button(
+                format_quick_duration(Duration::minutes(i64::from(minutes))),
+                Message::LargeSliderChanged(minutes),
+                if minutes == self.minutes { iced::theme::Button::Primary } else { iced::theme::Button::Secondary },
+            )),
+        );
+        
+        // 每行三个操作，窄窗口中也不会挤出边界
+        let actions = TaskAction::available().chunks(3).fold(
+            column![].spacing(10),
+            |rows, chunk| rows.push(chunk.iter().fold(
+                row![].spacing(10),
+                |row, &action| row.push(large_button(
+                    action.to_string(),
+                    Message::ActionSelected(action),
+                    if action == self.action { iced::theme::Button::Primary } else { iced::theme::Button::Secondary },
+                )),
+            )),
+        );
+        
+        let mut controls = row![
+            large_button("开始", Message::StartLargeCountdown, iced::theme::Button::Primary),
+            large_button("取消", Message::CancelCountdown, iced::theme::Button::Destructive),
+        ]
+        .spacing(10);
+        if self.show_snooze {
+            controls = controls.push(large_button(format!("推迟{}分钟", SNOOZE_MINUTES), Message::Snooze, iced::theme::Button::Secondary));
+        }
+        
+        column![
+            text(&self.status).size(22),
+            text(selected).size(28),
+            slider(LARGE_SLIDER_MIN_MINUTES..=LARGE_SLIDER_MAX_MINUTES, self.minutes, Message::LargeSliderChanged)
+                .step(LARGE_SLIDER_STEP_MINUTES)
+                .height(LARGE_TOUCH_TARGET),
+            presets,
+            actions,
+            controls,
+        ]
+        .spacing(15)
+        .into()
+    }
+}
+
+/// 大按钮模式中的按钮
+fn large_button(label: impl ToString, message: Message, style: iced::theme::Button) -> iced::widget::Button<'static, Message> {
+    button(
+        text(label.to_string())
+            .size(20)
+            .horizontal_alignment(iced::alignment::Horizontal::Center)
+            .vertical_alignment(iced::alignment::Vertical::Center)
+    )
+    .on_press(message)
+    .padding([0, 20])
+    .height(Length::Fixed(LARGE_TOUCH_TARGET))
+    .style(style)
+}
+
+/// 任务下次执行的说明，如"下次执行: 2024-03-08 22:30，关机"
+/// 
+/// # 参数
+/// 
+/// * `task` - 任务数据
+/// * `now` - 当前时间
+pub fn next_fire_text(task: &TaskData, now: DateTime<Local>) -> String {
+    match schedule::next_fire(task, now) {
+        Some((time, action)) => format!("下次执行: {}，{}", time.format("%Y-%m-%d %H:%M"), action),
+        None if task.task_type == TaskType::Logon => "每天第一次登录时执行".to_string(),
+        None => "不再执行".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    
+    use crate::core::types::TaskAction;
+    
+    #[test]
+    fn test_large_button_presets() {
+        let panel = LargeButtonPanel {
+            minutes: DEFAULT_LARGE_SLIDER_MINUTES,
+            action: TaskAction::Shutdown,
+            presets: vec![Duration::minutes(1), Duration::minutes(30), Duration::minutes(90), Duration::minutes(32), Duration::hours(5)],
+            status: String::new(),
+            show_snooze: false,
+        };
+        // 超出滑块范围或不是整格的预设不显示
+        assert_eq!(panel.preset_minutes(), vec![30, 90]);
+    }
+    
+    #[test]
+    fn test_next_fire_text() {
+        let now = Local::now();
+        let task = TaskData::from_time_input(TaskType::Once, TimeInput::Duration(Duration::minutes(30)), TaskAction::Sleep, now).unwrap();
+        let expected = (now + Duration::minutes(30)).format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(next_fire_text(&task, now), format!("下次执行: {}，睡眠", expected));
+        
+        // 停用的任务仍显示计划的下次执行时间
+        let mut daily = TaskData::from_time_input(
+            TaskType::Daily,
+            TimeInput::DailyTime(NaiveTime::from_hms_opt(22, 30, 0).unwrap()),
+            TaskAction::Shutdown,
+            now,
+        ).unwrap();
+        daily.enabled = false;
+        assert!(next_fire_text(&daily, now).ends_with("22:30，关机"));
+        
+        assert_eq!(next_fire_text(&task, now + Duration::hours(1)), "不再执行");
+        assert_eq!(next_fire_text(&TaskData::logon(Vec::new(), now), now), "每天第一次登录时执行");
+    }
+    
+    #[test]
+    fn test_task_editor() {
+        let parser = TimeParser::new();
+        let now = Local::now();
+        let mut daily = TaskData::from_time_input(
+            TaskType::Daily,
+            TimeInput::DailyTime(NaiveTime::from_hms_opt(22, 30, 0).unwrap()),
+            TaskAction::Shutdown,
+            now,
+        ).unwrap();
+        daily.enabled = false;
+        
+        let mut editor = TaskEditor::new(daily.clone(), now).unwrap();
+        assert_eq!(editor.time_input, "每天22:30");
+        assert_eq!(editor.repeat, TaskType::Daily);
+        
+        // 修改时间和操作，保留标识和启用状态
+        editor.time_input = "23:15".to_string();
+        editor.action = TaskAction::Sleep;
+        let edited = editor.build_task(&parser, now).unwrap();
+        assert_eq!(edited.id, daily.id);
+        assert!(!edited.enabled);
+        assert_eq!(edited.daily_time, NaiveTime::from_hms_opt(23, 15, 0));
+        assert_eq!(edited.action, TaskAction::Sleep);
+        
+        // 输入带有星期时改为每周计划
+        editor.time_input = "每周一三五 23:00".to_string();
+        let edited = editor.build_task(&parser, now).unwrap();
+        assert_eq!(edited.task_type, TaskType::Weekly);
+        assert_eq!(edited.weekly_slots.len(), 3);
+        
+        // 每周计划只改时间时保留星期，原任务没有星期时报错
+        let mut weekly = TaskEditor::new(edited, now).unwrap();
+        weekly.time_input = "21:00".to_string();
+        let edited = weekly.build_task(&parser, now).unwrap();
+        assert_eq!(edited.weekly_slots.len(), 3);
+        assert!(edited.weekly_slots.iter().all(|slot| slot.time == NaiveTime::from_hms_opt(21, 0, 0).unwrap()));
+        editor.time_input = "21:00".to_string();
+        editor.repeat = TaskType::Weekly;
+        assert!(editor.build_task(&parser, now).is_err());
+        
+        editor.repeat = TaskType::Daily;
+        editor.time_input = "30分钟".to_string();
+        assert!(editor.build_task(&parser, now).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! 专注助手模块
+//! 
+//! 最后警告期间开启专注助手（仅闹钟），暂时屏蔽其他程序的通知，
+//! 让关机警告不被淹没；执行、取消或延长后恢复原来的状态
+
+use anyhow::Result;
+use log::{info, warn};
+
+use crate::platform::{self, DisplayControl, FocusAssist};
+
+/// 最后警告期间的专注助手管理器
+#[derive(Debug)]
+pub struct FocusAssistGuard {
+    /// 是否在最后警告期间开启专注助手
+    enabled: bool,
+    /// 开启前的专注助手状态，为空表示当前没有由本程序开启
+    previous: Option<FocusAssist>,
+}
+
+impl FocusAssistGuard {
+    /// 创建新的专注助手管理器
+    /// 
+    /// # 参数
+    /// 
+    /// * `enabled` - 是否在最后警告期间开启专注助手，当前平台不支持时忽略
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: enabled && platform::capabilities().focus_assist,
+            previous: None,
+        }
+    }
+    
+    /// 是否在最后警告期间开启专注助手
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    /// 设置是否在最后警告期间开启专注助手
+    /// 
+    /// 关闭时立即恢复原来的状态
+    /// 
+    /// # 参数
+    /// 
+    /// * `enabled` - 是否开启
+    pub fn set_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.enabled = enabled && platform::capabilities().focus_assist;
+        if !self.enabled {
+            self.restore()?;
+        }
+        Ok(())
+    }
+    
+    /// 是否已由本程序开启专注助手
+    pub fn is_active(&self) -> bool {
+        self.previous.is_some()
+    }
+    
+    /// 按是否处于最后警告期间同步专注助手状态
+    /// 
+    /// # 参数
+    /// 
+    /// * `final_warning` - 是否处于最后警告期间
+    pub fn sync(&mut self, final_warning: bool) -> Result<()> {
+        if final_warning && self.enabled {
+            self.activate()
+        } else {
+            self.restore()
+        }
+    }
+    
+    /// 开启专注助手并记录原来的状态
+    /// 
+    /// 已开启或无法查询当前状态时不做任何操作
+    fn activate(&mut self) -> Result<()> {
+        if self.previous.is_some() {
+            return Ok(());
+        }
+        let Some(previous) = platform::native().focus_assist() else {
+            return Ok(());
+        };
+        
+        if previous != FocusAssist::AlarmsOnly {
+            platform::native().set_focus_assist(FocusAssist::AlarmsOnly)?;
+            info!("最后警告期间已开启专注助手，原状态: {:?}", previous);
+        }
+        self.previous = Some(previous);
+        Ok(())
+    }
+    
+    /// 恢复开启前的专注助手状态
+    /// 
+    /// 没有由本程序开启时不做任何操作
+    fn restore(&mut self) -> Result<()> {
+        let Some(previous) = self.previous.take() else {
+            return Ok(());
+        };
+        if previous != FocusAssist::AlarmsOnly {
+            platform::native().set_focus_assist(previous)?;
+            info!("已恢复专注助手状态: {:?}", previous);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for FocusAssistGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.restore() {
+            warn!("恢复专注助手状态失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_disabled_guard_stays_inactive() {
+        let mut guard = FocusAssistGuard::new(false);
+        assert!(!guard.is_enabled());
+        
+        assert!(guard.sync(true).is_ok());
+        assert!(!guard.is_active());
+        
+        // 未开启时恢复应直接成功
+        assert!(guard.sync(false).is_ok());
+        assert!(!guard.is_active());
+    }
+    
+    #[test]
+    fn test_enabled_only_where_supported() {
+        let guard = FocusAssistGuard::new(true);
+        assert_eq!(guard.is_enabled(), platform::capabilities().focus_assist);
+    }
+}
@@ -2,25 +2,887 @@
 //! 
 //! 负责管理整个用户界面，使用iced框架实现跨平台GUI
 
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{NaiveTime, Weekday};
 use iced::{
-    widget::{button, column, container, row, text, text_input, Space},
-    Application, Command, Element, Length, Settings, Theme as IcedTheme, executor, Font, time, window,
+    widget::{button, column, container, pick_list, row, text, text_input, Row, Space},
+    Command, Element, Length, Settings, Theme as IcedTheme, executor, Font, window,
 };
+use iced::multi_window::Application;
 use iced::widget::container::Appearance as ContainerAppearance;
 use iced::{Background, Border, Color};
-use log::{info, error};
+use log::{info, warn, error};
 use tokio::sync::{mpsc, broadcast};
+use tray_icon::{menu::MenuEvent, TrayIconEvent};
 
 use crate::core::{
+    autostart::{self, AutostartState},
+    battery::{BatterySettings, DEFAULT_CANCEL_BELOW_PERCENT},
+    bundle::SettingsBundle,
+    calendar,
+    condition::ConditionSettings,
+    countdown::StatusHandle,
+    history::{CancelReason, HistoryLog},
+    ics::{self, CalendarImport},
+    persistence::TaskPersistence,
+    scheduler::{self, TaskListHandle},
+    plan::{format_warning_offset, is_short_countdown, parse_warning_offsets, RescheduleChoice, TaskPlan, ADJUST_MINUTES, FINAL_WARNING_SECONDS, SHORT_COUNTDOWN_SECONDS, SNOOZE_MINUTES},
+    remote::{self, RemoteSettings, RemoteTarget, DEFAULT_REMOTE_DELAY_SECONDS},
+    routine::{LogonRoutine, RoutineStep},
+    shutdown::ShutdownBlocker,
+    storage::{self, TaskBackup},
+    suggestions::SuggestionProvider,
+    system_compat::{self, SystemCompatibility},
+    template::{self, TemplatePreview},
     time_parser::TimeParser,
-    types::{CountdownUpdate, CountdownStatus, UIEvent, TaskType, TimeInput},
+    trigger::{ProcessExitTrigger, DEFAULT_PROCESS_EXIT_DELAY_MINUTES},
+    types::{CountdownUpdate, CountdownStatus, StatusSnapshot, StatusState, UIEvent, TaskData, TaskSource, TaskType, TimeInput, ProfileData, PowerRequirement, TaskAction, WeeklySlot, WindowMode, WindowRequest, CustomCommand, format_quick_duration, weekday_label, QUICK_COUNTDOWN_ACTION},
+    webhook::{self, WebhookEvent, WebhookPayload},
 };
+use crate::platform::{self, DisplayControl, SessionInfo, SystemInfo};
+use crate::selftest::{self, CheckStatus, SelfTestReport};
 use crate::ui::{
+    announcer::ScreenReaderAnnouncer,
+    components::{power_badges, LargeButtonPanel, TaskEditor, TaskListView, DEFAULT_LARGE_SLIDER_MINUTES},
+    focus_assist::FocusAssistGuard,
+    placement::{self, SnapDirection},
     tray::TrayManager,
-    theme::Theme,
+    theme::{self, Theme, ThemeSchedule, ThemeType, DEFAULT_FONT_FAMILY, FONT_CHOICES},
+    warning_overlay::WarningOverlay,
+};
+use crate::utils::{
+    config::{AppConfig, ConfigManager, ConfigValidator, SoundSettings, UISettings},
+    crash,
+    logger::{self, LogFilter, LoggerManager},
+    notification::{NotificationBuilder, NotificationManager, NotificationMessage, NotificationType},
+    policy::Policy,
+    sound,
+    system,
 };
 
+/// 没有倒计时时的窗口标题
+const DEFAULT_WINDOW_TITLE: &str = "QtShut - 定时关机";
+
+/// 系统结束会话时最多等待保存任务状态的时间，超时后不再阻塞系统
+const SESSION_END_WAIT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// 设置窗口"历史"分页显示最近多少天的记录
+const HISTORY_VIEW_DAYS: i64 = 30;
+
+/// 设置窗口"历史"分页最多显示的记录数
+const HISTORY_VIEW_ENTRIES: usize = 20;
+
+/// 日志查看器最多显示的行数，只显示最近的日志
+const LOG_VIEW_LINES: usize = 20;
+
+/// 日志查看器可选的最低日志级别
+const LOG_LEVEL_CHOICES: [log::Level; 5] = [log::Level::Error, log::Level::Warn, log::Level::Info, log::Level::Debug, log::Level::Trace];
+
+/// 每周计划中某一天的操作选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotActionChoice {
+    /// 当天不执行任何操作
+    Skip,
+    /// 执行指定操作
+    Run(TaskAction),
+}
+
+impl SlotActionChoice {
+    /// 所有可选项
+    fn all() -> Vec<SlotActionChoice> {
+        std::iter::once(SlotActionChoice::Skip)
+            .chain(TaskAction::available().into_iter().map(SlotActionChoice::Run))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for SlotActionChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlotActionChoice::Skip => write!(f, "不执行"),
+            SlotActionChoice::Run(action) => write!(f, "{}", action),
+        }
+    }
+}
+
+/// 设置中可以恢复的备份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupChoice {
+    /// 任务备份
+    Tasks(TaskBackup),
+    /// 配置文件备份
+    Config(std::path::PathBuf, chrono::DateTime<chrono::Local>),
+}
+
+impl std::fmt::Display for BackupChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackupChoice::Tasks(backup) => write!(f, "任务 {}", backup.at.format("%Y-%m-%d %H:%M:%S")),
+            BackupChoice::Config(_, at) => write!(f, "设置 {}", at.format("%Y-%m-%d %H:%M:%S")),
+        }
+    }
+}
+
+/// 设置中自定义命令的输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CustomCommandInput {
+    /// 程序路径输入
+    program: String,
+    /// 参数输入
+    args: String,
+    /// 工作目录输入
+    working_dir: String,
+    /// 保存结果提示
+    message: Option<String>,
+}
+
+impl CustomCommandInput {
+    /// 根据已保存的自定义命令创建输入
+    fn from_command(command: &CustomCommand) -> Self {
+        Self {
+            program: command.program.clone(),
+            args: command.args_text(),
+            working_dir: command.working_dir.clone().unwrap_or_default(),
+            message: None,
+        }
+    }
+    
+    /// 转换为自定义命令
+    fn to_command(&self) -> CustomCommand {
+        let working_dir = self.working_dir.trim();
+        CustomCommand {
+            program: self.program.trim().to_string(),
+            args: CustomCommand::split_args(&self.args),
+            working_dir: (!working_dir.is_empty()).then(|| working_dir.to_string()),
+        }
+    }
+}
+
+/// 设置中CPU占用条件的输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CpuThresholdInput {
+    /// 百分比输入，为空表示不检查CPU占用
+    value: String,
+    /// 保存结果提示
+    message: Option<String>,
+}
+
+impl CpuThresholdInput {
+    /// 根据已保存的执行条件创建输入
+    fn from_conditions(conditions: &ConditionSettings) -> Self {
+        Self {
+            value: conditions.cpu_above_percent.map(|percent| percent.to_string()).unwrap_or_default(),
+            message: None,
+        }
+    }
+    
+    /// 解析输入的百分比
+    /// 
+    /// # 返回值
+    /// 
+    /// 输入为空时返回None，不是1到100之间的整数时返回错误说明
+    fn parse(&self) -> Result<Option<u32>, String> {
+        let value = self.value.trim().trim_end_matches('%').trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        match value.parse::<u32>() {
+            Ok(percent) if (1..=100).contains(&percent) => Ok(Some(percent)),
+            _ => Err("请输入1到100之间的整数".to_string()),
+        }
+    }
+}
+
+/// 设置中日志查看器的筛选条件和结果
+#[derive(Debug, Clone, PartialEq)]
+struct LogViewerInput {
+    /// 最低日志级别
+    level: log::Level,
+    /// 搜索关键词，为空表示不限
+    search: String,
+    /// 起始时间输入，如"2024-05-06 22:00"或"2024-05-06"，为空表示不限
+    from: String,
+    /// 结束时间输入，只写日期时包含当天全天，为空表示不限
+    to: String,
+    /// 最近符合条件的日志行
+    lines: Vec<String>,
+    /// 查看或导出结果提示
+    message: Option<String>,
+}
+
+impl Default for LogViewerInput {
+    fn default() -> Self {
+        Self {
+            level: log::Level::Info,
+            search: String::new(),
+            from: String::new(),
+            to: String::new(),
+            lines: Vec::new(),
+            message: None,
+        }
+    }
+}
+
+impl LogViewerInput {
+    /// 按当前输入生成日志过滤条件
+    /// 
+    /// # 返回值
+    /// 
+    /// 时间格式有误时返回错误提示
+    fn filter(&self) -> Result<LogFilter, String> {
+        let from = Self::parse_time(&self.from, chrono::NaiveTime::MIN)
+            .map_err(|_| format!("起始时间格式有误: {}", self.from.trim()))?;
+        let to = Self::parse_time(&self.to, chrono::NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap_or_default())
+            .map_err(|_| format!("结束时间格式有误: {}", self.to.trim()))?;
+        Ok(LogFilter::new()
+            .with_min_level(self.level)
+            .with_time_range(from, to)
+            .with_search_term(self.search.trim()))
+    }
+    
+    /// 解析时间范围输入
+    /// 
+    /// # 参数
+    /// 
+    /// * `input` - 输入的时间，为空表示不限
+    /// * `day_time` - 只写日期时使用的时刻
+    fn parse_time(input: &str, day_time: chrono::NaiveTime) -> Result<Option<chrono::NaiveDateTime>, chrono::ParseError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        chrono::NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| chrono::NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M"))
+            .or_else(|_| chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d").map(|date| date.and_time(day_time)))
+            .map(Some)
+    }
+}
+
+/// 设置中推迟执行的进程列表输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BlockingProcessesInput {
+    /// 进程名，以逗号或换行分隔
+    value: String,
+    /// 保存结果提示
+    message: Option<String>,
+}
+
+impl BlockingProcessesInput {
+    /// 根据已保存的执行条件创建输入
+    fn from_conditions(conditions: &ConditionSettings) -> Self {
+        Self {
+            value: conditions.blocking_processes.join(", "),
+            message: None,
+        }
+    }
+    
+    /// 解析输入的进程名列表
+    /// 
+    /// 进程名中可能含有空格，只按中英文逗号、分号和换行分隔，去掉重复的名称
+    fn parse(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for name in self.value.split([',', '，', ';', '；', '\n']).map(str::trim).filter(|name| !name.is_empty()) {
+            if !names.iter().any(|existing| existing.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+}
+
+/// 设置中的提醒声音文件输入
+#[derive(Debug, Clone, PartialEq)]
+struct SoundFilesInput {
+    /// 正在编辑的通知类型
+    selected: NotificationType,
+    /// 各类型通知的声音文件路径输入
+    files: HashMap<NotificationType, String>,
+    /// 音量百分比输入
+    volume: String,
+    /// 保存或试听结果提示
+    message: Option<String>,
+}
+
+impl Default for SoundFilesInput {
+    fn default() -> Self {
+        Self::from_settings(&SoundSettings::default())
+    }
+}
+
+impl SoundFilesInput {
+    /// 根据已保存的声音设置创建输入
+    fn from_settings(settings: &SoundSettings) -> Self {
+        Self {
+            selected: NotificationType::Countdown,
+            files: settings.files.clone(),
+            volume: settings.volume.to_string(),
+            message: None,
+        }
+    }
+    
+    /// 正在编辑的通知类型的文件路径
+    fn path(&self) -> &str {
+        self.files.get(&self.selected).map(String::as_str).unwrap_or("")
+    }
+    
+    /// 解析输入的音量
+    /// 
+    /// # 返回值
+    /// 
+    /// 不是0到100之间的整数时返回错误说明
+    fn parse_volume(&self) -> Result<u8, String> {
+        match self.volume.trim().trim_end_matches('%').trim().parse::<u8>() {
+            Ok(percent) if percent <= 100 => Ok(percent),
+            _ => Err("音量请输入0到100之间的整数".to_string()),
+        }
+    }
+    
+    /// 检查并整理各类型通知的声音文件
+    /// 
+    /// # 返回值
+    /// 
+    /// 去掉路径为空的类型，文件不存在或格式不支持时返回错误说明
+    fn parse_files(&self) -> Result<HashMap<NotificationType, String>, String> {
+        let mut files = HashMap::new();
+        for notification_type in NotificationType::ALL {
+            let path = self.files.get(&notification_type).map(|path| path.trim()).unwrap_or("");
+            if path.is_empty() {
+                continue;
+            }
+            sound::check_file(std::path::Path::new(path))
+                .map_err(|e| format!("{}通知: {}", notification_type, e))?;
+            files.insert(notification_type, path.to_string());
+        }
+        Ok(files)
+    }
+}
+
+/// 设置中的界面字体输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct FontFamilyInput {
+    /// 字体名称，为空表示使用默认字体
+    value: String,
+    /// 窗口当前使用的字体
+    current: String,
+    /// 保存结果提示
+    message: Option<String>,
+}
+
+impl FontFamilyInput {
+    /// 根据窗口当前使用的字体创建输入
+    fn from_family(family: &str) -> Self {
+        Self {
+            value: family.to_string(),
+            current: family.to_string(),
+            message: None,
+        }
+    }
+    
+    /// 输入的字体名称，为空时为默认字体
+    fn family(&self) -> String {
+        match self.value.trim() {
+            "" => DEFAULT_FONT_FAMILY.to_string(),
+            family => family.to_string(),
+        }
+    }
+    
+    /// 选择列表中与输入相同的字体
+    fn selected_choice(&self) -> Option<&'static str> {
+        FONT_CHOICES.iter().copied().find(|choice| *choice == self.family())
+    }
+    
+    /// 输入的字体是否与窗口当前使用的字体不同
+    fn needs_switch(&self) -> bool {
+        self.family() != self.current
+    }
+}
+
+/// 设置中登录任务的输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LogonRoutineInput {
+    /// 是否显示今日计划
+    show_schedule: bool,
+    /// 音量百分比，为空表示不调整音量
+    volume: String,
+    /// 是否运行自定义命令
+    run_command: bool,
+    /// 保存结果提示
+    message: Option<String>,
+}
+
+impl LogonRoutineInput {
+    /// 根据任务列表中的登录任务创建输入
+    fn from_tasks(tasks: &[TaskData]) -> Self {
+        let steps = tasks.iter()
+            .find(|task| task.task_type == TaskType::Logon)
+            .and_then(|task| task.routine.as_ref())
+            .map(|routine| routine.steps.clone())
+            .unwrap_or_default();
+        Self {
+            show_schedule: steps.contains(&RoutineStep::ShowSchedule),
+            volume: steps.iter()
+                .find_map(|step| match step {
+                    RoutineStep::SetVolume(percent) => Some(percent.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+            run_command: steps.contains(&RoutineStep::RunCommand),
+            message: None,
+        }
+    }
+    
+    /// 解析为按顺序执行的步骤
+    /// 
+    /// 先调整音量，再显示今日计划，最后运行命令
+    /// 
+    /// # 返回值
+    /// 
+    /// 没有选择任何步骤时返回空列表，音量不是0到100之间的整数时返回错误说明
+    fn parse(&self) -> Result<Vec<RoutineStep>, String> {
+        let mut steps = Vec::new();
+        let volume = self.volume.trim().trim_end_matches('%').trim();
+        if !volume.is_empty() {
+            match volume.parse::<u8>() {
+                Ok(percent) if percent <= 100 => steps.push(RoutineStep::SetVolume(percent)),
+                _ => return Err("音量请输入0到100之间的整数".to_string()),
+            }
+        }
+        if self.show_schedule {
+            steps.push(RoutineStep::ShowSchedule);
+        }
+        if self.run_command {
+            steps.push(RoutineStep::RunCommand);
+        }
+        Ok(steps)
+    }
+}
+
+/// 设置窗口的分页
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum SettingsTab {
+    /// 启动、关闭窗口、开机运行、任务备份和模板
+    #[default]
+    General,
+    /// 主题、字体、提醒方式和声音
+    Interface,
+    /// 到点前提醒、执行前检查、自定义命令和电池
+    Shutdown,
+    /// 日志、数据目录、远程关机和外部通知
+    Advanced,
+    /// 最近执行、取消和执行失败的任务
+    History,
+}
+
+impl SettingsTab {
+    /// 所有分页，按显示顺序排列
+    const ALL: [SettingsTab; 5] = [SettingsTab::General, SettingsTab::Interface, SettingsTab::Shutdown, SettingsTab::Advanced, SettingsTab::History];
+    
+    /// 分页标题
+    fn label(self) -> &'static str {
+        match self {
+            SettingsTab::General => "常规",
+            SettingsTab::Interface => "界面",
+            SettingsTab::Shutdown => "关机",
+            SettingsTab::Advanced => "高级",
+            SettingsTab::History => "历史",
+        }
+    }
+}
+
+/// 设置中的开关选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFlag {
+    /// 启动时最小化到托盘（`app.start_minimized`）
+    StartMinimized,
+    /// 关闭主窗口时隐藏到托盘（`app.minimize_on_close`）
+    MinimizeOnClose,
+    /// 取消后询问原因（`ui.ask_cancel_reason`）
+    AskCancelReason,
+    /// 录制事件（`advanced.record_events`）
+    RecordEvents,
+    /// 保存前备份数据（`advanced.backup_data`）
+    BackupData,
+    /// 加密保存数据（`advanced.encrypt_data`）
+    EncryptData,
+}
+
+/// 设置中的文字选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigText {
+    /// 屏幕阅读器播报间隔（`ui.announce_interval_minutes`）
+    AnnounceInterval,
+    /// 全屏最后警告秒数（`ui.fullscreen_warning_seconds`）
+    FullscreenWarning,
+    /// 托盘图标闪烁分钟数（`ui.tray_attention_minutes`）
+    TrayAttention,
+    /// 到点前的提醒时间（`shutdown.warning_offsets`）
+    WarningOffsets,
+    /// 每日屏幕使用时间上限（`shutdown.screen_time.daily_minutes`）
+    ScreenTimeLimit,
+    /// 日志级别（`advanced.log_level`）
+    LogLevel,
+    /// 备份文件数（`advanced.max_backup_files`）
+    MaxBackupFiles,
+    /// 数据目录大小上限（`advanced.max_data_size_mb`）
+    MaxDataSize,
+}
+
+/// 设置窗口中直接对应配置文件字段的选项
+/// 
+/// 保存时写回配置，并用`ConfigValidator`检查整个配置，有错误时不保存
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ConfigForm {
+    /// 启动时最小化到托盘
+    start_minimized: bool,
+    /// 关闭主窗口时隐藏到托盘
+    minimize_on_close: bool,
+    /// 取消后询问原因
+    ask_cancel_reason: bool,
+    /// 屏幕阅读器播报间隔（分钟）
+    announce_interval_minutes: String,
+    /// 全屏最后警告秒数
+    fullscreen_warning_seconds: String,
+    /// 托盘图标闪烁分钟数
+    tray_attention_minutes: String,
+    /// 到点前的提醒时间，逗号分隔
+    warning_offsets: String,
+    /// 每日屏幕使用时间上限（分钟），为空表示不限制
+    screen_time_minutes: String,
+    /// 达到屏幕使用时间上限后执行的操作
+    screen_time_action: TaskAction,
+    /// 日志级别
+    log_level: String,
+    /// 任务文件备份数
+    max_backup_files: String,
+    /// 数据目录大小上限（MB）
+    max_data_size_mb: String,
+    /// 录制事件
+    record_events: bool,
+    /// 保存前备份数据
+    backup_data: bool,
+    /// 加密保存数据
+    encrypt_data: bool,
+    /// 保存结果提示
+    message: Option<String>,
+}
+
+impl ConfigForm {
+    /// 根据配置创建选项
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            start_minimized: config.app.start_minimized,
+            minimize_on_close: config.app.minimize_on_close,
+            ask_cancel_reason: config.ui.ask_cancel_reason,
+            announce_interval_minutes: config.ui.announce_interval_minutes.to_string(),
+            fullscreen_warning_seconds: config.ui.fullscreen_warning_seconds.to_string(),
+            tray_attention_minutes: config.ui.tray_attention_minutes.to_string(),
+            warning_offsets: config.shutdown.warning_offsets.join(", "),
+            screen_time_minutes: config.shutdown.screen_time.daily_minutes.map(|minutes| minutes.to_string()).unwrap_or_default(),
+            screen_time_action: config.shutdown.screen_time.action,
+            log_level: config.advanced.log_level.clone(),
+            max_backup_files: config.advanced.max_backup_files.to_string(),
+            max_data_size_mb: config.advanced.max_data_size_mb.to_string(),
+            record_events: config.advanced.record_events,
+            backup_data: config.advanced.backup_data,
+            encrypt_data: config.advanced.encrypt_data,
+            message: None,
+        }
+    }
+    
+    /// 开关选项的当前值
+    fn flag(&self, flag: ConfigFlag) -> bool {
+        match flag {
+            ConfigFlag::StartMinimized => self.start_minimized,
+            ConfigFlag::MinimizeOnClose => self.minimize_on_close,
+            ConfigFlag::AskCancelReason => self.ask_cancel_reason,
+            ConfigFlag::RecordEvents => self.record_events,
+            ConfigFlag::BackupData => self.backup_data,
+            ConfigFlag::EncryptData => self.encrypt_data,
+        }
+    }
+    
+    /// 切换开关选项
+    fn toggle(&mut self, flag: ConfigFlag) {
+        let value = match flag {
+            ConfigFlag::StartMinimized => &mut self.start_minimized,
+            ConfigFlag::MinimizeOnClose => &mut self.minimize_on_close,
+            ConfigFlag::AskCancelReason => &mut self.ask_cancel_reason,
+            ConfigFlag::RecordEvents => &mut self.record_events,
+            ConfigFlag::BackupData => &mut self.backup_data,
+            ConfigFlag::EncryptData => &mut self.encrypt_data,
+        };
+        *value = !*value;
+    }
+    
+    /// 修改文字选项
+    fn set_text(&mut self, field: ConfigText, value: String) {
+        let target = match field {
+            ConfigText::AnnounceInterval => &mut self.announce_interval_minutes,
+            ConfigText::FullscreenWarning => &mut self.fullscreen_warning_seconds,
+            ConfigText::TrayAttention => &mut self.tray_attention_minutes,
+            ConfigText::WarningOffsets => &mut self.warning_offsets,
+            ConfigText::ScreenTimeLimit => &mut self.screen_time_minutes,
+            ConfigText::LogLevel => &mut self.log_level,
+            ConfigText::MaxBackupFiles => &mut self.max_backup_files,
+            ConfigText::MaxDataSize => &mut self.max_data_size_mb,
+        };
+        *target = value;
+    }
+    
+    /// 把选项写入配置并检查
+    /// 
+    /// # 参数
+    /// 
+    /// * `config` - 要修改的配置
+    /// 
+    /// # 返回值
+    /// 
+    /// 数字格式错误或配置检查不通过时返回错误说明，此时配置可能已被部分修改，不应保存
+    fn apply(&self, config: &mut AppConfig) -> Result<(), String> {
+        /// 解析数字选项
+        fn number<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, String> {
+            value.trim().parse().map_err(|_| format!("{}必须是整数", name))
+        }
+        
+        config.app.start_minimized = self.start_minimized;
+        config.app.minimize_on_close = self.minimize_on_close;
+        config.ui.ask_cancel_reason = self.ask_cancel_reason;
+        config.ui.announce_interval_minutes = number(&self.announce_interval_minutes, "播报间隔")?;
+        config.ui.fullscreen_warning_seconds = number(&self.fullscreen_warning_seconds, "全屏警告时间")?;
+        config.ui.tray_attention_minutes = number(&self.tray_attention_minutes, "托盘提醒时间")?;
+        config.shutdown.warning_offsets = self.warning_offsets
+            .split([',', '，'])
+            .map(str::trim)
+            .filter(|offset| !offset.is_empty())
+            .map(str::to_string)
+            .collect();
+        config.shutdown.screen_time.daily_minutes = match self.screen_time_minutes.trim() {
+            "" => None,
+            minutes => Some(number(minutes, "每日使用上限")?),
+        };
+        config.shutdown.screen_time.action = self.screen_time_action;
+        config.advanced.log_level = self.log_level.trim().to_lowercase();
+        config.advanced.max_backup_files = number(&self.max_backup_files, "备份文件数")?;
+        config.advanced.max_data_size_mb = number(&self.max_data_size_mb, "数据目录大小上限")?;
+        config.advanced.record_events = self.record_events;
+        config.advanced.backup_data = self.backup_data;
+        config.advanced.encrypt_data = self.encrypt_data;
+        
+        let (valid, errors) = ConfigValidator::validate_config(config);
+        if valid { Ok(()) } else { Err(errors.join("；")) }
+    }
+}
+
+/// 设置中的外部通知输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct WebhookInput {
+    /// 接收事件的网址，为空表示不发送
+    url: String,
+    /// 保存或测试结果提示
+    message: Option<String>,
+}
+
+impl WebhookInput {
+    /// 根据已保存的网址创建输入
+    fn from_url(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            message: None,
+        }
+    }
+    
+    /// 解析输入的网址
+    /// 
+    /// # 返回值
+    /// 
+    /// 输入为空时返回空字符串，格式不正确时返回错误说明
+    fn parse(&self) -> Result<String, String> {
+        let url = self.url.trim();
+        if url.is_empty() {
+            return Ok(String::new());
+        }
+        webhook::validate_url(url).map_err(|e| e.to_string())?;
+        Ok(url.to_string())
+    }
+}
+
+/// 设置中的远程关机输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RemoteInput {
+    /// 是否接受其他电脑发来的命令
+    enabled: bool,
+    /// 本机口令
+    secret: String,
+    /// 监听地址
+    bind_address: String,
+    /// 可以关机的其他电脑
+    targets: Vec<RemoteTarget>,
+    /// 要添加的电脑名称
+    name: String,
+    /// 要添加的电脑地址
+    address: String,
+    /// 要添加的电脑的口令
+    target_secret: String,
+    /// 操作结果提示
+    message: Option<String>,
+}
+
+impl RemoteInput {
+    /// 根据远程关机设置创建输入
+    fn from_settings(settings: &RemoteSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            secret: settings.secret.clone(),
+            bind_address: settings.bind_address.clone(),
+            targets: settings.targets.clone(),
+            ..Self::default()
+        }
+    }
+    
+    /// 解析要添加的电脑
+    /// 
+    /// 名称为空时使用地址
+    /// 
+    /// # 返回值
+    /// 
+    /// 地址或口令为空时返回错误说明
+    fn parse_target(&self) -> Result<RemoteTarget, String> {
+        let address = self.address.trim();
+        if address.is_empty() {
+            return Err("请输入对方电脑的地址，如 192.168.1.20".to_string());
+        }
+        if self.target_secret.trim().is_empty() {
+            return Err("请输入对方电脑设置的口令".to_string());
+        }
+        let name = match self.name.trim() {
+            "" => address,
+            name => name,
+        };
+        Ok(RemoteTarget {
+            name: name.to_string(),
+            address: address.to_string(),
+            secret: self.target_secret.trim().to_string(),
+        })
+    }
+    
+    /// 解析监听地址
+    /// 
+    /// 为空时只监听本机
+    fn parse_bind_address(&self) -> Result<String, String> {
+        match self.bind_address.trim() {
+            "" => Ok(remote::DEFAULT_REMOTE_BIND_ADDRESS.to_string()),
+            address => address.parse::<std::net::IpAddr>()
+                .map(|ip| ip.to_string())
+                .map_err(|_| format!("无效的监听地址: {}，请输入如 127.0.0.1 或 0.0.0.0 的IP地址", address)),
+        }
+    }
+    
+    /// 保存到配置文件
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let bind_address = self.parse_bind_address()?;
+        let mut config_manager = ConfigManager::new()?;
+        let remote = &mut config_manager.get_config_mut().remote;
+        remote.enabled = self.enabled;
+        remote.bind_address = bind_address;
+        remote.secret = self.secret.trim().to_string();
+        remote.targets = self.targets.clone();
+        config_manager.save_config()
+    }
+}
+
+/// 进程退出触发的输入
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ProcessWatchInput {
+    /// 正在运行的进程名，已去重并排序
+    processes: Vec<String>,
+    /// 选择的进程
+    selected: Option<String>,
+    /// 进程退出后再等待的分钟数
+    delay: String,
+    /// 错误提示
+    message: Option<String>,
+}
+
+impl ProcessWatchInput {
+    /// 读取当前正在运行的进程
+    fn load() -> Self {
+        let mut input = Self {
+            delay: DEFAULT_PROCESS_EXIT_DELAY_MINUTES.to_string(),
+            ..Default::default()
+        };
+        input.refresh();
+        input
+    }
+    
+    /// 重新读取正在运行的进程，保留仍在运行的已选进程
+    fn refresh(&mut self) {
+        match system::get_running_processes() {
+            Some(processes) => {
+                self.processes = Self::unique_sorted(processes);
+                self.message = None;
+            },
+            None => {
+                self.processes.clear();
+                self.message = Some("当前平台无法获取进程列表".to_string());
+            }
+        }
+        if self.selected.as_ref().is_some_and(|selected| !self.processes.contains(selected)) {
+            self.selected = None;
+        }
+    }
+    
+    /// 去掉重复的进程名（不区分大小写）并按名称排序
+    fn unique_sorted(mut processes: Vec<String>) -> Vec<String> {
+        processes.sort_by_key(|name| name.to_lowercase());
+        processes.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        processes
+    }
+    
+    /// 根据输入生成进程退出触发
+    /// 
+    /// # 返回值
+    /// 
+    /// 没有选择进程或分钟数不是0到1440之间的整数时返回错误说明
+    fn trigger(&self) -> Result<ProcessExitTrigger, String> {
+        let process = self.selected.clone().ok_or_else(|| "请选择要等待的进程".to_string())?;
+        let delay = self.delay.trim();
+        let delay_minutes = if delay.is_empty() {
+            0
+        } else {
+            match delay.parse::<u32>() {
+                Ok(minutes) if minutes <= 24 * 60 => minutes,
+                _ => return Err("请输入0到1440之间的分钟数".to_string()),
+            }
+        };
+        Ok(ProcessExitTrigger { process, delay_minutes })
+    }
+}
+
+/// 每周计划编辑器中一天的输入
+#[derive(Debug, Clone, PartialEq)]
+struct WeeklySlotInput {
+    /// 星期几
+    weekday: Weekday,
+    /// 时间输入
+    time: String,
+    /// 操作选项
+    choice: SlotActionChoice,
+}
+
+/// 创建空白的每周计划编辑器，周一到周日各一行
+fn default_weekly_editor() -> Vec<WeeklySlotInput> {
+    [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun]
+        .into_iter()
+        .map(|weekday| WeeklySlotInput {
+            weekday,
+            time: String::new(),
+            choice: SlotActionChoice::Skip,
+        })
+        .collect()
+}
+
 /// 应用程序消息类型
 /// 
 /// 定义了应用程序中所有可能的用户交互和系统事件
@@ -28,32 +890,288 @@ use crate::ui::{
 pub enum Message {
     /// 时间输入改变
     TimeInputChanged(String),
+    /// 在时间输入框中按下回车，选中了建议时使用该建议
+    SubmitTimeInput,
+    /// 用方向键移动选中的建议，1为下一条，-1为上一条
+    MoveSuggestion(isize),
+    /// 选择一条时间输入建议
+    SuggestionChosen(String),
     /// 更新时间输入
     UpdateTimeInput(TimeInput),
     /// 更新任务类型
     UpdateTaskType(TaskType),
     /// 开始倒计时
     StartCountdown,
+    /// 预览当前输入的执行计划
+    PreviewPlan,
+    /// 选择到点后执行的操作
+    ActionSelected(TaskAction),
     /// 取消倒计时
     CancelCountdown,
+    /// 选择取消原因，为空表示跳过
+    CancelReasonChosen(Option<CancelReason>),
+    /// 第一次提醒时选择改期方式
+    RescheduleChosen(RescheduleChoice),
+    /// 恢复上一个版本的任务文件
+    RestoreTaskBackup,
+    /// 选择要恢复的备份
+    BackupSelected(BackupChoice),
+    /// 恢复选择的备份
+    RestoreSelectedBackup,
+    /// 将任务计划导出为日历文件
+    ExportCalendar,
+    /// 要导入的日历文件路径或网址输入改变
+    CalendarSourceChanged(String),
+    /// 日历事件标题关键字输入改变
+    CalendarKeywordChanged(String),
+    /// 读取日历并预览要导入的任务
+    PreviewCalendar,
+    /// 日历读取完成
+    CalendarLoaded(Result<CalendarImport, String>),
+    /// 确认导入日历中的任务
+    ImportCalendar,
+    /// 放弃导入日历中的任务
+    DiscardCalendar,
+    /// 将任务、配置和配置方案导出为设置包
+    ExportSettings,
+    /// 设置包文件路径输入改变
+    BundleSourceChanged(String),
+    /// 导入设置包
+    ImportSettings,
+    /// 暂停或继续倒计时
+    TogglePause,
+    /// 推迟倒计时
+    Snooze,
+    /// 增加剩余时间
+    AddTime,
+    /// 减少剩余时间
+    SubtractTime,
+    /// 取消PIN输入改变
+    CancelPinChanged(String),
     /// 最小化到托盘
     MinimizeToTray,
     /// 从托盘恢复
     RestoreFromTray,
     /// 切换主题
     ToggleTheme,
+    /// 开启或关闭日落后自动深色
+    ToggleAutoTheme,
+    /// 按时间同步自动主题
+    SyncAutoTheme,
+    /// 开启或关闭最后警告期间的专注助手
+    ToggleFocusAssist,
     /// 退出应用
     Exit,
+    /// 后台代理方式下点击了主窗口的关闭按钮
+    MainWindowCloseRequested,
     /// 倒计时更新
     CountdownUpdate(CountdownUpdate),
     /// 快速倒计时
-    QuickCountdown(u32),
+    QuickCountdown(chrono::Duration),
+    /// 重复上次执行或取消的倒计时
+    RepeatLast,
+    /// 下载完成（网络空闲）后执行选择的操作
+    WaitForNetworkIdle,
+    /// 市电断开（UPS电池供电）后执行选择的操作
+    WaitForPowerLoss,
+    /// 显示或关闭进程退出触发设置
+    ToggleProcessWatch,
+    /// 重新读取进程列表
+    RefreshProcesses,
+    /// 选择等待退出的进程
+    ProcessSelected(String),
+    /// 进程退出后等待分钟数输入改变
+    ProcessDelayChanged(String),
+    /// 等待选择的进程退出后执行选择的操作
+    StartProcessWatch,
     /// 显示设置
     ShowSettings,
     /// 显示关于
     ShowAbout,
     /// 检查倒计时状态
     CheckCountdownStatus,
+    /// 切换配置方案
+    SwitchProfile(String),
+    /// 新配置方案名称输入改变
+    NewProfileNameChanged(String),
+    /// 创建配置方案
+    CreateProfile,
+    /// 将当前输入保存为预设
+    SavePreset,
+    /// 已获取主窗口句柄
+    WindowHandleFetched(Option<isize>),
+    /// 检查托盘事件
+    PollTrayEvents,
+    /// 取消检测到的外部关机计划
+    CancelExternalShutdown,
+    /// 保留外部关机计划并关闭提示
+    DismissExternalShutdownNotice,
+    /// 打开上次崩溃时保存的报告
+    OpenCrashReport,
+    /// 关闭崩溃报告提示
+    DismissCrashReport,
+    /// 确认在远程桌面会话中开始任务
+    ConfirmRemoteStart,
+    /// 放弃在远程桌面会话中开始任务
+    DismissRemoteWarning,
+    /// 确认开始不到一分钟的倒计时
+    ConfirmShortStart,
+    /// 放弃开始短倒计时，返回修改时间
+    DismissShortStart,
+    /// 显示/隐藏每周计划编辑器
+    ToggleWeeklyEditor,
+    /// 每周计划某一天的时间改变
+    WeeklyTimeChanged(usize, String),
+    /// 每周计划某一天的操作改变
+    WeeklyActionChanged(usize, SlotActionChoice),
+    /// 开始每周计划
+    StartWeekly,
+    /// 模板来源输入改变
+    TemplateSourceChanged(String),
+    /// 加载并预览模板
+    PreviewTemplate,
+    /// 模板加载完成
+    TemplateLoaded(Result<TemplatePreview, String>),
+    /// 确认导入模板
+    ImportTemplate,
+    /// 放弃导入模板
+    DiscardTemplate,
+    /// 自定义命令程序输入改变
+    CustomProgramChanged(String),
+    /// 自定义命令参数输入改变
+    CustomArgsChanged(String),
+    /// 自定义命令工作目录输入改变
+    CustomWorkingDirChanged(String),
+    /// 保存自定义命令
+    SaveCustomCommand,
+    /// CPU占用条件输入改变
+    CpuThresholdChanged(String),
+    /// 保存CPU占用条件
+    SaveCpuThreshold,
+    /// 日志查看器的最低级别改变
+    LogLevelSelected(log::Level),
+    /// 日志查看器的关键词输入改变
+    LogSearchChanged(String),
+    /// 日志查看器的起始时间输入改变
+    LogFromChanged(String),
+    /// 日志查看器的结束时间输入改变
+    LogToChanged(String),
+    /// 按条件查看日志
+    ViewLogs,
+    /// 导出符合条件的日志
+    ExportLogs,
+    /// 推迟执行的进程列表输入改变
+    BlockingProcessesChanged(String),
+    /// 保存推迟执行的进程列表
+    SaveBlockingProcesses,
+    /// 选择要设置声音文件的通知类型
+    SoundTypeSelected(NotificationType),
+    /// 声音文件路径输入改变
+    SoundFileChanged(String),
+    /// 声音文件音量输入改变
+    SoundVolumeChanged(String),
+    /// 试听选中通知类型的声音文件
+    PreviewSound,
+    /// 保存声音文件和音量
+    SaveSounds,
+    /// 开启或关闭使用电池供电时用休眠代替关机
+    ToggleBatteryHibernate,
+    /// 开启或关闭电量过低时取消执行
+    ToggleBatteryCancel,
+    /// 开启或关闭登录时显示今日计划
+    ToggleLogonSchedule,
+    /// 开启或关闭登录时运行自定义命令
+    ToggleLogonCommand,
+    /// 登录时音量输入改变
+    LogonVolumeChanged(String),
+    /// 保存登录任务
+    SaveLogonRoutine,
+    /// 界面字体输入改变
+    FontFamilyChanged(String),
+    /// 保存并切换界面字体
+    ApplyFontFamily,
+    /// 切换后的界面字体已加载
+    FontLoaded,
+    /// 开启或关闭大按钮模式
+    ToggleLargeButtons,
+    /// 开启或关闭同步到Windows任务计划程序
+    ToggleSystemTasks,
+    /// 开启或关闭开机后在后台运行
+    ToggleAutoStart,
+    /// 开机自动启动登记或检查完成，成功时包含实际登记状态，失败时包含错误信息
+    AutoStartChecked(Result<AutostartState, String>),
+    /// 开启或关闭接受其他电脑的远程命令
+    ToggleRemoteServer,
+    /// 本机口令输入改变
+    RemoteSecretChanged(String),
+    /// 监听地址输入改变
+    RemoteBindAddressChanged(String),
+    /// 保存本机口令和监听地址
+    SaveRemoteSecret,
+    /// 要添加的电脑名称输入改变
+    RemoteTargetNameChanged(String),
+    /// 要添加的电脑地址输入改变
+    RemoteTargetAddressChanged(String),
+    /// 要添加的电脑的口令输入改变
+    RemoteTargetSecretChanged(String),
+    /// 添加可以远程关机的电脑
+    AddRemoteTarget,
+    /// 删除可以远程关机的电脑
+    RemoveRemoteTarget(usize),
+    /// 远程关闭指定的电脑
+    SendRemoteShutdown(usize),
+    /// 远程关机命令已发送
+    RemoteShutdownSent(Result<String, String>),
+    /// 外部通知网址输入改变
+    WebhookUrlChanged(String),
+    /// 保存外部通知网址
+    SaveWebhook,
+    /// 发送测试消息
+    TestWebhook,
+    /// 测试消息已发送
+    WebhookTested(Result<(), String>),
+    /// 切换设置分页
+    SettingsTabSelected(SettingsTab),
+    /// 切换设置中的开关选项
+    ConfigFlagToggled(ConfigFlag),
+    /// 设置中的文字选项改变
+    ConfigTextChanged(ConfigText, String),
+    /// 选择达到屏幕使用时间上限后执行的操作
+    ScreenTimeActionSelected(TaskAction),
+    /// 保存设置中的选项
+    SaveConfigForm,
+    /// 大按钮模式滑块改变（分钟）
+    LargeSliderChanged(u32),
+    /// 按大按钮模式滑块选择的时长开始倒计时
+    StartLargeCountdown,
+    /// 将窗口停靠到屏幕边缘
+    SnapWindow(SnapDirection),
+    /// 窗口位置改变
+    WindowMoved,
+    /// 显示/隐藏任务列表
+    ToggleTaskList,
+    /// 删除任务
+    RemoveTask(String),
+    /// 启用或停用任务
+    SetTaskEnabled(String, bool),
+    /// 打开任务编辑器
+    EditTask(String),
+    /// 任务编辑器时间输入改变
+    EditorTimeChanged(String),
+    /// 任务编辑器重复方式改变
+    EditorRepeatChanged(TaskType),
+    /// 任务编辑器操作改变
+    EditorActionChanged(TaskAction),
+    /// 保存任务编辑
+    SaveTaskEdit,
+    /// 关闭任务编辑器
+    CloseTaskEditor,
+    /// 运行自检
+    RunSelfTest,
+    /// 自检完成
+    SelfTestFinished(SelfTestReport),
+    /// 关闭自检报告
+    CloseSelfTestReport,
 }
 
 /// UI管理器应用程序状态
@@ -63,6 +1181,10 @@ pub enum Message {
 pub struct UIManager {
     /// 时间输入字符串
     time_input: String,
+    /// 按当前输入筛选的时间输入建议
+    time_suggestions: Vec<String>,
+    /// 用方向键选中的建议
+    suggestion_index: Option<usize>,
     /// 当前倒计时状态
     countdown_status: CountdownStatus,
     /// 时间解析器
@@ -79,10 +1201,152 @@ pub struct UIManager {
     is_dark_theme: bool,
     /// 是否最小化到托盘
     minimized_to_tray: bool,
+    /// 是否以后台代理方式运行，关闭主窗口时隐藏而不退出
+    background_agent: bool,
+    /// 关闭主窗口时是否隐藏到托盘而不退出
+    minimize_on_close: bool,
     /// 是否显示设置窗口
     show_settings: bool,
     /// 是否显示关于窗口
     show_about: bool,
+    /// 配置方案持久化
+    task_persistence: Option<TaskPersistence>,
+    /// 当前配置方案
+    profile: ProfileData,
+    /// 所有配置方案名称
+    profile_names: Vec<String>,
+    /// 新配置方案名称输入
+    new_profile_name: String,
+    /// 当前任务的电源需求
+    power_requirements: Vec<PowerRequirement>,
+    /// 关机阻止原因管理器
+    shutdown_blocker: Option<ShutdownBlocker>,
+    /// 是否显示外部关机计划被接管的提示
+    show_external_shutdown_notice: bool,
+    /// 上次崩溃时保存的报告，为空时不提示
+    crash_report: Option<std::path::PathBuf>,
+    /// 等待下载完成或进程退出时显示的状态，没有等待时为空
+    trigger_wait: Option<String>,
+    /// 进程退出触发设置，为空时不显示
+    process_watch: Option<ProcessWatchInput>,
+    /// 是否显示每周计划编辑器
+    show_weekly_editor: bool,
+    /// 每周计划编辑器输入
+    weekly_editor: Vec<WeeklySlotInput>,
+    /// 模板文件路径或网址输入
+    template_source: String,
+    /// 待确认导入的模板预览
+    template_preview: Option<TemplatePreview>,
+    /// 模板导入结果提示
+    template_message: Option<String>,
+    /// 恢复任务备份的结果提示
+    task_backup_message: Option<String>,
+    /// 导出和导入日历文件的结果提示
+    calendar_message: Option<String>,
+    /// 要导入的日历文件路径或网址输入
+    calendar_source: String,
+    /// 要导入的日历事件标题关键字
+    calendar_keyword: String,
+    /// 待确认导入的日历任务
+    calendar_import: Option<CalendarImport>,
+    /// 可以恢复的任务和配置文件备份，从新到旧排列
+    backup_choices: Vec<BackupChoice>,
+    /// 选择要恢复的备份
+    selected_backup: Option<BackupChoice>,
+    /// 要导入的设置包文件路径输入
+    bundle_source: String,
+    /// 导出或导入设置的结果提示
+    bundle_message: Option<String>,
+    /// 当前输入的执行计划预览
+    plan_preview: Option<String>,
+    /// 到点后执行的操作
+    selected_action: TaskAction,
+    /// 计算机级管理策略
+    policy: Policy,
+    /// 取消PIN输入
+    cancel_pin_input: String,
+    /// 倒计时状态查询句柄
+    status_handle: Option<StatusHandle>,
+    /// 通知管理器，负责按操作播放提醒声音
+    notification_manager: NotificationManager,
+    /// 屏幕阅读器剩余时间播报器
+    announcer: ScreenReaderAnnouncer,
+    /// 最后几秒显示的全屏警告窗口
+    warning_overlay: WarningOverlay,
+    /// 最后警告期间开启的专注助手
+    focus_assist: FocusAssistGuard,
+    /// 日落后自动深色的时间安排，为空表示手动选择主题
+    auto_theme: Option<ThemeSchedule>,
+    /// 自定义命令输入
+    custom_command_input: CustomCommandInput,
+    /// CPU占用条件输入
+    cpu_threshold_input: CpuThresholdInput,
+    /// 日志查看器
+    log_viewer: LogViewerInput,
+    /// 推迟执行的进程列表输入
+    blocking_processes_input: BlockingProcessesInput,
+    /// 提醒声音文件输入
+    sound_files_input: SoundFilesInput,
+    /// 使用电池供电时的处理设置，没有电池时为空，不显示相关设置
+    battery_settings: Option<BatterySettings>,
+    /// 登录任务输入，打开设置时从任务列表读取
+    logon_routine_input: LogonRoutineInput,
+    /// 界面字体输入
+    font_family_input: FontFamilyInput,
+    /// 主窗口句柄
+    window_handle: Option<isize>,
+    /// 窗口最后一次移动的时间，用于在移动结束后保存位置
+    last_window_move: Option<std::time::Instant>,
+    /// 任务列表查询句柄
+    task_list: Option<TaskListHandle>,
+    /// 是否显示任务列表
+    show_task_list: bool,
+    /// 远程桌面会话中等待确认后开始的任务消息及其会结束远程连接的操作
+    pending_remote_start: Option<(Message, TaskAction)>,
+    /// 用户已确认在远程桌面会话中开始任务
+    remote_start_confirmed: bool,
+    /// 核心请求确认的短倒计时的秒数和操作
+    pending_short_start: Option<(i64, TaskAction)>,
+    /// 当前倒计时是短倒计时，开始后直接进入最后确认阶段
+    immediate_final_warning: bool,
+    /// 倒计时是否已暂停
+    countdown_paused: bool,
+    /// 任务编辑器，为空时不显示
+    task_editor: Option<TaskEditor>,
+    /// 最近一次自检报告，为空时不显示
+    self_test_report: Option<SelfTestReport>,
+    /// 窗口标题，倒计时运行时包含剩余时间
+    window_title: String,
+    /// 取消倒计时后是否询问原因
+    ask_cancel_reason: bool,
+    /// 到点前的提醒时间，用于执行计划预览
+    warning_offsets: Vec<chrono::Duration>,
+    /// 是否正在显示取消原因选项
+    show_cancel_reason: bool,
+    /// 第一次提醒时显示的改期选项，为空时不显示
+    reschedule_choices: Vec<RescheduleChoice>,
+    /// 本次倒计时是否已收到过提醒
+    first_warning_seen: bool,
+    /// 是否使用大按钮模式
+    large_buttons: bool,
+    /// 是否同步到Windows任务计划程序
+    system_tasks: bool,
+    /// 是否开机后在后台运行
+    auto_start: bool,
+    /// 开机自动启动的实际登记状态或登记失败的提示
+    autostart_message: Option<String>,
+    /// 大按钮模式滑块选择的分钟数
+    large_slider_minutes: u32,
+    /// 远程关机输入
+    remote_input: RemoteInput,
+    /// 外部通知输入
+    webhook_input: WebhookInput,
+    /// 设置窗口当前的分页
+    settings_tab: SettingsTab,
+    /// 设置窗口中直接对应配置文件字段的选项
+    config_form: ConfigForm,
+    /// 设置窗口"历史"分页显示的记录说明，最新的在前，加载失败时为错误信息
+    history_lines: Vec<String>,
 }
 
 impl UIManager {
@@ -101,24 +1365,15 @@ impl UIManager {
         info!("初始化UI管理器...");
         
         // 创建托盘管理器
-        let tray_manager = if let Some(sender) = &ui_event_sender {
-            let mut tray_manager_instance = TrayManager::new(sender.clone());
-            match tray_manager_instance.initialize() {
-                Ok(_) => {
-                    info!("托盘图标创建成功");
-                    Some(tray_manager_instance)
-                },
-                Err(e) => {
-                    error!("创建托盘图标失败: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
+        let tray_manager = Self::create_tray_manager(&ui_event_sender);
         
-        Ok(Self {
+        let (task_persistence, profile, profile_names) = Self::load_profile_state();
+        let crash_report = task_persistence.as_ref().and_then(|persistence| crash::take_pending_report(persistence.get_data_dir()));
+        
+        let mut ui_manager = Self {
             time_input: String::new(),
+            time_suggestions: Vec::new(),
+            suggestion_index: None,
             countdown_status: CountdownStatus::Idle,
             time_parser,
             tray_manager,
@@ -127,66 +1382,1163 @@ impl UIManager {
             theme: Theme::default(),
             is_dark_theme: false,
             minimized_to_tray: false,
+            background_agent: false,
+            minimize_on_close: ConfigManager::load_minimize_on_close(),
             show_settings: false,
             show_about: false,
-        })
+            task_persistence,
+            profile,
+            profile_names,
+            new_profile_name: String::new(),
+            power_requirements: Vec::new(),
+            shutdown_blocker: None,
+            show_external_shutdown_notice: false,
+            crash_report,
+            trigger_wait: None,
+            process_watch: None,
+            show_weekly_editor: false,
+            weekly_editor: default_weekly_editor(),
+            template_source: String::new(),
+            template_preview: None,
+            template_message: None,
+            task_backup_message: None,
+            calendar_message: None,
+            calendar_source: String::new(),
+            calendar_keyword: ics::DEFAULT_KEYWORD.to_string(),
+            calendar_import: None,
+            backup_choices: Vec::new(),
+            selected_backup: None,
+            bundle_source: String::new(),
+            bundle_message: None,
+            plan_preview: None,
+            selected_action: TaskAction::default(),
+            policy: Policy::load(),
+            cancel_pin_input: String::new(),
+            status_handle: None,
+            notification_manager: Self::create_notification_manager(),
+            announcer: Self::create_announcer(),
+            ask_cancel_reason: Self::load_ask_cancel_reason(),
+            warning_offsets: ConfigManager::load_warning_offsets(),
+            show_cancel_reason: false,
+            reschedule_choices: Vec::new(),
+            first_warning_seen: false,
+            large_buttons: Self::load_large_buttons(),
+            system_tasks: ConfigManager::load_system_tasks(),
+            auto_start: ConfigManager::load_auto_start().unwrap_or(false),
+            autostart_message: None,
+            large_slider_minutes: DEFAULT_LARGE_SLIDER_MINUTES,
+            remote_input: RemoteInput::from_settings(&ConfigManager::load_remote()),
+            webhook_input: WebhookInput::from_url(&ConfigManager::load_webhook().url),
+            settings_tab: SettingsTab::default(),
+            config_form: Self::load_config_form(),
+            history_lines: Vec::new(),
+            warning_overlay: Self::create_warning_overlay(),
+            focus_assist: Self::create_focus_assist(),
+            auto_theme: Self::load_auto_theme(),
+            custom_command_input: Self::load_custom_command_input(),
+            cpu_threshold_input: CpuThresholdInput::from_conditions(&ConfigManager::load_conditions()),
+            log_viewer: LogViewerInput::default(),
+            blocking_processes_input: BlockingProcessesInput::from_conditions(&ConfigManager::load_conditions()),
+            sound_files_input: SoundFilesInput::from_settings(&ConfigManager::load_sounds()),
+            battery_settings: Self::load_battery_settings(),
+            logon_routine_input: LogonRoutineInput::default(),
+            font_family_input: FontFamilyInput::from_family(&ConfigManager::load_font_family()),
+            window_handle: None,
+            last_window_move: None,
+            task_list: None,
+            show_task_list: false,
+            pending_remote_start: None,
+            remote_start_confirmed: false,
+            pending_short_start: None,
+            immediate_final_warning: false,
+            countdown_paused: false,
+            task_editor: None,
+            self_test_report: None,
+            window_title: DEFAULT_WINDOW_TITLE.to_string(),
+        };
+        ui_manager.sync_auto_theme();
+        ui_manager.sync_tray_presets();
+        
+        Ok(ui_manager)
     }
     
-    /// 处理倒计时更新
+    /// 创建并初始化托盘管理器
     /// 
     /// # 参数
     /// 
-    /// * `update` - 倒计时更新消息
-    fn handle_countdown_update(&mut self, update: CountdownUpdate) {
-        info!("收到倒计时更新: {:?}", update);
-        match update {
-            CountdownUpdate::Progress { remaining, progress: _ } => {
-                info!("倒计时进度更新 - 剩余时间: {:?}", remaining);
-                self.countdown_status = CountdownStatus::Running { remaining };
-            },
-            CountdownUpdate::Finished => {
-                info!("倒计时完成");
-                self.countdown_status = CountdownStatus::Finished;
-                self.show_shutdown_notification();
+    /// * `ui_event_sender` - UI事件发送器
+    /// 
+    /// # 返回值
+    /// 
+    /// 创建成功返回托盘管理器，没有事件发送器、当前平台不支持托盘或创建失败时返回None
+    fn create_tray_manager(ui_event_sender: &Option<mpsc::UnboundedSender<UIEvent>>) -> Option<TrayManager> {
+        let sender = ui_event_sender.as_ref()?;
+        if !platform::capabilities().system_tray {
+            info!("当前平台不支持托盘图标");
+            return None;
+        }
+        let mut tray_manager_instance = TrayManager::new(sender.clone());
+        tray_manager_instance.set_attention_minutes(Self::load_tray_attention_minutes());
+        tray_manager_instance.set_postpone_allowed(!Policy::load().requires_pin());
+        match tray_manager_instance.initialize() {
+            Ok(_) => {
+                info!("托盘图标创建成功");
+                Some(tray_manager_instance)
             },
-            CountdownUpdate::Cancelled => {
-                info!("倒计时被取消");
-                self.countdown_status = CountdownStatus::Cancelled;
+            Err(e) => {
+                error!("创建托盘图标失败: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// 创建通知管理器
+    /// 
+    /// 使用配置中各操作的提醒声音方案、声音文件和合并重复通知的时间窗口，配置加载失败时使用默认设置
+    fn create_notification_manager() -> NotificationManager {
+        let mut notification_manager = NotificationManager::new(5, 20);
+        match ConfigManager::new() {
+            Ok(config_manager) => {
+                let config = config_manager.get_config();
+                notification_manager.set_sound_profiles(&config.sounds.profiles);
+                notification_manager.set_early_warning_sound(config.sounds.early_warnings);
+                notification_manager.set_sound_files(&config.sounds.files, config.sounds.volume);
+                notification_manager.set_dedup_window(config.ui.notification_dedup_seconds);
             },
-            CountdownUpdate::Error(msg) => {
-                error!("倒计时错误: {}", msg);
-                self.countdown_status = CountdownStatus::Error(msg.clone());
+            Err(e) => error!("加载提醒声音设置失败: {}", e),
+        }
+        notification_manager
+    }
+    
+    /// 创建屏幕阅读器播报器
+    /// 
+    /// 播报间隔来自界面设置，默认不播报，当前平台不支持屏幕阅读器通知时不播报
+    fn create_announcer() -> ScreenReaderAnnouncer {
+        if !platform::capabilities().screen_reader {
+            return ScreenReaderAnnouncer::new(0);
+        }
+        match ConfigManager::new() {
+            Ok(config_manager) => ScreenReaderAnnouncer::new(config_manager.get_config().ui.announce_interval_minutes),
+            Err(e) => {
+                error!("加载屏幕阅读器播报设置失败: {}", e);
+                ScreenReaderAnnouncer::new(0)
+            }
+        }
+    }
+    
+    /// 加载取消后是否询问原因的设置
+    fn load_ask_cancel_reason() -> bool {
+        match ConfigManager::new() {
+            Ok(config_manager) => config_manager.get_config().ui.ask_cancel_reason,
+            Err(e) => {
+                error!("加载取消原因设置失败: {}", e);
+                false
+            }
+        }
+    }
+    
+    /// 加载设置窗口中的选项，配置加载失败时使用默认配置
+    fn load_config_form() -> ConfigForm {
+        match ConfigManager::new() {
+            Ok(config_manager) => ConfigForm::from_config(config_manager.get_config()),
+            Err(e) => {
+                error!("加载设置失败: {}", e);
+                ConfigForm::from_config(&AppConfig::default())
+            }
+        }
+    }
+    
+    /// 加载"历史"分页显示的记录说明
+    /// 
+    /// 只显示最近的记录，最新的在前
+    fn load_history_lines(&self) -> Vec<String> {
+        let Some(persistence) = &self.task_persistence else {
+            return vec!["存储不可用，无法查看历史记录".to_string()];
+        };
+        let history = HistoryLog::new(storage::open(persistence.clone(), ConfigManager::load_storage_backend()));
+        let now = chrono::Local::now();
+        match history.between(now - chrono::Duration::days(HISTORY_VIEW_DAYS), now + chrono::Duration::seconds(1)) {
+            Ok(entries) if entries.is_empty() => vec!["还没有记录".to_string()],
+            Ok(entries) => entries.iter().rev().take(HISTORY_VIEW_ENTRIES).map(|entry| entry.summary()).collect(),
+            Err(e) => {
+                error!("加载历史记录失败: {}", e);
+                vec![format!("加载历史记录失败: {}", e)]
+            }
+        }
+    }
+    
+    /// "重复上次"将要执行的操作
+    /// 
+    /// 从历史记录中取出上次的倒计时，没有记录或读取失败时按关机处理
+    fn last_repeat_action(&self) -> TaskAction {
+        let Some(persistence) = &self.task_persistence else {
+            return TaskAction::Shutdown;
+        };
+        let history = HistoryLog::new(storage::open(persistence.clone(), ConfigManager::load_storage_backend()));
+        match history.last_repeatable() {
+            Ok(repeat) => repeat.map(|repeat| repeat.action).unwrap_or_default(),
+            Err(e) => {
+                warn!("读取上次的倒计时失败: {}", e);
+                TaskAction::Shutdown
+            }
+        }
+    }
+    
+    /// 按保存后的配置更新正在运行的界面
+    /// 
+    /// 日志级别、录制事件和倒计时使用的提醒时间在启动时读取，下次启动时生效
+    fn apply_config(&mut self, config: &AppConfig) {
+        self.minimize_on_close = config.app.minimize_on_close;
+        self.ask_cancel_reason = config.ui.ask_cancel_reason;
+        self.announcer = ScreenReaderAnnouncer::new(config.ui.announce_interval_minutes);
+        self.warning_overlay.set_seconds(config.ui.fullscreen_warning_seconds);
+        self.warning_offsets = parse_warning_offsets(&config.shutdown.warning_offsets).unwrap_or_default();
+        if let Some(tray) = &mut self.tray_manager {
+            tray.set_attention_minutes(config.ui.tray_attention_minutes);
+        }
+        if let Some(persistence) = &mut self.task_persistence {
+            let max_backups = if config.advanced.backup_data { config.advanced.max_backup_files as usize } else { 0 };
+            persistence.set_max_backups(max_backups);
+            persistence.set_encrypt_data(config.advanced.encrypt_data);
+        }
+    }
+    
+    /// 加载托盘图标开始闪烁的剩余分钟数
+    fn load_tray_attention_minutes() -> u32 {
+        match ConfigManager::new() {
+            Ok(config_manager) => config_manager.get_config().ui.tray_attention_minutes,
+            Err(e) => {
+                error!("加载托盘提醒设置失败: {}", e);
+                UISettings::default().tray_attention_minutes
+            }
+        }
+    }
+    
+    /// 加载是否使用大按钮模式
+    fn load_large_buttons() -> bool {
+        match ConfigManager::new() {
+            Ok(config_manager) => config_manager.get_config().ui.large_buttons,
+            Err(e) => {
+                error!("加载大按钮模式设置失败: {}", e);
+                false
+            }
+        }
+    }
+    
+    /// 创建全屏最后警告
+    /// 
+    /// 显示时长来自配置中的`ui.fullscreen_warning_seconds`，配置加载失败时使用默认值
+    fn create_warning_overlay() -> WarningOverlay {
+        match ConfigManager::new() {
+            Ok(config_manager) => WarningOverlay::new(config_manager.get_config().ui.fullscreen_warning_seconds),
+            Err(e) => {
+                error!("加载全屏警告设置失败: {}", e);
+                WarningOverlay::new(FINAL_WARNING_SECONDS as u32)
+            }
+        }
+    }
+    
+    /// 创建最后警告期间的专注助手管理器
+    /// 
+    /// 是否开启来自配置中的`ui.focus_assist_in_final_warning`，配置加载失败时不开启
+    fn create_focus_assist() -> FocusAssistGuard {
+        match ConfigManager::new() {
+            Ok(config_manager) => FocusAssistGuard::new(config_manager.get_config().ui.focus_assist_in_final_warning),
+            Err(e) => {
+                error!("加载专注助手设置失败: {}", e);
+                FocusAssistGuard::new(false)
+            }
+        }
+    }
+    
+    /// 加载使用电池供电时的处理设置
+    /// 
+    /// 检测不到电池（如台式机）时返回None
+    fn load_battery_settings() -> Option<BatterySettings> {
+        system::get_power_status().map(|_| ConfigManager::load_battery())
+    }
+    
+    /// 保存使用电池供电时的处理设置
+    fn save_battery_settings(settings: &BatterySettings) {
+        let result = ConfigManager::new().and_then(|mut config_manager| {
+            config_manager.get_config_mut().shutdown.battery = settings.clone();
+            config_manager.save_config()
+        });
+        if let Err(e) = result {
+            error!("保存电池设置失败: {}", e);
+        }
+    }
+    
+    /// 加载日落后自动深色设置
+    fn load_auto_theme() -> Option<ThemeSchedule> {
+        match ConfigManager::new() {
+            Ok(config_manager) => {
+                let ui = &config_manager.get_config().ui;
+                (ui.theme_type == ThemeType::TimeOfDay).then(|| ThemeSchedule::new(ui.auto_dark_hour))
+            },
+            Err(e) => {
+                error!("加载主题设置失败: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// 加载设置中的自定义命令
+    fn load_custom_command_input() -> CustomCommandInput {
+        match ConfigManager::new() {
+            Ok(config_manager) => CustomCommandInput::from_command(&config_manager.get_config().shutdown.custom_command),
+            Err(e) => {
+                error!("加载自定义命令设置失败: {}", e);
+                CustomCommandInput::default()
+            }
+        }
+    }
+    
+    /// 恢复上次保存的窗口位置
+    /// 
+    /// 只在保存时所在的显示器仍然连接时恢复
+    fn restore_window_placement(&self) {
+        let Some(window_handle) = self.window_handle else {
+            return;
+        };
+        let config_manager = match ConfigManager::new() {
+            Ok(config_manager) => config_manager,
+            Err(e) => {
+                error!("加载窗口位置失败: {}", e);
+                return;
+            }
+        };
+        
+        let ui = &config_manager.get_config().ui;
+        if let (Some(monitor_name), Some((x, y))) = (&ui.window_monitor, ui.window_position) {
+            let position = (x as i32, y as i32);
+            match placement::restore_position(monitor_name, position, &placement::list_monitors()) {
+                Some(position) => {
+                    info!("恢复窗口位置: {} {:?}", monitor_name, position);
+                    placement::move_window(window_handle, position);
+                },
+                None => info!("上次所在的显示器 {} 不可用，使用默认窗口位置", monitor_name),
+            }
+        }
+    }
+    
+    /// 保存当前窗口位置和所在显示器
+    fn save_window_placement(&self) {
+        let Some((monitor, rect)) = self.window_handle.and_then(placement::window_placement) else {
+            return;
+        };
+        
+        let result = ConfigManager::new().and_then(|mut config_manager| {
+            let ui = &mut config_manager.get_config_mut().ui;
+            ui.window_monitor = Some(monitor.name.clone());
+            ui.window_position = Some((rect.left as f32, rect.top as f32));
+            config_manager.save_config()
+        });
+        if let Err(e) = result {
+            error!("保存窗口位置失败: {}", e);
+        }
+    }
+    
+    /// 按当前时间应用自动主题
+    /// 
+    /// 未开启日落后自动深色时不做任何改变
+    fn sync_auto_theme(&mut self) {
+        let Some(schedule) = self.auto_theme else {
+            return;
+        };
+        
+        let is_dark = schedule.is_dark_at(chrono::Local::now().naive_local());
+        if is_dark != self.is_dark_theme {
+            info!("自动切换到{}主题", if is_dark { "深色" } else { "浅色" });
+            self.set_dark_theme(is_dark);
+        }
+    }
+    
+    /// 设置深色或浅色主题
+    /// 
+    /// # 参数
+    /// 
+    /// * `is_dark` - 是否使用深色主题
+    fn set_dark_theme(&mut self, is_dark: bool) {
+        self.is_dark_theme = is_dark;
+        self.theme = if is_dark {
+            Theme::dark_theme()
+        } else {
+            Theme::light_theme()
+        };
+    }
+    
+    /// 保存主题类型到配置文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `theme_type` - 主题类型
+    fn save_theme_type(theme_type: ThemeType) {
+        let result = ConfigManager::new().and_then(|mut config_manager| {
+            config_manager.get_config_mut().ui.theme_type = theme_type;
+            config_manager.save_config()
+        });
+        if let Err(e) = result {
+            error!("保存主题设置失败: {}", e);
+        }
+    }
+    
+    /// 加载可以恢复的备份，任务备份和配置文件备份各自从新到旧排列
+    fn load_backup_choices(&self) -> Vec<BackupChoice> {
+        let tasks = self.task_persistence.as_ref()
+            .map(|persistence| storage::open(persistence.clone(), ConfigManager::load_storage_backend()).task_backups())
+            .unwrap_or_default();
+        let configs = match ConfigManager::new() {
+            Ok(config_manager) => config_manager.list_backups(),
+            Err(e) => {
+                warn!("加载配置文件备份失败: {}", e);
+                Vec::new()
+            }
+        };
+        tasks.into_iter().map(BackupChoice::Tasks)
+            .chain(configs.into_iter().map(|(path, at)| BackupChoice::Config(path, at)))
+            .collect()
+    }
+    
+    /// 恢复选择的备份
+    /// 
+    /// 任务备份交给核心替换当前任务，配置文件备份直接写入并更新界面
+    fn restore_backup(&mut self, choice: &BackupChoice) -> Result<()> {
+        match choice {
+            BackupChoice::Tasks(backup) => {
+                let persistence = self.task_persistence.clone().ok_or_else(|| anyhow!("存储不可用"))?;
+                let tasks = storage::open(persistence, ConfigManager::load_storage_backend()).load_task_backup(&backup.id)?;
+                self.send_ui_event(UIEvent::ImportTasks(tasks));
+            },
+            BackupChoice::Config(path, _) => {
+                let mut config_manager = ConfigManager::new().map_err(|e| anyhow!("加载配置失败: {}", e))?;
+                config_manager.restore_backup(path).map_err(|e| anyhow!("{}", e))?;
+                let config = config_manager.get_config().clone();
+                self.apply_config(&config);
+                self.config_form = ConfigForm::from_config(&config);
+            },
+        }
+        Ok(())
+    }
+    
+    /// 将任务、配置和配置方案导出为设置包
+    /// 
+    /// 把日历中的任务合并到当前任务中，交给核心保存
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回显示给用户的导入结果
+    fn import_calendar(&self, import: &CalendarImport) -> Result<String> {
+        let tasks = match (&self.task_list, &self.task_persistence) {
+            (Some(handle), _) => handle.tasks(),
+            (None, Some(persistence)) => storage::open(persistence.clone(), ConfigManager::load_storage_backend()).load_tasks()?,
+            (None, None) => return Err(anyhow!("存储不可用")),
+        };
+        let (tasks, added, updated) = import.merge(&tasks);
+        self.send_ui_event(UIEvent::ImportTasks(tasks));
+        info!("已导入日历: 新增{}个任务，更新{}个任务", added, updated);
+        Ok(format!("已从日历新增{}个任务、更新{}个任务", added, updated))
+    }
+    
+    /// 优先保存到桌面，方便复制到新电脑
+    /// 
+    /// # 返回值
+    /// 
+    /// 设置包文件路径
+    fn export_settings(&self) -> Result<std::path::PathBuf> {
+        let persistence = self.task_persistence.as_ref().ok_or_else(|| anyhow!("存储不可用"))?;
+        let tasks = match &self.task_list {
+            Some(handle) => handle.tasks(),
+            None => storage::open(persistence.clone(), ConfigManager::load_storage_backend()).load_tasks()?,
+        };
+        let config = ConfigManager::new().map_err(|e| anyhow!("加载配置失败: {}", e))?.get_config().clone();
+        let profiles = persistence.list_profiles()?.iter()
+            .map(|name| persistence.load_profile(name))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let bundle = SettingsBundle::new(&tasks, &config, profiles, &webhook::computer_name(), chrono::Local::now())?;
+        let dir = dirs::desktop_dir().unwrap_or_else(|| persistence.get_data_dir().to_path_buf());
+        bundle.write_to(&dir)
+    }
+    
+    /// 导入设置包
+    /// 
+    /// 配置和配置方案直接写入，任务交给核心替换当前任务
+    /// 
+    /// # 返回值
+    /// 
+    /// 导入结果提示
+    fn import_settings(&mut self) -> Result<String> {
+        let source = self.bundle_source.trim().to_string();
+        if source.is_empty() {
+            return Err(anyhow!("请输入设置包文件路径"));
+        }
+        let persistence = self.task_persistence.clone().ok_or_else(|| anyhow!("存储不可用"))?;
+        let bundle = SettingsBundle::read_from(std::path::Path::new(&source))?;
+        let tasks = bundle.tasks()?;
+        let config = bundle.config()?;
+        
+        for profile in &bundle.profiles {
+            persistence.save_profile(profile)?;
+            if !self.profile_names.contains(&profile.name) {
+                self.profile_names.push(profile.name.clone());
+            }
+            if profile.name == self.profile.name {
+                self.profile = profile.clone();
+                self.sync_tray_presets();
+            }
+        }
+        
+        let mut config_manager = ConfigManager::new().map_err(|e| anyhow!("加载配置失败: {}", e))?;
+        *config_manager.get_config_mut() = config.clone();
+        config_manager.save_config().map_err(|e| anyhow!("保存配置失败: {}", e))?;
+        self.apply_config(&config);
+        self.config_form = ConfigForm::from_config(&config);
+        
+        let task_count = tasks.len();
+        self.send_ui_event(UIEvent::ImportTasks(tasks));
+        info!("已导入设置包: {}", source);
+        Ok(format!(
+            "已导入\"{}\"的{}个任务、{}个配置方案和设置，日志级别、录制事件、数据加密和倒计时的提醒时间在下次启动时生效",
+            bundle.computer, task_count, bundle.profiles.len(),
+        ))
+    }
+    
+    /// 加载配置方案状态
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回持久化管理器、当前配置方案和所有配置方案名称，持久化不可用时使用默认方案
+    fn load_profile_state() -> (Option<TaskPersistence>, ProfileData, Vec<String>) {
+        let task_persistence = match TaskPersistence::new() {
+            Ok(mut persistence) => {
+                persistence.set_max_backups(ConfigManager::load_max_backup_files());
+                persistence.set_encrypt_data(ConfigManager::load_encrypt_data());
+                Some(persistence)
+            },
+            Err(e) => {
+                error!("初始化配置方案存储失败: {}", e);
+                None
+            }
+        };
+        
+        let profile = task_persistence.as_ref()
+            .and_then(|persistence| persistence.load_active_profile()
+                .map_err(|e| error!("加载配置方案失败: {}", e))
+                .ok())
+            .unwrap_or_default();
+        
+        let profile_names = task_persistence.as_ref()
+            .and_then(|persistence| persistence.list_profiles().ok())
+            .unwrap_or_else(|| vec![profile.name.clone()]);
+        
+        (task_persistence, profile, profile_names)
+    }
+    
+    /// 保存当前配置方案
+    fn save_profile(&self) {
+        if let Some(persistence) = &self.task_persistence {
+            if let Err(e) = persistence.save_profile(&self.profile) {
+                error!("保存配置方案失败: {}", e);
+            }
+        }
+    }
+    
+    /// 按当前配置方案更新托盘菜单中的快速倒计时预设
+    fn sync_tray_presets(&mut self) {
+        if let Some(tray_manager) = &mut self.tray_manager {
+            tray_manager.set_quick_presets(&self.profile.quick_durations(), &self.profile.presets);
+        }
+    }
+    
+    /// 切换到指定配置方案
+    /// 
+    /// 先保存当前方案，再加载目标方案并重置与方案相关的界面状态
+    /// 
+    /// # 参数
+    /// 
+    /// * `name` - 目标配置方案名称
+    fn switch_profile(&mut self, name: &str) {
+        if name == self.profile.name {
+            return;
+        }
+        
+        self.save_profile();
+        
+        let profile = match &self.task_persistence {
+            Some(persistence) => match persistence.load_profile(name) {
+                Ok(profile) => profile,
+                Err(e) => {
+                    error!("加载配置方案失败: {}", e);
+                    self.countdown_status = CountdownStatus::Error(format!("加载配置方案失败: {}", e));
+                    return;
+                }
+            },
+            None => ProfileData::new(name),
+        };
+        
+        if let Some(persistence) = &self.task_persistence {
+            if let Err(e) = persistence.set_active_profile(name) {
+                error!("记录当前配置方案失败: {}", e);
+            }
+        }
+        
+        info!("已切换到配置方案: {}", name);
+        self.profile = profile;
+        self.sync_tray_presets();
+        self.time_input.clear();
+        self.new_profile_name.clear();
+        
+        if !self.profile_names.iter().any(|item| item == name) {
+            self.profile_names.push(name.to_string());
+        }
+    }
+    
+    /// 处理倒计时更新
+    /// 
+    /// # 参数
+    /// 
+    /// * `update` - 倒计时更新消息
+    fn handle_countdown_update(&mut self, update: CountdownUpdate) {
+        info!("收到倒计时更新: {:?}", update);
+        let status_before = self.countdown_status.clone();
+        let was_final_warning = self.is_final_warning();
+        // 下载完成或进程退出后开始倒计时、取消或出错时不再显示等待状态
+        if matches!(update, CountdownUpdate::Progress { .. } | CountdownUpdate::Cancelled | CountdownUpdate::Error(_)) {
+            self.trigger_wait = None;
+        }
+        match update {
+            CountdownUpdate::Progress { remaining, progress: _ } => {
+                info!("倒计时进度更新 - 剩余时间: {:?}", remaining);
+                self.countdown_status = CountdownStatus::Running { remaining };
+                if let Some(text) = self.announcer.due_announcement(remaining) {
+                    if let Err(e) = self.announcer.announce(&text) {
+                        error!("屏幕阅读器播报失败: {}", e);
+                    }
+                }
+            },
+            CountdownUpdate::Warning { remaining } => {
+                self.announce_warning(remaining);
+                // 只在第一次提醒时询问是否改期，管理策略要求PIN时不能改期
+                if !self.first_warning_seen && self.can_postpone() {
+                    self.first_warning_seen = true;
+                    let now = chrono::Local::now();
+                    self.reschedule_choices = RescheduleChoice::available(now + remaining, now);
+                }
+            },
+            CountdownUpdate::Finished => {
+                info!("倒计时完成");
+                self.countdown_status = CountdownStatus::Finished;
+                self.power_requirements.clear();
+                self.announcer.reset();
+                self.warning_overlay.set_paused(false);
+                self.countdown_paused = false;
+                self.reset_reschedule_prompt();
+                self.show_shutdown_notification();
+            },
+            CountdownUpdate::Cancelled => {
+                info!("倒计时被取消");
+                self.countdown_status = CountdownStatus::Cancelled;
+                self.power_requirements.clear();
+                self.announcer.reset();
+                self.warning_overlay.set_paused(false);
+                self.countdown_paused = false;
+                self.reset_reschedule_prompt();
+            },
+            CountdownUpdate::Error(msg) => {
+                error!("倒计时错误: {}", msg);
+                self.countdown_status = CountdownStatus::Error(msg.clone());
+                self.countdown_paused = false;
                 self.show_error_notification(&msg);
             },
             CountdownUpdate::Paused => {
                 info!("倒计时已暂停");
+                self.countdown_paused = true;
+                self.warning_overlay.set_paused(true);
+                if let Some(tray) = &mut self.tray_manager {
+                    tray.set_paused(true);
+                }
             },
             CountdownUpdate::Resumed => {
                 info!("倒计时已恢复");
+                self.countdown_paused = false;
+                self.warning_overlay.set_paused(false);
+                if let Some(tray) = &mut self.tray_manager {
+                    tray.set_paused(false);
+                }
+            },
+            CountdownUpdate::TaskCompleted { task_info: _ } => {
+                info!("任务已完成");
+                self.power_requirements.clear();
+            },
+            CountdownUpdate::ExternalShutdownDetected => {
+                info!("检测到外部关机计划，询问用户保留还是取消");
+                self.show_external_shutdown_notice = true;
+            },
+            CountdownUpdate::NetworkWaiting { rate_kbps, quiet_seconds } => {
+                self.trigger_wait = Some(Self::format_network_wait(rate_kbps, quiet_seconds));
+            },
+            CountdownUpdate::Postponed { reason, retry_after } => {
+                info!("执行条件不满足，已推迟: {}", reason);
+                let content = format!("{}，{}推迟{}分钟", reason, self.current_action(), retry_after.num_minutes());
+                self.notify(NotificationBuilder::warning("QtShut - 已推迟", content));
+            },
+            CountdownUpdate::Notice { title, content } => {
+                info!("显示提示: {}", content);
+                self.notify(NotificationBuilder::info(title, content));
+            },
+            CountdownUpdate::WindowRequested(request) => {
+                // 需要返回窗口命令，由handle_window_request处理
+                warn!("忽略主窗口请求: {:?}", request);
+            }
+        }
+        
+        // 同步托盘状态
+        if self.countdown_status != status_before {
+            if let Some(tray) = &mut self.tray_manager {
+                tray.update_status(self.countdown_status.clone());
+            }
+        }
+        
+        // 托盘提示和窗口标题使用与其他查询方式相同的状态快照
+        if let Some(snapshot) = self.status_handle.as_ref().and_then(|handle| handle.try_snapshot()) {
+            if let Some(tray) = &mut self.tray_manager {
+                let upcoming = self.task_list.as_ref()
+                    .map(|handle| handle.upcoming(chrono::Local::now(), scheduler::UPCOMING_LIMIT))
+                    .unwrap_or_default();
+                tray.update_tooltip(&snapshot, &upcoming);
+            }
+            self.window_title = Self::format_window_title(&snapshot);
+        }
+        
+        if !was_final_warning && self.is_final_warning() {
+            self.announce_final_warning();
+        }
+        
+        self.sync_shutdown_block();
+    }
+    
+    /// 生成等待下载完成时的状态文字
+    /// 
+    /// # 参数
+    /// 
+    /// * `rate_kbps` - 当前网速（KB/s）
+    /// * `quiet_seconds` - 网速已持续低于设置值的秒数
+    fn format_network_wait(rate_kbps: f64, quiet_seconds: u64) -> String {
+        if quiet_seconds == 0 {
+            format!("等待下载完成：当前网速 {:.0} KB/s", rate_kbps)
+        } else {
+            format!("等待下载完成：当前网速 {:.0} KB/s，已持续低速 {}:{:02}", rate_kbps, quiet_seconds / 60, quiet_seconds % 60)
+        }
+    }
+    
+    /// 由状态快照生成窗口标题
+    /// 
+    /// 倒计时运行时在标题后附加按分钟取整的剩余时间（如"QtShut — 剩余 1:05"），
+    /// 任务栏悬停和Alt-Tab中也能看到；标题每分钟才变化一次
+    /// 
+    /// # 参数
+    /// 
+    /// * `snapshot` - 倒计时状态快照
+    fn format_window_title(snapshot: &StatusSnapshot) -> String {
+        let label = match snapshot.state {
+            StatusState::Running => "剩余",
+            StatusState::Paused => "已暂停，剩余",
+            _ => return DEFAULT_WINDOW_TITLE.to_string(),
+        };
+        let minutes = (snapshot.remaining_seconds.unwrap_or(0).max(0) + 59) / 60;
+        format!("QtShut — {} {}:{:02}", label, minutes / 60, minutes % 60)
+    }
+    
+    /// 构建全屏最后警告窗口的界面
+    /// 
+    /// 巨大的剩余秒数和取消按钮，管理策略要求PIN时同时显示PIN输入框
+    fn warning_overlay_view(&self) -> Element<Message> {
+        let action = self.current_action();
+        let remaining_seconds = match &self.countdown_status {
+            CountdownStatus::Running { remaining } => remaining.num_seconds().max(0),
+            _ => 0,
+        };
+        
+        let mut content = column![
+            text(remaining_seconds.to_string()).size(240).style(Color::WHITE),
+            text(format!("{}秒后将{}", remaining_seconds, action)).size(40).style(Color::WHITE),
+            Space::with_height(40),
+        ]
+        .spacing(20)
+        .align_items(iced::Alignment::Center);
+        
+        if self.policy.requires_pin() {
+            content = content.push(
+                text_input("PIN", &self.cancel_pin_input)
+                    .on_input(Message::CancelPinChanged)
+                    .on_submit(Message::CancelCountdown)
+                    .secure(true)
+                    .size(32)
+                    .width(Length::Fixed(240.0))
+            );
+        }
+        
+        content = content.push(
+            button(text(format!("取消{}", action)).size(64))
+                .on_press(Message::CancelCountdown)
+                .padding([30, 80])
+        );
+        
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .style(ContainerAppearance {
+                background: Some(Background::Color(Color::from_rgb8(160, 20, 30))),
+                ..Default::default()
+            })
+            .into()
+    }
+    
+    /// 是否可以推迟、延长倒计时或改期
+    /// 
+    /// 管理策略要求PIN时推迟同样会让策略要求的关机一直不执行，不显示推迟、延长按钮和改期选择
+    fn can_postpone(&self) -> bool {
+        !self.policy.requires_pin()
+    }
+    
+    /// 是否处于关机前的最后确认阶段
+    fn is_final_warning(&self) -> bool {
+        match &self.countdown_status {
+            CountdownStatus::Running { remaining } => {
+                let limit = if self.immediate_final_warning { SHORT_COUNTDOWN_SECONDS } else { FINAL_WARNING_SECONDS };
+                remaining.num_seconds() > 0 && remaining.num_seconds() <= limit
+            },
+            _ => false,
+        }
+    }
+    
+    /// 同步关机阻止原因和专注助手
+    /// 
+    /// 最后确认阶段注册阻止原因，防止其他程序发起的关机与本程序竞争，
+    /// 并按设置开启专注助手；离开该阶段（取消、延长或开始关机）时释放并恢复
+    fn sync_shutdown_block(&mut self) {
+        let is_final_warning = self.is_final_warning();
+        let action = self.current_action();
+        if let Some(blocker) = &mut self.shutdown_blocker {
+            let result = if is_final_warning {
+                blocker.block(&format!("QtShut 即将执行定时{}", action))
+            } else {
+                blocker.release()
+            };
+            
+            if let Err(e) = result {
+                error!("同步关机阻止原因失败: {}", e);
+            }
+        }
+        
+        if let Err(e) = self.focus_assist.sync(is_final_warning) {
+            error!("同步专注助手状态失败: {}", e);
+        }
+    }
+    
+    /// 根据编辑器输入生成每周计划时间段
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回设置了操作的时间段，时间格式错误或没有任何时间段时返回错误信息
+    fn collect_weekly_slots(&self) -> Result<Vec<WeeklySlot>, String> {
+        let mut slots = Vec::new();
+        for input in &self.weekly_editor {
+            let action = match input.choice {
+                SlotActionChoice::Skip => continue,
+                SlotActionChoice::Run(action) => action,
+            };
+            
+            let time_text = input.time.trim().replace('：', ":");
+            let time = NaiveTime::parse_from_str(&time_text, "%H:%M")
+                .map_err(|_| format!("{}的时间格式错误，请输入如 23:00", weekday_label(input.weekday)))?;
+            slots.push(WeeklySlot { weekday: input.weekday, time, action });
+        }
+        
+        if slots.is_empty() {
+            return Err("请至少为一天选择要执行的操作".to_string());
+        }
+        Ok(slots)
+    }
+    
+    /// 远程桌面会话中开始关机类任务前要求确认
+    /// 
+    /// 确认后重新处理原来的消息，此时不再检查
+    /// 
+    /// # 参数
+    /// 
+    /// * `actions` - 任务到点后执行的操作
+    /// * `pending` - 确认后重新处理的消息
+    /// 
+    /// # 返回值
+    /// 
+    /// 需要等待用户确认时返回true
+    fn require_remote_confirmation(&mut self, actions: &[TaskAction], pending: Message) -> bool {
+        if std::mem::take(&mut self.remote_start_confirmed) {
+            return false;
+        }
+        let Some(&action) = actions.iter().find(|action| action.ends_remote_access()) else {
+            return false;
+        };
+        if !SystemCompatibility::new().is_remote_session() {
+            return false;
+        }
+        
+        warn!("当前为远程桌面会话，开始{}任务前需要用户确认", action);
+        self.pending_remote_start = Some((pending, action));
+        true
+    }
+    
+    /// 输入的时间距离现在不到一分钟时返回剩余秒数
+    fn short_countdown_seconds(&self) -> Option<i64> {
+        let now = chrono::Local::now();
+        let time_input = self.time_parser.parse(&self.time_input).ok()?;
+        let task_type = time_input.task_type_or(TaskType::Once);
+        let target_time = TaskData::from_time_input(task_type, time_input, self.selected_action, now).ok()?.target_time?;
+        is_short_countdown(target_time, now).then(|| (target_time - now).num_seconds())
+    }
+    
+    /// 远程桌面会话中执行该操作时的警告
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    fn remote_session_warning(action: TaskAction) -> Option<String> {
+        (action.ends_remote_access() && SystemCompatibility::new().is_remote_session())
+            .then(|| system_compat::remote_session_warning(action))
+    }
+    
+    /// 当前任务到点后执行的操作
+    /// 
+    /// 没有正在运行的任务时使用界面上选择的操作
+    fn current_action(&self) -> TaskAction {
+        self.status_handle.as_ref()
+            .and_then(|handle| handle.try_snapshot())
+            .and_then(|snapshot| snapshot.action)
+            .unwrap_or(self.selected_action)
+    }
+    
+    /// 倒计时状态说明
+    fn status_text(&self) -> String {
+        match &self.countdown_status {
+            CountdownStatus::Idle => "等待开始...".to_string(),
+            CountdownStatus::Running { remaining } => {
+                format!("剩余时间: {}小时{}分钟{}秒，到点后{}", 
+                    remaining.num_hours(),
+                    remaining.num_minutes() % 60,
+                    remaining.num_seconds() % 60,
+                    self.current_action()
+                )
+            },
+            CountdownStatus::Finished => "倒计时结束！".to_string(),
+            CountdownStatus::Cancelled => "倒计时已取消".to_string(),
+            CountdownStatus::Error(msg) => format!("错误: {}", msg),
+        }
+    }
+    
+    /// 在后台线程中登记或检查开机自动启动
+    /// 
+    /// reg.exe启动较慢，不在界面线程中等待
+    /// 
+    /// # 参数
+    /// 
+    /// * `task` - 登记或检查操作，返回实际登记状态
+    fn check_autostart<F>(task: F) -> Command<Message>
+    where
+        F: FnOnce() -> Result<AutostartState> + Send + 'static,
+    {
+        Command::perform(
+            async move {
+                match tokio::task::spawn_blocking(task).await {
+                    Ok(result) => result.map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                }
             },
-            CountdownUpdate::TaskCompleted { task_info: _ } => {
-                info!("任务已完成");
-            }
+            Message::AutoStartChecked,
+        )
+    }
+    
+    /// 处理托盘菜单等对主窗口的请求
+    /// 
+    /// 后台代理方式下主窗口启动时不显示，第一次在这里显示
+    /// 
+    /// # 参数
+    /// 
+    /// * `request` - 主窗口请求
+    fn handle_window_request(&mut self, request: WindowRequest) -> Command<Message> {
+        info!("主窗口请求: {:?}", request);
+        let mut commands = Vec::new();
+        match request {
+            WindowRequest::Exit => return self.update(Message::Exit),
+            WindowRequest::ConfirmShortStart { seconds, action } => self.pending_short_start = Some((seconds, action)),
+            WindowRequest::Toggle if !self.minimized_to_tray => return self.hide_main_window(),
+            WindowRequest::Settings if !self.show_settings => commands.push(self.update(Message::ShowSettings)),
+            WindowRequest::About => self.show_about = true,
+            _ => {},
         }
+        commands.push(self.show_main_window());
+        Command::batch(commands)
     }
     
-    /// 显示关机通知
-    fn show_shutdown_notification(&self) {
-        info!("显示关机通知");
+    /// 管理策略要求PIN且策略关机正在倒计时，此时不能退出程序
+    fn policy_shutdown_locked(&self) -> bool {
+        self.policy.requires_pin()
+            && self.status_handle.as_ref()
+                .and_then(|handle| handle.try_snapshot())
+                .is_some_and(|snapshot| snapshot.source == Some(TaskSource::Policy))
+    }
+    
+    /// 显示、还原主窗口并切换到前台
+    fn show_main_window(&mut self) -> Command<Message> {
+        self.minimized_to_tray = false;
+        Command::batch([
+            window::change_mode(window::Id::MAIN, window::Mode::Windowed),
+            window::minimize(window::Id::MAIN, false),
+            window::gain_focus(window::Id::MAIN),
+        ])
+    }
+    
+    /// 隐藏主窗口到托盘
+    /// 
+    /// 有托盘图标或以后台代理方式运行时隐藏窗口，只保留托盘图标，倒计时继续运行，
+    /// 点击托盘图标或托盘菜单中的"显示主窗口"还原；没有托盘图标时最小化到任务栏
+    fn hide_main_window(&mut self) -> Command<Message> {
+        self.minimized_to_tray = true;
+        if self.background_agent || self.tray_manager.is_some() {
+            window::change_mode(window::Id::MAIN, window::Mode::Hidden)
+        } else {
+            window::minimize(window::Id::MAIN, true)
+        }
+    }
+    
+    /// 倒计时结束或取消后不再显示改期选项，下次倒计时重新询问
+    fn reset_reschedule_prompt(&mut self) {
+        self.reschedule_choices.clear();
+        self.first_warning_seen = false;
+        self.set_immediate_final_warning(false);
+    }
+    
+    /// 设置当前倒计时是否直接进入最后确认阶段
+    /// 
+    /// # 参数
+    /// 
+    /// * `immediate` - 是否为短倒计时
+    fn set_immediate_final_warning(&mut self, immediate: bool) {
+        self.immediate_final_warning = immediate;
+        self.warning_overlay.set_immediate(immediate);
+    }
+    
+    /// 发出到点前的提醒
+    /// 
+    /// 剩余时间越少提醒越醒目，设置中关闭提前提醒的提示音时只有最后1分钟内的提醒播放
+    /// 
+    /// # 参数
+    /// 
+    /// * `remaining` - 到达的提醒时间
+    fn announce_warning(&mut self, remaining: chrono::Duration) {
+        let action = self.current_action();
+        let mut notification = NotificationBuilder::action_warning(action, remaining.num_seconds())
+            .with_sound(self.notification_manager.warning_sound(remaining.num_seconds()));
+        notification.content = format!("{}后将{}", format_warning_offset(remaining), action);
+        self.notify(notification);
+    }
+    
+    /// 发出最后确认阶段的提醒
+    /// 
+    /// 提示音和语音由通知管理器按当前任务的操作选择
+    fn announce_final_warning(&mut self) {
+        let action = self.current_action();
+        let remaining_seconds = match &self.countdown_status {
+            CountdownStatus::Running { remaining } => remaining.num_seconds(),
+            _ => FINAL_WARNING_SECONDS,
+        };
+        
+        let mut notification = NotificationBuilder::action_warning(action, remaining_seconds);
+        if self.can_postpone() {
+            notification.content = format!("{}，可在托盘菜单中推迟{}分钟", notification.content, SNOOZE_MINUTES);
+        }
+        if let Some(warning) = Self::remote_session_warning(action) {
+            notification.content = format!("{}\n{}", notification.content, warning);
+        }
+        self.notify(notification);
+    }
+    
+    /// 显示托盘通知
+    /// 
+    /// 时间窗口内的相同通知由通知管理器合并，只在标题上更新合并次数（如"×3"）
+    /// 并替换原来的托盘提示，不再重复播放提示音
+    /// 
+    /// # 参数
+    /// 
+    /// * `notification` - 通知消息
+    fn notify(&mut self, notification: NotificationMessage) {
+        let notification = self.notification_manager.submit(notification);
+        if notification.repeat_count == 1 {
+            self.notification_manager.play_notification_sound(&notification);
+        }
         if let Some(tray) = &self.tray_manager {
-            tray.show_notification("QtShut", "倒计时结束，即将关机");
+            tray.show_notification(&notification.badge_title(), &notification.content);
         }
     }
     
+    /// 显示关机通知
+    fn show_shutdown_notification(&mut self) {
+        info!("显示关机通知");
+        let content = format!("倒计时结束，即将{}", self.current_action());
+        self.notify(NotificationBuilder::info("QtShut", content));
+    }
+    
     /// 显示错误通知
     /// 
     /// # 参数
     /// 
     /// * `message` - 错误消息
-    fn show_error_notification(&self, message: &str) {
+    fn show_error_notification(&mut self, message: &str) {
         error!("显示错误通知: {}", message);
-        if let Some(tray) = &self.tray_manager {
-            tray.show_notification("QtShut - 错误", message);
+        self.notify(NotificationBuilder::error("QtShut - 错误", message).with_sound(false));
+    }
+    
+    /// 在系统因其他原因关机、重启或注销前保存任务状态
+    /// 
+    /// 会话结束消息在窗口线程中处理，发送事件后等待事件循环处理完成再让系统继续
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 主窗口句柄
+    fn watch_session_end(&self, window_handle: isize) {
+        let sender = match &self.ui_event_sender {
+            Some(sender) if platform::capabilities().session_end_notice => sender.clone(),
+            _ => return,
+        };
+        let on_end = move || {
+            let (done, finished) = std::sync::mpsc::channel();
+            if sender.send(UIEvent::SessionEnding(done)).is_ok() && finished.recv_timeout(SESSION_END_WAIT).is_err() {
+                warn!("保存会话结束前的任务状态超时");
+            }
+        };
+        if let Err(e) = platform::native().watch_session_end(window_handle, Box::new(on_end)) {
+            warn!("监听会话结束失败: {}", e);
+        }
+    }
+    
+    /// 系统从睡眠中唤醒后立即重新计算倒计时
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 主窗口句柄
+    fn watch_resume(&self, window_handle: isize) {
+        let sender = match &self.ui_event_sender {
+            Some(sender) if platform::capabilities().resume_notice => sender.clone(),
+            _ => return,
+        };
+        let on_resume = move || {
+            let _ = sender.send(UIEvent::SystemResumed);
+        };
+        if let Err(e) = platform::native().watch_resume(window_handle, Box::new(on_resume)) {
+            warn!("监听系统唤醒失败: {}", e);
         }
     }
     
@@ -211,6 +2563,9 @@ impl UIManager {
 /// * `time_parser` - 时间解析器
 /// * `countdown_receiver` - 倒计时更新接收器
 /// * `ui_event_sender` - UI事件发送器
+/// * `status_handle` - 倒计时状态查询句柄
+/// * `task_list` - 任务列表查询句柄
+/// * `window_mode` - 主窗口的显示方式，后台代理方式下主窗口启动时不显示，关闭时隐藏
 /// 
 /// # 返回值
 /// 
@@ -219,8 +2574,17 @@ pub fn run_with_params(
     time_parser: TimeParser,
     countdown_receiver: Option<broadcast::Receiver<CountdownUpdate>>,
     ui_event_sender: Option<mpsc::UnboundedSender<UIEvent>>,
+    status_handle: Option<StatusHandle>,
+    task_list: Option<TaskListHandle>,
+    window_mode: WindowMode,
 ) -> iced::Result {
-    let flags = (time_parser, countdown_receiver, ui_event_sender);
+    let background_agent = window_mode == WindowMode::TrayOnly;
+    let flags = (time_parser, countdown_receiver, ui_event_sender, status_handle, task_list, window_mode);
+    // 默认字体使用无衬线字体族，设置中切换字体时修改该字体族对应的字体
+    let font_family = ConfigManager::load_font_family();
+    if theme::set_default_font_family(&font_family).is_none() {
+        warn!("没有找到字体{}，使用系统默认字体", font_family);
+    }
     let settings = Settings {
         id: None,
         window: window::Settings {
@@ -228,18 +2592,19 @@ pub fn run_with_params(
             position: window::Position::default(),
             min_size: None,
             max_size: None,
-            visible: true,
+            visible: !background_agent,
             resizable: true,
             decorations: true,
             transparent: false,
             level: window::Level::Normal,
             icon: None,
             platform_specific: Default::default(),
-            exit_on_close_request: true,
+            // 关闭请求由界面处理，按设置隐藏到托盘或退出
+            exit_on_close_request: false,
         },
         flags,
         fonts: vec![],
-        default_font: Font::with_name("Microsoft YaHei"),
+        default_font: Font::DEFAULT,
         default_text_size: iced::Pixels(16.0),
         antialiasing: false,
     };
@@ -250,55 +2615,242 @@ pub fn run_with_params(
 /// 
 /// 启动iced应用程序的主循环
 pub fn run() -> iced::Result {
-    let time_parser = TimeParser::new();
-    run_with_params(time_parser, None, None)
+    let time_parser = TimeParser::configured();
+    run_with_params(time_parser, None, None, None, None, WindowMode::Normal)
 }
 
 impl Application for UIManager {
     type Message = Message;
     type Theme = IcedTheme;
     type Executor = executor::Default;
-    type Flags = (TimeParser, Option<broadcast::Receiver<CountdownUpdate>>, Option<mpsc::UnboundedSender<UIEvent>>);
+    type Flags = (
+        TimeParser,
+        Option<broadcast::Receiver<CountdownUpdate>>,
+        Option<mpsc::UnboundedSender<UIEvent>>,
+        Option<StatusHandle>,
+        Option<TaskListHandle>,
+        WindowMode,
+    );
     
     /// 订阅外部事件
     fn subscription(&self) -> iced::Subscription<Self::Message> {
         // 创建一个定时器来定期检查倒计时状态
-        iced::time::every(std::time::Duration::from_millis(500))
-            .map(|_| Message::CheckCountdownStatus)
+        let countdown_check = iced::time::every(std::time::Duration::from_millis(500))
+            .map(|_| Message::CheckCountdownStatus);
+        
+        // 托盘点击需要及时响应，使用更短的轮询间隔
+        let tray_check = iced::time::every(std::time::Duration::from_millis(100))
+            .map(|_| Message::PollTrayEvents);
+        
+        // Ctrl+方向键将窗口停靠到屏幕边缘
+        let snap_keys = iced::keyboard::on_key_press(|key, modifiers| {
+            use iced::keyboard::key::{Key, Named};
+            if !modifiers.control() {
+                return None;
+            }
+            let direction = match key {
+                Key::Named(Named::ArrowLeft) => SnapDirection::Left,
+                Key::Named(Named::ArrowRight) => SnapDirection::Right,
+                Key::Named(Named::ArrowUp) => SnapDirection::Up,
+                Key::Named(Named::ArrowDown) => SnapDirection::Down,
+                _ => return None,
+            };
+            Some(Message::SnapWindow(direction))
+        });
+        
+        let window_moves = iced::event::listen_with(|event, _status| match event {
+            iced::Event::Window(window::Id::MAIN, window::Event::Moved { .. }) => Some(Message::WindowMoved),
+            iced::Event::Window(window::Id::MAIN, window::Event::CloseRequested) => Some(Message::MainWindowCloseRequested),
+            _ => None,
+        });
+        
+        let mut subscriptions = vec![countdown_check, tray_check, snap_keys, window_moves];
+        
+        // 列出时间输入建议时用上下方向键选择，输入框不处理这两个键
+        if !self.time_suggestions.is_empty() {
+            subscriptions.push(iced::keyboard::on_key_press(|key, modifiers| {
+                use iced::keyboard::key::{Key, Named};
+                match key {
+                    Key::Named(Named::ArrowUp) if !modifiers.control() => Some(Message::MoveSuggestion(-1)),
+                    Key::Named(Named::ArrowDown) if !modifiers.control() => Some(Message::MoveSuggestion(1)),
+                    _ => None,
+                }
+            }));
+        }
+        
+        // 日落后自动深色按分钟检查即可
+        if self.auto_theme.is_some() {
+            subscriptions.push(iced::time::every(std::time::Duration::from_secs(60))
+                .map(|_| Message::SyncAutoTheme));
+        }
+        
+        iced::Subscription::batch(subscriptions)
     }
 
     /// 创建应用程序实例
     fn new(flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        let (time_parser, countdown_receiver, ui_event_sender) = flags;
+        let (time_parser, countdown_receiver, ui_event_sender, status_handle, task_list, window_mode) = flags;
         info!("创建UIManager实例，倒计时接收器: {}", if countdown_receiver.is_some() { "已设置" } else { "未设置" });
         
-        let ui_manager = Self {
+        let (task_persistence, profile, profile_names) = Self::load_profile_state();
+        let crash_report = task_persistence.as_ref().and_then(|persistence| crash::take_pending_report(persistence.get_data_dir()));
+        
+        // 托盘图标必须在界面线程上创建
+        let tray_manager = Self::create_tray_manager(&ui_event_sender);
+        
+        let mut ui_manager = Self {
             time_input: String::new(),
+            time_suggestions: Vec::new(),
+            suggestion_index: None,
             countdown_status: CountdownStatus::Idle,
             time_parser,
-            tray_manager: None,
+            tray_manager,
             ui_event_sender,
             countdown_receiver,
             theme: Theme::default(),
             is_dark_theme: false,
             minimized_to_tray: false,
+            background_agent: false,
+            minimize_on_close: ConfigManager::load_minimize_on_close(),
             show_settings: false,
             show_about: false,
+            task_persistence,
+            profile,
+            profile_names,
+            new_profile_name: String::new(),
+            power_requirements: Vec::new(),
+            shutdown_blocker: None,
+            show_external_shutdown_notice: false,
+            crash_report,
+            trigger_wait: None,
+            process_watch: None,
+            show_weekly_editor: false,
+            weekly_editor: default_weekly_editor(),
+            template_source: String::new(),
+            template_preview: None,
+            template_message: None,
+            task_backup_message: None,
+            calendar_message: None,
+            calendar_source: String::new(),
+            calendar_keyword: ics::DEFAULT_KEYWORD.to_string(),
+            calendar_import: None,
+            backup_choices: Vec::new(),
+            selected_backup: None,
+            bundle_source: String::new(),
+            bundle_message: None,
+            plan_preview: None,
+            selected_action: TaskAction::default(),
+            policy: Policy::load(),
+            cancel_pin_input: String::new(),
+            status_handle,
+            notification_manager: Self::create_notification_manager(),
+            announcer: Self::create_announcer(),
+            ask_cancel_reason: Self::load_ask_cancel_reason(),
+            warning_offsets: ConfigManager::load_warning_offsets(),
+            show_cancel_reason: false,
+            reschedule_choices: Vec::new(),
+            first_warning_seen: false,
+            large_buttons: Self::load_large_buttons(),
+            system_tasks: ConfigManager::load_system_tasks(),
+            auto_start: ConfigManager::load_auto_start().unwrap_or(false),
+            autostart_message: None,
+            large_slider_minutes: DEFAULT_LARGE_SLIDER_MINUTES,
+            remote_input: RemoteInput::from_settings(&ConfigManager::load_remote()),
+            webhook_input: WebhookInput::from_url(&ConfigManager::load_webhook().url),
+            settings_tab: SettingsTab::default(),
+            config_form: Self::load_config_form(),
+            history_lines: Vec::new(),
+            warning_overlay: Self::create_warning_overlay(),
+            focus_assist: Self::create_focus_assist(),
+            auto_theme: Self::load_auto_theme(),
+            custom_command_input: Self::load_custom_command_input(),
+            cpu_threshold_input: CpuThresholdInput::from_conditions(&ConfigManager::load_conditions()),
+            log_viewer: LogViewerInput::default(),
+            blocking_processes_input: BlockingProcessesInput::from_conditions(&ConfigManager::load_conditions()),
+            sound_files_input: SoundFilesInput::from_settings(&ConfigManager::load_sounds()),
+            battery_settings: Self::load_battery_settings(),
+            logon_routine_input: LogonRoutineInput::default(),
+            font_family_input: FontFamilyInput::from_family(&ConfigManager::load_font_family()),
+            window_handle: None,
+            last_window_move: None,
+            task_list,
+            show_task_list: false,
+            pending_remote_start: None,
+            remote_start_confirmed: false,
+            pending_short_start: None,
+            immediate_final_warning: false,
+            countdown_paused: false,
+            task_editor: None,
+            self_test_report: None,
+            window_title: DEFAULT_WINDOW_TITLE.to_string(),
         };
+        ui_manager.sync_auto_theme();
+        ui_manager.sync_tray_presets();
+        
+        // 获取主窗口句柄，供托盘快捷菜单弹出使用
+        let fetch_handle = window::run_with_handle(window::Id::MAIN, |handle| {
+            match handle.as_raw() {
+                window::raw_window_handle::RawWindowHandle::Win32(win32) => Some(win32.hwnd.get()),
+                _ => None,
+            }
+        })
+        .map(Message::WindowHandleFetched);
         
-        (ui_manager, Command::none())
+        // 从快捷方式启动时可以直接最小化到托盘，倒计时在后台进行
+        match window_mode {
+            WindowMode::Normal => (ui_manager, fetch_handle),
+            WindowMode::Minimized => {
+                info!("启动时最小化到托盘");
+                ui_manager.minimized_to_tray = true;
+                (ui_manager, Command::batch([fetch_handle, window::minimize(window::Id::MAIN, true)]))
+            },
+            WindowMode::TrayOnly => {
+                info!("以后台代理方式启动，只显示托盘图标");
+                ui_manager.minimized_to_tray = true;
+                ui_manager.background_agent = true;
+                (ui_manager, fetch_handle)
+            },
+        }
     }
 
     /// 应用程序标题
-    fn title(&self) -> String {
-        "QtShut - 定时关机".to_string()
+    fn title(&self, window: window::Id) -> String {
+        if Some(window) == self.warning_overlay.window() {
+            return format!("QtShut — 即将{}", self.current_action());
+        }
+        self.window_title.clone()
     }
 
     /// 处理消息更新
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
-        match message {
+        let command = match message {
             Message::TimeInputChanged(input) => {
+                self.time_suggestions = SuggestionProvider::new(&self.profile.input_history, &self.profile.presets).suggest(&input);
+                self.suggestion_index = None;
                 self.time_input = input;
+                self.plan_preview = None;
+                Command::none()
+            },
+            Message::SubmitTimeInput => {
+                match self.suggestion_index.and_then(|index| self.time_suggestions.get(index)).cloned() {
+                    Some(suggestion) => self.update(Message::SuggestionChosen(suggestion)),
+                    None => Command::none(),
+                }
+            },
+            Message::MoveSuggestion(step) => {
+                let len = self.time_suggestions.len() as isize;
+                if len > 0 {
+                    // 没有选中时向下从第一条开始，向上从最后一条开始
+                    let current = self.suggestion_index.map_or(if step > 0 { -1 } else { len }, |index| index as isize);
+                    self.suggestion_index = Some((current + step).rem_euclid(len) as usize);
+                }
+                Command::none()
+            },
+            Message::SuggestionChosen(suggestion) => {
+                self.time_input = suggestion;
+                self.time_suggestions.clear();
+                self.suggestion_index = None;
+                self.plan_preview = None;
                 Command::none()
             },
             Message::UpdateTimeInput(_time_input) => {
@@ -313,13 +2865,29 @@ impl Application for UIManager {
             },
             Message::StartCountdown => {
                 info!("用户请求开始倒计时，当前输入: '{}'", self.time_input);
+                self.plan_preview = None;
+                self.time_suggestions.clear();
+                self.suggestion_index = None;
+                if self.require_remote_confirmation(&[self.selected_action], Message::StartCountdown) {
+                    return Command::none();
+                }
                 
                 // 解析时间输入
                 match self.time_parser.parse(&self.time_input) {
                     Ok(time_input) => {
                         info!("时间解析成功: {:?}", time_input);
+                        let input = self.time_input.clone();
+                        self.profile.record_input(&input);
+                        self.save_profile();
                         info!("发送StartCountdown事件到应用层");
-                        self.send_ui_event(UIEvent::StartCountdown(time_input, TaskType::Once));
+                        // "每周五22:00"等输入创建每周计划
+                        let task_type = time_input.task_type_or(TaskType::Once);
+                        self.send_ui_event(UIEvent::StartCountdown(time_input, task_type, self.selected_action));
+                        // 短倒计时由核心请求确认，确认后才开始
+                        if self.short_countdown_seconds().is_some() {
+                            return Command::none();
+                        }
+                        self.power_requirements = PowerRequirement::for_task(task_type, &[self.selected_action]);
                         self.countdown_status = CountdownStatus::Running { 
                             remaining: chrono::Duration::seconds(0) // 临时值，会被实际倒计时更新
                         };
@@ -331,63 +2899,641 @@ impl Application for UIManager {
                 }
                 Command::none()
             },
+            Message::PreviewPlan => {
+                let has_admin = SystemCompatibility::new().has_admin_privileges();
+                let now = chrono::Local::now();
+                let plan = TaskPlan::resolve(
+                    &self.time_parser,
+                    &self.time_input,
+                    TaskType::Once,
+                    self.selected_action,
+                    has_admin,
+                    now,
+                );
+                self.plan_preview = Some(match plan {
+                    Ok(plan) => plan.with_warning_offsets(&self.warning_offsets, now).summary(),
+                    Err(e) => format!("时间格式错误: {}", e),
+                });
+                Command::none()
+            },
             Message::CancelCountdown => {
                 info!("用户请求取消倒计时");
-                self.send_ui_event(UIEvent::CancelCountdown);
+                if self.policy.requires_pin() {
+                    let pin = std::mem::take(&mut self.cancel_pin_input);
+                    if !self.policy.verify_pin(&pin) {
+                        self.show_error_notification("PIN错误，无法取消倒计时");
+                        return Command::none();
+                    }
+                    self.send_ui_event(UIEvent::CancelCountdownWithPin(pin));
+                } else {
+                    self.send_ui_event(UIEvent::CancelCountdown);
+                }
                 self.countdown_status = CountdownStatus::Cancelled;
+                self.power_requirements.clear();
+                self.sync_shutdown_block();
+                self.show_cancel_reason = self.ask_cancel_reason;
+                Command::none()
+            },
+            Message::CancelReasonChosen(reason) => {
+                self.show_cancel_reason = false;
+                if let Some(reason) = reason {
+                    self.send_ui_event(UIEvent::RecordCancelReason(reason));
+                }
+                Command::none()
+            },
+            Message::RescheduleChosen(choice) => {
+                info!("用户选择改期: {}", choice.label());
+                self.reschedule_choices.clear();
+                if choice != RescheduleChoice::Keep {
+                    // 改期后的倒计时到了新的提醒时间再询问一次
+                    self.first_warning_seen = false;
+                    self.send_ui_event(UIEvent::Reschedule(choice));
+                }
+                Command::none()
+            },
+            Message::TogglePause => {
+                // 界面在收到倒计时管理器的暂停或恢复消息后才更新
+                if self.countdown_paused {
+                    info!("用户继续倒计时");
+                    self.send_ui_event(UIEvent::ResumeCountdown);
+                } else {
+                    info!("用户暂停倒计时");
+                    self.send_ui_event(UIEvent::PauseCountdown);
+                }
+                Command::none()
+            },
+            Message::Snooze => {
+                info!("用户推迟倒计时 {} 分钟", SNOOZE_MINUTES);
+                // 新的剩余时间由倒计时管理器立即广播
+                self.send_ui_event(UIEvent::ExtendCountdown(SNOOZE_MINUTES * 60));
+                Command::none()
+            },
+            Message::AddTime => {
+                info!("用户增加剩余时间 {} 分钟", ADJUST_MINUTES);
+                self.send_ui_event(UIEvent::ExtendCountdown(ADJUST_MINUTES * 60));
+                Command::none()
+            },
+            Message::SubtractTime => {
+                info!("用户减少剩余时间 {} 分钟", ADJUST_MINUTES);
+                // 剩余时间不足时由倒计时管理器拒绝，不会立即执行
+                self.send_ui_event(UIEvent::ShortenCountdown(ADJUST_MINUTES * 60));
+                Command::none()
+            },
+            Message::ActionSelected(action) => {
+                self.selected_action = action;
+                self.plan_preview = None;
+                Command::none()
+            },
+            Message::CancelPinChanged(pin) => {
+                self.cancel_pin_input = pin;
                 Command::none()
             },
             Message::MinimizeToTray => {
-                info!("最小化窗口");
+                info!("最小化到托盘");
                 self.send_ui_event(UIEvent::MinimizeToTray);
-                self.minimized_to_tray = true;
-                window::minimize(window::Id::MAIN, true)
+                self.hide_main_window()
             },
             Message::RestoreFromTray => {
                 self.send_ui_event(UIEvent::RestoreFromTray);
                 self.minimized_to_tray = false;
                 Command::none()
             },
-            Message::ToggleTheme => {
-                self.is_dark_theme = !self.is_dark_theme;
-                self.theme = if self.is_dark_theme {
-                    Theme::dark_theme()
-                } else {
-                    Theme::light_theme()
+            Message::ToggleTheme => {
+                // 手动切换主题时关闭自动深色
+                if self.auto_theme.take().is_some() {
+                    Self::save_theme_type(if self.is_dark_theme { ThemeType::Light } else { ThemeType::Dark });
+                }
+                self.set_dark_theme(!self.is_dark_theme);
+                Command::none()
+            },
+            Message::ToggleAutoTheme => {
+                if self.auto_theme.take().is_some() {
+                    Self::save_theme_type(if self.is_dark_theme { ThemeType::Dark } else { ThemeType::Light });
+                } else {
+                    let dark_from_hour = ConfigManager::new().ok()
+                        .and_then(|config_manager| config_manager.get_config().ui.auto_dark_hour);
+                    self.auto_theme = Some(ThemeSchedule::new(dark_from_hour));
+                    Self::save_theme_type(ThemeType::TimeOfDay);
+                    self.sync_auto_theme();
+                }
+                Command::none()
+            },
+            Message::SyncAutoTheme => {
+                self.sync_auto_theme();
+                Command::none()
+            },
+            Message::ToggleFocusAssist => {
+                let enabled = !self.focus_assist.is_enabled();
+                if let Err(e) = self.focus_assist.set_enabled(enabled) {
+                    error!("恢复专注助手状态失败: {}", e);
+                }
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().ui.focus_assist_in_final_warning = enabled;
+                    config_manager.save_config()
+                });
+                if let Err(e) = result {
+                    error!("保存专注助手设置失败: {}", e);
+                }
+                self.sync_shutdown_block();
+                Command::none()
+            },
+            Message::ToggleLargeButtons => {
+                self.large_buttons = !self.large_buttons;
+                let large_buttons = self.large_buttons;
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().ui.large_buttons = large_buttons;
+                    config_manager.save_config()
+                });
+                if let Err(e) = result {
+                    error!("保存大按钮模式设置失败: {}", e);
+                }
+                Command::none()
+            },
+            Message::ToggleAutoStart => {
+                self.auto_start = !self.auto_start;
+                let auto_start = self.auto_start;
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().app.auto_start = auto_start;
+                    config_manager.save_config()
+                });
+                if let Err(e) = result {
+                    error!("保存开机自动启动设置失败: {}", e);
+                }
+                // 安全模式下只保存设置，正常启动时再按设置登记
+                if system::is_safe_mode() {
+                    self.autostart_message = Some("安全模式下不修改开机自动启动登记，正常启动后生效".to_string());
+                    return Command::none();
+                }
+                self.autostart_message = None;
+                Self::check_autostart(move || autostart::set_autostart(auto_start))
+            },
+            Message::AutoStartChecked(result) => {
+                self.autostart_message = Some(match result {
+                    Ok(state) => state.to_string(),
+                    Err(e) => {
+                        error!("登记开机自动启动失败: {}", e);
+                        format!("登记失败: {}", e)
+                    },
+                });
+                Command::none()
+            },
+            Message::ToggleSystemTasks => {
+                self.system_tasks = !self.system_tasks;
+                let system_tasks = self.system_tasks;
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().shutdown.system_tasks = system_tasks;
+                    config_manager.save_config()
+                });
+                if let Err(e) = result {
+                    error!("保存任务计划程序同步设置失败: {}", e);
+                }
+                self.send_ui_event(UIEvent::SetSystemTasks(system_tasks));
+                Command::none()
+            },
+            Message::ToggleRemoteServer => {
+                if !self.remote_input.enabled && self.remote_input.secret.trim().is_empty() {
+                    self.remote_input.message = Some("请先设置本机口令，其他电脑需要使用相同的口令".to_string());
+                    return Command::none();
+                }
+                self.remote_input.enabled = !self.remote_input.enabled;
+                self.remote_input.message = Some(match self.remote_input.save() {
+                    Ok(()) => "已保存，重新启动QtShut后生效".to_string(),
+                    Err(e) => format!("保存失败: {}", e),
+                });
+                Command::none()
+            },
+            Message::RemoteSecretChanged(secret) => {
+                self.remote_input.secret = secret;
+                self.remote_input.message = None;
+                Command::none()
+            },
+            Message::RemoteBindAddressChanged(address) => {
+                self.remote_input.bind_address = address;
+                self.remote_input.message = None;
+                Command::none()
+            },
+            Message::SaveRemoteSecret => {
+                if self.remote_input.secret.trim().is_empty() {
+                    self.remote_input.enabled = false;
+                }
+                self.remote_input.message = Some(match self.remote_input.save() {
+                    Ok(()) => "口令和监听地址已保存，重新启动QtShut后生效".to_string(),
+                    Err(e) => format!("保存失败: {}", e),
+                });
+                Command::none()
+            },
+            Message::RemoteTargetNameChanged(name) => {
+                self.remote_input.name = name;
+                Command::none()
+            },
+            Message::RemoteTargetAddressChanged(address) => {
+                self.remote_input.address = address;
+                Command::none()
+            },
+            Message::RemoteTargetSecretChanged(secret) => {
+                self.remote_input.target_secret = secret;
+                Command::none()
+            },
+            Message::AddRemoteTarget => {
+                let target = match self.remote_input.parse_target() {
+                    Ok(target) => target,
+                    Err(e) => {
+                        self.remote_input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                self.remote_input.targets.push(target);
+                self.remote_input.message = Some(match self.remote_input.save() {
+                    Ok(()) => {
+                        self.remote_input.name.clear();
+                        self.remote_input.address.clear();
+                        self.remote_input.target_secret.clear();
+                        "已添加".to_string()
+                    },
+                    Err(e) => format!("保存失败: {}", e),
+                });
+                Command::none()
+            },
+            Message::RemoveRemoteTarget(index) => {
+                if index < self.remote_input.targets.len() {
+                    self.remote_input.targets.remove(index);
+                    if let Err(e) = self.remote_input.save() {
+                        self.remote_input.message = Some(format!("保存失败: {}", e));
+                    }
+                }
+                Command::none()
+            },
+            Message::SendRemoteShutdown(index) => {
+                let Some(target) = self.remote_input.targets.get(index).cloned() else {
+                    return Command::none();
+                };
+                info!("远程关机: {} ({})", target.name, target.address);
+                self.remote_input.message = Some(format!("正在连接{}...", target.name));
+                let from = platform::native().computer_name().unwrap_or_else(|_| "QtShut".to_string());
+                Command::perform(
+                    async move {
+                        remote::send_command(&target, TaskAction::Shutdown, DEFAULT_REMOTE_DELAY_SECONDS, &from).await
+                            .map(|message| format!("{}: {}", target.name, message))
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::RemoteShutdownSent,
+                )
+            },
+            Message::RemoteShutdownSent(result) => {
+                self.remote_input.message = Some(match result {
+                    Ok(message) => format!("已发送，{}", message),
+                    Err(e) => {
+                        error!("远程关机失败: {}", e);
+                        format!("远程关机失败: {}", e)
+                    },
+                });
+                Command::none()
+            },
+            Message::WebhookUrlChanged(url) => {
+                self.webhook_input.url = url;
+                self.webhook_input.message = None;
+                Command::none()
+            },
+            Message::SaveWebhook => {
+                let url = match self.webhook_input.parse() {
+                    Ok(url) => url,
+                    Err(e) => {
+                        self.webhook_input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().webhook.url = url.clone();
+                    config_manager.save_config()
+                });
+                self.webhook_input.message = Some(match result {
+                    Ok(_) if url.is_empty() => "已关闭外部通知".to_string(),
+                    Ok(_) => "已保存: 任务执行、取消或失败时发送通知".to_string(),
+                    Err(e) => {
+                        error!("保存外部通知设置失败: {}", e);
+                        format!("保存失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::SettingsTabSelected(tab) => {
+                self.settings_tab = tab;
+                match tab {
+                    SettingsTab::General => self.backup_choices = self.load_backup_choices(),
+                    SettingsTab::History => self.history_lines = self.load_history_lines(),
+                    _ => {},
+                }
+                Command::none()
+            },
+            Message::ConfigFlagToggled(flag) => {
+                self.config_form.toggle(flag);
+                Command::none()
+            },
+            Message::ConfigTextChanged(field, value) => {
+                self.config_form.set_text(field, value);
+                Command::none()
+            },
+            Message::ScreenTimeActionSelected(action) => {
+                self.config_form.screen_time_action = action;
+                Command::none()
+            },
+            Message::SaveConfigForm => {
+                let result = ConfigManager::new()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut config_manager| {
+                        self.config_form.apply(config_manager.get_config_mut())?;
+                        config_manager.save_config().map_err(|e| e.to_string())?;
+                        Ok(config_manager.get_config().clone())
+                    });
+                match result {
+                    Ok(config) => {
+                        info!("已保存设置");
+                        self.apply_config(&config);
+                        self.config_form = ConfigForm::from_config(&config);
+                        self.config_form.message = Some("已保存，日志级别、录制事件、数据加密和倒计时的提醒时间在下次启动时生效".to_string());
+                    },
+                    Err(e) => {
+                        error!("保存设置失败: {}", e);
+                        self.config_form.message = Some(format!("保存失败: {}", e));
+                    }
+                }
+                Command::none()
+            },
+            Message::TestWebhook => {
+                let url = match self.webhook_input.parse() {
+                    Ok(url) if url.is_empty() => {
+                        self.webhook_input.message = Some("请先输入接收通知的网址".to_string());
+                        return Command::none();
+                    },
+                    Ok(url) => url,
+                    Err(e) => {
+                        self.webhook_input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                self.webhook_input.message = Some("正在发送测试消息...".to_string());
+                let payload = WebhookPayload::new(WebhookEvent::Test, &webhook::computer_name(), None, None, None, chrono::Local::now());
+                Command::perform(
+                    async move { webhook::send(&url, &payload).await.map_err(|e| e.to_string()) },
+                    Message::WebhookTested,
+                )
+            },
+            Message::WebhookTested(result) => {
+                self.webhook_input.message = Some(match result {
+                    Ok(()) => "测试消息已发送".to_string(),
+                    Err(e) => e,
+                });
+                Command::none()
+            },
+            Message::LargeSliderChanged(minutes) => {
+                self.large_slider_minutes = minutes;
+                Command::none()
+            },
+            Message::StartLargeCountdown => {
+                // 与在输入框中输入时长后开始相同
+                self.time_input = format_quick_duration(chrono::Duration::minutes(i64::from(self.large_slider_minutes)));
+                self.update(Message::StartCountdown)
+            },
+            Message::SnapWindow(direction) => {
+                if let Some(window_handle) = self.window_handle {
+                    if let Some((monitor, rect)) = placement::window_placement(window_handle) {
+                        let position = placement::snap_position(rect, monitor.work_area, direction);
+                        if placement::move_window(window_handle, position) {
+                            self.last_window_move = Some(std::time::Instant::now());
+                        }
+                    }
+                }
+                Command::none()
+            },
+            Message::WindowMoved => {
+                self.last_window_move = Some(std::time::Instant::now());
+                Command::none()
+            },
+            Message::ToggleTaskList => {
+                self.show_task_list = !self.show_task_list;
+                Command::none()
+            },
+            Message::RemoveTask(id) => {
+                info!("用户请求删除任务: {}", id);
+                self.send_ui_event(UIEvent::RemoveTask(id));
+                Command::none()
+            },
+            Message::SetTaskEnabled(id, enabled) => {
+                info!("用户请求{}任务: {}", if enabled { "启用" } else { "停用" }, id);
+                self.send_ui_event(UIEvent::SetTaskEnabled(id, enabled));
+                Command::none()
+            },
+            Message::EditTask(id) => {
+                let now = chrono::Local::now();
+                let task = self.task_list.as_ref().and_then(|handle| handle.tasks().into_iter().find(|task| task.id == id));
+                match task.and_then(|task| TaskEditor::new(task, now)) {
+                    Some(editor) => {
+                        info!("用户请求编辑任务: {}", id);
+                        self.task_editor = Some(editor);
+                    },
+                    None => self.show_error_notification("该任务无法编辑"),
+                }
+                Command::none()
+            },
+            Message::EditorTimeChanged(value) => {
+                if let Some(editor) = self.task_editor.as_mut() {
+                    editor.time_input = value;
+                    editor.error = None;
+                }
+                Command::none()
+            },
+            Message::EditorRepeatChanged(repeat) => {
+                if let Some(editor) = self.task_editor.as_mut() {
+                    editor.repeat = repeat;
+                    editor.error = None;
+                }
+                Command::none()
+            },
+            Message::EditorActionChanged(action) => {
+                if let Some(editor) = self.task_editor.as_mut() {
+                    editor.action = action;
+                }
+                Command::none()
+            },
+            Message::SaveTaskEdit => {
+                let Some(editor) = self.task_editor.as_mut() else {
+                    return Command::none();
                 };
+                match editor.build_task(&self.time_parser, chrono::Local::now()) {
+                    Ok(task) => {
+                        info!("保存任务修改: {}", task.schedule_text());
+                        self.task_editor = None;
+                        self.send_ui_event(UIEvent::UpdateTask(task));
+                    },
+                    Err(e) => editor.error = Some(format!("时间格式错误: {}", e)),
+                }
                 Command::none()
             },
+            Message::CloseTaskEditor => {
+                self.task_editor = None;
+                Command::none()
+            },
+            Message::MainWindowCloseRequested => {
+                if !self.background_agent && !self.minimize_on_close {
+                    return self.update(Message::Exit);
+                }
+                info!("关闭主窗口，继续在后台运行");
+                if self.last_window_move.take().is_some() {
+                    self.save_window_placement();
+                }
+                self.hide_main_window()
+            },
             Message::Exit => {
+                if self.policy_shutdown_locked() {
+                    self.show_error_notification("管理策略要求的关机正在倒计时，不能退出程序");
+                    return Command::none();
+                }
                 info!("用户请求退出应用程序");
-                self.send_ui_event(UIEvent::Exit);
+                if self.last_window_move.take().is_some() {
+                    self.save_window_placement();
+                }
+                // process::exit 不会运行析构函数，退出前恢复最后警告时开启的专注助手
+                if let Err(e) = self.focus_assist.sync(false) {
+                    warn!("恢复专注助手状态失败: {}", e);
+                }
                 std::process::exit(0);
             },
+            Message::CountdownUpdate(CountdownUpdate::WindowRequested(request)) => self.handle_window_request(request),
             Message::CountdownUpdate(update) => {
                 self.handle_countdown_update(update);
                 Command::none()
             },
-            Message::QuickCountdown(minutes) => {
-                info!("快速倒计时: {} 分钟", minutes);
+            Message::QuickCountdown(duration) => {
+                info!("快速倒计时: {}", format_quick_duration(duration));
+                if self.require_remote_confirmation(&[QUICK_COUNTDOWN_ACTION], Message::QuickCountdown(duration)) {
+                    return Command::none();
+                }
                 // 更新输入框显示
-                self.time_input = format!("{}分钟", minutes);
+                self.time_input = format_quick_duration(duration);
                 // 发送UI事件
-                let seconds = minutes * 60;
-                self.send_ui_event(UIEvent::QuickCountdown(seconds));
+                self.send_ui_event(UIEvent::QuickCountdown(duration));
+                self.power_requirements = PowerRequirement::for_task(TaskType::Once, &[QUICK_COUNTDOWN_ACTION]);
+                Command::none()
+            },
+            Message::RepeatLast => {
+                info!("重复上次的倒计时");
+                // 核心按同一条历史记录重新创建任务，远程桌面确认使用上次的操作
+                let action = self.last_repeat_action();
+                if self.require_remote_confirmation(&[action], Message::RepeatLast) {
+                    return Command::none();
+                }
+                self.send_ui_event(UIEvent::RepeatLast);
+                self.power_requirements = PowerRequirement::for_task(TaskType::Once, &[action]);
+                Command::none()
+            },
+            Message::WaitForNetworkIdle => {
+                self.plan_preview = None;
+                if self.require_remote_confirmation(&[self.selected_action], Message::WaitForNetworkIdle) {
+                    return Command::none();
+                }
+                let settings = ConfigManager::load_network_idle();
+                info!("用户请求{}后{}", settings.describe(), self.selected_action);
+                self.send_ui_event(UIEvent::WaitForNetworkIdle(self.selected_action));
+                self.trigger_wait = Some(format!("等待下载完成：{}后{}", settings.describe(), self.selected_action));
+                Command::none()
+            },
+            Message::WaitForPowerLoss => {
+                self.plan_preview = None;
+                if self.require_remote_confirmation(&[self.selected_action], Message::WaitForPowerLoss) {
+                    return Command::none();
+                }
+                info!("用户请求UPS断电后{}", self.selected_action);
+                self.send_ui_event(UIEvent::WaitForPowerLoss(self.selected_action));
+                self.trigger_wait = Some(format!("等待UPS断电：改由电池供电后{}", self.selected_action));
+                Command::none()
+            },
+            Message::ToggleProcessWatch => {
+                self.process_watch = match self.process_watch {
+                    Some(_) => None,
+                    None => Some(ProcessWatchInput::load()),
+                };
+                Command::none()
+            },
+            Message::RefreshProcesses => {
+                if let Some(input) = &mut self.process_watch {
+                    input.refresh();
+                }
+                Command::none()
+            },
+            Message::ProcessSelected(process) => {
+                if let Some(input) = &mut self.process_watch {
+                    input.selected = Some(process);
+                    input.message = None;
+                }
+                Command::none()
+            },
+            Message::ProcessDelayChanged(delay) => {
+                if let Some(input) = &mut self.process_watch {
+                    input.delay = delay;
+                    input.message = None;
+                }
+                Command::none()
+            },
+            Message::StartProcessWatch => {
+                let Some(input) = &mut self.process_watch else {
+                    return Command::none();
+                };
+                let trigger = match input.trigger() {
+                    Ok(trigger) => trigger,
+                    Err(e) => {
+                        input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                self.plan_preview = None;
+                if self.require_remote_confirmation(&[self.selected_action], Message::StartProcessWatch) {
+                    return Command::none();
+                }
+                info!("用户请求{}{}", trigger.describe(), self.selected_action);
+                self.trigger_wait = Some(format!("等待进程退出：{}{}", trigger.describe(), self.selected_action));
+                self.send_ui_event(UIEvent::WaitForProcessExit(trigger, self.selected_action));
+                self.process_watch = None;
                 Command::none()
             },
             Message::ShowSettings => {
                 info!("显示设置窗口");
                 self.show_settings = !self.show_settings;
-                self.send_ui_event(UIEvent::ShowSettings);
+                if self.show_settings {
+                    self.backup_choices = self.load_backup_choices();
+                    self.selected_backup = None;
+                    let tasks = self.task_list.as_ref().map(|handle| handle.tasks()).unwrap_or_default();
+                    self.logon_routine_input = LogonRoutineInput::from_tasks(&tasks);
+                    // 显示实际的登记状态，用户可能在任务管理器中禁用了启动项
+                    if platform::capabilities().autostart {
+                        return Self::check_autostart(autostart::query_state);
+                    }
+                }
                 Command::none()
             },
             Message::ShowAbout => {
                 info!("显示关于窗口");
                 self.show_about = !self.show_about;
-                self.send_ui_event(UIEvent::ShowAbout);
+                Command::none()
+            },
+            Message::RunSelfTest => {
+                info!("运行自检");
+                Command::perform(selftest::run(), Message::SelfTestFinished)
+            },
+            Message::SelfTestFinished(report) => {
+                self.self_test_report = Some(report);
+                Command::none()
+            },
+            Message::CloseSelfTestReport => {
+                self.self_test_report = None;
                 Command::none()
             },
             Message::CheckCountdownStatus => {
+                // 窗口停止移动一秒后保存位置，避免拖动过程中频繁写入配置
+                if self.last_window_move.is_some_and(|moved| moved.elapsed() >= std::time::Duration::from_secs(1)) {
+                    self.last_window_move = None;
+                    self.save_window_placement();
+                }
+                
                 // 检查是否有倒计时更新
                 let mut updates = Vec::new();
                 let mut message_count = 0;
@@ -421,20 +3567,657 @@ impl Application for UIManager {
                 if message_count > 0 {
                     info!("检查到{}条倒计时更新消息", message_count);
                 } else {
-                    info!("检查倒计时状态 - 无新消息");
+                    info!("检查倒计时状态 - 无新消息");
+                }
+                
+                // 处理收集到的更新，主窗口请求返回窗口命令
+                let mut commands = Vec::new();
+                for update in updates {
+                    match update {
+                        CountdownUpdate::WindowRequested(request) => commands.push(self.handle_window_request(request)),
+                        update => self.handle_countdown_update(update),
+                    }
+                }
+                Command::batch(commands)
+            },
+            Message::SwitchProfile(name) => {
+                info!("用户请求切换配置方案: {}", name);
+                self.switch_profile(&name);
+                Command::none()
+            },
+            Message::NewProfileNameChanged(name) => {
+                self.new_profile_name = name;
+                Command::none()
+            },
+            Message::CreateProfile => {
+                let name = self.new_profile_name.trim().to_string();
+                match TaskPersistence::validate_profile_name(&name) {
+                    Ok(_) => self.switch_profile(&name),
+                    Err(e) => {
+                        error!("配置方案名称无效: {}", e);
+                        self.countdown_status = CountdownStatus::Error(e.to_string());
+                    }
+                }
+                Command::none()
+            },
+            Message::WindowHandleFetched(window_handle) => {
+                match (window_handle, &mut self.tray_manager) {
+                    (Some(window_handle), Some(tray)) => tray.set_window_handle(window_handle),
+                    (None, _) => error!("获取主窗口句柄失败，托盘快捷菜单不可用"),
+                    _ => {}
+                }
+                if let Some(window_handle) = window_handle {
+                    if platform::capabilities().shutdown_block {
+                        self.shutdown_blocker = Some(ShutdownBlocker::new(window_handle));
+                    }
+                    self.announcer.set_window_handle(window_handle);
+                    self.window_handle = Some(window_handle);
+                    self.restore_window_placement();
+                    self.watch_session_end(window_handle);
+                    self.watch_resume(window_handle);
+                }
+                Command::none()
+            },
+            Message::PollTrayEvents => {
+                if let Some(tray) = &mut self.tray_manager {
+                    while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+                        tray.handle_tray_event(event);
+                    }
+                    while let Ok(event) = MenuEvent::receiver().try_recv() {
+                        tray.handle_menu_event(event);
+                    }
+                }
+                Command::none()
+            },
+            Message::CancelExternalShutdown => {
+                self.show_external_shutdown_notice = false;
+                self.send_ui_event(UIEvent::CancelExternalShutdown);
+                Command::none()
+            },
+            Message::DismissExternalShutdownNotice => {
+                self.show_external_shutdown_notice = false;
+                Command::none()
+            },
+            Message::OpenCrashReport => {
+                if let Some(path) = self.crash_report.take() {
+                    if let Err(e) = platform::native().open_file(&path) {
+                        error!("打开崩溃报告失败: {}", e);
+                        self.show_error_notification(&format!("无法打开崩溃报告，请手动打开: {}", path.display()));
+                    }
+                }
+                Command::none()
+            },
+            Message::DismissCrashReport => {
+                self.crash_report = None;
+                Command::none()
+            },
+            Message::ConfirmRemoteStart => match self.pending_remote_start.take() {
+                Some((message, _)) => {
+                    info!("用户确认在远程桌面会话中开始任务");
+                    self.remote_start_confirmed = true;
+                    self.update(message)
+                },
+                None => Command::none(),
+            },
+            Message::DismissRemoteWarning => {
+                self.pending_remote_start = None;
+                Command::none()
+            },
+            Message::ConfirmShortStart => {
+                if let Some((seconds, action)) = self.pending_short_start.take() {
+                    info!("用户确认开始{}秒的倒计时", seconds);
+                    self.send_ui_event(UIEvent::ConfirmShortStart(true));
+                    // 短倒计时开始后直接进入最后确认阶段
+                    self.set_immediate_final_warning(true);
+                    self.power_requirements = PowerRequirement::for_task(TaskType::Once, &[action]);
+                    self.countdown_status = CountdownStatus::Running {
+                        remaining: chrono::Duration::seconds(seconds)
+                    };
+                }
+                Command::none()
+            },
+            Message::DismissShortStart => {
+                if self.pending_short_start.take().is_some() {
+                    self.send_ui_event(UIEvent::ConfirmShortStart(false));
+                }
+                Command::none()
+            },
+            Message::ToggleWeeklyEditor => {
+                self.show_weekly_editor = !self.show_weekly_editor;
+                Command::none()
+            },
+            Message::WeeklyTimeChanged(index, time) => {
+                if let Some(input) = self.weekly_editor.get_mut(index) {
+                    input.time = time;
+                }
+                Command::none()
+            },
+            Message::WeeklyActionChanged(index, choice) => {
+                if let Some(input) = self.weekly_editor.get_mut(index) {
+                    input.choice = choice;
+                }
+                Command::none()
+            },
+            Message::StartWeekly => {
+                match self.collect_weekly_slots() {
+                    Ok(slots) => {
+                        info!("用户请求开始每周计划: {} 个时间段", slots.len());
+                        let actions: Vec<TaskAction> = slots.iter().map(|slot| slot.action).collect();
+                        if self.require_remote_confirmation(&actions, Message::StartWeekly) {
+                            return Command::none();
+                        }
+                        self.send_ui_event(UIEvent::StartWeekly(slots));
+                        self.power_requirements = PowerRequirement::for_task(TaskType::Weekly, &actions);
+                        self.countdown_status = CountdownStatus::Running {
+                            remaining: chrono::Duration::seconds(0) // 临时值，会被实际倒计时更新
+                        };
+                        self.show_weekly_editor = false;
+                    },
+                    Err(e) => {
+                        error!("每周计划无效: {}", e);
+                        self.countdown_status = CountdownStatus::Error(e);
+                    }
+                }
+                Command::none()
+            },
+            Message::TemplateSourceChanged(source) => {
+                self.template_source = source;
+                Command::none()
+            },
+            Message::PreviewTemplate => {
+                info!("加载模板: {}", self.template_source);
+                self.template_preview = None;
+                self.template_message = Some("正在加载模板...".to_string());
+                let source = self.template_source.clone();
+                Command::perform(
+                    async move {
+                        template::load_template_preview(&source).await.map_err(|e| e.to_string())
+                    },
+                    Message::TemplateLoaded,
+                )
+            },
+            Message::TemplateLoaded(result) => {
+                match result {
+                    Ok(preview) if preview.is_empty() => {
+                        self.template_message = Some(format!("模板中没有可导入的内容\n{}", preview.summary()));
+                    },
+                    Ok(preview) => {
+                        self.template_message = None;
+                        self.template_preview = Some(preview);
+                    },
+                    Err(e) => {
+                        error!("加载模板失败: {}", e);
+                        self.template_message = Some(e);
+                    }
+                }
+                Command::none()
+            },
+            Message::ImportTemplate => {
+                if let Some(preview) = self.template_preview.take() {
+                    self.template_message = Some(match &self.task_persistence {
+                        Some(persistence) => match preview.merge_into(
+                            storage::open(persistence.clone(), ConfigManager::load_storage_backend()).as_ref(),
+                            persistence,
+                            &mut self.profile,
+                        ) {
+                            Ok((tasks, presets)) => format!("已导入{}个任务、{}个预设，任务将在下次启动时生效", tasks, presets),
+                            Err(e) => {
+                                error!("导入模板失败: {}", e);
+                                format!("导入模板失败: {}", e)
+                            }
+                        },
+                        None => "存储不可用，无法导入模板".to_string(),
+                    });
+                }
+                Command::none()
+            },
+            Message::RestoreTaskBackup => {
+                let backups = self.task_persistence.as_ref()
+                    .map(|persistence| storage::open(persistence.clone(), ConfigManager::load_storage_backend()).task_backups().len())
+                    .unwrap_or_default();
+                self.task_backup_message = Some(if backups == 0 {
+                    "没有可恢复的任务备份".to_string()
+                } else {
+                    self.send_ui_event(UIEvent::RestoreTaskBackup);
+                    format!("已恢复上一个版本的任务，还可再回退{}次", backups - 1)
+                });
+                self.backup_choices = self.load_backup_choices();
+                Command::none()
+            },
+            Message::BackupSelected(choice) => {
+                self.selected_backup = Some(choice);
+                Command::none()
+            },
+            Message::RestoreSelectedBackup => {
+                let Some(choice) = self.selected_backup.take() else {
+                    self.task_backup_message = Some("请先选择要恢复的备份".to_string());
+                    return Command::none();
+                };
+                self.task_backup_message = Some(match self.restore_backup(&choice) {
+                    Ok(()) => format!("已恢复{}的备份，恢复前的内容也已备份", choice),
+                    Err(e) => {
+                        error!("恢复备份失败: {}", e);
+                        format!("恢复备份失败: {}", e)
+                    }
+                });
+                self.backup_choices = self.load_backup_choices();
+                Command::none()
+            },
+            Message::ExportCalendar => {
+                let tasks = self.task_list.as_ref().map(|handle| handle.tasks()).unwrap_or_default();
+                // 优先保存到桌面，方便拖入日历程序
+                let dir = dirs::desktop_dir()
+                    .or_else(|| self.task_persistence.as_ref().map(|persistence| persistence.get_data_dir().to_path_buf()));
+                self.calendar_message = Some(match dir {
+                    Some(dir) => match calendar::write_calendar(&tasks, &dir, chrono::Local::now()) {
+                        Ok(path) => format!("已导出到 {}", path.display()),
+                        Err(e) => format!("导出日历失败: {}", e),
+                    },
+                    None => "找不到可以保存的目录".to_string(),
+                });
+                Command::none()
+            },
+            Message::CalendarSourceChanged(source) => {
+                self.calendar_source = source;
+                Command::none()
+            },
+            Message::CalendarKeywordChanged(keyword) => {
+                self.calendar_keyword = keyword;
+                Command::none()
+            },
+            Message::PreviewCalendar => {
+                info!("读取日历: {}", self.calendar_source);
+                self.calendar_import = None;
+                self.calendar_message = Some("正在读取日历...".to_string());
+                let source = self.calendar_source.clone();
+                let keyword = self.calendar_keyword.clone();
+                Command::perform(
+                    async move {
+                        ics::load_calendar_import(&source, &keyword).await.map_err(|e| e.to_string())
+                    },
+                    Message::CalendarLoaded,
+                )
+            },
+            Message::CalendarLoaded(result) => {
+                match result {
+                    Ok(import) if import.is_empty() => {
+                        self.calendar_message = Some(format!("日历中没有可导入的事件\n{}", import.summary()));
+                    },
+                    Ok(import) => {
+                        self.calendar_message = None;
+                        self.calendar_import = Some(import);
+                    },
+                    Err(e) => {
+                        error!("读取日历失败: {}", e);
+                        self.calendar_message = Some(e);
+                    }
+                }
+                Command::none()
+            },
+            Message::ImportCalendar => {
+                if let Some(import) = self.calendar_import.take() {
+                    self.calendar_message = Some(self.import_calendar(&import).unwrap_or_else(|e| {
+                        error!("导入日历失败: {}", e);
+                        format!("导入日历失败: {}", e)
+                    }));
+                }
+                Command::none()
+            },
+            Message::DiscardCalendar => {
+                self.calendar_import = None;
+                self.calendar_message = None;
+                Command::none()
+            },
+            Message::ExportSettings => {
+                self.bundle_message = Some(match self.export_settings() {
+                    Ok(path) => format!("已导出到 {}", path.display()),
+                    Err(e) => {
+                        error!("导出设置失败: {}", e);
+                        format!("导出设置失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::BundleSourceChanged(source) => {
+                self.bundle_source = source;
+                self.bundle_message = None;
+                Command::none()
+            },
+            Message::ImportSettings => {
+                self.bundle_message = Some(match self.import_settings() {
+                    Ok(message) => message,
+                    Err(e) => {
+                        error!("导入设置失败: {}", e);
+                        format!("导入设置失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::DiscardTemplate => {
+                self.template_preview = None;
+                self.template_message = None;
+                Command::none()
+            },
+            Message::CustomProgramChanged(program) => {
+                self.custom_command_input.program = program;
+                self.custom_command_input.message = None;
+                Command::none()
+            },
+            Message::CustomArgsChanged(args) => {
+                self.custom_command_input.args = args;
+                self.custom_command_input.message = None;
+                Command::none()
+            },
+            Message::CustomWorkingDirChanged(working_dir) => {
+                self.custom_command_input.working_dir = working_dir;
+                self.custom_command_input.message = None;
+                Command::none()
+            },
+            Message::SaveCustomCommand => {
+                let command = self.custom_command_input.to_command();
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().shutdown.custom_command = command.clone();
+                    config_manager.save_config()
+                });
+                self.custom_command_input.message = Some(match result {
+                    Ok(_) if command.is_empty() => "已清除自定义命令".to_string(),
+                    Ok(_) => format!("已保存: {}", command),
+                    Err(e) => {
+                        error!("保存自定义命令失败: {}", e);
+                        format!("保存失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::CpuThresholdChanged(value) => {
+                self.cpu_threshold_input.value = value;
+                self.cpu_threshold_input.message = None;
+                Command::none()
+            },
+            Message::SaveCpuThreshold => {
+                let threshold = match self.cpu_threshold_input.parse() {
+                    Ok(threshold) => threshold,
+                    Err(e) => {
+                        self.cpu_threshold_input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().shutdown.conditions.cpu_above_percent = threshold;
+                    config_manager.save_config()
+                });
+                self.cpu_threshold_input.message = Some(match (result, threshold) {
+                    (Ok(_), Some(percent)) => format!("已保存: CPU占用高于{}%时每分钟重新检查", percent),
+                    (Ok(_), None) => "已关闭CPU占用条件".to_string(),
+                    (Err(e), _) => {
+                        error!("保存CPU占用条件失败: {}", e);
+                        format!("保存失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::LogLevelSelected(level) => {
+                self.log_viewer.level = level;
+                Command::none()
+            },
+            Message::LogSearchChanged(search) => {
+                self.log_viewer.search = search;
+                Command::none()
+            },
+            Message::LogFromChanged(from) => {
+                self.log_viewer.from = from;
+                Command::none()
+            },
+            Message::LogToChanged(to) => {
+                self.log_viewer.to = to;
+                Command::none()
+            },
+            Message::ViewLogs => {
+                let filter = match self.log_viewer.filter() {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        self.log_viewer.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                let Some(log_dir) = logger::log_dir() else {
+                    self.log_viewer.message = Some("找不到日志目录".to_string());
+                    return Command::none();
+                };
+                match LoggerManager::read_logs_in(log_dir, &filter) {
+                    Ok(lines) => {
+                        self.log_viewer.message = Some(format!("共{}行符合条件", lines.len()));
+                        self.log_viewer.lines = lines[lines.len().saturating_sub(LOG_VIEW_LINES)..].to_vec();
+                    },
+                    Err(e) => {
+                        error!("读取日志失败: {}", e);
+                        self.log_viewer.message = Some(format!("读取日志失败: {}", e));
+                        self.log_viewer.lines.clear();
+                    }
+                }
+                Command::none()
+            },
+            Message::ExportLogs => {
+                let filter = match self.log_viewer.filter() {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        self.log_viewer.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                let Some(log_dir) = logger::log_dir() else {
+                    self.log_viewer.message = Some("找不到日志目录".to_string());
+                    return Command::none();
+                };
+                let dir = dirs::desktop_dir()
+                    .or_else(|| self.task_persistence.as_ref().map(|persistence| persistence.get_data_dir().to_path_buf()));
+                self.log_viewer.message = Some(match dir {
+                    Some(dir) => {
+                        let path = dir.join(format!("QtShut日志_{}.txt", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+                        match LoggerManager::export_logs_in(log_dir, &filter, &path) {
+                            Ok(count) => format!("已导出{}行到 {}", count, path.display()),
+                            Err(e) => {
+                                error!("导出日志失败: {}", e);
+                                format!("导出日志失败: {}", e)
+                            }
+                        }
+                    },
+                    None => "找不到可以保存的目录".to_string(),
+                });
+                Command::none()
+            },
+            Message::BlockingProcessesChanged(value) => {
+                self.blocking_processes_input.value = value;
+                self.blocking_processes_input.message = None;
+                Command::none()
+            },
+            Message::SaveBlockingProcesses => {
+                let names = self.blocking_processes_input.parse();
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().shutdown.conditions.blocking_processes = names.clone();
+                    config_manager.save_config()
+                });
+                self.blocking_processes_input.message = Some(match result {
+                    Ok(_) if names.is_empty() => "已关闭进程条件".to_string(),
+                    Ok(_) => {
+                        self.blocking_processes_input.value = names.join(", ");
+                        format!("已保存: 到点时{}个进程中任一正在运行则推迟执行", names.len())
+                    },
+                    Err(e) => {
+                        error!("保存进程条件失败: {}", e);
+                        format!("保存失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::SoundTypeSelected(notification_type) => {
+                self.sound_files_input.selected = notification_type;
+                self.sound_files_input.message = None;
+                Command::none()
+            },
+            Message::SoundFileChanged(path) => {
+                self.sound_files_input.files.insert(self.sound_files_input.selected, path);
+                self.sound_files_input.message = None;
+                Command::none()
+            },
+            Message::SoundVolumeChanged(volume) => {
+                self.sound_files_input.volume = volume;
+                self.sound_files_input.message = None;
+                Command::none()
+            },
+            Message::PreviewSound => {
+                let path = self.sound_files_input.path().trim().to_string();
+                self.sound_files_input.message = Some(match self.sound_files_input.parse_volume() {
+                    Err(e) => e,
+                    Ok(_) if path.is_empty() => format!("请先输入{}通知的声音文件", self.sound_files_input.selected),
+                    Ok(volume) => match sound::play_file(std::path::Path::new(&path), volume) {
+                        Ok(_) => format!("正在试听，音量{}%", volume),
+                        Err(e) => e.to_string(),
+                    },
+                });
+                Command::none()
+            },
+            Message::SaveSounds => {
+                let parsed = self.sound_files_input.parse_volume()
+                    .and_then(|volume| self.sound_files_input.parse_files().map(|files| (files, volume)));
+                let (files, volume) = match parsed {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        self.sound_files_input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    let sounds = &mut config_manager.get_config_mut().sounds;
+                    sounds.files = files.clone();
+                    sounds.volume = volume;
+                    config_manager.save_config()
+                });
+                self.sound_files_input.message = Some(match result {
+                    Ok(_) => {
+                        self.notification_manager.set_sound_files(&files, volume);
+                        if files.is_empty() {
+                            "已保存: 所有通知使用系统提示音".to_string()
+                        } else {
+                            format!("已保存: {}种通知使用声音文件", files.len())
+                        }
+                    },
+                    Err(e) => {
+                        error!("保存提醒声音失败: {}", e);
+                        format!("保存失败: {}", e)
+                    }
+                });
+                Command::none()
+            },
+            Message::ToggleBatteryHibernate => {
+                if let Some(settings) = &mut self.battery_settings {
+                    settings.hibernate_instead_of_shutdown = !settings.hibernate_instead_of_shutdown;
+                    Self::save_battery_settings(settings);
+                }
+                Command::none()
+            },
+            Message::ToggleBatteryCancel => {
+                if let Some(settings) = &mut self.battery_settings {
+                    settings.cancel_below_percent = match settings.cancel_below_percent {
+                        Some(_) => None,
+                        None => Some(DEFAULT_CANCEL_BELOW_PERCENT),
+                    };
+                    Self::save_battery_settings(settings);
+                }
+                Command::none()
+            },
+            Message::ToggleLogonSchedule => {
+                self.logon_routine_input.show_schedule = !self.logon_routine_input.show_schedule;
+                self.logon_routine_input.message = None;
+                Command::none()
+            },
+            Message::ToggleLogonCommand => {
+                self.logon_routine_input.run_command = !self.logon_routine_input.run_command;
+                self.logon_routine_input.message = None;
+                Command::none()
+            },
+            Message::LogonVolumeChanged(value) => {
+                self.logon_routine_input.volume = value;
+                self.logon_routine_input.message = None;
+                Command::none()
+            },
+            Message::SaveLogonRoutine => {
+                let steps = match self.logon_routine_input.parse() {
+                    Ok(steps) => steps,
+                    Err(e) => {
+                        self.logon_routine_input.message = Some(e);
+                        return Command::none();
+                    }
+                };
+                self.logon_routine_input.message = Some(if steps.is_empty() {
+                    "已关闭登录任务".to_string()
+                } else {
+                    format!("已保存: 每天第一次登录时{}", LogonRoutine { steps: steps.clone(), last_run: None }.describe())
+                });
+                self.send_ui_event(UIEvent::SaveLogonRoutine(steps));
+                Command::none()
+            },
+            Message::FontFamilyChanged(value) => {
+                self.font_family_input.value = value;
+                self.font_family_input.message = None;
+                Command::none()
+            },
+            Message::ApplyFontFamily => {
+                let family = self.font_family_input.family();
+                let result = ConfigManager::new().and_then(|mut config_manager| {
+                    config_manager.get_config_mut().ui.font_family = Some(family.clone()).filter(|family| family != DEFAULT_FONT_FAMILY);
+                    config_manager.save_config()
+                });
+                if let Err(e) = result {
+                    error!("保存界面字体失败: {}", e);
+                    self.font_family_input.message = Some(format!("保存失败: {}", e));
+                    return Command::none();
+                }
+                if !self.font_family_input.needs_switch() {
+                    self.font_family_input.message = Some(format!("已在使用: {}", family));
+                    return Command::none();
                 }
                 
-                // 处理收集到的更新
-                for update in updates {
-                    self.handle_countdown_update(update);
+                info!("切换界面字体: {} -> {}", self.font_family_input.current, family);
+                match theme::set_default_font_family(&family) {
+                    Some(data) => {
+                        self.font_family_input.current = family.clone();
+                        self.font_family_input.message = Some(format!("已切换为{}", family));
+                        // 加载字体后界面按新的默认字体重新排版
+                        iced::font::load(data).map(|_| Message::FontLoaded)
+                    },
+                    None => {
+                        warn!("没有找到字体: {}", family);
+                        self.font_family_input.message = Some(format!("已保存，但没有找到已安装的{}", family));
+                        Command::none()
+                    }
                 }
+            },
+            Message::FontLoaded => {
+                info!("界面字体已加载");
                 Command::none()
             },
-        }
+            Message::SavePreset => {
+                let preset = self.time_input.clone();
+                if self.profile.add_preset(&preset) {
+                    info!("已保存预设: {}", preset);
+                    self.save_profile();
+                    self.sync_tray_presets();
+                }
+                Command::none()
+            },
+        };
+        
+        // 任何消息都可能改变倒计时状态，统一同步全屏警告窗口
+        Command::batch([command, self.warning_overlay.sync(&self.countdown_status)])
     }
 
     /// 构建用户界面
-    fn view(&self) -> Element<Self::Message> {
+    fn view(&self, window: window::Id) -> Element<Self::Message> {
+        if Some(window) == self.warning_overlay.window() {
+            return self.warning_overlay_view();
+        }
+        
         let title = text("QtShut - 定时关机")
             .size(24)
             .width(Length::Fill);
@@ -444,9 +4227,30 @@ impl Application for UIManager {
             &self.time_input,
         )
         .on_input(Message::TimeInputChanged)
+        .on_submit(Message::SubmitTimeInput)
         .padding(10)
         .size(16)
         .width(Length::Fixed(300.0));
+        
+        // 时间输入建议，选中的一条突出显示
+        let time_suggestions = self.time_suggestions.iter().enumerate().fold(
+            column![].spacing(2),
+            |column, (index, suggestion)| {
+                let style = if self.suggestion_index == Some(index) { iced::theme::Button::Primary } else { iced::theme::Button::Secondary };
+                column.push(
+                    button(text(suggestion).size(14))
+                        .on_press(Message::SuggestionChosen(suggestion.clone()))
+                        .style(style)
+                        .width(Length::Fixed(300.0))
+                )
+            },
+        );
+        
+        let action_picker = row![
+            text("到点后:").size(16),
+            pick_list(TaskAction::available(), Some(self.selected_action), Message::ActionSelected),
+        ]
+        .spacing(5);
 
         let start_button = button("开始倒计时")
             .on_press(Message::StartCountdown)
@@ -456,43 +4260,226 @@ impl Application for UIManager {
             .on_press(Message::CancelCountdown)
             .padding(10);
 
-        let button_row = row![
+        let preview_button = button("预览")
+            .on_press(Message::PreviewPlan)
+            .padding(10);
+        
+        let mut button_row = row![
             start_button,
             Space::with_width(10),
+            preview_button,
+            Space::with_width(10),
+            button("下载完成后执行").on_press(Message::WaitForNetworkIdle).padding(10),
+            Space::with_width(10),
+            button("进程退出后执行").on_press(Message::ToggleProcessWatch).padding(10),
+            Space::with_width(10),
             cancel_button,
         ]
         .spacing(10);
+        
+        // 检测到UPS或电池时可以在市电断开后执行
+        if self.battery_settings.is_some() {
+            button_row = button_row.push(button("UPS断电后执行").on_press(Message::WaitForPowerLoss).padding(10));
+        }
+        
+        // 倒计时运行时可以暂停、一键推迟或微调剩余时间，不必取消后重新输入
+        if let CountdownStatus::Running { remaining } = &self.countdown_status {
+            let can_subtract = remaining.num_seconds() > ADJUST_MINUTES as i64 * 60;
+            // 管理策略要求PIN时不能暂停，也不显示推迟和延长按钮
+            let can_pause = self.countdown_paused || !self.policy.requires_pin();
+            button_row = button_row.push(
+                button(if self.countdown_paused { "继续" } else { "暂停" })
+                    .on_press_maybe(can_pause.then_some(Message::TogglePause))
+                    .padding(10)
+            );
+            if self.can_postpone() {
+                button_row = button_row
+                    .push(button(text(format!("推迟{}分钟", SNOOZE_MINUTES))).on_press(Message::Snooze).padding(10))
+                    .push(button(text(format!("+{}分钟", ADJUST_MINUTES))).on_press(Message::AddTime).padding(10));
+            }
+            button_row = button_row.push(
+                button(text(format!("−{}分钟", ADJUST_MINUTES)))
+                    .on_press_maybe(can_subtract.then_some(Message::SubtractTime))
+                    .padding(10)
+            );
+        }
+        
+        // 管理策略要求PIN时显示PIN输入框
+        if self.policy.requires_pin() {
+            button_row = button_row.push(
+                text_input("PIN", &self.cancel_pin_input)
+                    .on_input(Message::CancelPinChanged)
+                    .on_submit(Message::CancelCountdown)
+                    .secure(true)
+                    .width(Length::Fixed(80.0))
+            );
+        }
 
         // 显示倒计时状态
-        let status_text = match &self.countdown_status {
-            CountdownStatus::Idle => "等待开始...".to_string(),
-            CountdownStatus::Running { remaining } => {
-                format!("剩余时间: {}小时{}分钟{}秒", 
-                    remaining.num_hours(),
-                    remaining.num_minutes() % 60,
-                    remaining.num_seconds() % 60
-                )
-            },
-            CountdownStatus::Finished => "倒计时结束！".to_string(),
-            CountdownStatus::Cancelled => "倒计时已取消".to_string(),
-            CountdownStatus::Error(msg) => format!("错误: {}", msg),
-        };
-
-        let status_display = text(status_text)
+        let status_display = text(self.status_text())
             .size(18)
             .width(Length::Fill);
+        
+        // 执行计划预览
+        let plan_preview = text(self.plan_preview.as_deref().unwrap_or(""))
+            .size(14)
+            .width(Length::Fill);
+        
+        // 最后确认阶段的警告
+        let final_warning = if self.is_final_warning() {
+            let action = self.current_action();
+            let remote_warning = Self::remote_session_warning(action)
+                .map(|warning| format!("\n{}", warning))
+                .unwrap_or_default();
+            let warning = row![
+                text(format!("即将{}！如需中止请点击\"取消倒计时\"{}", action, remote_warning))
+                    .size(16)
+                    .style(Color::from_rgb8(220, 53, 69))
+                    .width(Length::Fill),
+            ]
+            .spacing(5);
+            if self.can_postpone() {
+                warning.push(button(text(format!("推迟{}分钟", SNOOZE_MINUTES))).on_press(Message::Snooze))
+            } else {
+                warning
+            }
+        } else {
+            row![]
+        };
+        
+        // 安全模式说明
+        let safe_mode_banner = if system::is_safe_mode() {
+            row![
+                container(text(system::SAFE_MODE_NOTICE).size(14))
+                    .padding([4, 8])
+                    .width(Length::Fill)
+                    .style(ContainerAppearance {
+                        background: Some(Background::Color(Color::from_rgb8(255, 243, 205))),
+                        border: Border {
+                            color: Color::from_rgb8(255, 193, 7),
+                            width: 1.0,
+                            radius: 4.0.into(),
+                        },
+                        ..Default::default()
+                    }),
+            ]
+        } else {
+            row![]
+        };
+        
+        // 外部关机计划接管提示
+        let external_shutdown_notice = if self.show_external_shutdown_notice {
+            row![
+                text("检测到系统中已有其他程序或手动设置的关机计划，可以保留该计划，或取消后在此重新设置关机时间")
+                    .size(14)
+                    .width(Length::Fill),
+                button("取消该计划").on_press(Message::CancelExternalShutdown),
+                button("保留").on_press(Message::DismissExternalShutdownNotice),
+            ]
+            .spacing(5)
+        } else {
+            row![]
+        };
+        
+        // 上次崩溃的报告
+        let crash_report_notice = match &self.crash_report {
+            Some(path) => row![
+                text(format!("QtShut 上次意外退出，已保存诊断报告: {}，反馈问题时请附上该文件", path.display()))
+                    .size(14)
+                    .width(Length::Fill),
+                button("打开报告").on_press(Message::OpenCrashReport),
+                button("知道了").on_press(Message::DismissCrashReport),
+            ]
+            .spacing(5),
+            None => row![],
+        };
+        
+        // 远程桌面会话中开始任务前的确认
+        let remote_session_notice = if let Some((_, action)) = &self.pending_remote_start {
+            row![
+                text(format!("{}，确定要开始吗？", system_compat::remote_session_warning(*action)))
+                    .size(14)
+                    .style(Color::from_rgb8(220, 53, 69))
+                    .width(Length::Fill),
+                button("仍然开始").on_press(Message::ConfirmRemoteStart),
+                button("取消").on_press(Message::DismissRemoteWarning),
+            ]
+            .spacing(5)
+        } else {
+            row![]
+        };
 
-        // 快速倒计时按钮
-        let quick_buttons = row![
-            button("5分钟").on_press(Message::QuickCountdown(5)),
-            Space::with_width(5),
-            button("10分钟").on_press(Message::QuickCountdown(10)),
-            Space::with_width(5),
-            button("30分钟").on_press(Message::QuickCountdown(30)),
-            Space::with_width(5),
-            button("1小时").on_press(Message::QuickCountdown(60)),
+        // 开始不到一分钟的倒计时前的确认
+        let short_start_notice = match self.pending_short_start {
+            Some((seconds, action)) => row![
+                text(format!("倒计时只有{}秒，开始后将很快{}，确定要开始吗？", seconds, action))
+                    .size(14)
+                    .style(Color::from_rgb8(220, 53, 69))
+                    .width(Length::Fill),
+                button("确定开始").on_press(Message::ConfirmShortStart),
+                button("返回修改").on_press(Message::DismissShortStart),
+            ]
+            .spacing(5),
+            None => row![],
+        };
+
+        // 取消后询问原因
+        let cancel_reason_prompt = if self.show_cancel_reason {
+            CancelReason::ALL.iter().fold(
+                Row::new().spacing(5).push(text("取消原因：").size(14)),
+                |row, &reason| row.push(button(text(reason.to_string()).size(14)).on_press(Message::CancelReasonChosen(Some(reason)))),
+            )
+            .push(button(text("跳过").size(14)).on_press(Message::CancelReasonChosen(None)))
+        } else {
+            Row::new()
+        };
+        
+        // 第一次提醒时询问是否改期
+        let reschedule_prompt = if self.reschedule_choices.is_empty() {
+            Row::new()
+        } else {
+            self.reschedule_choices.iter().fold(
+                Row::new().spacing(5).push(text("即将到点：").size(14)),
+                |row, &choice| row.push(button(text(choice.label()).size(14)).on_press(Message::RescheduleChosen(choice))),
+            )
+        };
+        
+        // 电源需求标记
+        let power_badges = power_badges(&self.power_requirements);
+        
+        // 配置方案切换
+        let profile_row = row![
+            text("配置方案:").size(16),
+            pick_list(
+                self.profile_names.clone(),
+                Some(self.profile.name.clone()),
+                Message::SwitchProfile,
+            ),
+            text_input("新方案名称", &self.new_profile_name)
+                .on_input(Message::NewProfileNameChanged)
+                .on_submit(Message::CreateProfile)
+                .width(Length::Fixed(100.0)),
+            button("新建").on_press(Message::CreateProfile),
         ]
         .spacing(5);
+        
+        // 快速倒计时按钮
+        let quick_buttons = self.profile.quick_durations().into_iter().fold(
+            Row::new().spacing(5),
+            |row, duration| row.push(button(text(format_quick_duration(duration))).on_press(Message::QuickCountdown(duration))),
+        )
+        .push(button("重复上次").on_press(Message::RepeatLast));
+        
+        // 预设与最近输入
+        let preset_buttons = self.profile.presets.iter().fold(
+            Row::new().spacing(5).push(button("保存为预设").on_press(Message::SavePreset)),
+            |row, preset| row.push(button(text(preset)).on_press(Message::TimeInputChanged(preset.clone()))),
+        );
+        
+        let history_buttons = self.profile.input_history.iter().take(5).fold(
+            Row::new().spacing(5).push(text("最近:").size(14)),
+            |row, input| row.push(button(text(input).size(14)).on_press(Message::TimeInputChanged(input.clone()))),
+        );
 
         // 控制按钮
         let control_buttons = row![
@@ -500,31 +4487,92 @@ impl Application for UIManager {
             Space::with_width(5),
             button("关于").on_press(Message::ShowAbout),
             Space::with_width(5),
+            button("自检").on_press(Message::RunSelfTest),
+            Space::with_width(5),
+            button("每周计划").on_press(Message::ToggleWeeklyEditor),
+            Space::with_width(5),
+            button("任务列表").on_press(Message::ToggleTaskList),
+            Space::with_width(5),
             button("切换主题").on_press(Message::ToggleTheme),
             Space::with_width(5),
             button("最小化").on_press(Message::MinimizeToTray),
         ]
         .spacing(5);
 
-        let content = column![
-            title,
-            Space::with_height(20),
-            time_input,
-            Space::with_height(15),
-            button_row,
-            Space::with_height(20),
-            status_display,
-            Space::with_height(20),
-            text("快速倒计时:").size(16),
-            Space::with_height(10),
-            quick_buttons,
-            Space::with_height(20),
-            control_buttons,
-        ]
-        .spacing(10)
-        .padding(20)
-        .width(Length::Fill)
-        .height(Length::Fill);
+        // 大按钮模式只保留滑块、预设和大按钮，不需要输入文字
+        let content = if self.large_buttons {
+            let panel = LargeButtonPanel {
+                minutes: self.large_slider_minutes,
+                action: self.selected_action,
+                presets: self.profile.quick_durations(),
+                status: self.status_text(),
+                show_snooze: self.is_final_warning() && self.can_postpone(),
+            };
+            let mut pin_row = Row::new();
+            if self.policy.requires_pin() {
+                pin_row = pin_row.push(
+                    text_input("取消需要输入PIN", &self.cancel_pin_input)
+                        .on_input(Message::CancelPinChanged)
+                        .on_submit(Message::CancelCountdown)
+                        .secure(true)
+                        .size(20)
+                        .width(Length::Fixed(200.0))
+                );
+            }
+            column![
+                title,
+                safe_mode_banner,
+                remote_session_notice,
+                short_start_notice,
+                panel.view(),
+                pin_row,
+                reschedule_prompt,
+                cancel_reason_prompt,
+                row![
+                    button(text("设置").size(20)).on_press(Message::ShowSettings).padding([16, 20]),
+                    button(text("普通模式").size(20)).on_press(Message::ToggleLargeButtons).padding([16, 20]),
+                ]
+                .spacing(10),
+            ]
+        } else {
+            column![
+                title,
+                safe_mode_banner,
+                external_shutdown_notice,
+                crash_report_notice,
+                remote_session_notice,
+                short_start_notice,
+                Space::with_height(10),
+                profile_row,
+                Space::with_height(10),
+                time_input,
+                time_suggestions,
+                action_picker,
+                history_buttons,
+                preset_buttons,
+                Space::with_height(15),
+                button_row,
+                plan_preview,
+                text(self.trigger_wait.as_deref().unwrap_or("")).size(14),
+                Space::with_height(20),
+                status_display,
+                final_warning,
+                reschedule_prompt,
+                cancel_reason_prompt,
+                power_badges,
+                Space::with_height(20),
+                text("快速倒计时:").size(16),
+                Space::with_height(10),
+                quick_buttons,
+                Space::with_height(20),
+                control_buttons,
+            ]
+        };
+        let content = content
+            .spacing(10)
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill);
 
         let main_content = container(content)
             .width(Length::Fill)
@@ -532,37 +4580,569 @@ impl Application for UIManager {
             .center_x()
             .center_y();
 
+        // 如果显示每周计划编辑器
+        if self.show_weekly_editor {
+            let slot_rows = self.weekly_editor.iter().enumerate().fold(
+                column![].spacing(5),
+                |rows, (index, input)| {
+                    rows.push(
+                        row![
+                            text(weekday_label(input.weekday)).width(Length::Fixed(40.0)),
+                            text_input("23:00", &input.time)
+                                .on_input(move |value| Message::WeeklyTimeChanged(index, value))
+                                .width(Length::Fixed(80.0)),
+                            pick_list(
+                                SlotActionChoice::all(),
+                                Some(input.choice),
+                                move |choice| Message::WeeklyActionChanged(index, choice),
+                            ),
+                        ]
+                        .spacing(5)
+                    )
+                },
+            );
+            
+            let editor_content = column![
+                text("每周计划").size(24),
+                text("每天可以设置不同的时间和操作").size(14),
+                Space::with_height(10),
+                slot_rows,
+                Space::with_height(10),
+                row![
+                    button("开始每周计划").on_press(Message::StartWeekly),
+                    button("关闭").on_press(Message::ToggleWeeklyEditor),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10)
+            .padding(20)
+            .width(Length::Fixed(320.0));
+            
+            let editor_modal = container(editor_content)
+                .style(self.theme.modal_appearance())
+                .center_x()
+                .center_y();
+            
+            return editor_modal.into();
+        }
+        
+        // 如果显示进程退出触发设置
+        if let Some(input) = &self.process_watch {
+            let watch_content = column![
+                text("进程退出后执行").size(24),
+                text(format!("选择的进程退出后倒计时，然后{}", self.selected_action)).size(14),
+                Space::with_height(10),
+                row![
+                    pick_list(input.processes.clone(), input.selected.clone(), Message::ProcessSelected)
+                        .placeholder("选择进程")
+                        .width(Length::Fill),
+                    button("刷新").on_press(Message::RefreshProcesses),
+                ]
+                .spacing(5),
+                row![
+                    text("退出后"),
+                    text_input("5", &input.delay)
+                        .on_input(Message::ProcessDelayChanged)
+                        .on_submit(Message::StartProcessWatch)
+                        .width(Length::Fixed(60.0)),
+                    text("分钟"),
+                ]
+                .spacing(5)
+                .align_items(iced::Alignment::Center),
+                text(input.message.as_deref().unwrap_or("")).size(14),
+                row![
+                    button("开始等待").on_press(Message::StartProcessWatch),
+                    button("关闭").on_press(Message::ToggleProcessWatch),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10)
+            .padding(20)
+            .width(Length::Fixed(360.0));
+            
+            let watch_modal = container(watch_content)
+                .style(self.theme.modal_appearance())
+                .center_x()
+                .center_y();
+            
+            return watch_modal.into();
+        }
+        
+        // 如果显示任务编辑器
+        if let Some(editor) = &self.task_editor {
+            let editor_content = container(editor.view())
+                .padding(20)
+                .width(Length::Fixed(420.0));
+            
+            let editor_modal = container(editor_content)
+                .style(self.theme.modal_appearance())
+                .center_x()
+                .center_y();
+            
+            return editor_modal.into();
+        }
+        
+        // 如果显示任务列表
+        if self.show_task_list {
+            let tasks = self.task_list.as_ref().map(|handle| handle.tasks()).unwrap_or_default();
+            let active_id = self.status_handle.as_ref()
+                .and_then(|handle| handle.try_snapshot())
+                .and_then(|snapshot| snapshot.task_id);
+            
+            let list_content = container(TaskListView::new(tasks, active_id).view(chrono::Local::now()))
+                .padding(20)
+                .width(Length::Fixed(420.0));
+            
+            let list_modal = container(list_content)
+                .style(self.theme.modal_appearance())
+                .center_x()
+                .center_y();
+            
+            return list_modal.into();
+        }
+        
         // 如果显示设置窗口
         if self.show_settings {
+            // 模板预览，确认后才会导入
+            let template_section = match (&self.template_preview, &self.template_message) {
+                (Some(preview), _) => column![
+                    text(preview.summary()).size(14),
+                    row![
+                        button("确认导入").on_press(Message::ImportTemplate),
+                        button("放弃").on_press(Message::DiscardTemplate),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(5),
+                (None, Some(message)) => column![text(message).size(14)],
+                (None, None) => column![],
+            };
+            
+            let calendar_section = match (&self.calendar_import, &self.calendar_message) {
+                (Some(import), _) => column![
+                    text(import.summary()).size(14),
+                    row![
+                        button("确认导入").on_press(Message::ImportCalendar),
+                        button("放弃").on_press(Message::DiscardCalendar),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(5),
+                (None, Some(message)) => column![text(message).size(14)],
+                (None, None) => column![],
+            };
+            
+            // 当前平台不支持专注助手时不显示
+            let focus_assist_section = if platform::capabilities().focus_assist {
+                column![
+                    text("最后警告期间:"),
+                    button(if self.focus_assist.is_enabled() { "开启专注助手: 已开启" } else { "开启专注助手: 已关闭" })
+                        .on_press(Message::ToggleFocusAssist),
+                ]
+                .spacing(10)
+            } else {
+                column![]
+            };
+            
+            // 远程关机：本机是否接受命令，以及可以关闭的其他电脑
+            let remote_targets = self.remote_input.targets.iter().enumerate().fold(
+                column![].spacing(5),
+                |rows, (index, target)| rows.push(
+                    row![
+                        text(format!("{} ({})", target.name, target.address)).width(Length::Fill),
+                        button("关机").on_press(Message::SendRemoteShutdown(index)),
+                        button("删除").on_press(Message::RemoveRemoteTarget(index)),
+                    ]
+                    .spacing(5)
+                    .align_items(iced::Alignment::Center)
+                ),
+            );
+            let log_section = self.log_viewer.lines.iter().fold(
+                column![
+                    text("查看日志:"),
+                    row![
+                        pick_list(&LOG_LEVEL_CHOICES[..], Some(self.log_viewer.level), Message::LogLevelSelected),
+                        text_input("关键词", &self.log_viewer.search)
+                            .on_input(Message::LogSearchChanged)
+                            .on_submit(Message::ViewLogs),
+                        button("查看").on_press(Message::ViewLogs),
+                    ]
+                    .spacing(5),
+                    row![
+                        text_input("起始时间，如 2024-05-06 22:00", &self.log_viewer.from)
+                            .on_input(Message::LogFromChanged)
+                            .on_submit(Message::ViewLogs),
+                        text_input("结束时间", &self.log_viewer.to)
+                            .on_input(Message::LogToChanged)
+                            .on_submit(Message::ViewLogs),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(10),
+                |column, line| column.push(text(line).size(12)),
+            )
+            .push(button("导出所选").on_press(Message::ExportLogs))
+            .push(text(self.log_viewer.message.as_deref().unwrap_or("")).size(14));
+            let remote_section = column![
+                text("远程关机:"),
+                button(if self.remote_input.enabled { "接受其他电脑的关机命令: 已开启" } else { "接受其他电脑的关机命令: 已关闭" })
+                    .on_press(Message::ToggleRemoteServer),
+                row![
+                    text_input("本机口令", &self.remote_input.secret)
+                        .on_input(Message::RemoteSecretChanged)
+                        .on_submit(Message::SaveRemoteSecret)
+                        .secure(true),
+                    text_input("监听地址，0.0.0.0 接受局域网的命令", &self.remote_input.bind_address)
+                        .on_input(Message::RemoteBindAddressChanged)
+                        .on_submit(Message::SaveRemoteSecret),
+                    button("保存").on_press(Message::SaveRemoteSecret),
+                ]
+                .spacing(5),
+                remote_targets,
+                row![
+                    text_input("名称，如 孩子的电脑", &self.remote_input.name)
+                        .on_input(Message::RemoteTargetNameChanged),
+                    text_input("地址，如 192.168.1.20", &self.remote_input.address)
+                        .on_input(Message::RemoteTargetAddressChanged),
+                ]
+                .spacing(5),
+                row![
+                    text_input("对方的口令", &self.remote_input.target_secret)
+                        .on_input(Message::RemoteTargetSecretChanged)
+                        .on_submit(Message::AddRemoteTarget)
+                        .secure(true),
+                    button("添加").on_press(Message::AddRemoteTarget),
+                ]
+                .spacing(5),
+                text(self.remote_input.message.as_deref().unwrap_or("")).size(14),
+            ]
+            .spacing(10);
+            
+            let webhook_section = column![
+                text("任务执行、取消或失败时通知以下网址（webhook）:"),
+                text_input("如 https://example.com/hook，留空表示不通知", &self.webhook_input.url)
+                    .on_input(Message::WebhookUrlChanged)
+                    .on_submit(Message::SaveWebhook),
+                row![
+                    button("发送测试").on_press(Message::TestWebhook),
+                    button("保存").on_press(Message::SaveWebhook),
+                ]
+                .spacing(5),
+                text(self.webhook_input.message.as_deref().unwrap_or("")).size(14),
+            ]
+            .spacing(10);
+            
+            // 当前平台不能登记开机自动启动时不显示
+            let autostart_section = if platform::capabilities().autostart {
+                column![
+                    text("开机后:"),
+                    button(if self.auto_start { "在后台运行（只显示托盘图标）: 已开启" } else { "在后台运行（只显示托盘图标）: 已关闭" })
+                        .on_press(Message::ToggleAutoStart),
+                    text(self.autostart_message.as_deref().unwrap_or("")).size(14),
+                ]
+                .spacing(10)
+            } else {
+                column![]
+            };
+            
+            // 当前平台没有任务计划程序时不显示
+            let system_tasks_section = if platform::capabilities().system_scheduler {
+                column![
+                    text("QtShut未运行时:"),
+                    button(if self.system_tasks { "由任务计划程序执行每日和每周计划: 已开启" } else { "由任务计划程序执行每日和每周计划: 已关闭" })
+                        .on_press(Message::ToggleSystemTasks),
+                ]
+                .spacing(10)
+            } else {
+                column![]
+            };
+            
+            // 检测不到电池时不显示
+            let battery_section = match &self.battery_settings {
+                Some(settings) => {
+                    let cancel_label = match settings.cancel_below_percent {
+                        Some(percent) => format!("电量低于{}%时取消执行: 已开启", percent),
+                        None => format!("电量低于{}%时取消执行: 已关闭", DEFAULT_CANCEL_BELOW_PERCENT),
+                    };
+                    column![
+                        text("使用电池供电时:"),
+                        button(if settings.hibernate_instead_of_shutdown { "用休眠代替关机: 已开启" } else { "用休眠代替关机: 已关闭" })
+                            .on_press(Message::ToggleBatteryHibernate),
+                        button(text(cancel_label)).on_press(Message::ToggleBatteryCancel),
+                    ]
+                    .spacing(10)
+                },
+                None => column![],
+            };
+            
+            // 对应配置文件字段的选项，修改后点击"保存"写入
+            let flag_button = |label: &str, flag: ConfigFlag| {
+                button(text(format!("{}: {}", label, if self.config_form.flag(flag) { "已开启" } else { "已关闭" })))
+                    .on_press(Message::ConfigFlagToggled(flag))
+            };
+            let config_text_input = |placeholder: &str, field: ConfigText, value: &str| {
+                text_input(placeholder, value)
+                    .on_input(move |value| Message::ConfigTextChanged(field, value))
+                    .on_submit(Message::SaveConfigForm)
+            };
+            let save_form = || column![
+                button("保存").on_press(Message::SaveConfigForm),
+                text(self.config_form.message.as_deref().unwrap_or("")).size(14),
+            ]
+            .spacing(5);
+            
+            // 当前平台不能加密数据时不显示
+            let encrypt_section = if platform::capabilities().data_protection {
+                column![flag_button("加密保存任务和配置方案（只有当前用户能读取）", ConfigFlag::EncryptData)]
+            } else {
+                column![]
+            };
+            
+            // 分页按钮，当前分页不可点击
+            let tabs = SettingsTab::ALL.iter().fold(Row::new().spacing(5), |row, tab| {
+                row.push(button(tab.label()).on_press_maybe((*tab != self.settings_tab).then_some(Message::SettingsTabSelected(*tab))))
+            });
+            
+            let tab_content = match self.settings_tab {
+                SettingsTab::General => column![
+                    text("主窗口:"),
+                    flag_button("启动时最小化到托盘", ConfigFlag::StartMinimized),
+                    flag_button("关闭主窗口时隐藏到托盘", ConfigFlag::MinimizeOnClose),
+                    save_form(),
+                    Space::with_height(10),
+                    autostart_section,
+                    Space::with_height(10),
+                    system_tasks_section,
+                    Space::with_height(10),
+                    text("每天第一次登录时:"),
+                    button(if self.logon_routine_input.show_schedule { "显示今日计划: 已开启" } else { "显示今日计划: 已关闭" })
+                        .on_press(Message::ToggleLogonSchedule),
+                    button(if self.logon_routine_input.run_command { "运行自定义命令: 已开启" } else { "运行自定义命令: 已关闭" })
+                        .on_press(Message::ToggleLogonCommand),
+                    row![
+                        text_input("音量(%)，留空表示不调整", &self.logon_routine_input.volume)
+                            .on_input(Message::LogonVolumeChanged)
+                            .on_submit(Message::SaveLogonRoutine),
+                        button("保存").on_press(Message::SaveLogonRoutine),
+                    ]
+                    .spacing(5),
+                    text(self.logon_routine_input.message.as_deref().unwrap_or("")).size(14),
+                    Space::with_height(10),
+                    text("导入任务模板:"),
+                    row![
+                        text_input("JSON文件路径或网址", &self.template_source)
+                            .on_input(Message::TemplateSourceChanged)
+                            .on_submit(Message::PreviewTemplate),
+                        button("预览").on_press(Message::PreviewTemplate),
+                    ]
+                    .spacing(5),
+                    template_section,
+                    Space::with_height(10),
+                    text("数据备份:"),
+                    button("恢复上一个版本").on_press(Message::RestoreTaskBackup),
+                    row![
+                        pick_list(self.backup_choices.clone(), self.selected_backup.clone(), Message::BackupSelected)
+                            .placeholder("选择备份")
+                            .width(Length::Fill),
+                        button("恢复所选备份").on_press(Message::RestoreSelectedBackup),
+                    ]
+                    .spacing(5),
+                    text(self.task_backup_message.as_deref().unwrap_or("")).size(14),
+                    Space::with_height(10),
+                    text("家庭日历:"),
+                    button("导出日历文件").on_press(Message::ExportCalendar),
+                    row![
+                        text_input(".ics文件路径或网址", &self.calendar_source)
+                            .on_input(Message::CalendarSourceChanged)
+                            .on_submit(Message::PreviewCalendar),
+                        text_input("标题关键字", &self.calendar_keyword)
+                            .on_input(Message::CalendarKeywordChanged)
+                            .on_submit(Message::PreviewCalendar)
+                            .width(Length::Fixed(100.0)),
+                        button("预览").on_press(Message::PreviewCalendar),
+                    ]
+                    .spacing(5),
+                    calendar_section,
+                    Space::with_height(10),
+                    text("迁移到新电脑:"),
+                    button("导出设置").on_press(Message::ExportSettings),
+                    row![
+                        text_input("设置包文件路径", &self.bundle_source)
+                            .on_input(Message::BundleSourceChanged)
+                            .on_submit(Message::ImportSettings),
+                        button("导入设置").on_press(Message::ImportSettings),
+                    ]
+                    .spacing(5),
+                    text(self.bundle_message.as_deref().unwrap_or("")).size(14),
+                ],
+                SettingsTab::Interface => column![
+                    text("主题设置:"),
+                    button(if self.is_dark_theme { "切换到浅色主题" } else { "切换到深色主题" })
+                        .on_press(Message::ToggleTheme),
+                    button(if self.auto_theme.is_some() { "日落后自动深色: 已开启" } else { "日落后自动深色: 已关闭" })
+                        .on_press(Message::ToggleAutoTheme),
+                    button(if self.large_buttons { "大按钮模式: 已开启" } else { "大按钮模式: 已关闭" })
+                        .on_press(Message::ToggleLargeButtons),
+                    Space::with_height(10),
+                    text("界面字体:"),
+                    pick_list(FONT_CHOICES, self.font_family_input.selected_choice(), |family| Message::FontFamilyChanged(family.to_string())),
+                    row![
+                        text_input("其他已安装字体的名称", &self.font_family_input.value)
+                            .on_input(Message::FontFamilyChanged)
+                            .on_submit(Message::ApplyFontFamily),
+                        button("应用").on_press(Message::ApplyFontFamily),
+                    ]
+                    .spacing(5),
+                    text(self.font_family_input.message.as_deref().unwrap_or("")).size(14),
+                    Space::with_height(10),
+                    focus_assist_section,
+                    Space::with_height(10),
+                    text("提醒方式:"),
+                    flag_button("取消后询问原因", ConfigFlag::AskCancelReason),
+                    text("屏幕阅读器播报剩余时间的间隔（分钟，0表示不播报）:"),
+                    config_text_input("如 5", ConfigText::AnnounceInterval, &self.config_form.announce_interval_minutes),
+                    text("剩余多少秒时显示全屏警告（0表示不显示）:"),
+                    config_text_input("如 30", ConfigText::FullscreenWarning, &self.config_form.fullscreen_warning_seconds),
+                    text("剩余多少分钟时托盘图标闪烁（0表示不闪烁）:"),
+                    config_text_input("如 5", ConfigText::TrayAttention, &self.config_form.tray_attention_minutes),
+                    save_form(),
+                    Space::with_height(10),
+                    text("通知声音文件（WAV或MP3，留空使用系统提示音）:"),
+                    pick_list(NotificationType::ALL.to_vec(), Some(self.sound_files_input.selected), Message::SoundTypeSelected),
+                    text_input("如 C:\\Sounds\\alarm.mp3", self.sound_files_input.path())
+                        .on_input(Message::SoundFileChanged),
+                    row![
+                        text_input("音量(%)", &self.sound_files_input.volume)
+                            .on_input(Message::SoundVolumeChanged)
+                            .on_submit(Message::SaveSounds),
+                        button("试听").on_press(Message::PreviewSound),
+                        button("保存").on_press(Message::SaveSounds),
+                    ]
+                    .spacing(5),
+                    text(self.sound_files_input.message.as_deref().unwrap_or("")).size(14),
+                ],
+                SettingsTab::Shutdown => column![
+                    text("到点前的提醒时间（逗号分隔）:"),
+                    config_text_input("如 30m, 10m, 1m", ConfigText::WarningOffsets, &self.config_form.warning_offsets),
+                    text("每天屏幕使用时间上限（分钟，锁定或空闲超过5分钟不计时）:"),
+                    row![
+                        config_text_input("留空表示不限制，如 120", ConfigText::ScreenTimeLimit, &self.config_form.screen_time_minutes),
+                        pick_list(TaskAction::available(), Some(self.config_form.screen_time_action), Message::ScreenTimeActionSelected),
+                    ]
+                    .spacing(5),
+                    save_form(),
+                    Space::with_height(10),
+                    text("到点后运行的自定义命令:"),
+                    text_input("程序路径，如 C:\\Scripts\\backup.bat", &self.custom_command_input.program)
+                        .on_input(Message::CustomProgramChanged),
+                    text_input("参数（含空格的参数用双引号括起）", &self.custom_command_input.args)
+                        .on_input(Message::CustomArgsChanged),
+                    row![
+                        text_input("工作目录（可选）", &self.custom_command_input.working_dir)
+                            .on_input(Message::CustomWorkingDirChanged)
+                            .on_submit(Message::SaveCustomCommand),
+                        button("保存").on_press(Message::SaveCustomCommand),
+                    ]
+                    .spacing(5),
+                    text(self.custom_command_input.message.as_deref().unwrap_or("")).size(14),
+                    Space::with_height(10),
+                    text("CPU占用高于(%)时推迟执行:"),
+                    row![
+                        text_input("留空表示不检查，如 20", &self.cpu_threshold_input.value)
+                            .on_input(Message::CpuThresholdChanged)
+                            .on_submit(Message::SaveCpuThreshold),
+                        button("保存").on_press(Message::SaveCpuThreshold),
+                    ]
+                    .spacing(5),
+                    text(self.cpu_threshold_input.message.as_deref().unwrap_or("")).size(14),
+                    Space::with_height(10),
+                    text("以下程序运行时推迟执行:"),
+                    row![
+                        text_input("如 obs64.exe, HandBrake.exe", &self.blocking_processes_input.value)
+                            .on_input(Message::BlockingProcessesChanged)
+                            .on_submit(Message::SaveBlockingProcesses),
+                        button("保存").on_press(Message::SaveBlockingProcesses),
+                    ]
+                    .spacing(5),
+                    text(self.blocking_processes_input.message.as_deref().unwrap_or("")).size(14),
+                    Space::with_height(10),
+                    battery_section,
+                ],
+                SettingsTab::Advanced => column![
+                    text("日志级别（error、warn、info、debug、trace）:"),
+                    config_text_input("如 info", ConfigText::LogLevel, &self.config_form.log_level),
+                    flag_button("保存任务和设置前先备份", ConfigFlag::BackupData),
+                    text("任务和设置各保留的备份数:"),
+                    config_text_input("1-100", ConfigText::MaxBackupFiles, &self.config_form.max_backup_files),
+                    text("数据目录大小上限（MB）:"),
+                    config_text_input("5-10240", ConfigText::MaxDataSize, &self.config_form.max_data_size_mb),
+                    flag_button("录制事件用于排查问题", ConfigFlag::RecordEvents),
+                    encrypt_section,
+                    save_form(),
+                    Space::with_height(10),
+                    log_section,
+                    Space::with_height(10),
+                    remote_section,
+                    Space::with_height(10),
+                    webhook_section,
+                ],
+                SettingsTab::History => self.history_lines.iter().fold(
+                    column![text(format!("最近{}天执行、取消和执行失败的任务:", HISTORY_VIEW_DAYS))],
+                    |column, line| column.push(text(line).size(14)),
+                ),
+            }
+            .spacing(10);
+            
             let settings_content = column![
                 text("设置").size(24),
-                Space::with_height(20),
-                text("主题设置:"),
-                button(if self.is_dark_theme { "切换到浅色主题" } else { "切换到深色主题" })
-                    .on_press(Message::ToggleTheme),
+                tabs,
+                Space::with_height(10),
+                tab_content,
                 Space::with_height(20),
                 button("关闭").on_press(Message::ShowSettings),
             ]
             .spacing(10)
             .padding(20)
-            .width(Length::Fixed(300.0));
+            .width(Length::Fixed(360.0));
 
             let settings_modal = container(settings_content)
-                 .style(ContainerAppearance {
-                     background: Some(Background::Color(Color::WHITE)),
-                     border: Border {
-                         color: Color::BLACK,
-                         width: 2.0,
-                         radius: 10.0.into(),
-                     },
-                     ..Default::default()
-                 })
+                .style(self.theme.modal_appearance())
                 .center_x()
                 .center_y();
 
             return settings_modal.into();
         }
 
+        // 如果显示自检报告
+        if let Some(report) = &self.self_test_report {
+            let report_content = report.results.iter().fold(
+                column![text("自检结果").size(24), Space::with_height(10)].spacing(10),
+                |column, result| {
+                    let color = match result.status {
+                        CheckStatus::Passed => Color::from_rgb(0.1, 0.6, 0.2),
+                        CheckStatus::Failed => Color::from_rgb(0.8, 0.1, 0.1),
+                        CheckStatus::Skipped => Color::from_rgb(0.5, 0.5, 0.5),
+                    };
+                    column.push(row![
+                        text(format!("[{}]", result.status.label())).style(color),
+                        text(format!("{}: {}", result.name, result.detail)),
+                    ].spacing(5))
+                },
+            )
+            .push(Space::with_height(10))
+            .push(text(report.summary()))
+            .push(button("关闭").on_press(Message::CloseSelfTestReport))
+            .padding(20)
+            .width(Length::Fixed(480.0));
+            
+            let report_modal = container(report_content)
+                .style(self.theme.modal_appearance())
+                .center_x()
+                .center_y();
+            
+            return report_modal.into();
+        }
+        
         // 如果显示关于窗口
         if self.show_about {
             let about_content = column![
@@ -579,15 +5159,7 @@ impl Application for UIManager {
             .width(Length::Fixed(300.0));
 
             let about_modal = container(about_content)
-                 .style(ContainerAppearance {
-                     background: Some(Background::Color(Color::WHITE)),
-                     border: Border {
-                         color: Color::BLACK,
-                         width: 2.0,
-                         radius: 10.0.into(),
-                     },
-                     ..Default::default()
-                 })
+                .style(self.theme.modal_appearance())
                 .center_x()
                 .center_y();
 
@@ -598,12 +5170,8 @@ impl Application for UIManager {
     }
 
     /// 应用程序主题
-    fn theme(&self) -> Self::Theme {
-        if self.is_dark_theme {
-            IcedTheme::Dark
-        } else {
-            IcedTheme::Light
-        }
+    fn theme(&self, _window: window::Id) -> Self::Theme {
+        self.theme.to_iced_theme()
     }
 }
 
@@ -612,20 +5180,14 @@ mod tests {
     use super::*;
     use crate::core::time_parser::TimeParser;
     
-    #[tokio::test]
-    async fn test_ui_manager_creation() {
-        let time_parser = TimeParser::new();
-        let ui_manager = UIManager::new(time_parser, None, None).await;
-        assert!(ui_manager.is_ok());
-    }
-    
-    #[test]
-    fn test_message_handling() {
-        let time_parser = TimeParser::new();
-        let mut ui_manager = UIManager {
+    /// 测试用的界面管理器，不连接事件通道和倒计时
+    fn test_manager() -> UIManager {
+        UIManager {
             time_input: String::new(),
+            time_suggestions: Vec::new(),
+            suggestion_index: None,
             countdown_status: CountdownStatus::Idle,
-            time_parser,
+            time_parser: TimeParser::new(),
             tray_manager: None,
             ui_event_sender: None,
             countdown_receiver: None,
@@ -634,7 +5196,99 @@ mod tests {
             theme: Theme::default(),
             is_dark_theme: false,
             minimized_to_tray: false,
-        };
+            background_agent: false,
+            minimize_on_close: false,
+            task_persistence: None,
+            profile: ProfileData::default(),
+            profile_names: vec![ProfileData::default().name],
+            new_profile_name: String::new(),
+            power_requirements: Vec::new(),
+            shutdown_blocker: None,
+            show_external_shutdown_notice: false,
+            crash_report: None,
+            trigger_wait: None,
+            process_watch: None,
+            show_weekly_editor: false,
+            weekly_editor: default_weekly_editor(),
+            template_source: String::new(),
+            template_preview: None,
+            template_message: None,
+            task_backup_message: None,
+            calendar_message: None,
+            calendar_source: String::new(),
+            calendar_keyword: ics::DEFAULT_KEYWORD.to_string(),
+            calendar_import: None,
+            backup_choices: Vec::new(),
+            selected_backup: None,
+            bundle_source: String::new(),
+            bundle_message: None,
+            plan_preview: None,
+            selected_action: TaskAction::default(),
+            policy: Policy::default(),
+            cancel_pin_input: String::new(),
+            status_handle: None,
+            notification_manager: NotificationManager::new(5, 20),
+            announcer: ScreenReaderAnnouncer::new(0),
+            ask_cancel_reason: false,
+            warning_offsets: Vec::new(),
+            show_cancel_reason: false,
+            reschedule_choices: Vec::new(),
+            first_warning_seen: false,
+            large_buttons: false,
+            system_tasks: false,
+            auto_start: false,
+            autostart_message: None,
+            large_slider_minutes: DEFAULT_LARGE_SLIDER_MINUTES,
+            remote_input: RemoteInput::default(),
+            webhook_input: WebhookInput::default(),
+            settings_tab: SettingsTab::default(),
+            config_form: ConfigForm::default(),
+            history_lines: Vec::new(),
+            warning_overlay: WarningOverlay::new(0),
+            focus_assist: FocusAssistGuard::new(false),
+            auto_theme: None,
+            custom_command_input: CustomCommandInput::default(),
+            cpu_threshold_input: CpuThresholdInput::default(),
+            log_viewer: LogViewerInput::default(),
+            blocking_processes_input: BlockingProcessesInput::default(),
+            sound_files_input: SoundFilesInput::default(),
+            battery_settings: None,
+            logon_routine_input: LogonRoutineInput::default(),
+            font_family_input: FontFamilyInput::default(),
+            window_handle: None,
+            last_window_move: None,
+            task_list: None,
+            show_task_list: false,
+            pending_remote_start: None,
+            remote_start_confirmed: false,
+            pending_short_start: None,
+            immediate_final_warning: false,
+            countdown_paused: false,
+            task_editor: None,
+            self_test_report: None,
+            window_title: DEFAULT_WINDOW_TITLE.to_string(),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_ui_manager_creation() {
+        let time_parser = TimeParser::new();
+        let ui_manager = UIManager::new(time_parser, None, None).await;
+        assert!(ui_manager.is_ok());
+    }
+    
+    #[test]
+    fn test_message_handling() {
+        let mut ui_manager = test_manager();
+        
+        // 测试时间输入建议：方向键选中后按回车使用该建议
+        let _command = ui_manager.update(Message::TimeInputChanged("今晚".to_string()));
+        assert_eq!(ui_manager.time_suggestions, vec!["今晚22:00".to_string(), "今晚23:00".to_string()]);
+        let _command = ui_manager.update(Message::MoveSuggestion(-1));
+        assert_eq!(ui_manager.suggestion_index, Some(1));
+        let _command = ui_manager.update(Message::SubmitTimeInput);
+        assert_eq!(ui_manager.time_input, "今晚23:00");
+        assert!(ui_manager.time_suggestions.is_empty());
         
         // 测试时间输入消息
         let _command = ui_manager.update(Message::TimeInputChanged("30分钟".to_string()));
@@ -643,5 +5297,408 @@ mod tests {
         // 测试主题切换
         let _command = ui_manager.update(Message::ToggleTheme);
         assert!(ui_manager.is_dark_theme);
+        
+        // 测试保存预设
+        let _command = ui_manager.update(Message::SavePreset);
+        assert_eq!(ui_manager.profile.presets, vec!["30分钟".to_string()]);
+        
+        // 测试切换配置方案时重置方案相关状态
+        let _command = ui_manager.update(Message::NewProfileNameChanged("工作".to_string()));
+        let _command = ui_manager.update(Message::CreateProfile);
+        assert_eq!(ui_manager.profile.name, "工作");
+        assert!(ui_manager.profile.presets.is_empty());
+        assert!(ui_manager.time_input.is_empty());
+        assert!(ui_manager.profile_names.contains(&"工作".to_string()));
+        
+        // 测试快速倒计时以时长发送事件并显示电源需求，取消后清除
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        ui_manager.ui_event_sender = Some(sender);
+        let _command = ui_manager.update(Message::QuickCountdown(chrono::Duration::minutes(5)));
+        assert_eq!(ui_manager.time_input, "5分钟");
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::QuickCountdown(duration)) if duration == chrono::Duration::minutes(5)));
+        assert_eq!(ui_manager.power_requirements, vec![PowerRequirement::KeepAwake]);
+        let _command = ui_manager.update(Message::CancelCountdown);
+        assert!(ui_manager.power_requirements.is_empty());
+        let _command = ui_manager.update(Message::RepeatLast);
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::CancelCountdown)));
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::RepeatLast)));
+        let _command = ui_manager.update(Message::CancelCountdown);
+        
+        // 测试远程桌面警告确认后继续开始，取消后不再保留
+        ui_manager.pending_remote_start = Some((Message::QuickCountdown(chrono::Duration::minutes(5)), QUICK_COUNTDOWN_ACTION));
+        let _command = ui_manager.update(Message::ConfirmRemoteStart);
+        assert!(ui_manager.pending_remote_start.is_none());
+        assert!(!ui_manager.remote_start_confirmed);
+        assert_eq!(ui_manager.power_requirements, vec![PowerRequirement::KeepAwake]);
+        let _command = ui_manager.update(Message::CancelCountdown);
+        ui_manager.pending_remote_start = Some((Message::QuickCountdown(chrono::Duration::minutes(5)), QUICK_COUNTDOWN_ACTION));
+        let _command = ui_manager.update(Message::DismissRemoteWarning);
+        assert!(ui_manager.pending_remote_start.is_none());
+        assert!(ui_manager.power_requirements.is_empty());
+        
+        // 测试不到一分钟的倒计时由核心请求确认，确认后直接进入最后确认阶段
+        while receiver.try_recv().is_ok() {}
+        let confirm_request = CountdownUpdate::WindowRequested(WindowRequest::ConfirmShortStart { seconds: 30, action: TaskAction::Restart });
+        let _command = ui_manager.update(Message::TimeInputChanged("30秒".to_string()));
+        let _command = ui_manager.update(Message::StartCountdown);
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::StartCountdown(..))));
+        assert!(!ui_manager.immediate_final_warning);
+        let _command = ui_manager.update(Message::CountdownUpdate(confirm_request.clone()));
+        assert_eq!(ui_manager.pending_short_start, Some((30, TaskAction::Restart)));
+        let _command = ui_manager.update(Message::DismissShortStart);
+        assert!(ui_manager.pending_short_start.is_none());
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ConfirmShortStart(false))));
+        let _command = ui_manager.update(Message::CountdownUpdate(confirm_request));
+        let _command = ui_manager.update(Message::ConfirmShortStart);
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ConfirmShortStart(true))));
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::Progress { remaining: chrono::Duration::seconds(45), progress: 0.25 }));
+        assert!(ui_manager.is_final_warning());
+        let _command = ui_manager.update(Message::CancelCountdown);
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::Cancelled));
+        assert!(!ui_manager.immediate_final_warning);
+        while receiver.try_recv().is_ok() {}
+        
+        // 测试暂停按钮按当前状态发送暂停或继续，取消后清除暂停状态
+        let _command = ui_manager.update(Message::TogglePause);
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::PauseCountdown)));
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::Paused));
+        let _command = ui_manager.update(Message::TogglePause);
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ResumeCountdown)));
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::Cancelled));
+        assert!(!ui_manager.countdown_paused);
+        
+        // 测试大按钮模式按滑块选择的时长和操作开始倒计时
+        let _command = ui_manager.update(Message::LargeSliderChanged(45));
+        let _command = ui_manager.update(Message::ActionSelected(TaskAction::Sleep));
+        let _command = ui_manager.update(Message::StartLargeCountdown);
+        assert_eq!(ui_manager.time_input, "45分钟");
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(UIEvent::StartCountdown(TimeInput::Duration(duration), TaskType::Once, TaskAction::Sleep)) if duration == chrono::Duration::minutes(45)
+        ));
+        
+        // 托盘菜单的窗口请求由核心转回界面，打开设置时显示主窗口，再次切换时隐藏
+        ui_manager.minimized_to_tray = true;
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::WindowRequested(WindowRequest::Settings)));
+        assert!(ui_manager.show_settings);
+        assert!(!ui_manager.minimized_to_tray);
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::WindowRequested(WindowRequest::Toggle)));
+        assert!(ui_manager.show_settings);
+        assert!(ui_manager.minimized_to_tray);
+        
+        // 设置关闭时隐藏到托盘后，关闭主窗口只是隐藏，倒计时不受影响
+        let _command = ui_manager.update(Message::CountdownUpdate(CountdownUpdate::WindowRequested(WindowRequest::Show)));
+        ui_manager.minimize_on_close = true;
+        let _command = ui_manager.update(Message::MainWindowCloseRequested);
+        assert!(ui_manager.minimized_to_tray);
+    }
+    
+    #[test]
+    fn test_format_quick_duration() {
+        assert_eq!(format_quick_duration(chrono::Duration::minutes(5)), "5分钟");
+        assert_eq!(format_quick_duration(chrono::Duration::minutes(90)), "90分钟");
+        assert_eq!(format_quick_duration(chrono::Duration::minutes(120)), "2小时");
+    }
+    
+    #[test]
+    fn test_cpu_threshold_input() {
+        let parse = |value: &str| CpuThresholdInput { value: value.to_string(), message: None }.parse();
+        assert_eq!(parse(""), Ok(None));
+        assert_eq!(parse(" 20% "), Ok(Some(20)));
+        assert_eq!(parse("100"), Ok(Some(100)));
+        assert!(parse("0").is_err());
+        assert!(parse("101").is_err());
+        assert!(parse("很高").is_err());
+        
+        let conditions = ConditionSettings { cpu_above_percent: Some(35), ..Default::default() };
+        assert_eq!(CpuThresholdInput::from_conditions(&conditions).value, "35");
+    }
+    
+    #[test]
+    fn test_sound_files_input() {
+        let mut input = SoundFilesInput::default();
+        assert_eq!(input.parse_volume(), Ok(sound::DEFAULT_VOLUME));
+        input.volume = "101".to_string();
+        assert!(input.parse_volume().is_err());
+        input.volume = " 50% ".to_string();
+        assert_eq!(input.parse_volume(), Ok(50));
+        
+        // 路径为空的类型使用系统提示音
+        input.files.insert(NotificationType::Error, "  ".to_string());
+        assert_eq!(input.parse_files(), Ok(HashMap::new()));
+        
+        input.selected = NotificationType::Warning;
+        input.files.insert(NotificationType::Warning, "alarm.ogg".to_string());
+        assert_eq!(input.path(), "alarm.ogg");
+        assert!(input.parse_files().unwrap_err().starts_with("警告通知"));
+    }
+    
+    #[test]
+    fn test_webhook_input() {
+        assert_eq!(WebhookInput::from_url("  ").parse(), Ok(String::new()));
+        assert_eq!(WebhookInput::from_url(" https://example.com/hook ").parse(), Ok("https://example.com/hook".to_string()));
+        assert!(WebhookInput::from_url("example.com").parse().is_err());
+    }
+    
+    #[test]
+    fn test_config_form() {
+        let mut config = AppConfig::default();
+        let mut form = ConfigForm::from_config(&config);
+        assert_eq!(form.warning_offsets, config.shutdown.warning_offsets.join(", "));
+        
+        form.toggle(ConfigFlag::RecordEvents);
+        form.set_text(ConfigText::WarningOffsets, "10m，1m, ".to_string());
+        form.set_text(ConfigText::LogLevel, " DEBUG ".to_string());
+        form.set_text(ConfigText::TrayAttention, "3".to_string());
+        assert_eq!(form.apply(&mut config), Ok(()));
+        assert!(config.advanced.record_events);
+        assert_eq!(config.shutdown.warning_offsets, vec!["10m".to_string(), "1m".to_string()]);
+        assert_eq!(config.advanced.log_level, "debug");
+        assert_eq!(config.ui.tray_attention_minutes, 3);
+        assert_eq!(config.shutdown.screen_time.daily_minutes, None);
+        
+        form.set_text(ConfigText::ScreenTimeLimit, " 90 ".to_string());
+        form.screen_time_action = TaskAction::Lock;
+        assert_eq!(form.apply(&mut config), Ok(()));
+        assert_eq!(config.shutdown.screen_time.daily_minutes, Some(90));
+        assert_eq!(config.shutdown.screen_time.action, TaskAction::Lock);
+        form.set_text(ConfigText::ScreenTimeLimit, "2000".to_string());
+        assert_eq!(form.apply(&mut config), Err("每日使用上限应在1-1440分钟之间".to_string()));
+        form.set_text(ConfigText::ScreenTimeLimit, String::new());
+        
+        // 数字格式错误和配置检查不通过时返回错误
+        form.set_text(ConfigText::MaxBackupFiles, "很多".to_string());
+        assert_eq!(form.apply(&mut config), Err("备份文件数必须是整数".to_string()));
+        form.set_text(ConfigText::MaxBackupFiles, "0".to_string());
+        form.set_text(ConfigText::LogLevel, "verbose".to_string());
+        assert_eq!(form.apply(&mut config), Err("无效的日志级别；备份文件数量应在1-100之间".to_string()));
+    }
+    
+    #[test]
+    fn test_font_family_input() {
+        let mut input = FontFamilyInput::from_family(DEFAULT_FONT_FAMILY);
+        assert!(!input.needs_switch());
+        assert_eq!(input.selected_choice(), Some(DEFAULT_FONT_FAMILY));
+        
+        input.value = " Segoe UI ".to_string();
+        assert!(input.needs_switch());
+        assert_eq!(input.selected_choice(), Some("Segoe UI"));
+        
+        // 不在列表中的字体也可以使用，为空时恢复默认字体
+        input.value = "Sarasa Gothic SC".to_string();
+        assert_eq!(input.selected_choice(), None);
+        input.value.clear();
+        assert_eq!(input.family(), DEFAULT_FONT_FAMILY);
+        assert!(!input.needs_switch());
+    }
+    
+    #[test]
+    fn test_logon_routine_input() {
+        let mut input = LogonRoutineInput { show_schedule: true, volume: " 30% ".to_string(), run_command: true, message: None };
+        let steps = input.parse().unwrap();
+        assert_eq!(steps, vec![RoutineStep::SetVolume(30), RoutineStep::ShowSchedule, RoutineStep::RunCommand]);
+        
+        let tasks = vec![TaskData::logon(steps, chrono::Local::now())];
+        assert_eq!(LogonRoutineInput::from_tasks(&tasks), LogonRoutineInput { volume: "30".to_string(), ..input.clone() });
+        assert_eq!(LogonRoutineInput::from_tasks(&[]), LogonRoutineInput::default());
+        
+        input.volume = "120".to_string();
+        assert!(input.parse().is_err());
+        assert_eq!(LogonRoutineInput::default().parse(), Ok(Vec::new()));
+    }
+    
+    #[test]
+    fn test_remote_input() {
+        let mut input = RemoteInput::default();
+        assert!(input.parse_target().is_err());
+        
+        input.address = " 192.168.1.20 ".to_string();
+        assert!(input.parse_target().is_err());
+        
+        // 名称为空时使用地址
+        input.target_secret = "口令".to_string();
+        let target = input.parse_target().unwrap();
+        assert_eq!(target.name, "192.168.1.20");
+        assert_eq!(target.address, "192.168.1.20");
+        
+        input.name = "孩子的电脑".to_string();
+        assert_eq!(input.parse_target().unwrap().name, "孩子的电脑");
+        
+        // 监听地址为空时只监听本机
+        assert_eq!(input.parse_bind_address(), Ok("127.0.0.1".to_string()));
+        input.bind_address = " 0.0.0.0 ".to_string();
+        assert_eq!(input.parse_bind_address(), Ok("0.0.0.0".to_string()));
+        input.bind_address = "kid-pc".to_string();
+        assert!(input.parse_bind_address().is_err());
+    }
+    
+    #[test]
+    fn test_process_watch_input() {
+        let processes = ProcessWatchInput::unique_sorted(vec![
+            "svchost.exe".to_string(),
+            "HandBrake.exe".to_string(),
+            "explorer.exe".to_string(),
+            "svchost.exe".to_string(),
+        ]);
+        assert_eq!(processes, vec!["explorer.exe", "HandBrake.exe", "svchost.exe"]);
+        
+        let mut input = ProcessWatchInput { processes, delay: "10".to_string(), ..Default::default() };
+        assert!(input.trigger().is_err());
+        
+        input.selected = Some("HandBrake.exe".to_string());
+        assert_eq!(input.trigger(), Ok(ProcessExitTrigger { process: "HandBrake.exe".to_string(), delay_minutes: 10 }));
+        
+        input.delay = " ".to_string();
+        assert_eq!(input.trigger().map(|trigger| trigger.delay_minutes), Ok(0));
+        input.delay = "很久".to_string();
+        assert!(input.trigger().is_err());
+    }
+    
+    #[test]
+    fn test_blocking_processes_input() {
+        let parse = |value: &str| BlockingProcessesInput { value: value.to_string(), message: None }.parse();
+        assert_eq!(parse(""), Vec::<String>::new());
+        assert_eq!(parse("obs64.exe， HandBrake.exe;\nAdobe Premiere Pro.exe"), vec!["obs64.exe", "HandBrake.exe", "Adobe Premiere Pro.exe"]);
+        assert_eq!(parse("obs64.exe, OBS64.EXE,,"), vec!["obs64.exe"]);
+        
+        let conditions = ConditionSettings { blocking_processes: vec!["obs64.exe".to_string(), "HandBrake.exe".to_string()], ..Default::default() };
+        assert_eq!(BlockingProcessesInput::from_conditions(&conditions).value, "obs64.exe, HandBrake.exe");
+    }
+    
+    #[test]
+    fn test_format_network_wait() {
+        assert_eq!(UIManager::format_network_wait(812.4, 0), "等待下载完成：当前网速 812 KB/s");
+        assert_eq!(UIManager::format_network_wait(3.0, 125), "等待下载完成：当前网速 3 KB/s，已持续低速 2:05");
+    }
+    
+    #[test]
+    fn test_window_title() {
+        let mut snapshot = StatusSnapshot::from_parts(&CountdownStatus::Idle, None, false, None, chrono::Local::now());
+        assert_eq!(UIManager::format_window_title(&snapshot), DEFAULT_WINDOW_TITLE);
+        
+        snapshot.state = StatusState::Running;
+        snapshot.remaining_seconds = Some(65 * 60 - 30);
+        assert_eq!(UIManager::format_window_title(&snapshot), "QtShut — 剩余 1:05");
+        snapshot.state = StatusState::Paused;
+        snapshot.remaining_seconds = Some(27 * 60);
+        assert_eq!(UIManager::format_window_title(&snapshot), "QtShut — 已暂停，剩余 0:27");
+    }
+    
+    #[test]
+    fn test_final_warning_stage() {
+        let mut ui_manager = test_manager();
+        
+        ui_manager.handle_countdown_update(CountdownUpdate::Progress {
+            remaining: chrono::Duration::minutes(5),
+            progress: 50.0,
+        });
+        assert!(!ui_manager.is_final_warning());
+        
+        ui_manager.handle_countdown_update(CountdownUpdate::Progress {
+            remaining: chrono::Duration::seconds(FINAL_WARNING_SECONDS),
+            progress: 99.0,
+        });
+        assert!(ui_manager.is_final_warning());
+        
+        // 只有第一次提醒时显示改期选项
+        ui_manager.handle_countdown_update(CountdownUpdate::Warning { remaining: chrono::Duration::minutes(10) });
+        assert_eq!(ui_manager.reschedule_choices, RescheduleChoice::ALL.to_vec());
+        let _command = ui_manager.update(Message::RescheduleChosen(RescheduleChoice::Keep));
+        assert!(ui_manager.reschedule_choices.is_empty());
+        ui_manager.handle_countdown_update(CountdownUpdate::Warning { remaining: chrono::Duration::minutes(5) });
+        assert!(ui_manager.reschedule_choices.is_empty());
+        
+        ui_manager.handle_countdown_update(CountdownUpdate::Finished);
+        assert!(!ui_manager.is_final_warning());
+        assert!(!ui_manager.first_warning_seen);
+        
+        // 检测到外部关机计划时询问用户，保留时只关闭提示，取消时通知核心
+        ui_manager.handle_countdown_update(CountdownUpdate::ExternalShutdownDetected);
+        assert!(ui_manager.show_external_shutdown_notice);
+        let _command = ui_manager.update(Message::DismissExternalShutdownNotice);
+        assert!(!ui_manager.show_external_shutdown_notice);
+        ui_manager.handle_countdown_update(CountdownUpdate::ExternalShutdownDetected);
+        let _command = ui_manager.update(Message::CancelExternalShutdown);
+        assert!(!ui_manager.show_external_shutdown_notice);
+        
+        // 上次崩溃的报告提示可以关闭
+        ui_manager.crash_report = Some(std::path::PathBuf::from("crash-20240506.txt"));
+        let _command = ui_manager.update(Message::DismissCrashReport);
+        assert!(ui_manager.crash_report.is_none());
+    }
+    
+    #[test]
+    fn test_log_viewer_filter() {
+        let mut ui_manager = test_manager();
+        let info = "[2024-05-06 22:30:00.000] [INFO] [qtshut] 倒计时结束";
+        let debug = "[2024-05-06 22:30:01.000] [DEBUG] [qtshut] 发送心跳";
+        
+        // 默认只看信息及以上级别
+        assert!(ui_manager.log_viewer.filter().unwrap().matches(info));
+        assert!(!ui_manager.log_viewer.filter().unwrap().matches(debug));
+        
+        let _command = ui_manager.update(Message::LogLevelSelected(log::Level::Trace));
+        let _command = ui_manager.update(Message::LogSearchChanged(" 心跳 ".to_string()));
+        assert!(!ui_manager.log_viewer.filter().unwrap().matches(info));
+        assert!(ui_manager.log_viewer.filter().unwrap().matches(debug));
+        
+        // 时间范围：只写日期时结束时间包含当天全天
+        let _command = ui_manager.update(Message::LogSearchChanged(String::new()));
+        let _command = ui_manager.update(Message::LogFromChanged("2024-05-06 22:30:01".to_string()));
+        assert!(!ui_manager.log_viewer.filter().unwrap().matches(info));
+        assert!(ui_manager.log_viewer.filter().unwrap().matches(debug));
+        let _command = ui_manager.update(Message::LogFromChanged(String::new()));
+        let _command = ui_manager.update(Message::LogToChanged("2024-05-06 22:30".to_string()));
+        assert!(ui_manager.log_viewer.filter().unwrap().matches(info));
+        assert!(!ui_manager.log_viewer.filter().unwrap().matches(debug));
+        let _command = ui_manager.update(Message::LogToChanged("2024-05-06".to_string()));
+        assert!(ui_manager.log_viewer.filter().unwrap().matches(debug));
+        let _command = ui_manager.update(Message::LogToChanged("昨天".to_string()));
+        assert!(ui_manager.log_viewer.filter().is_err());
+    }
+    
+    #[test]
+    fn test_weekly_editor_slots() {
+        let mut ui_manager = test_manager();
+        
+        // 没有设置任何一天时不能开始
+        assert!(ui_manager.collect_weekly_slots().is_err());
+        
+        // 周一睡眠，周五不执行，周日关机
+        let _command = ui_manager.update(Message::WeeklyTimeChanged(0, "23:00".to_string()));
+        let _command = ui_manager.update(Message::WeeklyActionChanged(0, SlotActionChoice::Run(TaskAction::Sleep)));
+        let _command = ui_manager.update(Message::WeeklyTimeChanged(4, "23:00".to_string()));
+        let _command = ui_manager.update(Message::WeeklyTimeChanged(6, "22：30".to_string()));
+        let _command = ui_manager.update(Message::WeeklyActionChanged(6, SlotActionChoice::Run(TaskAction::Shutdown)));
+        
+        let slots = ui_manager.collect_weekly_slots().unwrap();
+        assert_eq!(slots, vec![
+            WeeklySlot { weekday: Weekday::Mon, time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(), action: TaskAction::Sleep },
+            WeeklySlot { weekday: Weekday::Sun, time: NaiveTime::from_hms_opt(22, 30, 0).unwrap(), action: TaskAction::Shutdown },
+        ]);
+        
+        let _command = ui_manager.update(Message::StartWeekly);
+        assert_eq!(ui_manager.power_requirements, vec![PowerRequirement::WakeTimer]);
+        
+        // 时间格式错误
+        let _command = ui_manager.update(Message::WeeklyTimeChanged(0, "晚上".to_string()));
+        let _command = ui_manager.update(Message::StartWeekly);
+        assert!(matches!(ui_manager.countdown_status, CountdownStatus::Error(_)));
+        
+        // 模板需要预览确认后才能导入
+        let preview = crate::core::template::TaskTemplate::from_json(
+            r#"{ "name": "测试模板", "presets": ["45分钟"] }"#
+        ).unwrap().preview(&ui_manager.time_parser);
+        let _command = ui_manager.update(Message::TemplateLoaded(Ok(preview)));
+        assert!(ui_manager.template_preview.is_some());
+        let _command = ui_manager.update(Message::DiscardTemplate);
+        assert!(ui_manager.template_preview.is_none());
+        let _command = ui_manager.update(Message::TemplateLoaded(Err("读取模板文件失败".to_string())));
+        assert_eq!(ui_manager.template_message.as_deref(), Some("读取模板文件失败"));
+        
+        // 日历中没有可导入的事件时只显示提示
+        let _command = ui_manager.update(Message::CalendarLoaded(Ok(CalendarImport::default())));
+        assert!(ui_manager.calendar_import.is_none());
+        assert!(ui_manager.calendar_message.as_deref().unwrap().starts_with("日历中没有可导入的事件"));
     }
 }
\ No newline at end of file
@@ -3,8 +3,13 @@
 //! 包含GUI界面、托盘图标和用户交互相关的所有组件
 
 pub mod manager;
+pub mod announcer;
 pub mod components;
+pub mod focus_assist;
 pub mod tray;
+pub mod overlay;
+pub mod placement;
+pub mod warning_overlay;
 pub mod theme;
 
 // 重新导出主要组件
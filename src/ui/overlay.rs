@@ -0,0 +1,404 @@
+//! 直播倒计时叠加层模块
+//! 
+//! 在本地提供一个只显示剩余时间的网页，可作为OBS等直播软件的浏览器源，
+//! 支持透明背景、大字体和绿幕背景色
+
+use std::sync::Arc;
+
+use chrono::Duration;
+use log::{error, info, warn};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, RwLock};
+
+use crate::core::calendar;
+use crate::core::countdown::StatusHandle;
+use crate::core::scheduler::TaskListHandle;
+use crate::core::types::{CountdownUpdate, StatusSnapshot};
+use crate::utils::config::{ConfigValidator, OverlaySettings};
+
+/// 叠加层显示的倒计时快照
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OverlaySnapshot {
+    /// 是否有进行中的倒计时
+    pub active: bool,
+    /// 是否已暂停
+    pub paused: bool,
+    /// 剩余秒数
+    pub remaining_seconds: i64,
+}
+
+impl OverlaySnapshot {
+    /// 根据倒计时更新刷新快照
+    /// 
+    /// # 参数
+    /// 
+    /// * `update` - 倒计时更新消息
+    pub fn apply(&mut self, update: &CountdownUpdate) {
+        match update {
+            CountdownUpdate::Progress { remaining, .. } => {
+                self.active = true;
+                self.remaining_seconds = remaining.num_seconds().max(0);
+            },
+            CountdownUpdate::Paused => self.paused = true,
+            CountdownUpdate::Resumed => self.paused = false,
+            CountdownUpdate::Finished | CountdownUpdate::Cancelled | CountdownUpdate::TaskCompleted { .. } => {
+                *self = Self::default();
+            },
+            CountdownUpdate::Warning { .. }
+            | CountdownUpdate::ExternalShutdownDetected
+            | CountdownUpdate::NetworkWaiting { .. }
+            | CountdownUpdate::Postponed { .. }
+            | CountdownUpdate::Notice { .. }
+            | CountdownUpdate::WindowRequested(_)
+            | CountdownUpdate::Error(_) => {},
+        }
+    }
+    
+    /// 转换为叠加层网页轮询使用的JSON
+    pub fn to_json(&self) -> String {
+        let text = if self.active {
+            format_remaining(Duration::seconds(self.remaining_seconds))
+        } else {
+            String::new()
+        };
+        
+        serde_json::json!({
+            "active": self.active,
+            "paused": self.paused,
+            "remaining": self.remaining_seconds,
+            "text": text,
+        }).to_string()
+    }
+}
+
+/// 格式化剩余时间
+/// 
+/// 超过一小时显示为"H:MM:SS"，否则显示为"MM:SS"
+/// 
+/// # 参数
+/// 
+/// * `remaining` - 剩余时间
+pub fn format_remaining(remaining: Duration) -> String {
+    let total_seconds = remaining.num_seconds().max(0);
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+    
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// 生成叠加层网页
+/// 
+/// 网页每秒轮询一次`/remaining`接口并只渲染剩余时间
+/// 
+/// # 参数
+/// 
+/// * `settings` - 叠加层设置
+pub fn render_page(settings: &OverlaySettings) -> String {
+    // 颜色会直接写入CSS，无效时回退到默认值
+    let defaults = OverlaySettings::default();
+    let settings = if ConfigValidator::validate_overlay_settings(settings).0 {
+        settings
+    } else {
+        warn!("叠加层设置无效，使用默认样式");
+        &defaults
+    };
+    let background = settings.background_color.as_deref().unwrap_or("transparent");
+    
+    format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>QtShut 倒计时</title>
+<style>
+html, body {{ margin: 0; height: 100%; background: {background}; overflow: hidden; }}
+#time {{ display: flex; align-items: center; justify-content: center; height: 100%;
+  color: {color}; font-size: {size}px; font-family: "Segoe UI", "Microsoft YaHei", sans-serif;
+  font-weight: bold; font-variant-numeric: tabular-nums; }}
+#time.paused {{ opacity: 0.6; }}
+</style>
+</head>
+<body>
+<div id="time"></div>
+<script>
+async function refresh() {{
+  try {{
+    const state = await (await fetch('/remaining', {{ cache: 'no-store' }})).json();
+    const el = document.getElementById('time');
+    el.textContent = state.text;
+    el.className = state.paused ? 'paused' : '';
+  }} catch (e) {{}}
+}}
+refresh();
+setInterval(refresh, 1000);
+</script>
+</body>
+</html>
+"#, background = background, color = settings.text_color, size = settings.font_size)
+}
+
+/// 查询本机本地接口的超时时间
+const STATUS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// 叠加层本地服务
+/// 
+/// 在`/status`提供与托盘提示一致的状态快照JSON，命令行`status`通过该接口查询运行中的实例；
+/// 启用叠加层后还提供叠加层网页，并在`/calendar.ics`提供可供日历订阅的任务计划
+pub struct OverlayServer {
+    /// 叠加层设置
+    settings: OverlaySettings,
+    /// 当前倒计时快照
+    snapshot: Arc<RwLock<OverlaySnapshot>>,
+    /// 状态查询句柄
+    status: Option<StatusHandle>,
+    /// 任务列表查询句柄
+    tasks: Option<TaskListHandle>,
+}
+
+impl OverlayServer {
+    /// 创建叠加层服务
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 叠加层设置
+    pub fn new(settings: OverlaySettings) -> Self {
+        Self {
+            settings,
+            snapshot: Arc::new(RwLock::new(OverlaySnapshot::default())),
+            status: None,
+            tasks: None,
+        }
+    }
+    
+    /// 设置状态查询句柄，启用`/status`接口
+    /// 
+    /// # 参数
+    /// 
+    /// * `status` - 状态查询句柄
+    pub fn with_status(mut self, status: StatusHandle) -> Self {
+        self.status = Some(status);
+        self
+    }
+    
+    /// 设置任务列表查询句柄，启用`/calendar.ics`接口
+    /// 
+    /// # 参数
+    /// 
+    /// * `tasks` - 任务列表查询句柄
+    pub fn with_tasks(mut self, tasks: TaskListHandle) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+    
+    /// 获取叠加层网页地址
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}/", self.settings.port)
+    }
+    
+    /// 启动叠加层服务
+    /// 
+    /// 只监听本机地址，倒计时更新通过`receiver`持续写入快照
+    /// 
+    /// # 参数
+    /// 
+    /// * `receiver` - 倒计时更新接收器
+    pub async fn start(self, mut receiver: broadcast::Receiver<CountdownUpdate>) -> Result<(), Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind(("127.0.0.1", self.settings.port)).await?;
+        if self.settings.enabled {
+            info!("直播倒计时叠加层已启动: {}", self.url());
+        } else {
+            info!("本地状态接口已启动: {}status", self.url());
+        }
+        
+        // 接收倒计时更新
+        let snapshot = Arc::clone(&self.snapshot);
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(update) => snapshot.write().await.apply(&update),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        
+        // 处理浏览器请求
+        let page = Arc::new(render_page(&self.settings));
+        let enabled = self.settings.enabled;
+        let snapshot = Arc::clone(&self.snapshot);
+        let status = self.status.clone();
+        let tasks = self.tasks.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let page = Arc::clone(&page);
+                        let snapshot = Arc::clone(&snapshot);
+                        let status = status.clone();
+                        let tasks = tasks.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = Self::handle_connection(stream, enabled.then_some(page.as_str()), &snapshot, status.as_ref(), tasks.as_ref()).await {
+                                warn!("处理叠加层请求失败: {}", e);
+                            }
+                        });
+                    },
+                    Err(e) => {
+                        error!("叠加层服务接受连接失败: {}", e);
+                        break;
+                    },
+                }
+            }
+        });
+        
+        Ok(())
+    }
+    
+    /// 处理单个HTTP连接
+    /// 
+    /// 没有启用叠加层时`page`为None，只提供`/status`接口
+    async fn handle_connection(
+        mut stream: TcpStream,
+        page: Option<&str>,
+        snapshot: &RwLock<OverlaySnapshot>,
+        status: Option<&StatusHandle>,
+        tasks: Option<&TaskListHandle>,
+    ) -> std::io::Result<()> {
+        let mut buffer = [0u8; 1024];
+        let read = stream.read(&mut buffer).await?;
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        
+        let json;
+        let ics;
+        let (status, content_type, body) = match (Self::request_path(&request), page) {
+            (Some("/"), Some(page)) => ("200 OK", "text/html; charset=utf-8", page),
+            (Some("/remaining"), Some(_)) => {
+                json = snapshot.read().await.to_json();
+                ("200 OK", "application/json", json.as_str())
+            },
+            (Some("/status"), _) => match status {
+                Some(status) => {
+                    json = status.snapshot().await.to_json();
+                    ("200 OK", "application/json", json.as_str())
+                },
+                None => ("404 Not Found", "text/plain; charset=utf-8", "Not Found"),
+            },
+            (Some("/calendar.ics"), Some(_)) => match tasks {
+                Some(tasks) => {
+                    ics = calendar::export_calendar(&tasks.tasks(), chrono::Local::now());
+                    ("200 OK", "text/calendar; charset=utf-8", ics.as_str())
+                },
+                None => ("404 Not Found", "text/plain; charset=utf-8", "Not Found"),
+            },
+            _ => ("404 Not Found", "text/plain; charset=utf-8", "Not Found"),
+        };
+        
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+            status, content_type, body.len(), body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+    
+    /// 查询本机正在运行的实例的状态
+    /// 
+    /// 连接本地接口的`/status`，没有实例在运行时连接失败
+    /// 
+    /// # 参数
+    /// 
+    /// * `port` - 本地接口的端口
+    pub async fn fetch_status(port: u16) -> anyhow::Result<StatusSnapshot> {
+        let mut stream = tokio::time::timeout(STATUS_TIMEOUT, TcpStream::connect(("127.0.0.1", port))).await??;
+        stream.write_all(b"GET /status HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n").await?;
+        let mut response = String::new();
+        tokio::time::timeout(STATUS_TIMEOUT, stream.read_to_string(&mut response)).await??;
+        
+        let (head, body) = response.split_once("\r\n\r\n")
+            .ok_or_else(|| anyhow::anyhow!("本地接口的响应无效"))?;
+        if !head.starts_with("HTTP/1.1 200") {
+            return Err(anyhow::anyhow!("本地接口没有提供状态: {}", head.lines().next().unwrap_or_default()));
+        }
+        Ok(serde_json::from_str(body)?)
+    }
+    
+    /// 从HTTP请求中提取路径，忽略查询参数
+    fn request_path(request: &str) -> Option<&str> {
+        let mut parts = request.lines().next()?.split_whitespace();
+        if parts.next()? != "GET" {
+            return None;
+        }
+        parts.next()?.split('?').next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::seconds(75)), "01:15");
+        assert_eq!(format_remaining(Duration::seconds(3725)), "1:02:05");
+        assert_eq!(format_remaining(Duration::seconds(-5)), "00:00");
+    }
+    
+    #[test]
+    fn test_snapshot_updates() {
+        let mut snapshot = OverlaySnapshot::default();
+        snapshot.apply(&CountdownUpdate::Progress { remaining: Duration::seconds(90), progress: 0.5 });
+        assert!(snapshot.active);
+        assert_eq!(snapshot.remaining_seconds, 90);
+        assert!(snapshot.to_json().contains("\"text\":\"01:30\""));
+        
+        snapshot.apply(&CountdownUpdate::Paused);
+        assert!(snapshot.paused);
+        
+        snapshot.apply(&CountdownUpdate::Cancelled);
+        assert_eq!(snapshot, OverlaySnapshot::default());
+    }
+    
+    #[test]
+    fn test_render_page() {
+        let mut settings = OverlaySettings::default();
+        assert!(render_page(&settings).contains("background: transparent"));
+        
+        settings.background_color = Some("#00FF00".to_string());
+        settings.font_size = 120;
+        let page = render_page(&settings);
+        assert!(page.contains("background: #00FF00"));
+        assert!(page.contains("font-size: 120px"));
+        
+        // 无效颜色回退到默认样式
+        settings.text_color = "red;}".to_string();
+        assert!(render_page(&settings).contains("color: #FFFFFF"));
+    }
+    
+    #[test]
+    fn test_request_path() {
+        assert_eq!(OverlayServer::request_path("GET / HTTP/1.1\r\n"), Some("/"));
+        assert_eq!(OverlayServer::request_path("GET /remaining?t=1 HTTP/1.1\r\n"), Some("/remaining"));
+        assert_eq!(OverlayServer::request_path("POST / HTTP/1.1\r\n"), None);
+        assert_eq!(OverlayServer::request_path(""), None);
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_status() {
+        let settings = OverlaySettings { port: 17398, ..OverlaySettings::default() };
+        
+        // 没有实例在运行时查询失败
+        assert!(OverlayServer::fetch_status(settings.port).await.is_err());
+        
+        // 没有启用叠加层时也提供状态接口
+        let manager = crate::core::countdown::CountdownManager::new().await.unwrap();
+        let (sender, receiver) = broadcast::channel(4);
+        OverlayServer::new(settings.clone()).with_status(manager.status_handle()).start(receiver).await.unwrap();
+        let snapshot = OverlayServer::fetch_status(settings.port).await.unwrap();
+        assert_eq!(snapshot, manager.status_handle().snapshot().await);
+        drop(sender);
+    }
+}
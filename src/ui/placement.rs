@@ -0,0 +1,135 @@
+//! 窗口位置模块
+//! 
+//! 记住主窗口所在的显示器和位置，重新启动时恢复到同一显示器；
+//! 提供Ctrl+方向键把窗口停靠到屏幕边角的计算，适合多显示器下放置小计时窗口。
+//! 坐标均为物理像素，与Windows API一致
+
+use crate::platform::{self, DisplayControl};
+
+pub use crate::platform::{MonitorInfo, ScreenRect};
+
+/// 停靠时窗口与屏幕边缘的距离（像素）
+const SNAP_MARGIN: i32 = 8;
+
+/// 停靠方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapDirection {
+    /// 停靠到左边
+    Left,
+    /// 停靠到右边
+    Right,
+    /// 停靠到上边
+    Up,
+    /// 停靠到下边
+    Down,
+}
+
+/// 计算停靠后的窗口位置
+/// 
+/// 只移动方向键对应的一个方向，另一个方向保持不变（限制在工作区内），
+/// 连续按两个方向键即可停靠到任意角落
+/// 
+/// # 参数
+/// 
+/// * `window` - 当前窗口区域
+/// * `work_area` - 窗口所在显示器的工作区域
+/// * `direction` - 停靠方向
+/// 
+/// # 返回值
+/// 
+/// 返回窗口左上角的新位置
+pub fn snap_position(window: ScreenRect, work_area: ScreenRect, direction: SnapDirection) -> (i32, i32) {
+    let clamp = |value: i32, min: i32, max: i32| value.min(max).max(min);
+    let max_x = work_area.right - window.width() - SNAP_MARGIN;
+    let max_y = work_area.bottom - window.height() - SNAP_MARGIN;
+    let min_x = work_area.left + SNAP_MARGIN;
+    let min_y = work_area.top + SNAP_MARGIN;
+    let x = clamp(window.left, min_x, max_x.max(min_x));
+    let y = clamp(window.top, min_y, max_y.max(min_y));
+    
+    match direction {
+        SnapDirection::Left => (min_x, y),
+        SnapDirection::Right => (max_x.max(min_x), y),
+        SnapDirection::Up => (x, min_y),
+        SnapDirection::Down => (x, max_y.max(min_y)),
+    }
+}
+
+/// 计算恢复窗口时的位置
+/// 
+/// 只有保存时所在的显示器仍然连接、且位置仍在其工作区内时才恢复，
+/// 避免窗口出现在已断开的显示器上而无法找到
+/// 
+/// # 参数
+/// 
+/// * `monitor_name` - 保存的显示器名称
+/// * `position` - 保存的窗口位置
+/// * `monitors` - 当前连接的显示器
+pub fn restore_position(monitor_name: &str, position: (i32, i32), monitors: &[MonitorInfo]) -> Option<(i32, i32)> {
+    monitors.iter()
+        .find(|monitor| monitor.name == monitor_name)
+        .filter(|monitor| monitor.work_area.contains(position.0, position.1))
+        .map(|_| position)
+}
+
+/// 获取所有已连接的显示器
+pub fn list_monitors() -> Vec<MonitorInfo> {
+    platform::native().list_monitors()
+}
+
+/// 获取窗口当前所在的显示器和窗口区域
+/// 
+/// # 参数
+/// 
+/// * `window_handle` - 窗口句柄（Windows下为HWND）
+pub fn window_placement(window_handle: isize) -> Option<(MonitorInfo, ScreenRect)> {
+    platform::native().window_placement(window_handle)
+}
+
+/// 移动窗口
+/// 
+/// # 参数
+/// 
+/// * `window_handle` - 窗口句柄（Windows下为HWND）
+/// * `position` - 窗口左上角的新位置
+pub fn move_window(window_handle: isize, position: (i32, i32)) -> bool {
+    platform::native().move_window(window_handle, position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> ScreenRect {
+        ScreenRect { left, top, right, bottom }
+    }
+    
+    #[test]
+    fn test_snap_position() {
+        // 第二个显示器在主显示器右侧，底部有任务栏
+        let work_area = rect(1920, 0, 3840, 1040);
+        let window = rect(2500, 300, 2900, 800);
+        
+        assert_eq!(snap_position(window, work_area, SnapDirection::Left), (1928, 300));
+        assert_eq!(snap_position(window, work_area, SnapDirection::Down), (2500, 532));
+        
+        // 先右后上停靠到右上角
+        let (x, y) = snap_position(window, work_area, SnapDirection::Right);
+        assert_eq!((x, y), (3432, 300));
+        let moved = rect(x, y, x + 400, y + 500);
+        assert_eq!(snap_position(moved, work_area, SnapDirection::Up), (3432, 8));
+    }
+    
+    #[test]
+    fn test_restore_position() {
+        let monitors = vec![
+            MonitorInfo { name: r"\\.\DISPLAY1".to_string(), work_area: rect(0, 0, 1920, 1040) },
+            MonitorInfo { name: r"\\.\DISPLAY2".to_string(), work_area: rect(1920, 0, 3840, 1040) },
+        ];
+        
+        assert_eq!(restore_position(r"\\.\DISPLAY2", (3000, 100), &monitors), Some((3000, 100)));
+        // 显示器已断开或位置不在原显示器上时不恢复
+        assert_eq!(restore_position(r"\\.\DISPLAY3", (3000, 100), &monitors), None);
+        assert_eq!(restore_position(r"\\.\DISPLAY1", (3000, 100), &monitors), None);
+    }
+}
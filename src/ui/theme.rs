@@ -2,9 +2,82 @@
 //! 
 //! 定义应用程序的视觉主题，包括颜色、字体、样式等
 
-use iced::{Color, Background};
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime};
+use iced::advanced::graphics::text::font_system;
+use iced::theme::palette::{Extended, Pair};
+use iced::theme::Palette;
+use iced::widget::container::Appearance as ContainerAppearance;
+use iced::{Background, Border, Color, Theme as IcedTheme};
 use serde::{Deserialize, Serialize};
 
+/// 各月份的大致日出、日落时间（时, 分），以中国中部地区为参考
+const SUN_TIMES: [((u32, u32), (u32, u32)); 12] = [
+    ((7, 30), (17, 20)),
+    ((7, 10), (17, 50)),
+    ((6, 35), (18, 15)),
+    ((5, 55), (18, 40)),
+    ((5, 20), (19, 5)),
+    ((5, 0), (19, 25)),
+    ((5, 10), (19, 25)),
+    ((5, 35), (19, 0)),
+    ((6, 0), (18, 20)),
+    ((6, 25), (17, 45)),
+    ((6, 55), (17, 15)),
+    ((7, 20), (17, 5)),
+];
+
+/// 使用固定时间切换深色主题时，早上恢复浅色主题的时间（时）
+const FIXED_LIGHT_HOUR: u32 = 7;
+
+/// 默认界面字体
+pub const DEFAULT_FONT_FAMILY: &str = "Microsoft YaHei";
+
+/// 设置中可选的界面字体，也可以输入其他已安装字体的名称
+pub const FONT_CHOICES: [&str; 8] = [
+    "Microsoft YaHei",
+    "DengXian",
+    "SimSun",
+    "Microsoft JhengHei",
+    "Segoe UI",
+    "PingFang SC",
+    "Noto Sans CJK SC",
+    "WenQuanYi Micro Hei",
+];
+
+/// 把界面的默认字体切换为已安装的字体
+/// 
+/// 窗口以无衬线字体族作为默认字体，这里修改该字体族对应的字体名称，
+/// 之后用`iced::font::load`加载一次字体，界面就会按新的字体重新排版
+/// 
+/// # 参数
+/// 
+/// * `family` - 字体名称
+/// 
+/// # 返回值
+/// 
+/// 返回该字体的文件内容，没有安装该字体时返回None，不修改默认字体
+pub fn set_default_font_family(family: &str) -> Option<Vec<u8>> {
+    let mut font_system = font_system().write().ok()?;
+    let db = font_system.raw().db_mut();
+    let id = db.faces()
+        .find(|face| face.families.iter().any(|(name, _)| name.eq_ignore_ascii_case(family)))?
+        .id;
+    let data = db.with_face_data(id, |data, _| data.to_vec())?;
+    db.set_sans_serif_family(family);
+    Some(data)
+}
+
+/// 弹出窗口的边框宽度
+const MODAL_BORDER_WIDTH: f32 = 2.0;
+
+/// 弹出窗口的圆角半径
+const MODAL_RADIUS: f32 = 10.0;
+
+/// 将RGB数组转换为iced颜色
+fn rgb([r, g, b]: [u8; 3]) -> Color {
+    Color::from_rgb8(r, g, b)
+}
+
 /// 应用主题
 /// 
 /// 定义应用程序的整体视觉风格
@@ -33,6 +106,18 @@ pub enum ThemeType {
     Dark,
     /// 自动主题（跟随系统）
     Auto,
+    /// 日落后自动深色
+    TimeOfDay,
+}
+
+/// 按时间自动切换深色主题的安排
+/// 
+/// 白天使用浅色主题，到指定时间（未指定时为当月日落时间）后使用深色主题，
+/// 第二天早上恢复浅色主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ThemeSchedule {
+    /// 切换到深色主题的时间（时），为空时使用日落时间
+    pub dark_from_hour: Option<u32>,
 }
 
 /// 主题颜色配置
@@ -105,6 +190,57 @@ pub struct ThemeRounding {
     pub input: f32,
 }
 
+impl ThemeSchedule {
+    /// 创建自动切换安排
+    /// 
+    /// # 参数
+    /// 
+    /// * `dark_from_hour` - 切换到深色主题的时间（时），为空时使用日落时间
+    pub fn new(dark_from_hour: Option<u32>) -> Self {
+        Self { dark_from_hour }
+    }
+    
+    /// 指定时间是否应使用深色主题
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 本地时间
+    pub fn is_dark_at(&self, now: NaiveDateTime) -> bool {
+        let ((sunrise_hour, sunrise_minute), (sunset_hour, sunset_minute)) = SUN_TIMES[now.month0() as usize];
+        let (light_from, dark_from) = match self.dark_from_hour {
+            Some(hour) => (
+                NaiveTime::from_hms_opt(FIXED_LIGHT_HOUR, 0, 0),
+                NaiveTime::from_hms_opt(hour.min(23), 0, 0),
+            ),
+            None => (
+                NaiveTime::from_hms_opt(sunrise_hour, sunrise_minute, 0),
+                NaiveTime::from_hms_opt(sunset_hour, sunset_minute, 0),
+            ),
+        };
+        
+        match (light_from, dark_from) {
+            (Some(light_from), Some(dark_from)) => {
+                let time = now.time();
+                time >= dark_from || time < light_from
+            },
+            _ => false,
+        }
+    }
+    
+    /// 获取指定时间应使用的主题
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 本地时间
+    pub fn theme_at(&self, now: NaiveDateTime) -> Theme {
+        if self.is_dark_at(now) {
+            Theme::dark_theme()
+        } else {
+            Theme::light_theme()
+        }
+    }
+}
+
 impl Default for Theme {
     /// 创建默认主题（浅色主题）
     fn default() -> Self {
@@ -139,7 +275,7 @@ impl Theme {
                 default_size: 14.0,
                 heading_size: 24.0,
                 small_size: 12.0,
-                family: "Microsoft YaHei".to_string(),
+                family: DEFAULT_FONT_FAMILY.to_string(),
             },
             spacing: ThemeSpacing {
                 small: 4.0,
@@ -184,7 +320,7 @@ impl Theme {
                 default_size: 14.0,
                 heading_size: 24.0,
                 small_size: 12.0,
-                family: "Microsoft YaHei".to_string(),
+                family: DEFAULT_FONT_FAMILY.to_string(),
             },
             spacing: ThemeSpacing {
                 small: 4.0,
@@ -282,6 +418,51 @@ impl Theme {
         Background::Color(self.background_color())
     }
 
+    /// 生成iced使用的调色板
+    pub fn palette(&self) -> Palette {
+        Palette {
+            background: self.background_color(),
+            text: self.text_color(),
+            primary: self.primary_color(),
+            success: self.success_color(),
+            danger: self.error_color(),
+        }
+    }
+
+    /// 转换为iced主题
+    /// 
+    /// 按钮、输入框、选择列表等控件的样式由iced根据调色板计算；
+    /// 次要背景色用于输入框和列表等控件的底色，边框色和次要文本色用于控件边框和占位文字
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回以主题名称命名的自定义iced主题
+    pub fn to_iced_theme(&self) -> IcedTheme {
+        let background_secondary = rgb(self.colors.background_secondary);
+        let border = rgb(self.colors.border);
+        let text_secondary = rgb(self.colors.text_secondary);
+        IcedTheme::custom_with_fn(self.name.clone(), self.palette(), move |palette| {
+            let mut extended = Extended::generate(palette);
+            extended.background.weak = Pair::new(background_secondary, palette.text);
+            extended.background.strong = Pair::new(border, text_secondary);
+            extended
+        })
+    }
+
+    /// 弹出窗口（设置、任务列表等）的外观
+    pub fn modal_appearance(&self) -> ContainerAppearance {
+        ContainerAppearance {
+            background: Some(Background::Color(rgb(self.colors.background_secondary))),
+            text_color: Some(self.text_color()),
+            border: Border {
+                color: self.border_color(),
+                width: MODAL_BORDER_WIDTH,
+                radius: MODAL_RADIUS.into(),
+            },
+            ..Default::default()
+        }
+    }
+
     /// 检测系统主题
     /// 
     /// # 返回值
@@ -310,7 +491,8 @@ impl Theme {
             ThemeType::Auto => {
                 let detected = Self::detect_system_theme();
                 Self::apply_theme(detected)
-            }
+            },
+            ThemeType::TimeOfDay => ThemeSchedule::default().theme_at(Local::now().naive_local()),
         }
     }
 
@@ -327,7 +509,7 @@ impl Theme {
         match current_theme {
             ThemeType::Light => Self::dark_theme(),
             ThemeType::Dark => Self::light_theme(),
-            ThemeType::Auto => Self::light_theme(), // 自动主题切换为浅色
+            ThemeType::Auto | ThemeType::TimeOfDay => Self::light_theme(), // 自动主题切换为浅色
         }
     }
 
@@ -407,6 +589,25 @@ mod tests {
         assert_eq!(text_color.r, 33.0 / 255.0);
     }
 
+    #[test]
+    fn test_iced_theme() {
+        let theme = Theme::dark_theme();
+        let palette = theme.palette();
+        assert_eq!(palette.background, theme.background_color());
+        assert_eq!(palette.danger, theme.error_color());
+        
+        // 自定义颜色反映在iced主题中
+        let mut custom = Theme::light_theme();
+        custom.name = "Forest".to_string();
+        custom.colors.primary = [34, 139, 34];
+        custom.colors.border = [10, 20, 30];
+        let iced_theme = custom.to_iced_theme();
+        assert_eq!(iced_theme.to_string(), "Forest");
+        assert_eq!(iced_theme.palette().primary, Color::from_rgb8(34, 139, 34));
+        assert_eq!(iced_theme.extended_palette().background.strong.color, Color::from_rgb8(10, 20, 30));
+        assert_eq!(custom.modal_appearance().border.color, Color::from_rgb8(10, 20, 30));
+    }
+
     #[test]
     fn test_theme_toggle() {
         let light_theme = Theme::toggle_theme(ThemeType::Dark);
@@ -426,4 +627,25 @@ mod tests {
         assert!(deserialized.is_ok());
         assert_eq!(deserialized.unwrap().name, theme.name);
     }
+    
+    #[test]
+    fn test_theme_schedule() {
+        let at = |month: u32, hour: u32, minute: u32| {
+            chrono::NaiveDate::from_ymd_opt(2024, month, 15).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+        };
+        
+        // 按日落时间切换，冬季比夏季更早变暗
+        let sunset = ThemeSchedule::default();
+        assert!(!sunset.is_dark_at(at(12, 12, 0)));
+        assert!(sunset.is_dark_at(at(12, 17, 30)));
+        assert!(!sunset.is_dark_at(at(6, 17, 30)));
+        assert!(sunset.is_dark_at(at(6, 3, 0)));
+        
+        // 固定时间切换
+        let fixed = ThemeSchedule::new(Some(21));
+        assert!(!fixed.is_dark_at(at(12, 20, 59)));
+        assert!(fixed.is_dark_at(at(12, 21, 0)));
+        assert!(fixed.is_dark_at(at(12, 6, 59)));
+        assert_eq!(fixed.theme_at(at(1, 8, 0)).theme_type, ThemeType::Light);
+    }
 }
\ No newline at end of file
@@ -2,15 +2,28 @@
 //! 
 //! 实现系统托盘图标、右键菜单和托盘交互功能
 
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
 use log::{info, warn};
 use tokio::sync::mpsc;
 use tray_icon::{
     TrayIcon, TrayIconBuilder, TrayIconEvent, 
-    menu::{Menu, MenuItem, MenuEvent, PredefinedMenuItem},
+    menu::{Menu, MenuItem, MenuEvent, PredefinedMenuItem, Submenu},
     Icon
 };
+#[cfg(target_os = "windows")]
+use tray_icon::menu::ContextMenu;
 
-use crate::core::types::{UIEvent, CountdownStatus};
+use crate::core::plan::SNOOZE_MINUTES;
+use crate::core::scheduler;
+use crate::core::time_parser::TimeParser;
+use crate::core::types::{format_quick_duration, UIEvent, CountdownStatus, StatusSnapshot, TaskAction, TaskType};
+
+/// 剩余时间不超过该秒数时托盘提示每秒更新，否则每分钟更新一次
+const TOOLTIP_PER_SECOND_SECONDS: i64 = 60;
+
+/// 提醒状态下托盘提示开头的文字
+const ATTENTION_TOOLTIP_PREFIX: &str = "⚠ 即将执行 ⚠";
 
 /// 托盘图标管理器
 /// 
@@ -18,14 +31,101 @@ use crate::core::types::{UIEvent, CountdownStatus};
 pub struct TrayManager {
     /// 托盘图标
     tray_icon: Option<TrayIcon>,
-    /// 托盘菜单
-    tray_menu: Option<Menu>,
+    /// 托盘右键菜单
+    tray_menu: Option<TrayMenu>,
     /// UI事件发送器
     ui_event_sender: mpsc::UnboundedSender<UIEvent>,
     /// 当前倒计时状态
     current_status: CountdownStatus,
     /// 菜单项ID
     menu_items: TrayMenuItems,
+    /// 左键快捷菜单
+    quick_menu: Option<QuickMenu>,
+    /// 主窗口句柄，用于弹出快捷菜单
+    window_handle: Option<isize>,
+    /// 倒计时是否已暂停
+    is_paused: bool,
+    /// 上次写入托盘提示的快照和即将执行事件，内容不变时不再调用系统接口
+    last_tooltip: Option<(StatusSnapshot, Vec<(DateTime<Local>, TaskAction)>)>,
+    /// 快速倒计时子菜单中的预设，菜单项ID中的序号对应这里的位置
+    quick_presets: Vec<QuickPreset>,
+    /// 剩余时间不超过该秒数时进入提醒状态，0表示不提醒
+    attention_seconds: i64,
+    /// 提醒状态下当前是否显示提醒图标，不在提醒状态时为None
+    attention_frame: Option<bool>,
+    /// 是否可以推迟倒计时，管理策略要求PIN时不能推迟
+    postpone_allowed: bool,
+}
+
+/// 托盘右键菜单
+/// 
+/// 保留随倒计时状态启用、禁用或更新文字的菜单项
+struct TrayMenu {
+    /// 菜单对象
+    menu: Menu,
+    /// 剩余时间显示项
+    remaining_item: MenuItem,
+    /// 快速倒计时子菜单
+    start_menu: Submenu,
+    /// 快速倒计时子菜单中按预设生成的菜单项
+    preset_items: Vec<MenuItem>,
+    /// 暂停/继续倒计时
+    pause_item: MenuItem,
+    /// 取消倒计时
+    cancel_item: MenuItem,
+    /// 推迟倒计时
+    snooze_item: MenuItem,
+    /// 剩余时间显示项当前的文字，文字不变时不再调用系统接口
+    remaining_text: String,
+}
+
+/// 快速倒计时子菜单中的预设
+#[derive(Debug, Clone, PartialEq)]
+enum QuickPreset {
+    /// 配置方案中的快速倒计时按钮，到点后关机
+    Duration(Duration),
+    /// 保存的时间输入预设，如"今晚23:00"
+    Input(String),
+}
+
+impl QuickPreset {
+    /// 菜单项文字
+    fn label(&self) -> String {
+        match self {
+            QuickPreset::Duration(duration) => format_quick_duration(*duration),
+            QuickPreset::Input(input) => input.clone(),
+        }
+    }
+    
+    /// 点击后发送的事件
+    /// 
+    /// 时间输入预设按与输入框相同的规则解析，与快速倒计时一样到点后关机
+    fn event(&self) -> Result<UIEvent> {
+        match self {
+            QuickPreset::Duration(duration) => Ok(UIEvent::QuickCountdown(*duration)),
+            QuickPreset::Input(input) => {
+                let time_input = TimeParser::global().parse(input)?;
+                let task_type = time_input.task_type_or(TaskType::Once);
+                Ok(UIEvent::StartCountdown(time_input, task_type, TaskAction::Shutdown))
+            }
+        }
+    }
+}
+
+/// 左键快捷菜单
+/// 
+/// 显示剩余时间以及暂停、延长、取消三个常用操作
+struct QuickMenu {
+    /// 菜单对象
+    menu: Menu,
+    /// 剩余时间显示项
+    status_item: MenuItem,
+    /// 暂停/继续
+    pause_item: MenuItem,
+    /// 延长倒计时
+    extend_item: MenuItem,
+    /// 取消倒计时
+    cancel_item: MenuItem,
 }
 
 /// 托盘菜单项ID
@@ -33,27 +133,51 @@ pub struct TrayManager {
 struct TrayMenuItems {
     /// 显示/隐藏主窗口
     show_hide: String,
-    /// 开始倒计时
-    start_countdown: String,
+    /// 快速倒计时预设，后接预设的序号
+    quick_preset_prefix: String,
+    /// 快速倒计时：自定义时间
+    quick_custom: String,
+    /// 暂停/继续倒计时
+    pause_resume: String,
     /// 取消倒计时
     cancel_countdown: String,
+    /// 推迟倒计时
+    snooze: String,
+    /// 重复上次的倒计时
+    repeat_last: String,
     /// 设置
     settings: String,
     /// 关于
     about: String,
     /// 退出
     quit: String,
+    /// 快捷菜单：暂停/继续
+    quick_pause: String,
+    /// 快捷菜单：延长倒计时
+    quick_extend: String,
+    /// 快捷菜单：取消倒计时
+    quick_cancel: String,
+    /// 快捷菜单：显示主窗口
+    quick_show: String,
 }
 
 impl Default for TrayMenuItems {
     fn default() -> Self {
         Self {
             show_hide: "show_hide".to_string(),
-            start_countdown: "start_countdown".to_string(),
+            quick_preset_prefix: "quick_preset_".to_string(),
+            quick_custom: "quick_custom".to_string(),
+            pause_resume: "pause_resume".to_string(),
             cancel_countdown: "cancel_countdown".to_string(),
+            snooze: "snooze".to_string(),
+            repeat_last: "repeat_last".to_string(),
             settings: "settings".to_string(),
             about: "about".to_string(),
             quit: "quit".to_string(),
+            quick_pause: "quick_pause".to_string(),
+            quick_extend: "quick_extend".to_string(),
+            quick_cancel: "quick_cancel".to_string(),
+            quick_show: "quick_show".to_string(),
         }
     }
 }
@@ -71,9 +195,52 @@ impl TrayManager {
             ui_event_sender,
             current_status: CountdownStatus::Idle,
             menu_items: TrayMenuItems::default(),
+            quick_menu: None,
+            window_handle: None,
+            is_paused: false,
+            last_tooltip: None,
+            quick_presets: Vec::new(),
+            attention_seconds: 0,
+            attention_frame: None,
+            postpone_allowed: true,
         }
     }
     
+    /// 设置进入提醒状态的剩余分钟数
+    /// 
+    /// 剩余时间不超过该值时托盘图标在正常图标和提醒图标之间每秒切换，托盘提示每秒更新并以醒目文字开头，
+    /// 主窗口最小化到托盘时也能注意到即将执行
+    /// 
+    /// # 参数
+    /// 
+    /// * `minutes` - 剩余分钟数，0表示不提醒
+    pub fn set_attention_minutes(&mut self, minutes: u32) {
+        self.attention_seconds = i64::from(minutes) * 60;
+    }
+    
+    /// 设置是否可以推迟倒计时
+    /// 
+    /// 不能推迟时禁用右键菜单和快捷菜单中的推迟项
+    /// 
+    /// # 参数
+    /// 
+    /// * `allowed` - 是否可以推迟
+    pub fn set_postpone_allowed(&mut self, allowed: bool) {
+        self.postpone_allowed = allowed;
+    }
+    
+    /// 设置主窗口句柄
+    /// 
+    /// 快捷菜单需要依附于一个窗口才能弹出并接收菜单事件
+    /// 
+    /// # 参数
+    /// 
+    /// * `window_handle` - 主窗口句柄（Windows下为HWND）
+    pub fn set_window_handle(&mut self, window_handle: isize) {
+        info!("托盘快捷菜单已绑定主窗口: {:#x}", window_handle);
+        self.window_handle = Some(window_handle);
+    }
+    
     /// 初始化托盘图标
     /// 
     /// # 返回值
@@ -90,13 +257,16 @@ impl TrayManager {
         
         // 创建托盘图标
         let tray_icon = TrayIconBuilder::new()
-            .with_menu(Box::new(menu.clone()))
+            .with_menu(Box::new(menu.menu.clone()))
             .with_tooltip("QtShut - 定时关机")
             .with_icon(icon)
             .build()?;
         
         self.tray_icon = Some(tray_icon);
         self.tray_menu = Some(menu);
+        self.quick_menu = Some(self.create_quick_menu()?);
+        self.update_menu_items();
+        self.refresh_quick_menu();
         
         info!("系统托盘图标初始化成功");
         Ok(())
@@ -107,43 +277,228 @@ impl TrayManager {
     /// # 返回值
     /// 
     /// 成功返回菜单对象
-    fn create_tray_menu(&self) -> Result<Menu, Box<dyn std::error::Error>> {
+    fn create_tray_menu(&self) -> Result<TrayMenu, Box<dyn std::error::Error>> {
         let menu = Menu::new();
         
+        // 剩余时间（仅用于显示）
+        let remaining_text = Self::remaining_menu_text(&CountdownStatus::Idle, false);
+        let remaining_item = MenuItem::new(remaining_text.clone(), false, None);
+        menu.append(&remaining_item)?;
+        
         // 显示/隐藏主窗口
-        let show_hide_item = MenuItem::new("显示主窗口", true, None);
+        let show_hide_item = MenuItem::with_id(self.menu_items.show_hide.clone(), "显示主窗口", true, None);
         menu.append(&show_hide_item)?;
         
         // 分隔符
         menu.append(&PredefinedMenuItem::separator())?;
         
-        // 开始倒计时
-        let start_item = MenuItem::new("快速倒计时 (30分钟)", true, None);
-        menu.append(&start_item)?;
+        // 快速倒计时子菜单：预设和自定义时间
+        let start_menu = Submenu::new("快速倒计时", true);
+        let preset_items = self.insert_preset_items(&start_menu)?;
+        start_menu.append(&PredefinedMenuItem::separator())?;
+        let custom_item = MenuItem::with_id(self.menu_items.quick_custom.clone(), "自定义…", true, None);
+        start_menu.append(&custom_item)?;
+        menu.append(&start_menu)?;
+        
+        // 暂停/继续倒计时
+        let pause_item = MenuItem::with_id(self.menu_items.pause_resume.clone(), "暂停倒计时", false, None);
+        menu.append(&pause_item)?;
         
         // 取消倒计时
-        let cancel_item = MenuItem::new("取消倒计时", false, None); // 初始禁用
+        let cancel_item = MenuItem::with_id(self.menu_items.cancel_countdown.clone(), "取消倒计时", false, None); // 初始禁用
         menu.append(&cancel_item)?;
         
+        // 推迟倒计时
+        let snooze_item = MenuItem::with_id(self.menu_items.snooze.clone(), format!("推迟{}分钟", SNOOZE_MINUTES), false, None);
+        menu.append(&snooze_item)?;
+        
+        // 重复上次的倒计时
+        let repeat_item = MenuItem::with_id(self.menu_items.repeat_last.clone(), "重复上次", true, None);
+        menu.append(&repeat_item)?;
+        
         // 分隔符
         menu.append(&PredefinedMenuItem::separator())?;
         
         // 设置
-        let settings_item = MenuItem::new("设置", true, None);
+        let settings_item = MenuItem::with_id(self.menu_items.settings.clone(), "设置", true, None);
         menu.append(&settings_item)?;
         
         // 关于
-        let about_item = MenuItem::new("关于", true, None);
+        let about_item = MenuItem::with_id(self.menu_items.about.clone(), "关于", true, None);
         menu.append(&about_item)?;
         
         // 分隔符
         menu.append(&PredefinedMenuItem::separator())?;
         
         // 退出
-        let quit_item = MenuItem::new("退出", true, None);
+        let quit_item = MenuItem::with_id(self.menu_items.quit.clone(), "退出", true, None);
         menu.append(&quit_item)?;
         
-        Ok(menu)
+        Ok(TrayMenu {
+            menu,
+            remaining_item,
+            start_menu,
+            preset_items,
+            pause_item,
+            cancel_item,
+            snooze_item,
+            remaining_text,
+        })
+    }
+    
+    /// 在快速倒计时子菜单开头插入预设菜单项
+    /// 
+    /// # 参数
+    /// 
+    /// * `start_menu` - 快速倒计时子菜单
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回插入的菜单项，更换预设时用于移除
+    fn insert_preset_items(&self, start_menu: &Submenu) -> Result<Vec<MenuItem>, Box<dyn std::error::Error>> {
+        let mut items = Vec::with_capacity(self.quick_presets.len());
+        for (index, preset) in self.quick_presets.iter().enumerate() {
+            let id = format!("{}{}", self.menu_items.quick_preset_prefix, index);
+            let item = MenuItem::with_id(id, preset.label(), true, None);
+            start_menu.insert(&item, index)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+    
+    /// 更新快速倒计时子菜单中的预设
+    /// 
+    /// 先列出配置方案中的快速倒计时按钮，再列出保存的时间输入预设
+    /// 
+    /// # 参数
+    /// 
+    /// * `durations` - 快速倒计时按钮的时长
+    /// * `inputs` - 时间输入预设
+    pub fn set_quick_presets(&mut self, durations: &[Duration], inputs: &[String]) {
+        let quick_presets: Vec<QuickPreset> = durations.iter().copied().map(QuickPreset::Duration)
+            .chain(inputs.iter().cloned().map(QuickPreset::Input))
+            .collect();
+        if quick_presets == self.quick_presets {
+            return;
+        }
+        self.quick_presets = quick_presets;
+        
+        let Some(mut tray_menu) = self.tray_menu.take() else {
+            return;
+        };
+        for item in tray_menu.preset_items.drain(..) {
+            if let Err(e) = tray_menu.start_menu.remove(&item) {
+                warn!("移除快速倒计时预设失败: {}", e);
+            }
+        }
+        match self.insert_preset_items(&tray_menu.start_menu) {
+            Ok(items) => tray_menu.preset_items = items,
+            Err(e) => warn!("更新快速倒计时预设失败: {}", e),
+        }
+        self.tray_menu = Some(tray_menu);
+    }
+    
+    /// 创建左键快捷菜单
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回快捷菜单对象
+    fn create_quick_menu(&self) -> Result<QuickMenu, Box<dyn std::error::Error>> {
+        let menu = Menu::new();
+        
+        // 剩余时间（仅用于显示）
+        let status_item = MenuItem::new("当前没有倒计时", false, None);
+        menu.append(&status_item)?;
+        
+        // 分隔符
+        menu.append(&PredefinedMenuItem::separator())?;
+        
+        let pause_item = MenuItem::with_id(self.menu_items.quick_pause.clone(), "暂停", false, None);
+        menu.append(&pause_item)?;
+        
+        let extend_item = MenuItem::with_id(
+            self.menu_items.quick_extend.clone(),
+            format!("推迟{}分钟", SNOOZE_MINUTES),
+            false,
+            None,
+        );
+        menu.append(&extend_item)?;
+        
+        let cancel_item = MenuItem::with_id(self.menu_items.quick_cancel.clone(), "取消", false, None);
+        menu.append(&cancel_item)?;
+        
+        // 主窗口关闭到托盘后从这里还原
+        menu.append(&PredefinedMenuItem::separator())?;
+        let show_item = MenuItem::with_id(self.menu_items.quick_show.clone(), "显示主窗口", true, None);
+        menu.append(&show_item)?;
+        
+        Ok(QuickMenu {
+            menu,
+            status_item,
+            pause_item,
+            extend_item,
+            cancel_item,
+        })
+    }
+    
+    /// 生成快捷菜单中的状态文本
+    /// 
+    /// # 返回值
+    /// 
+    /// 状态文本字符串
+    fn quick_menu_status_text(&self) -> String {
+        match &self.current_status {
+            CountdownStatus::Running { remaining } if self.is_paused => {
+                format!("已暂停 - 剩余时间: {}", self.format_duration(remaining))
+            },
+            CountdownStatus::Running { remaining } => {
+                format!("剩余时间: {}", self.format_duration(remaining))
+            },
+            _ => "当前没有倒计时".to_string(),
+        }
+    }
+    
+    /// 根据当前状态刷新快捷菜单
+    fn refresh_quick_menu(&self) {
+        if let Some(quick_menu) = &self.quick_menu {
+            let is_running = matches!(self.current_status, CountdownStatus::Running { .. });
+            
+            quick_menu.status_item.set_text(self.quick_menu_status_text());
+            quick_menu.pause_item.set_text(if self.is_paused { "继续" } else { "暂停" });
+            quick_menu.pause_item.set_enabled(is_running);
+            quick_menu.extend_item.set_enabled(is_running && self.postpone_allowed);
+            quick_menu.cancel_item.set_enabled(is_running);
+        }
+    }
+    
+    /// 在鼠标位置弹出快捷菜单
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功弹出返回true，尚未绑定窗口或菜单未创建时返回false
+    pub fn show_quick_menu(&self) -> bool {
+        let (quick_menu, window_handle) = match (&self.quick_menu, self.window_handle) {
+            (Some(quick_menu), Some(window_handle)) => (quick_menu, window_handle),
+            _ => {
+                warn!("快捷菜单尚未就绪");
+                return false;
+            }
+        };
+        
+        self.refresh_quick_menu();
+        
+        #[cfg(target_os = "windows")]
+        {
+            // 传入None表示在当前鼠标位置弹出
+            quick_menu.menu.show_context_menu_for_hwnd(window_handle, None);
+            true
+        }
+        
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = (quick_menu, window_handle);
+            false
+        }
     }
     
     /// 加载托盘图标
@@ -192,23 +547,47 @@ impl TrayManager {
             .map_err(|e| format!("创建默认图标失败: {}", e).into())
     }
     
+    /// 创建提醒图标
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回提醒图标对象
+    fn create_attention_icon(&self) -> Result<Icon, Box<dyn std::error::Error>> {
+        let icon_data = Self::generate_icon_data([255, 200, 0, 255], [200, 50, 50, 255]);
+        
+        Icon::from_rgba(icon_data, 16, 16)
+            .map_err(|e| format!("创建提醒图标失败: {}", e).into())
+    }
+    
     /// 生成默认图标数据
     /// 
     /// # 返回值
     /// 
     /// RGBA格式的图标数据
     fn generate_default_icon_data(&self) -> Vec<u8> {
+        // 创建一个简单的16x16红色方块图标，边框黑色
+        Self::generate_icon_data([200, 50, 50, 255], [0, 0, 0, 255])
+    }
+    
+    /// 生成带边框的16x16方块图标数据
+    /// 
+    /// # 参数
+    /// 
+    /// * `fill` - 内部颜色（RGBA）
+    /// * `border` - 边框颜色（RGBA）
+    /// 
+    /// # 返回值
+    /// 
+    /// RGBA格式的图标数据
+    fn generate_icon_data(fill: [u8; 4], border: [u8; 4]) -> Vec<u8> {
         let mut data = Vec::with_capacity(16 * 16 * 4);
         
-        // 创建一个简单的16x16红色方块图标
         for y in 0..16 {
             for x in 0..16 {
-                if x >= 2 && x <= 13 && y >= 2 && y <= 13 {
-                    // 内部红色
-                    data.extend_from_slice(&[200, 50, 50, 255]); // RGBA
+                if (2..=13).contains(&x) && (2..=13).contains(&y) {
+                    data.extend_from_slice(&fill);
                 } else {
-                    // 边框黑色
-                    data.extend_from_slice(&[0, 0, 0, 255]); // RGBA
+                    data.extend_from_slice(&border);
                 }
             }
         }
@@ -226,8 +605,11 @@ impl TrayManager {
             TrayIconEvent::Click { button, button_state, .. } => {
                 info!("托盘图标点击事件: {:?}, {:?}", button, button_state);
                 
-                // 左键单击显示/隐藏主窗口
-                if button == tray_icon::MouseButton::Left {
+                // 左键单击弹出快捷菜单，快捷菜单不可用时显示/隐藏主窗口
+                if button == tray_icon::MouseButton::Left
+                    && button_state == tray_icon::MouseButtonState::Up
+                    && !self.show_quick_menu()
+                {
                     let _ = self.ui_event_sender.send(UIEvent::ToggleMainWindow);
                 }
             },
@@ -249,17 +631,43 @@ impl TrayManager {
         // 根据菜单ID处理不同的事件
         if menu_id == self.menu_items.show_hide {
             let _ = self.ui_event_sender.send(UIEvent::ToggleMainWindow);
-        } else if menu_id == self.menu_items.start_countdown {
-            // 快速开始30分钟倒计时
-            let _ = self.ui_event_sender.send(UIEvent::QuickCountdown(30));
+        } else if let Some(index) = menu_id.strip_prefix(&self.menu_items.quick_preset_prefix) {
+            let preset = index.parse::<usize>().ok().and_then(|index| self.quick_presets.get(index));
+            match preset.map(QuickPreset::event) {
+                Some(Ok(event)) => {
+                    let _ = self.ui_event_sender.send(event);
+                },
+                Some(Err(e)) => warn!("无法解析快速倒计时预设: {}", e),
+                None => warn!("未知的快速倒计时预设: {}", menu_id),
+            }
+        } else if menu_id == self.menu_items.quick_custom {
+            // 在主窗口中输入时间
+            let _ = self.ui_event_sender.send(UIEvent::ShowMainWindow);
         } else if menu_id == self.menu_items.cancel_countdown {
             let _ = self.ui_event_sender.send(UIEvent::CancelCountdown);
+        } else if menu_id == self.menu_items.repeat_last {
+            let _ = self.ui_event_sender.send(UIEvent::RepeatLast);
         } else if menu_id == self.menu_items.settings {
             let _ = self.ui_event_sender.send(UIEvent::ShowSettings);
         } else if menu_id == self.menu_items.about {
             let _ = self.ui_event_sender.send(UIEvent::ShowAbout);
         } else if menu_id == self.menu_items.quit {
             let _ = self.ui_event_sender.send(UIEvent::Exit);
+        } else if menu_id == self.menu_items.quick_pause || menu_id == self.menu_items.pause_resume {
+            let event = if self.is_paused {
+                UIEvent::ResumeCountdown
+            } else {
+                UIEvent::PauseCountdown
+            };
+            let _ = self.ui_event_sender.send(event);
+        } else if menu_id == self.menu_items.quick_extend || menu_id == self.menu_items.snooze {
+            if self.postpone_allowed {
+                let _ = self.ui_event_sender.send(UIEvent::ExtendCountdown(SNOOZE_MINUTES * 60));
+            }
+        } else if menu_id == self.menu_items.quick_cancel {
+            let _ = self.ui_event_sender.send(UIEvent::CancelCountdown);
+        } else if menu_id == self.menu_items.quick_show {
+            let _ = self.ui_event_sender.send(UIEvent::ShowMainWindow);
         }
     }
     
@@ -271,40 +679,165 @@ impl TrayManager {
     pub fn update_status(&mut self, status: CountdownStatus) {
         self.current_status = status.clone();
         
-        // 更新托盘图标提示文本
+        // 非运行状态下不再保留暂停标记
+        if !matches!(status, CountdownStatus::Running { .. }) {
+            self.is_paused = false;
+        }
+        self.update_attention();
+        
+        // 更新托盘图标提示文本，下一次快照更新时重新写入完整提示
         if let Some(_tray_icon) = &self.tray_icon {
             let tooltip = self.generate_tooltip(&status);
             if let Err(e) = _tray_icon.set_tooltip(Some(&tooltip)) {
                 warn!("更新托盘提示失败: {}", e);
             }
         }
+        self.last_tooltip = None;
         
         // 更新菜单项状态
-        self.update_menu_items(&status);
+        self.update_menu_items();
+        self.refresh_quick_menu();
     }
     
-    /// 生成提示文本
+    /// 更新暂停状态
+    /// 
+    /// # 参数
+    /// 
+    /// * `paused` - 倒计时是否已暂停
+    pub fn set_paused(&mut self, paused: bool) {
+        self.is_paused = paused;
+        self.update_attention();
+        self.update_menu_items();
+        self.refresh_quick_menu();
+    }
+    
+    /// 根据状态快照更新托盘提示
+    /// 
+    /// 快照包含操作和暂停等任务信息，与命令行和本地HTTP接口显示一致；
+    /// 有多个任务时另起一行列出接下来要执行的事件。
+    /// 剩余时间按整分钟显示，只在分钟变化或状态变化时更新，最后一分钟内每秒更新
+    /// 
+    /// # 参数
+    /// 
+    /// * `snapshot` - 倒计时状态快照
+    /// * `upcoming` - 调度器计算的即将执行事件
+    pub fn update_tooltip(&mut self, snapshot: &StatusSnapshot, upcoming: &[(DateTime<Local>, TaskAction)]) {
+        let tray_icon = match &self.tray_icon {
+            Some(tray_icon) => tray_icon,
+            None => return,
+        };
+        let snapshot = self.throttled_snapshot(snapshot);
+        if self.last_tooltip.as_ref().is_some_and(|(last, last_upcoming)| *last == snapshot && last_upcoming == upcoming) {
+            return;
+        }
+        
+        let mut tooltip = Self::snapshot_tooltip(&snapshot, upcoming, Local::now());
+        if self.attention_frame.is_some() {
+            tooltip = format!("{}\n{}", ATTENTION_TOOLTIP_PREFIX, tooltip);
+        }
+        if let Err(e) = tray_icon.set_tooltip(Some(&tooltip)) {
+            warn!("更新托盘提示失败: {}", e);
+        }
+        self.last_tooltip = Some((snapshot, upcoming.to_vec()));
+    }
+    
+    /// 按剩余时间进入、保持或退出提醒状态
+    /// 
+    /// 提醒状态下每次更新都在正常图标和提醒图标之间切换，退出时恢复正常图标
+    fn update_attention(&mut self) {
+        let frame = Self::attention_frame(&self.current_status, self.is_paused, self.attention_seconds);
+        if frame == self.attention_frame {
+            return;
+        }
+        if frame.is_some() != self.attention_frame.is_some() {
+            info!("托盘图标{}提醒状态", if frame.is_some() { "进入" } else { "退出" });
+            self.last_tooltip = None;
+        }
+        self.attention_frame = frame;
+        
+        let tray_icon = match &self.tray_icon {
+            Some(tray_icon) => tray_icon,
+            None => return,
+        };
+        let icon = if frame == Some(true) {
+            self.create_attention_icon()
+        } else {
+            // 启动时已记录过资源图标的加载结果，这里不再重复警告
+            self.load_icon_from_resource().or_else(|_| self.create_default_icon())
+        };
+        match icon {
+            Ok(icon) => {
+                if let Err(e) = tray_icon.set_icon(Some(icon)) {
+                    warn!("更新托盘图标失败: {}", e);
+                }
+            },
+            Err(e) => warn!("{}", e),
+        }
+    }
+    
+    /// 提醒状态下应显示的图标
     /// 
     /// # 参数
     /// 
     /// * `status` - 倒计时状态
+    /// * `paused` - 倒计时是否已暂停，暂停时不提醒
+    /// * `attention_seconds` - 进入提醒状态的剩余秒数，0表示不提醒
     /// 
     /// # 返回值
     /// 
-    /// 提示文本字符串
-    fn generate_tooltip(&self, status: &CountdownStatus) -> String {
+    /// 不在提醒状态时返回None，否则按剩余秒数的奇偶返回是否显示提醒图标
+    fn attention_frame(status: &CountdownStatus, paused: bool, attention_seconds: i64) -> Option<bool> {
         match status {
-            CountdownStatus::Idle => "QtShut - 定时关机 (空闲)".to_string(),
-            CountdownStatus::Running { remaining } => {
-                let time_str = self.format_duration(remaining);
-                format!("QtShut - 剩余时间: {}", time_str)
+            CountdownStatus::Running { remaining } if !paused && attention_seconds > 0 && remaining.num_seconds() <= attention_seconds => {
+                Some(remaining.num_seconds() % 2 == 0)
             },
-            CountdownStatus::Finished => "QtShut - 倒计时结束".to_string(),
-            CountdownStatus::Cancelled => "QtShut - 任务已取消".to_string(),
-            CountdownStatus::Error(msg) => format!("QtShut - 错误: {}", msg),
+            _ => None,
         }
     }
     
+    /// 将剩余时间向上取整到分钟，最后一分钟内和提醒状态下保持按秒显示
+    /// 
+    /// 提示文本不显示截止时刻，没有关联任务时截止时刻由当前时间推算，每次都略有不同，
+    /// 因此不参与比较
+    fn throttled_snapshot(&self, snapshot: &StatusSnapshot) -> StatusSnapshot {
+        let per_second_seconds = if self.attention_frame.is_some() { self.attention_seconds } else { 0 }
+            .max(TOOLTIP_PER_SECOND_SECONDS);
+        let remaining_seconds = snapshot.remaining_seconds
+            .map(|seconds| if seconds > per_second_seconds { (seconds + 59) / 60 * 60 } else { seconds });
+        StatusSnapshot {
+            remaining_seconds,
+            deadline: None,
+            ..snapshot.clone()
+        }
+    }
+    
+    /// 由状态快照生成提示文本
+    /// 
+    /// 只有一个事件时状态行已经说明，不再重复列出
+    fn snapshot_tooltip(snapshot: &StatusSnapshot, upcoming: &[(DateTime<Local>, TaskAction)], now: DateTime<Local>) -> String {
+        let summary = format!("QtShut - {}", snapshot.summary());
+        if upcoming.len() > 1 {
+            format!("{}\n即将执行: {}", summary, scheduler::format_upcoming(upcoming, now))
+        } else {
+            summary
+        }
+    }
+    
+    /// 生成提示文本
+    /// 
+    /// # 参数
+    /// 
+    /// * `status` - 倒计时状态
+    /// 
+    /// # 返回值
+    /// 
+    /// 提示文本字符串
+    fn generate_tooltip(&self, status: &CountdownStatus) -> String {
+        let now = Local::now();
+        let snapshot = StatusSnapshot::from_parts(status, None, self.is_paused, None, now);
+        Self::snapshot_tooltip(&snapshot, &[], now)
+    }
+    
     /// 格式化时间间隔
     /// 
     /// # 参数
@@ -332,27 +865,41 @@ impl TrayManager {
         }
     }
     
-    /// 更新菜单项状态
+    /// 根据当前状态更新右键菜单
+    /// 
+    /// 倒计时运行时才能暂停、取消和推迟，不能再开始快速倒计时；剩余时间每分钟更新一次
+    fn update_menu_items(&mut self) {
+        let is_running = matches!(self.current_status, CountdownStatus::Running { .. });
+        let remaining_text = Self::remaining_menu_text(&self.current_status, self.is_paused);
+        if let Some(tray_menu) = &mut self.tray_menu {
+            tray_menu.start_menu.set_enabled(!is_running);
+            tray_menu.pause_item.set_enabled(is_running);
+            tray_menu.pause_item.set_text(if self.is_paused { "继续倒计时" } else { "暂停倒计时" });
+            tray_menu.cancel_item.set_enabled(is_running);
+            tray_menu.snooze_item.set_enabled(is_running && self.postpone_allowed);
+            if tray_menu.remaining_text != remaining_text {
+                tray_menu.remaining_item.set_text(&remaining_text);
+                tray_menu.remaining_text = remaining_text;
+            }
+        }
+    }
+    
+    /// 右键菜单中的剩余时间文字，如"剩余 01:05"（小时:分钟）
+    /// 
+    /// 剩余时间向上取整到分钟，因此每分钟只变化一次
     /// 
     /// # 参数
     /// 
     /// * `status` - 倒计时状态
-    fn update_menu_items(&self, status: &CountdownStatus) {
-        if let Some(_menu) = &self.tray_menu {
-            // 根据状态启用/禁用相应的菜单项
-            match status {
-                CountdownStatus::Idle | CountdownStatus::Cancelled | CountdownStatus::Error(_) => {
-                    // 启用开始倒计时，禁用取消倒计时
-                    // 注意：tray-icon库的菜单项更新API可能有所不同
-                    // 这里提供一个概念性的实现
-                },
-                CountdownStatus::Running { .. } => {
-                    // 禁用开始倒计时，启用取消倒计时
-                },
-                CountdownStatus::Finished => {
-                    // 禁用所有倒计时相关操作
-                }
-            }
+    /// * `paused` - 倒计时是否已暂停
+    fn remaining_menu_text(status: &CountdownStatus, paused: bool) -> String {
+        match status {
+            CountdownStatus::Running { remaining } => {
+                let minutes = (remaining.num_seconds().max(0) + 59) / 60;
+                let text = format!("剩余 {:02}:{:02}", minutes / 60, minutes % 60);
+                if paused { format!("{}（已暂停）", text) } else { text }
+            },
+            _ => "当前没有倒计时".to_string(),
         }
     }
     
@@ -384,6 +931,7 @@ impl TrayManager {
         }
         
         self.tray_menu = None;
+        self.quick_menu = None;
     }
     
     /// 清理托盘资源
@@ -449,8 +997,14 @@ impl std::fmt::Debug for TrayManager {
         f.debug_struct("TrayManager")
             .field("current_status", &self.current_status)
             .field("menu_items", &self.menu_items)
+            .field("window_handle", &self.window_handle)
+            .field("is_paused", &self.is_paused)
+            .field("attention_seconds", &self.attention_seconds)
+            .field("attention_frame", &self.attention_frame)
+            .field("quick_presets", &self.quick_presets)
             .field("tray_icon", &"<TrayIcon>")
             .field("tray_menu", &"<Menu>")
+            .field("quick_menu", &"<QuickMenu>")
             .field("ui_event_sender", &"<Sender>")
             .finish()
     }
@@ -494,6 +1048,52 @@ mod tests {
         assert!(running_tooltip.contains("剩余时间"));
     }
     
+    #[test]
+    fn test_upcoming_tooltip() {
+        let now = Local::now();
+        let snapshot = StatusSnapshot::from_parts(&CountdownStatus::Idle, None, false, None, now);
+        let first = (now + chrono::Duration::minutes(30), TaskAction::Lock);
+        let second = (now + chrono::Duration::minutes(90), TaskAction::Shutdown);
+        
+        // 只有一个事件时不额外列出
+        let tooltip = TrayManager::snapshot_tooltip(&snapshot, &[first], now);
+        assert!(!tooltip.contains("即将执行"));
+        
+        let tooltip = TrayManager::snapshot_tooltip(&snapshot, &[first, second], now);
+        assert!(tooltip.contains("即将执行"));
+        assert!(tooltip.contains("锁定 · "));
+        assert!(tooltip.ends_with("关机"));
+    }
+    
+    #[test]
+    fn test_tooltip_throttle() {
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let mut manager = TrayManager::new(sender);
+        let now = Local::now();
+        let running = |seconds| StatusSnapshot::from_parts(
+            &CountdownStatus::Running { remaining: chrono::Duration::seconds(seconds) },
+            None,
+            false,
+            None,
+            now,
+        );
+        
+        // 同一分钟内的快照相同，最后一分钟内每秒都不同
+        assert_eq!(manager.throttled_snapshot(&running(299)), manager.throttled_snapshot(&running(241)));
+        assert_eq!(manager.throttled_snapshot(&running(299)).remaining_seconds, Some(300));
+        assert_ne!(manager.throttled_snapshot(&running(241)), manager.throttled_snapshot(&running(240)));
+        assert_ne!(manager.throttled_snapshot(&running(59)), manager.throttled_snapshot(&running(58)));
+        
+        // 提醒状态下每秒都不同
+        manager.set_attention_minutes(5);
+        manager.update_status(CountdownStatus::Running { remaining: chrono::Duration::seconds(299) });
+        assert_ne!(manager.throttled_snapshot(&running(299)), manager.throttled_snapshot(&running(298)));
+        
+        // 没有托盘图标时不记录
+        manager.update_tooltip(&running(299), &[]);
+        assert!(manager.last_tooltip.is_none());
+    }
+    
     #[test]
     fn test_duration_formatting() {
         let (sender, _receiver) = mpsc::unbounded_channel();
@@ -505,4 +1105,128 @@ mod tests {
         let duration = chrono::Duration::seconds(61); // 1分1秒
         assert_eq!(manager.format_duration(&duration), "01:01");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_quick_menu_events() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut manager = TrayManager::new(sender);
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_pause".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::PauseCountdown)));
+        
+        manager.set_paused(true);
+        manager.handle_menu_event(MenuEvent { id: "quick_pause".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ResumeCountdown)));
+        
+        // 右键菜单中的暂停项与快捷菜单相同
+        manager.handle_menu_event(MenuEvent { id: "pause_resume".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ResumeCountdown)));
+        manager.set_paused(false);
+        manager.handle_menu_event(MenuEvent { id: "pause_resume".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::PauseCountdown)));
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_extend".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ExtendCountdown(600))));
+        
+        manager.handle_menu_event(MenuEvent { id: "snooze".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ExtendCountdown(600))));
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_cancel".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::CancelCountdown)));
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_show".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ShowMainWindow)));
+        
+        // 管理策略要求PIN时不能推迟
+        manager.set_postpone_allowed(false);
+        manager.handle_menu_event(MenuEvent { id: "snooze".into() });
+        assert!(receiver.try_recv().is_err());
+    }
+    
+    #[test]
+    fn test_quick_presets_send_events() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut manager = TrayManager::new(sender);
+        manager.set_quick_presets(&[Duration::minutes(15), Duration::minutes(90)], &["23:00".to_string(), "无法解析".to_string()]);
+        assert_eq!(manager.quick_presets[1].label(), "90分钟");
+        assert_eq!(manager.quick_presets[2].label(), "23:00");
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_preset_1".into() });
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(UIEvent::QuickCountdown(duration)) if duration == Duration::minutes(90)
+        ));
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_preset_2".into() });
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(UIEvent::StartCountdown(_, TaskType::Once, TaskAction::Shutdown))
+        ));
+        
+        // 无法解析的预设和不存在的序号不发送事件
+        manager.handle_menu_event(MenuEvent { id: "quick_preset_3".into() });
+        manager.handle_menu_event(MenuEvent { id: "quick_preset_9".into() });
+        assert!(receiver.try_recv().is_err());
+        
+        manager.handle_menu_event(MenuEvent { id: "quick_custom".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::ShowMainWindow)));
+        
+        manager.handle_menu_event(MenuEvent { id: "repeat_last".into() });
+        assert!(matches!(receiver.try_recv(), Ok(UIEvent::RepeatLast)));
+    }
+    
+    #[test]
+    fn test_quick_menu_status_text() {
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let mut manager = TrayManager::new(sender);
+        
+        assert_eq!(manager.quick_menu_status_text(), "当前没有倒计时");
+        
+        manager.update_status(CountdownStatus::Running {
+            remaining: chrono::Duration::seconds(125)
+        });
+        assert_eq!(manager.quick_menu_status_text(), "剩余时间: 02:05");
+        
+        manager.set_paused(true);
+        assert!(manager.quick_menu_status_text().starts_with("已暂停"));
+        
+        // 倒计时结束后清除暂停标记
+        manager.update_status(CountdownStatus::Finished);
+        assert!(!manager.is_paused);
+        
+        // 未绑定窗口时无法弹出快捷菜单
+        assert!(!manager.show_quick_menu());
+    }
+    
+    #[test]
+    fn test_attention_frame() {
+        let running = |seconds| CountdownStatus::Running { remaining: chrono::Duration::seconds(seconds) };
+        assert_eq!(TrayManager::attention_frame(&running(301), false, 300), None);
+        // 进入提醒状态后每秒切换图标
+        assert_eq!(TrayManager::attention_frame(&running(300), false, 300), Some(true));
+        assert_eq!(TrayManager::attention_frame(&running(299), false, 300), Some(false));
+        // 暂停、未开启或不在倒计时时不提醒
+        assert_eq!(TrayManager::attention_frame(&running(10), true, 300), None);
+        assert_eq!(TrayManager::attention_frame(&running(10), false, 0), None);
+        assert_eq!(TrayManager::attention_frame(&CountdownStatus::Finished, false, 300), None);
+        
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let mut manager = TrayManager::new(sender);
+        manager.set_attention_minutes(5);
+        manager.update_status(running(120));
+        assert_eq!(manager.attention_frame, Some(true));
+        manager.set_paused(true);
+        assert_eq!(manager.attention_frame, None);
+    }
+    
+    #[test]
+    fn test_remaining_menu_text() {
+        let running = |seconds| CountdownStatus::Running { remaining: chrono::Duration::seconds(seconds) };
+        assert_eq!(TrayManager::remaining_menu_text(&running(754 * 60), false), "剩余 12:34");
+        // 向上取整到分钟，同一分钟内文字不变
+        assert_eq!(TrayManager::remaining_menu_text(&running(3841), false), "剩余 01:05");
+        assert_eq!(TrayManager::remaining_menu_text(&running(3900), false), "剩余 01:05");
+        assert_eq!(TrayManager::remaining_menu_text(&running(30), true), "剩余 00:01（已暂停）");
+        assert_eq!(TrayManager::remaining_menu_text(&CountdownStatus::Idle, false), "当前没有倒计时");
+    }
+}
@@ -0,0 +1,186 @@
+//! 全屏最后警告模块
+//! 
+//! 倒计时最后几秒在所有窗口之上打开一个全屏窗口，显示巨大的倒计时和"取消"按钮，
+//! 主窗口被遮挡或最小化到托盘时也能及时中止
+
+use iced::{window, Command};
+use log::info;
+
+use crate::core::{plan::SHORT_COUNTDOWN_SECONDS, types::CountdownStatus};
+
+/// 全屏最后警告窗口
+#[derive(Debug)]
+pub struct WarningOverlay {
+    /// 剩余多少秒时显示，0表示不显示
+    seconds: u32,
+    /// 已打开的警告窗口
+    window: Option<window::Id>,
+    /// 倒计时是否已暂停
+    paused: bool,
+    /// 是否为不到一分钟的短倒计时，开始后立即显示
+    immediate: bool,
+}
+
+impl WarningOverlay {
+    /// 创建全屏警告
+    /// 
+    /// # 参数
+    /// 
+    /// * `seconds` - 剩余多少秒时显示，0表示不显示
+    pub fn new(seconds: u32) -> Self {
+        Self { seconds, window: None, paused: false, immediate: false }
+    }
+    
+    /// 设置剩余多少秒时显示
+    /// 
+    /// # 参数
+    /// 
+    /// * `seconds` - 剩余秒数，0表示不显示
+    pub fn set_seconds(&mut self, seconds: u32) {
+        self.seconds = seconds;
+    }
+    
+    /// 设置是否为短倒计时，短倒计时在剩余时间不超过`SHORT_COUNTDOWN_SECONDS`时显示
+    /// 
+    /// # 参数
+    /// 
+    /// * `immediate` - 是否为短倒计时
+    pub fn set_immediate(&mut self, immediate: bool) {
+        self.immediate = immediate;
+    }
+    
+    /// 设置倒计时是否已暂停，暂停期间不显示警告
+    /// 
+    /// # 参数
+    /// 
+    /// * `paused` - 是否已暂停
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+    
+    /// 已打开的警告窗口
+    pub fn window(&self) -> Option<window::Id> {
+        self.window
+    }
+    
+    /// 当前状态下是否应显示全屏警告
+    /// 
+    /// # 参数
+    /// 
+    /// * `status` - 倒计时状态
+    pub fn should_show(&self, status: &CountdownStatus) -> bool {
+        if self.paused || self.seconds == 0 {
+            return false;
+        }
+        let limit = if self.immediate {
+            (self.seconds as i64).max(SHORT_COUNTDOWN_SECONDS)
+        } else {
+            self.seconds as i64
+        };
+        match status {
+            CountdownStatus::Running { remaining } => {
+                let seconds = remaining.num_seconds();
+                seconds > 0 && seconds <= limit
+            },
+            _ => false,
+        }
+    }
+    
+    /// 按倒计时状态打开或关闭警告窗口
+    /// 
+    /// 进入最后阶段时打开，取消、延长或倒计时结束后关闭
+    /// 
+    /// # 参数
+    /// 
+    /// * `status` - 倒计时状态
+    /// 
+    /// # 返回值
+    /// 
+    /// 打开或关闭窗口的命令，状态不变时为空命令
+    pub fn sync<Message: 'static>(&mut self, status: &CountdownStatus) -> Command<Message> {
+        match (self.should_show(status), self.window) {
+            (true, None) => {
+                info!("打开全屏最后警告");
+                let (id, spawn) = window::spawn(Self::window_settings());
+                self.window = Some(id);
+                Command::batch([
+                    spawn,
+                    window::change_mode(id, window::Mode::Fullscreen),
+                    window::gain_focus(id),
+                ])
+            },
+            (false, Some(id)) => {
+                info!("关闭全屏最后警告");
+                self.window = None;
+                window::close(id)
+            },
+            _ => Command::none(),
+        }
+    }
+    
+    /// 警告窗口设置
+    /// 
+    /// 无边框并始终置顶；关闭请求被忽略，只能通过取消按钮或倒计时结束关闭
+    fn window_settings() -> window::Settings {
+        window::Settings {
+            size: iced::Size::new(800.0, 600.0),
+            position: window::Position::Centered,
+            resizable: false,
+            decorations: false,
+            level: window::Level::AlwaysOnTop,
+            exit_on_close_request: false,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    
+    #[test]
+    fn test_should_show() {
+        let overlay = WarningOverlay::new(30);
+        assert!(overlay.should_show(&CountdownStatus::Running { remaining: Duration::seconds(30) }));
+        assert!(!overlay.should_show(&CountdownStatus::Running { remaining: Duration::seconds(31) }));
+        assert!(!overlay.should_show(&CountdownStatus::Running { remaining: Duration::zero() }));
+        assert!(!overlay.should_show(&CountdownStatus::Finished));
+        
+        // 设置为0时不显示
+        let mut disabled = WarningOverlay::new(0);
+        assert!(!disabled.should_show(&CountdownStatus::Running { remaining: Duration::seconds(5) }));
+        disabled.set_immediate(true);
+        assert!(!disabled.should_show(&CountdownStatus::Running { remaining: Duration::seconds(5) }));
+        
+        // 短倒计时开始后立即显示，延长后不再显示
+        let mut overlay = WarningOverlay::new(30);
+        overlay.set_immediate(true);
+        assert!(overlay.should_show(&CountdownStatus::Running { remaining: Duration::seconds(45) }));
+        assert!(!overlay.should_show(&CountdownStatus::Running { remaining: Duration::minutes(10) }));
+    }
+    
+    #[test]
+    fn test_sync_closes_after_cancel() {
+        let mut overlay = WarningOverlay::new(30);
+        let _command: Command<()> = overlay.sync(&CountdownStatus::Running { remaining: Duration::seconds(10) });
+        assert!(overlay.window().is_some());
+        
+        let _command: Command<()> = overlay.sync(&CountdownStatus::Cancelled);
+        assert!(overlay.window().is_none());
+    }
+    
+    #[test]
+    fn test_hidden_while_paused() {
+        let mut overlay = WarningOverlay::new(30);
+        let status = CountdownStatus::Running { remaining: Duration::seconds(10) };
+        let _command: Command<()> = overlay.sync(&status);
+        
+        overlay.set_paused(true);
+        let _command: Command<()> = overlay.sync(&status);
+        assert!(overlay.window().is_none());
+        
+        overlay.set_paused(false);
+        assert!(overlay.should_show(&status));
+    }
+}
@@ -2,14 +2,31 @@
 //! 
 //! 负责应用程序配置的加载、保存和管理
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::fs;
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use dirs::config_dir;
 
-use crate::ui::theme::ThemeType;
-use crate::core::types::ShutdownMethod;
+use crate::ui::theme::{ThemeType, DEFAULT_FONT_FAMILY};
+use crate::core::battery::BatterySettings;
+use crate::core::condition::ConditionSettings;
+use crate::core::locale::{LocalePack, DEFAULT_LOCALE, LOCALE_DIR};
+use crate::core::migration::{self, SchemaKind};
+use crate::core::persistence::{backup_file, backup_time, is_sealed, list_backups, open_data, seal_data, write_atomic, DEFAULT_MAX_BACKUPS};
+use crate::platform::{self, DataProtection};
+use crate::core::plan::{parse_warning_offsets, DEFAULT_WARNING_OFFSETS, FINAL_WARNING_SECONDS};
+use crate::core::remote::RemoteSettings;
+use crate::core::screen_time::{ScreenTimeSettings, MAX_DAILY_MINUTES};
+use crate::core::shutdown::DEFAULT_REASON_TEMPLATE;
+use crate::core::storage::StorageBackend;
+use crate::core::trigger::NetworkIdleSettings;
+use crate::core::webhook::WebhookSettings;
+use crate::core::types::{ActivityBehavior, CustomCommand, LockedBehavior, ShutdownMethod, TaskAction};
+use crate::utils::notification::{default_sound_profiles, NotificationType, SoundProfile, DEFAULT_DEDUP_WINDOW_SECONDS};
+use crate::utils::sound::DEFAULT_VOLUME;
+use crate::utils::policy::Policy;
 
 /// 应用程序配置
 /// 
@@ -24,6 +41,18 @@ pub struct AppConfig {
     pub shutdown: ShutdownSettings,
     /// 高级设置
     pub advanced: AdvancedSettings,
+    /// 直播倒计时叠加层设置
+    #[serde(default)]
+    pub overlay: OverlaySettings,
+    /// 提醒声音设置
+    #[serde(default)]
+    pub sounds: SoundSettings,
+    /// 远程关机设置
+    #[serde(default)]
+    pub remote: RemoteSettings,
+    /// 外部通知设置
+    #[serde(default)]
+    pub webhook: WebhookSettings,
 }
 
 /// 应用程序基本设置
@@ -39,6 +68,14 @@ pub struct AppSettings {
     pub language: String,
     /// 检查更新
     pub check_updates: bool,
+    /// 时间解析使用的语言包，按顺序合并，后面的覆盖前面的同名条目
+    #[serde(default = "default_parser_locales")]
+    pub parser_locales: Vec<String>,
+}
+
+/// 默认的时间解析语言包
+fn default_parser_locales() -> Vec<String> {
+    vec![DEFAULT_LOCALE.to_string()]
 }
 
 /// UI界面设置
@@ -50,12 +87,57 @@ pub struct UISettings {
     pub custom_theme: Option<String>,
     /// 窗口位置
     pub window_position: Option<(f32, f32)>,
+    /// 窗口所在显示器的设备名称
+    #[serde(default)]
+    pub window_monitor: Option<String>,
     /// 窗口大小
     pub window_size: Option<(f32, f32)>,
     /// 总是置顶
     pub always_on_top: bool,
     /// 显示托盘通知
     pub show_tray_notifications: bool,
+    /// 屏幕阅读器播报剩余时间的间隔（分钟），0表示不播报
+    #[serde(default)]
+    pub announce_interval_minutes: u32,
+    /// 日落后自动深色时切换到深色主题的时间（时），为空时使用日落时间
+    #[serde(default)]
+    pub auto_dark_hour: Option<u32>,
+    /// 合并相同通知的时间窗口（秒），0表示不合并
+    #[serde(default = "default_notification_dedup_seconds")]
+    pub notification_dedup_seconds: u64,
+    /// 倒计时剩余多少秒时显示全屏最后警告，0表示不显示
+    #[serde(default = "default_fullscreen_warning_seconds")]
+    pub fullscreen_warning_seconds: u32,
+    /// 取消倒计时后询问取消原因并记录到历史
+    #[serde(default)]
+    pub ask_cancel_reason: bool,
+    /// 最后警告期间开启专注助手，屏蔽其他程序的通知
+    #[serde(default)]
+    pub focus_assist_in_final_warning: bool,
+    /// 界面字体，为空时使用默认字体
+    #[serde(default)]
+    pub font_family: Option<String>,
+    /// 大按钮模式，适合触摸屏
+    #[serde(default)]
+    pub large_buttons: bool,
+    /// 倒计时剩余多少分钟时托盘图标闪烁提醒，0表示不闪烁
+    #[serde(default = "default_tray_attention_minutes")]
+    pub tray_attention_minutes: u32,
+}
+
+/// 默认合并相同通知的时间窗口（秒）
+fn default_notification_dedup_seconds() -> u64 {
+    DEFAULT_DEDUP_WINDOW_SECONDS
+}
+
+/// 默认显示全屏最后警告的剩余秒数，与最后确认阶段一致
+fn default_fullscreen_warning_seconds() -> u32 {
+    FINAL_WARNING_SECONDS as u32
+}
+
+/// 默认托盘图标开始闪烁的剩余分钟数
+fn default_tray_attention_minutes() -> u32 {
+    5
 }
 
 /// 关机相关设置
@@ -69,8 +151,46 @@ pub struct ShutdownSettings {
     pub confirmation_timeout: u32,
     /// 强制关机（忽略未保存的工作）
     pub force_shutdown: bool,
-    /// 关机前警告时间（分钟）
-    pub warning_time: u32,
+    /// 到点前的提醒时间，如"30m"、"10s"
+    #[serde(default = "default_warning_offsets")]
+    pub warning_offsets: Vec<String>,
+    /// "运行命令"操作执行的自定义命令
+    #[serde(default)]
+    pub custom_command: CustomCommand,
+    /// 系统关机对话框中显示的原因，支持{task}、{action}、{seconds}、{time}、{source}占位符
+    #[serde(default = "default_reason_template")]
+    pub reason_template: String,
+    /// 到点时工作站已锁定的处理方式
+    #[serde(default)]
+    pub when_locked: LockedBehavior,
+    /// 最后警告期间有人使用电脑时的处理方式
+    #[serde(default)]
+    pub on_activity: ActivityBehavior,
+    /// 到点后执行操作前检查的条件
+    #[serde(default)]
+    pub conditions: ConditionSettings,
+    /// "下载完成后执行"的网络空闲判断
+    #[serde(default)]
+    pub network_idle: NetworkIdleSettings,
+    /// 使用电池供电时的处理方式
+    #[serde(default)]
+    pub battery: BatterySettings,
+    /// 每日屏幕使用时间限制
+    #[serde(default)]
+    pub screen_time: ScreenTimeSettings,
+    /// 是否把每日和每周计划同步到Windows任务计划程序
+    #[serde(default)]
+    pub system_tasks: bool,
+}
+
+/// 默认的到点前提醒时间
+fn default_warning_offsets() -> Vec<String> {
+    DEFAULT_WARNING_OFFSETS.iter().map(|offset| offset.to_string()).collect()
+}
+
+/// 默认的关机原因模板
+fn default_reason_template() -> String {
+    DEFAULT_REASON_TEMPLATE.to_string()
 }
 
 /// 高级设置
@@ -86,6 +206,57 @@ pub struct AdvancedSettings {
     pub max_backup_files: u32,
     /// 性能监控
     pub performance_monitoring: bool,
+    /// 数据目录（日志、历史等）总大小上限（MB）
+    #[serde(default = "default_max_data_size_mb")]
+    pub max_data_size_mb: u64,
+    /// 将界面事件和倒计时更新录制到数据目录的`events.jsonl`，用于排查问题
+    #[serde(default)]
+    pub record_events: bool,
+    /// 加密保存数据目录中的任务、配置和配置方案，只有当前用户能读取（仅Windows）
+    #[serde(default)]
+    pub encrypt_data: bool,
+    /// 任务和历史记录的存储方式，SQLite需要编译时启用`sqlite`特性
+    #[serde(default)]
+    pub storage: StorageBackend,
+}
+
+/// 默认的数据目录大小上限（MB）
+fn default_max_data_size_mb() -> u64 {
+    50
+}
+
+/// 直播倒计时叠加层设置
+/// 
+/// 叠加层以本地网页形式提供，可作为OBS浏览器源使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// 是否启用叠加层
+    pub enabled: bool,
+    /// 本地监听端口
+    pub port: u16,
+    /// 文字颜色
+    pub text_color: String,
+    /// 背景颜色，为空时使用透明背景，可设置为绿幕色如"#00FF00"
+    pub background_color: Option<String>,
+    /// 字体大小（像素）
+    pub font_size: u32,
+}
+
+/// 提醒声音设置
+/// 
+/// 为每种操作分别设置到点前提醒的提示音和语音文本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    /// 各操作的声音方案
+    pub profiles: HashMap<TaskAction, SoundProfile>,
+    /// 1分钟以前的到点前提醒是否播放提示音，关闭后只在最后1分钟内播放
+    pub early_warnings: bool,
+    /// 各类型通知代替系统提示音播放的WAV或MP3文件
+    pub files: HashMap<NotificationType, String>,
+    /// 播放声音文件的音量（百分比）
+    pub volume: u8,
 }
 
 impl Default for AppConfig {
@@ -95,6 +266,21 @@ impl Default for AppConfig {
             ui: UISettings::default(),
             shutdown: ShutdownSettings::default(),
             advanced: AdvancedSettings::default(),
+            overlay: OverlaySettings::default(),
+            sounds: SoundSettings::default(),
+            remote: RemoteSettings::default(),
+            webhook: WebhookSettings::default(),
+        }
+    }
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            profiles: default_sound_profiles(),
+            early_warnings: true,
+            files: HashMap::new(),
+            volume: DEFAULT_VOLUME,
         }
     }
 }
@@ -107,6 +293,7 @@ impl Default for AppSettings {
             minimize_on_close: true,
             language: "zh-CN".to_string(),
             check_updates: true,
+            parser_locales: default_parser_locales(),
         }
     }
 }
@@ -117,9 +304,19 @@ impl Default for UISettings {
             theme_type: ThemeType::Light,
             custom_theme: None,
             window_position: None,
+            window_monitor: None,
             window_size: Some((400.0, 500.0)),
             always_on_top: false,
             show_tray_notifications: true,
+            announce_interval_minutes: 0,
+            auto_dark_hour: None,
+            notification_dedup_seconds: DEFAULT_DEDUP_WINDOW_SECONDS,
+            fullscreen_warning_seconds: default_fullscreen_warning_seconds(),
+            ask_cancel_reason: false,
+            focus_assist_in_final_warning: false,
+            font_family: None,
+            large_buttons: false,
+            tray_attention_minutes: default_tray_attention_minutes(),
         }
     }
 }
@@ -131,7 +328,28 @@ impl Default for ShutdownSettings {
             confirm_before_shutdown: true,
             confirmation_timeout: 30,
             force_shutdown: false,
-            warning_time: 5,
+            warning_offsets: default_warning_offsets(),
+            custom_command: CustomCommand::default(),
+            reason_template: default_reason_template(),
+            when_locked: LockedBehavior::default(),
+            on_activity: ActivityBehavior::default(),
+            conditions: ConditionSettings::default(),
+            network_idle: NetworkIdleSettings::default(),
+            battery: BatterySettings::default(),
+            screen_time: ScreenTimeSettings::default(),
+            system_tasks: false,
+        }
+    }
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 17321,
+            text_color: "#FFFFFF".to_string(),
+            background_color: None,
+            font_size: 96,
         }
     }
 }
@@ -144,10 +362,17 @@ impl Default for AdvancedSettings {
             backup_data: true,
             max_backup_files: 5,
             performance_monitoring: false,
+            max_data_size_mb: default_max_data_size_mb(),
+            record_events: false,
+            encrypt_data: false,
+            storage: StorageBackend::default(),
         }
     }
 }
 
+/// 配置文件备份的文件名前缀
+const CONFIG_BACKUP_PREFIX: &str = "config-";
+
 /// 配置管理器
 /// 
 /// 负责配置文件的加载、保存和管理
@@ -167,7 +392,10 @@ impl ConfigManager {
     /// 成功返回配置管理器，失败返回错误信息
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let config_path = Self::get_config_file_path()?;
-        let config = Self::load_config(&config_path)?;
+        let mut config = Self::load_config(&config_path)?;
+        
+        // 计算机级策略固定的设置优先于用户配置
+        Policy::load().apply_to_config(&mut config);
         
         Ok(Self {
             config_path,
@@ -180,7 +408,7 @@ impl ConfigManager {
     /// # 返回值
     /// 
     /// 成功返回配置文件路径，失败返回错误信息
-    fn get_config_file_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    pub fn get_config_file_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
         let config_dir = config_dir()
             .ok_or("无法获取配置目录")?;
         
@@ -217,28 +445,53 @@ impl ConfigManager {
         
         info!("加载配置文件: {:?}", path);
         
-        let config_content = fs::read_to_string(path)?;
+        // 是否加密保存在配置文件中，加密和未加密的配置文件都要能读取
+        let data = fs::read(path)?;
+        let sealed = is_sealed(&data);
+        let config_content = open_data(data, platform::native(), false)?;
         
-        match serde_json::from_str::<AppConfig>(&config_content) {
+        let value = match serde_json::from_str::<serde_json::Value>(&config_content) {
+            Ok(value) => value,
+            Err(e) => return Self::reset_corrupted_config(path, &e.to_string()),
+        };
+        
+        // 更新版本的程序保存的配置不能用默认配置覆盖，返回错误后各项设置按默认值处理
+        migration::check_supported(SchemaKind::Config, &value)?;
+        
+        let config = migration::migrate(SchemaKind::Config, value)
+            .and_then(|value| serde_json::from_value::<AppConfig>(value).map_err(Into::into));
+        match config {
             Ok(config) => {
                 info!("配置文件加载成功");
+                if !sealed && Self::protection(&config).is_some() {
+                    // 开启加密后仍以明文保存的配置文件立即重新加密
+                    Self::save_config_to_file(&config, path)?;
+                }
                 Ok(config)
             },
-            Err(e) => {
-                warn!("配置文件格式错误: {}, 使用默认配置", e);
-                
-                // 备份损坏的配置文件
-                let backup_path = path.with_extension("json.backup");
-                if let Err(backup_err) = fs::copy(path, &backup_path) {
-                    warn!("备份损坏的配置文件失败: {}", backup_err);
-                }
-                
-                let default_config = AppConfig::default();
-                Self::save_config_to_file(&default_config, path)?;
-                
-                Ok(default_config)
-            }
+            Err(e) => Self::reset_corrupted_config(path, &e.to_string()),
+        }
+    }
+    
+    /// 备份无法读取的配置文件并改用默认配置
+    /// 
+    /// # 参数
+    /// 
+    /// * `path` - 配置文件路径
+    /// * `reason` - 无法读取的原因
+    fn reset_corrupted_config(path: &Path, reason: &str) -> Result<AppConfig, Box<dyn std::error::Error>> {
+        warn!("配置文件格式错误: {}, 使用默认配置", reason);
+        
+        // 备份损坏的配置文件
+        let backup_path = path.with_extension("json.backup");
+        if let Err(backup_err) = fs::copy(path, &backup_path) {
+            warn!("备份损坏的配置文件失败: {}", backup_err);
         }
+        
+        let default_config = AppConfig::default();
+        Self::save_config_to_file(&default_config, path)?;
+        
+        Ok(default_config)
     }
     
     /// 保存配置到文件
@@ -252,12 +505,359 @@ impl ConfigManager {
     /// 
     /// 成功返回Ok(())，失败返回错误信息
     fn save_config_to_file(config: &AppConfig, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        let config_json = serde_json::to_string_pretty(config)?;
-        fs::write(path, config_json)?;
+        let value = migration::stamp(SchemaKind::Config, serde_json::to_value(config)?)?;
+        let config_json = serde_json::to_string_pretty(&value)?;
+        let max_backups = if config.advanced.backup_data { config.advanced.max_backup_files as usize } else { 0 };
+        backup_file(path, &Self::backup_dir(path), CONFIG_BACKUP_PREFIX, max_backups);
+        write_atomic(path, &seal_data(&config_json, Self::protection(config))?)?;
         info!("配置文件保存成功: {:?}", path);
         Ok(())
     }
     
+    /// 保存配置文件使用的加密接口，未开启加密或当前平台不支持时为None
+    /// 
+    /// # 参数
+    /// 
+    /// * `config` - 配置对象
+    fn protection(config: &AppConfig) -> Option<&'static dyn DataProtection> {
+        if config.advanced.encrypt_data && platform::capabilities().data_protection {
+            Some(platform::native())
+        } else {
+            None
+        }
+    }
+    
+    /// 读取设置中是否启动时最小化到托盘
+    /// 
+    /// 配置加载失败时正常显示主窗口
+    pub fn load_start_minimized() -> bool {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().app.start_minimized,
+            Err(e) => {
+                warn!("加载启动设置失败: {}", e);
+                false
+            }
+        }
+    }
+    
+    /// 读取关闭主窗口时是否隐藏到托盘
+    /// 
+    /// 配置加载失败时隐藏到托盘，避免误关窗口后倒计时停止
+    pub fn load_minimize_on_close() -> bool {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().app.minimize_on_close,
+            Err(e) => {
+                warn!("加载关闭窗口设置失败: {}", e);
+                true
+            }
+        }
+    }
+    
+    /// 读取设置中是否开机自动启动
+    /// 
+    /// 配置加载失败时不改变当前的登记状态
+    pub fn load_auto_start() -> Option<bool> {
+        match Self::new() {
+            Ok(config_manager) => Some(config_manager.get_config().app.auto_start),
+            Err(e) => {
+                warn!("加载开机自动启动设置失败: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// 读取是否录制事件
+    /// 
+    /// 配置加载失败时不录制
+    pub fn load_record_events() -> bool {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().advanced.record_events,
+            Err(e) => {
+                warn!("加载事件录制设置失败: {}", e);
+                false
+            }
+        }
+    }
+    
+    /// 读取设置中的界面字体
+    /// 
+    /// 未设置或配置加载失败时使用默认字体
+    pub fn load_font_family() -> String {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().ui.font_family.clone()
+                .unwrap_or_else(|| DEFAULT_FONT_FAMILY.to_string()),
+            Err(e) => {
+                warn!("加载字体设置失败: {}", e);
+                DEFAULT_FONT_FAMILY.to_string()
+            }
+        }
+    }
+    
+    /// 读取设置中的关机原因模板
+    /// 
+    /// 配置加载失败时使用默认模板
+    pub fn load_reason_template() -> String {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.reason_template.clone(),
+            Err(e) => {
+                warn!("加载关机原因模板失败: {}", e);
+                default_reason_template()
+            }
+        }
+    }
+    
+    /// 读取设置中到点时工作站已锁定的处理方式
+    /// 
+    /// 配置加载失败时按时执行
+    pub fn load_locked_behavior() -> LockedBehavior {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.when_locked,
+            Err(e) => {
+                warn!("加载锁定时的处理方式失败: {}", e);
+                LockedBehavior::default()
+            }
+        }
+    }
+    
+    /// 读取设置中最后警告期间检测到输入时的处理方式
+    /// 
+    /// 配置加载失败时不处理
+    pub fn load_activity_behavior() -> ActivityBehavior {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.on_activity,
+            Err(e) => {
+                warn!("加载检测到输入时的处理方式失败: {}", e);
+                ActivityBehavior::default()
+            }
+        }
+    }
+    
+    /// 读取设置中的日志级别
+    /// 
+    /// 级别无效或配置加载失败时使用info
+    pub fn load_log_level() -> log::LevelFilter {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().advanced.log_level.parse().unwrap_or_else(|_| {
+                warn!("日志级别设置有误，使用info: {}", config_manager.get_config().advanced.log_level);
+                log::LevelFilter::Info
+            }),
+            Err(e) => {
+                warn!("加载日志级别失败: {}", e);
+                log::LevelFilter::Info
+            }
+        }
+    }
+    
+    /// 读取设置中是否加密保存数据
+    /// 
+    /// 配置加载失败时不加密，已加密的文件仍能读取
+    pub fn load_encrypt_data() -> bool {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().advanced.encrypt_data,
+            Err(e) => {
+                warn!("加载加密设置失败: {}", e);
+                false
+            }
+        }
+    }
+    
+    /// 读取设置中的存储方式
+    /// 
+    /// 配置加载失败时使用JSON文件
+    pub fn load_storage_backend() -> StorageBackend {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().advanced.storage,
+            Err(e) => {
+                warn!("加载存储设置失败: {}", e);
+                StorageBackend::default()
+            }
+        }
+    }
+    
+    /// 读取设置中保留的任务文件备份数
+    /// 
+    /// 关闭数据备份时为0，配置加载失败时使用默认值
+    pub fn load_max_backup_files() -> usize {
+        match Self::new() {
+            Ok(config_manager) => {
+                let advanced = &config_manager.get_config().advanced;
+                if advanced.backup_data { advanced.max_backup_files as usize } else { 0 }
+            },
+            Err(e) => {
+                warn!("加载备份设置失败: {}", e);
+                DEFAULT_MAX_BACKUPS
+            }
+        }
+    }
+    
+    /// 读取设置中到点前的提醒时间
+    /// 
+    /// 格式错误或配置加载失败时使用默认提醒时间
+    pub fn load_warning_offsets() -> Vec<chrono::Duration> {
+        let default_offsets = || parse_warning_offsets(&DEFAULT_WARNING_OFFSETS).unwrap_or_default();
+        match Self::new() {
+            Ok(config_manager) => parse_warning_offsets(&config_manager.get_config().shutdown.warning_offsets)
+                .unwrap_or_else(|e| {
+                    warn!("提醒时间设置有误，使用默认提醒时间: {}", e);
+                    default_offsets()
+                }),
+            Err(e) => {
+                warn!("加载提醒时间失败: {}", e);
+                default_offsets()
+            }
+        }
+    }
+    
+    /// 读取设置中执行操作前检查的条件
+    /// 
+    /// 配置加载失败时不检查任何条件
+    pub fn load_conditions() -> ConditionSettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.conditions.clone(),
+            Err(e) => {
+                warn!("加载执行条件失败: {}", e);
+                ConditionSettings::default()
+            }
+        }
+    }
+    
+    /// 读取设置中的提醒声音
+    /// 
+    /// 配置加载失败时使用默认值
+    pub fn load_sounds() -> SoundSettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().sounds.clone(),
+            Err(e) => {
+                warn!("加载提醒声音设置失败: {}", e);
+                SoundSettings::default()
+            }
+        }
+    }
+    
+    /// 读取设置中判断下载完成的网络空闲条件
+    /// 
+    /// 配置加载失败时使用默认值
+    pub fn load_network_idle() -> NetworkIdleSettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.network_idle.clone(),
+            Err(e) => {
+                warn!("加载网络空闲设置失败: {}", e);
+                NetworkIdleSettings::default()
+            }
+        }
+    }
+    
+    /// 读取设置中使用电池供电时的处理方式
+    /// 
+    /// 配置加载失败时不做任何调整
+    pub fn load_battery() -> BatterySettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.battery.clone(),
+            Err(e) => {
+                warn!("加载电池设置失败: {}", e);
+                BatterySettings::default()
+            }
+        }
+    }
+    
+    /// 读取屏幕使用时间限制
+    /// 
+    /// 配置加载失败时不限制使用时间
+    pub fn load_screen_time() -> ScreenTimeSettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.screen_time.clone(),
+            Err(e) => {
+                warn!("加载屏幕使用时间设置失败: {}", e);
+                ScreenTimeSettings::default()
+            }
+        }
+    }
+    
+    /// 读取是否同步到Windows任务计划程序
+    /// 
+    /// 配置加载失败时不同步，已同步的任务保持不变
+    pub fn load_system_tasks() -> bool {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().shutdown.system_tasks,
+            Err(e) => {
+                warn!("加载任务计划程序同步设置失败: {}", e);
+                false
+            }
+        }
+    }
+    
+    /// 读取远程关机设置
+    /// 
+    /// 配置加载失败时不接受远程命令
+    pub fn load_remote() -> RemoteSettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().remote.clone(),
+            Err(e) => {
+                warn!("加载远程关机设置失败: {}", e);
+                RemoteSettings::default()
+            }
+        }
+    }
+    
+    /// 读取直播叠加层设置
+    /// 
+    /// 配置加载失败时不启用叠加层，本地接口使用默认端口
+    pub fn load_overlay() -> OverlaySettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().overlay.clone(),
+            Err(e) => {
+                warn!("加载直播叠加层设置失败: {}", e);
+                OverlaySettings::default()
+            }
+        }
+    }
+    
+    /// 读取外部通知设置
+    /// 
+    /// 配置加载失败时不发送外部通知
+    pub fn load_webhook() -> WebhookSettings {
+        match Self::new() {
+            Ok(config_manager) => config_manager.get_config().webhook.clone(),
+            Err(e) => {
+                warn!("加载外部通知设置失败: {}", e);
+                WebhookSettings::default()
+            }
+        }
+    }
+    
+    /// 读取设置中的时间解析语言包
+    /// 
+    /// 用户语言包放在配置目录的`locales`文件夹中，加载失败时使用内置语言包
+    pub fn load_parser_locale() -> LocalePack {
+        let names = match Self::new() {
+            Ok(config_manager) => config_manager.get_config().app.parser_locales.clone(),
+            Err(e) => {
+                warn!("加载语言包设置失败: {}", e);
+                default_parser_locales()
+            }
+        };
+        let locale_dir = Self::get_config_file_path().ok()
+            .and_then(|path| path.parent().map(|dir| dir.join(LOCALE_DIR)));
+        LocalePack::load(&names, locale_dir.as_deref()).unwrap_or_else(|e| {
+            warn!("加载语言包失败，使用内置语言包: {}", e);
+            LocalePack::builtin()
+        })
+    }
+    
+    /// 读取设置中的自定义命令
+    /// 
+    /// 未设置程序或配置加载失败时返回None
+    pub fn load_custom_command() -> Option<CustomCommand> {
+        match Self::new() {
+            Ok(config_manager) => Some(config_manager.get_config().shutdown.custom_command.clone())
+                .filter(|command| !command.is_empty()),
+            Err(e) => {
+                warn!("加载自定义命令失败: {}", e);
+                None
+            }
+        }
+    }
+    
     /// 获取当前配置
     pub fn get_config(&self) -> &AppConfig {
         &self.config
@@ -299,6 +899,48 @@ impl ConfigManager {
         Ok(())
     }
     
+    /// 配置文件备份目录，与配置文件在同一目录中
+    fn backup_dir(config_path: &Path) -> PathBuf {
+        config_path.parent().unwrap_or_else(|| Path::new(".")).join("backups")
+    }
+    
+    /// 列出配置文件备份
+    /// 
+    /// # 返回值
+    /// 
+    /// 按时间从新到旧排列的备份文件路径和备份时间
+    pub fn list_backups(&self) -> Vec<(PathBuf, chrono::DateTime<chrono::Local>)> {
+        list_backups(&Self::backup_dir(&self.config_path), CONFIG_BACKUP_PREFIX)
+            .into_iter()
+            .filter_map(|path| {
+                let time = backup_time(&path, CONFIG_BACKUP_PREFIX)?;
+                Some((path, time))
+            })
+            .collect()
+    }
+    
+    /// 从备份恢复配置
+    /// 
+    /// 当前配置先备份，备份无效或由更新版本的程序保存时返回错误，不修改当前配置
+    /// 
+    /// # 参数
+    /// 
+    /// * `backup` - 备份文件路径
+    pub fn restore_backup(&mut self, backup: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let value: serde_json::Value = serde_json::from_str(&open_data(fs::read(backup)?, platform::native(), false)?)?;
+        let mut config: AppConfig = serde_json::from_value(migration::migrate(SchemaKind::Config, value)?)?;
+        let (valid, errors) = ConfigValidator::validate_config(&config);
+        if !valid {
+            return Err(format!("备份中的配置无效: {}", errors.join("；")).into());
+        }
+        Policy::load().apply_to_config(&mut config);
+        
+        self.config = config;
+        self.save_config()?;
+        info!("配置已从备份恢复: {:?}", backup);
+        Ok(())
+    }
+    
     /// 导出配置到指定路径
     /// 
     /// # 参数
@@ -394,6 +1036,30 @@ pub enum ConfigUpdateEvent {
 pub struct ConfigValidator;
 
 impl ConfigValidator {
+    /// 验证整个配置
+    /// 
+    /// # 参数
+    /// 
+    /// * `config` - 应用程序配置
+    /// 
+    /// # 返回值
+    /// 
+    /// 验证结果和各部分的错误信息
+    pub fn validate_config(config: &AppConfig) -> (bool, Vec<String>) {
+        let errors: Vec<String> = [
+            Self::validate_app_settings(&config.app),
+            Self::validate_ui_settings(&config.ui),
+            Self::validate_shutdown_settings(&config.shutdown),
+            Self::validate_advanced_settings(&config.advanced),
+            Self::validate_overlay_settings(&config.overlay),
+        ]
+        .into_iter()
+        .flat_map(|(_, errors)| errors)
+        .collect();
+        
+        (errors.is_empty(), errors)
+    }
+    
     /// 验证应用设置
     /// 
     /// # 参数
@@ -434,6 +1100,18 @@ impl ConfigValidator {
             }
         }
         
+        if settings.auto_dark_hour.is_some_and(|hour| hour > 23) {
+            errors.push("自动深色时间必须在0到23时之间".to_string());
+        }
+        
+        if settings.fullscreen_warning_seconds > 300 {
+            errors.push("全屏警告时间应在0-300秒之间".to_string());
+        }
+        
+        if settings.tray_attention_minutes > 60 {
+            errors.push("托盘提醒时间应在0-60分钟之间".to_string());
+        }
+        
         (errors.is_empty(), errors)
     }
     
@@ -453,13 +1131,54 @@ impl ConfigValidator {
             errors.push("确认超时时间应在1-300秒之间".to_string());
         }
         
-        if settings.warning_time > 60 {
-            errors.push("警告时间不应超过60分钟".to_string());
+        if let Err(e) = parse_warning_offsets(&settings.warning_offsets) {
+            errors.push(e.to_string());
+        }
+        
+        if settings.screen_time.daily_minutes.is_some_and(|minutes| minutes == 0 || minutes > MAX_DAILY_MINUTES) {
+            errors.push(format!("每日使用上限应在1-{}分钟之间", MAX_DAILY_MINUTES));
+        }
+        
+        (errors.is_empty(), errors)
+    }
+    
+    /// 验证叠加层设置
+    /// 
+    /// # 参数
+    /// 
+    /// * `settings` - 叠加层设置
+    /// 
+    /// # 返回值
+    /// 
+    /// 验证结果和错误信息
+    pub fn validate_overlay_settings(settings: &OverlaySettings) -> (bool, Vec<String>) {
+        let mut errors = Vec::new();
+        
+        if settings.port < 1024 {
+            errors.push("叠加层端口应在1024-65535之间".to_string());
+        }
+        
+        let colors = std::iter::once(&settings.text_color).chain(settings.background_color.iter());
+        for color in colors {
+            if !Self::is_valid_color(color) {
+                errors.push(format!("无效的颜色值: {}", color));
+            }
+        }
+        
+        if settings.font_size < 8 || settings.font_size > 500 {
+            errors.push("叠加层字体大小应在8-500之间".to_string());
         }
         
         (errors.is_empty(), errors)
     }
     
+    /// 检查颜色是否为#RRGGBB格式
+    fn is_valid_color(color: &str) -> bool {
+        color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+    }
+    
     /// 验证高级设置
     /// 
     /// # 参数
@@ -481,6 +1200,10 @@ impl ConfigValidator {
             errors.push("备份文件数量应在1-100之间".to_string());
         }
         
+        if settings.max_data_size_mb < 5 || settings.max_data_size_mb > 10240 {
+            errors.push("数据目录大小上限应在5-10240MB之间".to_string());
+        }
+        
         (errors.is_empty(), errors)
     }
 }
@@ -513,6 +1236,66 @@ mod tests {
         // 测试反序列化
         let deserialized: AppConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(config.app.language, deserialized.app.language);
+        
+        // 旧配置文件没有锁定时的处理方式，按时执行
+        let mut value = serde_json::to_value(&config).unwrap();
+        value["shutdown"].as_object_mut().unwrap().remove("when_locked");
+        let deserialized: AppConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized.shutdown.when_locked, LockedBehavior::RunOnTime);
+    }
+    
+    #[test]
+    fn test_config_schema_version() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        
+        // 没有版本号的旧配置迁移后保留用户设置
+        let mut legacy = serde_json::to_value(AppConfig::default()).unwrap();
+        legacy["app"]["language"] = serde_json::json!("en-US");
+        fs::write(&path, legacy.to_string()).unwrap();
+        assert_eq!(ConfigManager::load_config(&path).unwrap().app.language, "en-US");
+        
+        let mut config = AppConfig::default();
+        config.app.language = "en-US".to_string();
+        ConfigManager::save_config_to_file(&config, &path).unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved[migration::SCHEMA_VERSION_KEY], SchemaKind::Config.current_version());
+        
+        // 更新版本保存的配置返回错误，不用默认配置覆盖
+        let newer = format!(r#"{{"schema_version":{},"app":{{}}}}"#, SchemaKind::Config.current_version() + 1);
+        fs::write(&path, &newer).unwrap();
+        assert!(ConfigManager::load_config(&path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+    }
+    
+    #[test]
+    fn test_config_backups() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let mut manager = ConfigManager {
+            config_path: path.clone(),
+            config: AppConfig::default(),
+        };
+        manager.config.advanced.max_backup_files = 2;
+        for language in ["zh-CN", "en-US", "ja-JP", "ko-KR"] {
+            manager.config.app.language = language.to_string();
+            manager.save_config().unwrap();
+        }
+        // 第一次保存时还没有配置文件，之后每次保存前备份，只保留最近两份
+        let backups = manager.list_backups();
+        assert_eq!(backups.len(), 2);
+        
+        manager.restore_backup(&backups[1].0).unwrap();
+        assert_eq!(manager.config.app.language, "en-US");
+        let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["app"]["language"], "en-US");
+        
+        // 关闭数据备份后不再备份
+        let before = manager.list_backups();
+        manager.config.advanced.backup_data = false;
+        manager.save_config().unwrap();
+        assert_eq!(manager.list_backups(), before);
+        assert!(manager.restore_backup(&temp_dir.path().join("missing.json")).is_err());
     }
     
     #[test]
@@ -537,6 +1320,47 @@ mod tests {
         let (valid, errors) = ConfigValidator::validate_ui_settings(&ui_settings);
         assert!(valid);
         assert!(errors.is_empty());
+        
+        // 整个配置的错误来自各部分
+        let mut config = AppConfig::default();
+        assert!(ConfigValidator::validate_config(&config).0);
+        config.ui.tray_attention_minutes = 90;
+        config.advanced.log_level = "verbose".to_string();
+        let (valid, errors) = ConfigValidator::validate_config(&config);
+        assert!(!valid);
+        assert_eq!(errors, vec!["托盘提醒时间应在0-60分钟之间".to_string(), "无效的日志级别".to_string()]);
+    }
+    
+    #[test]
+    fn test_data_size_budget_setting() {
+        let mut advanced = AdvancedSettings::default();
+        assert_eq!(advanced.max_data_size_mb, 50);
+        assert!(ConfigValidator::validate_advanced_settings(&advanced).0);
+        
+        advanced.max_data_size_mb = 0;
+        assert!(!ConfigValidator::validate_advanced_settings(&advanced).0);
+    }
+    
+    #[test]
+    fn test_overlay_settings_validation() {
+        let mut overlay = OverlaySettings::default();
+        assert!(!overlay.enabled);
+        assert!(ConfigValidator::validate_overlay_settings(&overlay).0);
+        
+        overlay.background_color = Some("#00FF00".to_string());
+        assert!(ConfigValidator::validate_overlay_settings(&overlay).0);
+        
+        overlay.text_color = "white".to_string();
+        overlay.port = 80;
+        let (valid, errors) = ConfigValidator::validate_overlay_settings(&overlay);
+        assert!(!valid);
+        assert_eq!(errors.len(), 2);
+        
+        // 旧配置文件没有overlay字段时使用默认值
+        let mut json: serde_json::Value = serde_json::to_value(AppConfig::default()).unwrap();
+        json.as_object_mut().unwrap().remove("overlay");
+        let config: AppConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.overlay.port, OverlaySettings::default().port);
     }
     
     #[test]
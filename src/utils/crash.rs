@@ -0,0 +1,276 @@
+//! 崩溃报告模块
+//! 
+//! 程序崩溃（panic）时把调用栈、最近的日志和配置快照追加到数据目录的`crash-YYYYMMDD.txt`，
+//! 下次启动时在主窗口中提示并可以打开报告。图形界面程序崩溃时没有任何输出，
+//! 用户很难描述发生了什么，有了报告才能反馈问题。配置中的口令不会写入报告
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use serde_json::Value;
+
+use crate::utils::config::ConfigManager;
+
+/// 报告中保留的最近日志行数
+const RECENT_LOG_LINES: usize = 200;
+
+/// 记录还没有提示过的崩溃报告路径的文件名
+const PENDING_FILE: &str = "crash-pending.txt";
+
+/// 报告中替换配置里口令的文字
+const REDACTED: &str = "<已隐藏>";
+
+/// 需要隐藏的配置字段名称中的单词，如`secret`、`webhook.url`、`cancel_pin_hash`
+const SECRET_KEY_WORDS: [&str; 5] = ["secret", "url", "token", "pin", "password"];
+
+/// 最近的日志，由日志格式化函数写入
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// 记录一行日志，只保留最近的日志
+/// 
+/// # 参数
+/// 
+/// * `line` - 格式化后的日志行
+pub fn remember_log(line: &str) {
+    let mut logs = match RECENT_LOGS.lock() {
+        Ok(logs) => logs,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if logs.len() >= RECENT_LOG_LINES {
+        logs.pop_front();
+    }
+    logs.push_back(line.to_string());
+}
+
+/// 安装崩溃处理函数
+/// 
+/// 写入报告后仍调用原来的处理函数，在控制台输出崩溃信息
+/// 
+/// # 参数
+/// 
+/// * `data_dir` - 保存报告的数据目录
+pub fn install_panic_hook(data_dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let report = format_report(
+            &panic_message(panic_info.payload(), panic_info.location()),
+            &Backtrace::force_capture().to_string(),
+            &recent_logs(),
+            &config_snapshot(),
+            Local::now(),
+        );
+        if let Err(e) = write_report(&data_dir, &report, Local::now()) {
+            eprintln!("保存崩溃报告失败: {}", e);
+        }
+        previous(panic_info);
+    }));
+}
+
+/// 取出上次崩溃后还没有提示过的报告
+/// 
+/// 取出后不再提示，报告文件已被删除时返回None
+/// 
+/// # 参数
+/// 
+/// * `data_dir` - 数据目录
+pub fn take_pending_report(data_dir: &Path) -> Option<PathBuf> {
+    let pending = data_dir.join(PENDING_FILE);
+    let content = fs::read_to_string(&pending).ok()?;
+    if let Err(e) = fs::remove_file(&pending) {
+        warn!("删除崩溃报告标记失败: {}", e);
+    }
+    let path = PathBuf::from(content.trim());
+    if !path.exists() {
+        return None;
+    }
+    info!("上次运行时崩溃，报告: {:?}", path);
+    Some(path)
+}
+
+/// 崩溃信息：线程、位置和消息
+fn panic_message(payload: &(dyn Any + Send), location: Option<&Location<'_>>) -> String {
+    let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "未知错误".to_string());
+    let location = location
+        .map(|location| format!("{}:{}", location.file(), location.line()))
+        .unwrap_or_default();
+    let thread = std::thread::current();
+    format!("线程 '{}' 在 {} 崩溃: {}", thread.name().unwrap_or("未命名"), location, message)
+}
+
+/// 最近的日志
+/// 
+/// 崩溃可能发生在记录日志时，取不到锁时不等待
+fn recent_logs() -> Vec<String> {
+    match RECENT_LOGS.try_lock() {
+        Ok(logs) => logs.iter().cloned().collect(),
+        Err(_) => vec!["（日志正在写入，无法读取）".to_string()],
+    }
+}
+
+/// 隐藏口令后的配置文件内容
+fn config_snapshot() -> String {
+    let content = ConfigManager::get_config_file_path()
+        .map_err(|e| e.to_string())
+        .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()));
+    match content {
+        Ok(content) => redact_config(&content),
+        Err(e) => format!("（读取配置文件失败: {}）", e),
+    }
+}
+
+/// 隐藏配置中的口令
+/// 
+/// # 参数
+/// 
+/// * `content` - 配置文件内容
+fn redact_config(content: &str) -> String {
+    let mut value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return format!("（配置文件格式错误: {}）", e),
+    };
+    redact_secrets(&mut value);
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+/// 判断配置字段是否保存口令、网址等敏感信息
+/// 
+/// 字段名按下划线拆分为单词，任一单词属于`SECRET_KEY_WORDS`即视为敏感字段
+/// 
+/// # 参数
+/// 
+/// * `key` - 字段名称
+fn is_secret_key(key: &str) -> bool {
+    key.to_lowercase()
+        .split(['_', '-'])
+        .any(|word| SECRET_KEY_WORDS.contains(&word))
+}
+
+/// 把口令、令牌、PIN和网址字段替换为占位文字，包括远程电脑列表中的口令
+/// 
+/// 未设置（空值或空字符串）的字段保持原样，便于看出是否设置过
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) {
+                    if !value.is_null() && value.as_str() != Some("") {
+                        *value = Value::String(REDACTED.to_string());
+                    }
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        },
+        Value::Array(values) => values.iter_mut().for_each(redact_secrets),
+        _ => {},
+    }
+}
+
+/// 生成崩溃报告
+/// 
+/// # 参数
+/// 
+/// * `message` - 崩溃信息
+/// * `backtrace` - 调用栈
+/// * `logs` - 最近的日志
+/// * `config` - 配置快照
+/// * `now` - 崩溃时间
+pub fn format_report(message: &str, backtrace: &str, logs: &[String], config: &str, now: DateTime<Local>) -> String {
+    format!(
+        "===== QtShut 崩溃报告 {} =====\n版本: {}\n系统: {} {}\n\n{}\n\n----- 调用栈 -----\n{}\n\n----- 最近{}行日志 -----\n{}\n\n----- 配置 -----\n{}\n\n",
+        now.format("%Y-%m-%d %H:%M:%S"),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        message,
+        backtrace.trim_end(),
+        logs.len(),
+        logs.join("\n"),
+        config,
+    )
+}
+
+/// 把报告追加到当天的报告文件，并记录下次启动时要提示的报告
+/// 
+/// # 返回值
+/// 
+/// 报告文件路径
+fn write_report(data_dir: &Path, report: &str, now: DateTime<Local>) -> std::io::Result<PathBuf> {
+    let path = data_dir.join(format!("crash-{}.txt", now.format("%Y%m%d")));
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(report.as_bytes())?;
+    fs::write(data_dir.join(PENDING_FILE), path.to_string_lossy().as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_report_written_and_pending_once() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(take_pending_report(temp_dir.path()), None);
+        
+        let now = Local::now();
+        let logs = vec!["[INFO] 启动".to_string()];
+        let report = format_report("线程 'main' 在 src/app.rs:1 崩溃: 测试", "0: main", &logs, "{}", now);
+        let path = write_report(temp_dir.path(), &report, now).unwrap();
+        write_report(temp_dir.path(), &report, now).unwrap();
+        assert!(path.file_name().unwrap().to_string_lossy().starts_with("crash-"));
+        
+        // 同一天的报告追加到同一个文件
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("QtShut 崩溃报告").count(), 2);
+        assert!(content.contains("[INFO] 启动"));
+        
+        // 下次启动只提示一次
+        assert_eq!(take_pending_report(temp_dir.path()), Some(path));
+        assert_eq!(take_pending_report(temp_dir.path()), None);
+    }
+    
+    #[test]
+    fn test_redact_config() {
+        let config = r#"{"remote":{"enabled":true,"secret":"abc123","peers":[{"name":"书房","secret":"xyz"}]}}"#;
+        let redacted = redact_config(config);
+        assert!(!redacted.contains("abc123"));
+        assert!(!redacted.contains("xyz"));
+        assert!(redacted.contains("书房"));
+        assert!(redacted.contains(REDACTED));
+        
+        let config = r#"{"webhook":{"url":"https://example.com/hook?key=k1"},"policy":{"cancel_pin_hash":"h2","cancel_pin":"1234"},"api_token":"t3","proxy":{"password":"p4","user_name":"张三"},"overlay":{"port":17321}}"#;
+        let redacted = redact_config(config);
+        for secret in ["example.com", "h2", "1234", "t3", "p4"] {
+            assert!(!redacted.contains(secret), "{} 未隐藏", secret);
+        }
+        assert!(redacted.contains("张三"));
+        assert!(redacted.contains("17321"));
+        
+        // 未设置的字段保持原样
+        let redacted = redact_config(r#"{"webhook":{"url":""},"policy":{"cancel_pin":null}}"#);
+        assert!(!redacted.contains(REDACTED));
+        assert!(!is_secret_key("spinner"));
+        assert!(is_secret_key("Webhook-URL"));
+    }
+    
+    #[test]
+    fn test_recent_logs_capped() {
+        for index in 0..RECENT_LOG_LINES + 5 {
+            remember_log(&format!("第{}行", index));
+        }
+        let logs = recent_logs();
+        assert_eq!(logs.len(), RECENT_LOG_LINES);
+        assert_eq!(logs.last().unwrap(), &format!("第{}行", RECENT_LOG_LINES + 4));
+    }
+}
@@ -4,11 +4,12 @@
 
 use std::path::{Path, PathBuf};
 use std::fs;
-use log::{info, warn, LevelFilter};
+use std::str::FromStr;
+use log::{info, warn, Level, LevelFilter};
 use env_logger::{Builder, Target};
 use std::io::Write;
-use std::sync::Once;
-use chrono::{DateTime, Local};
+use std::sync::{Mutex, Once, OnceLock};
+use chrono::{DateTime, Local, NaiveDateTime};
 use dirs::data_local_dir;
 
 static INIT: Once = Once::new();
@@ -65,11 +66,9 @@ impl LoggerManager {
     /// 
     /// 成功返回日志文件路径，失败返回错误信息
     fn create_log_file_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let data_dir = data_local_dir()
+        let app_data_dir = log_dir()
             .ok_or("无法获取本地数据目录")?;
         
-        let app_data_dir = data_dir.join("QtShut").join("logs");
-        
         // 确保日志目录存在
         if !app_data_dir.exists() {
             fs::create_dir_all(&app_data_dir)?;
@@ -325,6 +324,275 @@ impl LoggerManager {
         
         stats
     }
+    
+    /// 收集当前日志及其轮转备份文件
+    /// 
+    /// 轮转后的文件名形如 `qtshut_20240101.log.20240101_120000`，
+    /// 因此按修改时间从旧到新排序，保证读取顺序与写入顺序一致
+    /// 
+    /// # 参数
+    /// 
+    /// * `log_dir` - 日志目录
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回按时间排序的日志文件列表，失败返回错误信息
+    fn collect_log_files(log_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        if !log_dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let mut files = Vec::new();
+        for entry in fs::read_dir(log_dir)?.flatten() {
+            let path = entry.path();
+            let is_log_file = path.file_name().map_or(false, |name| {
+                let name = name.to_string_lossy();
+                name.starts_with("qtshut_") && name.contains(".log")
+            });
+            
+            if path.is_file() && is_log_file {
+                let modified = fs::metadata(&path)?.modified()?;
+                files.push((modified, path));
+            }
+        }
+        
+        files.sort();
+        Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+    
+    /// 读取符合过滤条件的日志行
+    /// 
+    /// 不以时间戳开头的行（如多行消息的后续行）跟随其所属记录的过滤结果
+    /// 
+    /// # 参数
+    /// 
+    /// * `filter` - 日志过滤条件
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回匹配的日志行，失败返回错误信息
+    pub fn read_filtered_logs(&self, filter: &LogFilter) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        match self.log_file_path.as_deref().and_then(Path::parent) {
+            Some(log_dir) => Self::read_logs_in(log_dir, filter),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    /// 读取日志目录中符合过滤条件的日志行
+    /// 
+    /// 日志查看器直接读取程序的日志目录，不需要另外创建日志管理器
+    /// 
+    /// # 参数
+    /// 
+    /// * `log_dir` - 日志目录
+    /// * `filter` - 日志过滤条件
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回匹配的日志行，失败返回错误信息
+    pub fn read_logs_in(log_dir: &Path, filter: &LogFilter) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut lines = Vec::new();
+        
+        for path in Self::collect_log_files(log_dir)? {
+            let content = fs::read_to_string(&path)?;
+            let mut current_record_matches = false;
+            
+            for line in content.lines() {
+                if LogFilter::parse_record_header(line).is_some() {
+                    current_record_matches = filter.matches(line);
+                }
+                
+                if current_record_matches {
+                    lines.push(line.to_string());
+                }
+            }
+        }
+        
+        Ok(lines)
+    }
+    
+    /// 导出符合过滤条件的日志到文本文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `filter` - 日志过滤条件
+    /// * `export_path` - 导出文件路径
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回导出的行数，失败返回错误信息
+    pub fn export_filtered_logs(
+        &self,
+        filter: &LogFilter,
+        export_path: &Path,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        Self::write_export(&self.read_filtered_logs(filter)?, export_path)
+    }
+    
+    /// 导出日志目录中符合过滤条件的日志到文本文件
+    /// 
+    /// # 参数
+    /// 
+    /// * `log_dir` - 日志目录
+    /// * `filter` - 日志过滤条件
+    /// * `export_path` - 导出文件路径
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回导出的行数，失败返回错误信息
+    pub fn export_logs_in(
+        log_dir: &Path,
+        filter: &LogFilter,
+        export_path: &Path,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        Self::write_export(&Self::read_logs_in(log_dir, filter)?, export_path)
+    }
+    
+    /// 把日志行写入导出文件
+    fn write_export(lines: &[String], export_path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut content = lines.join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        if let Some(parent) = export_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(export_path, content)?;
+        
+        info!("已导出 {} 行日志到: {:?}", lines.len(), export_path);
+        Ok(lines.len())
+    }
+}
+
+/// 日志过滤条件
+/// 
+/// 用于日志查看器按级别、时间范围和关键词筛选日志行
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// 最低日志级别（如Warn表示只保留Warn和Error），None表示不限
+    pub min_level: Option<Level>,
+    /// 起始时间（包含）
+    pub start_time: Option<NaiveDateTime>,
+    /// 结束时间（包含）
+    pub end_time: Option<NaiveDateTime>,
+    /// 搜索关键词（不区分大小写）
+    pub search_term: Option<String>,
+}
+
+impl LogFilter {
+    /// 创建不做任何过滤的条件
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 设置最低日志级别
+    /// 
+    /// # 参数
+    /// 
+    /// * `level` - 最低日志级别
+    /// 
+    /// # 返回值
+    /// 
+    /// 自身，支持链式调用
+    pub fn with_min_level(mut self, level: Level) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+    
+    /// 设置时间范围
+    /// 
+    /// # 参数
+    /// 
+    /// * `start_time` - 起始时间，None表示不限
+    /// * `end_time` - 结束时间，None表示不限
+    /// 
+    /// # 返回值
+    /// 
+    /// 自身，支持链式调用
+    pub fn with_time_range(
+        mut self,
+        start_time: Option<NaiveDateTime>,
+        end_time: Option<NaiveDateTime>,
+    ) -> Self {
+        self.start_time = start_time;
+        self.end_time = end_time;
+        self
+    }
+    
+    /// 设置搜索关键词
+    /// 
+    /// # 参数
+    /// 
+    /// * `term` - 搜索关键词，空字符串表示不限
+    /// 
+    /// # 返回值
+    /// 
+    /// 自身，支持链式调用
+    pub fn with_search_term(mut self, term: impl Into<String>) -> Self {
+        let term = term.into();
+        self.search_term = if term.trim().is_empty() { None } else { Some(term) };
+        self
+    }
+    
+    /// 解析日志行头部的时间戳和级别
+    /// 
+    /// 日志格式为 `[2024-01-01 12:00:00.000] [INFO] [file:line] message`
+    /// 
+    /// # 参数
+    /// 
+    /// * `line` - 日志行
+    /// 
+    /// # 返回值
+    /// 
+    /// 成功返回时间戳和级别，非记录起始行返回None
+    fn parse_record_header(line: &str) -> Option<(NaiveDateTime, Level)> {
+        let rest = line.strip_prefix('[')?;
+        let (timestamp_str, rest) = rest.split_once("] [")?;
+        let (level_str, _) = rest.split_once(']')?;
+        
+        let timestamp = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M:%S%.3f").ok()?;
+        let level = Level::from_str(level_str).ok()?;
+        
+        Some((timestamp, level))
+    }
+    
+    /// 检查日志记录行是否符合过滤条件
+    /// 
+    /// # 参数
+    /// 
+    /// * `line` - 日志记录的起始行
+    /// 
+    /// # 返回值
+    /// 
+    /// 是否符合过滤条件
+    pub fn matches(&self, line: &str) -> bool {
+        let (timestamp, level) = match Self::parse_record_header(line) {
+            Some(header) => header,
+            None => return false,
+        };
+        
+        if let Some(min_level) = self.min_level {
+            if level > min_level {
+                return false;
+            }
+        }
+        
+        if self.start_time.map_or(false, |start| timestamp < start) {
+            return false;
+        }
+        
+        if self.end_time.map_or(false, |end| timestamp > end) {
+            return false;
+        }
+        
+        if let Some(term) = &self.search_term {
+            if !line.to_lowercase().contains(&term.to_lowercase()) {
+                return false;
+            }
+        }
+        
+        true
+    }
 }
 
 /// 日志统计信息
@@ -432,6 +700,38 @@ pub fn init_logger(
     Ok(logger_manager)
 }
 
+/// 程序的日志目录，本地数据目录不可用时为None
+/// 
+/// 只在第一次调用时确定，程序写入日志和日志查看器读取使用同一个目录
+pub fn log_dir() -> Option<&'static Path> {
+    static LOG_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    LOG_DIR.get_or_init(|| data_local_dir().map(|dir| dir.join("QtShut").join("logs"))).as_deref()
+}
+
+/// 把一行日志追加到日志目录中当天的日志文件，供日志查看器读取
+/// 
+/// 第一次写入时打开文件，目录不可用或打开失败时不写入
+/// 
+/// # 参数
+/// 
+/// * `line` - 格式化后的日志行
+pub fn write_log_line(line: &str) {
+    static LOG_FILE: OnceLock<Option<Mutex<fs::File>>> = OnceLock::new();
+    let file = LOG_FILE.get_or_init(|| {
+        let dir = log_dir()?;
+        fs::create_dir_all(dir).ok()?;
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("qtshut_{}.log", Local::now().format("%Y%m%d"))))
+            .ok()
+            .map(Mutex::new)
+    });
+    if let Some(Ok(mut file)) = file.as_ref().map(Mutex::lock) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
 /// 快速初始化默认日志系统
 /// 
 /// # 返回值
@@ -503,4 +803,60 @@ mod tests {
         
         println!("Test completed successfully");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_log_filter_matches() {
+        let filter = LogFilter::new()
+            .with_min_level(Level::Warn)
+            .with_search_term("关机");
+        
+        assert!(filter.matches("[2024-01-01 12:00:00.000] [WARN] [qtshut::core] 关机失败"));
+        assert!(filter.matches("[2024-01-01 12:00:00.000] [ERROR] [qtshut::core] 关机命令执行失败"));
+        assert!(!filter.matches("[2024-01-01 12:00:00.000] [INFO] [qtshut::core] 关机成功"));
+        assert!(!filter.matches("[2024-01-01 12:00:00.000] [WARN] [qtshut::core] 配置已保存"));
+        
+        let start = NaiveDateTime::parse_from_str("2024-01-01 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let end = NaiveDateTime::parse_from_str("2024-01-01 11:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let filter = LogFilter::new().with_time_range(Some(start), Some(end));
+        
+        assert!(filter.matches("[2024-01-01 10:30:00.000] [INFO] [qtshut] 在范围内"));
+        assert!(!filter.matches("[2024-01-01 11:30:00.000] [INFO] [qtshut] 超出范围"));
+        assert!(!filter.matches("没有时间戳的行"));
+    }
+    
+    #[test]
+    fn test_export_filtered_logs() {
+        let dir = tempdir().unwrap();
+        let log_file_path = dir.path().join("qtshut_20240101.log");
+        let rotated_path = dir.path().join("qtshut_20240101.log.20240101_090000");
+        
+        fs::write(&rotated_path, "[2024-01-01 09:00:00.000] [ERROR] [qtshut] 旧的错误\n").unwrap();
+        fs::write(
+            &log_file_path,
+            "[2024-01-01 12:00:00.000] [INFO] [qtshut] 普通信息\n\
+             [2024-01-01 12:00:01.000] [ERROR] [qtshut] 新的错误\n\
+             错误详情第二行\n",
+        ).unwrap();
+        
+        let logger = LoggerManager {
+            log_file_path: Some(log_file_path),
+            log_level: LevelFilter::Info,
+            file_logging_enabled: true,
+            console_logging_enabled: false,
+        };
+        
+        let filter = LogFilter::new().with_min_level(Level::Error);
+        let lines = logger.read_filtered_logs(&filter).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().any(|line| line.contains("错误详情第二行")));
+        assert!(!lines.iter().any(|line| line.contains("普通信息")));
+        
+        let export_path = dir.path().join("export").join("filtered.log");
+        let count = logger.export_filtered_logs(&filter, &export_path).unwrap();
+        assert_eq!(count, 3);
+        
+        let exported = fs::read_to_string(&export_path).unwrap();
+        assert!(exported.contains("旧的错误"));
+        assert!(exported.contains("新的错误"));
+    }
+}
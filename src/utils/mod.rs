@@ -4,8 +4,12 @@
 
 pub mod system;
 pub mod config;
+pub mod crash;
 pub mod logger;
+pub mod monitor;
 pub mod notification;
+pub mod policy;
+pub mod sound;
 
 // 为了兼容性，将system模块也作为system_compat导出
 pub mod system_compat {
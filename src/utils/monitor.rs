@@ -0,0 +1,145 @@
+//! 系统监控模块
+//! 
+//! 轻量的CPU占用和网络流量采样，两次读取系统累计计数并按差值计算占用率或速度，
+//! 供执行条件判断电脑是否仍在忙碌（如渲染、编译、下载）
+
+use std::time::{Duration, Instant};
+
+use crate::platform::{self, CpuTimes, SystemInfo};
+
+/// 测量CPU占用时两次采样的默认间隔
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// CPU占用采样器
+/// 
+/// 每次采样返回与上次采样之间的平均占用率
+#[derive(Debug, Clone, Default)]
+pub struct CpuSampler {
+    /// 上次采样的累计CPU时间
+    last: Option<CpuTimes>,
+}
+
+impl CpuSampler {
+    /// 创建采样器
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 采样一次
+    /// 
+    /// # 返回值
+    /// 
+    /// 与上次采样之间的CPU占用百分比，第一次采样或平台不支持时为空
+    pub fn sample(&mut self) -> Option<f64> {
+        let current = platform::native().cpu_times()?;
+        let usage = self.last.and_then(|last| usage_between(last, current));
+        self.last = Some(current);
+        usage
+    }
+}
+
+/// 计算两次采样之间的CPU占用百分比
+/// 
+/// # 参数
+/// 
+/// * `previous` - 较早的采样
+/// * `current` - 较晚的采样
+/// 
+/// # 返回值
+/// 
+/// 0到100之间的占用率，两次采样之间没有经过时间时为空
+pub fn usage_between(previous: CpuTimes, current: CpuTimes) -> Option<f64> {
+    let total = current.total.checked_sub(previous.total)?;
+    let idle = current.idle.saturating_sub(previous.idle).min(total);
+    if total == 0 {
+        return None;
+    }
+    Some((total - idle) as f64 * 100.0 / total as f64)
+}
+
+/// 测量一段时间内的平均CPU占用
+/// 
+/// # 参数
+/// 
+/// * `interval` - 两次采样的间隔
+/// 
+/// # 返回值
+/// 
+/// CPU占用百分比，平台不支持时为空
+pub async fn measure_cpu_usage(interval: Duration) -> Option<f64> {
+    let mut sampler = CpuSampler::new();
+    sampler.sample();
+    tokio::time::sleep(interval).await;
+    sampler.sample()
+}
+
+/// 网络流量采样器
+/// 
+/// 每次采样返回与上次采样之间的平均收发速度
+#[derive(Debug, Clone, Default)]
+pub struct NetworkSampler {
+    /// 上次采样的时间和累计收发字节数
+    last: Option<(Instant, u64)>,
+}
+
+impl NetworkSampler {
+    /// 创建采样器
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 采样一次
+    /// 
+    /// # 返回值
+    /// 
+    /// 与上次采样之间的收发速度（KB/s），第一次采样或平台不支持时为空
+    pub fn sample(&mut self) -> Option<f64> {
+        let bytes = platform::native().network_bytes()?;
+        let now = Instant::now();
+        let rate = self.last.and_then(|(at, previous)| throughput_between(previous, bytes, now - at));
+        self.last = Some((now, bytes));
+        rate
+    }
+}
+
+/// 计算两次采样之间的收发速度
+/// 
+/// # 参数
+/// 
+/// * `previous` - 较早采样的累计字节数
+/// * `current` - 较晚采样的累计字节数
+/// * `elapsed` - 两次采样间隔的时间
+/// 
+/// # 返回值
+/// 
+/// 速度（KB/s），没有经过时间或计数器重置时为空
+pub fn throughput_between(previous: u64, current: u64, elapsed: Duration) -> Option<f64> {
+    let bytes = current.checked_sub(previous)?;
+    if elapsed.is_zero() {
+        return None;
+    }
+    Some(bytes as f64 / 1024.0 / elapsed.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_usage_between() {
+        let previous = CpuTimes { idle: 1000, total: 2000 };
+        assert_eq!(usage_between(previous, CpuTimes { idle: 1250, total: 3000 }), Some(75.0));
+        assert_eq!(usage_between(previous, CpuTimes { idle: 2000, total: 3000 }), Some(0.0));
+        assert_eq!(usage_between(previous, previous), None);
+        // 计数器回绕或重置时不给出结果
+        assert_eq!(usage_between(previous, CpuTimes { idle: 10, total: 20 }), None);
+    }
+    
+    #[test]
+    fn test_throughput_between() {
+        assert_eq!(throughput_between(0, 10 * 1024, Duration::from_secs(2)), Some(5.0));
+        assert_eq!(throughput_between(4096, 4096, Duration::from_secs(5)), Some(0.0));
+        assert_eq!(throughput_between(0, 1024, Duration::ZERO), None);
+        assert_eq!(throughput_between(1024, 0, Duration::from_secs(1)), None);
+    }
+}
@@ -2,10 +2,104 @@
 //! 
 //! 负责系统通知的显示和管理
 
-use log::info;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::TaskAction;
+use crate::platform::{self, Beep, DisplayControl};
+use crate::utils::sound::{self, DEFAULT_VOLUME};
+
+/// 系统提示音
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SystemSound {
+    /// 不播放声音
+    Silent,
+    /// 默认提示音
+    #[default]
+    Beep,
+    /// 信息提示音
+    Asterisk,
+    /// 警告提示音
+    Exclamation,
+    /// 严重错误提示音
+    Hand,
+}
+
+/// 提醒声音方案
+/// 
+/// 语音文本中的`{action}`替换为操作名称，`{remaining}`替换为剩余时间
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundProfile {
+    /// 提示音
+    pub sound: SystemSound,
+    /// 朗读的语音文本，为空时不朗读
+    pub phrase: Option<String>,
+}
+
+impl SoundProfile {
+    /// 生成要朗读的文本
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    /// * `remaining_seconds` - 剩余秒数
+    pub fn render_phrase(&self, action: TaskAction, remaining_seconds: i64) -> Option<String> {
+        let phrase = self.phrase.as_deref()?.trim();
+        if phrase.is_empty() {
+            return None;
+        }
+        Some(phrase
+            .replace("{action}", &action.to_string())
+            .replace("{remaining}", &format!("{}秒", remaining_seconds)))
+    }
+}
+
+/// 默认的提醒声音方案
+/// 
+/// 关机和注销使用警告音，重启使用信息提示音，睡眠和锁定只使用默认提示音，
+/// 关闭显示器通常用于入睡，不播放声音
+pub fn default_sound_profiles() -> HashMap<TaskAction, SoundProfile> {
+    let mut profiles = HashMap::new();
+    profiles.insert(TaskAction::Shutdown, SoundProfile {
+        sound: SystemSound::Exclamation,
+        phrase: Some("电脑将在{remaining}后关机".to_string()),
+    });
+    profiles.insert(TaskAction::Restart, SoundProfile {
+        sound: SystemSound::Asterisk,
+        phrase: Some("电脑将在{remaining}后重启".to_string()),
+    });
+    profiles.insert(TaskAction::Sleep, SoundProfile {
+        sound: SystemSound::Beep,
+        phrase: None,
+    });
+    profiles.insert(TaskAction::Lock, SoundProfile {
+        sound: SystemSound::Beep,
+        phrase: None,
+    });
+    profiles.insert(TaskAction::MonitorOff, SoundProfile {
+        sound: SystemSound::Silent,
+        phrase: None,
+    });
+    profiles.insert(TaskAction::Logoff, SoundProfile {
+        sound: SystemSound::Exclamation,
+        phrase: Some("将在{remaining}后注销".to_string()),
+    });
+    profiles
+}
+
+/// 默认合并相同通知的时间窗口（秒）
+pub const DEFAULT_DEDUP_WINDOW_SECONDS: u64 = 60;
+
+/// 剩余时间不超过该秒数的到点前提醒升级为紧急通知
+pub const CRITICAL_WARNING_SECONDS: i64 = 60;
 
 /// 通知类型
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NotificationType {
     /// 信息通知
     Info,
@@ -19,6 +113,29 @@ pub enum NotificationType {
     Countdown,
 }
 
+impl NotificationType {
+    /// 所有通知类型，用于在设置中选择声音文件
+    pub const ALL: [NotificationType; 5] = [
+        NotificationType::Countdown,
+        NotificationType::Warning,
+        NotificationType::Error,
+        NotificationType::Success,
+        NotificationType::Info,
+    ];
+}
+
+impl fmt::Display for NotificationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationType::Info => write!(f, "信息"),
+            NotificationType::Warning => write!(f, "警告"),
+            NotificationType::Error => write!(f, "错误"),
+            NotificationType::Success => write!(f, "成功"),
+            NotificationType::Countdown => write!(f, "到点提醒"),
+        }
+    }
+}
+
 /// 通知优先级
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum NotificationPriority {
@@ -51,6 +168,12 @@ pub struct NotificationMessage {
     pub dismissible: bool,
     /// 是否播放声音
     pub play_sound: bool,
+    /// 关联的任务操作，用于选择提醒声音方案
+    pub action: Option<TaskAction>,
+    /// 提醒时的剩余秒数
+    pub remaining_seconds: Option<i64>,
+    /// 时间窗口内合并的相同通知次数
+    pub repeat_count: u32,
     /// 创建时间
     pub created_at: std::time::Instant,
 }
@@ -83,6 +206,9 @@ impl NotificationMessage {
             duration_ms: Some(5000), // 默认5秒
             dismissible: true,
             play_sound: false,
+            action: None,
+            remaining_seconds: None,
+            repeat_count: 1,
             created_at: std::time::Instant::now(),
         }
     }
@@ -143,6 +269,49 @@ impl NotificationMessage {
         self
     }
     
+    /// 关联任务操作
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    /// * `remaining_seconds` - 剩余秒数
+    /// 
+    /// # 返回值
+    /// 
+    /// 自身的可变引用，支持链式调用
+    pub fn with_action(mut self, action: TaskAction, remaining_seconds: i64) -> Self {
+        self.action = Some(action);
+        self.remaining_seconds = Some(remaining_seconds);
+        self
+    }
+    
+    /// 是否与另一条通知内容相同
+    /// 
+    /// 标题、内容、类型和关联操作都相同时视为重复通知
+    /// 
+    /// # 参数
+    /// 
+    /// * `other` - 另一条通知
+    pub fn is_duplicate_of(&self, other: &NotificationMessage) -> bool {
+        self.title == other.title
+            && self.content == other.content
+            && self.notification_type == other.notification_type
+            && self.action == other.action
+    }
+    
+    /// 获取带合并次数标记的标题
+    /// 
+    /// # 返回值
+    /// 
+    /// 合并过重复通知时返回如"标题 ×3"，否则返回原标题
+    pub fn badge_title(&self) -> String {
+        if self.repeat_count > 1 {
+            format!("{} ×{}", self.title, self.repeat_count)
+        } else {
+            self.title.clone()
+        }
+    }
+    
     /// 检查通知是否已过期
     /// 
     /// # 返回值
@@ -193,6 +362,16 @@ pub struct NotificationManager {
     notification_history: Vec<NotificationMessage>,
     /// 最大历史记录数量
     max_history: usize,
+    /// 各操作的提醒声音方案
+    sound_profiles: HashMap<TaskAction, SoundProfile>,
+    /// 合并相同通知的时间窗口
+    dedup_window: std::time::Duration,
+    /// 1分钟以前的到点前提醒是否播放声音
+    early_warning_sound: bool,
+    /// 各类型通知代替系统提示音播放的声音文件
+    sound_files: HashMap<NotificationType, PathBuf>,
+    /// 播放声音文件的音量（百分比）
+    volume: u8,
 }
 
 impl NotificationManager {
@@ -214,7 +393,86 @@ impl NotificationManager {
             sound_enabled: true,
             notification_history: Vec::new(),
             max_history,
+            sound_profiles: default_sound_profiles(),
+            dedup_window: std::time::Duration::from_secs(DEFAULT_DEDUP_WINDOW_SECONDS),
+            early_warning_sound: true,
+            sound_files: HashMap::new(),
+            volume: DEFAULT_VOLUME,
+        }
+    }
+    
+    /// 设置各类型通知播放的声音文件和音量
+    /// 
+    /// # 参数
+    /// 
+    /// * `files` - 配置中的声音文件路径，路径为空的类型使用系统提示音
+    /// * `volume` - 音量百分比
+    pub fn set_sound_files(&mut self, files: &HashMap<NotificationType, String>, volume: u8) {
+        self.sound_files = files.iter()
+            .filter(|(_, path)| !path.trim().is_empty())
+            .map(|(notification_type, path)| (*notification_type, PathBuf::from(path.trim())))
+            .collect();
+        self.volume = volume;
+    }
+    
+    /// 设置1分钟以前的到点前提醒是否播放声音
+    /// 
+    /// 1分钟以内的提醒和最后确认提醒总是播放
+    /// 
+    /// # 参数
+    /// 
+    /// * `enabled` - 是否播放
+    pub fn set_early_warning_sound(&mut self, enabled: bool) {
+        self.early_warning_sound = enabled;
+    }
+    
+    /// 剩余指定秒数时的到点前提醒是否播放声音
+    /// 
+    /// # 参数
+    /// 
+    /// * `remaining_seconds` - 剩余秒数
+    pub fn warning_sound(&self, remaining_seconds: i64) -> bool {
+        self.early_warning_sound || remaining_seconds <= CRITICAL_WARNING_SECONDS
+    }
+    
+    /// 设置合并相同通知的时间窗口
+    /// 
+    /// # 参数
+    /// 
+    /// * `seconds` - 时间窗口（秒），0表示不合并
+    pub fn set_dedup_window(&mut self, seconds: u64) {
+        self.dedup_window = std::time::Duration::from_secs(seconds);
+    }
+    
+    /// 设置各操作的提醒声音方案
+    /// 
+    /// # 参数
+    /// 
+    /// * `profiles` - 配置中的声音方案，未配置的操作使用默认方案
+    pub fn set_sound_profiles(&mut self, profiles: &HashMap<TaskAction, SoundProfile>) {
+        let mut merged = default_sound_profiles();
+        merged.extend(profiles.iter().map(|(action, profile)| (*action, profile.clone())));
+        self.sound_profiles = merged;
+    }
+    
+    /// 查找通知对应的声音方案
+    /// 
+    /// 关联了任务操作的通知使用该操作的方案，其他通知按通知类型使用提示音
+    /// 
+    /// # 参数
+    /// 
+    /// * `notification` - 通知消息
+    pub fn resolve_sound_profile(&self, notification: &NotificationMessage) -> SoundProfile {
+        if let Some(profile) = notification.action.and_then(|action| self.sound_profiles.get(&action)) {
+            return profile.clone();
         }
+        
+        let sound = match notification.notification_type {
+            NotificationType::Info | NotificationType::Success => SystemSound::Asterisk,
+            NotificationType::Warning | NotificationType::Countdown => SystemSound::Exclamation,
+            NotificationType::Error => SystemSound::Hand,
+        };
+        SoundProfile { sound, phrase: None }
     }
     
     /// 显示通知
@@ -237,9 +495,48 @@ impl NotificationManager {
         
         info!("显示通知: {} - {}", notification.title, notification.content);
         
+        let notification = self.submit(notification);
+        if notification.repeat_count > 1 {
+            // 重复通知只更新合并次数，不再次提醒
+            return Ok(notification.id);
+        }
+        
         // 播放声音（如果启用）
-        if notification.play_sound && self.sound_enabled {
-            self.play_notification_sound(&notification.notification_type).await;
+        self.play_notification_sound(&notification);
+        
+        // 显示系统通知（Windows）
+        self.show_system_notification(&notification.id).await?;
+        
+        Ok(notification.id)
+    }
+    
+    /// 提交通知到活跃列表
+    /// 
+    /// 时间窗口内已有相同的通知时不新增，而是增加其合并次数并重新计时，
+    /// 避免反复推迟等情况下堆积相同的提醒
+    /// 
+    /// # 参数
+    /// 
+    /// * `notification` - 通知消息
+    /// 
+    /// # 返回值
+    /// 
+    /// 返回应显示的通知，合并次数大于1表示与已有通知合并
+    pub fn submit(&mut self, notification: NotificationMessage) -> NotificationMessage {
+        let dedup_window = self.dedup_window;
+        if let Some(existing) = self.active_notifications.iter_mut().find(|existing| {
+            existing.is_duplicate_of(&notification) && existing.created_at.elapsed() < dedup_window
+        }) {
+            existing.repeat_count += 1;
+            existing.created_at = std::time::Instant::now();
+            existing.remaining_seconds = notification.remaining_seconds;
+            let merged = existing.clone();
+            info!("合并重复通知: {}", merged.badge_title());
+            
+            if let Some(entry) = self.notification_history.iter_mut().find(|entry| entry.id == merged.id) {
+                entry.repeat_count = merged.repeat_count;
+            }
+            return merged;
         }
         
         // 检查是否需要移除旧通知
@@ -248,8 +545,6 @@ impl NotificationManager {
             self.remove_oldest_low_priority_notification();
         }
         
-        let notification_id = notification.id.clone();
-        
         // 添加到活跃通知列表
         self.active_notifications.push(notification.clone());
         
@@ -257,12 +552,9 @@ impl NotificationManager {
         self.active_notifications.sort_by(|a, b| b.priority.cmp(&a.priority));
         
         // 添加到历史记录
-        self.add_to_history(notification);
-        
-        // 显示系统通知（Windows）
-        self.show_system_notification(&notification_id).await?;
+        self.add_to_history(notification.clone());
         
-        Ok(notification_id)
+        notification
     }
     
     /// 显示系统通知
@@ -299,39 +591,37 @@ impl NotificationManager {
     
     /// 播放通知声音
     /// 
+    /// 设置了该类型通知的声音文件时播放文件，否则按声音方案播放提示音；
+    /// 设置了语音文本时同时朗读
+    /// 
     /// # 参数
     /// 
-    /// * `notification_type` - 通知类型
-    async fn play_notification_sound(&self, notification_type: &NotificationType) {
-        // 根据通知类型播放不同的声音
-        match notification_type {
-            NotificationType::Info => {
-                info!("播放信息通知声音");
-                // 播放信息声音
-            },
-            NotificationType::Warning => {
-                info!("播放警告通知声音");
-                // 播放警告声音
-            },
-            NotificationType::Error => {
-                info!("播放错误通知声音");
-                // 播放错误声音
-            },
-            NotificationType::Success => {
-                info!("播放成功通知声音");
-                // 播放成功声音
+    /// * `notification` - 通知消息
+    pub fn play_notification_sound(&self, notification: &NotificationMessage) {
+        if !notification.play_sound || !self.sound_enabled {
+            return;
+        }
+        
+        let profile = self.resolve_sound_profile(notification);
+        match self.sound_files.get(&notification.notification_type) {
+            // 声音方案为静音的操作不播放声音文件
+            Some(path) if profile.sound != SystemSound::Silent => {
+                info!("播放通知声音文件: {}", path.display());
+                if let Err(e) = sound::play_file(path, self.volume) {
+                    warn!("播放声音文件失败，使用系统提示音: {}", e);
+                    play_system_sound(profile.sound);
+                }
             },
-            NotificationType::Countdown => {
-                info!("播放倒计时通知声音");
-                // 播放倒计时声音
+            _ => {
+                info!("播放通知声音: {:?}", profile.sound);
+                play_system_sound(profile.sound);
             },
         }
         
-        // 在Windows上可以使用MessageBeep API
-        #[cfg(target_os = "windows")]
-        {
-            // 使用Windows API播放系统声音
-            // 这里是一个简化的实现
+        if let Some(action) = notification.action {
+            if let Some(phrase) = profile.render_phrase(action, notification.remaining_seconds.unwrap_or(0)) {
+                speak(&phrase);
+            }
         }
     }
     
@@ -487,6 +777,58 @@ impl NotificationManager {
     }
 }
 
+/// 播放系统提示音
+/// 
+/// # 参数
+/// 
+/// * `sound` - 提示音
+fn play_system_sound(sound: SystemSound) {
+    let beep = match sound {
+        SystemSound::Silent => return,
+        SystemSound::Beep => Beep::Default,
+        SystemSound::Asterisk => Beep::Asterisk,
+        SystemSound::Exclamation => Beep::Exclamation,
+        SystemSound::Hand => Beep::Hand,
+    };
+    platform::native().beep(beep);
+}
+
+/// 朗读语音文本
+/// 
+/// 使用系统自带的语音合成（System.Speech），在后台进程中朗读，不阻塞界面
+/// 
+/// # 参数
+/// 
+/// * `phrase` - 语音文本
+fn speak(phrase: &str) {
+    info!("朗读提醒: {}", phrase);
+    
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        
+        /// 不为子进程创建控制台窗口
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+            phrase.replace('\'', "''"),
+        );
+        if let Err(e) = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+        {
+            warn!("启动语音朗读失败: {}", e);
+        }
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    {
+        warn!("当前平台不支持语音朗读");
+    }
+}
+
 /// 通知统计信息
 #[derive(Debug, Default, Clone)]
 pub struct NotificationStats {
@@ -591,6 +933,31 @@ impl NotificationBuilder {
             .with_priority(NotificationPriority::High)
             .with_duration(Some(10000)) // 倒计时通知10秒后消失
     }
+    
+    /// 创建到点前的提醒通知
+    /// 
+    /// 声音和语音按操作对应的声音方案播放。剩余时间越少提醒越醒目：
+    /// 1分钟以内的提醒为紧急通知，不会自动消失
+    /// 
+    /// # 参数
+    /// 
+    /// * `action` - 到点后执行的操作
+    /// * `remaining_seconds` - 剩余秒数
+    /// 
+    /// # 返回值
+    /// 
+    /// 通知消息
+    pub fn action_warning(action: TaskAction, remaining_seconds: i64) -> NotificationMessage {
+        let notification = NotificationMessage::new("QtShut", format!("{}秒后将{}", remaining_seconds, action), NotificationType::Countdown)
+            .with_sound(true)
+            .with_action(action, remaining_seconds);
+        if remaining_seconds <= CRITICAL_WARNING_SECONDS {
+            // 临近执行的提醒不自动消失
+            notification.with_priority(NotificationPriority::Critical).with_duration(None)
+        } else {
+            notification.with_priority(NotificationPriority::High)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -670,6 +1037,72 @@ mod tests {
         assert_eq!(error.duration_ms, None);
     }
     
+    #[test]
+    fn test_action_warning_escalates() {
+        let early = NotificationBuilder::action_warning(TaskAction::Shutdown, 600);
+        assert_eq!(early.priority, NotificationPriority::High);
+        assert!(early.duration_ms.is_some());
+        
+        let last_minute = NotificationBuilder::action_warning(TaskAction::Shutdown, 60);
+        assert_eq!(last_minute.priority, NotificationPriority::Critical);
+        assert_eq!(last_minute.duration_ms, None);
+        
+        let mut manager = NotificationManager::new(5, 10);
+        assert!(manager.warning_sound(600));
+        manager.set_early_warning_sound(false);
+        assert!(!manager.warning_sound(600));
+        assert!(manager.warning_sound(60));
+    }
+    
+    #[test]
+    fn test_resolve_sound_profile() {
+        let mut manager = NotificationManager::new(5, 10);
+        let mut profiles = HashMap::new();
+        profiles.insert(TaskAction::Restart, SoundProfile {
+            sound: SystemSound::Hand,
+            phrase: Some("还有{remaining}{action}".to_string()),
+        });
+        manager.set_sound_profiles(&profiles);
+        
+        let restart = NotificationBuilder::action_warning(TaskAction::Restart, 30);
+        let profile = manager.resolve_sound_profile(&restart);
+        assert_eq!(profile.sound, SystemSound::Hand);
+        assert_eq!(profile.render_phrase(TaskAction::Restart, 30).as_deref(), Some("还有30秒重启"));
+        
+        // 未配置的操作使用默认方案
+        let shutdown = NotificationBuilder::action_warning(TaskAction::Shutdown, 10);
+        assert_eq!(manager.resolve_sound_profile(&shutdown).sound, SystemSound::Exclamation);
+        
+        // 没有关联操作的通知按类型选择提示音
+        let error = NotificationBuilder::error("错误", "内容");
+        assert_eq!(manager.resolve_sound_profile(&error), SoundProfile { sound: SystemSound::Hand, phrase: None });
+    }
+    
+    #[test]
+    fn test_submit_coalesces_duplicates() {
+        let mut manager = NotificationManager::new(5, 10);
+        
+        let first = manager.submit(NotificationBuilder::warning("QtShut", "已推迟10分钟"));
+        assert_eq!(first.repeat_count, 1);
+        manager.submit(NotificationBuilder::warning("QtShut", "已推迟10分钟"));
+        let third = manager.submit(NotificationBuilder::warning("QtShut", "已推迟10分钟"));
+        
+        assert_eq!(third.id, first.id);
+        assert_eq!(third.badge_title(), "QtShut ×3");
+        assert_eq!(manager.get_active_notifications().len(), 1);
+        assert_eq!(manager.get_notification_history()[0].repeat_count, 3);
+        
+        // 内容不同的通知不合并
+        manager.submit(NotificationBuilder::warning("QtShut", "已推迟5分钟"));
+        assert_eq!(manager.get_active_notifications().len(), 2);
+        
+        // 时间窗口为0时不合并
+        manager.set_dedup_window(0);
+        let separate = manager.submit(NotificationBuilder::warning("QtShut", "已推迟5分钟"));
+        assert_eq!(separate.repeat_count, 1);
+        assert_eq!(manager.get_active_notifications().len(), 3);
+    }
+    
     #[test]
     fn test_notification_stats() {
         let mut manager = NotificationManager::new(5, 10);
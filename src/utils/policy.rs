@@ -0,0 +1,266 @@
+//! 管理策略模块
+//! 
+//! 读取计算机级的`policy.json`（位于ProgramData目录），由管理员统一设置，
+//! 其中固定的设置优先于用户配置，适用于家庭或共享计算机
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveTime};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::stats::TaskStats;
+use crate::core::schedule;
+use crate::core::types::{TaskAction, TaskData, TaskSource, TaskType};
+use crate::utils::config::AppConfig;
+
+/// 策略文件名
+const POLICY_FILE_NAME: &str = "policy.json";
+
+/// 策略每日关机任务的固定标识
+/// 
+/// 每次生成的策略任务使用相同的标识，调度时可以识别已在运行的策略任务
+pub const POLICY_TASK_ID: &str = "policy";
+
+/// 管理策略
+/// 
+/// 所有字段都是可选的，未设置的项不做限制。
+/// 取消PIN只保存加盐哈希，管理员仍应限制该文件只允许管理员修改
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// 每天强制关机的时间，如"23:00"
+    pub daily_shutdown: Option<NaiveTime>,
+    /// 取消或暂停倒计时需要输入的PIN的加盐哈希，格式为"盐:SHA-256"，用`qtshut --hash-pin <PIN>`生成。
+    /// 设置后也不能推迟倒计时，策略关机倒计时期间不能退出程序或修改任务
+    pub cancel_pin_hash: Option<String>,
+    /// 旧版本以明文保存的PIN，加载时转换为哈希
+    #[serde(skip_serializing)]
+    pub cancel_pin: Option<String>,
+    /// 固定开机自动启动设置
+    pub auto_start: Option<bool>,
+    /// 固定强制关机设置
+    pub force_shutdown: Option<bool>,
+    /// 固定关机前确认设置
+    pub confirm_before_shutdown: Option<bool>,
+}
+
+impl Policy {
+    /// 获取策略文件路径
+    /// 
+    /// 优先使用`ProgramData`环境变量，未设置时使用默认的`C:\ProgramData`
+    pub fn policy_file_path() -> PathBuf {
+        let program_data = std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+        program_data.join("QtShut").join(POLICY_FILE_NAME)
+    }
+    
+    /// 加载计算机级策略
+    /// 
+    /// 策略文件不存在或格式错误时返回空策略
+    pub fn load() -> Self {
+        Self::load_from(&Self::policy_file_path())
+    }
+    
+    /// 从指定路径加载策略
+    /// 
+    /// # 参数
+    /// 
+    /// * `path` - 策略文件路径
+    pub fn load_from(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        
+        match serde_json::from_str::<Policy>(&content) {
+            Ok(mut policy) => {
+                info!("已加载管理策略: {:?}", path);
+                if let Some(pin) = policy.cancel_pin.take().filter(|pin| !pin.is_empty()) {
+                    warn!("管理策略以明文保存取消PIN，请改用 cancel_pin_hash");
+                    policy.cancel_pin_hash.get_or_insert_with(|| hash_pin(&pin));
+                }
+                policy
+            },
+            Err(e) => {
+                warn!("管理策略文件格式错误，已忽略: {}", e);
+                Self::default()
+            }
+        }
+    }
+    
+    /// 是否设置了任何策略
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+    
+    /// 取消倒计时是否需要PIN
+    pub fn requires_pin(&self) -> bool {
+        self.cancel_pin_hash.as_deref().is_some_and(|hash| !hash.is_empty())
+    }
+    
+    /// 校验取消PIN
+    /// 
+    /// 未设置PIN时总是通过，哈希格式错误时总是失败
+    /// 
+    /// # 参数
+    /// 
+    /// * `input` - 用户输入的PIN
+    pub fn verify_pin(&self, input: &str) -> bool {
+        match self.cancel_pin_hash.as_deref() {
+            Some(hash) if !hash.is_empty() => hash
+                .split_once(':')
+                .is_some_and(|(salt, digest)| pin_digest(salt, input.trim()).eq_ignore_ascii_case(digest)),
+            _ => true,
+        }
+    }
+    
+    /// 用策略固定的设置覆盖用户配置
+    /// 
+    /// # 参数
+    /// 
+    /// * `config` - 用户配置
+    pub fn apply_to_config(&self, config: &mut AppConfig) {
+        if let Some(auto_start) = self.auto_start {
+            config.app.auto_start = auto_start;
+        }
+        if let Some(force_shutdown) = self.force_shutdown {
+            config.shutdown.force_shutdown = force_shutdown;
+        }
+        if let Some(confirm_before_shutdown) = self.confirm_before_shutdown {
+            config.shutdown.confirm_before_shutdown = confirm_before_shutdown;
+        }
+    }
+    
+    /// 生成策略要求的每日关机任务
+    /// 
+    /// # 参数
+    /// 
+    /// * `now` - 当前时间
+    pub fn forced_daily_task(&self, now: DateTime<Local>) -> Option<TaskData> {
+        let time = self.daily_shutdown?;
+        let target_time = match schedule::next_daily(time, now) {
+            Ok(target_time) => target_time,
+            Err(e) => {
+                warn!("计算策略关机时间失败: {}", e);
+                return None;
+            }
+        };
+        
+        Some(TaskData {
+            id: POLICY_TASK_ID.to_string(),
+            task_type: TaskType::Daily,
+            target_time: Some(target_time),
+            daily_time: Some(time),
+            enabled: true,
+            created_at: now,
+            action: TaskAction::Shutdown,
+            weekly_slots: Vec::new(),
+            source: TaskSource::Policy,
+            custom_command: None,
+            cron: None,
+            routine: None,
+            stats: TaskStats::default(),
+            skip_until: None,
+        })
+    }
+}
+
+/// 生成PIN的加盐哈希，格式为"盐:SHA-256"，供管理员写入策略文件的`cancel_pin_hash`
+/// 
+/// # 参数
+/// 
+/// * `pin` - PIN
+pub fn hash_pin(pin: &str) -> String {
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let digest = pin_digest(&salt, pin.trim());
+    format!("{}:{}", salt, digest)
+}
+
+/// 计算加盐的PIN摘要，返回十六进制字符串
+fn pin_digest(salt: &str, pin: &str) -> String {
+    Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update(pin.as_bytes())
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_load_policy() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(POLICY_FILE_NAME);
+        
+        // 文件不存在时不做任何限制
+        let policy = Policy::load_from(&path);
+        assert!(!policy.is_active());
+        assert!(policy.verify_pin(""));
+        
+        let content = format!(r#"{{ "daily_shutdown": "23:00:00", "cancel_pin_hash": "{}", "auto_start": true }}"#, hash_pin("1234"));
+        fs::write(&path, content).unwrap();
+        let policy = Policy::load_from(&path);
+        assert!(policy.is_active());
+        assert!(policy.requires_pin());
+        assert!(policy.verify_pin(" 1234 "));
+        assert!(!policy.verify_pin("0000"));
+        
+        let task = policy.forced_daily_task(Local::now()).unwrap();
+        assert_eq!(task.daily_time, NaiveTime::from_hms_opt(23, 0, 0));
+        assert!(task.target_time.unwrap() > Local::now());
+        
+        // 旧版本的明文PIN加载时转换为哈希
+        fs::write(&path, r#"{ "cancel_pin": "1234" }"#).unwrap();
+        let policy = Policy::load_from(&path);
+        assert_eq!(policy.cancel_pin, None);
+        assert!(policy.verify_pin("1234"));
+        assert!(!policy.verify_pin("12345"));
+        
+        fs::write(&path, "不是JSON").unwrap();
+        assert!(!Policy::load_from(&path).is_active());
+    }
+    
+    #[test]
+    fn test_hash_pin() {
+        let first = hash_pin("pin1234");
+        let second = hash_pin("pin1234");
+        // 每次使用不同的盐，哈希中不包含明文
+        assert_ne!(first, second);
+        assert!(!first.contains("pin"));
+        
+        let policy = Policy { cancel_pin_hash: Some(first), ..Default::default() };
+        assert!(policy.verify_pin("pin1234"));
+        assert!(!policy.verify_pin(""));
+        
+        // 格式错误的哈希不能通过
+        let broken = Policy { cancel_pin_hash: Some("1234".to_string()), ..Default::default() };
+        assert!(broken.requires_pin());
+        assert!(!broken.verify_pin("1234"));
+    }
+    
+    #[test]
+    fn test_policy_overrides_config() {
+        let policy = Policy {
+            auto_start: Some(true),
+            confirm_before_shutdown: Some(false),
+            ..Default::default()
+        };
+        let mut config = AppConfig::default();
+        config.shutdown.force_shutdown = true;
+        
+        policy.apply_to_config(&mut config);
+        assert!(config.app.auto_start);
+        assert!(!config.shutdown.confirm_before_shutdown);
+        // 未固定的设置保持用户配置
+        assert!(config.shutdown.force_shutdown);
+    }
+}
@@ -0,0 +1,99 @@
+//! 声音文件播放模块
+//! 
+//! 在设置中为每种通知选择WAV或MP3文件后，通知时播放该文件代替系统提示音。
+//! 文件在调用时打开并检查格式，便于试听时立即提示错误；播放在后台线程中进行，不阻塞界面
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use rodio::{Decoder, OutputStream, Sink};
+
+/// 支持的声音文件扩展名
+pub const SUPPORTED_EXTENSIONS: [&str; 2] = ["wav", "mp3"];
+
+/// 默认音量（百分比）
+pub const DEFAULT_VOLUME: u8 = 80;
+
+/// 检查声音文件是否存在且格式受支持
+/// 
+/// # 参数
+/// 
+/// * `path` - 声音文件路径
+pub fn check_file(path: &Path) -> Result<()> {
+    let extension = path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(anyhow!("只支持WAV和MP3文件: {}", path.display()));
+    }
+    if !path.is_file() {
+        return Err(anyhow!("声音文件不存在: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// 播放声音文件
+/// 
+/// # 参数
+/// 
+/// * `path` - 声音文件路径
+/// * `volume` - 音量百分比，超过100时按100播放
+/// 
+/// # 返回值
+/// 
+/// 文件不存在或无法解码时返回错误，没有音频设备等播放时的错误只记录警告
+pub fn play_file(path: &Path, volume: u8) -> Result<()> {
+    check_file(path)?;
+    let file = File::open(path).map_err(|e| anyhow!("打开声音文件失败: {}", e))?;
+    let source = Decoder::new(BufReader::new(file)).map_err(|e| anyhow!("无法解码声音文件: {}", e))?;
+    let volume = f32::from(volume.min(100)) / 100.0;
+    debug!("播放声音文件: {}，音量: {}", path.display(), volume);
+    
+    // 输出流不能跨线程传递，在播放线程中创建，播放结束前保持
+    std::thread::spawn(move || {
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                warn!("打开音频设备失败: {}", e);
+                return;
+            }
+        };
+        match Sink::try_new(&handle) {
+            Ok(sink) => {
+                sink.set_volume(volume);
+                sink.append(source);
+                sink.sleep_until_end();
+            },
+            Err(e) => warn!("播放声音文件失败: {}", e),
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_check_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let wav = temp_dir.path().join("提醒.WAV");
+        std::fs::write(&wav, b"RIFF").unwrap();
+        assert!(check_file(&wav).is_ok());
+        
+        let missing = temp_dir.path().join("不存在.mp3");
+        assert!(check_file(&missing).unwrap_err().to_string().contains("不存在"));
+        
+        let ogg = temp_dir.path().join("提醒.ogg");
+        std::fs::write(&ogg, b"OggS").unwrap();
+        assert!(check_file(&ogg).unwrap_err().to_string().contains("WAV和MP3"));
+        
+        // 不是声音文件时无法解码
+        assert!(play_file(&wav, DEFAULT_VOLUME).unwrap_err().to_string().contains("解码"));
+    }
+}
@@ -2,22 +2,22 @@
 //! 
 //! 提供系统相关的工具函数，如版本检测、权限检查等
 
-use std::ffi::OsString;
-use std::os::windows::ffi::OsStringExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use log::{info, warn};
-// use winapi::um::sysinfoapi::GetVersionExW; // 需要sysinfoapi feature
-use winapi::um::sysinfoapi::GetVersion;
-use winapi::um::winuser::{GetSystemMetrics, SM_CLEANBOOT};
-use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
-use winapi::um::securitybaseapi::GetTokenInformation;
-use winapi::um::winnt::{TOKEN_ELEVATION, TokenElevation, HANDLE};
-use winapi::shared::minwindef::DWORD;
 
 use crate::core::types::{WindowsVersion, UserPermissions};
+use crate::platform::{self, BootMode, PowerStatus, SessionInfo, SystemInfo};
 
 /// SystemCompat类型别名，用于兼容性
 pub type SystemCompat = SystemCompatibility;
 
+/// 本次是否以安全模式启动
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 安全模式下停用的功能说明，显示在主窗口顶部
+pub const SAFE_MODE_NOTICE: &str = "安全模式：直播叠加层接口、远程关机、登录任务、自定义命令、开机自动启动登记和远程模板下载已停用，不带 --safe-mode 重新启动即可恢复";
+
 /// 操作系统版本信息
 #[derive(Debug, Clone, PartialEq)]
 pub struct OsVersion {
@@ -80,32 +80,33 @@ impl SystemCompatibility {
     fn detect_windows_version(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         info!("检测Windows版本");
         
-        unsafe {
-            let version = GetVersion();
-            let major = (version & 0xFF) as u32;
-            let minor = ((version >> 8) & 0xFF) as u32;
-            let build = 0; // GetVersion不提供build号
-            
-            info!("检测到Windows版本: {}.{}.{}", major, minor, build);
-            
-            // 根据版本号确定Windows版本
-            let windows_version = match (major, minor) {
-                (10, 0) => {
-                    if build >= 22000 {
-                        WindowsVersion::Windows11
-                    } else {
-                        WindowsVersion::Windows10
-                    }
-                },
-                (6, 3) => WindowsVersion::Windows81,
-                (6, 2) => WindowsVersion::Windows8,
-                (6, 1) => WindowsVersion::Windows7,
-                _ => WindowsVersion::Unknown,
-            };
-            
-            info!("Windows版本: {:?}", windows_version);
-            self.windows_version = Some(windows_version);
-        }
+        let (major, minor, build) = match platform::native().os_version() {
+            Ok(version) => (version.major, version.minor, version.build),
+            Err(e) => {
+                warn!("无法获取Windows版本信息: {}", e);
+                (0, 0, 0)
+            }
+        };
+        
+        info!("检测到Windows版本: {}.{}.{}", major, minor, build);
+        
+        // 根据版本号确定Windows版本
+        let windows_version = match (major, minor) {
+            (10, 0) => {
+                if build >= 22000 {
+                    WindowsVersion::Windows11
+                } else {
+                    WindowsVersion::Windows10
+                }
+            },
+            (6, 3) => WindowsVersion::Windows81,
+            (6, 2) => WindowsVersion::Windows8,
+            (6, 1) => WindowsVersion::Windows7,
+            _ => WindowsVersion::Unknown,
+        };
+        
+        info!("Windows版本: {:?}", windows_version);
+        self.windows_version = Some(windows_version);
         
         Ok(())
     }
@@ -138,41 +139,7 @@ impl SystemCompatibility {
     /// 
     /// 成功返回是否为管理员，失败返回错误信息
     fn is_running_as_administrator(&self) -> Result<bool, Box<dyn std::error::Error>> {
-        unsafe {
-            let mut token: HANDLE = std::ptr::null_mut();
-            
-            // 获取当前进程的访问令牌
-            let result = OpenProcessToken(
-                GetCurrentProcess(),
-                winapi::um::winnt::TOKEN_QUERY,
-                &mut token
-            );
-            
-            if result == 0 {
-                return Err("无法获取进程访问令牌".into());
-            }
-            
-            // 查询令牌提升信息
-            let mut elevation: TOKEN_ELEVATION = std::mem::zeroed();
-            let mut return_length: DWORD = 0;
-            
-            let result = GetTokenInformation(
-                token,
-                TokenElevation,
-                &mut elevation as *mut _ as *mut _,
-                std::mem::size_of::<TOKEN_ELEVATION>() as DWORD,
-                &mut return_length
-            );
-            
-            // 关闭令牌句柄
-            winapi::um::handleapi::CloseHandle(token);
-            
-            if result == 0 {
-                return Err("无法查询令牌提升信息".into());
-            }
-            
-            Ok(elevation.TokenIsElevated != 0)
-        }
+        Ok(platform::native().is_elevated()?)
     }
     
     /// 检查关机权限
@@ -297,23 +264,7 @@ pub fn get_system_info() -> String {
 /// 
 /// 成功返回计算机名，失败返回错误信息
 fn get_computer_name() -> Result<String, Box<dyn std::error::Error>> {
-    use winapi::um::winbase::GetComputerNameW;
-    
-    const MAX_COMPUTERNAME_LENGTH: u32 = 15; // Windows标准计算机名最大长度
-    
-    unsafe {
-        let mut buffer = vec![0u16; (MAX_COMPUTERNAME_LENGTH + 1) as usize];
-        let mut size = buffer.len() as DWORD;
-        
-        let result = GetComputerNameW(buffer.as_mut_ptr(), &mut size);
-        if result == 0 {
-            return Err("无法获取计算机名".into());
-        }
-        
-        buffer.truncate(size as usize);
-        let os_string = OsString::from_wide(&buffer);
-        Ok(os_string.to_string_lossy().to_string())
-    }
+    Ok(platform::native().computer_name()?)
 }
 
 /// 获取用户名
@@ -322,22 +273,7 @@ fn get_computer_name() -> Result<String, Box<dyn std::error::Error>> {
 /// 
 /// 成功返回用户名，失败返回错误信息
 fn get_user_name() -> Result<String, Box<dyn std::error::Error>> {
-    use winapi::um::winbase::GetUserNameW;
-    const UNLEN: u32 = 256; // Windows标准用户名最大长度
-    
-    unsafe {
-        let mut buffer = vec![0u16; (UNLEN + 1) as usize];
-        let mut size = buffer.len() as DWORD;
-        
-        let result = GetUserNameW(buffer.as_mut_ptr(), &mut size);
-        if result == 0 {
-            return Err("无法获取用户名".into());
-        }
-        
-        buffer.truncate((size - 1) as usize); // 去掉null终止符
-        let os_string = OsString::from_wide(&buffer);
-        Ok(os_string.to_string_lossy().to_string())
-    }
+    Ok(platform::native().user_name()?)
 }
 
 /// 获取系统启动模式
@@ -346,14 +282,11 @@ fn get_user_name() -> Result<String, Box<dyn std::error::Error>> {
 /// 
 /// 启动模式字符串
 fn get_boot_mode() -> String {
-    unsafe {
-        let boot_mode = GetSystemMetrics(SM_CLEANBOOT);
-        match boot_mode {
-            0 => "正常启动".to_string(),
-            1 => "安全模式".to_string(),
-            2 => "安全模式（带网络）".to_string(),
-            _ => format!("未知启动模式 ({})", boot_mode),
-        }
+    match platform::native().boot_mode() {
+        BootMode::Normal => "正常启动".to_string(),
+        BootMode::SafeMode => "安全模式".to_string(),
+        BootMode::SafeModeWithNetwork => "安全模式（带网络）".to_string(),
+        BootMode::Unknown(mode) => format!("未知启动模式 ({})", mode),
     }
 }
 
@@ -374,11 +307,75 @@ pub fn is_reboot_pending() -> bool {
 /// 
 /// 系统运行时间（毫秒）
 pub fn get_system_uptime() -> u64 {
-    use winapi::um::sysinfoapi::GetTickCount64;
-    
-    unsafe {
-        GetTickCount64()
-    }
+    platform::native().uptime_ms()
+}
+
+/// 获取系统空闲时间
+/// 
+/// # 返回值
+/// 
+/// 距离上次键盘鼠标输入的时长，平台不支持空闲检测时为0
+pub fn get_idle_time() -> std::time::Duration {
+    platform::native().idle_duration()
+}
+
+/// 获取正在运行的进程名
+/// 
+/// # 返回值
+/// 
+/// 所有进程的可执行文件名（如"obs64.exe"），平台不支持进程枚举时为空
+pub fn get_running_processes() -> Option<Vec<String>> {
+    platform::native().process_names()
+}
+
+/// 获取电源状态
+/// 
+/// # 返回值
+/// 
+/// 是否使用电池供电和剩余电量，没有电池或平台不支持时为空
+pub fn get_power_status() -> Option<PowerStatus> {
+    platform::native().power_status()
+}
+
+/// 进入安全模式
+/// 
+/// 由`--safe-mode`启动参数在创建应用前调用，之后不再启动外部接口（直播叠加层、远程关机）和登录任务，
+/// 也不运行自定义命令和下载远程模板，用于在这些功能导致启动失败时恢复
+pub fn enable_safe_mode() {
+    warn!("以安全模式启动，外部接口、登录任务和自定义命令已停用");
+    SAFE_MODE.store(true, Ordering::Relaxed);
+}
+
+/// 本次是否以安全模式启动
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::Relaxed)
+}
+
+/// 在正在运行的进程中查找列表中的程序
+/// 
+/// 忽略大小写，列表中的名称可以省略".exe"后缀
+/// 
+/// # 参数
+/// 
+/// * `watched` - 要查找的进程名列表
+/// * `running` - 正在运行的进程名
+/// 
+/// # 返回值
+/// 
+/// 返回列表中第一个正在运行的进程名，都没有运行时返回None
+pub fn find_running_process<'a>(watched: &'a [String], running: &[String]) -> Option<&'a str> {
+    let normalize = |name: &str| {
+        let name = name.trim().to_lowercase();
+        match name.strip_suffix(".exe") {
+            Some(stem) => stem.to_string(),
+            None => name,
+        }
+    };
+    let running: std::collections::HashSet<String> = running.iter().map(|name| normalize(name)).collect();
+    watched.iter()
+        .map(|name| name.as_str())
+        .filter(|name| !name.trim().is_empty())
+        .find(|name| running.contains(&normalize(name)))
 }
 
 /// 格式化系统运行时间
@@ -458,6 +455,18 @@ mod tests {
         assert!(uptime > 0); // 系统运行时间应该大于0
     }
     
+    #[test]
+    fn test_find_running_process() {
+        let running = vec!["explorer.exe".to_string(), "OBS64.EXE".to_string(), "ffmpeg".to_string()];
+        let watched = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        
+        assert_eq!(find_running_process(&watched(&["HandBrake.exe", "obs64.exe"]), &running), Some("obs64.exe"));
+        assert_eq!(find_running_process(&watched(&["ffmpeg.exe"]), &running), Some("ffmpeg.exe"));
+        assert_eq!(find_running_process(&watched(&["explorer"]), &running), Some("explorer"));
+        assert_eq!(find_running_process(&watched(&["HandBrake.exe", ""]), &running), None);
+        assert_eq!(find_running_process(&[], &running), None);
+    }
+    
     #[test]
     fn test_format_uptime() {
         // 测试不同的时间格式